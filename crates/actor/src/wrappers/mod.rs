@@ -1,7 +1,11 @@
 mod io;
 mod logging;
+#[cfg(all(not(feature = "module"), feature = "component"))]
+mod net;
 mod random;
 
 pub use io::*;
 pub use logging::*;
+#[cfg(all(not(feature = "module"), feature = "component"))]
+pub use net::*;
 pub use random::*;
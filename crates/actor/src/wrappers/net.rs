@@ -0,0 +1,288 @@
+use std::io::{self, Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr};
+use std::time::Duration;
+
+use crate::wasi::clocks::monotonic_clock::{self, Instant};
+use crate::wasi::io::poll::{poll, Pollable};
+use crate::wasi::io::streams::{InputStream, OutputStream, StreamError};
+use crate::wasi::sockets::network::{
+    ErrorCode, IpAddress, IpAddressFamily, IpSocketAddress, Ipv4SocketAddress, Ipv6SocketAddress,
+    Network,
+};
+use crate::wasi::sockets::tcp::{ShutdownType, TcpSocket};
+use crate::wasi::sockets::{instance_network, ip_name_lookup, tcp_create_socket};
+
+/// Timeout used by [`TcpStream::connect`] for name resolution and connection establishment
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default timeout of a single read from or write to a [`TcpStream`]
+pub const DEFAULT_IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// An outbound TCP connection established via `wasi:sockets`.
+///
+/// The host decides which destinations an actor may connect to, connecting to any other
+/// destination fails with [`io::ErrorKind::PermissionDenied`].
+pub struct TcpStream {
+    // Streams are children of the socket and must be dropped before it
+    input: InputStream,
+    output: OutputStream,
+    socket: TcpSocket,
+    read_timeout: Option<Duration>,
+    write_timeout: Option<Duration>,
+}
+
+impl TcpStream {
+    /// Connect to `port` on `host`, which is either an IP address or a name to resolve, using
+    /// [`DEFAULT_CONNECT_TIMEOUT`]
+    pub fn connect(host: &str, port: u16) -> io::Result<Self> {
+        Self::connect_timeout(host, port, DEFAULT_CONNECT_TIMEOUT)
+    }
+
+    /// Connect to `port` on `host`, which is either an IP address or a name to resolve.
+    /// Each resolved address is tried in turn until a connection is established or `timeout`
+    /// elapses.
+    pub fn connect_timeout(host: &str, port: u16, timeout: Duration) -> io::Result<Self> {
+        let deadline = deadline(Some(timeout));
+        let network = instance_network::instance_network();
+        let mut last_err = None;
+        for addr in resolve(&network, host, deadline)? {
+            match connect_addr(&network, addr, port, deadline) {
+                Ok(stream) => return Ok(stream),
+                Err(err) if err.kind() == io::ErrorKind::TimedOut => return Err(err),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("`{host}` did not resolve to any address"),
+            )
+        }))
+    }
+
+    /// Set the timeout of a single read, `None` blocks indefinitely
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) {
+        self.read_timeout = timeout;
+    }
+
+    /// Set the timeout of a single write or flush, `None` blocks indefinitely
+    pub fn set_write_timeout(&mut self, timeout: Option<Duration>) {
+        self.write_timeout = timeout;
+    }
+
+    pub fn read_timeout(&self) -> Option<Duration> {
+        self.read_timeout
+    }
+
+    pub fn write_timeout(&self) -> Option<Duration> {
+        self.write_timeout
+    }
+
+    /// Returns the address of the remote peer
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.socket
+            .remote_address()
+            .map(socket_addr)
+            .map_err(socket_error)
+    }
+
+    /// Shut down the read, write or both halves of the connection
+    pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
+        let how = match how {
+            Shutdown::Read => ShutdownType::Receive,
+            Shutdown::Write => ShutdownType::Send,
+            Shutdown::Both => ShutdownType::Both,
+        };
+        self.socket.shutdown(how).map_err(socket_error)
+    }
+}
+
+impl Read for TcpStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let deadline = deadline(self.read_timeout);
+        let n = buf.len().try_into().unwrap_or(u64::MAX);
+        let pollable = self.input.subscribe();
+        loop {
+            match self.input.read(n) {
+                Ok(chunk) if chunk.is_empty() => wait(&pollable, deadline)?,
+                Ok(chunk) => {
+                    let n = chunk.len();
+                    if n > buf.len() {
+                        return Err(io::Error::new(
+                            io::ErrorKind::Other,
+                            "more bytes read than requested",
+                        ));
+                    }
+                    buf[..n].copy_from_slice(&chunk);
+                    return Ok(n);
+                }
+                Err(StreamError::Closed) => return Ok(0),
+                Err(err) => return Err(stream_error(err)),
+            }
+        }
+    }
+}
+
+impl Write for TcpStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        let deadline = deadline(self.write_timeout);
+        let pollable = self.output.subscribe();
+        loop {
+            match self.output.check_write() {
+                Ok(0) => wait(&pollable, deadline)?,
+                Ok(n) => {
+                    let n = buf.len().min(n.try_into().unwrap_or(usize::MAX));
+                    self.output.write(&buf[..n]).map_err(stream_error)?;
+                    return Ok(n);
+                }
+                Err(err) => return Err(stream_error(err)),
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let deadline = deadline(self.write_timeout);
+        self.output.flush().map_err(stream_error)?;
+        // The output stream becomes ready once the flush completes
+        wait(&self.output.subscribe(), deadline)?;
+        self.output.check_write().map_err(stream_error)?;
+        Ok(())
+    }
+}
+
+/// Returns the instant at which `timeout` elapses
+fn deadline(timeout: Option<Duration>) -> Option<Instant> {
+    let timeout = timeout?.as_nanos().try_into().unwrap_or(u64::MAX);
+    Some(monotonic_clock::now().saturating_add(timeout))
+}
+
+/// Block until `pollable` is ready or `deadline` is reached
+fn wait(pollable: &Pollable, deadline: Option<Instant>) -> io::Result<()> {
+    let Some(deadline) = deadline else {
+        pollable.block();
+        return Ok(());
+    };
+    let timer = monotonic_clock::subscribe_instant(deadline);
+    if poll(&[pollable, &timer]).contains(&0) {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::TimedOut.into())
+    }
+}
+
+fn resolve(network: &Network, host: &str, deadline: Option<Instant>) -> io::Result<Vec<IpAddress>> {
+    if let Ok(ip) = host.parse() {
+        return Ok(vec![ip_address(ip)]);
+    }
+    let addrs = ip_name_lookup::resolve_addresses(network, host).map_err(socket_error)?;
+    let pollable = addrs.subscribe();
+    let mut resolved = Vec::new();
+    loop {
+        match addrs.resolve_next_address() {
+            Ok(Some(addr)) => resolved.push(addr),
+            Ok(None) => return Ok(resolved),
+            Err(ErrorCode::WouldBlock) => wait(&pollable, deadline)?,
+            Err(err) => return Err(socket_error(err)),
+        }
+    }
+}
+
+fn connect_addr(
+    network: &Network,
+    addr: IpAddress,
+    port: u16,
+    deadline: Option<Instant>,
+) -> io::Result<TcpStream> {
+    let (family, remote) = match addr {
+        IpAddress::Ipv4(address) => (
+            IpAddressFamily::Ipv4,
+            IpSocketAddress::Ipv4(Ipv4SocketAddress { port, address }),
+        ),
+        IpAddress::Ipv6(address) => (
+            IpAddressFamily::Ipv6,
+            IpSocketAddress::Ipv6(Ipv6SocketAddress {
+                port,
+                flow_info: 0,
+                address,
+                scope_id: 0,
+            }),
+        ),
+    };
+    let socket = tcp_create_socket::create_tcp_socket(family).map_err(socket_error)?;
+    socket
+        .start_connect(network, remote)
+        .map_err(socket_error)?;
+    let pollable = socket.subscribe();
+    let (input, output) = loop {
+        match socket.finish_connect() {
+            Ok(streams) => break streams,
+            Err(ErrorCode::WouldBlock) => wait(&pollable, deadline)?,
+            Err(err) => return Err(socket_error(err)),
+        }
+    };
+    drop(pollable);
+    Ok(TcpStream {
+        input,
+        output,
+        socket,
+        read_timeout: Some(DEFAULT_IO_TIMEOUT),
+        write_timeout: Some(DEFAULT_IO_TIMEOUT),
+    })
+}
+
+fn ip_address(ip: IpAddr) -> IpAddress {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, d] = ip.octets();
+            IpAddress::Ipv4((a, b, c, d))
+        }
+        IpAddr::V6(ip) => {
+            let [a, b, c, d, e, f, g, h] = ip.segments();
+            IpAddress::Ipv6((a, b, c, d, e, f, g, h))
+        }
+    }
+}
+
+fn socket_addr(addr: IpSocketAddress) -> SocketAddr {
+    match addr {
+        IpSocketAddress::Ipv4(Ipv4SocketAddress {
+            port,
+            address: (a, b, c, d),
+        }) => SocketAddr::new(Ipv4Addr::new(a, b, c, d).into(), port),
+        IpSocketAddress::Ipv6(Ipv6SocketAddress {
+            port,
+            address: (a, b, c, d, e, f, g, h),
+            ..
+        }) => SocketAddr::new(Ipv6Addr::new(a, b, c, d, e, f, g, h).into(), port),
+    }
+}
+
+fn socket_error(err: ErrorCode) -> io::Error {
+    let kind = match err {
+        ErrorCode::AccessDenied => io::ErrorKind::PermissionDenied,
+        ErrorCode::Timeout => io::ErrorKind::TimedOut,
+        ErrorCode::ConnectionRefused => io::ErrorKind::ConnectionRefused,
+        ErrorCode::ConnectionReset => io::ErrorKind::ConnectionReset,
+        ErrorCode::ConnectionAborted => io::ErrorKind::ConnectionAborted,
+        ErrorCode::InvalidArgument => io::ErrorKind::InvalidInput,
+        ErrorCode::WouldBlock => io::ErrorKind::WouldBlock,
+        ErrorCode::NameUnresolvable => io::ErrorKind::NotFound,
+        _ => io::ErrorKind::Other,
+    };
+    io::Error::new(kind, format!("{err:?}"))
+}
+
+fn stream_error(err: StreamError) -> io::Error {
+    match err {
+        StreamError::Closed => io::ErrorKind::BrokenPipe.into(),
+        StreamError::LastOperationFailed(err) => {
+            io::Error::new(io::ErrorKind::Other, err.to_debug_string())
+        }
+    }
+}
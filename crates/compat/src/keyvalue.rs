@@ -92,3 +92,12 @@ pub struct SetRequest {
     #[serde(default)]
     pub expires: u32,
 }
+
+/// A key and its value, as exchanged by the `batch` interface of `wasmcloud:keyvalue`
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct KeyValuePair {
+    #[serde(default)]
+    pub key: String,
+    #[serde(default)]
+    pub value: String,
+}
@@ -65,6 +65,14 @@ pub fn clear_config(
     )
 }
 
+pub fn put_cluster_issuer(topic_prefix: &Option<String>, lattice_prefix: &str) -> String {
+    format!("{}.issuers.put", prefix(topic_prefix, lattice_prefix))
+}
+
+pub fn delete_cluster_issuer(topic_prefix: &Option<String>, lattice_prefix: &str) -> String {
+    format!("{}.issuers.del", prefix(topic_prefix, lattice_prefix))
+}
+
 pub fn put_label(topic_prefix: &Option<String>, lattice_prefix: &str, host_id: &str) -> String {
     format!(
         "{}.labels.{}.put",
@@ -119,6 +127,26 @@ pub mod commands {
     pub fn stop_host(topic_prefix: &Option<String>, lattice_prefix: &str, host: &str) -> String {
         format!("{}.cmd.{}.stop", prefix(topic_prefix, lattice_prefix), host)
     }
+
+    pub fn profile_host(topic_prefix: &Option<String>, lattice_prefix: &str, host: &str) -> String {
+        format!(
+            "{}.cmd.{}.profile",
+            prefix(topic_prefix, lattice_prefix),
+            host
+        )
+    }
+
+    pub fn apply_manifest(
+        topic_prefix: &Option<String>,
+        lattice_prefix: &str,
+        host: &str,
+    ) -> String {
+        format!(
+            "{}.cmd.{}.apply",
+            prefix(topic_prefix, lattice_prefix),
+            host
+        )
+    }
 }
 
 pub mod queries {
@@ -140,6 +168,14 @@ pub mod queries {
         format!("{}.get.{}.inv", prefix(topic_prefix, lattice_prefix), host)
     }
 
+    pub fn host_metrics(topic_prefix: &Option<String>, lattice_prefix: &str, host: &str) -> String {
+        format!(
+            "{}.get.{}.metrics",
+            prefix(topic_prefix, lattice_prefix),
+            host
+        )
+    }
+
     pub fn hosts(topic_prefix: &Option<String>, lattice_prefix: &str) -> String {
         format!("{}.ping.hosts", prefix(topic_prefix, lattice_prefix))
     }
@@ -177,6 +177,21 @@ impl Client {
         }
     }
 
+    /// Retrieves invocation metrics of a running host, broken down by link
+    #[instrument(level = "debug", skip_all)]
+    pub async fn get_host_metrics(&self, host_id: &str) -> Result<HostMetrics> {
+        let subject = broker::queries::host_metrics(
+            &self.topic_prefix,
+            &self.lattice_prefix,
+            parse_identifier(&IdentifierKind::HostId, host_id)?.as_str(),
+        );
+        debug!("get_host_metrics:request {}", &subject);
+        match self.request_timeout(subject, vec![], self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive host metrics from target host: {e}").into()),
+        }
+    }
+
     /// Retrieves the full set of all cached claims in the lattice.   
     #[instrument(level = "debug", skip_all)]
     pub async fn get_claims(&self) -> Result<Vec<HashMap<String, String>>> {
@@ -298,6 +313,30 @@ impl Client {
         }
     }
 
+    /// Instructs all hosts of the lattice to additionally trust invocations signed by the cluster
+    /// `issuer`, which they forward to their providers. Returns the acknowledgements of all hosts,
+    /// which responded within the auction timeout
+    #[instrument(level = "debug", skip_all)]
+    pub async fn put_cluster_issuer(&self, issuer: &str) -> Result<Vec<CtlOperationAck>> {
+        let issuer = parse_identifier(&IdentifierKind::ClusterIssuer, issuer)?;
+        let subject = broker::put_cluster_issuer(&self.topic_prefix, &self.lattice_prefix);
+        debug!("put_cluster_issuer:publish {}", &subject);
+        let bytes = json_serialize(ClusterIssuerRequest { issuer })?;
+        self.publish_and_wait(subject, bytes).await
+    }
+
+    /// Instructs all hosts of the lattice to stop trusting invocations signed by the cluster
+    /// `issuer`. Hosts, which sign invocations with the issuer, reject the request. Returns the
+    /// acknowledgements of all hosts, which responded within the auction timeout
+    #[instrument(level = "debug", skip_all)]
+    pub async fn delete_cluster_issuer(&self, issuer: &str) -> Result<Vec<CtlOperationAck>> {
+        let issuer = parse_identifier(&IdentifierKind::ClusterIssuer, issuer)?;
+        let subject = broker::delete_cluster_issuer(&self.topic_prefix, &self.lattice_prefix);
+        debug!("delete_cluster_issuer:publish {}", &subject);
+        let bytes = json_serialize(ClusterIssuerRequest { issuer })?;
+        self.publish_and_wait(subject, bytes).await
+    }
+
     /// Puts a link into the lattice. Returns an error if it was unable to put the link
     #[instrument(level = "debug", skip_all)]
     pub async fn advertise_link(
@@ -672,6 +711,66 @@ impl Client {
         }
     }
 
+    /// Issues a command to a host to reconcile toward the given declarative [`Manifest`]: actors
+    /// are scaled, providers started and links put as described. If `prune` is set, actors and
+    /// providers running on the host which are not part of the manifest are stopped, and links
+    /// which are not part of the manifest are deleted. The target
+    /// host acknowledges receipt of the command before reconciling, the outcome is published as a
+    /// `manifest_applied` or `manifest_apply_failed` event in the control event stream
+    #[instrument(level = "debug", skip_all)]
+    pub async fn apply_manifest(
+        &self,
+        host_id: &str,
+        manifest: Manifest,
+        prune: bool,
+    ) -> Result<CtlOperationAck> {
+        let host_id = parse_identifier(&IdentifierKind::HostId, host_id)?;
+        let subject = broker::commands::apply_manifest(
+            &self.topic_prefix,
+            &self.lattice_prefix,
+            host_id.as_str(),
+        );
+        debug!("apply_manifest:request {}", &subject);
+        let bytes = json_serialize(ApplyManifestCommand {
+            host_id,
+            manifest,
+            prune,
+        })?;
+        match self.request_timeout(subject, bytes, self.timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive apply manifest acknowledgement: {e}").into()),
+        }
+    }
+
+    /// Issues a command to a specific host to capture a CPU profile of itself for `duration_ms`.
+    /// `format` is either `pprof` (default) or `flamegraph`. Profiling must be enabled on the host
+    #[instrument(level = "debug", skip_all)]
+    pub async fn profile_host(
+        &self,
+        host_id: &str,
+        duration_ms: u64,
+        format: Option<String>,
+    ) -> Result<ProfileHostResponse> {
+        let host_id = parse_identifier(&IdentifierKind::HostId, host_id)?;
+        let subject = broker::commands::profile_host(
+            &self.topic_prefix,
+            &self.lattice_prefix,
+            host_id.as_str(),
+        );
+        debug!("profile_host:request {}", &subject);
+        let bytes = json_serialize(ProfileHostCommand {
+            host_id,
+            duration_ms: Some(duration_ms),
+            format,
+        })?;
+        // The host only replies once the profile has been captured
+        let timeout = self.timeout + Duration::from_millis(duration_ms);
+        match self.request_timeout(subject, bytes, timeout).await {
+            Ok(msg) => Ok(json_deserialize(&msg.payload)?),
+            Err(e) => Err(format!("Did not receive profile host response: {e}").into()),
+        }
+    }
+
     async fn publish_and_wait<D: DeserializeOwned>(
         &self,
         subject: String,
@@ -825,6 +924,7 @@ enum IdentifierKind {
     ProviderRef,
     ContractId,
     LinkName,
+    ClusterIssuer,
 }
 
 fn assert_non_empty_string(input: &str, message: &str) -> Result<String> {
@@ -851,6 +951,9 @@ fn parse_identifier<T: AsRef<str>>(kind: &IdentifierKind, value: T) -> Result<St
         }
         IdentifierKind::ContractId => assert_non_empty_string(value, "Contract ID cannot be empty"),
         IdentifierKind::LinkName => assert_non_empty_string(value, "Link Name cannot be empty"),
+        IdentifierKind::ClusterIssuer => {
+            assert_non_empty_string(value, "Cluster issuer cannot be empty")
+        }
     }
 }
 
@@ -901,7 +1004,35 @@ mod tests {
         assert!(parse_identifier(&IdentifierKind::HostId, "host_id").is_ok());
         let actor_id = parse_identifier(&IdentifierKind::ActorId, "            iambatman  ")?;
         assert_eq!(actor_id, "iambatman");
+        assert!(parse_identifier(&IdentifierKind::ClusterIssuer, "").is_err());
 
         Ok(())
     }
+
+    #[test]
+    fn test_manifest_defaults() -> Result<()> {
+        let manifest: Manifest = serde_json::from_str(
+            r#"{
+                "actors": [{ "actor_ref": "wasmcloud.azurecr.io/echo:0.3.8" }],
+                "links": [{ "actor": "wasmcloud.azurecr.io/echo:0.3.8", "provider": "wasmcloud.azurecr.io/httpserver:0.19.1", "contract_id": "wasmcloud:httpserver" }]
+            }"#,
+        )?;
+        assert_eq!(
+            manifest,
+            Manifest {
+                actors: vec![ManifestActor {
+                    actor_ref: "wasmcloud.azurecr.io/echo:0.3.8".into(),
+                    ..Default::default()
+                }],
+                providers: vec![],
+                links: vec![ManifestLink {
+                    actor: "wasmcloud.azurecr.io/echo:0.3.8".into(),
+                    provider: "wasmcloud.azurecr.io/httpserver:0.19.1".into(),
+                    contract_id: "wasmcloud:httpserver".into(),
+                    ..Default::default()
+                }],
+            }
+        );
+        Ok(())
+    }
 }
@@ -64,6 +64,23 @@ pub struct ActorInstance {
     /// The maximum number of concurrent requests this instance can handle
     #[serde(default)]
     pub max_concurrent: u16,
+    /// Execution limits of this instance, set via annotations of the start request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limits: Option<ActorLimits>,
+}
+
+/// Execution limits enforced by the host for an actor instance
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ActorLimits {
+    /// Maximum time in milliseconds a single invocation may take, before it is aborted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_execution_time_ms: Option<u64>,
+    /// Maximum amount of linear memory in bytes a single actor instance may allocate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_memory_bytes: Option<u64>,
+    /// Maximum number of invocations handled concurrently
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent_invocations: Option<u16>,
 }
 
 pub type AnnotationMap = std::collections::HashMap<String, String>;
@@ -130,6 +147,51 @@ pub struct Host {
     pub version: Option<String>,
 }
 
+/// Invocation metrics of a host at the time of a query
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct HostMetrics {
+    /// The host's unique ID
+    #[serde(default)]
+    pub host_id: String,
+    /// Metrics of invocations made by actors on the host over links, one entry per actor,
+    /// provider, link name and operation
+    #[serde(default)]
+    pub links: Vec<LinkMetrics>,
+}
+
+/// Latency and error metrics of invocations of a single operation over a link
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LinkMetrics {
+    /// Public key of the invoking actor
+    pub actor_id: String,
+    /// Contract ID of the link
+    pub contract_id: String,
+    /// Name of the link
+    pub link_name: String,
+    /// Public key of the invoked provider
+    pub provider_id: String,
+    /// Invoked operation
+    pub operation: String,
+    /// Number of invocations
+    pub count: u64,
+    /// Number of invocations that failed
+    pub errors: u64,
+    /// Total latency of all invocations, in microseconds
+    pub sum_us: u64,
+    /// Cumulative latency histogram
+    pub buckets: Vec<LatencyBucket>,
+}
+
+/// A bucket of a cumulative latency histogram
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct LatencyBucket {
+    /// Inclusive upper bound of the bucket, in milliseconds. `None` for the last bucket, which
+    /// counts all invocations
+    pub le_ms: Option<u64>,
+    /// Number of invocations with a latency of at most `le_ms`
+    pub count: u64,
+}
+
 /// Describes the known contents of a given host at the time of
 /// a query
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
@@ -363,6 +425,35 @@ pub struct StopHostCommand {
     pub timeout: Option<u64>,
 }
 
+/// A command sent to request a CPU profile of the given host
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProfileHostCommand {
+    /// The ID of the target host
+    #[serde(default)]
+    pub host_id: String,
+    /// Duration of the profile, in milliseconds. The host default is used if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// Format of the profile, either `pprof` (default) or `flamegraph`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+/// A response to a [`ProfileHostCommand`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ProfileHostResponse {
+    #[serde(default)]
+    pub accepted: bool,
+    #[serde(default)]
+    pub error: String,
+    /// Format of the captured profile
+    #[serde(default)]
+    pub format: String,
+    /// Base64-encoded profile, a pprof protobuf or an SVG flamegraph depending on `format`
+    #[serde(default)]
+    pub profile: String,
+}
+
 /// A request to stop the given provider on the indicated host
 #[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
 pub struct StopProviderCommand {
@@ -406,6 +497,90 @@ pub struct UpdateActorCommand {
     pub new_actor_ref: String,
 }
 
+/// A declarative description of the actors, providers and links a host should run
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Manifest {
+    /// Actors to run on the host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actors: Vec<ManifestActor>,
+    /// Capability providers to run on the host
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub providers: Vec<ManifestProvider>,
+    /// Link definitions to put in the lattice
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<ManifestLink>,
+}
+
+/// An actor in a [`Manifest`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ManifestActor {
+    /// Image reference of the actor
+    pub actor_ref: String,
+    /// The maximum number of concurrent executing instances of this actor. If set to `None`
+    /// there is no maximum, while setting to `0` will stop the actor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrent: Option<u16>,
+    /// Annotations of the actor instance
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub annotations: AnnotationMap,
+}
+
+/// A capability provider in a [`Manifest`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ManifestProvider {
+    /// Image reference of the provider
+    pub provider_ref: String,
+    /// Link name of the provider, `default` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_name: Option<String>,
+    /// Optional provider configuration in the form of an opaque string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<String>,
+    /// Annotations of the provider instance
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub annotations: AnnotationMap,
+}
+
+/// A link definition in a [`Manifest`]
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ManifestLink {
+    /// Image reference of an actor in the manifest or the public key of an actor
+    pub actor: String,
+    /// Image reference of a provider in the manifest or the public key of a provider
+    pub provider: String,
+    /// Contract ID of the link
+    pub contract_id: String,
+    /// Link name, `default` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link_name: Option<String>,
+    /// Link values
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub values: LinkSettings,
+}
+
+/// A request sent to all hosts of a lattice to trust or retire a cluster issuer key
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ClusterIssuerRequest {
+    /// The public key of the cluster issuer
+    pub issuer: String,
+}
+
+/// A command sent to a host requesting it to reconcile toward the given [`Manifest`]. Applying the
+/// same manifest multiple times has no further effect
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ApplyManifestCommand {
+    /// The ID of the target host
+    #[serde(default)]
+    pub host_id: String,
+    /// The desired state of the host
+    #[serde(default)]
+    pub manifest: Manifest,
+    /// Whether to stop actors and providers running on the host and delete links, which are not
+    /// part of the manifest
+    #[serde(default)]
+    pub prune: bool,
+}
+
 // Below are copied structs to avoid depedency conflicts on wasmbus_rpc
 
 // COPIED FROM https://github.com/wasmCloud/weld/blob/wasmbus-rpc-v0.13.0/rpc-rs/src/wasmbus_core.rs#L1176
@@ -0,0 +1,333 @@
+//! Benchmarks for the per-invocation work performed on the lattice: signing, encoding, decoding
+//! and validating [`Invocation`]s sent by actors and hosts to providers, and encoding them in the
+//! supported serialization formats.
+//!
+//! If `WASMCLOUD_BENCH_NATS_URL` is set, invocation round trips through the NATS server at that
+//! URL are benchmarked as well, along with chunking of payloads above the chunking threshold,
+//! both inline and through the JetStream object store. The latter requires JetStream to be
+//! enabled on the server.
+
+use std::env;
+use std::io::Cursor;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use futures::StreamExt;
+use nkeys::KeyPair;
+use wasmcloud_core::chunking::{ChunkEndpoint, ChunkTransport, CHUNK_THRESHOLD_BYTES};
+use wasmcloud_core::{Invocation, InvocationResponse, WasmCloudEntity};
+
+/// Payload sizes to benchmark, all below the chunking threshold
+const PAYLOAD_SIZES: [usize; 4] = [0, 1024, 64 * 1024, 512 * 1024];
+
+/// Payload sizes to benchmark chunking with, all above the chunking threshold
+const CHUNKED_PAYLOAD_SIZES: [usize; 3] =
+    [CHUNK_THRESHOLD_BYTES + 1, 4 * 1024 * 1024, 16 * 1024 * 1024];
+
+const LATTICE_PREFIX: &str = "bench";
+
+/// Sender of the benchmarked invocations, all of which target a provider
+#[derive(Clone, Copy)]
+enum Origin {
+    /// Invocations of provider operations by actors
+    Actor,
+    /// Invocations sent by the host itself, e.g. link and health check requests
+    Host,
+}
+
+impl Origin {
+    const ALL: [Self; 2] = [Self::Actor, Self::Host];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Actor => "actor_to_provider",
+            Self::Host => "host_to_provider",
+        }
+    }
+}
+
+fn target(provider_key: &KeyPair) -> WasmCloudEntity {
+    WasmCloudEntity {
+        public_key: provider_key.public_key(),
+        link_name: "default".into(),
+        contract_id: "wasmcloud:bench".into(),
+    }
+}
+
+struct Keys {
+    cluster: KeyPair,
+    host: KeyPair,
+    actor: KeyPair,
+    provider: KeyPair,
+}
+
+impl Keys {
+    fn new() -> Self {
+        Self {
+            cluster: KeyPair::new_cluster(),
+            host: KeyPair::new_server(),
+            actor: KeyPair::new_module(),
+            provider: KeyPair::new_service(),
+        }
+    }
+
+    fn origin(&self, origin: Origin) -> WasmCloudEntity {
+        let key = match origin {
+            Origin::Actor => &self.actor,
+            Origin::Host => &self.host,
+        };
+        WasmCloudEntity {
+            public_key: key.public_key(),
+            ..Default::default()
+        }
+    }
+
+    fn invocation(&self, origin: Origin, msg: Vec<u8>) -> Invocation {
+        let operation = match origin {
+            Origin::Actor => "wasmcloud:bench/Bench.Echo",
+            Origin::Host => "HealthRequest",
+        };
+        Invocation::new(
+            &self.cluster,
+            &self.host,
+            self.origin(origin),
+            target(&self.provider),
+            operation,
+            msg,
+            Vec::default(),
+        )
+        .expect("failed to create invocation")
+    }
+}
+
+fn invocation(c: &mut Criterion) {
+    let keys = Keys::new();
+    let issuers = vec![keys.cluster.public_key()];
+
+    let mut group = c.benchmark_group("invocation");
+    for origin in Origin::ALL {
+        let path = origin.name();
+        for size in PAYLOAD_SIZES {
+            let msg = vec![0x42; size];
+            let inv = keys.invocation(origin, msg.clone());
+            let encoded = rmp_serde::to_vec_named(&inv).expect("failed to encode invocation");
+
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(
+                BenchmarkId::new(format!("{path}/sign"), size),
+                &msg,
+                |b, msg| {
+                    b.iter(|| keys.invocation(origin, msg.clone()));
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{path}/encode"), size),
+                &inv,
+                |b, inv| {
+                    b.iter(|| rmp_serde::to_vec_named(inv).expect("failed to encode invocation"));
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{path}/decode"), size),
+                &encoded,
+                |b, buf| {
+                    b.iter(|| {
+                        rmp_serde::from_slice::<Invocation>(buf)
+                            .expect("failed to decode invocation")
+                    });
+                },
+            );
+            group.bench_with_input(
+                BenchmarkId::new(format!("{path}/validate"), size),
+                &inv,
+                |b, inv| {
+                    b.iter(|| {
+                        inv.validate_antiforgery(&issuers)
+                            .expect("failed to validate invocation");
+                    });
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+/// Compare the MessagePack encoding used on the lattice, with and without field names, to JSON
+fn serialization_format(c: &mut Criterion) {
+    let keys = Keys::new();
+
+    let mut group = c.benchmark_group("serialization_format");
+    for size in PAYLOAD_SIZES {
+        let inv = keys.invocation(Origin::Actor, vec![0x42; size]);
+        let named = rmp_serde::to_vec_named(&inv).expect("failed to encode invocation");
+        let compact = rmp_serde::to_vec(&inv).expect("failed to encode invocation");
+        let json = serde_json::to_vec(&inv).expect("failed to encode invocation");
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("msgpack_named/encode", size),
+            &inv,
+            |b, inv| {
+                b.iter(|| rmp_serde::to_vec_named(inv).expect("failed to encode invocation"));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("msgpack_named/decode", size),
+            &named,
+            |b, buf| {
+                b.iter(|| {
+                    rmp_serde::from_slice::<Invocation>(buf).expect("failed to decode invocation")
+                });
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("msgpack_compact/encode", size),
+            &inv,
+            |b, inv| {
+                b.iter(|| rmp_serde::to_vec(inv).expect("failed to encode invocation"));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("msgpack_compact/decode", size),
+            &compact,
+            |b, buf| {
+                b.iter(|| {
+                    rmp_serde::from_slice::<Invocation>(buf).expect("failed to decode invocation")
+                });
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("json/encode", size), &inv, |b, inv| {
+            b.iter(|| serde_json::to_vec(inv).expect("failed to encode invocation"));
+        });
+        group.bench_with_input(BenchmarkId::new("json/decode", size), &json, |b, buf| {
+            b.iter(|| {
+                serde_json::from_slice::<Invocation>(buf).expect("failed to decode invocation")
+            });
+        });
+    }
+    group.finish();
+}
+
+fn nats_round_trip(c: &mut Criterion) {
+    let Ok(url) = env::var("WASMCLOUD_BENCH_NATS_URL") else {
+        return;
+    };
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime");
+    let keys = Keys::new();
+    let issuers = vec![keys.cluster.public_key()];
+    let topic = format!(
+        "wasmbus.rpc.{LATTICE_PREFIX}.{}.default",
+        keys.provider.public_key()
+    );
+
+    let nats = rt
+        .block_on(async_nats::connect(url))
+        .expect("failed to connect to NATS");
+    // Respond to invocations the way a provider would: decode, validate and echo the payload
+    let mut sub = rt
+        .block_on(nats.subscribe(topic.clone()))
+        .expect("failed to subscribe");
+    let responder = nats.clone();
+    rt.spawn(async move {
+        while let Some(msg) = sub.next().await {
+            let Some(reply) = msg.reply else {
+                continue;
+            };
+            let inv: Invocation =
+                rmp_serde::from_slice(&msg.payload).expect("failed to decode invocation");
+            inv.validate_antiforgery(&issuers)
+                .expect("failed to validate invocation");
+            let res = InvocationResponse {
+                content_length: inv.msg.len() as u64,
+                msg: inv.msg,
+                invocation_id: inv.id,
+                ..Default::default()
+            };
+            let res = rmp_serde::to_vec_named(&res).expect("failed to encode response");
+            responder
+                .publish(reply, res.into())
+                .await
+                .expect("failed to publish response");
+        }
+    });
+
+    let mut group = c.benchmark_group("nats_round_trip");
+    group.measurement_time(Duration::from_secs(10));
+    for origin in Origin::ALL {
+        for size in PAYLOAD_SIZES {
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(BenchmarkId::new(origin.name(), size), &size, |b, &size| {
+                b.to_async(&rt).iter(|| async {
+                    let inv = keys.invocation(origin, vec![0x42; size]);
+                    let payload =
+                        rmp_serde::to_vec_named(&inv).expect("failed to encode invocation");
+                    let res = nats
+                        .request(topic.clone(), payload.into())
+                        .await
+                        .expect("failed to perform request");
+                    rmp_serde::from_slice::<InvocationResponse>(&res.payload)
+                        .expect("failed to decode response")
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+/// Benchmark sending and receiving payloads above the chunking threshold through each transport
+fn chunking(c: &mut Criterion) {
+    let Ok(url) = env::var("WASMCLOUD_BENCH_NATS_URL") else {
+        return;
+    };
+    let rt = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("failed to build tokio runtime");
+    let nats = rt
+        .block_on(async_nats::connect(url))
+        .expect("failed to connect to NATS");
+    let endpoint =
+        ChunkEndpoint::with_client(LATTICE_PREFIX, nats, None::<&str>).with_inline_chunking(true);
+    let next_id = AtomicUsize::default();
+
+    let mut group = c.benchmark_group("chunking");
+    group.measurement_time(Duration::from_secs(10));
+    group.sample_size(10);
+    for (name, transport) in [
+        ("inline", ChunkTransport::Inline),
+        ("object_store", ChunkTransport::ObjectStore),
+    ] {
+        for size in CHUNKED_PAYLOAD_SIZES {
+            let payload = vec![0x42; size];
+            group.throughput(Throughput::Bytes(size as u64));
+            group.bench_with_input(BenchmarkId::new(name, size), &payload, |b, payload| {
+                b.to_async(&rt).iter(|| async {
+                    let id = format!("bench-{}", next_id.fetch_add(1, Ordering::Relaxed));
+                    // Payloads are chunked before the invocation referring to them is sent
+                    endpoint
+                        .chunkify_via(transport, &id, Cursor::new(payload.as_slice()))
+                        .await
+                        .expect("failed to chunk payload");
+                    endpoint
+                        .get_unchunkified_via(transport, &id)
+                        .await
+                        .expect("failed to dechunk payload")
+                });
+            });
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    invocation,
+    serialization_format,
+    nats_round_trip,
+    chunking
+);
+criterion_main!(benches);
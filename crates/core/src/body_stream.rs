@@ -0,0 +1,214 @@
+//! Streaming of message bodies over the lattice.
+//!
+//! Instead of buffering a whole body into an invocation, the sender of an invocation may refer to
+//! a body stream by ID. The receiver pulls request bodies one frame at a time and pushes response
+//! bodies back one frame at a time, waiting for every frame to be acknowledged. This way at most
+//! a couple of frames per direction are held in memory, regardless of the size of the body.
+//!
+//! A body stream is terminated by an empty frame.
+
+use std::io;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use async_nats::{Client, Subscriber};
+use bytes::{Bytes, BytesMut};
+use futures::{stream, Stream, StreamExt};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_util::io::StreamReader;
+use tracing::{debug, error, instrument, trace};
+
+/// Maximum size of a single body frame
+pub const FRAME_SIZE_BYTES: usize = 64 * 1024;
+
+/// Amount of time to wait for the peer to request or acknowledge a body frame
+pub const FRAME_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Returns the subject, on which frames of body stream `stream_id` are pulled by the receiver
+#[must_use]
+pub fn pull_subject(lattice_prefix: &str, stream_id: &str) -> String {
+    format!("wasmbus.body.{lattice_prefix}.{stream_id}.pull")
+}
+
+/// Returns the subject, on which frames of body stream `stream_id` are pushed to the sender
+#[must_use]
+pub fn push_subject(lattice_prefix: &str, stream_id: &str) -> String {
+    format!("wasmbus.body.{lattice_prefix}.{stream_id}.push")
+}
+
+#[derive(Clone, Debug)]
+pub struct BodyStreamEndpoint {
+    lattice: String,
+    nats: Client,
+}
+
+impl BodyStreamEndpoint {
+    #[must_use]
+    pub fn new(lattice: &str, nats: Client) -> Self {
+        BodyStreamEndpoint {
+            lattice: lattice.to_string(),
+            nats,
+        }
+    }
+
+    /// Serve `body` to the receiver of body stream `stream_id`, one frame per pull request.
+    ///
+    /// The subscription is established before this function returns, so `stream_id` may be
+    /// shared with the receiver as soon as it does. The returned task completes once the whole
+    /// body has been served or the receiver stops pulling for longer than [`FRAME_TIMEOUT`].
+    #[instrument(level = "trace", skip(self, body))]
+    pub async fn serve(
+        &self,
+        stream_id: &str,
+        mut body: impl AsyncRead + Send + Unpin + 'static,
+    ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
+        let mut sub = self
+            .nats
+            .subscribe(pull_subject(&self.lattice, stream_id))
+            .await
+            .context("failed to subscribe to body pull subject")?;
+        let nats = self.nats.clone();
+        let stream_id = stream_id.to_string();
+        Ok(tokio::spawn(async move {
+            loop {
+                let Some(msg) = tokio::time::timeout(FRAME_TIMEOUT, sub.next())
+                    .await
+                    .context("timed out waiting for body pull request")?
+                else {
+                    bail!("body pull subscription closed")
+                };
+                let Some(reply) = msg.reply else {
+                    bail!("body pull request is missing a reply subject")
+                };
+                let frame = read_frame(&mut body)
+                    .await
+                    .context("failed to read body frame")?;
+                let done = frame.is_empty();
+                trace!(%stream_id, len = frame.len(), "serving body frame");
+                nats.publish(reply, frame)
+                    .await
+                    .context("failed to publish body frame")?;
+                if done {
+                    debug!(%stream_id, "body stream served");
+                    return Ok(());
+                }
+            }
+        }))
+    }
+
+    /// Returns a reader of body stream `stream_id` served by [`Self::serve`].
+    ///
+    /// Frames are pulled in a background task, which stays at most one frame ahead of the reader.
+    #[must_use]
+    pub fn pull(&self, stream_id: &str) -> impl AsyncRead + Send + Sync + Unpin {
+        let (tx, rx) = mpsc::channel(1);
+        let nats = self.nats.clone();
+        let subject = pull_subject(&self.lattice, stream_id);
+        tokio::spawn(async move {
+            loop {
+                let frame = match tokio::time::timeout(
+                    FRAME_TIMEOUT,
+                    nats.request(subject.clone(), Bytes::new()),
+                )
+                .await
+                {
+                    Ok(Ok(msg)) => msg.payload,
+                    Ok(Err(err)) => {
+                        error!(%err, %subject, "failed to pull body frame");
+                        let _ = tx.send(Err(io::Error::other(err))).await;
+                        return;
+                    }
+                    Err(_) => {
+                        error!(%subject, "timed out pulling body frame");
+                        let _ = tx.send(Err(io::Error::from(io::ErrorKind::TimedOut))).await;
+                        return;
+                    }
+                };
+                if frame.is_empty() || tx.send(Ok(frame)).await.is_err() {
+                    return;
+                }
+            }
+        });
+        StreamReader::new(ReceiverStream::new(rx))
+    }
+
+    /// Push `body` to the sender of body stream `stream_id`, waiting for every frame to be
+    /// acknowledged before reading the next one.
+    #[instrument(level = "trace", skip(self, body))]
+    pub async fn push(
+        &self,
+        stream_id: &str,
+        mut body: impl AsyncRead + Unpin,
+    ) -> anyhow::Result<()> {
+        let subject = push_subject(&self.lattice, stream_id);
+        loop {
+            let frame = read_frame(&mut body)
+                .await
+                .context("failed to read body frame")?;
+            let done = frame.is_empty();
+            trace!(%stream_id, len = frame.len(), "pushing body frame");
+            tokio::time::timeout(FRAME_TIMEOUT, self.nats.request(subject.clone(), frame))
+                .await
+                .context("timed out waiting for body frame acknowledgement")?
+                .context("failed to push body frame")?;
+            if done {
+                debug!(%stream_id, "body stream pushed");
+                return Ok(());
+            }
+        }
+    }
+
+    /// Receive body stream `stream_id` pushed by [`Self::push`].
+    ///
+    /// The subscription is established before this function returns, so `stream_id` may be
+    /// shared with the pusher as soon as it does. Each frame is only acknowledged once the
+    /// next one is requested from the returned stream, which applies backpressure to the pusher.
+    #[instrument(level = "trace", skip(self))]
+    pub async fn receive(
+        &self,
+        stream_id: &str,
+    ) -> anyhow::Result<impl Stream<Item = anyhow::Result<Bytes>> + Send + 'static> {
+        let sub = self
+            .nats
+            .subscribe(push_subject(&self.lattice, stream_id))
+            .await
+            .context("failed to subscribe to body push subject")?;
+        let nats = self.nats.clone();
+        Ok(stream::try_unfold(
+            (nats, sub, None),
+            |(nats, mut sub, ack): (Client, Subscriber, Option<async_nats::Subject>)| async move {
+                if let Some(ack) = ack {
+                    nats.publish(ack, Bytes::new())
+                        .await
+                        .context("failed to acknowledge body frame")?;
+                }
+                let Some(msg) = tokio::time::timeout(FRAME_TIMEOUT, sub.next())
+                    .await
+                    .context("timed out waiting for body frame")?
+                else {
+                    bail!("body push subscription closed")
+                };
+                if msg.payload.is_empty() {
+                    if let Some(reply) = msg.reply {
+                        nats.publish(reply, Bytes::new())
+                            .await
+                            .context("failed to acknowledge end of body")?;
+                    }
+                    return Ok(None);
+                }
+                Ok(Some((msg.payload, (nats, sub, msg.reply))))
+            },
+        ))
+    }
+}
+
+/// Read the next frame of at most [`FRAME_SIZE_BYTES`] from `body` as soon as any data is
+/// available, an empty frame signals end of body
+async fn read_frame(body: &mut (impl AsyncRead + Unpin)) -> io::Result<Bytes> {
+    let mut frame = BytesMut::with_capacity(FRAME_SIZE_BYTES);
+    body.read_buf(&mut frame).await?;
+    Ok(frame.freeze())
+}
@@ -15,14 +15,21 @@
 //
 // I will always be chunkified ...
 
+use std::collections::HashSet;
 use std::marker::Unpin;
+use std::sync::{Arc, RwLock};
 use std::time::Duration;
 
-use anyhow::{anyhow, Context};
+use anyhow::{anyhow, ensure, Context};
 use async_nats::jetstream;
 use async_nats::jetstream::object_store::{self, ObjectStore};
+use async_nats::HeaderMap;
+use bytes::Bytes;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tokio::io::{AsyncRead, AsyncReadExt};
-use tracing::{debug, error, instrument};
+use tracing::{debug, error, instrument, trace};
 
 /// Amount of time to add to rpc timeout if chunkifying
 pub const CHUNK_RPC_EXTRA_TIME: Duration = Duration::from_secs(13);
@@ -34,37 +41,309 @@ pub const CHUNK_THRESHOLD_BYTES: usize = 1024 * 900; // 900KB
 #[cfg(test)]
 pub const CHUNK_THRESHOLD_BYTES: usize = 1024; // 1KB
 
+/// Maximum size of the data of a single chunk, when chunking inline
+pub const INLINE_CHUNK_SIZE_BYTES: usize = 1024 * 512; // 512KB
+
+/// Maximum size of a payload chunked inline. Payloads are reassembled in memory, so receivers
+/// refuse to pull larger ones
+pub const MAX_INLINE_PAYLOAD_BYTES: usize = 1024 * 1024 * 128; // 128MB
+
+/// Header carrying [`ChunkEnvelope::invocation_id`]
+pub const CHUNK_INVOCATION_ID_HEADER: &str = "wasmcloud-chunk-invocation-id";
+/// Header carrying [`ChunkEnvelope::sequence`]
+pub const CHUNK_SEQUENCE_HEADER: &str = "wasmcloud-chunk-sequence";
+/// Header carrying [`ChunkEnvelope::total`]
+pub const CHUNK_TOTAL_HEADER: &str = "wasmcloud-chunk-total";
+/// Header carrying [`ChunkEnvelope::digest`]
+pub const CHUNK_DIGEST_HEADER: &str = "wasmcloud-chunk-digest";
+
+/// Returns the subject, on which the receiver of the payload chunked inline as `object` pulls its
+/// chunks, by sending the sequence number of the chunk
+#[must_use]
+pub fn inline_chunk_subject(lattice_prefix: &str, object: &str) -> String {
+    format!("wasmbus.chunk.{lattice_prefix}.{object}")
+}
+
+/// How the chunks of a payload are transferred from its sender to its receiver, sent along with
+/// invocations and responses, see [`Invocation::chunk_transport`](crate::Invocation::chunk_transport)
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChunkTransport {
+    /// Chunks are stored in a JetStream object store named after the lattice. Supported by all
+    /// peers
+    #[default]
+    ObjectStore,
+    /// Chunks are pulled by the receiver from the sender over NATS, see [`inline_chunk_subject`].
+    /// Only used for peers advertising support, see [`ChunkEndpoint::record_peer`]
+    Inline,
+}
+
+/// A single chunk of a payload chunked inline, sent as a NATS message with the metadata in its
+/// headers and the data in its payload
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChunkEnvelope {
+    /// ID of the invocation the payload belongs to, suffixed with `-r` for responses
+    pub invocation_id: String,
+    /// Index of the chunk in the payload, starting at 0
+    pub sequence: u32,
+    /// Number of chunks the payload consists of
+    pub total: u32,
+    /// Hex-encoded SHA-256 digest of the whole payload, which the receiver verifies after
+    /// reassembly
+    pub digest: String,
+    /// The data of the chunk
+    pub data: Bytes,
+}
+
+impl ChunkEnvelope {
+    /// Returns the headers carrying the metadata of the chunk
+    #[must_use]
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CHUNK_INVOCATION_ID_HEADER, self.invocation_id.as_str());
+        headers.insert(CHUNK_SEQUENCE_HEADER, self.sequence.to_string().as_str());
+        headers.insert(CHUNK_TOTAL_HEADER, self.total.to_string().as_str());
+        headers.insert(CHUNK_DIGEST_HEADER, self.digest.as_str());
+        headers
+    }
+
+    /// Parses a chunk from a received NATS message
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message is missing any of the chunk headers, or they are invalid
+    pub fn from_message(msg: async_nats::Message) -> anyhow::Result<Self> {
+        Self::from_parts(msg.headers.as_ref(), msg.payload)
+    }
+
+    fn from_parts(headers: Option<&HeaderMap>, data: Bytes) -> anyhow::Result<Self> {
+        let headers = headers.context("chunk is missing headers")?;
+        let header = |name: &str| {
+            headers
+                .get(name)
+                .map(|value| value.as_str().to_string())
+                .with_context(|| format!("chunk is missing `{name}` header"))
+        };
+        Ok(Self {
+            invocation_id: header(CHUNK_INVOCATION_ID_HEADER)?,
+            sequence: header(CHUNK_SEQUENCE_HEADER)?
+                .parse()
+                .context("invalid chunk sequence")?,
+            total: header(CHUNK_TOTAL_HEADER)?
+                .parse()
+                .context("invalid chunk total")?,
+            digest: header(CHUNK_DIGEST_HEADER)?,
+            data,
+        })
+    }
+}
+
+/// Payload chunked inline, reassembled from its chunks in order
+#[derive(Debug)]
+struct Reassembly {
+    invocation_id: String,
+    /// Total and digest of the first chunk, which all further chunks must match
+    expected: Option<(u32, String)>,
+    data: Vec<u8>,
+}
+
+impl Reassembly {
+    fn new(invocation_id: &str) -> Self {
+        Self {
+            invocation_id: invocation_id.to_string(),
+            expected: None,
+            data: Vec::new(),
+        }
+    }
+
+    /// Returns the sequence number of the next chunk to pull
+    fn next_sequence(&self) -> u32 {
+        self.expected
+            .as_ref()
+            .map_or(0, |_| self.data.len().div_ceil(INLINE_CHUNK_SIZE_BYTES))
+            .try_into()
+            .unwrap_or(u32::MAX)
+    }
+
+    /// Appends `chunk`, returning the reassembled payload once all chunks are appended
+    fn push(&mut self, chunk: ChunkEnvelope) -> anyhow::Result<Option<Vec<u8>>> {
+        let sequence = self.next_sequence();
+        ensure!(
+            chunk.invocation_id == self.invocation_id && chunk.sequence == sequence,
+            "received unexpected chunk {} of `{}`",
+            chunk.sequence,
+            chunk.invocation_id
+        );
+        // Chunks are only valid if they do not exceed the chunk size, and all but the last are
+        // full, which also ensures that the sender's total cannot exceed the maximum payload size
+        let (total, digest) = self
+            .expected
+            .get_or_insert_with(|| (chunk.total, chunk.digest));
+        let max_total = MAX_INLINE_PAYLOAD_BYTES.div_ceil(INLINE_CHUNK_SIZE_BYTES);
+        ensure!(
+            *total > 0 && usize::try_from(*total).is_ok_and(|total| total <= max_total),
+            "payload of {total} chunks exceeds the maximum of {MAX_INLINE_PAYLOAD_BYTES} bytes"
+        );
+        ensure!(
+            chunk.total == *total,
+            "chunk metadata changed while pulling"
+        );
+        let last = sequence + 1 == *total;
+        ensure!(
+            chunk.data.len() == INLINE_CHUNK_SIZE_BYTES
+                || (last && chunk.data.len() < INLINE_CHUNK_SIZE_BYTES),
+            "chunk {sequence} has invalid size {}",
+            chunk.data.len()
+        );
+        ensure!(
+            self.data.len() + chunk.data.len() <= MAX_INLINE_PAYLOAD_BYTES,
+            "payload exceeds the maximum of {MAX_INLINE_PAYLOAD_BYTES} bytes"
+        );
+        self.data.extend_from_slice(&chunk.data);
+        if !last {
+            return Ok(None);
+        }
+        ensure!(
+            hex::encode(Sha256::digest(&self.data)) == *digest,
+            "digest mismatch of reassembled payload"
+        );
+        Ok(Some(std::mem::take(&mut self.data)))
+    }
+}
+
+/// Public keys of the peers known to support inline chunking, shared by all clones of a
+/// [`ChunkEndpoint`]
+#[derive(Clone, Debug, Default)]
+struct InlinePeers(Arc<RwLock<HashSet<String>>>);
+
+impl InlinePeers {
+    fn record(&self, peer: &str, inline_chunking: bool) {
+        let Ok(mut peers) = self.0.write() else {
+            return;
+        };
+        if inline_chunking {
+            if !peers.contains(peer) {
+                peers.insert(peer.to_string());
+            }
+        } else {
+            peers.remove(peer);
+        }
+    }
+
+    fn contains(&self, peer: &str) -> bool {
+        self.0.read().is_ok_and(|peers| peers.contains(peer))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct ChunkEndpoint {
     lattice: String,
     js: jetstream::Context,
+    /// Client chunks are pulled and served over, if the endpoint supports inline chunking
+    nats: Option<async_nats::Client>,
+    /// Whether payloads are chunked inline for peers supporting it
+    inline: bool,
+    inline_peers: InlinePeers,
 }
 
 impl ChunkEndpoint {
+    /// Constructs an endpoint chunking through the JetStream object store of the lattice only
     #[must_use]
     pub fn new(lattice: &str, js: jetstream::Context) -> Self {
         ChunkEndpoint {
             lattice: lattice.to_string(),
             js,
+            nats: None,
+            inline: false,
+            inline_peers: InlinePeers::default(),
         }
     }
 
+    /// Constructs an endpoint chunking through the JetStream object store of the lattice, which
+    /// can also receive payloads chunked inline over `nc`, see [`Self::with_inline_chunking`]
     pub fn with_client(
         lattice: &str,
         nc: async_nats::Client,
         domain: Option<impl AsRef<str>>,
     ) -> Self {
         let js = if let Some(domain) = domain {
-            jetstream::with_domain(nc, domain)
+            jetstream::with_domain(nc.clone(), domain)
         } else {
-            jetstream::new(nc)
+            jetstream::new(nc.clone())
         };
-        ChunkEndpoint::new(lattice, js)
+        ChunkEndpoint {
+            nats: Some(nc),
+            ..ChunkEndpoint::new(lattice, js)
+        }
+    }
+
+    /// Chunks payloads inline over NATS, which does not require JetStream, if `inline` is set.
+    /// Payloads are only chunked inline for peers advertising support, see
+    /// [`Self::transport_for`], and through the object store for all others. Requires an endpoint
+    /// constructed with [`Self::with_client`]
+    #[must_use]
+    pub fn with_inline_chunking(mut self, inline: bool) -> Self {
+        self.inline = inline;
+        self
+    }
+
+    /// Returns an endpoint chunking payloads in the lattice `lattice`, sharing the transports and
+    /// known peers of this one
+    #[must_use]
+    pub fn with_lattice(&self, lattice: &str) -> Self {
+        ChunkEndpoint {
+            lattice: lattice.to_string(),
+            ..self.clone()
+        }
+    }
+
+    /// Returns true if the endpoint can receive payloads chunked inline, which senders of
+    /// invocations and responses advertise to their peers
+    #[must_use]
+    pub fn supports_inline(&self) -> bool {
+        self.nats.is_some()
+    }
+
+    /// Records whether the peer with public key `peer` advertised support for inline chunking.
+    /// The latest advertisement of a peer determines how payloads are chunked for it
+    pub fn record_peer(&self, peer: &str, inline_chunking: bool) {
+        self.inline_peers.record(peer, inline_chunking);
+    }
+
+    /// Returns the transport to chunk payloads sent to the peer with public key `peer` through,
+    /// which is [`ChunkTransport::Inline`] only if this endpoint chunks inline and the peer
+    /// advertised support for it
+    #[must_use]
+    pub fn transport_for(&self, peer: &str) -> ChunkTransport {
+        if self.inline && self.supports_inline() && self.inline_peers.contains(peer) {
+            ChunkTransport::Inline
+        } else {
+            ChunkTransport::ObjectStore
+        }
     }
 
     /// load the message after de-chunking
-    #[instrument(level = "trace", skip(self))]
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
     pub async fn get_unchunkified(&self, inv_id: &str) -> anyhow::Result<Vec<u8>> {
+        self.get_unchunkified_via(ChunkTransport::ObjectStore, inv_id)
+            .await
+    }
+
+    /// load the message chunked through `transport` after de-chunking
+    #[instrument(level = "trace", skip(self))]
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn get_unchunkified_via(
+        &self,
+        transport: ChunkTransport,
+        inv_id: &str,
+    ) -> anyhow::Result<Vec<u8>> {
+        if transport == ChunkTransport::Inline {
+            let nc = self
+                .nats
+                .as_ref()
+                .context("endpoint does not support inline chunking")?;
+            return self.pull_inline(nc, inv_id).await;
+        }
         let mut result = Vec::new();
         let store = self
             .create_or_reuse_store()
@@ -89,17 +368,49 @@ impl ChunkEndpoint {
     /// load response after de-chunking
     #[allow(clippy::missing_errors_doc)] // TODO: Document errors
     pub async fn get_unchunkified_response(&self, inv_id: &str) -> anyhow::Result<Vec<u8>> {
+        self.get_unchunkified_response_via(ChunkTransport::ObjectStore, inv_id)
+            .await
+    }
+
+    /// load response chunked through `transport` after de-chunking
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn get_unchunkified_response_via(
+        &self,
+        transport: ChunkTransport,
+        inv_id: &str,
+    ) -> anyhow::Result<Vec<u8>> {
         // responses are stored in the object store with '-r' suffix on the object name
-        self.get_unchunkified(&format!("{inv_id}-r")).await
+        self.get_unchunkified_via(transport, &format!("{inv_id}-r"))
+            .await
     }
 
     /// chunkify a message
-    #[instrument(level = "trace", skip(self, bytes))]
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
     pub async fn chunkify(
         &self,
         inv_id: &str,
+        bytes: (impl AsyncRead + Unpin),
+    ) -> anyhow::Result<()> {
+        self.chunkify_via(ChunkTransport::ObjectStore, inv_id, bytes)
+            .await
+    }
+
+    /// chunkify a message through `transport`, see [`Self::transport_for`]
+    #[instrument(level = "trace", skip(self, bytes))]
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn chunkify_via(
+        &self,
+        transport: ChunkTransport,
+        inv_id: &str,
         mut bytes: (impl AsyncRead + Unpin),
     ) -> anyhow::Result<()> {
+        if transport == ChunkTransport::Inline {
+            let nc = self
+                .nats
+                .as_ref()
+                .context("endpoint does not support inline chunking")?;
+            return self.serve_inline(nc, inv_id, bytes).await;
+        }
         let store = self.create_or_reuse_store().await?;
         debug!(invocation_id = %inv_id, "chunkify starting to send");
         let info = store
@@ -119,7 +430,125 @@ impl ChunkEndpoint {
         inv_id: &str,
         bytes: (impl AsyncRead + Unpin),
     ) -> anyhow::Result<()> {
-        self.chunkify(&format!("{inv_id}-r"), bytes).await
+        self.chunkify_response_via(ChunkTransport::ObjectStore, inv_id, bytes)
+            .await
+    }
+
+    /// chunkify a portion of a response through `transport`, see [`Self::transport_for`]
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub async fn chunkify_response_via(
+        &self,
+        transport: ChunkTransport,
+        inv_id: &str,
+        bytes: (impl AsyncRead + Unpin),
+    ) -> anyhow::Result<()> {
+        self.chunkify_via(transport, &format!("{inv_id}-r"), bytes)
+            .await
+    }
+
+    /// Serve the chunks of `bytes` to the receiver, one chunk per pull request.
+    ///
+    /// The subscription is established before this function returns, so the invocation may be
+    /// sent as soon as it does. Chunks are served in the background until the last one is pulled,
+    /// or the receiver stops pulling for longer than [`CHUNK_RPC_EXTRA_TIME`]
+    async fn serve_inline(
+        &self,
+        nc: &async_nats::Client,
+        inv_id: &str,
+        mut bytes: (impl AsyncRead + Unpin),
+    ) -> anyhow::Result<()> {
+        let mut payload = Vec::new();
+        bytes
+            .read_to_end(&mut payload)
+            .await
+            .context("failed to read payload to chunk")?;
+        ensure!(
+            payload.len() <= MAX_INLINE_PAYLOAD_BYTES,
+            "payload of {} bytes exceeds the maximum of {MAX_INLINE_PAYLOAD_BYTES} bytes chunked inline",
+            payload.len()
+        );
+        let payload = Bytes::from(payload);
+        let digest = hex::encode(Sha256::digest(&payload));
+        let total = u32::try_from(payload.len().div_ceil(INLINE_CHUNK_SIZE_BYTES).max(1))
+            .context("payload consists of too many chunks")?;
+        let mut sub = nc
+            .subscribe(inline_chunk_subject(&self.lattice, inv_id))
+            .await
+            .context("failed to subscribe to chunk pull subject")?;
+        debug!(invocation_id = %inv_id, len = payload.len(), total, "chunkify starting to serve");
+        let nc = nc.clone();
+        let inv_id = inv_id.to_string();
+        tokio::spawn(async move {
+            loop {
+                let msg = match tokio::time::timeout(CHUNK_RPC_EXTRA_TIME, sub.next()).await {
+                    Ok(Some(msg)) => msg,
+                    Ok(None) => return,
+                    Err(_) => {
+                        error!(invocation_id = %inv_id, "timed out waiting for chunk pull request");
+                        return;
+                    }
+                };
+                let Some(reply) = msg.reply else {
+                    continue;
+                };
+                let sequence = match std::str::from_utf8(&msg.payload)
+                    .ok()
+                    .and_then(|sequence| sequence.parse::<u32>().ok())
+                {
+                    Some(sequence) if sequence < total => sequence,
+                    _ => {
+                        error!(invocation_id = %inv_id, "received invalid chunk pull request");
+                        continue;
+                    }
+                };
+                let start = sequence as usize * INLINE_CHUNK_SIZE_BYTES;
+                let end = payload.len().min(start + INLINE_CHUNK_SIZE_BYTES);
+                let chunk = ChunkEnvelope {
+                    invocation_id: inv_id.clone(),
+                    sequence,
+                    total,
+                    digest: digest.clone(),
+                    data: payload.slice(start..end),
+                };
+                trace!(invocation_id = %inv_id, sequence, total, "serving chunk");
+                if let Err(err) = nc
+                    .publish_with_headers(reply, chunk.headers(), chunk.data)
+                    .await
+                {
+                    error!(invocation_id = %inv_id, %err, "failed to publish chunk");
+                    return;
+                }
+                if sequence + 1 == total {
+                    debug!(invocation_id = %inv_id, "chunkify served all chunks");
+                    return;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Pull the chunks of the payload served by [`Self::serve_inline`] in order, and verify the
+    /// digest of the reassembled payload
+    async fn pull_inline(&self, nc: &async_nats::Client, inv_id: &str) -> anyhow::Result<Vec<u8>> {
+        let subject = inline_chunk_subject(&self.lattice, inv_id);
+        debug!(invocation_id = %inv_id, "chunkify starting to pull");
+        let mut reassembly = Reassembly::new(inv_id);
+        loop {
+            let msg = tokio::time::timeout(
+                CHUNK_RPC_EXTRA_TIME,
+                nc.request(
+                    subject.clone(),
+                    reassembly.next_sequence().to_string().into(),
+                ),
+            )
+            .await
+            .context("timed out pulling chunk")?
+            .context("failed to pull chunk")?;
+            if let Some(result) = reassembly.push(ChunkEnvelope::from_message(msg)?)? {
+                debug!(invocation_id = %inv_id, len = result.len(), "chunkify pulled all chunks");
+                return Ok(result);
+            }
+        }
     }
 
     // TODO: cache the store locally
@@ -139,3 +568,118 @@ impl ChunkEndpoint {
         Ok(store)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits `payload` into the chunks served inline
+    fn chunks(invocation_id: &str, payload: &[u8]) -> Vec<ChunkEnvelope> {
+        let digest = hex::encode(Sha256::digest(payload));
+        let payload = Bytes::copy_from_slice(payload);
+        let total = payload.len().div_ceil(INLINE_CHUNK_SIZE_BYTES).max(1);
+        (0..total)
+            .map(|sequence| ChunkEnvelope {
+                invocation_id: invocation_id.to_string(),
+                sequence: sequence.try_into().expect("too many chunks"),
+                total: total.try_into().expect("too many chunks"),
+                digest: digest.clone(),
+                data: payload.slice(
+                    sequence * INLINE_CHUNK_SIZE_BYTES
+                        ..payload.len().min((sequence + 1) * INLINE_CHUNK_SIZE_BYTES),
+                ),
+            })
+            .collect()
+    }
+
+    fn reassemble(invocation_id: &str, chunks: Vec<ChunkEnvelope>) -> anyhow::Result<Vec<u8>> {
+        let mut reassembly = Reassembly::new(invocation_id);
+        for chunk in chunks {
+            ensure!(chunk.sequence == reassembly.next_sequence());
+            if let Some(payload) = reassembly.push(chunk)? {
+                return Ok(payload);
+            }
+        }
+        anyhow::bail!("payload incomplete")
+    }
+
+    #[test]
+    fn envelope_round_trip() -> anyhow::Result<()> {
+        let chunk = ChunkEnvelope {
+            invocation_id: "inv-r".to_string(),
+            sequence: 2,
+            total: 3,
+            digest: "abc".to_string(),
+            data: Bytes::from_static(b"data"),
+        };
+        assert_eq!(
+            ChunkEnvelope::from_parts(Some(&chunk.headers()), chunk.data.clone())?,
+            chunk
+        );
+
+        assert!(ChunkEnvelope::from_parts(None, Bytes::new()).is_err());
+        let mut headers = chunk.headers();
+        headers.insert(CHUNK_SEQUENCE_HEADER, "-1");
+        assert!(ChunkEnvelope::from_parts(Some(&headers), Bytes::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reassembly() -> anyhow::Result<()> {
+        let payload: Vec<u8> = (0..INLINE_CHUNK_SIZE_BYTES * 2 + 10)
+            .map(|i| i.to_le_bytes()[0])
+            .collect();
+        assert_eq!(reassemble("inv", chunks("inv", &payload))?, payload);
+        assert_eq!(reassemble("inv", chunks("inv", b""))?, b"");
+
+        // Chunks must be received in order and belong to the payload
+        let mut reordered = chunks("inv", &payload);
+        reordered.swap(0, 1);
+        assert!(reassemble("inv", reordered).is_err());
+        assert!(reassemble("other", chunks("inv", &payload)).is_err());
+
+        // The reassembled payload must match the digest
+        let mut tampered = chunks("inv", &payload);
+        tampered[2].data = Bytes::from_static(b"tampered");
+        assert!(reassemble("inv", tampered).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn reassembly_limits() {
+        // The total announced by the sender is bounded
+        let mut reassembly = Reassembly::new("inv");
+        let mut chunk = chunks("inv", b"data").remove(0);
+        chunk.total = u32::MAX;
+        chunk.data = Bytes::from(vec![0; INLINE_CHUNK_SIZE_BYTES]);
+        assert!(reassembly.push(chunk).is_err());
+
+        // Chunks may not exceed the chunk size
+        let mut reassembly = Reassembly::new("inv");
+        let mut chunk = chunks("inv", b"data").remove(0);
+        chunk.data = Bytes::from(vec![0; INLINE_CHUNK_SIZE_BYTES + 1]);
+        assert!(reassembly.push(chunk).is_err());
+
+        // All but the last chunk must be full
+        let mut reassembly = Reassembly::new("inv");
+        let mut chunk = chunks("inv", b"data").remove(0);
+        chunk.total = 2;
+        assert!(reassembly.push(chunk).is_err());
+    }
+
+    #[test]
+    fn inline_peers() {
+        let peers = InlinePeers::default();
+        assert!(!peers.contains("VPEER"));
+        peers.record("VPEER", true);
+        assert!(peers.contains("VPEER"));
+        assert!(!peers.contains("VOTHER"));
+        // Peers may stop supporting inline chunking, e.g. if downgraded
+        peers.record("VPEER", false);
+        assert!(!peers.contains("VPEER"));
+        // Clones share the known peers
+        let clone = peers.clone();
+        clone.record("VOTHER", true);
+        assert!(peers.contains("VOTHER"));
+    }
+}
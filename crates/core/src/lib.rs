@@ -1,14 +1,19 @@
 #![warn(clippy::pedantic)]
 #![forbid(clippy::unwrap_used)]
 
+pub mod body_stream;
 pub mod chunking;
 pub mod logging;
+pub mod redact;
+pub mod secrets;
 
 use logging::Level;
 
 use core::fmt;
 
 use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use anyhow::{anyhow, bail, ensure, Context};
 use nkeys::{KeyPair, KeyPairType};
@@ -38,9 +43,66 @@ pub struct HealthCheckResponse {
     pub message: Option<String>,
 }
 
+/// Version of the schema of [`HostData`] and [`Invocation`] exchanged between hosts and
+/// providers, incremented on every change, which peers supporting older versions cannot process
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Oldest schema version, which can still be processed by this version of the crate. Data sent by
+/// peers predating schema versioning does not carry a version and is treated as version 0
+pub const MIN_SCHEMA_VERSION: u32 = 0;
+
+/// Content type of MessagePack-encoded payloads, see [`Invocation::content_type`]
+pub const CONTENT_TYPE_MSGPACK: &str = "application/msgpack";
+/// Content type of JSON-encoded payloads, see [`Invocation::content_type`]
+pub const CONTENT_TYPE_JSON: &str = "application/json";
+/// Content type of CBOR-encoded payloads, see [`Invocation::content_type`]
+pub const CONTENT_TYPE_CBOR: &str = "application/cbor";
+
+/// Negotiate the schema version to use for communicating with a peer supporting schema versions
+/// `peer_min_version` through `peer_version`, which is the newest version supported by both sides.
+///
+/// # Errors
+///
+/// Returns an error describing the mismatch if there is no schema version supported by both sides
+pub fn negotiate_schema_version(peer_version: u32, peer_min_version: u32) -> anyhow::Result<u32> {
+    let version = peer_version.min(SCHEMA_VERSION);
+    ensure!(
+        version >= peer_min_version && (MIN_SCHEMA_VERSION..=SCHEMA_VERSION).contains(&version),
+        "peer supports schema versions {peer_min_version} through {peer_version}, which are \
+         incompatible with supported versions {MIN_SCHEMA_VERSION} through {SCHEMA_VERSION}, \
+         the {} must be upgraded",
+        if version < peer_min_version {
+            "local side"
+        } else {
+            "peer"
+        }
+    );
+    Ok(version)
+}
+
+/// Ensure that data of schema `version` sent by a peer can be processed
+///
+/// # Errors
+///
+/// Returns an error describing the mismatch if `version` is not supported
+pub fn ensure_schema_version(version: u32) -> anyhow::Result<()> {
+    ensure!(
+        (MIN_SCHEMA_VERSION..=SCHEMA_VERSION).contains(&version),
+        "schema version {version} is not supported, supported versions are {MIN_SCHEMA_VERSION} \
+         through {SCHEMA_VERSION}"
+    );
+    Ok(())
+}
+
 /// initialization data for a capability provider
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct HostData {
+    /// Newest schema version supported by the host, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Oldest schema version supported by the host, see [`MIN_SCHEMA_VERSION`]
+    #[serde(default)]
+    pub min_schema_version: u32,
     #[serde(default)]
     pub host_id: String,
     #[serde(default)]
@@ -82,11 +144,267 @@ pub struct HostData {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub log_level: Option<Level>,
     pub otel_config: OtelConfig,
+    /// Time-based validation rules providers should apply to invocation claims
+    #[serde(default)]
+    pub invocation_validity: InvocationValidity,
+    /// Link definition value key patterns providers should redact from logs, in addition to
+    /// [`redact::DEFAULT_SENSITIVE_PATTERNS`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub secret_patterns: Vec<String>,
+    /// Whether providers must connect to the lattice RPC NATS server over TLS
+    #[serde(default)]
+    pub lattice_rpc_tls: bool,
+    /// TLS settings providers should use to connect to the lattice RPC NATS server
+    #[serde(default)]
+    pub lattice_rpc_tls_config: TlsConfig,
+    /// Whether providers should forward their log records to the host, see
+    /// [`logging::forwarded_logs_subject`]
+    #[serde(default)]
+    pub forward_logs: bool,
+    /// Whether providers should record the contents of invocation payloads in traces, with values
+    /// of keys matching [`HostData::secret_patterns`] redacted. Intended for debugging only
+    #[serde(default)]
+    pub capture_payloads: bool,
+    /// Port providers should serve metrics of handled invocations on, in the Prometheus text
+    /// format. Metrics are not collected if not set, see [`METRICS_PORT_ANNOTATION`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_port: Option<u16>,
+    /// Whether payloads larger than [`chunking::CHUNK_THRESHOLD_BYTES`] are chunked inline over
+    /// NATS, instead of through a JetStream object store, for peers advertising support for it,
+    /// see [`chunking::ChunkEndpoint::with_inline_chunking`]. Hosts not supporting inline chunking
+    /// leave this unset, in which case providers chunk through JetStream
+    #[serde(default)]
+    pub inline_chunking: bool,
+    /// Prefixes of further lattices the provider serves invocations and link definitions of, in
+    /// addition to [`HostData::lattice_rpc_prefix`], see [`ADDITIONAL_LATTICES_ANNOTATION`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_lattice_prefixes: Vec<String>,
+}
+
+/// TLS settings for a NATS connection
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct TlsConfig {
+    /// PEM file containing root certificates to verify the server certificate with
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_file: Option<PathBuf>,
+    /// PEM file containing the client certificate to present to the server
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_cert_file: Option<PathBuf>,
+    /// PEM file containing the private key of the client certificate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_key_file: Option<PathBuf>,
+}
+
+impl TlsConfig {
+    /// Returns `true` if any TLS setting is configured, in which case TLS is required
+    #[must_use]
+    pub fn is_configured(&self) -> bool {
+        self.ca_file.is_some() || self.client_cert_file.is_some() || self.client_key_file.is_some()
+    }
+
+    /// Apply the TLS settings to NATS connection options. If a client certificate is configured,
+    /// the server can verify it to authenticate the connection (mutual TLS).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if only one of `client_cert_file` and `client_key_file` is set
+    pub fn apply(
+        &self,
+        opts: async_nats::ConnectOptions,
+    ) -> anyhow::Result<async_nats::ConnectOptions> {
+        if !self.is_configured() {
+            return Ok(opts);
+        }
+        let opts = opts.require_tls(true);
+        let opts = if let Some(ca_file) = &self.ca_file {
+            opts.add_root_certificates(ca_file.clone())
+        } else {
+            opts
+        };
+        match (&self.client_cert_file, &self.client_key_file) {
+            (Some(cert), Some(key)) => Ok(opts.add_client_certificate(cert.clone(), key.clone())),
+            (None, None) => Ok(opts),
+            _ => bail!("TLS client certificate and key must be specified together"),
+        }
+    }
 }
 
 /// Environment settings for initializing a capability provider
 pub type HostEnvValues = WitMap<String>;
 
+/// Annotation of an actor or provider overriding the ratio of traces to sample, between 0 and 1,
+/// for traces starting at that component
+pub const TRACE_SAMPLER_RATIO_ANNOTATION: &str = "wasmcloud.dev/trace-sampler-ratio";
+
+/// Returns the trace sampling ratio override set via [`TRACE_SAMPLER_RATIO_ANNOTATION`] in
+/// `annotations`, if any
+pub fn annotated_sampler_ratio<'a>(
+    annotations: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> Option<f64> {
+    annotations
+        .into_iter()
+        .find(|(k, _)| *k == TRACE_SAMPLER_RATIO_ANNOTATION)
+        .and_then(|(_, v)| v.trim().parse::<f64>().ok())
+        .map(|ratio| ratio.clamp(0.0, 1.0))
+}
+
+/// Returns the subject updates of the provider-level configuration of the provider with public
+/// key `provider_key` are published on, see [`ProviderConfigUpdate`]
+#[must_use]
+pub fn provider_config_subject(lattice_prefix: &str, provider_key: &str) -> String {
+    format!("wasmbus.cfg.{lattice_prefix}.{provider_key}")
+}
+
+/// Returns the subject the provider instance with public key `provider_key` and link name
+/// `link_name` requests its current link definitions on, which the host responds to with a
+/// MessagePack-encoded list of [`LinkDefinition`]s
+#[must_use]
+pub fn provider_links_subject(lattice_prefix: &str, provider_key: &str, link_name: &str) -> String {
+    format!("wasmbus.rpc.{lattice_prefix}.{provider_key}.{link_name}.linkdefs.get")
+}
+
+/// Provider-level configuration delivered to running providers on [`provider_config_subject`]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ProviderConfigUpdate {
+    /// ID of the host running the provider instance to update. Instances on all hosts are
+    /// updated if empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub host_id: String,
+    /// The complete updated configuration
+    #[serde(default)]
+    pub values: HashMap<String, String>,
+}
+
+/// Update of the cluster issuers a provider instance accepts invocations from, which the host
+/// running the instance publishes on `wasmbus.rpc.<lattice>.<provider>.<link>.issuers.put` signed
+/// with its host key
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ClusterIssuersUpdate {
+    /// ID of the host that sent the update, which is the public key the update is signed with
+    pub host_id: String,
+    /// The complete set of cluster issuers to accept invocations from
+    pub cluster_issuers: ClusterIssuers,
+    /// Time the update was issued at in nanoseconds since the UNIX epoch, which lets providers
+    /// discard replayed updates
+    #[serde(default)]
+    pub issued_at: u64,
+    /// Hex-encoded signature of the update by the host key
+    #[serde(default)]
+    pub signature: String,
+}
+
+impl ClusterIssuersUpdate {
+    /// Returns the bytes of the update covered by [`Self::signature`]
+    fn signed_bytes(&self) -> Vec<u8> {
+        format!(
+            "{}\n{}\n{}",
+            self.host_id,
+            self.issued_at,
+            self.cluster_issuers.join(",")
+        )
+        .into_bytes()
+    }
+
+    /// Returns an update of the cluster issuers to `cluster_issuers` signed by `host_key`
+    ///
+    /// # Errors
+    ///
+    /// Fails if `host_key` is not a server key or signing fails
+    pub fn new(host_key: &KeyPair, cluster_issuers: ClusterIssuers) -> anyhow::Result<Self> {
+        ensure!(
+            host_key.key_pair_type() == KeyPairType::Server,
+            "cluster issuers updates must be signed by a host key"
+        );
+        let issued_at = u64::try_from(since_the_epoch()?.as_nanos())
+            .context("current time does not fit in 64 bits")?;
+        let mut update = Self {
+            host_id: host_key.public_key(),
+            cluster_issuers,
+            issued_at,
+            signature: String::default(),
+        };
+        let signature = host_key
+            .sign(&update.signed_bytes())
+            .context("failed to sign cluster issuers update")?;
+        update.signature = hex::encode(signature);
+        Ok(update)
+    }
+
+    /// Verifies that the update was signed by the host with ID `host_id`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the update was not sent by `host_id` or the signature is invalid
+    pub fn verify(&self, host_id: &str) -> anyhow::Result<()> {
+        ensure!(
+            self.host_id == host_id,
+            "update was sent by host `{}`, expected `{host_id}`",
+            self.host_id
+        );
+        let key = KeyPair::from_public_key(host_id).context("invalid host ID")?;
+        ensure!(
+            key.key_pair_type() == KeyPairType::Server,
+            "host ID `{host_id}` is not a server public key"
+        );
+        let signature = hex::decode(&self.signature).context("invalid signature encoding")?;
+        key.verify(&self.signed_bytes(), &signature)
+            .context("invalid cluster issuers update signature")
+    }
+}
+
+/// Annotation of a provider setting the port it serves metrics of handled invocations on
+pub const METRICS_PORT_ANNOTATION: &str = "wasmcloud.dev/metrics-port";
+
+/// Returns the metrics port set via [`METRICS_PORT_ANNOTATION`] in `annotations`, if any
+pub fn annotated_metrics_port<'a>(
+    annotations: impl IntoIterator<Item = (&'a String, &'a String)>,
+) -> Option<u16> {
+    annotations
+        .into_iter()
+        .find(|(k, _)| *k == METRICS_PORT_ANNOTATION)
+        .and_then(|(_, v)| v.trim().parse::<u16>().ok())
+}
+
+/// Annotation of a provider setting a comma-separated list of lattice prefixes it serves in
+/// addition to the lattice of the host running it, e.g. `tenant-a,tenant-b`
+pub const ADDITIONAL_LATTICES_ANNOTATION: &str = "wasmcloud.dev/additional-lattices";
+
+/// Returns the lattice prefixes set via [`ADDITIONAL_LATTICES_ANNOTATION`] in `annotations`,
+/// without duplicates and `lattice_prefix` of the host itself
+pub fn annotated_additional_lattices<'a>(
+    annotations: impl IntoIterator<Item = (&'a String, &'a String)>,
+    lattice_prefix: &str,
+) -> Vec<String> {
+    let Some((_, lattices)) = annotations
+        .into_iter()
+        .find(|(k, _)| *k == ADDITIONAL_LATTICES_ANNOTATION)
+    else {
+        return Vec::new();
+    };
+    let mut prefixes: Vec<String> = Vec::new();
+    for prefix in lattices.split(',').map(str::trim) {
+        if !prefix.is_empty() && prefix != lattice_prefix && !prefixes.iter().any(|p| p == prefix) {
+            prefixes.push(prefix.to_string());
+        }
+    }
+    prefixes
+}
+
+/// Annotation of an actor limiting the time a single invocation may take, e.g. `5s` or `500ms`
+pub const MAX_EXECUTION_TIME_ANNOTATION: &str = "wasmcloud.dev/max-execution-time";
+
+/// Annotation of an actor limiting the linear memory a single instance may allocate, in bytes or
+/// with a binary unit suffix, e.g. `64MiB`
+pub const MAX_MEMORY_ANNOTATION: &str = "wasmcloud.dev/max-memory";
+
+/// Annotation of an actor limiting the number of invocations handled concurrently
+pub const MAX_CONCURRENT_INVOCATIONS_ANNOTATION: &str = "wasmcloud.dev/max-concurrent-invocations";
+
+/// Annotation of an actor setting the number of pre-instantiated instances kept warm in a pool,
+/// which is refilled in the background, to avoid instantiation latency on invocation
+pub const WARM_INSTANCES_ANNOTATION: &str = "wasmcloud.dev/warm-instances";
+
 /// Configuration values for Open Telemetry
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct OtelConfig {
@@ -94,6 +412,108 @@ pub struct OtelConfig {
     pub traces_exporter: Option<String>,
     /// OTEL_EXPORTER_OTLP_ENDPOINT https://opentelemetry.io/docs/concepts/sdk-configuration/otlp-exporter-configuration/#otel_exporter_otlp_endpoint
     pub exporter_otlp_endpoint: Option<String>,
+    /// OTEL_EXPORTER_OTLP_PROTOCOL https://opentelemetry.io/docs/concepts/sdk-configuration/otlp-exporter-configuration/#otel_exporter_otlp_protocol
+    #[serde(default)]
+    pub exporter_otlp_protocol: Option<String>,
+    /// OTEL_TRACES_SAMPLER_ARG https://opentelemetry.io/docs/concepts/sdk-configuration/general-sdk-configuration/#otel_traces_sampler_arg,
+    /// the ratio of traces to sample, between 0 and 1
+    #[serde(default)]
+    pub traces_sampler_ratio: Option<f64>,
+    /// OTEL_RESOURCE_ATTRIBUTES https://opentelemetry.io/docs/concepts/sdk-configuration/general-sdk-configuration/#otel_resource_attributes
+    #[serde(default)]
+    pub resource_attributes: HashMap<String, String>,
+}
+
+impl OtelConfig {
+    /// Override values with the respective `OTEL_*` environment variables, if set. Resource
+    /// attributes from the environment are merged, replacing values of existing keys.
+    #[must_use]
+    pub fn with_env_overrides(mut self) -> Self {
+        let var = |name| std::env::var(name).ok().filter(|v| !v.trim().is_empty());
+        if let Some(exporter) = var("OTEL_TRACES_EXPORTER") {
+            self.traces_exporter = Some(exporter);
+        }
+        if let Some(endpoint) = var("OTEL_EXPORTER_OTLP_ENDPOINT") {
+            self.exporter_otlp_endpoint = Some(endpoint);
+        }
+        if let Some(protocol) = var("OTEL_EXPORTER_OTLP_PROTOCOL") {
+            self.exporter_otlp_protocol = Some(protocol);
+        }
+        if let Some(ratio) = var("OTEL_TRACES_SAMPLER_ARG").and_then(|v| v.trim().parse().ok()) {
+            self.traces_sampler_ratio = Some(ratio);
+        }
+        if let Some(attributes) = var("OTEL_RESOURCE_ATTRIBUTES") {
+            self.resource_attributes
+                .extend(parse_resource_attributes(&attributes));
+        }
+        self
+    }
+}
+
+/// Parse a comma-separated list of `key=value` resource attributes, as accepted by
+/// `OTEL_RESOURCE_ATTRIBUTES`. Malformed entries are skipped.
+#[must_use]
+pub fn parse_resource_attributes(s: &str) -> HashMap<String, String> {
+    s.split(',')
+        .filter_map(|kv| {
+            let (k, v) = kv.split_once('=')?;
+            let k = k.trim();
+            (!k.is_empty()).then(|| (k.to_string(), v.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Time-based validation rules applied to the claims of incoming invocations
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct InvocationValidity {
+    /// Maximum tolerated difference between the clock of the host that signed an invocation and
+    /// the clock of the party validating it. Applied to `exp`, `nbf` and `iat` checks.
+    pub clock_skew: Duration,
+    /// If set, invocations whose claims were issued longer than this ago are rejected as expired,
+    /// even if the claims themselves do not expire. This limits the window in which a captured
+    /// invocation can be replayed.
+    pub max_age: Option<Duration>,
+}
+
+impl InvocationValidity {
+    /// Returns `true` if `claims` are expired at `now` (time since UNIX epoch)
+    #[must_use]
+    pub fn is_expired<T>(&self, claims: &Claims<T>, now: Duration) -> bool {
+        let skew = self.clock_skew.as_secs();
+        let now = now.as_secs();
+        if claims
+            .expires
+            .is_some_and(|exp| exp.saturating_add(skew) < now)
+        {
+            return true;
+        }
+        self.max_age.is_some_and(|max_age| {
+            claims
+                .issued_at
+                .saturating_add(max_age.as_secs())
+                .saturating_add(skew)
+                < now
+        })
+    }
+
+    /// Returns `true` if `claims` are not valid yet at `now` (time since UNIX epoch)
+    #[must_use]
+    pub fn is_not_valid_yet<T>(&self, claims: &Claims<T>, now: Duration) -> bool {
+        let limit = now.as_secs().saturating_add(self.clock_skew.as_secs());
+        if claims.not_before.is_some_and(|nbf| nbf > limit) {
+            return true;
+        }
+        // Claims issued in the future can only be detected reliably once the age is checked
+        self.max_age.is_some() && claims.issued_at > limit
+    }
+}
+
+/// Returns the current time since UNIX epoch
+fn since_the_epoch() -> anyhow::Result<Duration> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system time is before UNIX epoch")
 }
 
 pub fn invocation_hash(
@@ -113,6 +533,9 @@ pub fn invocation_hash(
 /// RPC message to capability provider
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Invocation {
+    /// Schema version of the invocation, see [`SCHEMA_VERSION`]
+    #[serde(default)]
+    pub schema_version: u32,
     pub origin: WasmCloudEntity,
     pub target: WasmCloudEntity,
     #[serde(default)]
@@ -136,6 +559,19 @@ pub struct Invocation {
         deserialize_with = "deserialize_wit_map"
     )]
     pub trace_context: TraceContext,
+    /// Content type of `msg` (ex. [`CONTENT_TYPE_MSGPACK`]). Receivers expecting payloads in a
+    /// different encoding transcode the payload, and respond in the encoding of the invocation.
+    /// The encoding is unknown if not set, in which case the payload is passed on as-is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// Transport the payload was chunked through, if it exceeds
+    /// [`chunking::CHUNK_THRESHOLD_BYTES`]. Peers predating inline chunking do not set it
+    #[serde(default)]
+    pub chunk_transport: chunking::ChunkTransport,
+    /// Whether the sender can receive payloads chunked inline, e.g. the response to this
+    /// invocation, see [`chunking::ChunkEndpoint::record_peer`]
+    #[serde(default)]
+    pub inline_chunking: bool,
 }
 
 impl Invocation {
@@ -161,6 +597,32 @@ impl Invocation {
         operation: impl Into<String>,
         msg: Vec<u8>,
         trace_context: TraceContext,
+    ) -> anyhow::Result<Invocation> {
+        Self::new_with_origin_claims(
+            cluster_key,
+            host_key,
+            origin,
+            target,
+            operation,
+            msg,
+            trace_context,
+            None,
+        )
+    }
+
+    /// Creates a new invocation like [`Invocation::new`], additionally embedding `origin_claims`,
+    /// custom claims of the originating actor, in the signed invocation claims
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    #[allow(clippy::too_many_arguments)]
+    pub fn new_with_origin_claims(
+        cluster_key: &KeyPair,
+        host_key: &KeyPair,
+        origin: WasmCloudEntity,
+        target: WasmCloudEntity,
+        operation: impl Into<String>,
+        msg: Vec<u8>,
+        trace_context: TraceContext,
+        origin_claims: Option<HashMap<String, String>>,
     ) -> anyhow::Result<Invocation> {
         let operation = operation.into();
         let (_, operation) = operation
@@ -169,19 +631,23 @@ impl Invocation {
         // TODO: Support per-interface links
         let id = Uuid::from_u128(Ulid::new().into()).to_string();
         let target_url = format!("{}/{operation}", target.url());
-        let claims = jwt::Claims::<jwt::Invocation>::new(
+        let mut claims = jwt::Claims::<jwt::Invocation>::new(
             cluster_key.public_key(),
             id.to_string(),
             &target_url,
             &origin.url(),
             &invocation_hash(&target_url, origin.url(), operation, &msg),
         );
+        if let Some(metadata) = claims.metadata.as_mut() {
+            metadata.origin_claims = origin_claims;
+        }
         let encoded_claims = claims
             .encode(cluster_key)
             .context("failed to encode claims")?;
 
         let operation = operation.to_string();
         Ok(Invocation {
+            schema_version: SCHEMA_VERSION,
             content_length: msg.len() as _,
             origin,
             target,
@@ -191,6 +657,9 @@ impl Invocation {
             encoded_claims,
             host_id: host_key.public_key(),
             trace_context,
+            content_type: None,
+            chunk_transport: chunking::ChunkTransport::default(),
+            inline_chunking: false,
         })
     }
 
@@ -221,6 +690,17 @@ impl Invocation {
     /// not been forged, are not expired, etc
     #[allow(clippy::missing_errors_doc)] // TODO: Document errors
     pub fn validate_antiforgery(&self, valid_issuers: &[String]) -> anyhow::Result<()> {
+        self.validate_antiforgery_with(valid_issuers, &InvocationValidity::default())
+    }
+
+    /// Like [`Invocation::validate_antiforgery`], but checks the validity period of the
+    /// invocation claims according to `validity`
+    #[allow(clippy::missing_errors_doc)] // TODO: Document errors
+    pub fn validate_antiforgery_with(
+        &self,
+        valid_issuers: &[String],
+        validity: &InvocationValidity,
+    ) -> anyhow::Result<()> {
         match KeyPair::from_public_key(&self.host_id) {
             Ok(kp) if kp.key_pair_type() == KeyPairType::Server => (),
             _ => bail!("invalid host ID on invocation: '{}'", self.host_id),
@@ -229,11 +709,6 @@ impl Invocation {
         let token_validation =
             jwt::validate_token::<wascap::prelude::Invocation>(&self.encoded_claims)
                 .map_err(|e| anyhow!(e))?;
-        ensure!(!token_validation.expired, "invocation claims token expired");
-        ensure!(
-            !token_validation.cannot_use_yet,
-            "attempt to use invocation before claims token allows"
-        );
         ensure!(
             token_validation.signature_valid,
             "invocation claims signature invalid"
@@ -241,6 +716,15 @@ impl Invocation {
 
         let claims = Claims::<wascap::prelude::Invocation>::decode(&self.encoded_claims)
             .map_err(|e| anyhow!(e))?;
+        let now = since_the_epoch()?;
+        ensure!(
+            !validity.is_expired(&claims, now),
+            "invocation claims token expired"
+        );
+        ensure!(
+            !validity.is_not_valid_yet(&claims, now),
+            "attempt to use invocation before claims token allows"
+        );
         ensure!(
             valid_issuers.contains(&claims.issuer),
             "issuer of this invocation is not among the list of valid issuers"
@@ -287,6 +771,12 @@ pub struct InvocationResponse {
     /// optional error message
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// kind of the error, if set by the responder
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_kind: Option<InvocationErrorKind>,
+    /// Content type of `msg`, see [`Invocation::content_type`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
     /// total message size
     pub content_length: u64,
     #[serde(rename = "traceContext")]
@@ -296,6 +786,85 @@ pub struct InvocationResponse {
         deserialize_with = "deserialize_wit_map"
     )]
     pub trace_context: TraceContext,
+    /// Transport the response was chunked through, see [`Invocation::chunk_transport`]
+    #[serde(default)]
+    pub chunk_transport: chunking::ChunkTransport,
+    /// Whether the responder can receive payloads chunked inline, see
+    /// [`Invocation::inline_chunking`]
+    #[serde(default)]
+    pub inline_chunking: bool,
+}
+
+/// Kind of error an invocation failed with, see [`InvocationResponse::error_kind`].
+///
+/// Kinds are sent as kebab-case strings, and kinds unknown to the receiver are received as
+/// [`InvocationErrorKind::Custom`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum InvocationErrorKind {
+    /// Handling the invocation, or a call made while handling it, timed out
+    Timeout,
+    /// The sender of the invocation is not linked to its target
+    NotLinked,
+    /// The invocation is malformed, e.g. its payload could not be deserialized
+    Malformed,
+    /// A service the target of the invocation depends on is unavailable
+    BackendUnavailable,
+    /// The sender of the invocation is not permitted to perform it
+    PermissionDenied,
+    /// The sender of the invocation exceeded the rate of invocations it is allowed to send
+    Throttled,
+    /// Any other error
+    #[default]
+    Custom,
+}
+
+impl InvocationErrorKind {
+    /// Returns the wire representation of the kind
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::NotLinked => "not-linked",
+            Self::Malformed => "malformed",
+            Self::BackendUnavailable => "backend-unavailable",
+            Self::PermissionDenied => "permission-denied",
+            Self::Throttled => "throttled",
+            Self::Custom => "custom",
+        }
+    }
+}
+
+impl fmt::Display for InvocationErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<&str> for InvocationErrorKind {
+    fn from(s: &str) -> Self {
+        match s {
+            "timeout" => Self::Timeout,
+            "not-linked" => Self::NotLinked,
+            "malformed" => Self::Malformed,
+            "backend-unavailable" => Self::BackendUnavailable,
+            "permission-denied" => Self::PermissionDenied,
+            "throttled" => Self::Throttled,
+            _ => Self::Custom,
+        }
+    }
+}
+
+impl Serialize for InvocationErrorKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for InvocationErrorKind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let kind = String::deserialize(deserializer)?;
+        Ok(Self::from(kind.as_str()))
+    }
 }
 
 /// Link definition for binding actor to provider
@@ -410,3 +979,145 @@ where
     let values = HashMap::<String, T>::deserialize(deserializer)?;
     Ok(values.into_iter().collect())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use nkeys::KeyPair;
+
+    use super::{
+        annotated_additional_lattices, ensure_schema_version, negotiate_schema_version,
+        ClusterIssuersUpdate, ADDITIONAL_LATTICES_ANNOTATION, MIN_SCHEMA_VERSION, SCHEMA_VERSION,
+    };
+
+    fn annotations(value: &str) -> HashMap<String, String> {
+        HashMap::from([
+            ("other".to_string(), "annotation".to_string()),
+            (
+                ADDITIONAL_LATTICES_ANNOTATION.to_string(),
+                value.to_string(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn cluster_issuers_update_is_verified() {
+        let host_key = KeyPair::new_server();
+        let host_id = host_key.public_key();
+        let issuers = vec![KeyPair::new_cluster().public_key()];
+        let update =
+            ClusterIssuersUpdate::new(&host_key, issuers.clone()).expect("failed to sign update");
+        assert_eq!(update.cluster_issuers, issuers);
+        update.verify(&host_id).expect("valid update rejected");
+
+        // updates survive encoding
+        let update: ClusterIssuersUpdate =
+            serde_json::from_slice(&serde_json::to_vec(&update).expect("failed to encode"))
+                .expect("failed to decode");
+        update.verify(&host_id).expect("decoded update rejected");
+
+        // updates of a different host are rejected
+        let other_id = KeyPair::new_server().public_key();
+        assert!(update.verify(&other_id).is_err());
+        let mut forged = update.clone();
+        forged.host_id = other_id.clone();
+        assert!(forged.verify(&other_id).is_err());
+
+        // tampered updates are rejected
+        let mut forged = update.clone();
+        forged
+            .cluster_issuers
+            .push(KeyPair::new_cluster().public_key());
+        assert!(forged.verify(&host_id).is_err());
+        let mut forged = update.clone();
+        forged.issued_at += 1;
+        assert!(forged.verify(&host_id).is_err());
+
+        // unsigned updates are rejected
+        let unsigned = ClusterIssuersUpdate {
+            signature: String::default(),
+            ..update
+        };
+        assert!(unsigned.verify(&host_id).is_err());
+
+        // only host keys sign updates
+        assert!(ClusterIssuersUpdate::new(&KeyPair::new_cluster(), issuers).is_err());
+    }
+
+    #[test]
+    fn additional_lattices_are_parsed() {
+        assert_eq!(
+            annotated_additional_lattices(&annotations("tenant-a, tenant-b"), "default"),
+            vec!["tenant-a".to_string(), "tenant-b".to_string()]
+        );
+    }
+
+    #[test]
+    fn additional_lattices_skip_duplicates_and_host_lattice() {
+        assert_eq!(
+            annotated_additional_lattices(
+                &annotations("tenant-a,default,,tenant-a , tenant-b"),
+                "default"
+            ),
+            vec!["tenant-a".to_string(), "tenant-b".to_string()]
+        );
+        assert!(annotated_additional_lattices(&annotations(" default ,"), "default").is_empty());
+    }
+
+    #[test]
+    fn additional_lattices_default_to_none() {
+        let annotations = HashMap::from([("other".to_string(), "tenant-a".to_string())]);
+        assert!(annotated_additional_lattices(&annotations, "default").is_empty());
+        assert!(annotated_additional_lattices(&HashMap::new(), "default").is_empty());
+    }
+
+    #[test]
+    fn schema_version_with_same_peer() {
+        assert_eq!(
+            negotiate_schema_version(SCHEMA_VERSION, MIN_SCHEMA_VERSION).ok(),
+            Some(SCHEMA_VERSION)
+        );
+        assert!(ensure_schema_version(SCHEMA_VERSION).is_ok());
+        assert!(ensure_schema_version(MIN_SCHEMA_VERSION).is_ok());
+    }
+
+    #[test]
+    fn schema_version_with_older_peer() {
+        // Peers predating schema versioning only support version 0
+        assert_eq!(
+            negotiate_schema_version(MIN_SCHEMA_VERSION, MIN_SCHEMA_VERSION).ok(),
+            Some(MIN_SCHEMA_VERSION)
+        );
+    }
+
+    #[test]
+    fn schema_version_with_newer_peer() {
+        // The newest version supported by both sides is used
+        assert_eq!(
+            negotiate_schema_version(SCHEMA_VERSION + 1, MIN_SCHEMA_VERSION).ok(),
+            Some(SCHEMA_VERSION)
+        );
+        assert_eq!(
+            negotiate_schema_version(SCHEMA_VERSION + 1, SCHEMA_VERSION).ok(),
+            Some(SCHEMA_VERSION)
+        );
+        // Peers, which dropped support for all local versions, require a local upgrade
+        let err = negotiate_schema_version(SCHEMA_VERSION + 2, SCHEMA_VERSION + 1)
+            .expect_err("negotiation should fail");
+        assert!(err.to_string().contains("local side must be upgraded"));
+        assert!(ensure_schema_version(SCHEMA_VERSION + 1).is_err());
+    }
+
+    #[test]
+    fn schema_version_below_min() {
+        // Versions below `MIN_SCHEMA_VERSION` only exist once support for version 0 is dropped
+        if let Some(version) = MIN_SCHEMA_VERSION.checked_sub(1) {
+            let err = negotiate_schema_version(version, 0).expect_err("negotiation should fail");
+            assert!(err.to_string().contains("peer must be upgraded"));
+            assert!(ensure_schema_version(version).is_err());
+        }
+        // Invalid ranges advertised by peers are rejected
+        assert!(negotiate_schema_version(MIN_SCHEMA_VERSION, MIN_SCHEMA_VERSION + 1).is_err());
+    }
+}
@@ -1,5 +1,7 @@
 // This would be the generated types from wasi logging when we generate it
 
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -24,3 +26,35 @@ impl From<tracing::Level> for Level {
         }
     }
 }
+
+/// Returns the subject, on which providers forward log records to the host with ID `host_id`
+#[must_use]
+pub fn forwarded_logs_subject(lattice_prefix: &str, host_id: &str) -> String {
+    format!("wasmbus.rpc.{lattice_prefix}.{host_id}.logs")
+}
+
+/// A log record emitted by a provider and forwarded to its host over the lattice
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ForwardedLogRecord {
+    /// Public key of the provider, which emitted the record
+    pub provider_id: String,
+    /// Link name of the provider
+    pub link_name: String,
+    /// ID of the provider instance
+    pub instance_id: String,
+    /// Level of the record
+    pub level: Level,
+    /// Target of the record, usually the module path of the emitter
+    pub target: String,
+    /// Log message
+    pub message: String,
+    /// Structured fields of the record, formatted as strings
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub fields: HashMap<String, String>,
+    /// ID of the trace the record was emitted in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trace_id: Option<String>,
+    /// ID of the span the record was emitted in, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub span_id: Option<String>,
+}
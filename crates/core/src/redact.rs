@@ -0,0 +1,282 @@
+//! Redaction of sensitive link definition values
+//!
+//! Link definition values frequently carry credentials (tokens, passwords, connection strings),
+//! which must not end up in logs, published events or inventory responses. Values, which are
+//! [secret references](crate::secrets), only point to a secret and are never redacted.
+
+use core::fmt;
+
+use crate::secrets::SecretReference;
+use crate::LinkDefinition;
+
+/// Replacement for redacted values
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Case-insensitive key patterns considered sensitive by default. `*` matches any sequence of
+/// characters.
+pub const DEFAULT_SENSITIVE_PATTERNS: &[&str] = &[
+    "*token*",
+    "*password*",
+    "*passwd*",
+    "*secret*",
+    "*credential*",
+    "*private_key*",
+    "*api_key*",
+    "*apikey*",
+];
+
+/// Redacts values of sensitive keys
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Redactor {
+    patterns: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(DEFAULT_SENSITIVE_PATTERNS.iter().copied())
+    }
+}
+
+impl Redactor {
+    /// Construct a [`Redactor`] matching only `patterns`, without the
+    /// [`DEFAULT_SENSITIVE_PATTERNS`]
+    #[must_use]
+    pub fn new(patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            patterns: patterns
+                .into_iter()
+                .map(|pattern| pattern.as_ref().to_lowercase())
+                .collect(),
+        }
+    }
+
+    /// Additionally match `patterns`
+    #[must_use]
+    pub fn with_patterns(mut self, patterns: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        self.patterns.extend(
+            patterns
+                .into_iter()
+                .map(|pattern| pattern.as_ref().to_lowercase()),
+        );
+        self
+    }
+
+    /// Returns `true` if values stored under `key` should be redacted
+    #[must_use]
+    pub fn is_sensitive(&self, key: &str) -> bool {
+        let key = key.to_lowercase();
+        self.patterns
+            .iter()
+            .any(|pattern| glob_match(pattern.as_bytes(), key.as_bytes()))
+    }
+
+    /// Returns [`REDACTED`] if `key` is sensitive and `value` is not a secret reference, and
+    /// `value` otherwise
+    #[must_use]
+    pub fn redact<'a>(&self, key: &str, value: &'a str) -> &'a str {
+        if self.is_sensitive(key) && !SecretReference::is_reference(value) {
+            REDACTED
+        } else {
+            value
+        }
+    }
+
+    /// Redact all sensitive `values`
+    #[must_use]
+    pub fn redact_values<C>(
+        &self,
+        values: impl IntoIterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
+    ) -> C
+    where
+        C: FromIterator<(String, String)>,
+    {
+        values
+            .into_iter()
+            .map(|(k, v)| {
+                let (k, v) = (k.as_ref(), v.as_ref());
+                (k.to_string(), self.redact(k, v).to_string())
+            })
+            .collect()
+    }
+
+    /// Returns a copy of `ld` with all sensitive values redacted
+    #[must_use]
+    pub fn redact_link(&self, ld: &LinkDefinition) -> LinkDefinition {
+        LinkDefinition {
+            values: self.redact_values(ld.values.iter().map(|(k, v)| (k, v))),
+            ..ld.clone()
+        }
+    }
+
+    /// Wraps `ld` in a type, which redacts sensitive values when formatted with [`fmt::Debug`]
+    #[must_use]
+    pub fn link<'a>(&'a self, ld: &'a LinkDefinition) -> RedactedLink<'a> {
+        RedactedLink { redactor: self, ld }
+    }
+}
+
+/// A [`LinkDefinition`], which redacts sensitive values when formatted
+pub struct RedactedLink<'a> {
+    redactor: &'a Redactor,
+    ld: &'a LinkDefinition,
+}
+
+impl fmt::Debug for RedactedLink<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let LinkDefinition {
+            actor_id,
+            provider_id,
+            link_name,
+            contract_id,
+            values,
+        } = self.ld;
+        f.debug_struct("LinkDefinition")
+            .field("actor_id", actor_id)
+            .field("provider_id", provider_id)
+            .field("link_name", link_name)
+            .field("contract_id", contract_id)
+            .field(
+                "values",
+                &values
+                    .iter()
+                    .map(|(k, v)| (k, self.redactor.redact(k, v)))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// Match `s` against `pattern`, where `*` matches any (possibly empty) sequence of bytes
+fn glob_match(pattern: &[u8], s: &[u8]) -> bool {
+    let (mut p, mut i) = (0, 0);
+    // Position of the last `*` in the pattern and the input position it was matched at
+    let mut backtrack = None;
+    while i < s.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p, i));
+                p += 1;
+            }
+            Some(c) if *c == s[i] => {
+                p += 1;
+                i += 1;
+            }
+            _ => {
+                let Some((star, matched)) = backtrack else {
+                    return false;
+                };
+                p = star + 1;
+                i = matched + 1;
+                backtrack = Some((star, matched + 1));
+            }
+        }
+    }
+    pattern[p..].iter().all(|c| *c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(values: &[(&str, &str)]) -> LinkDefinition {
+        LinkDefinition {
+            actor_id: "MACTOR".to_string(),
+            provider_id: "VPROVIDER".to_string(),
+            link_name: "default".to_string(),
+            contract_id: "wasmcloud:keyvalue".to_string(),
+            values: values
+                .iter()
+                .map(|(k, v)| ((*k).to_string(), (*v).to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn glob() {
+        let matches = |pattern: &str, s: &str| glob_match(pattern.as_bytes(), s.as_bytes());
+        // `*` matches any sequence, including the empty one
+        assert!(matches("*", ""));
+        assert!(matches("*", "token"));
+        assert!(matches("**", "token"));
+        // Prefixes and suffixes
+        assert!(matches("db_*", "db_password"));
+        assert!(!matches("db_*", "password_db"));
+        assert!(matches("*_key", "private_key"));
+        assert!(!matches("*_key", "key_id"));
+        assert!(matches("*token*", "token"));
+        assert!(matches("*token*", "refresh_token_id"));
+        assert!(matches("a*b*c", "aXbYbZc"));
+        assert!(!matches("a*b*c", "aXcYb"));
+        // Patterns without `*` must match exactly
+        assert!(matches("token", "token"));
+        assert!(!matches("token", "tokens"));
+        // The empty pattern only matches the empty key
+        assert!(matches("", ""));
+        assert!(!matches("", "token"));
+    }
+
+    #[test]
+    fn sensitive_keys() {
+        let redactor = Redactor::default();
+        assert!(redactor.is_sensitive("PASSWORD"));
+        assert!(redactor.is_sensitive("aws_secret_access_key"));
+        assert!(!redactor.is_sensitive("url"));
+
+        let redactor = Redactor::new(["DB_*"]);
+        assert!(redactor.is_sensitive("db_url"));
+        assert!(!redactor.is_sensitive("password"));
+        let redactor = redactor.with_patterns(["*password"]);
+        assert!(redactor.is_sensitive("password"));
+
+        let redactor = Redactor::new([""]);
+        assert!(!redactor.is_sensitive("token"));
+        assert!(!Redactor::new(Vec::<String>::new()).is_sensitive("token"));
+    }
+
+    #[test]
+    fn link_values_redacted() {
+        let redactor = Redactor::default().with_patterns(["conn_*"]);
+        let ld = link(&[
+            ("API_KEY", "abc"),
+            ("conn_string", "postgres://app:hunter2@db"),
+            ("region", "us-east-1"),
+        ]);
+        let redacted = redactor.redact_link(&ld);
+        assert_eq!(
+            redacted.values,
+            link(&[
+                ("API_KEY", REDACTED),
+                ("conn_string", REDACTED),
+                ("region", "us-east-1"),
+            ])
+            .values
+        );
+        assert_eq!(redacted.actor_id, ld.actor_id);
+        assert_eq!(redacted.contract_id, ld.contract_id);
+
+        let formatted = format!("{:?}", redactor.link(&ld));
+        assert!(formatted.contains(REDACTED));
+        assert!(formatted.contains("us-east-1"));
+        assert!(!formatted.contains("hunter2"));
+        assert!(!formatted.contains("abc"));
+    }
+
+    #[test]
+    fn secret_references_not_redacted() {
+        let redactor = Redactor::default();
+        let ld = redactor.redact_link(&link(&[
+            ("password", "secret://vault/db#password"),
+            ("token", "hunter2"),
+            ("url", "redis://127.0.0.1:6379"),
+        ]));
+        assert_eq!(
+            ld.values,
+            link(&[
+                ("password", "secret://vault/db#password"),
+                ("token", REDACTED),
+                ("url", "redis://127.0.0.1:6379"),
+            ])
+            .values
+        );
+    }
+}
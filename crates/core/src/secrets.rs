@@ -0,0 +1,333 @@
+//! Retrieval of secrets referenced by link definition values from secrets backends on the lattice.
+//!
+//! Instead of holding credentials in plaintext, a link definition value may refer to a secret as
+//! `secret://<backend>/<path>#<key>`, which providers resolve by requesting it from the backend
+//! on [`secrets_subject`]. The key is optional, for backends storing a single value per path.
+//!
+//! # Trust model
+//!
+//! Providers do not hold their own private key, so requests are signed with the cluster key the
+//! host passes to its providers, like invocations. A backend accepting only requests signed by a
+//! cluster issuer (see [`SecretRequest::validate`]) therefore only serves hosts and providers of
+//! the cluster, but cannot tell providers apart: any provider of the cluster may request secrets
+//! on behalf of another. Backends, which authorize requests by [`SecretRequest::provider_id`],
+//! must only be used if all providers of the cluster are trusted not to impersonate each other.
+
+use core::fmt;
+use core::str::FromStr;
+use core::time::Duration;
+
+use anyhow::{anyhow, bail, ensure, Context};
+use nkeys::KeyPair;
+use serde::{Deserialize, Serialize};
+use ulid::Ulid;
+use wascap::jwt;
+use wascap::prelude::Claims;
+
+use crate::{invocation_hash, since_the_epoch, InvocationValidity};
+
+/// Scheme of secret references in link definition values
+pub const SECRET_REFERENCE_SCHEME: &str = "secret://";
+
+/// Time after which the signed claims of a [`SecretRequest`] expire
+pub const SECRET_REQUEST_TTL: Duration = Duration::from_secs(60);
+
+/// Operation signed in the claims of a [`SecretRequest`]
+const SECRET_REQUEST_OPERATION: &str = "secrets.get";
+
+/// Returns the subject, on which the secrets backend named `backend` serves [`SecretRequest`]s
+#[must_use]
+pub fn secrets_subject(lattice_prefix: &str, backend: &str) -> String {
+    format!("wasmbus.secrets.{lattice_prefix}.{backend}.get")
+}
+
+/// A reference to a secret stored in a secrets backend, see the [module documentation](self)
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SecretReference {
+    /// Name of the secrets backend storing the secret
+    pub backend: String,
+    /// Path of the secret in the backend
+    pub path: String,
+    /// Key of the value within the secret, if the secret holds multiple values
+    pub key: Option<String>,
+}
+
+impl SecretReference {
+    /// Returns true if `value` is a secret reference, which may still be invalid
+    #[must_use]
+    pub fn is_reference(value: &str) -> bool {
+        value.starts_with(SECRET_REFERENCE_SCHEME)
+    }
+}
+
+impl FromStr for SecretReference {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let reference = s.strip_prefix(SECRET_REFERENCE_SCHEME).with_context(|| {
+            format!("secret reference must start with `{SECRET_REFERENCE_SCHEME}`")
+        })?;
+        let (location, key) = match reference.split_once('#') {
+            Some((location, key)) => (location, Some(key)),
+            None => (reference, None),
+        };
+        let Some((backend, path)) = location.split_once('/') else {
+            bail!("secret reference is missing a path")
+        };
+        if backend.is_empty() || backend.contains(['.', '*', '>', ' ']) {
+            bail!("invalid secrets backend name `{backend}`")
+        }
+        if path.is_empty() {
+            bail!("secret reference is missing a path")
+        }
+        if key.is_some_and(str::is_empty) {
+            bail!("secret reference has an empty key")
+        }
+        Ok(Self {
+            backend: backend.to_string(),
+            path: path.to_string(),
+            key: key.map(ToString::to_string),
+        })
+    }
+}
+
+impl fmt::Display for SecretReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SECRET_REFERENCE_SCHEME}{}/{}", self.backend, self.path)?;
+        if let Some(key) = &self.key {
+            write!(f, "#{key}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Request for a secret sent to a secrets backend, encoded as JSON
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SecretRequest {
+    /// Path of the secret in the backend
+    pub path: String,
+    /// Key of the value within the secret
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    /// Public key of the provider requesting the secret, which the backend may use to authorize
+    /// the request, see the [trust model](self#trust-model)
+    #[serde(default)]
+    pub provider_id: String,
+    /// Claims signed with the cluster key, covering the secret reference and `provider_id`
+    #[serde(default)]
+    pub encoded_claims: String,
+}
+
+impl SecretRequest {
+    /// Constructs a request for the secret referenced by `reference` on behalf of the provider
+    /// with public key `provider_id`, signed with `cluster_key`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the claims cannot be signed
+    pub fn new(
+        cluster_key: &KeyPair,
+        reference: &SecretReference,
+        provider_id: impl Into<String>,
+    ) -> anyhow::Result<Self> {
+        let provider_id = provider_id.into();
+        let target_url = reference.to_string();
+        let now = since_the_epoch()?.as_secs();
+        let claims = jwt::Claims::<jwt::Invocation>::with_dates(
+            cluster_key.public_key(),
+            Ulid::new().to_string(),
+            None,
+            Some(now.saturating_add(SECRET_REQUEST_TTL.as_secs())),
+            &target_url,
+            &provider_id,
+            &invocation_hash(&target_url, &provider_id, SECRET_REQUEST_OPERATION, b""),
+        );
+        let encoded_claims = claims
+            .encode(cluster_key)
+            .context("failed to encode claims")?;
+        Ok(Self {
+            path: reference.path.clone(),
+            key: reference.key.clone(),
+            provider_id,
+            encoded_claims,
+        })
+    }
+
+    /// Returns the reference to the requested secret, stored in the backend named `backend`
+    #[must_use]
+    pub fn reference(&self, backend: &str) -> SecretReference {
+        SecretReference {
+            backend: backend.to_string(),
+            path: self.path.clone(),
+            key: self.key.clone(),
+        }
+    }
+
+    /// Validates that the request for a secret of the backend named `backend` was signed by one of
+    /// `valid_issuers`, has not expired and was not altered
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the request is not valid
+    pub fn validate(&self, backend: &str, valid_issuers: &[String]) -> anyhow::Result<()> {
+        let validation =
+            jwt::validate_token::<jwt::Invocation>(&self.encoded_claims).map_err(|e| anyhow!(e))?;
+        ensure!(
+            validation.signature_valid,
+            "secret request claims signature invalid"
+        );
+        let claims =
+            Claims::<jwt::Invocation>::decode(&self.encoded_claims).map_err(|e| anyhow!(e))?;
+        let now = since_the_epoch()?;
+        let validity = InvocationValidity::default();
+        ensure!(
+            claims.expires.is_some() && !validity.is_expired(&claims, now),
+            "secret request claims expired"
+        );
+        ensure!(
+            !validity.is_not_valid_yet(&claims, now),
+            "secret request claims not valid yet"
+        );
+        ensure!(
+            valid_issuers.contains(&claims.issuer),
+            "issuer of this secret request is not among the list of valid issuers"
+        );
+        let metadata = claims
+            .metadata
+            .context("no wascap metadata found on claims")?;
+        let target_url = self.reference(backend).to_string();
+        ensure!(
+            metadata.target_url == target_url,
+            "secret request claims and requested secret do not match"
+        );
+        ensure!(
+            metadata.origin_url == self.provider_id,
+            "secret request claims and provider ID do not match"
+        );
+        ensure!(
+            metadata.invocation_hash
+                == invocation_hash(
+                    &target_url,
+                    &self.provider_id,
+                    SECRET_REQUEST_OPERATION,
+                    b""
+                ),
+            "secret request hash does not match signed claims hash"
+        );
+        Ok(())
+    }
+}
+
+/// Response of a secrets backend to a [`SecretRequest`], encoded as JSON. Exactly one of `value`
+/// and `error` is set
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct SecretResponse {
+    /// The value of the secret
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// The reason the secret could not be retrieved
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nkeys::KeyPairType;
+
+    #[test]
+    fn reference_round_trip() {
+        for (s, backend, path, key) in [
+            ("secret://vault/db", "vault", "db", None),
+            (
+                "secret://vault/db#password",
+                "vault",
+                "db",
+                Some("password"),
+            ),
+            (
+                "secret://vault/apps/kv/redis#url",
+                "vault",
+                "apps/kv/redis",
+                Some("url"),
+            ),
+        ] {
+            let reference: SecretReference = s.parse().expect("failed to parse reference");
+            assert_eq!(
+                reference,
+                SecretReference {
+                    backend: backend.to_string(),
+                    path: path.to_string(),
+                    key: key.map(ToString::to_string),
+                }
+            );
+            assert_eq!(reference.to_string(), s);
+            assert!(SecretReference::is_reference(s));
+        }
+    }
+
+    #[test]
+    fn invalid_references() {
+        for s in [
+            "vault/db",
+            "secret://",
+            "secret://vault",
+            "secret://vault/",
+            "secret:///db",
+            "secret://vault/db#",
+            "secret://va.ult/db",
+            "secret://*/db",
+            "secret://>/db",
+            "secret://my vault/db",
+        ] {
+            assert!(s.parse::<SecretReference>().is_err(), "`{s}` was accepted");
+        }
+        assert!(!SecretReference::is_reference("redis://127.0.0.1"));
+    }
+
+    #[test]
+    fn signed_requests() -> anyhow::Result<()> {
+        let cluster_key = KeyPair::new(KeyPairType::Cluster);
+        let issuers = vec![cluster_key.public_key()];
+        let reference: SecretReference = "secret://vault/db#password".parse()?;
+        let request = SecretRequest::new(&cluster_key, &reference, "VPROVIDER")?;
+        assert_eq!(request.reference("vault"), reference);
+        request.validate("vault", &issuers)?;
+
+        // The request survives encoding
+        let request: SecretRequest = serde_json::from_slice(&serde_json::to_vec(&request)?)?;
+        request.validate("vault", &issuers)?;
+
+        // Requests signed by other clusters are rejected
+        let other_key = KeyPair::new(KeyPairType::Cluster);
+        assert!(request
+            .validate("vault", &[other_key.public_key()])
+            .is_err());
+        // Requests cannot be redirected to other secrets or backends
+        assert!(request.validate("other", &issuers).is_err());
+        let altered = SecretRequest {
+            path: "other".to_string(),
+            ..request.clone()
+        };
+        assert!(altered.validate("vault", &issuers).is_err());
+        let altered = SecretRequest {
+            key: None,
+            ..request.clone()
+        };
+        assert!(altered.validate("vault", &issuers).is_err());
+        // Requests cannot be made on behalf of other providers
+        let altered = SecretRequest {
+            provider_id: "VOTHER".to_string(),
+            ..request.clone()
+        };
+        assert!(altered.validate("vault", &issuers).is_err());
+        // Unsigned requests are rejected
+        let unsigned = SecretRequest {
+            encoded_claims: String::new(),
+            ..request
+        };
+        assert!(unsigned.validate("vault", &issuers).is_err());
+        Ok(())
+    }
+}
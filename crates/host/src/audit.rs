@@ -0,0 +1,588 @@
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+use core::time::Duration;
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde::Serialize;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::spawn;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{instrument, warn};
+use wasmcloud_core::WasmCloudEntity;
+
+/// Default size, in bytes, after which an audit log file is rotated
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Default number of rotated audit log files to keep
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+/// Destination of audit records
+#[derive(Clone, Debug)]
+pub enum Sink {
+    /// Append records as JSON lines to a file at `path`. Once the file would exceed `max_bytes`,
+    /// it is renamed to `<path>.1`, shifting older files up to `<path>.<max_files>`, which is
+    /// removed.
+    File {
+        /// Path of the active audit log file
+        path: PathBuf,
+        /// Size, in bytes, after which the file is rotated
+        max_bytes: u64,
+        /// Number of rotated files to keep
+        max_files: usize,
+    },
+    /// Publish records as JSON on a NATS subject using the control interface connection
+    Nats {
+        /// Subject to publish records on
+        subject: String,
+    },
+}
+
+/// Audit log configuration
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Where to write audit records
+    pub sink: Sink,
+    /// Fraction, between 0 and 1, of successful invocations to record. Sampling is based on the
+    /// invocation ID, so all hosts make the same decision for the same invocation.
+    pub sample_rate: f64,
+    /// Whether failed and denied invocations are subject to sampling as well. If `false`, they
+    /// are always recorded.
+    pub sample_failures: bool,
+}
+
+impl Config {
+    /// Construct a configuration recording all invocations to `sink`
+    #[must_use]
+    pub fn new(sink: Sink) -> Self {
+        Self {
+            sink,
+            sample_rate: 1.0,
+            sample_failures: false,
+        }
+    }
+}
+
+/// Outcome of an invocation
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultClass {
+    /// The invocation was handled successfully
+    Success,
+    /// The invocation was rejected by claims validation or policy
+    Denied,
+    /// The invocation failed
+    Error,
+}
+
+/// Error returned when an invocation is rejected by claims validation or policy, used to classify
+/// the invocation as [`ResultClass::Denied`]
+#[derive(Debug)]
+pub(crate) struct Denied(pub String);
+
+impl fmt::Display for Denied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for Denied {}
+
+impl ResultClass {
+    /// Classify the result of handling an invocation
+    pub(crate) fn of<T>(res: &anyhow::Result<T>) -> Self {
+        match res {
+            Ok(_) => Self::Success,
+            Err(e) if e.downcast_ref::<Denied>().is_some() => Self::Denied,
+            Err(_) => Self::Error,
+        }
+    }
+}
+
+/// An entity taking part in an invocation
+#[derive(Clone, Debug, Serialize)]
+pub struct Entity {
+    /// Public key of the actor or provider
+    pub public_key: String,
+    /// Contract ID of the provider, empty for actors
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub contract_id: String,
+    /// Link name of the provider, empty for actors
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub link_name: String,
+}
+
+impl From<&WasmCloudEntity> for Entity {
+    fn from(entity: &WasmCloudEntity) -> Self {
+        Self {
+            public_key: entity.public_key.clone(),
+            contract_id: entity.contract_id.clone(),
+            link_name: entity.link_name.clone(),
+        }
+    }
+}
+
+/// A single audit record describing a handled invocation
+#[derive(Clone, Debug, Serialize)]
+pub struct Record {
+    /// RFC 3339 timestamp of when handling the invocation finished
+    pub timestamp: String,
+    /// Public key of the host, which handled the invocation
+    pub host_id: String,
+    /// ID of the invocation
+    pub invocation_id: String,
+    /// Caller of the invocation
+    pub source: Entity,
+    /// Target of the invocation
+    pub target: Entity,
+    /// Invoked operation
+    pub operation: String,
+    /// Outcome of the invocation
+    pub result: ResultClass,
+    /// Time spent handling the invocation, in microseconds
+    pub duration_us: u64,
+}
+
+/// Number of records buffered for the background writer, after which records are dropped
+const QUEUE_CAPACITY: usize = 4096;
+
+#[derive(Debug)]
+enum Writer {
+    File(RotatingFile),
+    Nats {
+        nats: async_nats::Client,
+        subject: String,
+    },
+}
+
+impl Writer {
+    async fn write(&mut self, record: &Record) -> anyhow::Result<()> {
+        let mut buf = serde_json::to_vec(record).context("failed to encode audit record")?;
+        match self {
+            Self::File(file) => {
+                buf.push(b'\n');
+                file.write(&buf).await
+            }
+            Self::Nats { nats, subject } => nats
+                .publish(subject.clone(), buf.into())
+                .await
+                .context("failed to publish audit record"),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Message {
+    Record(Record),
+    /// Acknowledged once all records queued before it are written
+    Flush(oneshot::Sender<()>),
+}
+
+/// Writes queued records until all senders are dropped
+async fn write_records(
+    mut writer: Writer,
+    mut messages: mpsc::Receiver<Message>,
+    dropped: Arc<AtomicU64>,
+) {
+    while let Some(msg) = messages.recv().await {
+        match msg {
+            Message::Record(record) => {
+                if let Err(e) = writer.write(&record).await {
+                    warn!(
+                        ?e,
+                        invocation_id = record.invocation_id,
+                        "failed to write audit record"
+                    );
+                }
+            }
+            Message::Flush(done) => {
+                let _ = done.send(());
+            }
+        }
+        let dropped = dropped.swap(0, Ordering::Relaxed);
+        if dropped > 0 {
+            warn!(dropped, "audit log queue was full, records were dropped");
+        }
+    }
+}
+
+/// 64-bit FNV-1a hash of `buf`, which, unlike [`std::hash::DefaultHasher`], is stable across
+/// hosts and Rust versions
+fn fnv1a(buf: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    buf.iter().fold(OFFSET_BASIS, |hash, b| {
+        (hash ^ u64::from(*b)).wrapping_mul(PRIME)
+    })
+}
+
+/// An append-only audit log of invocations handled by the host. Records are written by a
+/// background task, so recording never blocks handling an invocation. If the writer falls behind
+/// by more than [`QUEUE_CAPACITY`] records, new records are dropped and the number of dropped
+/// records is logged.
+#[derive(Debug)]
+pub(crate) struct Log {
+    host_id: String,
+    sample_rate: f64,
+    sample_failures: bool,
+    messages: mpsc::Sender<Message>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Log {
+    /// Open the audit log described by `config` and spawn its writer
+    pub(crate) async fn new(
+        config: Config,
+        host_id: String,
+        nats: async_nats::Client,
+    ) -> anyhow::Result<Self> {
+        let writer = match config.sink {
+            Sink::File {
+                path,
+                max_bytes,
+                max_files,
+            } => Writer::File(RotatingFile::open(path, max_bytes, max_files).await?),
+            Sink::Nats { subject } => Writer::Nats { nats, subject },
+        };
+        Ok(Self::spawn(&config, host_id, writer))
+    }
+
+    fn spawn(config: &Config, host_id: String, writer: Writer) -> Self {
+        let (messages, rx) = mpsc::channel(QUEUE_CAPACITY);
+        let dropped = Arc::default();
+        spawn(write_records(writer, rx, Arc::clone(&dropped)));
+        Self {
+            host_id,
+            sample_rate: config.sample_rate.clamp(0.0, 1.0),
+            sample_failures: config.sample_failures,
+            messages,
+            dropped,
+        }
+    }
+
+    fn sampled(&self, invocation_id: &str, result: ResultClass) -> bool {
+        if result != ResultClass::Success && !self.sample_failures {
+            return true;
+        }
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+        #[allow(clippy::cast_precision_loss)] // Precision loss is irrelevant for sampling
+        let point = fnv1a(invocation_id.as_bytes()) as f64 / u64::MAX as f64;
+        point < self.sample_rate
+    }
+
+    /// Record the outcome of handling an invocation, subject to sampling. The record is queued
+    /// for the background writer. Failures to write the record are logged, but never fail the
+    /// invocation.
+    #[instrument(level = "trace", skip(self, source, target))]
+    pub(crate) fn record(
+        &self,
+        invocation_id: &str,
+        source: &WasmCloudEntity,
+        target: &WasmCloudEntity,
+        operation: &str,
+        result: ResultClass,
+        duration: Duration,
+    ) {
+        if !self.sampled(invocation_id, result) {
+            return;
+        }
+        let timestamp = match OffsetDateTime::now_utc().format(&Rfc3339) {
+            Ok(timestamp) => timestamp,
+            Err(e) => {
+                warn!(?e, "failed to format audit record timestamp");
+                return;
+            }
+        };
+        let record = Record {
+            timestamp,
+            host_id: self.host_id.clone(),
+            invocation_id: invocation_id.to_string(),
+            source: source.into(),
+            target: target.into(),
+            operation: operation.to_string(),
+            result,
+            duration_us: duration.as_micros().try_into().unwrap_or(u64::MAX),
+        };
+        match self.messages.try_send(Message::Record(record)) {
+            Ok(()) => {}
+            Err(mpsc::error::TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(mpsc::error::TrySendError::Closed(_)) => {
+                warn!(invocation_id, "audit log writer stopped, dropping record");
+            }
+        }
+    }
+
+    /// Wait for all records recorded so far to be written
+    pub(crate) async fn flush(&self) {
+        let (done, written) = oneshot::channel();
+        if self.messages.send(Message::Flush(done)).await.is_ok() {
+            let _ = written.await;
+        }
+    }
+}
+
+/// An append-only file, which is rotated once it exceeds a size limit
+#[derive(Debug)]
+pub(crate) struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    /// Open the file at `path` for appending, creating it if it does not exist
+    pub(crate) async fn open(
+        path: PathBuf,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> anyhow::Result<Self> {
+        let file = open_append(&path).await?;
+        let size = file
+            .metadata()
+            .await
+            .with_context(|| format!("failed to query metadata of `{}`", path.display()))?
+            .len();
+        Ok(Self {
+            path,
+            file,
+            size,
+            max_bytes,
+            max_files,
+        })
+    }
+
+    /// Append `buf`, rotating the file first if it would exceed the size limit
+    pub(crate) async fn write(&mut self, buf: &[u8]) -> anyhow::Result<()> {
+        if self.size > 0 && self.size.saturating_add(buf.len() as u64) > self.max_bytes {
+            self.rotate().await?;
+        }
+        self.file
+            .write_all(buf)
+            .await
+            .with_context(|| format!("failed to write `{}`", self.path.display()))?;
+        self.file
+            .flush()
+            .await
+            .with_context(|| format!("failed to flush `{}`", self.path.display()))?;
+        self.size = self.size.saturating_add(buf.len() as u64);
+        Ok(())
+    }
+
+    async fn rotate(&mut self) -> anyhow::Result<()> {
+        self.file
+            .sync_all()
+            .await
+            .with_context(|| format!("failed to sync `{}`", self.path.display()))?;
+        if self.max_files == 0 {
+            fs::remove_file(&self.path)
+                .await
+                .with_context(|| format!("failed to remove `{}`", self.path.display()))?;
+        } else {
+            for n in (1..self.max_files).rev() {
+                let from = rotated_path(&self.path, n);
+                if fs::try_exists(&from).await.unwrap_or(false) {
+                    fs::rename(&from, rotated_path(&self.path, n + 1))
+                        .await
+                        .with_context(|| format!("failed to rotate `{}`", from.display()))?;
+                }
+            }
+            fs::rename(&self.path, rotated_path(&self.path, 1))
+                .await
+                .with_context(|| format!("failed to rotate `{}`", self.path.display()))?;
+        }
+        self.file = open_append(&self.path).await?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(format!(".{n}"));
+    path.into()
+}
+
+async fn open_append(path: &Path) -> anyhow::Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("failed to open `{}`", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(public_key: &str, contract_id: &str) -> WasmCloudEntity {
+        WasmCloudEntity {
+            public_key: public_key.to_string(),
+            contract_id: contract_id.to_string(),
+            link_name: if contract_id.is_empty() {
+                String::new()
+            } else {
+                "default".to_string()
+            },
+        }
+    }
+
+    fn config(sample_rate: f64, sample_failures: bool) -> Config {
+        Config {
+            sample_rate,
+            sample_failures,
+            ..Config::new(Sink::Nats {
+                subject: "wasmbus.audit".to_string(),
+            })
+        }
+    }
+
+    async fn open_log(
+        path: &Path,
+        config: &Config,
+        max_bytes: u64,
+        max_files: usize,
+    ) -> anyhow::Result<Log> {
+        let file = RotatingFile::open(path.to_path_buf(), max_bytes, max_files).await?;
+        Ok(Log::spawn(config, "NHOST".to_string(), Writer::File(file)))
+    }
+
+    #[test]
+    fn fnv1a_known_values() {
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x8594_4171_f739_67e8);
+    }
+
+    #[tokio::test]
+    async fn sampling() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("audit.log");
+
+        let all = open_log(&path, &config(1.0, false), DEFAULT_MAX_FILE_BYTES, 1).await?;
+        assert!(all.sampled("invocation", ResultClass::Success));
+
+        let none = open_log(&path, &config(0.0, false), DEFAULT_MAX_FILE_BYTES, 1).await?;
+        assert!(!none.sampled("invocation", ResultClass::Success));
+        // Failures are always recorded unless they are sampled as well
+        assert!(none.sampled("invocation", ResultClass::Error));
+        assert!(none.sampled("invocation", ResultClass::Denied));
+
+        let none = open_log(&path, &config(0.0, true), DEFAULT_MAX_FILE_BYTES, 1).await?;
+        assert!(!none.sampled("invocation", ResultClass::Error));
+        assert!(!none.sampled("invocation", ResultClass::Denied));
+
+        // Sampling is deterministic and close to the configured rate
+        let half = open_log(&path, &config(0.5, false), DEFAULT_MAX_FILE_BYTES, 1).await?;
+        let sampled = (0..10_000)
+            .filter(|i| half.sampled(&format!("invocation-{i}"), ResultClass::Success))
+            .count();
+        assert!((4_500..5_500).contains(&sampled), "sampled {sampled}");
+        for i in 0..100 {
+            let id = format!("invocation-{i}");
+            assert_eq!(
+                half.sampled(&id, ResultClass::Success),
+                half.sampled(&id, ResultClass::Success)
+            );
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn records_written_in_background() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("audit.log");
+        let log = open_log(&path, &config(1.0, false), DEFAULT_MAX_FILE_BYTES, 1).await?;
+
+        let actor = entity("MACTOR", "");
+        let provider = entity("VPROVIDER", "wasmcloud:keyvalue");
+        log.record(
+            "first",
+            &actor,
+            &provider,
+            "KeyValue.Get",
+            ResultClass::Success,
+            Duration::from_micros(42),
+        );
+        log.record(
+            "second",
+            &provider,
+            &actor,
+            "HttpServer.HandleRequest",
+            ResultClass::Denied,
+            Duration::from_millis(1),
+        );
+        log.flush().await;
+
+        let records = fs::read_to_string(&path).await?;
+        let records = records
+            .lines()
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<serde_json::Value>, _>>()?;
+        let [first, second] = &records[..] else {
+            panic!("expected two records, got {records:?}");
+        };
+        assert_eq!(first["host_id"], "NHOST");
+        assert_eq!(first["invocation_id"], "first");
+        assert_eq!(first["source"]["public_key"], "MACTOR");
+        assert!(first["source"].get("contract_id").is_none());
+        assert_eq!(first["target"]["contract_id"], "wasmcloud:keyvalue");
+        assert_eq!(first["target"]["link_name"], "default");
+        assert_eq!(first["operation"], "KeyValue.Get");
+        assert_eq!(first["result"], "success");
+        assert_eq!(first["duration_us"], 42);
+        assert_eq!(second["invocation_id"], "second");
+        assert_eq!(second["result"], "denied");
+        assert_eq!(second["duration_us"], 1000);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn file_rotation() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("audit.log");
+        let mut file = RotatingFile::open(path.clone(), 8, 2).await?;
+        file.write(b"first\n").await?;
+        file.write(b"second\n").await?;
+        file.write(b"third\n").await?;
+        file.write(b"fourth\n").await?;
+
+        assert_eq!(fs::read_to_string(&path).await?, "fourth\n");
+        assert_eq!(fs::read_to_string(rotated_path(&path, 1)).await?, "third\n");
+        assert_eq!(
+            fs::read_to_string(rotated_path(&path, 2)).await?,
+            "second\n"
+        );
+        assert!(!fs::try_exists(rotated_path(&path, 3)).await?);
+
+        // Reopening continues appending to the active file
+        let mut file = RotatingFile::open(path.clone(), 16, 2).await?;
+        file.write(b"fifth\n").await?;
+        assert_eq!(fs::read_to_string(&path).await?, "fourth\nfifth\n");
+        Ok(())
+    }
+
+    #[test]
+    fn result_class() {
+        assert_eq!(ResultClass::of(&anyhow::Ok(())), ResultClass::Success);
+        assert_eq!(
+            ResultClass::of::<()>(&Err(anyhow::anyhow!("failed"))),
+            ResultClass::Error
+        );
+        assert_eq!(
+            ResultClass::of::<()>(&Err(Denied("not signed".to_string()).into())),
+            ResultClass::Denied
+        );
+    }
+}
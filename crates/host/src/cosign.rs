@@ -0,0 +1,339 @@
+//! Verification of cosign signatures of artifacts fetched from OCI registries. Signatures can
+//! only be verified if the crate is built with the `cosign` feature.
+
+use core::str::FromStr;
+
+use anyhow::{bail, Context as _};
+use oci_distribution::secrets::RegistryAuth;
+use serde::Serialize;
+#[cfg(feature = "cosign")]
+use sigstore::cosign::verification_constraint::{
+    CertSubjectEmailVerifier, CertSubjectUrlVerifier, PublicKeyVerifier, VerificationConstraint,
+};
+#[cfg(feature = "cosign")]
+use sigstore::cosign::{verify_constraints, ClientBuilder, CosignCapabilities};
+#[cfg(feature = "cosign")]
+use sigstore::crypto::SigningScheme;
+#[cfg(feature = "cosign")]
+use sigstore::registry::{Auth, ClientConfig, ClientProtocol};
+#[cfg(feature = "cosign")]
+use sigstore::tuf::SigstoreRepository;
+#[cfg(feature = "cosign")]
+use tracing::debug;
+use tracing::{instrument, warn};
+
+/// Configuration of cosign signature verification of artifacts fetched from OCI registries.
+/// An artifact is accepted if it carries a valid signature matching any of the configured keys or
+/// identities.
+#[derive(Clone, Debug, Default)]
+pub struct Config {
+    /// PEM-encoded public keys to verify signatures with
+    pub public_keys: Vec<String>,
+    /// Identities of keyless signatures, verified against the Sigstore public-good instance
+    pub identities: Vec<Identity>,
+    /// How artifacts without a valid signature are handled
+    pub policy: Policy,
+}
+
+/// How artifacts without a valid signature are handled
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Policy {
+    /// Artifacts without a valid signature are rejected
+    #[default]
+    Require,
+    /// Artifacts without a valid signature are accepted, and a warning is logged
+    Warn,
+    /// Signatures are not verified
+    Ignore,
+}
+
+impl FromStr for Policy {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "require" => Ok(Self::Require),
+            "warn" => Ok(Self::Warn),
+            "ignore" => Ok(Self::Ignore),
+            _ => bail!("unknown signature policy `{s}`, expected `require`, `warn` or `ignore`"),
+        }
+    }
+}
+
+impl Policy {
+    /// Decide whether the artifact at `reference`, the signature verification of which resulted
+    /// in `verification`, is accepted, returning the verification to record if it is
+    ///
+    /// # Errors
+    ///
+    /// Returns the verification error if the artifact is rejected
+    pub fn decide(
+        self,
+        reference: &str,
+        verification: anyhow::Result<Verification>,
+    ) -> anyhow::Result<Option<Verification>> {
+        match (self, verification) {
+            (Self::Ignore, _) => Ok(None),
+            (_, Ok(verification)) => Ok(Some(verification)),
+            (Self::Require, Err(e)) => Err(e),
+            (Self::Warn, Err(e)) => {
+                warn!(reference, error = ?e, "accepting artifact without a valid signature");
+                Ok(None)
+            }
+        }
+    }
+}
+
+/// Identity of a keyless signature, as recorded in the Fulcio signing certificate
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Identity {
+    /// Email address or URI of the signer, e.g. a GitHub Actions workflow
+    pub subject: String,
+    /// OIDC issuer that authenticated the signer, e.g. `https://token.actions.githubusercontent.com`
+    pub issuer: String,
+}
+
+/// Result of a successful signature verification
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Verification {
+    /// Digest of the verified artifact manifest
+    pub digest: String,
+    /// The key or identity the artifact was signed by
+    pub signer: String,
+}
+
+/// Verifies cosign signatures of OCI artifacts
+pub struct Verifier {
+    policy: Policy,
+    #[cfg(feature = "cosign")]
+    constraints: Vec<(String, Box<dyn VerificationConstraint>)>,
+    #[cfg(feature = "cosign")]
+    trust_root: Option<SigstoreRepository>,
+}
+
+impl std::fmt::Debug for Verifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut f = f.debug_struct("Verifier");
+        f.field("policy", &self.policy);
+        #[cfg(feature = "cosign")]
+        f.field(
+            "signers",
+            &self
+                .constraints
+                .iter()
+                .map(|(signer, _)| signer)
+                .collect::<Vec<_>>(),
+        );
+        f.finish_non_exhaustive()
+    }
+}
+
+impl Verifier {
+    /// Construct a [`Verifier`] from `config`. If any keyless identities are configured, the
+    /// Sigstore trust root is fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no keys or identities are configured, a public key is invalid or the
+    /// trust root cannot be fetched
+    #[cfg(feature = "cosign")]
+    pub async fn new(config: Config) -> anyhow::Result<Self> {
+        let Config {
+            public_keys,
+            identities,
+            policy,
+        } = config;
+        if public_keys.is_empty() && identities.is_empty() {
+            bail!("at least one public key or identity is required to verify signatures");
+        }
+        let mut constraints: Vec<(String, Box<dyn VerificationConstraint>)> = Vec::new();
+        for (i, key) in public_keys.iter().enumerate() {
+            let verifier = PublicKeyVerifier::new(key.as_bytes(), &SigningScheme::default())
+                .with_context(|| format!("invalid signature verification public key #{i}"))?;
+            constraints.push((format!("public key #{i}"), Box::new(verifier)));
+        }
+        for Identity { subject, issuer } in identities {
+            let constraint: Box<dyn VerificationConstraint> = if subject.contains('@') {
+                Box::new(CertSubjectEmailVerifier {
+                    email: subject.clone(),
+                    issuer: Some(issuer.clone()),
+                })
+            } else {
+                Box::new(CertSubjectUrlVerifier {
+                    url: subject.clone(),
+                    issuer: issuer.clone(),
+                })
+            };
+            constraints.push((format!("{subject} ({issuer})"), constraint));
+        }
+        let trust_root = if constraints.len() > public_keys.len() {
+            let repo = tokio::task::spawn_blocking(|| SigstoreRepository::fetch(None))
+                .await
+                .context("failed to join trust root fetch task")?
+                .context("failed to fetch Sigstore trust root")?;
+            Some(repo)
+        } else {
+            None
+        };
+        Ok(Self {
+            policy,
+            constraints,
+            trust_root,
+        })
+    }
+
+    /// Construct a [`Verifier`] from `config`
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error, since signatures can only be verified with the `cosign` feature
+    #[cfg(not(feature = "cosign"))]
+    #[allow(clippy::unused_async)]
+    pub async fn new(config: Config) -> anyhow::Result<Self> {
+        let _ = config;
+        bail!("signature verification requires the host to be built with the `cosign` feature")
+    }
+
+    /// Returns how artifacts without a valid signature are handled
+    #[must_use]
+    pub fn policy(&self) -> Policy {
+        self.policy
+    }
+
+    /// Verify the signatures of the artifact at `reference`, which should be pinned to the
+    /// digest of the fetched manifest, returning the first key or identity, which signed it
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the artifact is unsigned or no signature matches a configured key or
+    /// identity
+    #[cfg(feature = "cosign")]
+    #[instrument(level = "debug", skip(self, auth))]
+    pub async fn verify(
+        &self,
+        reference: &str,
+        auth: &RegistryAuth,
+        insecure_registries: Vec<String>,
+    ) -> anyhow::Result<Verification> {
+        let auth = &match auth {
+            RegistryAuth::Basic(username, password) => {
+                Auth::Basic(username.clone(), password.clone())
+            }
+            RegistryAuth::Anonymous => Auth::Anonymous,
+        };
+        let protocol = if insecure_registries.is_empty() {
+            ClientProtocol::Https
+        } else {
+            ClientProtocol::HttpsExcept(insecure_registries)
+        };
+        let mut client = ClientBuilder::default().with_oci_client_config(ClientConfig {
+            protocol,
+            ..Default::default()
+        });
+        if let Some(repo) = &self.trust_root {
+            client = client
+                .with_rekor_pub_key(repo.rekor_pub_key())
+                .with_fulcio_certs(repo.fulcio_certs());
+        }
+        let mut client = client
+            .build()
+            .context("failed to build signature verification client")?;
+        let (signature_image, digest) = client
+            .triangulate(reference, auth)
+            .await
+            .context("failed to locate artifact signatures")?;
+        let layers = client
+            .trusted_signature_layers(auth, &digest, &signature_image)
+            .await
+            .context("artifact is not signed")?;
+        for (signer, constraint) in &self.constraints {
+            if verify_constraints(&layers, [constraint].into_iter()).is_ok() {
+                debug!(signer, digest, "verified artifact signature");
+                return Ok(Verification {
+                    digest,
+                    signer: signer.clone(),
+                });
+            }
+        }
+        bail!("no signature of artifact `{digest}` matches a trusted key or identity")
+    }
+
+    /// Verify the signatures of the artifact at `reference`
+    ///
+    /// # Errors
+    ///
+    /// Always returns an error, since signatures can only be verified with the `cosign` feature
+    #[cfg(not(feature = "cosign"))]
+    #[allow(clippy::unused_async)]
+    #[instrument(level = "debug", skip(self, _auth))]
+    pub async fn verify(
+        &self,
+        reference: &str,
+        _auth: &RegistryAuth,
+        _insecure_registries: Vec<String>,
+    ) -> anyhow::Result<Verification> {
+        bail!("signature verification requires the host to be built with the `cosign` feature")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use anyhow::anyhow;
+
+    fn verification() -> Verification {
+        Verification {
+            digest: "sha256:abc".to_string(),
+            signer: "public key #0".to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_policy() {
+        assert_eq!("require".parse::<Policy>().ok(), Some(Policy::Require));
+        assert_eq!("warn".parse::<Policy>().ok(), Some(Policy::Warn));
+        assert_eq!("ignore".parse::<Policy>().ok(), Some(Policy::Ignore));
+        assert!("enforce".parse::<Policy>().is_err());
+        assert_eq!(Policy::default(), Policy::Require);
+    }
+
+    #[test]
+    fn require_policy() {
+        let policy = Policy::Require;
+        assert_eq!(
+            policy.decide("ref", Ok(verification())).ok(),
+            Some(Some(verification()))
+        );
+        assert!(policy
+            .decide("ref", Err(anyhow!("artifact is not signed")))
+            .is_err());
+    }
+
+    #[test]
+    fn warn_policy() {
+        let policy = Policy::Warn;
+        assert_eq!(
+            policy.decide("ref", Ok(verification())).ok(),
+            Some(Some(verification()))
+        );
+        assert_eq!(
+            policy
+                .decide("ref", Err(anyhow!("artifact is not signed")))
+                .ok(),
+            Some(None)
+        );
+    }
+
+    #[test]
+    fn ignore_policy() {
+        let policy = Policy::Ignore;
+        assert_eq!(policy.decide("ref", Ok(verification())).ok(), Some(None));
+        assert_eq!(
+            policy
+                .decide("ref", Err(anyhow!("artifact is not signed")))
+                .ok(),
+            Some(None)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use core::time::Duration;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{ensure, Context as _};
+use tokio::io::{AsyncWriteExt, Stdout};
+use tokio::sync::Mutex;
+use tracing::{instrument, warn};
+use url::Url;
+
+use crate::audit::RotatingFile;
+
+/// Default size, in bytes, after which an event file is rotated
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Default number of rotated event files to keep
+pub const DEFAULT_MAX_FILES: usize = 5;
+
+/// Default timeout of webhook requests
+pub const DEFAULT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Destination of lattice events, in addition to the control interface NATS connection
+#[derive(Clone, Debug)]
+pub enum Sink {
+    /// `POST` each event as a structured JSON `CloudEvent` to `url`
+    Webhook {
+        /// URL to send events to
+        url: Url,
+        /// Timeout of a single request
+        timeout: Duration,
+    },
+    /// Append events as JSON lines to a file at `path`, rotated like the audit log, see
+    /// [`crate::AuditSink::File`]
+    File {
+        /// Path of the active event file
+        path: PathBuf,
+        /// Size, in bytes, after which the file is rotated
+        max_bytes: u64,
+        /// Number of rotated files to keep
+        max_files: usize,
+    },
+    /// Write events as JSON lines to stdout
+    Stdout,
+}
+
+impl Sink {
+    /// Construct a webhook sink sending events to `url` with the [default timeout](DEFAULT_WEBHOOK_TIMEOUT)
+    #[must_use]
+    pub fn webhook(url: Url) -> Self {
+        Self::Webhook {
+            url,
+            timeout: DEFAULT_WEBHOOK_TIMEOUT,
+        }
+    }
+
+    /// Construct a file sink writing events to `path` with the default rotation limits
+    #[must_use]
+    pub fn file(path: impl Into<PathBuf>) -> Self {
+        Self::File {
+            path: path.into(),
+            max_bytes: DEFAULT_MAX_FILE_BYTES,
+            max_files: DEFAULT_MAX_FILES,
+        }
+    }
+}
+
+/// Event sink configuration
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Where to send events
+    pub sink: Sink,
+    /// Names of events to send, e.g. `actor_started`. All events are sent if empty
+    pub event_types: Vec<String>,
+}
+
+impl Config {
+    /// Construct a configuration sending all events to `sink`
+    #[must_use]
+    pub fn new(sink: Sink) -> Self {
+        Self {
+            sink,
+            event_types: Vec::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum Writer {
+    Webhook { http: reqwest::Client, url: Url },
+    File(Mutex<RotatingFile>),
+    Stdout(Mutex<Stdout>),
+}
+
+impl Writer {
+    async fn write(&self, event: &[u8]) -> anyhow::Result<()> {
+        match self {
+            Self::Webhook { http, url } => {
+                let res = http
+                    .post(url.clone())
+                    .header(
+                        reqwest::header::CONTENT_TYPE,
+                        "application/cloudevents+json",
+                    )
+                    .body(event.to_vec())
+                    .send()
+                    .await
+                    .with_context(|| format!("failed to send event to `{url}`"))?;
+                let status = res.status();
+                ensure!(
+                    status.is_success(),
+                    "webhook `{url}` responded with status `{status}`"
+                );
+                Ok(())
+            }
+            Self::File(file) => {
+                let mut buf = event.to_vec();
+                buf.push(b'\n');
+                file.lock().await.write(&buf).await
+            }
+            Self::Stdout(stdout) => {
+                let mut stdout = stdout.lock().await;
+                stdout
+                    .write_all(event)
+                    .await
+                    .context("failed to write event to stdout")?;
+                stdout
+                    .write_all(b"\n")
+                    .await
+                    .context("failed to write event to stdout")?;
+                stdout.flush().await.context("failed to flush stdout")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct EventSink {
+    event_types: Vec<String>,
+    writer: Writer,
+}
+
+/// Set of configured event sinks, cheap to clone
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Sinks(Arc<[EventSink]>);
+
+impl Sinks {
+    /// Open all sinks described by `configs`
+    pub(crate) async fn new(configs: Vec<Config>) -> anyhow::Result<Self> {
+        let mut sinks = Vec::with_capacity(configs.len());
+        for Config { sink, event_types } in configs {
+            let writer = match sink {
+                Sink::Webhook { url, timeout } => Writer::Webhook {
+                    http: reqwest::Client::builder()
+                        .timeout(timeout)
+                        .build()
+                        .context("failed to build webhook client")?,
+                    url,
+                },
+                Sink::File {
+                    path,
+                    max_bytes,
+                    max_files,
+                } => Writer::File(Mutex::new(
+                    RotatingFile::open(path, max_bytes, max_files)
+                        .await
+                        .context("failed to open event file")?,
+                )),
+                Sink::Stdout => Writer::Stdout(Mutex::new(tokio::io::stdout())),
+            };
+            sinks.push(EventSink {
+                event_types,
+                writer,
+            });
+        }
+        Ok(Self(sinks.into()))
+    }
+
+    /// Send the encoded event named `name` to all sinks, which accept it. Failures are logged,
+    /// but never fail publishing the event.
+    #[instrument(level = "trace", skip(self, event))]
+    pub(crate) async fn send(&self, name: &str, event: &[u8]) {
+        for EventSink {
+            event_types,
+            writer,
+        } in self.0.iter()
+        {
+            if !event_types.is_empty() && !event_types.iter().any(|ty| ty == name) {
+                continue;
+            }
+            if let Err(e) = writer.write(event).await {
+                warn!(?e, name, "failed to send event to sink");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+
+    /// Serve HTTP requests on a local port, responding with `status` and forwarding the headers
+    /// and body of each request on the returned channel
+    async fn webhook(status: u16) -> anyhow::Result<(Url, mpsc::Receiver<(String, Vec<u8>)>)> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}/events", listener.local_addr()?).parse()?;
+        let (tx, rx) = mpsc::channel(16);
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = Vec::new();
+                let mut chunk = [0; 1024];
+                let (head, body_start) = loop {
+                    let Ok(n @ 1..) = stream.read(&mut chunk).await else {
+                        return;
+                    };
+                    buf.extend_from_slice(&chunk[..n]);
+                    if let Some(i) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break (String::from_utf8_lossy(&buf[..i]).to_lowercase(), i + 4);
+                    }
+                };
+                let len = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .and_then(|len| len.trim().parse().ok())
+                    .unwrap_or(0);
+                let mut body = buf.split_off(body_start);
+                while body.len() < len {
+                    let Ok(n @ 1..) = stream.read(&mut chunk).await else {
+                        return;
+                    };
+                    body.extend_from_slice(&chunk[..n]);
+                }
+                let res = format!("HTTP/1.1 {status} Status\r\ncontent-length: 0\r\n\r\n");
+                if stream.write_all(res.as_bytes()).await.is_err()
+                    || tx.send((head, body)).await.is_err()
+                {
+                    return;
+                }
+            }
+        });
+        Ok((url, rx))
+    }
+
+    #[tokio::test]
+    async fn file_sinks_filter_events() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let all = dir.path().join("all.jsonl");
+        let started = dir.path().join("started.jsonl");
+        let sinks = Sinks::new(vec![
+            Config::new(Sink::file(&all)),
+            Config {
+                event_types: vec!["actor_started".to_string()],
+                ..Config::new(Sink::file(&started))
+            },
+        ])
+        .await?;
+
+        sinks.send("actor_started", br#"{"n":1}"#).await;
+        sinks.send("actor_stopped", br#"{"n":2}"#).await;
+        sinks.send("actor_started", br#"{"n":3}"#).await;
+
+        assert_eq!(
+            tokio::fs::read_to_string(&all).await?,
+            "{\"n\":1}\n{\"n\":2}\n{\"n\":3}\n"
+        );
+        assert_eq!(
+            tokio::fs::read_to_string(&started).await?,
+            "{\"n\":1}\n{\"n\":3}\n"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn webhook_sink() -> anyhow::Result<()> {
+        let (url, mut requests) = webhook(200).await?;
+        let sinks = Sinks::new(vec![Config::new(Sink::webhook(url))]).await?;
+
+        sinks.send("actor_started", br#"{"n":1}"#).await;
+        let (head, body) = requests.recv().await.context("webhook was not called")?;
+        assert!(head.starts_with("post /events "), "{head}");
+        assert!(head.contains("content-type: application/cloudevents+json"));
+        assert_eq!(body, br#"{"n":1}"#);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sink_failures_are_not_fatal() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("events.jsonl");
+        let (url, mut requests) = webhook(500).await?;
+        let sinks = Sinks::new(vec![
+            Config::new(Sink::webhook(url)),
+            Config::new(Sink::file(&path)),
+        ])
+        .await?;
+
+        // Sinks after a failed one still receive the event
+        sinks.send("actor_started", br#"{"n":1}"#).await;
+        requests.recv().await.context("webhook was not called")?;
+        assert_eq!(tokio::fs::read_to_string(&path).await?, "{\"n\":1}\n");
+
+        let err = sinks.0[0]
+            .writer
+            .write(b"{}")
+            .await
+            .expect_err("unsuccessful status should fail the write");
+        assert!(err.to_string().contains("500"), "{err}");
+        Ok(())
+    }
+}
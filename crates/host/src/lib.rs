@@ -7,31 +7,54 @@
 /// wasmbus host
 pub mod wasmbus;
 
+/// Invocation audit log
+pub mod audit;
+
+/// OCI artifact signature verification
+pub mod cosign;
+
+/// Lattice event sinks
+pub mod event_sink;
+
+/// Invocation metrics
+pub mod metrics;
+
 /// OCI artifact fetching
 pub mod oci;
 
 /// wasmCloud policy service
 pub mod policy;
 
+/// On-demand CPU profiling
+pub mod profiling;
+
 /// Common registry types
 pub mod registry;
 
+/// Reference secrets backend
+pub mod secrets;
+
 /// Provider archive functionality
 mod par;
 
+pub use audit::{Config as AuditConfig, Sink as AuditSink};
+pub use event_sink::{Config as EventSinkConfig, Sink as EventSink};
 pub use oci::{Config as OciConfig, Fetcher as OciFetcher};
 pub use policy::{
     Action as PolicyAction, HostInfo as PolicyHostInfo, Manager as PolicyManager,
     RequestSource as PolicyRequestSource, RequestTarget as PolicyRequestTarget,
     Response as PolicyResponse,
 };
+pub use profiling::Config as ProfilingConfig;
 pub use registry::{Auth as RegistryAuth, Config as RegistryConfig, Type as RegistryType};
+pub use secrets::Config as SecretsConfig;
 pub use wasmbus::{Host as WasmbusHost, HostConfig as WasmbusHostConfig};
 
 pub use url;
 
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::{anyhow, bail, ensure, Context as _};
 use tokio::fs;
@@ -110,55 +133,67 @@ impl ResourceRef<'_> {
     }
 }
 
-/// Fetch an actor from a reference.
-#[instrument(level = "debug", skip(allow_file_load, registry_config))]
+/// Fetch an actor from a reference. If `verifier` is set, actors fetched from OCI must carry a
+/// valid signature, the verification of which is returned.
+#[instrument(level = "debug", skip(allow_file_load, registry_config, verifier))]
 pub async fn fetch_actor(
     actor_ref: &str,
     allow_file_load: bool,
     registry_config: &HashMap<String, RegistryConfig>,
-) -> anyhow::Result<Vec<u8>> {
+    verifier: Option<Arc<cosign::Verifier>>,
+) -> anyhow::Result<(Vec<u8>, Option<cosign::Verification>)> {
     match ResourceRef::try_from(actor_ref)? {
         ResourceRef::File(actor_ref) => {
             ensure!(
                 allow_file_load,
                 "unable to start actor from file, file loading is disabled"
             );
-            fs::read(actor_ref).await.context("failed to read actor")
+            let actor = fs::read(actor_ref).await.context("failed to read actor")?;
+            Ok((actor, None))
         }
         ref oci_ref @ ResourceRef::Oci(actor_ref) => oci_ref
             .authority()
             .and_then(|authority| registry_config.get(authority))
             .map(oci::Fetcher::from)
             .unwrap_or_default()
+            .with_signature_verifier(verifier)
             .fetch_actor(actor_ref)
             .await
             .with_context(|| format!("failed to fetch actor under OCI reference `{actor_ref}`")),
     }
 }
 
-/// Fetch a provider from a reference.
-#[instrument(skip(provider_ref, link_name))]
+/// Fetch a provider from a reference. If `verifier` is set, providers fetched from OCI must carry
+/// a valid signature, the verification of which is returned.
+#[instrument(skip(provider_ref, link_name, verifier))]
 pub async fn fetch_provider(
     provider_ref: impl AsRef<str>,
     link_name: impl AsRef<str>,
     allow_file_load: bool,
     registry_config: &HashMap<String, RegistryConfig>,
-) -> anyhow::Result<(PathBuf, jwt::Claims<jwt::CapabilityProvider>)> {
+    verifier: Option<Arc<cosign::Verifier>>,
+) -> anyhow::Result<(
+    PathBuf,
+    jwt::Claims<jwt::CapabilityProvider>,
+    Option<cosign::Verification>,
+)> {
     match ResourceRef::try_from(provider_ref.as_ref())? {
         ResourceRef::File(provider_ref) => {
             ensure!(
                 allow_file_load,
                 "unable to start provider from file, file loading is disabled"
             );
-            par::read(provider_ref, link_name)
+            let (path, claims) = par::read(provider_ref, link_name)
                 .await
-                .context("failed to read provider")
+                .context("failed to read provider")?;
+            Ok((path, claims, None))
         }
         ref oci_ref @ ResourceRef::Oci(provider_ref) => oci_ref
             .authority()
             .and_then(|authority| registry_config.get(authority))
             .map(oci::Fetcher::from)
             .unwrap_or_default()
+            .with_signature_verifier(verifier)
             .fetch_provider(&provider_ref, link_name)
             .await
             .with_context(|| {
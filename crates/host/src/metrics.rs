@@ -0,0 +1,191 @@
+use core::time::Duration;
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use tracing::warn;
+use wasmcloud_control_interface::{LatencyBucket, LinkMetrics};
+use wasmcloud_core::WasmCloudEntity;
+
+/// Inclusive upper bounds, in milliseconds, of the latency histogram buckets
+pub const LATENCY_BUCKETS_MS: [u64; 14] = [
+    1, 2, 5, 10, 25, 50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 30_000,
+];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+struct LinkKey {
+    actor_id: String,
+    provider_id: String,
+    contract_id: String,
+    link_name: String,
+    operation: String,
+}
+
+#[derive(Clone, Debug, Default)]
+struct LinkStats {
+    count: u64,
+    errors: u64,
+    sum_us: u64,
+    /// Non-cumulative counts per bucket, the last element counts invocations exceeding all bounds
+    buckets: [u64; LATENCY_BUCKETS_MS.len() + 1],
+}
+
+/// Invocation metrics of actors on the host, keyed by link
+#[derive(Debug, Default)]
+pub(crate) struct Metrics {
+    links: Mutex<HashMap<LinkKey, LinkStats>>,
+}
+
+impl Metrics {
+    /// Record an invocation of `operation` by `actor_id` on `target`. Invocations of actors are
+    /// ignored, since they are not made over a link.
+    pub(crate) fn record_link_invocation(
+        &self,
+        actor_id: &str,
+        target: &WasmCloudEntity,
+        operation: &str,
+        latency: Duration,
+        success: bool,
+    ) {
+        if target.contract_id.is_empty() {
+            return;
+        }
+        let key = LinkKey {
+            actor_id: actor_id.to_string(),
+            provider_id: target.public_key.clone(),
+            contract_id: target.contract_id.clone(),
+            link_name: target.link_name.clone(),
+            operation: operation.to_string(),
+        };
+        let latency_us = latency.as_micros().try_into().unwrap_or(u64::MAX);
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .position(|le_ms| latency_us <= le_ms.saturating_mul(1000))
+            .unwrap_or(LATENCY_BUCKETS_MS.len());
+        let Ok(mut links) = self.links.lock() else {
+            warn!("link metrics lock poisoned, dropping measurement");
+            return;
+        };
+        let stats = links.entry(key).or_default();
+        stats.count = stats.count.saturating_add(1);
+        if !success {
+            stats.errors = stats.errors.saturating_add(1);
+        }
+        stats.sum_us = stats.sum_us.saturating_add(latency_us);
+        stats.buckets[bucket] = stats.buckets[bucket].saturating_add(1);
+    }
+
+    /// Returns a snapshot of all link metrics with cumulative histograms
+    pub(crate) fn links(&self) -> Vec<LinkMetrics> {
+        let Ok(links) = self.links.lock() else {
+            warn!("link metrics lock poisoned");
+            return Vec::default();
+        };
+        links
+            .iter()
+            .map(|(key, stats)| {
+                let mut cumulative = 0;
+                let buckets = stats
+                    .buckets
+                    .iter()
+                    .enumerate()
+                    .map(|(i, count)| {
+                        cumulative += count;
+                        LatencyBucket {
+                            le_ms: LATENCY_BUCKETS_MS.get(i).copied(),
+                            count: cumulative,
+                        }
+                    })
+                    .collect();
+                LinkMetrics {
+                    actor_id: key.actor_id.clone(),
+                    contract_id: key.contract_id.clone(),
+                    link_name: key.link_name.clone(),
+                    provider_id: key.provider_id.clone(),
+                    operation: key.operation.clone(),
+                    count: stats.count,
+                    errors: stats.errors,
+                    sum_us: stats.sum_us,
+                    buckets,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target(contract_id: &str) -> WasmCloudEntity {
+        WasmCloudEntity {
+            public_key: "VPROVIDER".to_string(),
+            link_name: "default".to_string(),
+            contract_id: contract_id.to_string(),
+        }
+    }
+
+    fn buckets(metrics: &LinkMetrics) -> Vec<(Option<u64>, u64)> {
+        metrics
+            .buckets
+            .iter()
+            .map(|LatencyBucket { le_ms, count }| (*le_ms, *count))
+            .collect()
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let metrics = Metrics::default();
+        let provider = target("wasmcloud:keyvalue");
+        for (latency, success) in [
+            // Bucket bounds are inclusive
+            (Duration::from_millis(1), true),
+            (Duration::from_micros(1_001), true),
+            (Duration::from_millis(2), false),
+            (Duration::from_millis(30_000), true),
+            // Latencies exceeding all bounds are only counted in the last bucket
+            (Duration::from_secs(60), false),
+        ] {
+            metrics.record_link_invocation("MACTOR", &provider, "Get", latency, success);
+        }
+
+        let links = metrics.links();
+        assert_eq!(links.len(), 1);
+        let link = &links[0];
+        assert_eq!(link.count, 5);
+        assert_eq!(link.errors, 2);
+        assert_eq!(link.sum_us, 1_000 + 1_001 + 2_000 + 30_000_000 + 60_000_000);
+        // Buckets are cumulative, with one bucket per bound and one without a bound
+        assert_eq!(link.buckets.len(), LATENCY_BUCKETS_MS.len() + 1);
+        let buckets = buckets(link);
+        assert_eq!(buckets[0], (Some(1), 1));
+        assert_eq!(buckets[1], (Some(2), 3));
+        assert_eq!(buckets[2], (Some(5), 3));
+        assert_eq!(buckets[12], (Some(10_000), 3));
+        assert_eq!(buckets[13], (Some(30_000), 4));
+        assert_eq!(buckets[14], (None, 5));
+    }
+
+    #[test]
+    fn links_keyed_by_operation() {
+        let metrics = Metrics::default();
+        let provider = target("wasmcloud:keyvalue");
+        metrics.record_link_invocation("MACTOR", &provider, "Get", Duration::ZERO, true);
+        metrics.record_link_invocation("MACTOR", &provider, "Set", Duration::ZERO, true);
+        metrics.record_link_invocation("MACTOR", &provider, "Get", Duration::ZERO, true);
+        // Invocations of actors are not made over a link
+        metrics.record_link_invocation("MACTOR", &target(""), "Get", Duration::ZERO, true);
+
+        let mut links = metrics.links();
+        links.sort_by(|a, b| a.operation.cmp(&b.operation));
+        assert_eq!(
+            links
+                .iter()
+                .map(|link| (link.operation.as_str(), link.count))
+                .collect::<Vec<_>>(),
+            [("Get", 2), ("Set", 1)]
+        );
+        // Zero latencies fall into the first bucket
+        assert_eq!(buckets(&links[0])[0], (Some(1), 2));
+    }
+}
@@ -1,12 +1,13 @@
 // Adapted from
 // https://github.com/wasmCloud/wasmcloud-otp/blob/5f13500646d9e077afa1fca67a3fe9c8df5f3381/host_core/native/hostcore_wasmcloud_native/src/oci.rs
 
-use crate::{par, RegistryConfig};
+use crate::{cosign, par, RegistryConfig};
 
 use core::str::FromStr;
 
 use std::env::temp_dir;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 use anyhow::{bail, Context as _};
 use oci_distribution::client::{ClientConfig, ClientProtocol, ImageData};
@@ -108,6 +109,7 @@ pub struct Fetcher {
     allow_latest: bool,
     allow_insecure: bool,
     auth: RegistryAuth,
+    verifier: Option<Arc<cosign::Verifier>>,
 }
 
 impl Default for Fetcher {
@@ -116,6 +118,7 @@ impl Default for Fetcher {
             allow_latest: false,
             allow_insecure: false,
             auth: RegistryAuth::Anonymous,
+            verifier: None,
         }
     }
 }
@@ -133,6 +136,7 @@ impl From<&RegistryConfig> for Fetcher {
             auth: auth.into(),
             allow_latest: *allow_latest,
             allow_insecure: *allow_insecure,
+            verifier: None,
         }
     }
 }
@@ -150,17 +154,67 @@ impl From<RegistryConfig> for Fetcher {
             auth: auth.into(),
             allow_latest,
             allow_insecure,
+            verifier: None,
         }
     }
 }
 
 impl Fetcher {
+    /// Require fetched artifacts to carry a signature accepted by `verifier`
+    #[must_use]
+    pub fn with_signature_verifier(self, verifier: Option<Arc<cosign::Verifier>>) -> Self {
+        Self { verifier, ..self }
+    }
+
+    /// Verify the signature of the artifact `img` at `digest`, if a verifier is configured. Whether
+    /// an artifact without a valid signature is accepted is decided by the verifier policy
+    async fn verify(
+        &self,
+        img: &Reference,
+        digest: Option<&str>,
+    ) -> anyhow::Result<Option<cosign::Verification>> {
+        let Some(verifier) = &self.verifier else {
+            return Ok(None);
+        };
+        let policy = verifier.policy();
+        if policy == cosign::Policy::Ignore {
+            return Ok(None);
+        }
+        let verification = self.verify_signature(verifier, img, digest).await;
+        policy.decide(&img.whole(), verification)
+    }
+
+    async fn verify_signature(
+        &self,
+        verifier: &cosign::Verifier,
+        img: &Reference,
+        digest: Option<&str>,
+    ) -> anyhow::Result<cosign::Verification> {
+        let Some(digest) = digest.filter(|digest| !digest.is_empty()) else {
+            bail!("cannot verify signature of `{img}`, registry did not report a manifest digest")
+        };
+        let pinned = Reference::with_digest(
+            img.registry().to_string(),
+            img.repository().to_string(),
+            digest.to_string(),
+        );
+        let insecure = if self.allow_insecure {
+            vec![img.registry().to_string()]
+        } else {
+            Vec::default()
+        };
+        verifier
+            .verify(&pinned.whole(), &self.auth, insecure)
+            .await
+            .with_context(|| format!("failed to verify signature of `{img}`"))
+    }
+
     /// Fetch an OCI path
     async fn fetch_path(
         &self,
         img: impl AsRef<str>,
         accepted_media_types: Vec<&str>,
-    ) -> anyhow::Result<PathBuf> {
+    ) -> anyhow::Result<(PathBuf, Option<cosign::Verification>)> {
         let img = img.as_ref();
 
         let img = &img.to_lowercase(); // the OCI spec does not allow for capital letters in references
@@ -192,7 +246,8 @@ impl Fetcher {
             // If the digest file doesn't exist that is ok, we just unwrap to an empty string
             let file_digest = fs::read_to_string(&digest_file).await.unwrap_or_default();
             if !oci_digest.is_empty() && !file_digest.is_empty() && file_digest == oci_digest {
-                return Ok(cache_file);
+                let verification = self.verify(&img, Some(&oci_digest)).await?;
+                return Ok((cache_file, verification));
             }
         }
 
@@ -200,43 +255,58 @@ impl Fetcher {
             .pull(&img, &self.auth, accepted_media_types)
             .await
             .context("failed to fetch OCI bytes")?;
+        // Verify before caching, so that an unverified artifact never ends up in the cache
+        let verification = self.verify(&img, imgdata.digest.as_deref()).await?;
         cache_oci_image(imgdata, &cache_file, digest_file)
             .await
             .context("failed to cache OCI bytes")?;
-        Ok(cache_file)
+        Ok((cache_file, verification))
     }
 
-    /// Fetch actor from OCI
+    /// Fetch actor from OCI, returning the signature verification result if a verifier is
+    /// configured
     ///
     /// # Errors
     ///
-    /// Returns an error if either fetching fails or reading the fetched OCI path fails
-    pub async fn fetch_actor(&self, oci_ref: impl AsRef<str>) -> anyhow::Result<Vec<u8>> {
-        let path = self
+    /// Returns an error if either fetching fails, signature verification fails or reading the
+    /// fetched OCI path fails
+    pub async fn fetch_actor(
+        &self,
+        oci_ref: impl AsRef<str>,
+    ) -> anyhow::Result<(Vec<u8>, Option<cosign::Verification>)> {
+        let (path, verification) = self
             .fetch_path(oci_ref, vec![WASM_MEDIA_TYPE, OCI_MEDIA_TYPE])
             .await
             .context("failed to fetch OCI path")?;
-        fs::read(&path)
+        let actor = fs::read(&path)
             .await
-            .with_context(|| format!("failed to read `{}`", path.display()))
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        Ok((actor, verification))
     }
 
-    /// Fetch provider from OCI
+    /// Fetch provider from OCI, returning the signature verification result if a verifier is
+    /// configured
     ///
     /// # Errors
     ///
-    /// Returns an error if either fetching fails or reading the fetched OCI path fails
+    /// Returns an error if either fetching fails, signature verification fails or reading the
+    /// fetched OCI path fails
     pub async fn fetch_provider(
         &self,
         oci_ref: impl AsRef<str>,
         link_name: impl AsRef<str>,
-    ) -> anyhow::Result<(PathBuf, jwt::Claims<jwt::CapabilityProvider>)> {
-        let path = self
+    ) -> anyhow::Result<(
+        PathBuf,
+        jwt::Claims<jwt::CapabilityProvider>,
+        Option<cosign::Verification>,
+    )> {
+        let (path, verification) = self
             .fetch_path(oci_ref, vec![PROVIDER_ARCHIVE_MEDIA_TYPE, OCI_MEDIA_TYPE])
             .await
             .context("failed to fetch OCI path")?;
-        par::read(&path, link_name)
+        let (path, claims) = par::read(&path, link_name)
             .await
-            .with_context(|| format!("failed to read `{}`", path.display()))
+            .with_context(|| format!("failed to read `{}`", path.display()))?;
+        Ok((path, claims, verification))
     }
 }
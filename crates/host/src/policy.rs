@@ -15,6 +15,7 @@ use tokio::spawn;
 use tokio::sync::RwLock;
 use tracing::{debug, error, instrument, trace, warn};
 use ulid::Ulid;
+use url::Url;
 use uuid::Uuid;
 use wascap::jwt;
 
@@ -92,6 +93,13 @@ pub enum Action {
     /// The host is checking whether it may start the target provider
     #[serde(rename = "start_provider")]
     StartProvider,
+    /// The host is checking whether the source actor may be linked to the target provider
+    #[serde(rename = "put_link")]
+    PutLink,
+    /// The host is checking whether the link between the source actor and the target provider may
+    /// be deleted
+    #[serde(rename = "delete_link")]
+    DeleteLink,
 }
 
 /// A request for a policy decision
@@ -127,6 +135,51 @@ pub struct Response {
     pub message: Option<String>,
 }
 
+/// The body of a request to the OPA data API
+#[derive(Serialize)]
+struct OpaRequest<'a> {
+    input: &'a Request,
+}
+
+/// A decision returned by a Rego rule, which may either be a plain boolean or an object mirroring
+/// [`Response`]
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OpaDecision {
+    Permitted(bool),
+    Decision {
+        permitted: bool,
+        #[serde(default)]
+        message: Option<String>,
+    },
+}
+
+/// A response from the OPA data API. `result` is missing if the rule is undefined for the input
+#[derive(Debug, Deserialize)]
+struct OpaResponse {
+    result: Option<OpaDecision>,
+}
+
+impl OpaResponse {
+    /// Converts the OPA decision into a [`Response`] to the request with ID `request_id`, denying
+    /// the request if the decision is undefined
+    fn into_response(self, request_id: String) -> Response {
+        let (permitted, message) = match self.result {
+            Some(OpaDecision::Permitted(permitted)) => (permitted, None),
+            Some(OpaDecision::Decision { permitted, message }) => (permitted, message),
+            None => (
+                false,
+                Some("policy does not define a decision for this request".to_string()),
+            ),
+        };
+        Response {
+            request_id,
+            permitted,
+            message,
+        }
+    }
+}
+
 /// Policy services expect a source on all requests, even though no data is relevant for the start
 /// actions. When source is None, we still serialize an (empty) object
 fn serialize_source<S>(source: &Option<RequestSource>, serializer: S) -> Result<S::Ok, S::Error>
@@ -205,6 +258,8 @@ pub struct Manager {
     nats: async_nats::Client,
     host_info: HostInfo,
     policy_topic: Option<String>,
+    opa_url: Option<Url>,
+    http: reqwest::Client,
     policy_timeout: Duration,
     decision_cache: Arc<RwLock<HashMap<RequestKey, Response>>>,
     request_to_key: Arc<RwLock<HashMap<String, RequestKey>>>,
@@ -214,11 +269,15 @@ pub struct Manager {
 
 impl Manager {
     /// Construct a new policy manager. Can fail if policy_changes_topic is set but we fail to subscribe to it
+    ///
+    /// If `opa_url` is set, decisions are requested from the OPA data API at that URL instead of
+    /// `policy_topic`
     #[instrument(skip(nats))]
     pub async fn new(
         nats: async_nats::Client,
         host_info: HostInfo,
         policy_topic: Option<String>,
+        opa_url: Option<Url>,
         policy_timeout: Option<Duration>,
         policy_changes_topic: Option<String>,
     ) -> anyhow::Result<Arc<Self>> {
@@ -230,6 +289,8 @@ impl Manager {
             nats: nats.clone(),
             host_info,
             policy_topic,
+            opa_url,
+            http: reqwest::Client::new(),
             policy_timeout: policy_timeout.unwrap_or(DEFAULT_POLICY_TIMEOUT),
             decision_cache: Arc::default(),
             request_to_key: Arc::default(),
@@ -281,16 +342,20 @@ impl Manager {
             }
             hash_map::Entry::Vacant(entry) => {
                 let request_id = Uuid::from_u128(Ulid::new().into()).to_string();
-                let decision = if let Some(policy_topic) = self.policy_topic.clone() {
+                let request = Request {
+                    request_id: request_id.clone(),
+                    source,
+                    target,
+                    host: self.host_info.clone(),
+                    action,
+                };
+                let decision = if let Some(opa_url) = self.opa_url.clone() {
+                    trace!(?cache_key, "requesting OPA policy decision");
+                    self.request_opa_decision(opa_url, &request).await?
+                } else if let Some(policy_topic) = self.policy_topic.clone() {
                     trace!(?cache_key, "requesting policy decision");
-                    let payload = serde_json::to_vec(&Request {
-                        request_id: request_id.clone(),
-                        source,
-                        target,
-                        host: self.host_info.clone(),
-                        action,
-                    })
-                    .context("failed to serialize policy request")?;
+                    let payload = serde_json::to_vec(&request)
+                        .context("failed to serialize policy request")?;
                     let request = async_nats::Request::new()
                         .payload(payload.into())
                         .timeout(Some(self.policy_timeout));
@@ -321,6 +386,31 @@ impl Manager {
         }
     }
 
+    /// Evaluate `request` using the Rego policy served by OPA at `opa_url`, denying the request if
+    /// the policy does not define a decision for it
+    #[instrument(level = "trace", skip(self, request))]
+    async fn request_opa_decision(
+        &self,
+        opa_url: Url,
+        request: &Request,
+    ) -> anyhow::Result<Response> {
+        let res = self
+            .http
+            .post(opa_url)
+            .timeout(self.policy_timeout)
+            .json(&OpaRequest { input: request })
+            .send()
+            .await
+            .context("OPA policy request failed")?
+            .error_for_status()
+            .context("OPA policy request returned an error")?;
+        let res: OpaResponse = res
+            .json()
+            .await
+            .context("failed to deserialize OPA policy response")?;
+        Ok(res.into_response(request.request_id.clone()))
+    }
+
     #[instrument(skip(self))]
     async fn override_decision(&self, msg: async_nats::Message) -> anyhow::Result<()> {
         let Response {
@@ -354,3 +444,94 @@ impl Manager {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use serde_json::json;
+
+    fn request() -> Request {
+        Request {
+            request_id: "request".to_string(),
+            source: None,
+            target: RequestTarget {
+                public_key: Some("MACTOR".to_string()),
+                ..Default::default()
+            },
+            host: HostInfo {
+                public_key: "NHOST".to_string(),
+                lattice_id: "default".to_string(),
+                labels: HashMap::default(),
+                cluster_issuers: vec!["CISSUER".to_string()],
+            },
+            action: Action::StartActor,
+        }
+    }
+
+    fn decide(body: serde_json::Value) -> Response {
+        serde_json::from_value::<OpaResponse>(body)
+            .expect("failed to deserialize OPA response")
+            .into_response("request".to_string())
+    }
+
+    #[test]
+    fn opa_request_wraps_input() {
+        let request = request();
+        let body = serde_json::to_value(OpaRequest { input: &request })
+            .expect("failed to serialize OPA request");
+        assert_eq!(body["input"]["requestId"], "request");
+        assert_eq!(body["input"]["action"], "start_actor");
+        assert_eq!(body["input"]["target"]["publicKey"], "MACTOR");
+        assert_eq!(body["input"]["host"]["latticeId"], "default");
+        // a missing source is still serialized as an object
+        assert!(body["input"]["source"].is_object());
+    }
+
+    #[test]
+    fn opa_boolean_decision() {
+        let res = decide(json!({ "result": true }));
+        assert_eq!(res.request_id, "request");
+        assert!(res.permitted);
+        assert_eq!(res.message, None);
+
+        let res = decide(json!({ "result": false }));
+        assert!(!res.permitted);
+        assert_eq!(res.message, None);
+    }
+
+    #[test]
+    fn opa_object_decision() {
+        let res = decide(json!({
+            "result": { "permitted": false, "message": "actor is not signed by a trusted issuer" }
+        }));
+        assert!(!res.permitted);
+        assert_eq!(
+            res.message.as_deref(),
+            Some("actor is not signed by a trusted issuer")
+        );
+
+        let res = decide(json!({ "result": { "permitted": true } }));
+        assert!(res.permitted);
+        assert_eq!(res.message, None);
+    }
+
+    #[test]
+    fn opa_undefined_decision_denies() {
+        let res = decide(json!({}));
+        assert!(!res.permitted);
+        assert!(res.message.is_some());
+
+        let res = decide(json!({ "result": null }));
+        assert!(!res.permitted);
+    }
+
+    #[test]
+    fn opa_malformed_decision_fails() {
+        assert!(serde_json::from_value::<OpaResponse>(json!({ "result": "yes" })).is_err());
+        assert!(
+            serde_json::from_value::<OpaResponse>(json!({ "result": { "message": "no" } }))
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use core::str::FromStr;
+use core::time::Duration;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::{bail, Context as _};
+use bytes::Bytes;
+use http_body_util::Full;
+use hyper::body::Incoming;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use tokio::net::TcpListener;
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, instrument, warn};
+
+/// Default sampling frequency, in Hz. Not a round number to avoid sampling in lockstep with
+/// periodic activity
+pub const DEFAULT_FREQUENCY: i32 = 99;
+
+/// Default duration of a profile, if none is requested
+pub const DEFAULT_DURATION: Duration = Duration::from_secs(10);
+
+/// Default maximum duration of a profile
+pub const DEFAULT_MAX_DURATION: Duration = Duration::from_secs(300);
+
+/// CPU profiling configuration
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Address to serve the pprof-compatible `/debug/pprof/profile` and `/debug/pprof/flamegraph`
+    /// HTTP endpoints on. If `None`, profiles can only be requested via the control interface
+    pub listen_address: Option<SocketAddr>,
+    /// Sampling frequency, in Hz
+    pub frequency: i32,
+    /// Maximum duration of a single profile, longer requests are rejected
+    pub max_duration: Duration,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            listen_address: None,
+            frequency: DEFAULT_FREQUENCY,
+            max_duration: DEFAULT_MAX_DURATION,
+        }
+    }
+}
+
+/// Format of a captured profile
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Format {
+    /// pprof protobuf, as consumed by `go tool pprof`
+    #[default]
+    Pprof,
+    /// SVG flamegraph
+    Flamegraph,
+}
+
+impl Format {
+    /// Name of the format, as accepted by [`Format::from_str`]
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pprof => "pprof",
+            Self::Flamegraph => "flamegraph",
+        }
+    }
+
+    fn content_type(self) -> &'static str {
+        match self {
+            Self::Pprof => "application/octet-stream",
+            Self::Flamegraph => "image/svg+xml",
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pprof" => Ok(Self::Pprof),
+            "flamegraph" => Ok(Self::Flamegraph),
+            _ => bail!("unsupported profile format `{s}`, expected `pprof` or `flamegraph`"),
+        }
+    }
+}
+
+/// Captures CPU profiles of the host process, one at a time
+#[derive(Debug)]
+pub(crate) struct Profiler {
+    config: Config,
+    busy: Mutex<()>,
+}
+
+impl Profiler {
+    pub(crate) fn new(config: Config) -> Self {
+        Self {
+            config,
+            busy: Mutex::default(),
+        }
+    }
+
+    /// Capture a profile for `duration`, or [`DEFAULT_DURATION`] if `None`
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) async fn capture(
+        &self,
+        duration: Option<Duration>,
+        format: Format,
+    ) -> anyhow::Result<Vec<u8>> {
+        let duration = duration.unwrap_or(DEFAULT_DURATION);
+        if duration.is_zero() || duration > self.config.max_duration {
+            bail!(
+                "profile duration must be between 0 and {}ms",
+                self.config.max_duration.as_millis()
+            );
+        }
+        let Ok(_busy) = self.busy.try_lock() else {
+            bail!("a profile is already being captured");
+        };
+        info!(?duration, format = format.as_str(), "capturing CPU profile");
+        let frequency = self.config.frequency;
+        tokio::task::spawn_blocking(move || capture(duration, frequency, format))
+            .await
+            .context("failed to join profiling task")?
+    }
+}
+
+#[cfg(unix)]
+fn capture(duration: Duration, frequency: i32, format: Format) -> anyhow::Result<Vec<u8>> {
+    use pprof::protos::Message as _;
+
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(frequency)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()
+        .context("failed to start profiler")?;
+    std::thread::sleep(duration);
+    let report = guard
+        .report()
+        .build()
+        .context("failed to build profile report")?;
+    match format {
+        Format::Pprof => {
+            let profile = report.pprof().context("failed to encode pprof profile")?;
+            Ok(profile.encode_to_vec())
+        }
+        Format::Flamegraph => {
+            let mut buf = Vec::new();
+            report
+                .flamegraph(&mut buf)
+                .context("failed to render flamegraph")?;
+            Ok(buf)
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn capture(_: Duration, _: i32, _: Format) -> anyhow::Result<Vec<u8>> {
+    bail!("CPU profiling is not supported on this platform")
+}
+
+fn respond(
+    status: StatusCode,
+    content_type: &str,
+    body: impl Into<Bytes>,
+) -> Response<Full<Bytes>> {
+    let mut res = Response::new(Full::new(body.into()));
+    *res.status_mut() = status;
+    if let Ok(content_type) = content_type.parse() {
+        res.headers_mut()
+            .insert(hyper::header::CONTENT_TYPE, content_type);
+    }
+    res
+}
+
+async fn handle(profiler: &Profiler, req: Request<Incoming>) -> Response<Full<Bytes>> {
+    let format = match (req.method(), req.uri().path()) {
+        (&Method::GET, "/debug/pprof/profile") => Format::Pprof,
+        (&Method::GET, "/debug/pprof/flamegraph") => Format::Flamegraph,
+        _ => return respond(StatusCode::NOT_FOUND, "text/plain", "not found"),
+    };
+    // Duration is specified in seconds to match Go's `net/http/pprof`
+    let seconds = req.uri().query().and_then(|query| {
+        url::form_urlencoded::parse(query.as_bytes())
+            .find_map(|(k, v)| (k == "seconds").then(|| v.parse::<u64>()))
+    });
+    let duration = match seconds.transpose() {
+        Ok(seconds) => seconds.map(Duration::from_secs),
+        Err(e) => {
+            return respond(
+                StatusCode::BAD_REQUEST,
+                "text/plain",
+                format!("invalid `seconds`: {e}"),
+            )
+        }
+    };
+    match profiler.capture(duration, format).await {
+        Ok(profile) => respond(StatusCode::OK, format.content_type(), profile),
+        Err(e) => {
+            warn!(?e, "failed to capture profile");
+            respond(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "text/plain",
+                format!("{e:#}"),
+            )
+        }
+    }
+}
+
+/// Serve the profiling HTTP endpoints on `listener` until the task is aborted
+pub(crate) async fn serve(listener: TcpListener, profiler: Arc<Profiler>) {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                error!(?e, "failed to accept profiling connection");
+                continue;
+            }
+        };
+        debug!(%addr, "accepted profiling connection");
+        let profiler = Arc::clone(&profiler);
+        tokio::spawn(async move {
+            let svc = service_fn(|req| {
+                let profiler = Arc::clone(&profiler);
+                async move { anyhow::Ok(handle(&profiler, req).await) }
+            });
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), svc)
+                .await
+            {
+                warn!(?e, %addr, "failed to serve profiling connection");
+            }
+        });
+    }
+}
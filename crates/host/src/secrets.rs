@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use futures::StreamExt;
+use serde::Deserialize;
+use tokio::fs;
+use tokio::sync::RwLock;
+use tracing::{debug, instrument, warn};
+use wasmcloud_core::secrets::{SecretRequest, SecretResponse};
+
+/// Default name of the file secrets backend, referenced as `secret://file/<path>#<key>`
+pub const DEFAULT_BACKEND_NAME: &str = "file";
+
+/// Configuration of the reference secrets backend served by the host, which serves secrets from
+/// a JSON file.
+///
+/// The file maps secret paths to the values of the secret and, optionally, the public keys of the
+/// providers permitted to read it, e.g.
+///
+/// ```json
+/// {
+///   "db": {
+///     "providers": ["VAZVC4RX54J2NVCMCW7BPCAHGGG5XZXDBXFUMDUXGESTMQEJLC3YVZWB"],
+///     "values": { "user": "app", "password": "hunter2" }
+///   }
+/// }
+/// ```
+///
+/// If `providers` is empty, all providers of the cluster may read the secret. Note that providers
+/// are identified by their own claim, see the
+/// [trust model](wasmcloud_core::secrets#trust-model). The file is read once on host start.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Name of the backend in secret references
+    pub name: String,
+    /// Path of the JSON file to serve secrets from
+    pub path: PathBuf,
+}
+
+impl Config {
+    /// Construct a configuration serving secrets from the file at `path` under the
+    /// [default name](DEFAULT_BACKEND_NAME)
+    #[must_use]
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: DEFAULT_BACKEND_NAME.to_string(),
+            path: path.into(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Secret {
+    #[serde(default)]
+    providers: Vec<String>,
+    values: HashMap<String, String>,
+}
+
+/// Secrets loaded from the file of a [`Config`]
+#[derive(Debug)]
+pub(crate) struct FileBackend {
+    name: String,
+    secrets: HashMap<String, Secret>,
+}
+
+impl FileBackend {
+    /// Load the secrets of the backend described by `config`
+    pub(crate) async fn load(config: &Config) -> anyhow::Result<Self> {
+        let secrets = fs::read(&config.path)
+            .await
+            .with_context(|| format!("failed to read `{}`", config.path.display()))?;
+        let secrets = serde_json::from_slice(&secrets)
+            .with_context(|| format!("failed to parse `{}`", config.path.display()))?;
+        Ok(Self {
+            name: config.name.clone(),
+            secrets,
+        })
+    }
+
+    /// Returns the requested secret value, or the reason it cannot be returned. Requests must be
+    /// signed by one of `valid_issuers`
+    fn get(&self, request: &SecretRequest, valid_issuers: &[String]) -> Result<String, String> {
+        request
+            .validate(&self.name, valid_issuers)
+            .map_err(|e| format!("invalid request: {e:#}"))?;
+        let secret = self
+            .secrets
+            .get(&request.path)
+            .ok_or_else(|| format!("secret `{}` not found", request.path))?;
+        if !secret.providers.is_empty() && !secret.providers.contains(&request.provider_id) {
+            return Err(format!(
+                "provider `{}` is not permitted to read secret `{}`",
+                request.provider_id, request.path
+            ));
+        }
+        match (&request.key, secret.values.len()) {
+            (Some(key), _) => {
+                secret.values.get(key).cloned().ok_or_else(|| {
+                    format!("secret `{}` does not have a value `{key}`", request.path)
+                })
+            }
+            (None, 1) => Ok(secret.values.values().next().cloned().unwrap_or_default()),
+            (None, _) => Err(format!(
+                "secret `{}` holds multiple values, a key must be specified",
+                request.path
+            )),
+        }
+    }
+
+    /// Respond to the requests received on `requests` until the subscription ends
+    #[instrument(level = "debug", skip_all, fields(backend = %self.name))]
+    pub(crate) async fn serve(
+        self,
+        nats: async_nats::Client,
+        mut requests: async_nats::Subscriber,
+        valid_issuers: Arc<RwLock<Vec<String>>>,
+    ) {
+        while let Some(msg) = requests.next().await {
+            let Some(reply) = msg.reply else {
+                continue;
+            };
+            let res = match serde_json::from_slice::<SecretRequest>(&msg.payload) {
+                Ok(request) => {
+                    let res = self.get(&request, &valid_issuers.read().await);
+                    match &res {
+                        Ok(_) => debug!(
+                            path = %request.path,
+                            provider_id = %request.provider_id,
+                            "serving secret"
+                        ),
+                        Err(error) => warn!(
+                            path = %request.path,
+                            provider_id = %request.provider_id,
+                            error,
+                            "denied secret request"
+                        ),
+                    }
+                    res
+                }
+                Err(e) => Err(format!("invalid request: {e}")),
+            };
+            let res = match res {
+                Ok(value) => SecretResponse {
+                    value: Some(value),
+                    error: None,
+                },
+                Err(error) => SecretResponse {
+                    value: None,
+                    error: Some(error),
+                },
+            };
+            match serde_json::to_vec(&res) {
+                Ok(payload) => {
+                    if let Err(e) = nats.publish(reply, payload.into()).await {
+                        warn!(?e, "failed to respond to secret request");
+                    }
+                }
+                Err(e) => warn!(?e, "failed to encode secret response"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use nkeys::{KeyPair, KeyPairType};
+    use wasmcloud_core::secrets::SecretReference;
+
+    async fn backend(secrets: serde_json::Value) -> anyhow::Result<FileBackend> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("secrets.json");
+        fs::write(&path, serde_json::to_vec(&secrets)?).await?;
+        FileBackend::load(&Config::new(path)).await
+    }
+
+    fn request(cluster_key: &KeyPair, reference: &str, provider_id: &str) -> SecretRequest {
+        let reference: SecretReference = reference.parse().expect("invalid reference");
+        SecretRequest::new(cluster_key, &reference, provider_id).expect("failed to sign request")
+    }
+
+    #[tokio::test]
+    async fn file_backend() -> anyhow::Result<()> {
+        let backend = backend(serde_json::json!({
+            "db": {
+                "providers": ["VPROVIDER"],
+                "values": { "user": "app", "password": "hunter2" }
+            },
+            "token": { "values": { "token": "abc" } }
+        }))
+        .await?;
+        let cluster_key = KeyPair::new(KeyPairType::Cluster);
+        let issuers = [cluster_key.public_key()];
+
+        let get = |reference: &str, provider_id: &str| {
+            backend.get(&request(&cluster_key, reference, provider_id), &issuers)
+        };
+        assert_eq!(
+            get("secret://file/db#password", "VPROVIDER").as_deref(),
+            Ok("hunter2")
+        );
+        assert_eq!(get("secret://file/token", "VOTHER").as_deref(), Ok("abc"));
+        // Secrets are only served to permitted providers
+        assert!(get("secret://file/db#password", "VOTHER").is_err());
+        // Missing secrets and values
+        assert!(get("secret://file/missing", "VPROVIDER").is_err());
+        assert!(get("secret://file/db#missing", "VPROVIDER").is_err());
+        // A key is required for secrets holding multiple values
+        assert!(get("secret://file/db", "VPROVIDER").is_err());
+        // Requests for other backends or signed by other clusters are rejected
+        assert!(get("secret://vault/token", "VPROVIDER").is_err());
+        let other_key = KeyPair::new(KeyPairType::Cluster);
+        assert!(backend
+            .get(
+                &request(&other_key, "secret://file/token", "VPROVIDER"),
+                &issuers
+            )
+            .is_err());
+        Ok(())
+    }
+}
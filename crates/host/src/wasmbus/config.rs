@@ -1,12 +1,18 @@
-use crate::OciConfig;
+use crate::{cosign, AuditConfig, EventSinkConfig, OciConfig, ProfilingConfig, SecretsConfig};
 
 use std::collections::HashMap;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
 
+use anyhow::{ensure, Context as _};
+use ipnet::IpNet;
 use nkeys::KeyPair;
 use url::Url;
-use wasmcloud_core::{logging::Level as LogLevel, OtelConfig};
+use wasmcloud_core::{logging::Level as LogLevel, InvocationValidity, OtelConfig, TlsConfig};
+use wasmcloud_runtime::AllowedNetwork;
 
 /// wasmCloud Host configuration
 #[allow(clippy::struct_excessive_bools)]
@@ -32,6 +38,14 @@ pub struct Host {
     pub rpc_key: Option<Arc<KeyPair>>,
     /// Whether to require TLS for RPC connection
     pub rpc_tls: bool,
+    /// Account signing key pair used to issue each capability provider its own NATS user
+    /// credentials for the RPC connection, scoped to the lattices the provider serves. Requires
+    /// `rpc_jwt` issued by the same account. If not set, providers authenticate with `rpc_jwt` and
+    /// `rpc_key`
+    pub provider_rpc_signing_key: Option<Arc<KeyPair>>,
+    /// TLS settings, including an optional client certificate, for the RPC connection. Also
+    /// passed to capability providers, which use them to connect to the RPC NATS server
+    pub rpc_tls_config: TlsConfig,
     /// The lattice the host belongs to
     pub lattice_prefix: String,
     /// The domain to use for host Jetstream operations
@@ -44,14 +58,50 @@ pub struct Host {
     pub cluster_key: Option<Arc<KeyPair>>,
     /// The identity keys (a printable 256-bit Ed25519 public key) that this host should allow invocations from
     pub cluster_issuers: Option<Vec<String>>,
+    /// Time-based validation rules for invocation claims, also passed to capability providers
+    pub invocation_validity: InvocationValidity,
+    /// Link definition value key patterns to redact from logs, events and inventory responses, in
+    /// addition to [`wasmcloud_core::redact::DEFAULT_SENSITIVE_PATTERNS`]. `*` matches any sequence
+    /// of characters. Also passed to capability providers.
+    pub secret_patterns: Vec<String>,
+    /// Reference secrets backend to serve to the providers of the lattice, disabled if `None`
+    pub secrets_backend: Option<SecretsConfig>,
+    /// Names of custom actor claims to forward to capability providers in signed invocation
+    /// claims, e.g. `tenant`
+    pub forwarded_actor_claims: Vec<String>,
+    /// Audit log of invocations handled by actors on this host, disabled if `None`
+    pub audit_log: Option<AuditConfig>,
+    /// Destinations to send lattice events to, in addition to the control interface
+    pub event_sinks: Vec<EventSinkConfig>,
+    /// On-demand CPU profiling of the host process, disabled if `None`
+    pub profiling: Option<ProfilingConfig>,
     /// The amount of time to wait for a provider to gracefully shut down before terminating it
     pub provider_shutdown_delay: Option<Duration>,
     /// Configuration for downloading artifacts from OCI registries
     pub oci_opts: OciConfig,
+    /// Keys and identities, one of which must have signed actors and providers fetched from OCI
+    /// registries. Signatures are not verified if `None`
+    pub signature_verification: Option<cosign::Config>,
     /// Whether to allow loading actor or provider components from the filesystem
     pub allow_file_load: bool,
+    /// Directory to store durable per-actor state (`wasmcloud:state`) in. If `None`, state is
+    /// stored in JetStream key-value buckets shared by all hosts in the lattice
+    pub state_dir: Option<PathBuf>,
+    /// Destinations actors may open outbound TCP connections to using `wasi:sockets`. Actors
+    /// have no network access if empty
+    pub actor_network_allowlist: Vec<ActorNetworkRule>,
     /// Whether or not structured logging is enabled
     pub enable_structured_logging: bool,
+    /// Whether capability providers should forward their logs to the host, which re-emits them
+    /// attributed to the provider
+    pub forward_provider_logs: bool,
+    /// Whether capability providers should record the redacted contents of invocation payloads in
+    /// traces. Intended for debugging only
+    pub capture_provider_payloads: bool,
+    /// Whether large invocation payloads are chunked inline over NATS instead of through a
+    /// JetStream object store, for peers advertising support for it. Payloads sent to other
+    /// peers are chunked through JetStream
+    pub inline_chunking: bool,
     /// Log level to pass to capability providers to use. Should be parsed from a [`tracing::Level`]
     pub log_level: LogLevel,
     /// Whether to enable loading supplemental configuration
@@ -69,10 +119,83 @@ pub struct PolicyService {
     pub policy_topic: Option<String>,
     /// An optional topic to receive updated policy decisions on
     pub policy_changes_topic: Option<String>,
+    /// The URL of an OPA data API rule to request policy decisions from instead of `policy_topic`,
+    /// e.g. `http://localhost:8181/v1/data/wasmcloud/access/decision`
+    pub policy_opa_url: Option<Url>,
     /// The timeout for policy requests
     pub policy_timeout_ms: Option<Duration>,
 }
 
+/// Destination actors are allowed to connect to, parsed from
+/// `[<actor public key>=]<IP address or CIDR>[:<port>|:<start>-<end>]`, e.g.
+/// `10.0.0.0/8:5432` or `MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5=192.0.2.1:80-443`.
+/// IPv6 addresses followed by a port must include a prefix length
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActorNetworkRule {
+    /// Public key of the actor the rule applies to, all actors if `None`
+    pub actor: Option<String>,
+    /// Allowed destination addresses and ports
+    pub network: AllowedNetwork,
+}
+
+impl ActorNetworkRule {
+    /// Returns whether the rule applies to actor with public key `actor`
+    #[must_use]
+    pub fn applies_to(&self, actor: &str) -> bool {
+        self.actor.as_deref().map_or(true, |rule| rule == actor)
+    }
+}
+
+impl FromStr for ActorNetworkRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let (actor, dest) = match s.split_once('=') {
+            Some((actor, dest)) => {
+                ensure!(!actor.is_empty(), "actor public key must not be empty");
+                (Some(actor.to_string()), dest)
+            }
+            None => (None, s),
+        };
+        // the port separator must follow the prefix length, if any, since IPv6 addresses contain `:`
+        let ports_from = match dest.find('/') {
+            Some(i) => i,
+            None if dest.parse::<IpAddr>().is_ok() => dest.len(),
+            None => 0,
+        };
+        let (net, ports) = match dest[ports_from..].find(':') {
+            Some(i) => (&dest[..ports_from + i], Some(&dest[ports_from + i + 1..])),
+            None => (dest, None),
+        };
+        let net = if net.contains('/') {
+            net.parse()
+                .with_context(|| format!("invalid CIDR `{net}`"))?
+        } else {
+            let ip: IpAddr = net
+                .parse()
+                .with_context(|| format!("invalid IP address `{net}`"))?;
+            IpNet::from(ip)
+        };
+        let ports = match ports.map(|ports| ports.split_once('-').unwrap_or((ports, ports))) {
+            Some((start, end)) => {
+                let start = start
+                    .parse()
+                    .with_context(|| format!("invalid port `{start}`"))?;
+                let end = end
+                    .parse()
+                    .with_context(|| format!("invalid port `{end}`"))?;
+                ensure!(start <= end, "invalid port range `{start}-{end}`");
+                start..=end
+            }
+            None => 0..=u16::MAX,
+        };
+        Ok(Self {
+            actor,
+            network: AllowedNetwork { net, ports },
+        })
+    }
+}
+
 impl Default for Host {
     fn default() -> Self {
         Self {
@@ -88,16 +211,31 @@ impl Default for Host {
             rpc_jwt: None,
             rpc_key: None,
             rpc_tls: false,
+            provider_rpc_signing_key: None,
+            rpc_tls_config: TlsConfig::default(),
             lattice_prefix: "default".to_string(),
             js_domain: None,
             labels: HashMap::default(),
             host_key: None,
             cluster_key: None,
             cluster_issuers: None,
+            invocation_validity: InvocationValidity::default(),
+            secret_patterns: Vec::default(),
+            secrets_backend: None,
+            forwarded_actor_claims: Vec::default(),
+            audit_log: None,
+            event_sinks: Vec::default(),
+            profiling: None,
             provider_shutdown_delay: None,
             oci_opts: OciConfig::default(),
+            signature_verification: None,
             allow_file_load: false,
+            state_dir: None,
+            actor_network_allowlist: Vec::default(),
             enable_structured_logging: false,
+            forward_provider_logs: false,
+            capture_provider_payloads: false,
+            inline_chunking: false,
             log_level: LogLevel::Info,
             config_service_enabled: false,
             otel_config: OtelConfig::default(),
@@ -105,3 +243,39 @@ impl Default for Host {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_actor_network_rule() -> anyhow::Result<()> {
+        let rule: ActorNetworkRule = "10.0.0.0/8".parse()?;
+        assert_eq!(rule.actor, None);
+        assert_eq!(rule.network.net, "10.0.0.0/8".parse::<IpNet>()?);
+        assert_eq!(rule.network.ports, 0..=u16::MAX);
+        assert!(rule.applies_to("foo"));
+
+        let rule: ActorNetworkRule = "foo=192.0.2.1:80-443".parse()?;
+        assert_eq!(rule.actor.as_deref(), Some("foo"));
+        assert_eq!(rule.network.net, "192.0.2.1/32".parse::<IpNet>()?);
+        assert_eq!(rule.network.ports, 80..=443);
+        assert!(rule.applies_to("foo"));
+        assert!(!rule.applies_to("bar"));
+
+        let rule: ActorNetworkRule = "::1".parse()?;
+        assert_eq!(rule.network.net, "::1/128".parse::<IpNet>()?);
+        assert_eq!(rule.network.ports, 0..=u16::MAX);
+
+        let rule: ActorNetworkRule = "fd00::/8:5432".parse()?;
+        assert_eq!(rule.network.net, "fd00::/8".parse::<IpNet>()?);
+        assert_eq!(rule.network.ports, 5432..=5432);
+
+        assert!("10.0.0.0/33".parse::<ActorNetworkRule>().is_err());
+        assert!("10.0.0.1:443-80".parse::<ActorNetworkRule>().is_err());
+        assert!("10.0.0.1:65536".parse::<ActorNetworkRule>().is_err());
+        assert!("=10.0.0.1".parse::<ActorNetworkRule>().is_err());
+        assert!("example.com:80".parse::<ActorNetworkRule>().is_err());
+        Ok(())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{cosign, event_sink};
+
 use core::num::NonZeroUsize;
 
 use std::collections::{BTreeMap, HashMap};
@@ -10,6 +12,7 @@ use tracing::instrument;
 use ulid::Ulid;
 use uuid::Uuid;
 use wascap::jwt;
+use wasmcloud_core::WasmCloudEntity;
 
 fn format_actor_claims(claims: &jwt::Claims<jwt::Actor>) -> serde_json::Value {
     let issuer = &claims.issuer;
@@ -41,6 +44,7 @@ pub fn actor_started(
     annotations: &BTreeMap<String, String>,
     instance_id: Uuid,
     image_ref: impl AsRef<str>,
+    signature: Option<&cosign::Verification>,
 ) -> serde_json::Value {
     json!({
         "public_key": claims.subject,
@@ -49,6 +53,7 @@ pub fn actor_started(
         "instance_id": instance_id,
         "annotations": annotations,
         "claims": format_actor_claims(claims),
+        "signature": signature,
     })
 }
 
@@ -77,6 +82,7 @@ pub fn actors_started(
     host_id: impl AsRef<str>,
     count: impl Into<usize>,
     image_ref: impl AsRef<str>,
+    signature: Option<&cosign::Verification>,
 ) -> serde_json::Value {
     json!({
         "public_key": claims.subject,
@@ -85,6 +91,7 @@ pub fn actors_started(
         "host_id": host_id.as_ref(),
         "claims": format_actor_claims(claims),
         "count": count.into(),
+        "signature": signature,
     })
 }
 
@@ -163,6 +170,7 @@ pub fn provider_started(
     host_id: impl AsRef<str>,
     image_ref: impl AsRef<str>,
     link_name: impl AsRef<str>,
+    signature: Option<&cosign::Verification>,
 ) -> serde_json::Value {
     let metadata = claims.metadata.as_ref();
     json!({
@@ -181,6 +189,7 @@ pub fn provider_started(
             "not_before_human": "TODO",
             "expires_human": "TODO",
         },
+        "signature": signature,
     })
 }
 
@@ -228,6 +237,22 @@ pub fn provider_health_check(
     })
 }
 
+pub fn invocation_auth_failed(
+    origin: &WasmCloudEntity,
+    target: &WasmCloudEntity,
+    operation: impl AsRef<str>,
+    invocation_id: impl AsRef<str>,
+    error: &anyhow::Error,
+) -> serde_json::Value {
+    json!({
+        "source": origin,
+        "dest": target,
+        "operation": operation.as_ref(),
+        "invocation_id": invocation_id.as_ref(),
+        "error": format!("{error:#}"),
+    })
+}
+
 pub fn config_set(entity_id: impl AsRef<str>, key: impl AsRef<str>) -> serde_json::Value {
     json!({
         "entity_id": entity_id.as_ref(),
@@ -242,10 +267,44 @@ pub fn config_deleted(entity_id: impl AsRef<str>, key: impl AsRef<str>) -> serde
     })
 }
 
+pub fn cluster_issuers_updated(
+    host_id: impl AsRef<str>,
+    cluster_issuers: &[String],
+) -> serde_json::Value {
+    json!({
+        "host_id": host_id.as_ref(),
+        "cluster_issuers": cluster_issuers,
+    })
+}
+
+pub fn manifest_applied(
+    host_id: impl AsRef<str>,
+    actors: &[String],
+    providers: &[String],
+    links: usize,
+    pruned: &[String],
+) -> serde_json::Value {
+    json!({
+        "host_id": host_id.as_ref(),
+        "actors": actors,
+        "providers": providers,
+        "links": links,
+        "pruned": pruned,
+    })
+}
+
+pub fn manifest_apply_failed(host_id: impl AsRef<str>, error: &anyhow::Error) -> serde_json::Value {
+    json!({
+        "host_id": host_id.as_ref(),
+        "error": format!("{error:#}"),
+    })
+}
+
 #[instrument(level = "debug", skip(event_builder, ctl_nats, data))]
 pub(crate) async fn publish(
     event_builder: &EventBuilderV10,
     ctl_nats: &async_nats::Client,
+    sinks: &event_sink::Sinks,
     lattice_prefix: &str,
     name: &str,
     data: serde_json::Value,
@@ -262,6 +321,7 @@ pub(crate) async fn publish(
         .build()
         .context("failed to build cloud event")?;
     let ev = serde_json::to_vec(&ev).context("failed to serialize event")?;
+    sinks.send(name, &ev).await;
     // TODO(pre-1.0): deprecate general subject and remove this
     let _ = ctl_nats
         .publish(format!("wasmbus.evt.{lattice_prefix}"), ev.clone().into())
@@ -0,0 +1,153 @@
+use core::num::NonZeroUsize;
+use core::time::Duration;
+
+use anyhow::{bail, Context as _};
+use wasmcloud_core::{
+    MAX_CONCURRENT_INVOCATIONS_ANNOTATION, MAX_EXECUTION_TIME_ANNOTATION, MAX_MEMORY_ANNOTATION,
+};
+
+use super::Annotations;
+
+/// Execution limits of an actor instance, set via annotations of the start request
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Limits {
+    /// Maximum time a single invocation may take, before it is aborted
+    pub(crate) max_execution_time: Option<Duration>,
+    /// Maximum amount of linear memory in bytes a single actor instance may allocate
+    pub(crate) max_memory: Option<usize>,
+    /// Maximum number of invocations handled concurrently
+    pub(crate) max_concurrent_invocations: Option<NonZeroUsize>,
+}
+
+/// Parse an amount of memory in bytes, optionally with a binary unit suffix, e.g. `64MiB`
+fn parse_memory(value: &str) -> anyhow::Result<usize> {
+    let value = value.trim();
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(unit_start);
+    let amount: usize = amount.parse().context("invalid amount")?;
+    let factor = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "kib" => 1 << 10,
+        "mib" => 1 << 20,
+        "gib" => 1 << 30,
+        unit => bail!("unknown unit `{unit}`, expected one of `B`, `KiB`, `MiB` or `GiB`"),
+    };
+    amount.checked_mul(factor).context("amount too large")
+}
+
+impl Limits {
+    /// Parse the limits from `annotations`. Annotations, which are not set, impose no limit
+    pub(crate) fn from_annotations(annotations: &Annotations) -> anyhow::Result<Self> {
+        let max_execution_time = annotations
+            .get(MAX_EXECUTION_TIME_ANNOTATION)
+            .map(|value| {
+                humantime::parse_duration(value.trim()).with_context(|| {
+                    format!("invalid `{MAX_EXECUTION_TIME_ANNOTATION}` annotation `{value}`")
+                })
+            })
+            .transpose()?;
+        let max_memory = annotations
+            .get(MAX_MEMORY_ANNOTATION)
+            .map(|value| {
+                parse_memory(value).with_context(|| {
+                    format!("invalid `{MAX_MEMORY_ANNOTATION}` annotation `{value}`")
+                })
+            })
+            .transpose()?;
+        let max_concurrent_invocations = annotations
+            .get(MAX_CONCURRENT_INVOCATIONS_ANNOTATION)
+            .map(|value| {
+                value.trim().parse().with_context(|| {
+                    format!(
+                        "invalid `{MAX_CONCURRENT_INVOCATIONS_ANNOTATION}` annotation `{value}`"
+                    )
+                })
+            })
+            .transpose()?;
+        Ok(Self {
+            max_execution_time,
+            max_memory,
+            max_concurrent_invocations,
+        })
+    }
+
+    /// Returns the number of invocations an instance scaled to `max` may handle concurrently
+    pub(crate) fn concurrency(&self, max: Option<NonZeroUsize>) -> Option<NonZeroUsize> {
+        match (max, self.max_concurrent_invocations) {
+            (Some(max), Some(limit)) => Some(max.min(limit)),
+            (max, None) => max,
+            (None, limit) => limit,
+        }
+    }
+
+    /// Returns the limits as reported in the host inventory, `None` if no limits are set
+    pub(crate) fn inventory(&self) -> Option<wasmcloud_control_interface::ActorLimits> {
+        if *self == Self::default() {
+            return None;
+        }
+        Some(wasmcloud_control_interface::ActorLimits {
+            max_execution_time_ms: self
+                .max_execution_time
+                .map(|timeout| timeout.as_millis().try_into().unwrap_or(u64::MAX)),
+            max_memory_bytes: self
+                .max_memory
+                .map(|max| max.try_into().unwrap_or(u64::MAX)),
+            max_concurrent_invocations: self
+                .max_concurrent_invocations
+                .map(|max| max.get().try_into().unwrap_or(u16::MAX)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use crate::wasmbus::test_util::annotations;
+
+    #[test]
+    fn parse_limits() {
+        let limits = Limits::from_annotations(&annotations(&[
+            (MAX_EXECUTION_TIME_ANNOTATION, "1s 500ms"),
+            (MAX_MEMORY_ANNOTATION, "64MiB"),
+            (MAX_CONCURRENT_INVOCATIONS_ANNOTATION, "4"),
+        ]))
+        .expect("valid limits");
+        assert_eq!(limits.max_execution_time, Some(Duration::from_millis(1500)));
+        assert_eq!(limits.max_memory, Some(64 << 20));
+        assert_eq!(limits.max_concurrent_invocations, NonZeroUsize::new(4));
+        assert_eq!(
+            limits.concurrency(NonZeroUsize::new(10)),
+            NonZeroUsize::new(4)
+        );
+        assert_eq!(
+            limits.concurrency(NonZeroUsize::new(2)),
+            NonZeroUsize::new(2)
+        );
+        assert_eq!(limits.concurrency(None), NonZeroUsize::new(4));
+
+        let limits =
+            Limits::from_annotations(&annotations(&[("foo", "bar")])).expect("valid limits");
+        assert_eq!(limits, Limits::default());
+        assert_eq!(limits.inventory(), None);
+        assert_eq!(parse_memory("1024").unwrap(), 1024);
+        assert_eq!(parse_memory("2 KiB").unwrap(), 2048);
+    }
+
+    #[test]
+    fn parse_invalid_limits() {
+        for (annotation, value) in [
+            (MAX_EXECUTION_TIME_ANNOTATION, "forever"),
+            (MAX_MEMORY_ANNOTATION, "64MB"),
+            (MAX_MEMORY_ANNOTATION, "MiB"),
+            (MAX_CONCURRENT_INVOCATIONS_ANNOTATION, "0"),
+        ] {
+            assert!(
+                Limits::from_annotations(&annotations(&[(annotation, value)])).is_err(),
+                "{annotation}={value} should be invalid"
+            );
+        }
+    }
+}
@@ -4,11 +4,22 @@ pub mod config;
 pub use config::Host as HostConfig;
 
 mod event;
+mod limits;
+mod pool;
+mod provider_auth;
+mod state;
+#[cfg(test)]
+mod test_util;
+
+use limits::Limits;
+use pool::InstancePool;
+use provider_auth::{CredentialIssuer, Credentials};
+use state::ActorState;
 
 use crate::{
-    fetch_actor, socket_pair, OciConfig, PolicyAction, PolicyHostInfo, PolicyManager,
-    PolicyRequestSource, PolicyRequestTarget, PolicyResponse, RegistryAuth, RegistryConfig,
-    RegistryType,
+    audit, cosign, event_sink, fetch_actor, metrics, profiling, secrets, socket_pair, OciConfig,
+    PolicyAction, PolicyHostInfo, PolicyManager, PolicyRequestSource, PolicyRequestTarget,
+    PolicyResponse, RegistryAuth, RegistryConfig, RegistryType,
 };
 
 use core::future::Future;
@@ -41,6 +52,7 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 use sha2::{Digest, Sha256};
 use tokio::io::{empty, stderr, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpListener;
 use tokio::sync::{watch, RwLock};
 use tokio::task::JoinHandle;
 use tokio::time::{interval_at, Instant};
@@ -51,15 +63,26 @@ use ulid::Ulid;
 use uuid::Uuid;
 use wascap::{jwt, prelude::ClaimsBuilder};
 use wasmcloud_control_interface::{
-    ActorAuctionAck, ActorAuctionRequest, ActorDescription, GetClaimsResponse, HostInventory,
-    HostLabel, LinkDefinition, LinkDefinitionList, ProviderAuctionAck, ProviderAuctionRequest,
+    ActorAuctionAck, ActorAuctionRequest, ActorDescription, ApplyManifestCommand,
+    ClusterIssuerRequest, GetClaimsResponse, HostInventory, HostLabel, HostMetrics, LinkDefinition,
+    LinkDefinitionList, Manifest, ManifestActor, ManifestLink, ManifestProvider,
+    ProfileHostCommand, ProfileHostResponse, ProviderAuctionAck, ProviderAuctionRequest,
     ProviderDescription, RegistryCredential, RegistryCredentialMap, RemoveLinkDefinitionRequest,
     ScaleActorCommand, StartProviderCommand, StopActorCommand, StopHostCommand,
     StopProviderCommand, UpdateActorCommand,
 };
-use wasmcloud_core::chunking::{ChunkEndpoint, CHUNK_RPC_EXTRA_TIME, CHUNK_THRESHOLD_BYTES};
+use wasmcloud_core::body_stream::BodyStreamEndpoint;
+use wasmcloud_core::chunking::{
+    ChunkEndpoint, ChunkTransport, CHUNK_RPC_EXTRA_TIME, CHUNK_THRESHOLD_BYTES,
+};
+use wasmcloud_core::logging::{forwarded_logs_subject, ForwardedLogRecord, Level as LogLevel};
+use wasmcloud_core::redact::Redactor;
+use wasmcloud_core::secrets::secrets_subject;
 use wasmcloud_core::{
-    HealthCheckResponse, HostData, Invocation, InvocationResponse, OtelConfig, WasmCloudEntity,
+    annotated_additional_lattices, annotated_metrics_port, annotated_sampler_ratio,
+    ensure_schema_version, provider_links_subject, ClusterIssuersUpdate, HealthCheckResponse,
+    HostData, Invocation, InvocationResponse, InvocationValidity, TlsConfig, WasmCloudEntity,
+    CONTENT_TYPE_MSGPACK, MIN_SCHEMA_VERSION, SCHEMA_VERSION,
 };
 use wasmcloud_runtime::capability::logging::logging;
 use wasmcloud_runtime::capability::{
@@ -84,6 +107,7 @@ struct Queue {
     registries: async_nats::Subscriber,
     config: async_nats::Subscriber,
     config_get: async_nats::Subscriber,
+    issuers: async_nats::Subscriber,
 }
 
 impl Stream for Queue {
@@ -141,6 +165,11 @@ impl Stream for Queue {
             Poll::Ready(None) => {}
             Poll::Pending => pending = true,
         }
+        match Pin::new(&mut self.issuers).poll_next(cx) {
+            Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg)),
+            Poll::Ready(None) => {}
+            Poll::Pending => pending = true,
+        }
         if pending {
             Poll::Pending
         } else {
@@ -149,6 +178,27 @@ impl Stream for Queue {
     }
 }
 
+/// Returns the link definitions of the provider instance with public key `provider_id` and link
+/// name `link_name`
+fn provider_link_definitions(
+    links: &HashMap<String, LinkDefinition>,
+    provider_id: &str,
+    link_name: &str,
+) -> Vec<wasmcloud_core::LinkDefinition> {
+    // TODO: update type of links to use wasmcloud_core::LinkDefinition
+    links
+        .values()
+        .filter(|ld| ld.provider_id == provider_id && ld.link_name == link_name)
+        .map(|ld| wasmcloud_core::LinkDefinition {
+            actor_id: ld.actor_id.clone(),
+            provider_id: ld.provider_id.clone(),
+            link_name: ld.link_name.clone(),
+            contract_id: ld.contract_id.clone(),
+            values: ld.values.clone().into_iter().collect(),
+        })
+        .collect()
+}
+
 #[derive(Clone, Default)]
 struct AsyncBytesMut(Arc<std::sync::Mutex<BytesMut>>);
 
@@ -211,6 +261,7 @@ impl Queue {
             labels,
             config,
             config_get,
+            issuers,
         ) = try_join!(
             nats.subscribe(format!("{topic_prefix}.{lattice_prefix}.registries.put",)),
             nats.subscribe(format!("{topic_prefix}.{lattice_prefix}.ping.hosts",)),
@@ -236,6 +287,7 @@ impl Queue {
                 format!("{topic_prefix}.{lattice_prefix}.get.config.>"),
                 format!("{topic_prefix}.{lattice_prefix}.get.config")
             ),
+            nats.subscribe(format!("{topic_prefix}.{lattice_prefix}.issuers.*")),
         )
         .context("failed to subscribe to queues")?;
         Ok(Self {
@@ -249,6 +301,7 @@ impl Queue {
             registries,
             config,
             config_get,
+            issuers,
         })
     }
 }
@@ -261,11 +314,27 @@ struct ActorInstance {
     calls: AbortHandle,
     handler: Handler,
     chunk_endpoint: ChunkEndpoint,
+    /// Endpoint used to stream HTTP bodies to and from the `wasmcloud:httpserver` provider
+    body_stream_endpoint: BodyStreamEndpoint,
     annotations: Annotations,
+    /// Ratio of traces starting at this actor to sample, overriding the host default
+    sampling_ratio: Option<f64>,
     max: Option<NonZeroUsize>,
-    /// Cluster issuers that this actor should accept invocations from
-    valid_issuers: Vec<String>,
+    /// Execution limits set via annotations
+    limits: Limits,
+    /// Pool of warm instances, if requested via annotations
+    pool: Option<InstancePool>,
+    /// Durable state of the actor, exposed via `wasmcloud:state/store`
+    state: Arc<ActorState>,
+    /// Cluster issuers that this actor should accept invocations from, shared with the host
+    valid_issuers: Arc<RwLock<Vec<String>>>,
+    /// Time-based validation rules for invocation claims
+    invocation_validity: InvocationValidity,
+    ctl_nats: async_nats::Client,
+    event_builder: EventBuilderV10,
+    event_sinks: event_sink::Sinks,
     policy_manager: Arc<PolicyManager>,
+    audit_log: Option<Arc<audit::Log>>,
     image_reference: String,
     actor_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::Actor>>>>, // TODO: use a single map once Claims is an enum
     provider_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::CapabilityProvider>>>>,
@@ -287,12 +356,15 @@ struct Handler {
     cluster_key: Arc<KeyPair>,
     host_key: Arc<KeyPair>,
     claims: jwt::Claims<jwt::Actor>,
+    /// Custom claims of the actor forwarded to the targets of its invocations
+    origin_claims: Option<HashMap<String, String>>,
     origin: WasmCloudEntity,
     // package -> target -> entity
     links: Arc<RwLock<HashMap<String, HashMap<String, WasmCloudEntity>>>>,
     targets: Arc<RwLock<HashMap<TargetInterface, TargetEntity>>>,
     aliases: Arc<RwLock<HashMap<String, WasmCloudEntity>>>,
     chunk_endpoint: ChunkEndpoint,
+    metrics: Arc<metrics::Metrics>,
 }
 
 #[instrument(level = "trace")]
@@ -344,7 +416,7 @@ impl Handler {
         let needs_chunking = request.len() > CHUNK_THRESHOLD_BYTES;
         let injector = TraceContextInjector::default_with_span();
         let headers = injector_to_headers(&injector);
-        let mut invocation = Invocation::new(
+        let mut invocation = Invocation::new_with_origin_claims(
             &self.cluster_key,
             &self.host_key,
             self.origin.clone(),
@@ -352,7 +424,10 @@ impl Handler {
             operation,
             request,
             injector.into(),
+            self.origin_claims.clone(),
         )?;
+        // Payloads of actors are always encoded with MessagePack
+        invocation.content_type = Some(CONTENT_TYPE_MSGPACK.to_string());
 
         // Validate that the actor has the capability to call the target
         ensure_actor_capability(
@@ -360,9 +435,17 @@ impl Handler {
             &invocation.target.contract_id,
         )?;
 
+        invocation.inline_chunking = self.chunk_endpoint.supports_inline();
         if needs_chunking {
+            invocation.chunk_transport = self
+                .chunk_endpoint
+                .transport_for(&invocation.target.public_key);
             self.chunk_endpoint
-                .chunkify(&invocation.id, Cursor::new(invocation.msg))
+                .chunkify_via(
+                    invocation.chunk_transport,
+                    &invocation.id,
+                    Cursor::new(invocation.msg),
+                )
                 .await
                 .context("failed to chunk invocation")?;
             invocation.msg = vec![];
@@ -386,27 +469,47 @@ impl Handler {
             .payload(payload.into())
             .timeout(timeout)
             .headers(headers); // TODO: remove headers once all providers are built off the new SDK, which parses the trace context in the invocation
-        let res = self
-            .nats
-            .send_request(topic, request)
-            .await
-            .context("failed to publish on NATS topic")?;
-
+        let start = Instant::now();
+        let res = self.nats.send_request(topic, request).await;
+        if res.is_err() {
+            self.metrics.record_link_invocation(
+                &self.claims.subject,
+                &invocation.target,
+                &invocation.operation,
+                start.elapsed(),
+                false,
+            );
+        }
+        let res = res.context("failed to publish on NATS topic")?;
+
+        // Responses, which cannot be decoded, are recorded as failures
+        let res = rmp_serde::from_slice::<InvocationResponse>(&res.payload);
+        self.metrics.record_link_invocation(
+            &self.claims.subject,
+            &invocation.target,
+            &invocation.operation,
+            start.elapsed(),
+            matches!(res, Ok(InvocationResponse { error: None, .. })),
+        );
         let InvocationResponse {
             invocation_id,
             mut msg,
             content_length,
             error,
+            chunk_transport,
+            inline_chunking,
             ..
-        } = rmp_serde::from_slice(&res.payload).context("failed to decode invocation response")?;
+        } = res.context("failed to decode invocation response")?;
         ensure!(invocation_id == invocation.id, "invocation ID mismatch");
+        self.chunk_endpoint
+            .record_peer(&invocation.target.public_key, inline_chunking);
 
         let resp_length =
             usize::try_from(content_length).context("content length does not fit in usize")?;
         if resp_length > CHUNK_THRESHOLD_BYTES {
             msg = self
                 .chunk_endpoint
-                .get_unchunkified_response(&invocation_id)
+                .get_unchunkified_response_via(chunk_transport, &invocation_id)
                 .await
                 .context("failed to dechunk response")?;
         } else {
@@ -825,6 +928,9 @@ impl Bus for Handler {
         let cluster_key = self.cluster_key.clone();
         let host_key = self.host_key.clone();
         let claims_metadata = self.claims.metadata.clone();
+        let origin_claims = self.origin_claims.clone();
+        let actor_id = self.claims.subject.clone();
+        let metrics = Arc::clone(&self.metrics);
         Ok((
             async move {
                 // TODO: Stream data
@@ -846,7 +952,7 @@ impl Bus for Handler {
                 let needs_chunking = request.len() > CHUNK_THRESHOLD_BYTES;
                 let injector = TraceContextInjector::default_with_span();
                 let headers = injector_to_headers(&injector);
-                let mut invocation = Invocation::new(
+                let mut invocation = Invocation::new_with_origin_claims(
                     &cluster_key,
                     &host_key,
                     origin,
@@ -854,16 +960,25 @@ impl Bus for Handler {
                     operation,
                     request,
                     injector.into(),
+                    origin_claims,
                 )
                 .map_err(|e| e.to_string())?;
+                invocation.content_type = Some(CONTENT_TYPE_MSGPACK.to_string());
 
                 // Validate that the actor has the capability to call the target
                 ensure_actor_capability(claims_metadata.as_ref(), &invocation.target.contract_id)
                     .map_err(|e| e.to_string())?;
 
+                invocation.inline_chunking = chunk_endpoint.supports_inline();
                 if needs_chunking {
+                    invocation.chunk_transport =
+                        chunk_endpoint.transport_for(&invocation.target.public_key);
                     chunk_endpoint
-                        .chunkify(&invocation.id, Cursor::new(invocation.msg))
+                        .chunkify_via(
+                            invocation.chunk_transport,
+                            &invocation.id,
+                            Cursor::new(invocation.msg),
+                        )
                         .await
                         .context("failed to chunk invocation")
                         .map_err(|e| e.to_string())?;
@@ -889,31 +1004,52 @@ impl Bus for Handler {
                     .payload(payload.into())
                     .timeout(timeout)
                     .headers(headers); // TODO: remove headers once all providers are built off the new SDK, which parses the trace context in the invocation
-                let res = nats
-                    .send_request(topic, request)
-                    .await
+                let start = Instant::now();
+                let res = nats.send_request(topic, request).await;
+                if res.is_err() {
+                    metrics.record_link_invocation(
+                        &actor_id,
+                        &invocation.target,
+                        &invocation.operation,
+                        start.elapsed(),
+                        false,
+                    );
+                }
+                let res = res
                     .context("failed to call provider")
                     .map_err(|e| e.to_string())?;
 
+                // Responses, which cannot be decoded, are recorded as failures
+                let res = rmp_serde::from_slice::<InvocationResponse>(&res.payload);
+                metrics.record_link_invocation(
+                    &actor_id,
+                    &invocation.target,
+                    &invocation.operation,
+                    start.elapsed(),
+                    matches!(res, Ok(InvocationResponse { error: None, .. })),
+                );
                 let InvocationResponse {
                     invocation_id,
                     mut msg,
                     content_length,
                     error,
+                    chunk_transport,
+                    inline_chunking,
                     ..
-                } = rmp_serde::from_slice(&res.payload)
+                } = res
                     .context("failed to decode invocation response")
                     .map_err(|e| e.to_string())?;
                 if invocation_id != invocation.id {
                     return Err("invocation ID mismatch".into());
                 }
+                chunk_endpoint.record_peer(&invocation.target.public_key, inline_chunking);
 
                 let resp_length = usize::try_from(content_length)
                     .context("content length does not fit in usize")
                     .map_err(|e| e.to_string())?;
                 if resp_length > CHUNK_THRESHOLD_BYTES {
                     msg = chunk_endpoint
-                        .get_unchunkified_response(&invocation_id)
+                        .get_unchunkified_response_via(chunk_transport, &invocation_id)
                         .await
                         .context("failed to dechunk response")
                         .map_err(|e| e.to_string())?;
@@ -1076,6 +1212,98 @@ impl KeyValueReadWrite for Handler {
             .await
             .and_then(decode_provider_response)
     }
+
+    /// Reads all `keys` in a single invocation of the provider, which serves batch operations
+    /// along with `wasi:keyvalue/readwrite`
+    #[instrument(skip(self))]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        if !bucket.is_empty() {
+            bail!("buckets not currently supported")
+        }
+        let target = self
+            .identify_interface_target(&TargetInterface::WasiKeyvalueReadwrite)
+            .await?;
+        let res = self
+            .call_operation(target, "wasmcloud:keyvalue/Batch.GetMany", &keys)
+            .await?;
+        let pairs: Vec<Option<wasmcloud_compat::keyvalue::KeyValuePair>> =
+            decode_provider_response(res)?;
+        ensure!(
+            pairs.len() == keys.len(),
+            "expected {} values, got {}",
+            keys.len(),
+            pairs.len()
+        );
+        keys.into_iter()
+            .zip(pairs)
+            .map(|(key, pair)| {
+                let wasmcloud_compat::keyvalue::KeyValuePair { value, .. } =
+                    pair.with_context(|| format!("key `{key}` not found"))?;
+                let size = value
+                    .len()
+                    .try_into()
+                    .context("value size does not fit in `u64`")?;
+                let value: Box<dyn AsyncRead + Sync + Send + Unpin> = Box::new(Cursor::new(value));
+                Ok((value, size))
+            })
+            .collect()
+    }
+
+    #[instrument(skip(self))]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        if !bucket.is_empty() {
+            bail!("buckets not currently supported")
+        }
+        let target = self
+            .identify_interface_target(&TargetInterface::WasiKeyvalueReadwrite)
+            .await?;
+        self.call_operation(target, "wasmcloud:keyvalue/Batch.GetKeys", &())
+            .await
+            .and_then(decode_provider_response)
+    }
+
+    #[instrument(skip(self, values))]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        values: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        if !bucket.is_empty() {
+            bail!("buckets not currently supported")
+        }
+        let mut pairs = Vec::with_capacity(values.len());
+        for (key, mut value) in values {
+            let mut buf = String::new();
+            value
+                .read_to_string(&mut buf)
+                .await
+                .with_context(|| format!("failed to read value of `{key}`"))?;
+            pairs.push(wasmcloud_compat::keyvalue::KeyValuePair { key, value: buf });
+        }
+        let target = self
+            .identify_interface_target(&TargetInterface::WasiKeyvalueReadwrite)
+            .await?;
+        self.call_operation(target, "wasmcloud:keyvalue/Batch.SetMany", &pairs)
+            .await
+            .and_then(decode_empty_provider_response)
+    }
+
+    #[instrument(skip(self))]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        if !bucket.is_empty() {
+            bail!("buckets not currently supported")
+        }
+        let target = self
+            .identify_interface_target(&TargetInterface::WasiKeyvalueReadwrite)
+            .await?;
+        self.call_operation(target, "wasmcloud:keyvalue/Batch.DeleteMany", &keys)
+            .await
+            .and_then(decode_empty_provider_response)
+    }
 }
 
 #[async_trait]
@@ -1261,6 +1489,28 @@ impl OutgoingHttp for Handler {
     }
 }
 
+/// `wasmcloud:httpserver` request, the body of which may be streamed from the provider
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamingHttpServerRequest {
+    #[serde(flatten)]
+    request: wasmcloud_compat::HttpServerRequest,
+    /// ID of the body stream to pull the request body from, if any
+    #[serde(default)]
+    body_stream: Option<String>,
+}
+
+/// `wasmcloud:httpserver` response, the body of which may be streamed to the provider
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamingHttpResponse {
+    #[serde(flatten)]
+    response: wasmcloud_compat::HttpResponse,
+    /// ID of the body stream the response body is pushed to, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    body_stream: Option<String>,
+}
+
 impl ActorInstance {
     #[instrument(level = "debug", skip(self, msg))]
     async fn handle_invocation(
@@ -1272,11 +1522,12 @@ impl ActorInstance {
         // Validate that the actor has the capability to receive the invocation
         ensure_actor_capability(self.handler.claims.metadata.as_ref(), contract_id)?;
 
-        let mut instance = self
-            .actor
-            .instantiate()
-            .await
-            .context("failed to instantiate actor")?;
+        let mut instance = if let Some(pool) = &self.pool {
+            pool.take().await
+        } else {
+            self.actor.instantiate().await
+        }
+        .context("failed to instantiate actor")?;
         instance
             .stderr(stderr())
             .await
@@ -1287,29 +1538,60 @@ impl ActorInstance {
             .keyvalue_readwrite(Arc::new(self.handler.clone()))
             .logging(Arc::new(self.handler.clone()))
             .messaging(Arc::new(self.handler.clone()))
-            .outgoing_http(Arc::new(self.handler.clone()));
+            .outgoing_http(Arc::new(self.handler.clone()))
+            .state(self.state.clone());
         #[allow(clippy::single_match_else)] // TODO: Remove once more interfaces supported
         match (contract_id, operation) {
             ("wasmcloud:httpserver", "HttpServer.HandleRequest") => {
-                let req: wasmcloud_compat::HttpServerRequest =
-                    rmp_serde::from_slice(&msg).context("failed to decode HTTP request")?;
+                let StreamingHttpServerRequest {
+                    request: req,
+                    body_stream,
+                } = rmp_serde::from_slice(&msg).context("failed to decode HTTP request")?;
                 let req = http::Request::try_from(req).context("failed to convert request")?;
+                let req = req.map(|body| -> Box<dyn AsyncRead + Send + Sync + Unpin> {
+                    if let Some(stream_id) = body_stream.as_ref() {
+                        Box::new(self.body_stream_endpoint.pull(stream_id))
+                    } else {
+                        Box::new(Cursor::new(body))
+                    }
+                });
                 let res = match instance
                     .into_incoming_http()
                     .await
                     .context("failed to instantiate `wasi:http/incoming-handler`")?
-                    .handle(req.map(|body| -> Box<dyn AsyncRead + Send + Sync + Unpin> {
-                        Box::new(Cursor::new(body))
-                    }))
+                    .handle(req)
                     .await
                 {
                     Ok(res) => res,
                     Err(err) => return Ok(Err(format!("{err:#}"))),
                 };
-                let res = wasmcloud_compat::HttpResponse::from_http(res)
+                let res = if let Some(stream_id) = body_stream {
+                    // Reply with the response head right away and push the body to the provider
+                    // as the actor produces it
+                    let (parts, body) = res.into_parts();
+                    let response = wasmcloud_compat::HttpResponse::from_http(
+                        http::Response::from_parts(parts, empty()),
+                    )
                     .await
                     .context("failed to convert response")?;
-                let res = rmp_serde::to_vec_named(&res).context("failed to encode response")?;
+                    let endpoint = self.body_stream_endpoint.clone();
+                    let push_stream_id = stream_id.clone();
+                    spawn(async move {
+                        if let Err(err) = endpoint.push(&push_stream_id, body).await {
+                            error!(?err, "failed to stream HTTP response body");
+                        }
+                    });
+                    rmp_serde::to_vec_named(&StreamingHttpResponse {
+                        response,
+                        body_stream: Some(stream_id),
+                    })
+                } else {
+                    let res = wasmcloud_compat::HttpResponse::from_http(res)
+                        .await
+                        .context("failed to convert response")?;
+                    rmp_serde::to_vec_named(&res)
+                }
+                .context("failed to encode response")?;
                 Ok(Ok(res))
             }
             _ => {
@@ -1330,17 +1612,50 @@ impl ActorInstance {
     }
 
     #[instrument(level = "trace", skip_all)]
-    async fn handle_call(&self, invocation: Invocation) -> anyhow::Result<(Vec<u8>, u64)> {
+    async fn handle_call(
+        &self,
+        invocation: Invocation,
+    ) -> anyhow::Result<(Vec<u8>, u64, ChunkTransport)> {
         trace!(?invocation.origin, ?invocation.target, invocation.operation, "validate actor invocation");
-        invocation.validate_antiforgery(&self.valid_issuers)?;
+        ensure_schema_version(invocation.schema_version)
+            .context("invocation was sent by an incompatible peer")?;
+        let valid = {
+            let valid_issuers = self.valid_issuers.read().await;
+            invocation.validate_antiforgery_with(&valid_issuers, &self.invocation_validity)
+        };
+        if let Err(e) = valid {
+            if let Err(e) = event::publish(
+                &self.event_builder,
+                &self.ctl_nats,
+                &self.event_sinks,
+                &self.handler.lattice_prefix,
+                "invocation_auth_failed",
+                event::invocation_auth_failed(
+                    &invocation.origin,
+                    &invocation.target,
+                    &invocation.operation,
+                    &invocation.id,
+                    &e,
+                ),
+            )
+            .await
+            {
+                warn!(?e, "failed to publish invocation auth failed event");
+            }
+            bail!(audit::Denied(format!("{e:#}")));
+        }
 
         let content_length: usize = invocation
             .content_length
             .try_into()
             .context("failed to convert content_length to usize")?;
+        self.chunk_endpoint
+            .record_peer(&invocation.origin.public_key, invocation.inline_chunking);
         let inv_msg = if content_length > CHUNK_THRESHOLD_BYTES {
             debug!(inv_id = invocation.id, "dechunking invocation");
-            self.chunk_endpoint.get_unchunkified(&invocation.id).await?
+            self.chunk_endpoint
+                .get_unchunkified_via(invocation.chunk_transport, &invocation.id)
+                .await?
         } else {
             invocation.msg
         };
@@ -1348,6 +1663,7 @@ impl ActorInstance {
         trace!(?invocation.origin, ?invocation.target, invocation.operation, "handle actor invocation");
 
         let source_public_key = invocation.origin.public_key;
+        let chunk_transport = self.chunk_endpoint.transport_for(&source_public_key);
         // actors don't have a contract_id
         let source = if invocation.origin.contract_id.is_empty() {
             let actor_claims = self.actor_claims.read().await;
@@ -1392,47 +1708,69 @@ impl ActorInstance {
             .evaluate_action(Some(source), target, PolicyAction::PerformInvocation)
             .await?;
         if !resp.permitted {
-            bail!(
+            bail!(audit::Denied(format!(
                 "Policy denied request to invoke actor `{}`: `{:?}`",
-                resp.request_id,
-                resp.message
-            );
+                resp.request_id, resp.message
+            )));
         };
 
-        let maybe_resp = self
-            .handle_invocation(
-                &invocation.origin.contract_id,
-                &invocation.operation,
-                inv_msg,
-            )
-            .await
-            .context("failed to handle invocation")?;
+        let handle_invocation = self.handle_invocation(
+            &invocation.origin.contract_id,
+            &invocation.operation,
+            inv_msg,
+        );
+        let maybe_resp = if let Some(max_execution_time) = self.limits.max_execution_time {
+            if let Ok(res) = tokio::time::timeout(max_execution_time, handle_invocation).await {
+                res
+            } else {
+                warn!(
+                    inv_id = invocation.id,
+                    operation = invocation.operation,
+                    ?max_execution_time,
+                    "invocation exceeded max execution time, aborted"
+                );
+                Ok(Err(format!(
+                    "invocation exceeded max execution time of {}",
+                    humantime::format_duration(max_execution_time)
+                )))
+            }
+        } else {
+            handle_invocation.await
+        }
+        .context("failed to handle invocation")?;
 
         match maybe_resp {
             Ok(resp_msg) => {
                 let content_length = resp_msg.len();
-                let resp_msg = if content_length > CHUNK_THRESHOLD_BYTES {
+                let (resp_msg, chunk_transport) = if content_length > CHUNK_THRESHOLD_BYTES {
                     debug!(inv_id = invocation.id, "chunking invocation response");
                     self.chunk_endpoint
-                        .chunkify_response(&invocation.id, Cursor::new(resp_msg))
+                        .chunkify_response_via(
+                            chunk_transport,
+                            &invocation.id,
+                            Cursor::new(resp_msg),
+                        )
                         .await
                         .context("failed to chunk invocation response")?;
-                    vec![]
+                    (vec![], chunk_transport)
                 } else {
-                    resp_msg
+                    (resp_msg, ChunkTransport::default())
                 };
                 Ok((
                     resp_msg,
                     content_length
                         .try_into()
                         .context("failed to convert content_length to u64")?,
+                    chunk_transport,
                 ))
             }
             Err(e) => Err(anyhow!(e)),
         }
     }
 
-    #[instrument(level = "info", skip_all)] // NOTE: level needs to stay at info here to attach the incoming span context
+    // NOTE: level needs to stay at info here to attach the incoming span context. The field name
+    // must match `wasmcloud_tracing::SAMPLER_RATIO_ATTRIBUTE`
+    #[instrument(level = "info", skip_all, fields(sampling.ratio = self.sampling_ratio))]
     async fn handle_rpc_message(&self, message: async_nats::Message) {
         let async_nats::Message {
             ref subject,
@@ -1441,32 +1779,50 @@ impl ActorInstance {
             ..
         } = message;
 
+        let invocation = rmp_serde::from_slice::<Invocation>(payload);
+        match &invocation {
+            Ok(invocation) if !invocation.trace_context.is_empty() => {
+                wasmcloud_tracing::context::attach_span_context(&invocation.trace_context);
+            }
+            _ => {
+                // TODO: remove once all providers are built off the new SDK, which passes the trace context in the invocation
+                // fall back on message headers
+                opentelemetry_nats::attach_span_context(&message);
+            }
+        }
+        // Inject the context only after attaching the parent, so that the response continues the
+        // caller's trace
         let injector = TraceContextInjector::default_with_span();
         let headers = injector_to_headers(&injector);
         let trace_context = injector.into();
 
-        let inv_resp = match rmp_serde::from_slice::<Invocation>(payload) {
+        let inv_resp = match invocation {
             Ok(invocation) => {
-                if !invocation.trace_context.is_empty() {
-                    wasmcloud_tracing::context::attach_span_context(&invocation.trace_context);
-                } else if message.headers.is_some() {
-                    // TODO: remove once all providers are built off the new SDK, which passes the trace context in the invocation
-                    // fall back on message headers
-                    opentelemetry_nats::attach_span_context(&message);
-                }
-
                 let invocation_id = invocation.id.clone();
                 let origin = invocation.origin.clone();
                 let target = invocation.target.clone();
                 let operation = invocation.operation.clone();
 
+                let start = Instant::now();
                 let res = self.handle_call(invocation).await;
+                if let Some(audit_log) = &self.audit_log {
+                    audit_log.record(
+                        &invocation_id,
+                        &origin,
+                        &target,
+                        &operation,
+                        audit::ResultClass::of(&res),
+                        start.elapsed(),
+                    );
+                }
                 match res {
-                    Ok((msg, content_length)) => InvocationResponse {
+                    Ok((msg, content_length, chunk_transport)) => InvocationResponse {
                         msg,
                         invocation_id,
                         content_length,
                         trace_context,
+                        chunk_transport,
+                        inline_chunking: self.chunk_endpoint.supports_inline(),
                         ..Default::default()
                     },
                     Err(e) => {
@@ -1482,6 +1838,7 @@ impl ActorInstance {
                             invocation_id,
                             error: Some(e.to_string()),
                             trace_context,
+                            inline_chunking: self.chunk_endpoint.supports_inline(),
                             ..Default::default()
                         }
                     }
@@ -1570,9 +1927,13 @@ pub struct Host {
     // TODO: Clean up actors after stop
     actors: RwLock<HashMap<String, Arc<Actor>>>,
     chunk_endpoint: ChunkEndpoint,
+    body_stream_endpoint: BodyStreamEndpoint,
     cluster_key: Arc<KeyPair>,
-    cluster_issuers: Vec<String>,
+    /// Cluster issuers that invocations are accepted from, which can be updated at runtime to
+    /// rotate the cluster key
+    cluster_issuers: Arc<RwLock<Vec<String>>>,
     event_builder: EventBuilderV10,
+    event_sinks: event_sink::Sinks,
     friendly_name: String,
     heartbeat: AbortHandle,
     host_config: HostConfig,
@@ -1596,10 +1957,22 @@ pub struct Host {
     stop_rx: watch::Receiver<Option<Instant>>,
     queue: AbortHandle,
     aliases: Arc<RwLock<HashMap<String, WasmCloudEntity>>>,
-    links: RwLock<HashMap<String, LinkDefinition>>,
+    links: Arc<RwLock<HashMap<String, LinkDefinition>>>,
     actor_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::Actor>>>>, // TODO: use a single map once Claims is an enum
     provider_claims: Arc<RwLock<HashMap<String, jwt::Claims<jwt::CapabilityProvider>>>>,
     config_data_cache: Arc<RwLock<ConfigCache>>,
+    /// Redacts sensitive link definition values from logs, events and inventory responses
+    redactor: Redactor,
+    /// Issues NATS credentials to providers, if providers should not share the host credentials
+    provider_credentials: Option<CredentialIssuer>,
+    audit_log: Option<Arc<audit::Log>>,
+    signature_verifier: Option<Arc<cosign::Verifier>>,
+    profiler: Option<Arc<profiling::Profiler>>,
+    metrics: Arc<metrics::Metrics>,
+    /// Signature verifications of artifacts fetched from OCI, keyed by image reference
+    signatures: RwLock<HashMap<String, cosign::Verification>>,
+    /// Storage of durable per-actor state
+    state: Arc<state::Backend>,
 }
 
 #[allow(clippy::large_enum_variant)] // Without this clippy complains actor is at least 0 bytes while provider is at least 280 bytes. That doesn't make sense
@@ -1707,23 +2080,30 @@ async fn create_bucket(
     }
 }
 
-/// Given the NATS address, authentication jwt, seed, tls requirement and optional request timeout,
-/// attempt to establish connection.
+/// Given the NATS address, authentication jwt, seed, tls requirement and settings and optional
+/// request timeout, attempt to establish connection.
 ///
+/// If only a key is specified, the connection is authenticated using the nkey alone, proving
+/// possession of it by signing the nonce sent by the server.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - Only one of JWT or seed is specified, as we cannot authenticate with only one of them
+/// - A JWT is specified without a seed, as we cannot authenticate with only a JWT
+/// - The TLS configuration is invalid
 /// - Connection fails
 async fn connect_nats(
     addr: impl async_nats::ToServerAddrs,
     jwt: Option<&String>,
     key: Option<Arc<KeyPair>>,
     require_tls: bool,
+    tls_config: &TlsConfig,
     request_timeout: Option<Duration>,
 ) -> anyhow::Result<async_nats::Client> {
     let opts = async_nats::ConnectOptions::new().require_tls(require_tls);
+    let opts = tls_config
+        .apply(opts)
+        .context("invalid NATS TLS configuration")?;
     let opts = match (jwt, key) {
         (Some(jwt), Some(key)) => opts.jwt(jwt.to_string(), {
             move |nonce| {
@@ -1731,10 +2111,11 @@ async fn connect_nats(
                 async move { key.sign(&nonce).map_err(async_nats::AuthError::new) }
             }
         }),
-        (Some(_), None) | (None, Some(_)) => {
+        (None, Some(key)) => opts.nkey(key.seed().context("failed to get NATS key seed")?),
+        (Some(_), None) => {
             bail!("cannot authenticate if only one of jwt or seed is specified")
         }
-        _ => opts,
+        (None, None) => opts,
     };
     let opts = if let Some(timeout) = request_timeout {
         opts.request_timeout(Some(timeout))
@@ -1803,6 +2184,53 @@ async fn load_supplemental_config(
     }
 }
 
+/// Re-emit log records forwarded by providers on `sub` as events of the host, attributed to the
+/// emitting provider
+async fn receive_provider_logs(mut sub: async_nats::Subscriber) {
+    while let Some(msg) = sub.next().await {
+        let ForwardedLogRecord {
+            provider_id,
+            link_name,
+            instance_id,
+            level,
+            target,
+            message,
+            fields,
+            trace_id,
+            span_id,
+        } = match serde_json::from_slice(&msg.payload) {
+            Ok(record) => record,
+            Err(e) => {
+                warn!(?e, "failed to decode forwarded provider log record");
+                continue;
+            }
+        };
+        let fields = (!fields.is_empty()).then(|| json!(fields).to_string());
+        macro_rules! emit {
+            ($level:expr) => {
+                tracing::event!(
+                    $level,
+                    provider_id,
+                    link_name,
+                    instance_id,
+                    provider_target = target,
+                    trace_id,
+                    span_id,
+                    fields,
+                    "{message}"
+                )
+            };
+        }
+        match level {
+            LogLevel::Error | LogLevel::Critical => emit!(tracing::Level::ERROR),
+            LogLevel::Warn => emit!(tracing::Level::WARN),
+            LogLevel::Info => emit!(tracing::Level::INFO),
+            LogLevel::Debug => emit!(tracing::Level::DEBUG),
+            LogLevel::Trace => emit!(tracing::Level::TRACE),
+        }
+    }
+}
+
 #[instrument(level = "debug", skip_all)]
 async fn merge_registry_config(
     registry_config: &RwLock<HashMap<String, RegistryConfig>>,
@@ -1960,6 +2388,7 @@ impl Host {
                     config.ctl_jwt.as_ref(),
                     config.ctl_key.clone(),
                     config.ctl_tls,
+                    &TlsConfig::default(),
                     None,
                 )
                 .await
@@ -1986,6 +2415,7 @@ impl Host {
                     config.rpc_jwt.as_ref(),
                     config.rpc_key.clone(),
                     config.rpc_tls,
+                    &config.rpc_tls_config,
                     Some(config.rpc_timeout),
                 )
                 .await
@@ -2023,11 +2453,20 @@ impl Host {
         let config_bucket = format!("CONFIGDATA_{}", config.lattice_prefix);
         let config_data = create_bucket(&ctl_jetstream, &config_bucket).await?;
 
+        let state = if let Some(dir) = config.state_dir.as_ref() {
+            state::Backend::local(dir)
+        } else {
+            state::Backend::nats(ctl_jetstream, &config.lattice_prefix)
+        };
+
         let chunk_endpoint = ChunkEndpoint::with_client(
             &config.lattice_prefix,
             rpc_nats.clone(),
             config.js_domain.as_ref(),
-        );
+        )
+        .with_inline_chunking(config.inline_chunking);
+        let body_stream_endpoint =
+            BodyStreamEndpoint::new(&config.lattice_prefix, rpc_nats.clone());
 
         let (queue_abort, queue_abort_reg) = AbortHandle::new_pair();
         let (heartbeat_abort, heartbeat_abort_reg) = AbortHandle::new_pair();
@@ -2052,17 +2491,115 @@ impl Host {
                 cluster_issuers: cluster_issuers.clone(),
             },
             config.policy_service_config.policy_topic.clone(),
+            config.policy_service_config.policy_opa_url.clone(),
             config.policy_service_config.policy_timeout_ms,
             config.policy_service_config.policy_changes_topic.clone(),
         )
         .await?;
 
+        let provider_credentials = if let Some(signing_key) = &config.provider_rpc_signing_key {
+            let rpc_jwt = config
+                .rpc_jwt
+                .as_deref()
+                .context("issuing provider RPC credentials requires JWT authentication")?;
+            Some(
+                CredentialIssuer::new(Arc::clone(signing_key), rpc_jwt)
+                    .context("invalid provider RPC signing key")?,
+            )
+        } else {
+            None
+        };
+
+        let audit_log = if let Some(audit_config) = config.audit_log.clone() {
+            let audit_log = audit::Log::new(audit_config, host_key.public_key(), ctl_nats.clone())
+                .await
+                .context("failed to open audit log")?;
+            Some(Arc::new(audit_log))
+        } else {
+            None
+        };
+
+        let profiler = config
+            .profiling
+            .clone()
+            .map(|config| Arc::new(profiling::Profiler::new(config)));
+        let profiling_server = match (
+            &profiler,
+            config
+                .profiling
+                .as_ref()
+                .and_then(|config| config.listen_address),
+        ) {
+            (Some(profiler), Some(addr)) => {
+                let listener = TcpListener::bind(addr)
+                    .await
+                    .with_context(|| format!("failed to bind profiling endpoint on `{addr}`"))?;
+                info!(%addr, "serving CPU profiling endpoint");
+                Some(spawn(profiling::serve(listener, Arc::clone(profiler))))
+            }
+            _ => None,
+        };
+
+        let signature_verifier = match config.signature_verification.clone() {
+            Some(cosign::Config {
+                policy: cosign::Policy::Ignore,
+                ..
+            })
+            | None => None,
+            Some(verification_config) => {
+                let verifier = cosign::Verifier::new(verification_config)
+                    .await
+                    .context("failed to initialize signature verifier")?;
+                Some(Arc::new(verifier))
+            }
+        };
+
+        let event_sinks = event_sink::Sinks::new(config.event_sinks.clone())
+            .await
+            .context("failed to open event sinks")?;
+
+        let forwarded_logs = if config.forward_provider_logs {
+            let sub = rpc_nats
+                .subscribe(forwarded_logs_subject(
+                    &config.lattice_prefix,
+                    &host_key.public_key(),
+                ))
+                .await
+                .context("failed to subscribe to forwarded provider logs")?;
+            Some(spawn(receive_provider_logs(sub)))
+        } else {
+            None
+        };
+
+        let cluster_issuers = Arc::new(RwLock::new(cluster_issuers));
+        let secrets_backend = if let Some(secrets_config) = &config.secrets_backend {
+            let backend = secrets::FileBackend::load(secrets_config)
+                .await
+                .context("failed to load secrets backend")?;
+            let requests = rpc_nats
+                .subscribe(secrets_subject(
+                    &config.lattice_prefix,
+                    &secrets_config.name,
+                ))
+                .await
+                .context("failed to subscribe to secret requests")?;
+            Some(spawn(backend.serve(
+                rpc_nats.clone(),
+                requests,
+                Arc::clone(&cluster_issuers),
+            )))
+        } else {
+            None
+        };
+
         let host = Host {
             actors: RwLock::default(),
             chunk_endpoint,
+            body_stream_endpoint,
             cluster_key,
             cluster_issuers,
             event_builder,
+            event_sinks,
             friendly_name,
             heartbeat: heartbeat_abort.clone(),
             ctl_topic_prefix: config.ctl_topic_prefix.clone(),
@@ -2070,6 +2607,14 @@ impl Host {
             labels: RwLock::new(labels),
             ctl_nats,
             rpc_nats,
+            redactor: Redactor::default().with_patterns(&config.secret_patterns),
+            provider_credentials,
+            audit_log,
+            signature_verifier,
+            profiler,
+            metrics: Arc::default(),
+            signatures: RwLock::default(),
+            state: Arc::new(state),
             host_config: config,
             data: data.clone(),
             data_watch: data_watch_abort.clone(),
@@ -2084,7 +2629,7 @@ impl Host {
             stop_tx,
             queue: queue_abort.clone(),
             aliases: Arc::default(),
-            links: RwLock::default(),
+            links: Arc::default(),
             actor_claims: Arc::default(),
             provider_claims: Arc::default(),
             config_data_cache: Arc::default(),
@@ -2265,6 +2810,15 @@ impl Host {
         );
 
         Ok((Arc::clone(&host), async move {
+            if let Some(profiling_server) = profiling_server {
+                profiling_server.abort();
+            }
+            if let Some(forwarded_logs) = forwarded_logs {
+                forwarded_logs.abort();
+            }
+            if let Some(secrets_backend) = secrets_backend {
+                secrets_backend.abort();
+            }
             heartbeat_abort.abort();
             queue_abort.abort();
             data_watch_abort.abort();
@@ -2280,6 +2834,9 @@ impl Host {
             )
             .await
             .context("failed to publish stop event")?;
+            if let Some(audit_log) = &host.audit_log {
+                audit_log.flush().await;
+            }
             // Before we exit, make sure to flush all messages or we may lose some that we've
             // thought were sent (like the host_stopped event)
             try_join!(host.ctl_nats.flush(), host.rpc_nats.flush(),)
@@ -2365,6 +2922,7 @@ impl Host {
         event::publish(
             &self.event_builder,
             &self.ctl_nats,
+            &self.event_sinks,
             &self.host_config.lattice_prefix,
             name,
             data,
@@ -2387,12 +2945,24 @@ impl Host {
         trace!(actor_ref = actor_ref.as_ref(), max, "instantiating actor");
 
         let actor_ref = actor_ref.as_ref();
+        let limits = Limits::from_annotations(annotations)?;
+        let mut actor = actor.clone();
+        actor.set_max_memory(limits.max_memory);
+        actor.set_allowed_network(
+            self.host_config
+                .actor_network_allowlist
+                .iter()
+                .filter(|rule| rule.applies_to(&claims.subject))
+                .map(|rule| rule.network.clone())
+                .collect(),
+        );
+        let pool =
+            pool::warm_instances(annotations)?.map(|size| InstancePool::new(actor.clone(), size));
         let topic = format!(
             "wasmbus.rpc.{lattice_prefix}.{subject}",
             lattice_prefix = self.host_config.lattice_prefix,
             subject = claims.subject
         );
-        let actor = actor.clone();
         let handler = handler.clone();
         let instance = async move {
             let calls = self
@@ -2410,10 +2980,23 @@ impl Host {
                 calls: calls_abort,
                 handler: handler.clone(),
                 chunk_endpoint: self.chunk_endpoint.clone(),
+                body_stream_endpoint: self.body_stream_endpoint.clone(),
                 annotations: annotations.clone(),
+                sampling_ratio: annotated_sampler_ratio(annotations),
                 max,
-                valid_issuers: self.cluster_issuers.clone(),
+                limits,
+                pool,
+                state: Arc::new(ActorState::new(
+                    Arc::clone(&self.state),
+                    claims.subject.clone(),
+                )),
+                valid_issuers: Arc::clone(&self.cluster_issuers),
+                invocation_validity: self.host_config.invocation_validity,
+                ctl_nats: self.ctl_nats.clone(),
+                event_builder: self.event_builder.clone(),
+                event_sinks: self.event_sinks.clone(),
                 policy_manager: Arc::clone(&self.policy_manager),
+                audit_log: self.audit_log.clone(),
                 image_reference: actor_ref.to_string(),
                 actor_claims: Arc::clone(&self.actor_claims),
                 provider_claims: Arc::clone(&self.provider_claims),
@@ -2421,7 +3004,7 @@ impl Host {
 
             let _calls = spawn({
                 let instance = Arc::clone(&instance);
-                let limit = max.map(NonZeroUsize::get);
+                let limit = limits.concurrency(max).map(NonZeroUsize::get);
                 Abortable::new(calls, calls_abort_reg).for_each_concurrent(limit, move |msg| {
                     let instance = Arc::clone(&instance);
                     async move { instance.handle_rpc_message(msg).await }
@@ -2499,12 +3082,14 @@ impl Host {
             lattice_prefix: self.host_config.lattice_prefix.clone(),
             origin,
             cluster_key: Arc::clone(&self.cluster_key),
+            origin_claims: forwarded_claims(&claims, &self.host_config.forwarded_actor_claims),
             claims: claims.clone(),
             aliases: Arc::clone(&self.aliases),
             links: Arc::new(RwLock::new(links)),
             targets: Arc::new(RwLock::default()),
             host_key: Arc::clone(&self.host_key),
             chunk_endpoint: self.chunk_endpoint.clone(),
+            metrics: Arc::clone(&self.metrics),
         };
 
         let instance = self
@@ -2547,7 +3132,18 @@ impl Host {
     ) -> anyhow::Result<()> {
         trace!(actor_id = %entry.key(), "stopping actor");
 
-        let actor = entry.remove();
+        self.stop_actor_instances(entry.remove(), annotations, host_id)
+            .await
+    }
+
+    /// Stops the instances of `actor` matching `annotations`. The actor must already be removed
+    /// from the actor map, so that no lock on it is held while instances are stopped
+    async fn stop_actor_instances(
+        &self,
+        actor: Arc<Actor>,
+        annotations: &BTreeMap<String, String>,
+        host_id: &str,
+    ) -> anyhow::Result<()> {
         let claims = actor.claims().context("claims missing")?;
         let mut instances = actor.instances.write().await;
 
@@ -2647,13 +3243,20 @@ impl Host {
     #[instrument(level = "trace", skip_all)]
     async fn fetch_actor(&self, actor_ref: &str) -> anyhow::Result<wasmcloud_runtime::Actor> {
         let registry_config = self.registry_config.read().await;
-        let actor = fetch_actor(
+        let (actor, signature) = fetch_actor(
             actor_ref,
             self.host_config.allow_file_load,
             &registry_config,
+            self.signature_verifier.clone(),
         )
         .await
         .context("failed to fetch actor")?;
+        if let Some(signature) = signature {
+            self.signatures
+                .write()
+                .await
+                .insert(actor_ref.to_string(), signature);
+        }
         let actor = wasmcloud_runtime::Actor::new(&self.runtime, actor)
             .context("failed to initialize actor")?;
         Ok(actor)
@@ -2715,6 +3318,43 @@ impl Host {
         Ok(ACCEPTED.into())
     }
 
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_profile_host(
+        &self,
+        payload: impl AsRef<[u8]>,
+        _host_id: &str,
+    ) -> anyhow::Result<Bytes> {
+        let ProfileHostCommand {
+            duration_ms,
+            format,
+            ..
+        } = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize profile command")?;
+
+        debug!(?duration_ms, ?format, "handling profile host");
+
+        let profiler = self
+            .profiler
+            .as_ref()
+            .context("profiling is not enabled on this host")?;
+        let format = format
+            .as_deref()
+            .map(str::parse)
+            .transpose()?
+            .unwrap_or_default();
+        let profile = profiler
+            .capture(duration_ms.map(Duration::from_millis), format)
+            .await?;
+        let buf = serde_json::to_vec(&ProfileHostResponse {
+            accepted: true,
+            error: String::new(),
+            format: format.as_str().to_string(),
+            profile: STANDARD.encode(profile),
+        })
+        .context("failed to encode reply")?;
+        Ok(buf.into())
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_scale_actor(
         self: Arc<Self>,
@@ -3075,11 +3715,12 @@ impl Host {
         trace!(provider_ref, link_name, "launch provider task");
 
         let registry_config = self.registry_config.read().await;
-        let (path, claims) = crate::fetch_provider(
+        let (path, claims, signature) = crate::fetch_provider(
             provider_ref,
             link_name,
             self.host_config.allow_file_load,
             &registry_config,
+            self.signature_verifier.clone(),
         )
         .await
         .context("failed to fetch provider")?;
@@ -3116,27 +3757,33 @@ impl Host {
                 .cluster_key
                 .seed()
                 .context("cluster key seed missing")?;
-            let links = self.links.read().await;
-            // TODO: update type of links to use wasmcloud_core::LinkDefinition
-            let link_definitions: Vec<_> = links
-                .clone()
-                .into_values()
-                .filter(|ld| ld.provider_id == claims.subject && ld.link_name == link_name)
-                .map(|ld| wasmcloud_core::LinkDefinition {
-                    actor_id: ld.actor_id,
-                    provider_id: ld.provider_id,
-                    link_name: ld.link_name,
-                    contract_id: ld.contract_id,
-                    values: ld.values.into_iter().collect(),
-                })
-                .collect();
-            let lattice_rpc_user_seed = self
-                .host_config
-                .rpc_key
-                .as_ref()
-                .map(|key| key.seed())
-                .transpose()
-                .context("private key missing for provider RPC key")?;
+            let link_definitions =
+                provider_link_definitions(&*self.links.read().await, &claims.subject, link_name);
+            let additional_lattice_prefixes =
+                annotated_additional_lattices(&annotations, &self.host_config.lattice_prefix);
+            let (lattice_rpc_user_jwt, lattice_rpc_user_seed) =
+                if let Some(issuer) = &self.provider_credentials {
+                    let lattices: Vec<&str> = [self.host_config.lattice_prefix.as_str()]
+                        .into_iter()
+                        .chain(additional_lattice_prefixes.iter().map(String::as_str))
+                        .collect();
+                    let Credentials { jwt, seed } = issuer
+                        .issue(&claims.subject, link_name, &lattices)
+                        .context("failed to issue provider RPC credentials")?;
+                    (jwt, seed)
+                } else {
+                    let seed = self
+                        .host_config
+                        .rpc_key
+                        .as_ref()
+                        .map(|key| key.seed())
+                        .transpose()
+                        .context("private key missing for provider RPC key")?;
+                    (
+                        self.host_config.rpc_jwt.clone().unwrap_or_default(),
+                        seed.unwrap_or_default(),
+                    )
+                };
             let default_rpc_timeout_ms = Some(
                 self.host_config
                     .rpc_timeout
@@ -3144,20 +3791,22 @@ impl Host {
                     .try_into()
                     .context("failed to convert rpc_timeout to u64")?,
             );
-            let otel_config = OtelConfig {
-                traces_exporter: self.host_config.otel_config.traces_exporter.clone(),
-                exporter_otlp_endpoint: self.host_config.otel_config.exporter_otlp_endpoint.clone(),
-            };
+            let mut otel_config = self.host_config.otel_config.clone();
+            if let Some(ratio) = annotated_sampler_ratio(&annotations) {
+                otel_config.traces_sampler_ratio = Some(ratio);
+            }
             // TODO: set back to Some(self.host_config.log_level.clone()) once all providers can be
             // assumed to be built using the new SDK. Providers built using wasmbus-rpc <= 0.15
             // ignore RUST_LOG when log_level is set
             let log_level: Option<wasmcloud_core::logging::Level> = None;
             let host_data = HostData {
+                schema_version: SCHEMA_VERSION,
+                min_schema_version: MIN_SCHEMA_VERSION,
                 host_id: self.host_key.public_key(),
                 lattice_rpc_prefix: self.host_config.lattice_prefix.clone(),
                 link_name: link_name.to_string(),
-                lattice_rpc_user_jwt: self.host_config.rpc_jwt.clone().unwrap_or_default(),
-                lattice_rpc_user_seed: lattice_rpc_user_seed.unwrap_or_default(),
+                lattice_rpc_user_jwt,
+                lattice_rpc_user_seed,
                 lattice_rpc_url: self.host_config.rpc_nats_url.to_string(),
                 env_values: vec![],
                 instance_id: Uuid::from_u128(id.into()).to_string(),
@@ -3165,15 +3814,36 @@ impl Host {
                 link_definitions,
                 config_json: configuration,
                 default_rpc_timeout_ms,
-                cluster_issuers: self.cluster_issuers.clone(),
+                cluster_issuers: self.cluster_issuers.read().await.clone(),
                 invocation_seed,
                 log_level,
                 structured_logging: self.host_config.enable_structured_logging,
+                forward_logs: self.host_config.forward_provider_logs,
+                capture_payloads: self.host_config.capture_provider_payloads,
+                metrics_port: annotated_metrics_port(&annotations),
+                inline_chunking: self.host_config.inline_chunking,
+                additional_lattice_prefixes,
                 otel_config,
+                invocation_validity: self.host_config.invocation_validity,
+                secret_patterns: self.host_config.secret_patterns.clone(),
+                lattice_rpc_tls: self.host_config.rpc_tls,
+                lattice_rpc_tls_config: self.host_config.rpc_tls_config.clone(),
             };
             let host_data =
                 serde_json::to_vec(&host_data).context("failed to serialize provider data")?;
 
+            // Subscribe before spawning the provider, so that it can fetch its link definitions
+            // as soon as it starts
+            let mut links_sub = self
+                .rpc_nats
+                .subscribe(provider_links_subject(
+                    &self.host_config.lattice_prefix,
+                    &claims.subject,
+                    link_name,
+                ))
+                .await
+                .context("failed to subscribe to provider link definition requests")?;
+
             trace!("spawn provider process");
 
             let mut child_cmd = process::Command::new(&path);
@@ -3234,6 +3904,9 @@ impl Host {
             let rpc_nats = self.rpc_nats.clone();
             let ctl_nats = self.ctl_nats.clone();
             let event_builder = self.event_builder.clone();
+            let event_sinks = self.event_sinks.clone();
+            let links = Arc::clone(&self.links);
+            let redactor = self.redactor.clone();
             // NOTE: health_ prefix here is to allow us to move the variables into the closure
             let health_lattice_prefix = self.host_config.lattice_prefix.clone();
             let health_provider_id = claims.subject.to_string();
@@ -3266,6 +3939,7 @@ impl Host {
                                             if let Err(e) = event::publish(
                                                 &event_builder,
                                                 &ctl_nats,
+                                                &event_sinks,
                                                 &health_lattice_prefix,
                                                 "health_check_passed",
                                                 event::provider_health_check(
@@ -3283,6 +3957,7 @@ impl Host {
                                             if let Err(e) = event::publish(
                                                 &event_builder,
                                                 &ctl_nats,
+                                                &event_sinks,
                                                 &health_lattice_prefix,
                                                 "health_check_failed",
                                                 event::provider_health_check(
@@ -3299,6 +3974,7 @@ impl Host {
                                             if let Err(e) = event::publish(
                                                 &event_builder,
                                                 &ctl_nats,
+                                                &event_sinks,
                                                 &health_lattice_prefix,
                                                 "health_check_status",
                                                 event::provider_health_check(
@@ -3317,6 +3993,30 @@ impl Host {
                                     warn!("failed to request provider health, retrying in 30 seconds");
                                 }
                         }
+                        Some(msg) = links_sub.next() => {
+                            let Some(reply) = msg.reply else {
+                                continue;
+                            };
+                            trace!(provider_id=health_provider_id, "responding to provider link definitions request");
+                            // Anyone on the lattice may send this request, so sensitive values
+                            // are only shared with the provider as secret references
+                            let link_definitions: Vec<_> = provider_link_definitions(
+                                &*links.read().await,
+                                &health_provider_id,
+                                &health_link_name,
+                            )
+                            .iter()
+                            .map(|ld| redactor.redact_link(ld))
+                            .collect();
+                            match rmp_serde::to_vec_named(&link_definitions) {
+                                Ok(payload) => {
+                                    if let Err(e) = rpc_nats.publish(reply, payload.into()).await {
+                                        warn!(?e, "failed to respond to provider link definitions request");
+                                    }
+                                }
+                                Err(e) => warn!(?e, "failed to encode provider link definitions"),
+                            }
+                        }
                         exit_status = child.wait() => match exit_status {
                             Ok(status) => {
                                 debug!("`{}` exited with `{status:?}`", path.display());
@@ -3340,6 +4040,7 @@ impl Host {
                     host_id,
                     provider_ref,
                     link_name,
+                    signature.as_ref(),
                 ),
             )
             .await?;
@@ -3491,21 +4192,379 @@ impl Host {
     }
 
     #[instrument(level = "debug", skip_all)]
-    async fn handle_inventory(&self) -> anyhow::Result<Bytes> {
-        trace!("handling inventory");
-        let actors = self.actors.read().await;
-        let actors: Vec<_> = stream::iter(actors.iter())
-            .filter_map(|(id, actor)| async move {
-                let instances = actor.instances.read().await;
-                let instances: Vec<_> = instances
-                    .iter()
-                    .map(|(annotations, instance)| {
-                        let instance_id = Uuid::from_u128(instance.id.into()).to_string();
-                        let revision = actor
-                            .claims()
-                            .and_then(|claims| claims.metadata.as_ref())
-                            .and_then(|jwt::Actor { rev, .. }| *rev)
-                            .unwrap_or_default();
+    async fn handle_apply_manifest(
+        self: Arc<Self>,
+        payload: impl AsRef<[u8]>,
+        host_id: &str,
+    ) -> anyhow::Result<Bytes> {
+        let ApplyManifestCommand {
+            manifest, prune, ..
+        } = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize apply manifest command")?;
+
+        info!(
+            actors = manifest.actors.len(),
+            providers = manifest.providers.len(),
+            links = manifest.links.len(),
+            prune,
+            "handling apply manifest"
+        );
+
+        let host_id = host_id.to_string();
+        spawn(async move {
+            let res = self
+                .handle_apply_manifest_task(manifest, prune, &host_id)
+                .await;
+            let (name, data) = match res {
+                Ok(data) => ("manifest_applied", data),
+                Err(err) => {
+                    error!(?err, "failed to apply manifest");
+                    (
+                        "manifest_apply_failed",
+                        event::manifest_apply_failed(&host_id, &err),
+                    )
+                }
+            };
+            if let Err(err) = self.publish_event(name, data).await {
+                error!(?err, name, "failed to publish manifest event");
+            }
+        });
+        Ok(ACCEPTED.into())
+    }
+
+    /// Reconciles the host toward `manifest`, returning the `manifest_applied` event on success.
+    /// All entries of the manifest are attempted, even if some of them fail
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_apply_manifest_task(
+        &self,
+        manifest: Manifest,
+        prune: bool,
+        host_id: &str,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut errors = Vec::new();
+        // Public keys of the actors and providers in the manifest, keyed by image reference
+        let mut ids = HashMap::new();
+        for actor in &manifest.actors {
+            match self.apply_manifest_actor(actor, host_id).await {
+                Ok(Some(id)) => {
+                    ids.insert(actor.actor_ref.clone(), id);
+                }
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+        for provider in &manifest.providers {
+            match self.apply_manifest_provider(provider, host_id).await {
+                Ok(Some(id)) => {
+                    ids.insert(provider.provider_ref.clone(), id);
+                }
+                Ok(None) => {}
+                Err(err) => errors.push(err),
+            }
+        }
+        for link in &manifest.links {
+            if let Err(err) = self.apply_manifest_link(link, &ids).await {
+                errors.push(err);
+            }
+        }
+        let pruned = if prune {
+            self.prune_manifest(&manifest, &ids, host_id, &mut errors)
+                .await
+        } else {
+            Vec::new()
+        };
+
+        match errors.len() {
+            0 => {
+                info!(?pruned, "manifest applied");
+                let Manifest {
+                    actors,
+                    providers,
+                    links,
+                } = manifest;
+                let actors: Vec<_> = actors.into_iter().map(|actor| actor.actor_ref).collect();
+                let providers: Vec<_> = providers
+                    .into_iter()
+                    .map(|provider| provider.provider_ref)
+                    .collect();
+                Ok(event::manifest_applied(
+                    host_id,
+                    &actors,
+                    &providers,
+                    links.len(),
+                    &pruned,
+                ))
+            }
+            1 => Err(errors.remove(0)),
+            n => {
+                let errors = errors
+                    .iter()
+                    .map(|err| format!("{err:#}"))
+                    .collect::<Vec<_>>()
+                    .join("; ");
+                bail!("failed to apply {n} manifest entries: {errors}")
+            }
+        }
+    }
+
+    /// Scales an actor of a manifest, returning its public key if it is running afterwards
+    async fn apply_manifest_actor(
+        &self,
+        ManifestActor {
+            actor_ref,
+            max_concurrent,
+            annotations,
+        }: &ManifestActor,
+        host_id: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let annotations = annotations.clone().into_iter().collect();
+        self.handle_scale_actor_task(actor_ref, host_id, *max_concurrent, annotations)
+            .await
+            .with_context(|| format!("failed to scale actor `{actor_ref}`"))?;
+        for (id, actor) in self.actors.read().await.iter() {
+            if actor
+                .instances
+                .read()
+                .await
+                .values()
+                .any(|instance| instance.image_reference == *actor_ref)
+            {
+                return Ok(Some(id.clone()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Starts a provider of a manifest, unless it is already running, returning its public key
+    async fn apply_manifest_provider(
+        &self,
+        ManifestProvider {
+            provider_ref,
+            link_name,
+            configuration,
+            annotations,
+        }: &ManifestProvider,
+        host_id: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let link_name = link_name.as_deref().unwrap_or("default");
+        let running = self
+            .providers
+            .read()
+            .await
+            .iter()
+            .find(|(_, provider)| {
+                provider.image_ref == *provider_ref && provider.instances.contains_key(link_name)
+            })
+            .map(|(id, _)| id.clone());
+        if running.is_some() {
+            return Ok(running);
+        }
+        self.handle_launch_provider_task(
+            configuration.clone(),
+            link_name,
+            provider_ref,
+            annotations.clone(),
+            host_id,
+        )
+        .await
+        .with_context(|| format!("failed to start provider `{provider_ref}`"))?;
+        Ok(self
+            .providers
+            .read()
+            .await
+            .iter()
+            .find(|(_, provider)| provider.image_ref == *provider_ref)
+            .map(|(id, _)| id.clone()))
+    }
+
+    /// Puts a link of a manifest, unless an identical link already exists. Image references of
+    /// actors and providers in the manifest are resolved to public keys using `ids`
+    async fn apply_manifest_link(
+        &self,
+        ManifestLink {
+            actor,
+            provider,
+            contract_id,
+            link_name,
+            values,
+        }: &ManifestLink,
+        ids: &HashMap<String, String>,
+    ) -> anyhow::Result<()> {
+        let ld = LinkDefinition {
+            actor_id: ids.get(actor).unwrap_or(actor).clone(),
+            provider_id: ids.get(provider).unwrap_or(provider).clone(),
+            link_name: link_name.clone().unwrap_or_else(|| "default".into()),
+            contract_id: contract_id.clone(),
+            values: values.clone(),
+        };
+        let id = linkdef_hash(&ld.actor_id, &ld.contract_id, &ld.link_name);
+        if self.links.read().await.get(&id) == Some(&ld) {
+            return Ok(());
+        }
+        let payload = serde_json::to_vec(&ld).context("failed to encode link definition")?;
+        self.handle_linkdef_put(payload)
+            .await
+            .with_context(|| format!("failed to put link between `{actor}` and `{provider}`"))?;
+        Ok(())
+    }
+
+    /// Deletes all links and stops all actors and providers, which are not part of `manifest`,
+    /// returning the IDs of the deleted links and the public keys of the stopped entities. Image
+    /// references in the manifest links are resolved to public keys using `ids`. Failures are
+    /// appended to `errors`
+    async fn prune_manifest(
+        &self,
+        manifest: &Manifest,
+        ids: &HashMap<String, String>,
+        host_id: &str,
+        errors: &mut Vec<anyhow::Error>,
+    ) -> Vec<String> {
+        let mut pruned = Vec::new();
+
+        let manifest_links: HashSet<_> = manifest
+            .links
+            .iter()
+            .map(|link| {
+                linkdef_hash(
+                    ids.get(&link.actor).unwrap_or(&link.actor),
+                    &link.contract_id,
+                    link.link_name.as_deref().unwrap_or("default"),
+                )
+            })
+            .collect();
+        let stale: Vec<_> = self
+            .links
+            .read()
+            .await
+            .iter()
+            .filter(|(id, _)| !manifest_links.contains(*id))
+            .map(|(id, ld)| {
+                (
+                    id.clone(),
+                    RemoveLinkDefinitionRequest {
+                        actor_id: ld.actor_id.clone(),
+                        contract_id: ld.contract_id.clone(),
+                        link_name: ld.link_name.clone(),
+                    },
+                )
+            })
+            .collect();
+        for (id, req) in stale {
+            let res = async {
+                let payload =
+                    serde_json::to_vec(&req).context("failed to encode link deletion request")?;
+                self.handle_linkdef_del(payload).await
+            }
+            .await;
+            match res {
+                Ok(_) => pruned.push(id),
+                Err(err) => errors.push(err.context(format!(
+                    "failed to delete link of actor `{}` with contract `{}` and link name `{}`",
+                    req.actor_id, req.contract_id, req.link_name
+                ))),
+            }
+        }
+
+        // Stale actors are removed from the map while holding the lock, but stopped after it is
+        // released, so that invocations and other commands are not blocked meanwhile
+        let mut actors = self.actors.write().await;
+        let mut stale = Vec::new();
+        for (id, actor) in actors.iter() {
+            let instances = actor.instances.read().await;
+            if !instances.values().any(|instance| {
+                manifest
+                    .actors
+                    .iter()
+                    .any(|actor| actor.actor_ref == instance.image_reference)
+            }) {
+                stale.push(id.clone());
+            }
+        }
+        let stale: Vec<_> = stale
+            .into_iter()
+            .filter_map(|id| actors.remove(&id).map(|actor| (id, actor)))
+            .collect();
+        drop(actors);
+        for (id, actor) in stale {
+            trace!(actor_id = id, "stopping actor");
+            match self
+                .stop_actor_instances(actor, &Annotations::default(), host_id)
+                .await
+            {
+                Ok(()) => pruned.push(id),
+                Err(err) => errors.push(err.context(format!("failed to stop actor `{id}`"))),
+            }
+        }
+
+        let mut stale = Vec::new();
+        for (id, provider) in self.providers.read().await.iter() {
+            let contract_id = provider
+                .claims
+                .metadata
+                .as_ref()
+                .map(|metadata| metadata.capid.clone())
+                .unwrap_or_default();
+            for link_name in provider.instances.keys() {
+                if !manifest.providers.iter().any(|manifest_provider| {
+                    manifest_provider.provider_ref == provider.image_ref
+                        && manifest_provider.link_name.as_deref().unwrap_or("default")
+                            == link_name.as_str()
+                }) {
+                    stale.push(StopProviderCommand {
+                        contract_id: contract_id.clone(),
+                        host_id: host_id.to_string(),
+                        link_name: link_name.clone(),
+                        provider_ref: id.clone(),
+                        annotations: None,
+                    });
+                }
+            }
+        }
+        for cmd in stale {
+            let res = async {
+                let payload =
+                    serde_json::to_vec(&cmd).context("failed to encode provider stop command")?;
+                self.handle_stop_provider(payload, host_id).await
+            }
+            .await;
+            match res {
+                Ok(_) => pruned.push(cmd.provider_ref),
+                Err(err) => errors.push(err.context(format!(
+                    "failed to stop provider `{}` with link name `{}`",
+                    cmd.provider_ref, cmd.link_name
+                ))),
+            }
+        }
+        pruned
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    fn handle_metrics(&self) -> anyhow::Result<Bytes> {
+        trace!("handling metrics");
+        let buf = serde_json::to_vec(&HostMetrics {
+            host_id: self.host_key.public_key(),
+            links: self.metrics.links(),
+        })
+        .context("failed to encode reply")?;
+        Ok(buf.into())
+    }
+
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_inventory(&self) -> anyhow::Result<Bytes> {
+        trace!("handling inventory");
+        let actors = self.actors.read().await;
+        let actors: Vec<_> = stream::iter(actors.iter())
+            .filter_map(|(id, actor)| async move {
+                let instances = actor.instances.read().await;
+                let instances: Vec<_> = instances
+                    .iter()
+                    .map(|(annotations, instance)| {
+                        let instance_id = Uuid::from_u128(instance.id.into()).to_string();
+                        let revision = actor
+                            .claims()
+                            .and_then(|claims| claims.metadata.as_ref())
+                            .and_then(|jwt::Actor { rev, .. }| *rev)
+                            .unwrap_or_default();
                         let annotations = Some(annotations.clone().into_iter().collect());
                         wasmcloud_control_interface::ActorInstance {
                             annotations,
@@ -3517,6 +4576,7 @@ impl Host {
                                 .max
                                 .and_then(|m| u16::try_from(m.get()).ok())
                                 .unwrap_or(u16::MAX),
+                            limits: instance.limits.inventory(),
                         }
                     })
                     .collect();
@@ -3618,7 +4678,13 @@ impl Host {
         trace!("handling links"); // FIXME: set back to debug when instrumentation is re-enabled
 
         let links = self.links.read().await;
-        let links: Vec<LinkDefinition> = links.values().cloned().collect();
+        let links: Vec<LinkDefinition> = links
+            .values()
+            .map(|ld| LinkDefinition {
+                values: self.redactor.redact_values(&ld.values),
+                ..ld.clone()
+            })
+            .collect();
         let res = serde_json::to_vec(&LinkDefinitionList { links })
             .context("failed to serialize response")?;
         Ok(res.into())
@@ -3698,6 +4764,76 @@ impl Host {
         Ok(ACCEPTED.into())
     }
 
+    /// Trust invocations signed by an additional cluster issuer. To rotate the cluster key without
+    /// restarting the whole lattice at once:
+    ///
+    /// 1. Trust the new key on all hosts and their providers via `issuers.put`
+    /// 2. Restart hosts one by one with the new cluster seed, passing the old key as an issuer
+    /// 3. Retire the old key via `issuers.del`
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_issuers_put(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+        let ClusterIssuerRequest { issuer } = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize put cluster issuer request")?;
+        let cluster_issuers = {
+            let mut cluster_issuers = self.cluster_issuers.write().await;
+            if !trust_cluster_issuer(&mut cluster_issuers, issuer)? {
+                return Ok(ACCEPTED.into());
+            }
+            cluster_issuers.clone()
+        };
+        self.update_cluster_issuers(cluster_issuers).await?;
+        Ok(ACCEPTED.into())
+    }
+
+    /// Stop trusting invocations signed by a cluster issuer. The key this host signs invocations
+    /// with cannot be retired.
+    #[instrument(level = "debug", skip_all)]
+    async fn handle_issuers_del(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
+        let ClusterIssuerRequest { issuer } = serde_json::from_slice(payload.as_ref())
+            .context("failed to deserialize delete cluster issuer request")?;
+        let cluster_issuers = {
+            let mut cluster_issuers = self.cluster_issuers.write().await;
+            if !retire_cluster_issuer(
+                &mut cluster_issuers,
+                &issuer,
+                &self.cluster_key.public_key(),
+            )? {
+                return Ok(ACCEPTED.into());
+            }
+            cluster_issuers.clone()
+        };
+        self.update_cluster_issuers(cluster_issuers).await?;
+        Ok(ACCEPTED.into())
+    }
+
+    /// Send the updated set of cluster issuers to all providers running on this host, signed with
+    /// the host key, so that providers can verify that the update originates from their host
+    #[instrument(level = "debug", skip(self))]
+    async fn update_cluster_issuers(&self, cluster_issuers: Vec<String>) -> anyhow::Result<()> {
+        let update = ClusterIssuersUpdate::new(&self.host_key, cluster_issuers.clone())
+            .context("failed to sign cluster issuers update")?;
+        let req = serde_json::to_vec(&update).context("failed to encode cluster issuers update")?;
+        let lattice_prefix = &self.host_config.lattice_prefix;
+        for (provider_id, Provider { instances, .. }) in self.providers.read().await.iter() {
+            for link_name in instances.keys() {
+                self.rpc_nats
+                    .publish(
+                        format!(
+                            "wasmbus.rpc.{lattice_prefix}.{provider_id}.{link_name}.issuers.put"
+                        ),
+                        req.clone().into(),
+                    )
+                    .await
+                    .context("failed to publish cluster issuers update")?;
+            }
+        }
+        self.publish_event(
+            "cluster_issuers_updated",
+            event::cluster_issuers_updated(self.host_key.public_key(), &cluster_issuers),
+        )
+        .await
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_linkdef_put(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
         let payload = payload.as_ref();
@@ -3715,6 +4851,15 @@ impl Host {
             provider_id, link_name, contract_id, "handling put link definition"
         );
 
+        self.evaluate_link_policy(
+            &actor_id,
+            Some(&provider_id),
+            &contract_id,
+            &link_name,
+            PolicyAction::PutLink,
+        )
+        .await?;
+
         self.data
             .put(format!("LINKDEF_{id}"), Bytes::copy_from_slice(payload))
             .await
@@ -3737,6 +4882,21 @@ impl Host {
             link_name, contract_id, "handling delete link definition"
         );
 
+        let provider_id = self
+            .links
+            .read()
+            .await
+            .get(&id)
+            .map(|ld| ld.provider_id.clone());
+        self.evaluate_link_policy(
+            &actor_id,
+            provider_id.as_deref(),
+            &contract_id,
+            link_name,
+            PolicyAction::DeleteLink,
+        )
+        .await?;
+
         self.data
             .delete(format!("LINKDEF_{id}"))
             .await
@@ -3744,6 +4904,57 @@ impl Host {
         Ok(ACCEPTED.into())
     }
 
+    /// Request a policy decision on changing the link between an actor and a provider, failing if
+    /// the change is denied
+    async fn evaluate_link_policy(
+        &self,
+        actor_id: &str,
+        provider_id: Option<&str>,
+        contract_id: &str,
+        link_name: &str,
+        action: PolicyAction,
+    ) -> anyhow::Result<()> {
+        let source = self
+            .actor_claims
+            .read()
+            .await
+            .get(actor_id)
+            .cloned()
+            .map_or_else(
+                || PolicyRequestSource {
+                    public_key: Some(actor_id.to_string()),
+                    ..Default::default()
+                },
+                PolicyRequestSource::from,
+            );
+        let issuer = if let Some(provider_id) = provider_id {
+            self.provider_claims
+                .read()
+                .await
+                .get(provider_id)
+                .map(|claims| claims.issuer.clone())
+        } else {
+            None
+        };
+        let target = PolicyRequestTarget {
+            public_key: provider_id.map(ToString::to_string),
+            issuer,
+            contract_id: Some(contract_id.to_string()),
+            link_name: Some(link_name.to_string()),
+        };
+        let resp = self
+            .policy_manager
+            .evaluate_action(Some(source), target, action)
+            .await?;
+        ensure!(
+            resp.permitted,
+            "Policy denied request to change link from actor `{actor_id}` on contract `{contract_id}` with link name `{link_name}` `{}`: `{:?}`",
+            resp.request_id,
+            resp.message
+        );
+        Ok(())
+    }
+
     #[instrument(level = "debug", skip_all)]
     async fn handle_registries_put(&self, payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
         let registry_creds: RegistryCredentialMap = serde_json::from_slice(payload.as_ref())
@@ -3839,7 +5050,7 @@ impl Host {
     async fn handle_ping_hosts(&self, _payload: impl AsRef<[u8]>) -> anyhow::Result<Bytes> {
         trace!("replying to ping");
         let uptime = self.start_at.elapsed();
-        let cluster_issuers = self.cluster_issuers.clone().join(",");
+        let cluster_issuers = self.cluster_issuers.read().await.join(",");
 
         let buf = serde_json::to_vec(&json!({
           "id": self.host_key.public_key(),
@@ -3859,11 +5070,11 @@ impl Host {
         Ok(buf.into())
     }
 
-    #[instrument(level = "trace", skip_all, fields(subject = %message.subject))]
+    // NOTE: level needs to stay at info here for the same reason as in `handle_rpc_message`
+    #[instrument(level = "info", skip_all, fields(subject = %message.subject))]
     async fn handle_ctl_message(self: Arc<Self>, message: async_nats::Message) {
-        // NOTE: if log level is not `trace`, this won't have an effect, since the current span is
-        // disabled. In most cases that's fine, since we aren't aware of any control interface
-        // requests including a trace context
+        // The control interface client injects the trace context of the caller into the message
+        // headers, which makes e.g. provider launches and link puts part of the caller's trace
         opentelemetry_nats::attach_span_context(&message);
         // Skip the topic prefix and then the lattice prefix
         // e.g. `wasmbus.ctl.{prefix}`
@@ -3883,6 +5094,10 @@ impl Host {
             (Some("auction"), Some("provider"), None, None) => {
                 self.handle_auction_provider(message.payload).await
             }
+            (Some("cmd"), Some(host_id), Some("apply"), None) => Arc::clone(&self)
+                .handle_apply_manifest(message.payload, host_id)
+                .await
+                .map(Some),
             (Some("cmd"), Some(host_id), Some("lp"), None) => Arc::clone(&self)
                 .handle_launch_provider(message.payload, host_id)
                 .await
@@ -3899,6 +5114,10 @@ impl Host {
                 .handle_stop_provider(message.payload, host_id)
                 .await
                 .map(Some),
+            (Some("cmd"), Some(host_id), Some("profile"), None) => self
+                .handle_profile_host(message.payload, host_id)
+                .await
+                .map(Some),
             (Some("cmd"), Some(host_id), Some("stop"), None) => self
                 .handle_stop_host(message.payload, host_id)
                 .await
@@ -3910,6 +5129,7 @@ impl Host {
             (Some("get"), Some(_host_id), Some("inv"), None) => {
                 self.handle_inventory().await.map(Some)
             }
+            (Some("get"), Some(_host_id), Some("metrics"), None) => self.handle_metrics().map(Some),
             (Some("get"), Some("claims"), None, None) => self.handle_claims().await.map(Some),
             (Some("get"), Some("links"), None, None) => self.handle_links().await.map(Some),
             (Some("get"), Some("config"), Some(entity_id), Some(key)) => {
@@ -3946,6 +5166,12 @@ impl Host {
             (Some("config"), Some("clear"), Some(entity_id), None) => {
                 self.handle_config_clear(entity_id).await.map(Some)
             }
+            (Some("issuers"), Some("put"), None, None) => {
+                self.handle_issuers_put(message.payload).await.map(Some)
+            }
+            (Some("issuers"), Some("del"), None, None) => {
+                self.handle_issuers_del(message.payload).await.map(Some)
+            }
             _ => {
                 warn!("received control interface request on unsupported subject");
                 Ok(Some(
@@ -4058,7 +5284,14 @@ impl Host {
         if publish {
             self.publish_event(
                 "linkdef_set",
-                event::linkdef_set(id, actor_id, provider_id, link_name, contract_id, values),
+                event::linkdef_set(
+                    id,
+                    actor_id,
+                    provider_id,
+                    link_name,
+                    contract_id,
+                    &self.redactor.redact_values(values),
+                ),
             )
             .await?;
         }
@@ -4117,7 +5350,14 @@ impl Host {
         if publish {
             self.publish_event(
                 "linkdef_deleted",
-                event::linkdef_deleted(id, actor_id, provider_id, link_name, contract_id, values),
+                event::linkdef_deleted(
+                    id,
+                    actor_id,
+                    provider_id,
+                    link_name,
+                    contract_id,
+                    &self.redactor.redact_values(values),
+                ),
             )
             .await?;
         }
@@ -4274,6 +5514,8 @@ impl Host {
         host_id: impl AsRef<str>,
         actor_ref: impl AsRef<str>,
     ) -> anyhow::Result<()> {
+        let signatures = self.signatures.read().await;
+        let signature = signatures.get(actor_ref.as_ref());
         let () = stream::iter(0..count)
             .for_each(|_| async {
                 let _ = self
@@ -4284,6 +5526,7 @@ impl Host {
                             annotations,
                             Uuid::from_u128(instance_id.into()),
                             actor_ref.as_ref(),
+                            signature,
                         ),
                     )
                     .await;
@@ -4291,7 +5534,7 @@ impl Host {
             .await;
         self.publish_event(
             "actors_started",
-            event::actors_started(claims, annotations, host_id, count, actor_ref),
+            event::actors_started(claims, annotations, host_id, count, actor_ref, signature),
         )
         .await
     }
@@ -4620,6 +5863,42 @@ fn ensure_actor_capability(
     Ok(())
 }
 
+/// Add `issuer` to `cluster_issuers`, returns whether it was not trusted already
+fn trust_cluster_issuer(cluster_issuers: &mut Vec<String>, issuer: String) -> anyhow::Result<bool> {
+    let key = KeyPair::from_public_key(&issuer).context("invalid cluster issuer key")?;
+    ensure!(
+        key.key_pair_type() == KeyPairType::Cluster,
+        "`{issuer}` is not a cluster public key"
+    );
+    if cluster_issuers.contains(&issuer) {
+        debug!(issuer, "cluster issuer already trusted");
+        return Ok(false);
+    }
+    info!(issuer, "trusting cluster issuer");
+    cluster_issuers.push(issuer);
+    Ok(true)
+}
+
+/// Remove `issuer` from `cluster_issuers`, returns whether it was trusted. `signing_key`, which
+/// the host signs invocations with, cannot be retired
+fn retire_cluster_issuer(
+    cluster_issuers: &mut Vec<String>,
+    issuer: &str,
+    signing_key: &str,
+) -> anyhow::Result<bool> {
+    ensure!(
+        issuer != signing_key,
+        "cannot retire `{issuer}`, which this host signs invocations with"
+    );
+    let Some(idx) = cluster_issuers.iter().position(|key| key == issuer) else {
+        warn!(issuer, "could not retire untrusted cluster issuer");
+        return Ok(false);
+    };
+    info!(issuer, "retiring cluster issuer");
+    cluster_issuers.remove(idx);
+    Ok(true)
+}
+
 fn injector_to_headers(injector: &TraceContextInjector) -> async_nats::header::HeaderMap {
     injector
         .iter()
@@ -4644,16 +5923,32 @@ fn annotations_match_filter(annotations: &Annotations, filter: &Annotations) ->
     })
 }
 
+/// Select the custom claims of an actor named in `names`
+fn forwarded_claims(
+    claims: &jwt::Claims<jwt::Actor>,
+    names: &[String],
+) -> Option<HashMap<String, String>> {
+    let custom = claims.metadata.as_ref()?.custom.as_ref()?;
+    let forwarded: HashMap<_, _> = custom
+        .iter()
+        .filter(|(name, _)| names.contains(name))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    (!forwarded.is_empty()).then_some(forwarded)
+}
+
 #[cfg(test)]
 mod test {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
     use nkeys::KeyPair;
     use ulid::Ulid;
     use uuid::Uuid;
     use wascap::jwt;
-    use wasmcloud_core::{invocation_hash, WasmCloudEntity};
+    use wasmcloud_core::{invocation_hash, InvocationValidity, WasmCloudEntity};
     use wasmcloud_tracing::context::TraceContextInjector;
 
-    use super::Invocation;
+    use super::{retire_cluster_issuer, trust_cluster_issuer, Invocation};
 
     const CLUSTER_PUBKEY: &str = "CAQQHYABXBPDBZIGDZIT7E73HW66RPCFC3GGLQKSDDTVWUVOYZBYHUND";
     const CLUSTER_SEED: &str = "SCAIYCZTW775GJYX3MVWLURALVC3PULW43PTEKGH72JBMA3A7LOLGLQ2JA";
@@ -4795,6 +6090,118 @@ mod test {
                 .contains("invocation claims and invocation target URL do not match")));
     }
 
+    #[test]
+    fn validate_antiforgery_applies_invocation_validity() {
+        let clusterkey = KeyPair::from_seed(CLUSTER_SEED).expect("failed to create cluster key");
+        let hostkey = KeyPair::from_seed(HOSTKEY_SEED).expect("failed to create host key");
+        let origin = actor_entity(ACTOR_PUBKEY);
+        let target = provider_entity(PROVIDER_PUBKEY, "default", "wasmcloud:testoperation");
+        let operation = "wasmcloud:bus/TestOperation.HandleTest";
+        let msg = vec![0xF0, 0x9F, 0x8C, 0xAE];
+
+        let target_operation_url = format!("{}/TestOperation.HandleTest", target.url());
+        let valid_issuers = vec![CLUSTER_PUBKEY.to_string()];
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time is before UNIX epoch")
+            .as_secs();
+        let skewed = InvocationValidity {
+            clock_skew: Duration::from_secs(60),
+            max_age: None,
+        };
+
+        let basic_invocation: Invocation = Invocation::new(
+            &clusterkey,
+            &hostkey,
+            origin.clone(),
+            target.clone(),
+            operation.to_string(),
+            msg.clone(),
+            TraceContextInjector::default_with_span().into(),
+        )
+        .expect("failed to create invocation");
+        let invocation_with_dates = |nbf, exp| {
+            let claims = jwt::Claims::<jwt::Invocation>::with_dates(
+                CLUSTER_PUBKEY.to_string(),
+                Uuid::from_u128(Ulid::new().into()).to_string(),
+                nbf,
+                exp,
+                &target_operation_url,
+                &origin.url(),
+                &invocation_hash(&target_operation_url, origin.url(), operation, msg.clone()),
+            );
+            Invocation {
+                encoded_claims: claims.encode(&clusterkey).expect("failed to encode claims"),
+                ..basic_invocation.clone()
+            }
+        };
+
+        // Claims that expired recently are only accepted within the tolerated skew
+        let expired_invocation = invocation_with_dates(None, Some(now - 30));
+        assert!(expired_invocation
+            .validate_antiforgery(&valid_issuers)
+            .is_err_and(|e| e.to_string().contains("invocation claims token expired")));
+        assert!(expired_invocation
+            .validate_antiforgery_with(&valid_issuers, &skewed)
+            .is_ok());
+        let expired_invocation = invocation_with_dates(None, Some(now - 120));
+        assert!(expired_invocation
+            .validate_antiforgery_with(&valid_issuers, &skewed)
+            .is_err_and(|e| e.to_string().contains("invocation claims token expired")));
+
+        // Claims that become valid soon are only accepted within the tolerated skew
+        let nbf_invocation = invocation_with_dates(Some(now + 30), None);
+        assert!(nbf_invocation
+            .validate_antiforgery(&valid_issuers)
+            .is_err_and(|e| e
+                .to_string()
+                .contains("attempt to use invocation before claims token allows")));
+        assert!(nbf_invocation
+            .validate_antiforgery_with(&valid_issuers, &skewed)
+            .is_ok());
+        let nbf_invocation = invocation_with_dates(Some(now + 120), None);
+        assert!(nbf_invocation
+            .validate_antiforgery_with(&valid_issuers, &skewed)
+            .is_err_and(|e| e
+                .to_string()
+                .contains("attempt to use invocation before claims token allows")));
+
+        // Claims without an expiration are rejected once older than the maximum age
+        let mut old_claims = jwt::Claims::<jwt::Invocation>::new(
+            CLUSTER_PUBKEY.to_string(),
+            Uuid::from_u128(Ulid::new().into()).to_string(),
+            &target_operation_url,
+            &origin.url(),
+            &invocation_hash(&target_operation_url, origin.url(), operation, msg.clone()),
+        );
+        old_claims.issued_at = now - 600;
+        let old_invocation = Invocation {
+            encoded_claims: old_claims
+                .encode(&clusterkey)
+                .expect("failed to encode claims"),
+            ..basic_invocation.clone()
+        };
+        assert!(old_invocation.validate_antiforgery(&valid_issuers).is_ok());
+        assert!(old_invocation
+            .validate_antiforgery_with(
+                &valid_issuers,
+                &InvocationValidity {
+                    max_age: Some(Duration::from_secs(300)),
+                    ..skewed
+                }
+            )
+            .is_err_and(|e| e.to_string().contains("invocation claims token expired")));
+        assert!(basic_invocation
+            .validate_antiforgery_with(
+                &valid_issuers,
+                &InvocationValidity {
+                    max_age: Some(Duration::from_secs(300)),
+                    ..skewed
+                }
+            )
+            .is_ok());
+    }
+
     /// Helper test function for oneline creation of an actor [`WasmCloudEntity`]. Consider adding to the
     /// actual impl block if it's useful elsewhere.
     fn actor_entity(public_key: &str) -> WasmCloudEntity {
@@ -4813,4 +6220,64 @@ mod test {
             contract_id: contract_id.to_string(),
         }
     }
+
+    #[test]
+    fn cluster_issuers_are_trusted() {
+        let mut cluster_issuers = vec![CLUSTER_PUBKEY.to_string()];
+        assert!(
+            trust_cluster_issuer(&mut cluster_issuers, OUTSIDE_CLUSTER_PUBKEY.to_string())
+                .expect("failed to trust cluster issuer")
+        );
+        assert_eq!(cluster_issuers, [CLUSTER_PUBKEY, OUTSIDE_CLUSTER_PUBKEY]);
+
+        // trusting an issuer again has no effect
+        assert!(
+            !trust_cluster_issuer(&mut cluster_issuers, OUTSIDE_CLUSTER_PUBKEY.to_string())
+                .expect("failed to trust cluster issuer")
+        );
+        assert_eq!(cluster_issuers, [CLUSTER_PUBKEY, OUTSIDE_CLUSTER_PUBKEY]);
+
+        // only cluster keys can be trusted
+        for key in [
+            HOSTKEY_PUBKEY,
+            ACTOR_PUBKEY,
+            PROVIDER_PUBKEY,
+            "",
+            "not a key",
+        ] {
+            assert!(trust_cluster_issuer(&mut cluster_issuers, key.to_string()).is_err());
+        }
+        assert_eq!(cluster_issuers, [CLUSTER_PUBKEY, OUTSIDE_CLUSTER_PUBKEY]);
+    }
+
+    #[test]
+    fn cluster_issuers_are_retired() {
+        let mut cluster_issuers = vec![
+            CLUSTER_PUBKEY.to_string(),
+            OUTSIDE_CLUSTER_PUBKEY.to_string(),
+        ];
+
+        // the key the host signs invocations with cannot be retired
+        assert!(
+            retire_cluster_issuer(&mut cluster_issuers, CLUSTER_PUBKEY, CLUSTER_PUBKEY).is_err()
+        );
+        assert_eq!(cluster_issuers, [CLUSTER_PUBKEY, OUTSIDE_CLUSTER_PUBKEY]);
+
+        assert!(retire_cluster_issuer(
+            &mut cluster_issuers,
+            OUTSIDE_CLUSTER_PUBKEY,
+            CLUSTER_PUBKEY
+        )
+        .expect("failed to retire cluster issuer"));
+        assert_eq!(cluster_issuers, [CLUSTER_PUBKEY]);
+
+        // retiring an untrusted issuer has no effect
+        assert!(!retire_cluster_issuer(
+            &mut cluster_issuers,
+            OUTSIDE_CLUSTER_PUBKEY,
+            CLUSTER_PUBKEY
+        )
+        .expect("failed to retire cluster issuer"));
+        assert_eq!(cluster_issuers, [CLUSTER_PUBKEY]);
+    }
 }
@@ -0,0 +1,173 @@
+use core::num::NonZeroUsize;
+use core::time::Duration;
+
+use std::sync::Mutex;
+
+use anyhow::Context as _;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::sleep;
+use tracing::{error, trace, warn};
+use wasmcloud_core::WARM_INSTANCES_ANNOTATION;
+
+use super::Annotations;
+
+/// Amount of time to wait before retrying to fill the pool after instantiation failed
+const REFILL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Parse the size of the warm instance pool from `annotations`, `None` if no pool is requested
+pub(crate) fn warm_instances(annotations: &Annotations) -> anyhow::Result<Option<NonZeroUsize>> {
+    annotations
+        .get(WARM_INSTANCES_ANNOTATION)
+        .map(|value| {
+            value.trim().parse::<usize>().with_context(|| {
+                format!("invalid `{WARM_INSTANCES_ANNOTATION}` annotation `{value}`")
+            })
+        })
+        .transpose()
+        .map(|size| size.and_then(NonZeroUsize::new))
+}
+
+/// Instantiate `actor` and [prepare](wasmcloud_runtime::ActorInstance::prepare) the instance
+async fn instantiate_warm(
+    actor: &wasmcloud_runtime::Actor,
+) -> anyhow::Result<wasmcloud_runtime::ActorInstance> {
+    let mut instance = actor.instantiate().await?;
+    instance.prepare().await?;
+    Ok(instance)
+}
+
+/// Pool of pre-instantiated actor instances, which is refilled in the background as instances
+/// are taken from it, so that invocations do not have to wait for instantiation
+#[derive(Debug)]
+pub(crate) struct InstancePool {
+    actor: wasmcloud_runtime::Actor,
+    instances: Mutex<mpsc::Receiver<wasmcloud_runtime::ActorInstance>>,
+    refill: JoinHandle<()>,
+}
+
+impl InstancePool {
+    /// Start filling a pool of `size` instances of `actor`
+    pub(crate) fn new(actor: wasmcloud_runtime::Actor, size: NonZeroUsize) -> Self {
+        let (tx, rx) = mpsc::channel(size.get());
+        let refill = tokio::spawn({
+            let actor = actor.clone();
+            async move {
+                loop {
+                    match instantiate_warm(&actor).await {
+                        Ok(instance) => {
+                            // This only returns once there is room in the pool
+                            if tx.send(instance).await.is_err() {
+                                return;
+                            }
+                            trace!("added warm actor instance to pool");
+                        }
+                        Err(err) => {
+                            error!(?err, "failed to instantiate warm actor instance");
+                            sleep(REFILL_BACKOFF).await;
+                        }
+                    }
+                }
+            }
+        });
+        Self {
+            actor,
+            instances: Mutex::new(rx),
+            refill,
+        }
+    }
+
+    /// Take a warm instance from the pool, or instantiate one if the pool is drained
+    pub(crate) async fn take(&self) -> anyhow::Result<wasmcloud_runtime::ActorInstance> {
+        let instance = match self.instances.lock() {
+            Ok(mut instances) => instances.try_recv().ok(),
+            Err(_) => {
+                warn!("instance pool lock poisoned, instantiating actor");
+                None
+            }
+        };
+        if let Some(instance) = instance {
+            return Ok(instance);
+        }
+        trace!("instance pool drained, instantiating actor");
+        self.actor.instantiate().await
+    }
+}
+
+impl Drop for InstancePool {
+    fn drop(&mut self) {
+        self.refill.abort();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use std::io::Cursor;
+
+    use tokio::io::{AsyncReadExt as _, AsyncSeekExt as _};
+    use tokio::time::timeout;
+    use wasmcloud_runtime::io::AsyncVec;
+
+    use crate::wasmbus::test_util::annotations;
+
+    #[test]
+    fn parse_warm_instances() {
+        assert_eq!(
+            warm_instances(&annotations(&[(WARM_INSTANCES_ANNOTATION, " 4 ")])).unwrap(),
+            NonZeroUsize::new(4)
+        );
+        assert_eq!(
+            warm_instances(&annotations(&[(WARM_INSTANCES_ANNOTATION, "0")])).unwrap(),
+            None
+        );
+        assert_eq!(warm_instances(&annotations(&[])).unwrap(), None);
+        assert!(warm_instances(&annotations(&[(WARM_INSTANCES_ANNOTATION, "many")])).is_err());
+    }
+
+    #[tokio::test]
+    async fn pooled_instances_are_prepared() {
+        let rt = wasmcloud_runtime::Runtime::new().expect("failed to construct runtime");
+        let wasm = std::fs::read(test_actors::RUST_FOOBAR_COMPONENT_COMMAND_PREVIEW2_SIGNED)
+            .expect("failed to read actor");
+        let actor = wasmcloud_runtime::Actor::new(&rt, wasm).expect("failed to compile actor");
+        let pool = InstancePool::new(actor.clone(), NonZeroUsize::MIN);
+
+        // An instance instantiated on demand pays the instantiation cost on call
+        let instance = actor.instantiate().await.expect("failed to instantiate");
+        assert!(!instance.is_prepared());
+
+        // Instances taken from the pool are instantiated ahead of time
+        let mut instance = timeout(Duration::from_secs(30), async {
+            loop {
+                let instance = pool.take().await.expect("failed to take instance");
+                if instance.is_prepared() {
+                    return instance;
+                }
+                sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("pool was not filled");
+
+        let request = rmp_serde::to_vec("foo").expect("failed to encode request");
+        let mut response = AsyncVec::default();
+        instance
+            .call("foobar.foobar", Cursor::new(request), response.clone())
+            .await
+            .expect("failed to call actor")
+            .expect("actor failed");
+        // The call used the prepared instance
+        assert!(!instance.is_prepared());
+
+        response.rewind().await.expect("failed to rewind response");
+        let mut buf = vec![];
+        response
+            .read_to_end(&mut buf)
+            .await
+            .expect("failed to read response");
+        let response: String = rmp_serde::from_slice(&buf).expect("failed to decode response");
+        assert_eq!(response, "foobar");
+    }
+}
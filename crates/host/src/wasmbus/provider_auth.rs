@@ -0,0 +1,244 @@
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{ensure, Context as _};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use nkeys::{KeyPair, KeyPairType};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ulid::Ulid;
+
+/// Issues NATS credentials to capability providers, so that providers do not authenticate to the
+/// RPC NATS server using the credentials of the host.
+///
+/// Each provider instance receives a freshly generated user nkey and a user JWT for it, signed by
+/// a signing key of the account the host itself belongs to. The JWT only permits the provider to
+/// use the subjects of the lattices it serves, and can be revoked by the account without
+/// affecting the host or other providers.
+#[derive(Debug)]
+pub(crate) struct CredentialIssuer {
+    signing_key: Arc<KeyPair>,
+    account: String,
+}
+
+/// Credentials issued to a provider instance
+#[derive(Debug)]
+pub(crate) struct Credentials {
+    /// User JWT, to be passed as [`wasmcloud_core::HostData::lattice_rpc_user_jwt`]
+    pub jwt: String,
+    /// Seed of the user nkey, to be passed as [`wasmcloud_core::HostData::lattice_rpc_user_seed`]
+    pub seed: String,
+}
+
+#[derive(Deserialize)]
+struct HostUserClaims {
+    iss: String,
+    #[serde(default)]
+    nats: HostUserPermissions,
+}
+
+#[derive(Default, Deserialize)]
+struct HostUserPermissions {
+    #[serde(default)]
+    issuer_account: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Header {
+    typ: &'static str,
+    alg: &'static str,
+}
+
+impl CredentialIssuer {
+    /// Construct an issuer signing provider JWTs with `signing_key`, which must be a key of the
+    /// account `host_jwt`, the user JWT the host authenticates to the RPC NATS server with, was
+    /// issued by
+    pub(crate) fn new(signing_key: Arc<KeyPair>, host_jwt: &str) -> anyhow::Result<Self> {
+        ensure!(
+            signing_key.key_pair_type() == KeyPairType::Account,
+            "provider credentials must be signed by an account key"
+        );
+        let claims = host_jwt.split('.').nth(1).context("RPC JWT is malformed")?;
+        let claims = URL_SAFE_NO_PAD
+            .decode(claims.trim_end_matches('='))
+            .context("failed to decode RPC JWT claims")?;
+        let HostUserClaims { iss, nats } =
+            serde_json::from_slice(&claims).context("failed to parse RPC JWT claims")?;
+        Ok(Self {
+            signing_key,
+            // `issuer_account` is only set if the host JWT was signed by a signing key
+            account: nats.issuer_account.unwrap_or(iss),
+        })
+    }
+
+    /// Issue credentials to the instance of provider `provider_key` with link name `link_name`,
+    /// serving `lattices`
+    pub(crate) fn issue(
+        &self,
+        provider_key: &str,
+        link_name: &str,
+        lattices: &[&str],
+    ) -> anyhow::Result<Credentials> {
+        let user = KeyPair::new(KeyPairType::User);
+        let iat = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("system time is before the UNIX epoch")?
+            .as_secs();
+        let mut subjects: Vec<String> = lattices
+            .iter()
+            .map(|lattice| format!("wasmbus.*.{lattice}.>"))
+            .collect();
+        // Chunked payloads are stored in a JetStream object store named after the lattice
+        subjects.extend(lattices.iter().map(|lattice| format!("$O.{lattice}.>")));
+        subjects.push("_INBOX.>".to_string());
+        let mut publish = subjects.clone();
+        publish.push("$JS.API.>".to_string());
+
+        let signing_key = self.signing_key.public_key();
+        let mut nats = json!({
+            "pub": { "allow": publish },
+            "sub": { "allow": subjects },
+            "subs": -1,
+            "data": -1,
+            "payload": -1,
+            "type": "user",
+            "version": 2,
+        });
+        if signing_key != self.account {
+            nats["issuer_account"] = self.account.clone().into();
+        }
+        let claims = json!({
+            "jti": Ulid::new().to_string(),
+            "iat": iat,
+            "iss": signing_key,
+            "name": format!("{provider_key}/{link_name}"),
+            "sub": user.public_key(),
+            "nats": nats,
+        });
+
+        let header = serde_json::to_vec(&Header {
+            typ: "JWT",
+            alg: "ed25519-nkey",
+        })
+        .context("failed to encode JWT header")?;
+        let claims = serde_json::to_vec(&claims).context("failed to encode JWT claims")?;
+        let message = format!(
+            "{}.{}",
+            URL_SAFE_NO_PAD.encode(header),
+            URL_SAFE_NO_PAD.encode(claims)
+        );
+        let signature = self
+            .signing_key
+            .sign(message.as_bytes())
+            .context("failed to sign provider JWT")?;
+        Ok(Credentials {
+            jwt: format!("{message}.{}", URL_SAFE_NO_PAD.encode(signature)),
+            seed: user.seed().context("failed to get provider nkey seed")?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(jwt: &str) -> (serde_json::Value, serde_json::Value) {
+        let mut parts = jwt.split('.');
+        let mut part = || {
+            let part = URL_SAFE_NO_PAD
+                .decode(parts.next().expect("JWT part missing"))
+                .expect("failed to decode JWT part");
+            serde_json::from_slice(&part).expect("failed to parse JWT part")
+        };
+        (part(), part())
+    }
+
+    fn host_jwt(issuer: &KeyPair, issuer_account: Option<&str>) -> String {
+        let mut claims = json!({ "iss": issuer.public_key(), "nats": { "type": "user" } });
+        if let Some(account) = issuer_account {
+            claims["nats"]["issuer_account"] = account.into();
+        }
+        format!(
+            "e30.{}.c2ln",
+            URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims).unwrap())
+        )
+    }
+
+    #[test]
+    fn issues_scoped_provider_credentials() -> anyhow::Result<()> {
+        let account = Arc::new(KeyPair::new(KeyPairType::Account));
+        let issuer = CredentialIssuer::new(Arc::clone(&account), &host_jwt(&account, None))?;
+        let Credentials { jwt, seed } = issuer.issue("VPROVIDER", "default", &["default"])?;
+
+        let user = KeyPair::from_seed(&seed)?;
+        assert_eq!(user.key_pair_type(), KeyPairType::User);
+
+        let (header, claims) = decode(&jwt);
+        assert_eq!(header["alg"], "ed25519-nkey");
+        assert_eq!(claims["iss"], account.public_key());
+        assert_eq!(claims["sub"], user.public_key());
+        assert_eq!(claims["name"], "VPROVIDER/default");
+        assert_eq!(claims["nats"]["type"], "user");
+        assert!(claims["nats"].get("issuer_account").is_none());
+        assert_eq!(
+            claims["nats"]["sub"]["allow"],
+            json!(["wasmbus.*.default.>", "$O.default.>", "_INBOX.>"])
+        );
+        assert_eq!(
+            claims["nats"]["pub"]["allow"],
+            json!([
+                "wasmbus.*.default.>",
+                "$O.default.>",
+                "_INBOX.>",
+                "$JS.API.>"
+            ])
+        );
+
+        let (message, signature) = jwt.rsplit_once('.').unwrap();
+        let signature = URL_SAFE_NO_PAD.decode(signature)?;
+        KeyPair::from_public_key(&account.public_key())?.verify(message.as_bytes(), &signature)?;
+
+        // Every instance receives its own key
+        let Credentials { seed: other, .. } = issuer.issue("VPROVIDER", "other", &["default"])?;
+        assert_ne!(seed, other);
+        Ok(())
+    }
+
+    #[test]
+    fn signing_key_of_host_account() -> anyhow::Result<()> {
+        let account = KeyPair::new(KeyPairType::Account);
+        let signing_key = Arc::new(KeyPair::new(KeyPairType::Account));
+        let issuer = CredentialIssuer::new(
+            Arc::clone(&signing_key),
+            &host_jwt(&signing_key, Some(&account.public_key())),
+        )?;
+        let Credentials { jwt, .. } =
+            issuer.issue("VPROVIDER", "default", &["default", "other"])?;
+        let (_, claims) = decode(&jwt);
+        assert_eq!(claims["iss"], signing_key.public_key());
+        assert_eq!(claims["nats"]["issuer_account"], account.public_key());
+        assert_eq!(
+            claims["nats"]["sub"]["allow"],
+            json!([
+                "wasmbus.*.default.>",
+                "wasmbus.*.other.>",
+                "$O.default.>",
+                "$O.other.>",
+                "_INBOX.>"
+            ])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_invalid_keys() {
+        let user = Arc::new(KeyPair::new(KeyPairType::User));
+        let account = KeyPair::new(KeyPairType::Account);
+        assert!(CredentialIssuer::new(user, &host_jwt(&account, None)).is_err());
+        assert!(
+            CredentialIssuer::new(Arc::new(KeyPair::new(KeyPairType::Account)), "not a jwt")
+                .is_err()
+        );
+    }
+}
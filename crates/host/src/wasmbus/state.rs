@@ -0,0 +1,335 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{bail, ensure, Context as _};
+use async_nats::jetstream::kv::{Operation, Store};
+use async_trait::async_trait;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use tokio::fs;
+use tokio::sync::{Mutex, OnceCell};
+use tracing::{debug, instrument};
+use wasmcloud_runtime::capability::State;
+
+use super::create_bucket;
+
+/// Maximum number of attempts to atomically update a counter in a JetStream bucket
+const MAX_INCREMENT_ATTEMPTS: usize = 10;
+
+/// Storage of the per-actor state, shared by all actors on the host
+#[derive(Debug)]
+pub(crate) enum Backend {
+    /// JetStream key-value buckets, one per actor, shared by all hosts in the lattice
+    Nats {
+        jetstream: async_nats::jetstream::Context,
+        lattice_prefix: String,
+    },
+    /// Directory on the local filesystem with a subdirectory per actor
+    Local {
+        dir: PathBuf,
+        /// Serializes writes, so that increments are atomic
+        lock: Mutex<()>,
+    },
+}
+
+impl Backend {
+    /// Store state in JetStream key-value buckets
+    pub(crate) fn nats(jetstream: async_nats::jetstream::Context, lattice_prefix: &str) -> Self {
+        Self::Nats {
+            jetstream,
+            lattice_prefix: lattice_prefix.to_string(),
+        }
+    }
+
+    /// Store state in the local directory `dir`
+    pub(crate) fn local(dir: impl Into<PathBuf>) -> Self {
+        Self::Local {
+            dir: dir.into(),
+            lock: Mutex::default(),
+        }
+    }
+}
+
+/// Encode a key, such that it is valid both as a JetStream key and as a file name
+fn encode_key(key: &str) -> anyhow::Result<String> {
+    ensure!(!key.is_empty(), "key must not be empty");
+    Ok(URL_SAFE_NO_PAD.encode(key))
+}
+
+fn decode_key(key: &str) -> anyhow::Result<String> {
+    let key = URL_SAFE_NO_PAD
+        .decode(key)
+        .context("failed to decode key")?;
+    String::from_utf8(key).context("key is not valid UTF-8")
+}
+
+fn parse_counter(value: &[u8]) -> anyhow::Result<i64> {
+    std::str::from_utf8(value)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .context("value is not a counter")
+}
+
+/// State of a single actor, implementing `wasmcloud:state/store`
+#[derive(Debug)]
+pub(crate) struct ActorState {
+    backend: Arc<Backend>,
+    /// Public key of the actor, which the state is scoped to
+    actor: String,
+    /// Lazily created JetStream bucket of the actor
+    bucket: OnceCell<Store>,
+}
+
+impl ActorState {
+    /// Returns the state of `actor` stored in `backend`
+    pub(crate) fn new(backend: Arc<Backend>, actor: impl Into<String>) -> Self {
+        Self {
+            backend,
+            actor: actor.into(),
+            bucket: OnceCell::new(),
+        }
+    }
+
+    async fn bucket(
+        &self,
+        jetstream: &async_nats::jetstream::Context,
+        lattice_prefix: &str,
+    ) -> anyhow::Result<&Store> {
+        self.bucket
+            .get_or_try_init(|| {
+                create_bucket(jetstream, &format!("STATE_{lattice_prefix}_{}", self.actor))
+            })
+            .await
+    }
+
+    fn path(&self, dir: &Path, key: &str) -> anyhow::Result<PathBuf> {
+        Ok(dir.join(&self.actor).join(encode_key(key)?))
+    }
+}
+
+/// Write `value` to `path` atomically. Must be called with the lock of the local backend held
+async fn write_file(path: PathBuf, value: &[u8]) -> anyhow::Result<()> {
+    let dir = path.parent().context("invalid state path")?;
+    fs::create_dir_all(dir)
+        .await
+        .context("failed to create state directory")?;
+    // `.` is not part of the key encoding alphabet, so this cannot collide with a key
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, value)
+        .await
+        .context("failed to write value")?;
+    fs::rename(&tmp, &path)
+        .await
+        .context("failed to rename value file")
+}
+
+async fn read_file(path: PathBuf) -> anyhow::Result<Option<Vec<u8>>> {
+    match fs::read(path).await {
+        Ok(value) => Ok(Some(value)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).context("failed to read value"),
+    }
+}
+
+#[async_trait]
+impl State for ActorState {
+    #[instrument(level = "debug", skip(self), fields(actor = %self.actor))]
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        match &*self.backend {
+            Backend::Nats {
+                jetstream,
+                lattice_prefix,
+            } => {
+                let bucket = self.bucket(jetstream, lattice_prefix).await?;
+                let value = bucket
+                    .get(encode_key(key)?)
+                    .await
+                    .context("failed to get value")?;
+                Ok(value.map(|value| value.to_vec()))
+            }
+            Backend::Local { dir, .. } => read_file(self.path(dir, key)?).await,
+        }
+    }
+
+    #[instrument(level = "debug", skip(self, value), fields(actor = %self.actor))]
+    async fn set(&self, key: &str, value: Vec<u8>) -> anyhow::Result<()> {
+        match &*self.backend {
+            Backend::Nats {
+                jetstream,
+                lattice_prefix,
+            } => {
+                let bucket = self.bucket(jetstream, lattice_prefix).await?;
+                bucket
+                    .put(encode_key(key)?, value.into())
+                    .await
+                    .context("failed to set value")?;
+                Ok(())
+            }
+            Backend::Local { dir, lock } => {
+                let path = self.path(dir, key)?;
+                let _lock = lock.lock().await;
+                write_file(path, &value).await
+            }
+        }
+    }
+
+    #[instrument(level = "debug", skip(self), fields(actor = %self.actor))]
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        match &*self.backend {
+            Backend::Nats {
+                jetstream,
+                lattice_prefix,
+            } => {
+                let bucket = self.bucket(jetstream, lattice_prefix).await?;
+                bucket
+                    .delete(encode_key(key)?)
+                    .await
+                    .context("failed to delete value")
+            }
+            Backend::Local { dir, lock } => {
+                let path = self.path(dir, key)?;
+                let _lock = lock.lock().await;
+                match fs::remove_file(path).await {
+                    Err(err) if err.kind() != ErrorKind::NotFound => {
+                        Err(err).context("failed to delete value")
+                    }
+                    _ => Ok(()),
+                }
+            }
+        }
+    }
+
+    #[instrument(level = "debug", skip(self), fields(actor = %self.actor))]
+    async fn keys(&self) -> anyhow::Result<Vec<String>> {
+        match &*self.backend {
+            Backend::Nats {
+                jetstream,
+                lattice_prefix,
+            } => {
+                let bucket = self.bucket(jetstream, lattice_prefix).await?;
+                let keys: Vec<String> = bucket
+                    .keys()
+                    .await
+                    .context("failed to list keys")?
+                    .try_collect()
+                    .await
+                    .context("failed to list keys")?;
+                keys.iter().map(|key| decode_key(key)).collect()
+            }
+            Backend::Local { dir, .. } => {
+                let mut entries = match fs::read_dir(dir.join(&self.actor)).await {
+                    Ok(entries) => entries,
+                    Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+                    Err(err) => return Err(err).context("failed to read state directory"),
+                };
+                let mut keys = Vec::new();
+                while let Some(entry) = entries
+                    .next_entry()
+                    .await
+                    .context("failed to read state directory")?
+                {
+                    let name = entry.file_name();
+                    // Skip temporary files of interrupted writes
+                    match name.to_str() {
+                        Some(name) if !name.contains('.') => keys.push(decode_key(name)?),
+                        _ => {}
+                    }
+                }
+                Ok(keys)
+            }
+        }
+    }
+
+    #[instrument(level = "debug", skip(self), fields(actor = %self.actor))]
+    async fn increment(&self, key: &str, delta: i64) -> anyhow::Result<i64> {
+        match &*self.backend {
+            Backend::Nats {
+                jetstream,
+                lattice_prefix,
+            } => {
+                let bucket = self.bucket(jetstream, lattice_prefix).await?;
+                let key = encode_key(key)?;
+                for _ in 0..MAX_INCREMENT_ATTEMPTS {
+                    let entry = bucket.entry(&key).await.context("failed to get value")?;
+                    let (current, revision) = match entry {
+                        Some(entry) if entry.operation == Operation::Put => {
+                            (parse_counter(&entry.value)?, Some(entry.revision))
+                        }
+                        _ => (0, None),
+                    };
+                    let new = current.checked_add(delta).context("counter overflow")?;
+                    let value = Bytes::from(new.to_string());
+                    // Both fail if the value was concurrently modified
+                    let res = if let Some(revision) = revision {
+                        bucket
+                            .update(&key, value, revision)
+                            .await
+                            .map_err(anyhow::Error::from)
+                    } else {
+                        bucket
+                            .create(&key, value)
+                            .await
+                            .map_err(anyhow::Error::from)
+                    };
+                    match res {
+                        Ok(_) => return Ok(new),
+                        Err(err) => debug!(?err, "failed to update counter, retrying"),
+                    }
+                }
+                bail!("failed to update counter after {MAX_INCREMENT_ATTEMPTS} attempts")
+            }
+            Backend::Local { dir, lock } => {
+                let path = self.path(dir, key)?;
+                let _lock = lock.lock().await;
+                let current = match read_file(path.clone()).await? {
+                    Some(value) => parse_counter(&value)?,
+                    None => 0,
+                };
+                let new = current.checked_add(delta).context("counter overflow")?;
+                write_file(path, new.to_string().as_bytes()).await?;
+                Ok(new)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn local_state() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let backend = Arc::new(Backend::local(dir.path()));
+        let foo = ActorState::new(Arc::clone(&backend), "foo");
+        let bar = ActorState::new(backend, "bar");
+
+        assert_eq!(foo.get("key").await?, None);
+        assert!(foo.keys().await?.is_empty());
+        foo.set("key", b"value".to_vec()).await?;
+        foo.set("some/other key", b"other".to_vec()).await?;
+        assert_eq!(foo.get("key").await?, Some(b"value".to_vec()));
+        assert!(foo.exists("key").await?);
+        let mut keys = foo.keys().await?;
+        keys.sort();
+        assert_eq!(keys, ["key", "some/other key"]);
+
+        // State is isolated per actor
+        assert_eq!(bar.get("key").await?, None);
+        assert!(bar.keys().await?.is_empty());
+
+        assert_eq!(foo.increment("counter", 2).await?, 2);
+        assert_eq!(foo.increment("counter", -5).await?, -3);
+        assert_eq!(foo.get("counter").await?, Some(b"-3".to_vec()));
+        assert!(foo.increment("key", 1).await.is_err());
+
+        foo.delete("key").await?;
+        foo.delete("key").await?;
+        assert!(!foo.exists("key").await?);
+        assert!(foo.get("").await.is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+//! Helpers shared by the unit tests of the wasmbus modules
+
+use super::Annotations;
+
+/// Construct [`Annotations`] from key-value pairs
+pub(super) fn annotations(values: &[(&str, &str)]) -> Annotations {
+    values
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
@@ -10,6 +10,9 @@ use tracing_opentelemetry::OpenTelemetrySpanExt;
 
 static EMPTY_HEADERS: OnceLock<HeaderMap> = OnceLock::new();
 
+/// Name of the header carrying the W3C trace context
+const TRACEPARENT_HEADER: &str = "traceparent";
+
 fn empty_headers() -> &'static HeaderMap {
     EMPTY_HEADERS.get_or_init(HeaderMap::new)
 }
@@ -117,9 +120,9 @@ impl From<NatsHeaderInjector> for HeaderMap {
 /// [`NatsHeaderExtractor`] type directly
 pub fn attach_span_context(msg: &async_nats::Message) {
     // If we extract and there are no OTEL headers, setting the parent will orphan the current span
-    // hierarchy. Checking that there are headers is a heuristic to avoid this
+    // hierarchy. Messages may carry other headers, so check for the trace context header itself
     if let Some(ref headers) = msg.headers {
-        if headers.iter().len() > 0 {
+        if headers.get(TRACEPARENT_HEADER).is_some() {
             let extractor = NatsHeaderExtractor::new(headers);
             let ctx_propagator = TraceContextPropagator::new();
             let parent_ctx = ctx_propagator.extract(&extractor);
@@ -0,0 +1,217 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Expr, ExprLit, Fields, GenericArgument, Ident,
+    Lit, LitStr, Meta, Path, PathArguments, Type,
+};
+
+/// How the value of a field is looked up and parsed
+enum FieldKind<'a> {
+    /// A value is required, unless a default is set
+    Required(&'a Type),
+    /// `Option<T>`, which is `None` if no value is set
+    Optional(&'a Type),
+    /// `Vec<T>`, parsed from a comma-separated list, which is empty if no value is set
+    List(&'a Type),
+}
+
+/// Settings of a field from its `#[link_config(...)]` attributes
+#[derive(Default)]
+struct FieldAttrs {
+    name: Option<LitStr>,
+    env: Option<LitStr>,
+    default: Option<LitStr>,
+    validate: Option<Path>,
+}
+
+/// Returns the type argument of `ty` if it is the generic type `wrapper` (ex. `Option<T>`)
+fn wrapped_type<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != wrapper {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty),
+        _ => None,
+    }
+}
+
+impl<'a> FieldKind<'a> {
+    fn of(ty: &'a Type) -> Self {
+        if let Some(ty) = wrapped_type(ty, "Option") {
+            Self::Optional(ty)
+        } else if let Some(ty) = wrapped_type(ty, "Vec") {
+            Self::List(ty)
+        } else {
+            Self::Required(ty)
+        }
+    }
+}
+
+/// Parse the `#[link_config(...)]` attributes of a field, and its doc comment
+fn parse_field_attrs(field: &syn::Field) -> syn::Result<(FieldAttrs, String)> {
+    let mut attrs = FieldAttrs::default();
+    let mut doc = Vec::new();
+    for attr in &field.attrs {
+        if attr.path().is_ident("doc") {
+            if let Meta::NameValue(nv) = &attr.meta {
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(line),
+                    ..
+                }) = &nv.value
+                {
+                    doc.push(line.value().trim().to_string());
+                }
+            }
+        } else if attr.path().is_ident("link_config") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    attrs.name = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("env") {
+                    attrs.env = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("default") {
+                    attrs.default = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("validate") {
+                    attrs.validate = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error(
+                        "unsupported link_config attribute, expected one of `name`, `env`, `default` or `validate`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+    }
+    Ok((attrs, doc.join(" ").trim().to_string()))
+}
+
+/// Derive `LinkConfig` for a struct with named fields, parsing each field from the link
+/// definition value with the name of the field (or its uppercase form)
+#[proc_macro_derive(LinkConfig, attributes(link_config))]
+pub fn derive_link_config(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_link_config(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand_link_config(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "LinkConfig can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            input,
+            "LinkConfig can only be derived for structs with named fields",
+        ));
+    };
+
+    let lc = quote!(::wasmcloud_provider_sdk::link_config);
+    let mut field_infos = Vec::new();
+    let mut field_parsers = Vec::new();
+    let mut field_inits = Vec::new();
+    let mut required_idents: Vec<&Ident> = Vec::new();
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("named fields have identifiers");
+        let (attrs, doc) = parse_field_attrs(field)?;
+        let name = attrs
+            .name
+            .unwrap_or_else(|| LitStr::new(&ident.to_string(), ident.span()));
+        let kind = FieldKind::of(&field.ty);
+        let required = matches!(kind, FieldKind::Required(_)) && attrs.default.is_none();
+        let env = match &attrs.env {
+            Some(env) => quote!(Some(#env)),
+            None => quote!(None),
+        };
+        let default = match &attrs.default {
+            Some(default) => quote!(Some(#default)),
+            None => quote!(None::<&'static str>),
+        };
+
+        field_infos.push(quote!(
+            #lc::LinkConfigField {
+                name: #name,
+                env: #env,
+                default: #default,
+                required: #required,
+                doc: #doc,
+            }
+        ));
+
+        let parse = match kind {
+            FieldKind::Required(ty) | FieldKind::Optional(ty) => {
+                quote!(#lc::parse_value::<#ty>(#name, &raw, &mut errors))
+            }
+            FieldKind::List(ty) => quote!(#lc::parse_list::<#ty>(#name, &raw, &mut errors)),
+        };
+        let missing = if required {
+            quote!(errors.push(#lc::FieldError::missing(#name, #env));)
+        } else {
+            TokenStream2::new()
+        };
+        let validate = attrs.validate.map(|validate| {
+            quote!(
+                if let Some(value) = &#ident {
+                    if let Err(err) = #validate(value) {
+                        errors.push(#lc::FieldError::invalid(#name, err));
+                    }
+                }
+            )
+        });
+        field_parsers.push(quote!(
+            let #ident = match #lc::lookup(values, #name, #env)
+                .or_else(|| #default.map(::std::borrow::Cow::Borrowed))
+            {
+                Some(raw) => #parse,
+                None => {
+                    #missing
+                    None
+                }
+            };
+            #validate
+        ));
+
+        match kind {
+            FieldKind::Required(_) => {
+                required_idents.push(ident);
+                field_inits.push(quote!(#ident));
+            }
+            FieldKind::Optional(_) => field_inits.push(quote!(#ident)),
+            FieldKind::List(_) => field_inits.push(quote!(#ident: #ident.unwrap_or_default())),
+        }
+    }
+
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote!(
+        impl #impl_generics #lc::LinkConfig for #ty #ty_generics #where_clause {
+            fn fields() -> &'static [#lc::LinkConfigField] {
+                &[#(#field_infos),*]
+            }
+
+            #[allow(unused_mut)]
+            fn from_values(
+                values: &::std::collections::HashMap<String, String>,
+            ) -> Result<Self, #lc::LinkConfigError> {
+                let mut errors = Vec::new();
+                #(#field_parsers)*
+                match (#(#required_idents,)*) {
+                    (#(Some(#required_idents),)*) if errors.is_empty() => Ok(Self {
+                        #(#field_inits),*
+                    }),
+                    _ => Err(#lc::LinkConfigError::new(errors)),
+                }
+            }
+        }
+    ))
+}
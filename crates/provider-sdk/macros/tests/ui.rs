@@ -0,0 +1,8 @@
+/// Ensure that invalid `#[derive(LinkConfig)]` uses are rejected with helpful errors, and that
+/// valid ones parse link definition values as documented
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/fail/*.rs");
+    t.pass("tests/ui/pass/*.rs");
+}
@@ -0,0 +1,9 @@
+use wasmcloud_provider_sdk::link_config::LinkConfig;
+
+#[derive(LinkConfig)]
+struct Config {
+    #[link_config(default = 3)]
+    retries: u8,
+}
+
+fn main() {}
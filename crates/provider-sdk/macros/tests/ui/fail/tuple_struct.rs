@@ -0,0 +1,6 @@
+use wasmcloud_provider_sdk::link_config::LinkConfig;
+
+#[derive(LinkConfig)]
+struct Config(String);
+
+fn main() {}
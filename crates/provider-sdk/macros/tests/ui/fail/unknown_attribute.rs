@@ -0,0 +1,9 @@
+use wasmcloud_provider_sdk::link_config::LinkConfig;
+
+#[derive(LinkConfig)]
+struct Config {
+    #[link_config(ttl = "10")]
+    token: String,
+}
+
+fn main() {}
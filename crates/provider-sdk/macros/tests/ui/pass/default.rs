@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+use wasmcloud_provider_sdk::link_config::LinkConfig;
+
+#[derive(LinkConfig)]
+struct Config {
+    #[link_config(default = "secret")]
+    mount: String,
+    #[link_config(default = "3")]
+    retries: u8,
+}
+
+fn main() {
+    let config = Config::from_values(&HashMap::new()).expect("defaults should be used");
+    assert_eq!(config.mount, "secret");
+    assert_eq!(config.retries, 3);
+
+    let config = Config::from_values(&HashMap::from([("MOUNT".to_string(), "kv".to_string())]))
+        .expect("values should override defaults");
+    assert_eq!(config.mount, "kv");
+
+    let fields = Config::fields();
+    assert!(fields.iter().all(|field| !field.required));
+    assert_eq!(fields[0].default, Some("secret"));
+}
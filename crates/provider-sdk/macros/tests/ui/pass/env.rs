@@ -0,0 +1,40 @@
+use std::collections::HashMap;
+
+use wasmcloud_provider_sdk::link_config::{FieldError, LinkConfig};
+
+#[derive(LinkConfig)]
+struct Config {
+    #[link_config(env = "LINK_CONFIG_UI_TOKEN")]
+    token: String,
+    #[link_config(env = "LINK_CONFIG_UI_ADDR", default = "127.0.0.1")]
+    addr: String,
+}
+
+fn main() {
+    let err = Config::from_values(&HashMap::new())
+        .err()
+        .expect("token is required");
+    assert!(matches!(
+        err.errors[..],
+        [FieldError::Missing {
+            field: "token",
+            env: Some("LINK_CONFIG_UI_TOKEN")
+        }]
+    ));
+
+    std::env::set_var("LINK_CONFIG_UI_TOKEN", "from-env");
+    std::env::set_var("LINK_CONFIG_UI_ADDR", "10.0.0.1");
+    let config = Config::from_values(&HashMap::new()).expect("environment should be used");
+    assert_eq!(config.token, "from-env");
+    // The environment takes precedence over defaults
+    assert_eq!(config.addr, "10.0.0.1");
+
+    // Link definition values take precedence over the environment
+    let config = Config::from_values(&HashMap::from([(
+        "token".to_string(),
+        "from-link".to_string(),
+    )]))
+    .expect("values should parse");
+    assert_eq!(config.token, "from-link");
+    assert_eq!(Config::fields()[0].env, Some("LINK_CONFIG_UI_TOKEN"));
+}
@@ -0,0 +1,34 @@
+use std::collections::HashMap;
+
+use wasmcloud_provider_sdk::link_config::{FieldError, LinkConfig};
+
+#[derive(LinkConfig)]
+struct Config {
+    certs: Vec<String>,
+    ports: Vec<u16>,
+}
+
+fn main() {
+    let config = Config::from_values(&HashMap::new()).expect("lists may be unset");
+    assert!(config.certs.is_empty());
+    assert!(config.ports.is_empty());
+
+    let config = Config::from_values(&HashMap::from([
+        ("certs".to_string(), "a.pem, b.pem,".to_string()),
+        ("ports".to_string(), "80,443".to_string()),
+    ]))
+    .expect("valid lists should parse");
+    assert_eq!(config.certs, ["a.pem", "b.pem"]);
+    assert_eq!(config.ports, [80, 443]);
+
+    let err = Config::from_values(&HashMap::from([(
+        "ports".to_string(),
+        "80,http".to_string(),
+    )]))
+    .err()
+    .expect("every item of a list must be valid");
+    assert!(matches!(
+        err.errors[..],
+        [FieldError::Invalid { field: "ports", .. }]
+    ));
+}
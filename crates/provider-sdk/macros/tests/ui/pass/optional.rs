@@ -0,0 +1,35 @@
+use std::collections::HashMap;
+
+use wasmcloud_provider_sdk::link_config::{FieldError, LinkConfig};
+
+#[derive(LinkConfig)]
+struct Config {
+    timeout_ms: Option<u64>,
+}
+
+fn main() {
+    let config = Config::from_values(&HashMap::new()).expect("optional fields may be unset");
+    assert_eq!(config.timeout_ms, None);
+
+    let config = Config::from_values(&HashMap::from([(
+        "timeout_ms".to_string(),
+        "250".to_string(),
+    )]))
+    .expect("valid values should parse");
+    assert_eq!(config.timeout_ms, Some(250));
+
+    let err = Config::from_values(&HashMap::from([(
+        "timeout_ms".to_string(),
+        "soon".to_string(),
+    )]))
+    .err()
+    .expect("set optional fields must be valid");
+    assert!(matches!(
+        err.errors[..],
+        [FieldError::Invalid {
+            field: "timeout_ms",
+            ..
+        }]
+    ));
+    assert!(!Config::fields()[0].required);
+}
@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use wasmcloud_provider_sdk::link_config::{FieldError, LinkConfig};
+
+#[derive(LinkConfig)]
+struct Config {
+    /// Port to listen on
+    port: u16,
+    #[link_config(name = "server_name")]
+    name: String,
+}
+
+fn main() {
+    let config = Config::from_values(&HashMap::from([
+        ("port".to_string(), "8080".to_string()),
+        ("SERVER_NAME".to_string(), "test".to_string()),
+    ]))
+    .expect("valid values should parse");
+    assert_eq!(config.port, 8080);
+    assert_eq!(config.name, "test");
+
+    // All missing and invalid fields are reported at once
+    let err = Config::from_values(&HashMap::from([("port".to_string(), "x".to_string())]))
+        .err()
+        .expect("invalid port and missing name should fail");
+    assert_eq!(err.errors.len(), 2);
+    assert!(matches!(
+        err.errors[0],
+        FieldError::Invalid { field: "port", .. }
+    ));
+    assert!(matches!(
+        err.errors[1],
+        FieldError::Missing {
+            field: "server_name",
+            env: None
+        }
+    ));
+
+    let fields = Config::fields();
+    assert_eq!(fields.len(), 2);
+    assert!(fields.iter().all(|field| field.required));
+    assert_eq!(fields[0].doc, "Port to listen on");
+    assert_eq!(fields[1].name, "server_name");
+}
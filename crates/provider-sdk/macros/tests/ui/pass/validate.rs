@@ -0,0 +1,44 @@
+use std::collections::HashMap;
+
+use wasmcloud_provider_sdk::link_config::{FieldError, LinkConfig};
+
+#[derive(LinkConfig)]
+struct Config {
+    #[link_config(validate = at_least_one)]
+    max_connections: u32,
+    #[link_config(validate = at_least_one)]
+    max_retries: Option<u32>,
+}
+
+fn at_least_one(value: &u32) -> Result<(), &'static str> {
+    if *value == 0 {
+        Err("must be at least 1")
+    } else {
+        Ok(())
+    }
+}
+
+fn main() {
+    let config = Config::from_values(&HashMap::from([(
+        "max_connections".to_string(),
+        "4".to_string(),
+    )]))
+    .expect("valid values should pass validation");
+    assert_eq!(config.max_connections, 4);
+    assert_eq!(config.max_retries, None);
+
+    let err = Config::from_values(&HashMap::from([
+        ("max_connections".to_string(), "0".to_string()),
+        ("max_retries".to_string(), "0".to_string()),
+    ]))
+    .err()
+    .expect("values failing validation should be rejected");
+    assert_eq!(err.errors.len(), 2);
+    for (error, field) in err.errors.iter().zip(["max_connections", "max_retries"]) {
+        assert_eq!(error.field(), field);
+        assert_eq!(
+            error.to_string(),
+            format!("invalid setting for '{field}': must be at least 1")
+        );
+    }
+}
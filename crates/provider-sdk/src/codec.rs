@@ -0,0 +1,171 @@
+//! Encodings of invocation payloads, and transcoding between them, so that providers using one
+//! codec can be invoked by actors and hosts using another. The encoding of a payload is carried
+//! in [`Invocation::content_type`](crate::core::Invocation::content_type)
+
+use std::fmt;
+
+use serde::Deserializer;
+use wasmcloud_core::{CONTENT_TYPE_CBOR, CONTENT_TYPE_JSON, CONTENT_TYPE_MSGPACK};
+
+use crate::error::InvocationResult;
+
+/// Encoding of invocation payloads
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Codec {
+    /// MessagePack, which is understood by all wasmCloud actors and hosts
+    #[default]
+    MsgPack,
+    /// JSON
+    Json,
+    /// CBOR
+    Cbor,
+}
+
+impl Codec {
+    /// Returns the codec of payloads with content type `content_type`, if it is known
+    #[must_use]
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        match content_type {
+            CONTENT_TYPE_MSGPACK => Some(Self::MsgPack),
+            CONTENT_TYPE_JSON => Some(Self::Json),
+            CONTENT_TYPE_CBOR => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    /// Returns the content type of payloads encoded with this codec
+    #[must_use]
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::MsgPack => CONTENT_TYPE_MSGPACK,
+            Self::Json => CONTENT_TYPE_JSON,
+            Self::Cbor => CONTENT_TYPE_CBOR,
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.content_type())
+    }
+}
+
+/// Transcodes `payload` encoded with the codec `from` to the codec `to`.
+///
+/// Byte strings are transcoded to arrays of numbers for codecs without native byte strings (JSON),
+/// which deserialize into byte buffers all the same
+pub fn transcode(payload: &[u8], from: Codec, to: Codec) -> InvocationResult<Vec<u8>> {
+    if from == to {
+        return Ok(payload.to_vec());
+    }
+    match from {
+        Codec::MsgPack => transcode_into(&mut rmp_serde::Deserializer::new(payload), to),
+        Codec::Json => transcode_into(&mut serde_json::Deserializer::from_slice(payload), to),
+        Codec::Cbor => transcode_into(&mut serde_cbor::Deserializer::from_slice(payload), to),
+    }
+}
+
+/// Transcodes the value of `deserializer` to the codec `to`
+fn transcode_into<'de>(
+    deserializer: impl Deserializer<'de>,
+    to: Codec,
+) -> InvocationResult<Vec<u8>> {
+    let mut buf = Vec::new();
+    match to {
+        Codec::MsgPack => {
+            serde_transcode::transcode(deserializer, &mut rmp_serde::Serializer::new(&mut buf))?
+        }
+        Codec::Json => {
+            serde_transcode::transcode(deserializer, &mut serde_json::Serializer::new(&mut buf))?
+        }
+        Codec::Cbor => {
+            serde_transcode::transcode(deserializer, &mut serde_cbor::Serializer::new(&mut buf))?
+        }
+    }
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde::{Deserialize, Serialize};
+
+    use super::{transcode, Codec};
+    use crate::{
+        deserialize, deserialize_cbor, deserialize_json, serialize, serialize_cbor, serialize_json,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Request {
+        key: String,
+        count: u32,
+        expires: Option<u64>,
+        #[serde(with = "serde_bytes")]
+        value: Vec<u8>,
+    }
+
+    fn request() -> Request {
+        Request {
+            key: "counter".to_string(),
+            count: 42,
+            expires: None,
+            value: vec![0, 1, 2, 255],
+        }
+    }
+
+    #[test]
+    fn content_types_round_trip() {
+        for codec in [Codec::MsgPack, Codec::Json, Codec::Cbor] {
+            assert_eq!(Codec::from_content_type(codec.content_type()), Some(codec));
+        }
+        assert_eq!(Codec::from_content_type("text/plain"), None);
+    }
+
+    #[test]
+    fn transcode_to_same_codec_is_identity() {
+        let payload = serialize(&request()).unwrap();
+        assert_eq!(
+            transcode(&payload, Codec::MsgPack, Codec::MsgPack).unwrap(),
+            payload
+        );
+    }
+
+    #[test]
+    fn transcode_msgpack_round_trips() {
+        let payload = serialize(&request()).unwrap();
+
+        let json = transcode(&payload, Codec::MsgPack, Codec::Json).unwrap();
+        assert_eq!(deserialize_json::<Request>(&json).unwrap(), request());
+        let back = transcode(&json, Codec::Json, Codec::MsgPack).unwrap();
+        assert_eq!(deserialize::<Request>(&back).unwrap(), request());
+
+        let cbor = transcode(&payload, Codec::MsgPack, Codec::Cbor).unwrap();
+        assert_eq!(deserialize_cbor::<Request>(&cbor).unwrap(), request());
+        let back = transcode(&cbor, Codec::Cbor, Codec::MsgPack).unwrap();
+        assert_eq!(deserialize::<Request>(&back).unwrap(), request());
+    }
+
+    #[test]
+    fn transcode_json_and_cbor_round_trip() {
+        let json = serialize_json(&request()).unwrap();
+        let cbor = transcode(&json, Codec::Json, Codec::Cbor).unwrap();
+        assert_eq!(deserialize_cbor::<Request>(&cbor).unwrap(), request());
+
+        let cbor = serialize_cbor(&request()).unwrap();
+        let json = transcode(&cbor, Codec::Cbor, Codec::Json).unwrap();
+        assert_eq!(deserialize_json::<Request>(&json).unwrap(), request());
+    }
+
+    #[test]
+    fn transcode_bytes_to_json_array() {
+        let payload = serialize(&request()).unwrap();
+        let json = transcode(&payload, Codec::MsgPack, Codec::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        assert_eq!(value["value"], serde_json::json!([0, 1, 2, 255]));
+        assert_eq!(value["expires"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn transcode_invalid_payload_fails() {
+        assert!(transcode(b"{not json", Codec::Json, Codec::MsgPack).is_err());
+    }
+}
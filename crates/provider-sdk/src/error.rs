@@ -1,5 +1,7 @@
 //! Error types for interacting with a provider
 
+use wasmcloud_core::InvocationErrorKind;
+
 pub type InvocationResult<T> = Result<T, InvocationError>;
 pub type ProviderResult<T> = Result<T, ProviderError>;
 pub type ProviderInvocationResult<T> = Result<T, ProviderInvocationError>;
@@ -22,14 +24,82 @@ pub enum ProviderError {
 /// allows the provider-sdk to still handle an invocation error properly
 // NOTE(thomastaylor312): I don't _love_ this, but it does allow us to keep the provider SDK errors
 // separate from what each provider can return
+///
+/// The [`InvocationErrorKind`] of the error (see [`ProviderInvocationError::kind`]) is sent to the
+/// caller along with its message, so callers can tell e.g. an unavailable backend from a denied
+/// request
 #[derive(Debug, thiserror::Error)]
 pub enum ProviderInvocationError {
     #[error(transparent)]
     Invocation(#[from] InvocationError),
+    /// Handling the invocation, or a call made by the provider while handling it, timed out
+    #[error("{0}")]
+    Timeout(String),
+    /// The actor is not linked to the provider
+    #[error("{0}")]
+    NotLinked(String),
+    /// The request is malformed, e.g. has invalid arguments
+    #[error("{0}")]
+    Malformed(String),
+    /// A service the provider depends on (e.g. a database) is unavailable
+    #[error("{0}")]
+    BackendUnavailable(String),
+    /// The actor is not permitted to perform the request
+    #[error("{0}")]
+    PermissionDenied(String),
+    /// The actor exceeded the rate of invocations allowed by its link, see
+    /// [`crate::rate_limit`]
+    #[error("{0}")]
+    Throttled(String),
+    /// Any other error
     #[error("{0}")]
     Provider(String),
 }
 
+impl ProviderInvocationError {
+    /// Returns the kind of the error, which is sent to the caller
+    #[must_use]
+    pub fn kind(&self) -> InvocationErrorKind {
+        match self {
+            Self::Invocation(InvocationError::Timeout) | Self::Timeout(_) => {
+                InvocationErrorKind::Timeout
+            }
+            Self::Invocation(InvocationError::Validation(ValidationError::InvalidActor(_)))
+            | Self::NotLinked(_) => InvocationErrorKind::NotLinked,
+            Self::Invocation(InvocationError::Validation(_)) | Self::PermissionDenied(_) => {
+                InvocationErrorKind::PermissionDenied
+            }
+            Self::Invocation(
+                InvocationError::Malformed(_)
+                | InvocationError::Deser(_)
+                | InvocationError::Json(_)
+                | InvocationError::Cbor(_),
+            )
+            | Self::Malformed(_) => InvocationErrorKind::Malformed,
+            Self::Invocation(InvocationError::Network(_)) | Self::BackendUnavailable(_) => {
+                InvocationErrorKind::BackendUnavailable
+            }
+            Self::Throttled(_) => InvocationErrorKind::Throttled,
+            Self::Invocation(_) | Self::Provider(_) => InvocationErrorKind::Custom,
+        }
+    }
+
+    /// Constructs the error received in the response to an invocation, from its kind (if set by
+    /// the responder) and message
+    #[must_use]
+    pub fn from_response(kind: Option<InvocationErrorKind>, message: String) -> Self {
+        match kind.unwrap_or_default() {
+            InvocationErrorKind::Timeout => Self::Timeout(message),
+            InvocationErrorKind::NotLinked => Self::NotLinked(message),
+            InvocationErrorKind::Malformed => Self::Malformed(message),
+            InvocationErrorKind::BackendUnavailable => Self::BackendUnavailable(message),
+            InvocationErrorKind::PermissionDenied => Self::PermissionDenied(message),
+            InvocationErrorKind::Throttled => Self::Throttled(message),
+            InvocationErrorKind::Custom => Self::Provider(message),
+        }
+    }
+}
+
 impl From<std::io::Error> for ProviderInvocationError {
     fn from(e: std::io::Error) -> Self {
         Self::Provider(format!("i/o error: {e}"))
@@ -42,6 +112,85 @@ impl From<String> for ProviderInvocationError {
     }
 }
 
+impl From<LinkConfigError> for ProviderInvocationError {
+    fn from(e: LinkConfigError) -> Self {
+        Self::Provider(e.to_string())
+    }
+}
+
+impl From<SecretsError> for ProviderInvocationError {
+    fn from(e: SecretsError) -> Self {
+        match e {
+            SecretsError::Unavailable { .. } => Self::BackendUnavailable(e.to_string()),
+            _ => Self::Provider(e.to_string()),
+        }
+    }
+}
+
+/// Errors when resolving secret references, see [`crate::secrets::SecretsClient`]
+#[derive(Debug, thiserror::Error)]
+pub enum SecretsError {
+    /// A value looks like a secret reference, but is not a valid one
+    #[error("invalid secret reference: {0}")]
+    InvalidReference(String),
+    /// The secrets backend could not be reached or did not respond in time
+    #[error("secrets backend `{backend}` is unavailable: {reason}")]
+    Unavailable { backend: String, reason: String },
+    /// The secrets backend failed to retrieve the secret, e.g. because it does not exist
+    #[error("failed to retrieve secret `{reference}`: {reason}")]
+    Retrieval { reference: String, reason: String },
+}
+
+/// Errors of all missing and invalid settings of a link configuration, see
+/// [`crate::link_config::LinkConfig`]
+#[derive(Debug, thiserror::Error)]
+#[error("invalid link configuration: {}", .errors.iter().map(ToString::to_string).collect::<Vec<_>>().join("; "))]
+pub struct LinkConfigError {
+    /// Errors of the individual settings
+    pub errors: Vec<FieldError>,
+}
+
+impl LinkConfigError {
+    pub fn new(errors: Vec<FieldError>) -> Self {
+        Self { errors }
+    }
+}
+
+/// Error of a single setting of a link configuration
+#[derive(Debug, thiserror::Error)]
+pub enum FieldError {
+    /// The setting is required, but neither the link definition nor the environment set it
+    #[error("missing setting for '{field}'{}", .env.map(|env| format!(" or {env}")).unwrap_or_default())]
+    Missing {
+        field: &'static str,
+        env: Option<&'static str>,
+    },
+    /// The value of the setting could not be parsed or failed validation. The value itself is not
+    /// included, since it may be sensitive
+    #[error("invalid setting for '{field}': {reason}")]
+    Invalid { field: &'static str, reason: String },
+}
+
+impl FieldError {
+    pub fn missing(field: &'static str, env: Option<&'static str>) -> Self {
+        Self::Missing { field, env }
+    }
+
+    pub fn invalid(field: &'static str, reason: impl std::fmt::Display) -> Self {
+        Self::Invalid {
+            field,
+            reason: reason.to_string(),
+        }
+    }
+
+    /// Returns the name of the setting
+    pub fn field(&self) -> &'static str {
+        match self {
+            Self::Missing { field, .. } | Self::Invalid { field, .. } => field,
+        }
+    }
+}
+
 /// Errors that can occur when sending or receiving an invocation, including the `dispatch` method
 /// of the provider.
 #[derive(Debug, thiserror::Error)]
@@ -62,6 +211,12 @@ pub enum InvocationError {
     /// The invocation or dispatch failed when deserializing data from the wire
     #[error("Error when deserializing invocation: {0:?}")]
     Deser(#[from] rmp_serde::decode::Error),
+    /// The invocation or dispatch failed when serializing or deserializing JSON data
+    #[error("Error when serializing or deserializing invocation as JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// The invocation or dispatch failed when serializing or deserializing CBOR data
+    #[error("Error when serializing or deserializing invocation as CBOR: {0}")]
+    Cbor(#[from] serde_cbor::Error),
     /// An error that occurred when trying to publish or request over NATS
     #[error("Networking error during invocation: {0:?}")]
     Network(#[from] NetworkError),
@@ -73,6 +228,15 @@ pub enum InvocationError {
     Malformed(String),
 }
 
+impl InvocationError {
+    /// Whether sending the invocation again may succeed, i.e. it timed out or could not be
+    /// delivered
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::Timeout | Self::Network(_))
+    }
+}
+
 /// All errors that can occur when validating an invocation
 #[derive(Debug, thiserror::Error)]
 pub enum ValidationError {
@@ -112,6 +276,12 @@ pub enum ValidationError {
     /// The origin of the invocation is not valid
     #[error("Invocation claims and invocation origin URL do not match: {0} != {1}")]
     InvalidOriginUrl(String, String),
+    /// The schema version of the invocation is not supported by the provider
+    #[error("Invocation schema is incompatible: {0}")]
+    IncompatibleSchema(String),
+    /// The invocation was rejected by an [`crate::InvocationValidator`] of the provider
+    #[error("Invocation rejected: {0}")]
+    Rejected(String),
 }
 
 /// This is a wrapper around two different NATS errors that we use (publish and request). It
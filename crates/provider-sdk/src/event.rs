@@ -0,0 +1,62 @@
+//! Events published by providers on the lattice, as CloudEvents in the same format as the events
+//! of the host, e.g. when an invocation of the provider is rejected
+
+use async_nats::Subject;
+use cloudevents::{EventBuilder, EventBuilderV10};
+use serde_json::json;
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+use ulid::Ulid;
+use uuid::Uuid;
+
+use crate::{
+    core::WasmCloudEntity,
+    error::{InvocationError, InvocationResult, ValidationError},
+    RpcClient,
+};
+
+/// Name of the event published when an invocation of the provider fails validation, which is
+/// the name of the event the host publishes for invocations of actors failing validation
+pub const INVOCATION_AUTH_FAILED: &str = "invocation_auth_failed";
+
+/// Data of the [`INVOCATION_AUTH_FAILED`] event of an invocation rejected with `error`
+pub(crate) fn invocation_auth_failed(
+    origin: &WasmCloudEntity,
+    target: &WasmCloudEntity,
+    operation: impl AsRef<str>,
+    invocation_id: impl AsRef<str>,
+    error: &ValidationError,
+) -> serde_json::Value {
+    json!({
+        "source": origin,
+        "dest": target,
+        "operation": operation.as_ref(),
+        "invocation_id": invocation_id.as_ref(),
+        "error": error.to_string(),
+    })
+}
+
+/// Publishes the event `name` with `data` in the lattice with prefix `lattice` via `rpc_client`,
+/// sourced from the provider with public key `provider_key`
+pub(crate) async fn publish(
+    rpc_client: &RpcClient,
+    lattice: &str,
+    provider_key: &str,
+    name: &str,
+    data: serde_json::Value,
+) -> InvocationResult<()> {
+    let now = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .map_err(|e| InvocationError::Malformed(format!("failed to format current time: {e}")))?;
+    let ev = EventBuilderV10::new()
+        .source(provider_key)
+        .ty(format!("com.wasmcloud.lattice.{name}"))
+        .id(Uuid::from_u128(Ulid::new().into()).to_string())
+        .time(now)
+        .data("application/json", data)
+        .build()
+        .map_err(|e| InvocationError::Malformed(format!("failed to build cloud event: {e}")))?;
+    let ev = serde_json::to_vec(&ev)?;
+    rpc_client
+        .publish(Subject::from(format!("wasmbus.evt.{lattice}.{name}")), ev)
+        .await
+}
@@ -1,25 +1,48 @@
-use std::{borrow::Cow, collections::HashMap, time::Duration};
+use std::{borrow::Cow, collections::HashMap, future::Future, time::Duration};
 
 use async_nats::{ConnectOptions, Event};
 use async_trait::async_trait;
 use error::ProviderInvocationError;
 use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
+use tracing_futures::Instrument;
 
+pub mod codec;
 pub mod error;
+pub mod event;
+pub mod link_config;
+pub mod link_store;
+pub mod log_forwarding;
+pub mod logging;
+pub mod metrics;
 pub mod provider;
 pub mod provider_main;
+pub mod rate_limit;
 pub mod rpc_client;
+pub mod secrets;
+pub mod supervisor;
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
 
+pub use link_config::LinkConfig;
+pub use link_store::LinkStore;
+pub use logging::init_logging;
 pub use provider::ProviderConnection;
-pub use provider_main::{load_host_data, run_provider, start_provider};
+pub use provider_main::{
+    load_host_data, run_provider, run_provider_with_config, start_provider,
+    start_provider_with_config, ConnectionConfig,
+};
 pub use rpc_client::RpcClient;
+pub use secrets::SecretsClient;
+pub use supervisor::TaskSupervisor;
+/// Re-exported for use in code generated by `wasmcloud-provider-wit-bindgen`
+pub use tracing;
 pub use wasmcloud_core as core;
 pub use wasmcloud_tracing;
 
 use crate::{
-    core::{HealthCheckRequest, HealthCheckResponse, LinkDefinition, WasmCloudEntity},
-    error::{InvocationError, InvocationResult},
+    core::{HealthCheckRequest, HealthCheckResponse, Invocation, LinkDefinition, WasmCloudEntity},
+    error::{InvocationError, InvocationResult, ValidationError},
 };
 
 pub const URL_SCHEME: &str = "wasmbus";
@@ -37,6 +60,25 @@ pub fn serialize<T: Serialize>(data: &T) -> InvocationResult<Vec<u8>> {
     rmp_serde::to_vec_named(data).map_err(InvocationError::from)
 }
 
+// helper methods for serializing and deserializing payloads as JSON, for use with actors that
+// cannot use MessagePack
+pub fn deserialize_json<'de, T: Deserialize<'de>>(buf: &'de [u8]) -> InvocationResult<T> {
+    serde_json::from_slice(buf).map_err(InvocationError::from)
+}
+
+pub fn serialize_json<T: Serialize>(data: &T) -> InvocationResult<Vec<u8>> {
+    serde_json::to_vec(data).map_err(InvocationError::from)
+}
+
+// helper methods for serializing and deserializing payloads as CBOR
+pub fn deserialize_cbor<'de, T: Deserialize<'de>>(buf: &'de [u8]) -> InvocationResult<T> {
+    serde_cbor::from_slice(buf).map_err(InvocationError::from)
+}
+
+pub fn serialize_cbor<T: Serialize>(data: &T) -> InvocationResult<Vec<u8>> {
+    serde_cbor::to_vec(data).map_err(InvocationError::from)
+}
+
 /// Returns the rpc topic (subject) name for sending to an actor or provider.
 /// A provider entity must have the public_key and link_name fields filled in.
 /// An actor entity must have a public_key and an empty link_name.
@@ -100,6 +142,84 @@ pub struct Context {
 
     /// A map of tracing context information
     pub tracing: HashMap<String, String>,
+
+    /// Custom claims of the invoking actor, which the host was configured to forward. These are
+    /// part of the invocation claims signed by the cluster key and can be relied upon, e.g. to
+    /// apply per-tenant rules
+    pub actor_claims: HashMap<String, String>,
+
+    /// ID of the invocation being handled, if this context belongs to a received invocation
+    pub invocation_id: Option<String>,
+
+    /// Prefix of the lattice the invocation was received on, if this context belongs to a
+    /// received invocation. Providers serving multiple lattices must keep the resources of each
+    /// lattice apart
+    pub lattice: Option<String>,
+}
+
+impl Context {
+    /// Returns a span for dispatching an invocation of `method` of `interface` (part of
+    /// `contract`) with this context.
+    ///
+    /// With the `otel` feature enabled, the span continues the trace propagated with the
+    /// invocation
+    pub fn dispatch_span(&self, contract: &str, interface: &str, method: &str) -> tracing::Span {
+        let span = tracing::info_span!(
+            "handle_invocation",
+            contract,
+            interface,
+            method,
+            invocation_id = self.invocation_id.as_deref().unwrap_or_default(),
+        );
+        #[cfg(feature = "otel")]
+        if !self.tracing.is_empty() {
+            let trace_context: crate::core::TraceContext = self
+                .tracing
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect();
+            span.in_scope(|| wasmcloud_tracing::context::attach_span_context(&trace_context));
+        }
+        span
+    }
+
+    /// Returns the OpenTelemetry context propagated with the invocation, e.g. to use as the parent
+    /// of spans of calls to downstream services or to inject into their requests
+    #[cfg(feature = "otel")]
+    pub fn otel_context(&self) -> opentelemetry::Context {
+        use opentelemetry::propagation::TextMapPropagator;
+
+        opentelemetry::sdk::propagation::TraceContextPropagator::new().extract(&self.tracing)
+    }
+
+    /// Returns this context with the trace context of the current span, so that invocations sent
+    /// with it continue the trace from the current span rather than from the original invocation.
+    ///
+    /// Without the `otel` feature, or if the current span is not recorded, the context is
+    /// returned unchanged
+    #[must_use]
+    #[cfg_attr(not(feature = "otel"), allow(unused_mut))]
+    pub fn with_current_span(mut self) -> Self {
+        #[cfg(feature = "otel")]
+        self.tracing.extend(
+            wasmcloud_tracing::context::TraceContextInjector::default_with_span()
+                .iter()
+                .map(|(k, v)| (k.clone(), v.clone())),
+        );
+        self
+    }
+}
+
+/// Run `dispatch`, the handling of an invocation by a provider, within `span`
+/// (see [`Context::dispatch_span`])
+pub async fn dispatch_instrumented<F>(
+    span: tracing::Span,
+    dispatch: F,
+) -> Result<Vec<u8>, ProviderInvocationError>
+where
+    F: Future<Output = Result<Vec<u8>, ProviderInvocationError>>,
+{
+    dispatch.instrument(span).await
 }
 
 /// The super trait containing all necessary traits for a provider
@@ -115,6 +235,30 @@ pub trait MessageDispatch {
         method: String,
         body: Cow<'a, [u8]>,
     ) -> Result<Vec<u8>, ProviderInvocationError>;
+
+    /// Returns the codec of the payloads `dispatch` expects and returns, if known. Invocations
+    /// with payloads of a different [`Invocation::content_type`] are transcoded to this codec,
+    /// and their responses back to the codec of the invocation. Payloads are passed on as-is if
+    /// this returns `None`
+    fn codec(&self) -> Option<codec::Codec> {
+        None
+    }
+}
+
+/// Validation of received invocations in addition to the validation performed by the SDK, which
+/// verifies the signed claims of each invocation (issuer, target and hash of the payload) and
+/// that it was sent by an actor linked to the provider. Security-sensitive providers can use this
+/// to apply further rules before invocations are dispatched, see
+/// [`ConnectionConfig::with_invocation_validator`]
+#[async_trait]
+pub trait InvocationValidator: Send + Sync + 'static {
+    /// Returns an error to reject the invocation, in which case it is not dispatched and the error
+    /// is returned to the sender
+    async fn validate(
+        &self,
+        inv: &Invocation,
+        claims: &wascap::prelude::Claims<wascap::jwt::Invocation>,
+    ) -> Result<(), ValidationError>;
 }
 
 /// CapabilityProvider handling of messages from host
@@ -131,6 +275,22 @@ pub trait ProviderHandler: Sync {
     /// Notify the provider that the link is dropped
     async fn delete_link(&self, _actor_id: &str) {}
 
+    /// Like [`ProviderHandler::put_link`], for a link put in the lattice with prefix `lattice`.
+    ///
+    /// Providers serving additional lattices (see
+    /// [`HostData::additional_lattice_prefixes`](crate::core::HostData::additional_lattice_prefixes))
+    /// receive the same actor's links in different lattices as separate links, and should
+    /// override this to keep per-link resources apart. Defaults to [`ProviderHandler::put_link`]
+    async fn put_lattice_link(&self, _lattice: &str, ld: &LinkDefinition) -> bool {
+        self.put_link(ld).await
+    }
+
+    /// Like [`ProviderHandler::delete_link`], for a link deleted in the lattice with prefix
+    /// `lattice`. Defaults to [`ProviderHandler::delete_link`]
+    async fn delete_lattice_link(&self, _lattice: &str, actor_id: &str) {
+        self.delete_link(actor_id).await
+    }
+
     /// Perform health check. Called at regular intervals by host
     /// Default implementation always returns healthy
     async fn health_request(&self, _arg: &HealthCheckRequest) -> HealthCheckResponse {
@@ -140,6 +300,14 @@ pub trait ProviderHandler: Sync {
         }
     }
 
+    /// Provider should apply updated provider-level configuration (e.g. rotated credentials or
+    /// changed endpoints) delivered at runtime, without restarting. Return false if the
+    /// configuration is invalid or could not be applied, in which case the previous configuration
+    /// should remain in use
+    async fn update_config(&self, _values: &HashMap<String, String>) -> bool {
+        true
+    }
+
     /// Handle system shutdown message
     async fn shutdown(&self) {}
 }
@@ -0,0 +1,125 @@
+//! Typed configuration of links, parsed from the values of link definitions.
+//!
+//! Rather than looking up and parsing [`LinkDefinition::values`] by hand, providers can derive
+//! [`LinkConfig`] for a struct declaring their settings:
+//!
+//! ```ignore
+//! use wasmcloud_provider_sdk::link_config::LinkConfig;
+//!
+//! #[derive(LinkConfig)]
+//! struct Config {
+//!     /// Address of the server
+//!     #[link_config(env = "SERVER_ADDR", default = "http://127.0.0.1:8200")]
+//!     addr: url::Url,
+//!     /// Token to authenticate with
+//!     #[link_config(env = "SERVER_TOKEN")]
+//!     token: String,
+//!     /// Maximum number of connections, at least 1
+//!     #[link_config(validate = validate_max_connections)]
+//!     max_connections: Option<u32>,
+//!     /// Comma-separated list of certificate files
+//!     certs: Vec<String>,
+//! }
+//!
+//! fn validate_max_connections(max: &u32) -> Result<(), &'static str> {
+//!     if *max == 0 { Err("must be at least 1") } else { Ok(()) }
+//! }
+//!
+//! async fn put_link(&self, ld: &LinkDefinition) -> bool {
+//!     let config = match Config::from_link(ld) {
+//!         Ok(config) => config,
+//!         Err(err) => {
+//!             error!(%err, "invalid link configuration");
+//!             return false;
+//!         }
+//!     };
+//!     ...
+//! }
+//! ```
+//!
+//! Each field is set from the link definition value named like the field (or its uppercase
+//! form), the environment variable set with `env`, or the `default`, in that order, and parsed
+//! with [`FromStr`]. Fields are required unless they have a default, are an `Option` or are a
+//! `Vec`, which is parsed from a comma-separated list. All invalid fields are reported at once.
+
+use std::{borrow::Cow, collections::HashMap, fmt::Display, str::FromStr};
+
+use wasmcloud_core::LinkDefinition;
+
+pub use crate::error::{FieldError, LinkConfigError};
+pub use wasmcloud_provider_sdk_macros::LinkConfig;
+
+/// Description of a setting of a link configuration
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LinkConfigField {
+    /// Name of the link definition value the setting is read from
+    pub name: &'static str,
+    /// Environment variable the setting is read from if the link definition does not set it
+    pub env: Option<&'static str>,
+    /// Value used if neither the link definition nor the environment set the setting
+    pub default: Option<&'static str>,
+    /// Whether the setting must be set
+    pub required: bool,
+    /// Documentation of the setting, from the doc comment of the field
+    pub doc: &'static str,
+}
+
+/// Configuration of a link, usually derived with `#[derive(LinkConfig)]`
+pub trait LinkConfig: Sized {
+    /// Returns descriptions of all settings of the configuration
+    fn fields() -> &'static [LinkConfigField];
+
+    /// Parses the configuration from link definition values, falling back to environment
+    /// variables and defaults. Fails listing all missing and invalid settings
+    fn from_values(values: &HashMap<String, String>) -> Result<Self, LinkConfigError>;
+
+    /// Parses the configuration from the values of the link definition `ld`
+    fn from_link(ld: &LinkDefinition) -> Result<Self, LinkConfigError> {
+        Self::from_values(&ld.values.iter().cloned().collect())
+    }
+}
+
+/// Looks up the raw value of the setting `name`, see [`LinkConfig::from_values`]
+#[doc(hidden)]
+pub fn lookup<'a>(
+    values: &'a HashMap<String, String>,
+    name: &str,
+    env: Option<&str>,
+) -> Option<Cow<'a, str>> {
+    values
+        .get(name)
+        .or_else(|| values.get(&name.to_ascii_uppercase()))
+        .map(|value| Cow::Borrowed(value.as_str()))
+        .or_else(|| env.and_then(|env| std::env::var(env).ok()).map(Cow::Owned))
+}
+
+/// Parses the raw value of the setting `field`, recording an error if it is invalid
+#[doc(hidden)]
+pub fn parse_value<T>(field: &'static str, raw: &str, errors: &mut Vec<FieldError>) -> Option<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    match raw.parse() {
+        Ok(value) => Some(value),
+        Err(err) => {
+            errors.push(FieldError::invalid(field, err));
+            None
+        }
+    }
+}
+
+/// Parses the raw comma-separated list of the setting `field`, recording an error if any of its
+/// items is invalid
+#[doc(hidden)]
+pub fn parse_list<T>(field: &'static str, raw: &str, errors: &mut Vec<FieldError>) -> Option<Vec<T>>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    raw.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| parse_value(field, item, errors))
+        .collect()
+}
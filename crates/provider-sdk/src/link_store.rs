@@ -0,0 +1,75 @@
+//! Link definitions of the actors linked to a provider, kept up to date by the
+//! [`ProviderConnection`](crate::ProviderConnection) as links are put and deleted
+
+use std::{collections::HashMap, sync::Arc};
+
+use tokio::sync::RwLock;
+
+use crate::core::LinkDefinition;
+
+/// Link definitions of the actors linked to the provider, keyed by the lattice the link was put
+/// in and the actor ID. Providers serving additional lattices (see
+/// [`HostData::additional_lattice_prefixes`](crate::core::HostData::additional_lattice_prefixes))
+/// keep the links of an actor linked in more than one lattice apart this way.
+///
+/// The store of a running provider is available via
+/// [`ProviderConnection::link_store`](crate::ProviderConnection::link_store). Before the
+/// provider receives any invocations, the store is populated with the link definitions the host
+/// currently has for the provider
+#[derive(Clone, Debug, Default)]
+pub struct LinkStore {
+    links: Arc<RwLock<HashMap<(String, String), LinkDefinition>>>,
+}
+
+impl LinkStore {
+    /// Returns the link definition of the actor with ID `actor_id` in the lattice `lattice`, if
+    /// it is linked
+    pub async fn get(&self, lattice: &str, actor_id: &str) -> Option<LinkDefinition> {
+        self.links
+            .read()
+            .await
+            .get(&(lattice.to_string(), actor_id.to_string()))
+            .cloned()
+    }
+
+    /// Returns true if the actor with ID `actor_id` is linked in the lattice `lattice`
+    pub async fn contains(&self, lattice: &str, actor_id: &str) -> bool {
+        self.links
+            .read()
+            .await
+            .contains_key(&(lattice.to_string(), actor_id.to_string()))
+    }
+
+    /// Returns the link definitions of all linked actors, in all lattices
+    pub async fn all(&self) -> Vec<LinkDefinition> {
+        self.links.read().await.values().cloned().collect()
+    }
+
+    /// Returns the link definitions of the actors linked in the lattice `lattice`
+    pub async fn in_lattice(&self, lattice: &str) -> Vec<LinkDefinition> {
+        self.links
+            .read()
+            .await
+            .iter()
+            .filter(|((link_lattice, _), _)| link_lattice == lattice)
+            .map(|(_, ld)| ld.clone())
+            .collect()
+    }
+
+    /// Stores the link definition of the actor in the lattice `lattice`, replacing any previous
+    /// one
+    pub(crate) async fn insert(&self, lattice: &str, ld: LinkDefinition) {
+        self.links
+            .write()
+            .await
+            .insert((lattice.to_string(), ld.actor_id.clone()), ld);
+    }
+
+    /// Removes the link definition of the actor with ID `actor_id` in the lattice `lattice`
+    pub(crate) async fn remove(&self, lattice: &str, actor_id: &str) {
+        self.links
+            .write()
+            .await
+            .remove(&(lattice.to_string(), actor_id.to_string()));
+    }
+}
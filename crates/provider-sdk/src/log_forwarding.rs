@@ -0,0 +1,245 @@
+//! Forwarding of provider log records to the host over the lattice
+
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::time::{Duration, Instant};
+
+use tokio::sync::mpsc;
+use tracing::field::{Field, Visit};
+use tracing::{warn, Event, Subscriber};
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::Layer;
+
+use wasmcloud_core::logging::ForwardedLogRecord;
+use wasmcloud_core::HostData;
+
+/// Maximum number of records buffered before new records are dropped
+const BUFFER_SIZE: usize = 1024;
+
+/// Targets, which are never forwarded to avoid forwarding the logs produced by forwarding itself
+const IGNORED_TARGETS: [&str; 2] = ["async_nats", module_path!()];
+
+/// Minimum interval between warnings about records, which could not be forwarded
+const FAILURE_WARN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A [`Layer`], which sends enabled events to a channel to be published to the host
+pub struct ForwardingLayer {
+    provider_id: String,
+    link_name: String,
+    instance_id: String,
+    records: mpsc::Sender<ForwardedLogRecord>,
+}
+
+impl ForwardingLayer {
+    /// Construct a new [`ForwardingLayer`] for the provider described by `host_data` and return
+    /// it along with the receiving end of the record channel
+    pub fn new(host_data: &HostData) -> (Self, mpsc::Receiver<ForwardedLogRecord>) {
+        let (records, rx) = mpsc::channel(BUFFER_SIZE);
+        (
+            Self {
+                provider_id: host_data.provider_key.clone(),
+                link_name: host_data.link_name.clone(),
+                instance_id: host_data.instance_id.clone(),
+                records,
+            },
+            rx,
+        )
+    }
+}
+
+#[derive(Default)]
+struct FieldVisitor {
+    message: String,
+    fields: HashMap<String, String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message = value.to_string();
+        } else {
+            self.fields
+                .insert(field.name().to_string(), value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.message = format!("{value:?}");
+        } else {
+            self.fields
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+}
+
+#[cfg(feature = "otel")]
+fn current_span_context() -> (Option<String>, Option<String>) {
+    use opentelemetry::trace::TraceContextExt;
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    let ctx = tracing::Span::current().context();
+    let span = ctx.span();
+    let span_ctx = span.span_context();
+    if span_ctx.is_valid() {
+        (
+            Some(span_ctx.trace_id().to_string()),
+            Some(span_ctx.span_id().to_string()),
+        )
+    } else {
+        (None, None)
+    }
+}
+
+#[cfg(not(feature = "otel"))]
+fn current_span_context() -> (Option<String>, Option<String>) {
+    (None, None)
+}
+
+impl<S: Subscriber> Layer<S> for ForwardingLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let target = metadata.target();
+        if IGNORED_TARGETS
+            .iter()
+            .any(|ignored| target.starts_with(ignored))
+        {
+            return;
+        }
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+        let (trace_id, span_id) = current_span_context();
+        // Records are dropped rather than blocking the emitter if the host cannot keep up
+        let _ = self.records.try_send(ForwardedLogRecord {
+            provider_id: self.provider_id.clone(),
+            link_name: self.link_name.clone(),
+            instance_id: self.instance_id.clone(),
+            level: (*metadata.level()).into(),
+            target: target.to_string(),
+            message: visitor.message,
+            fields: visitor.fields,
+            trace_id,
+            span_id,
+        });
+    }
+}
+
+/// Counts records, which could not be forwarded, so that a failing connection to the host is
+/// reported periodically instead of once per record
+#[derive(Debug, Default)]
+struct Failures {
+    count: u64,
+    last_reported: Option<Instant>,
+}
+
+impl Failures {
+    /// Count a failure at `now`, returning the number of failures to report, if a report is due
+    fn record(&mut self, now: Instant) -> Option<u64> {
+        self.count += 1;
+        match self.last_reported {
+            Some(last) if now.duration_since(last) < FAILURE_WARN_INTERVAL => None,
+            _ => {
+                self.last_reported = Some(now);
+                Some(std::mem::take(&mut self.count))
+            }
+        }
+    }
+}
+
+/// Publish records received on `records` to the host until the channel is closed
+pub(crate) async fn forward(
+    nats: async_nats::Client,
+    subject: String,
+    mut records: mpsc::Receiver<ForwardedLogRecord>,
+) {
+    let mut failures = Failures::default();
+    while let Some(record) = records.recv().await {
+        let res = match serde_json::to_vec(&record) {
+            Ok(payload) => nats
+                .publish(subject.clone(), payload.into())
+                .await
+                .map_err(|e| e.to_string()),
+            Err(e) => Err(e.to_string()),
+        };
+        if let Err(error) = res {
+            // Events of this module are never forwarded, so this cannot feed back into the channel
+            if let Some(count) = failures.record(Instant::now()) {
+                warn!(count, error, "failed to forward log records to host");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tracing::{info, Level};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    fn forwarding_layer() -> (ForwardingLayer, mpsc::Receiver<ForwardedLogRecord>) {
+        ForwardingLayer::new(&HostData {
+            provider_key: "VPROVIDER".into(),
+            link_name: "default".into(),
+            instance_id: "instance".into(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn forwards_events() {
+        let (layer, mut records) = forwarding_layer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            info!(key = "value", count = 2, "hello");
+            tracing::event!(target: "async_nats", Level::INFO, "ignored");
+            tracing::event!(target: module_path!(), Level::WARN, "ignored");
+        });
+
+        let record = records.try_recv().expect("record not forwarded");
+        assert_eq!(record.provider_id, "VPROVIDER");
+        assert_eq!(record.link_name, "default");
+        assert_eq!(record.instance_id, "instance");
+        assert!(matches!(record.level, wasmcloud_core::logging::Level::Info));
+        assert_eq!(record.message, "hello");
+        assert_eq!(record.fields.get("key").map(String::as_str), Some("value"));
+        assert_eq!(record.fields.get("count").map(String::as_str), Some("2"));
+        assert!(
+            records.try_recv().is_err(),
+            "ignored targets were forwarded"
+        );
+    }
+
+    #[test]
+    fn drops_records_when_full() {
+        let (layer, mut records) = forwarding_layer();
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            for i in 0..BUFFER_SIZE + 10 {
+                info!(i, "record");
+            }
+        });
+        let mut received = 0;
+        while records.try_recv().is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, BUFFER_SIZE);
+    }
+
+    #[test]
+    fn failures_are_reported_periodically() {
+        let mut failures = Failures::default();
+        let start = Instant::now();
+        assert_eq!(failures.record(start), Some(1));
+        assert_eq!(failures.record(start + Duration::from_secs(1)), None);
+        assert_eq!(failures.record(start + Duration::from_secs(2)), None);
+        assert_eq!(
+            failures.record(start + FAILURE_WARN_INTERVAL + Duration::from_secs(1)),
+            Some(3)
+        );
+        assert_eq!(
+            failures.record(start + FAILURE_WARN_INTERVAL + Duration::from_secs(2)),
+            None
+        );
+    }
+}
@@ -0,0 +1,45 @@
+//! Initialization of the logging of providers.
+//!
+//! Providers started with [`start_provider`](crate::start_provider) or
+//! [`run_provider`](crate::run_provider) have logging configured as requested by the host, so
+//! they only need to log with the [`tracing`] macros. Events emitted while an invocation is
+//! dispatched are recorded within spans carrying the fields `provider_id`, `link_name`,
+//! `lattice_id` and `inv_id`, which are included in every JSON log line of such an event.
+
+use wasmcloud_core::HostData;
+use wasmcloud_tracing::ExtraLayer;
+
+use crate::error::{ProviderError, ProviderResult};
+
+/// Configures the global `tracing` subscriber of the provider to log events to stderr as JSON,
+/// at the log level set by the host in `host_data` (or the `RUST_LOG` environment variable).
+///
+/// Spans are exported as configured by the OpenTelemetry settings in `host_data`, under the
+/// service name `service_name`. This is only needed by providers not started via
+/// [`start_provider`](crate::start_provider) or [`run_provider`](crate::run_provider), which
+/// configure logging themselves.
+///
+/// # Errors
+///
+/// Returns an error if a global subscriber was already configured
+pub fn init_logging(host_data: &HostData, service_name: impl Into<String>) -> ProviderResult<()> {
+    configure(host_data, service_name.into(), true, None)
+}
+
+/// Configures the global `tracing` subscriber of the provider, logging events as JSON if `json`
+/// is set, and installing `extra_layer` if any
+pub(crate) fn configure(
+    host_data: &HostData,
+    service_name: String,
+    json: bool,
+    extra_layer: Option<ExtraLayer>,
+) -> ProviderResult<()> {
+    wasmcloud_tracing::configure_tracing_with_layer(
+        service_name,
+        &host_data.otel_config.clone().with_env_overrides(),
+        json,
+        host_data.log_level.as_ref(),
+        extra_layer,
+    )
+    .map_err(|e| ProviderError::Initialization(format!("failed to configure logging: {e:#}")))
+}
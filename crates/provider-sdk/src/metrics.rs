@@ -0,0 +1,225 @@
+//! Metrics of invocations handled by a provider, served in the Prometheus text format on the
+//! port set in [`HostData::metrics_port`](crate::core::HostData::metrics_port)
+
+use std::{collections::BTreeMap, fmt::Write, net::SocketAddr, sync::Arc, time::Duration};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    task::JoinHandle,
+};
+use tracing::{debug, error, info};
+
+use crate::{
+    error::{ProviderError, ProviderResult},
+    provider::QuitSignal,
+};
+
+/// Path metrics are served on
+pub const METRICS_PATH: &str = "/metrics";
+
+/// Upper bounds of the buckets of the invocation duration histogram, in seconds
+const DURATION_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Maximum size of a scrape request, which is not expected to have a body
+const MAX_REQUEST_LEN: usize = 8192;
+
+/// Time to wait for a scrape request to be received
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Metrics of the invocations of a single lattice method
+#[derive(Debug, Default)]
+struct MethodMetrics {
+    invocations: u64,
+    errors: u64,
+    /// Cumulative counts of invocations per bucket of [`DURATION_BUCKETS`]
+    duration_buckets: [u64; DURATION_BUCKETS.len()],
+    duration_sum: f64,
+}
+
+/// Metrics defined by a provider (ex. sizes of batches sent to its backend), which are served
+/// along with the invocation metrics, see
+/// [`ConnectionConfig::with_metrics_collector`](crate::ConnectionConfig::with_metrics_collector)
+pub trait MetricsCollector: Send + Sync + 'static {
+    /// Appends the metrics in the Prometheus text exposition format to `out`
+    fn render(&self, out: &mut String);
+}
+
+/// Counts of invocations, errors and histograms of invocation durations per lattice method
+#[derive(Debug, Default)]
+pub struct InvocationMetrics {
+    methods: std::sync::Mutex<BTreeMap<String, MethodMetrics>>,
+}
+
+impl InvocationMetrics {
+    /// Records an invocation of `method` which took `duration` to handle
+    pub fn record(&self, method: &str, duration: Duration, failed: bool) {
+        let mut methods = self.methods.lock().unwrap_or_else(|e| e.into_inner());
+        let metrics = methods.entry(method.to_string()).or_default();
+        metrics.invocations += 1;
+        if failed {
+            metrics.errors += 1;
+        }
+        let secs = duration.as_secs_f64();
+        metrics.duration_sum += secs;
+        for (count, le) in metrics.duration_buckets.iter_mut().zip(DURATION_BUCKETS) {
+            if secs <= le {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let methods = self.methods.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        // Writing to a `String` cannot fail
+        let _ = writeln!(
+            out,
+            "# HELP wasmcloud_provider_invocations_total Number of invocations handled by the provider\n\
+             # TYPE wasmcloud_provider_invocations_total counter"
+        );
+        for (method, metrics) in methods.iter() {
+            let _ = writeln!(
+                out,
+                "wasmcloud_provider_invocations_total{{method=\"{}\"}} {}",
+                escape_label(method),
+                metrics.invocations
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP wasmcloud_provider_invocation_errors_total Number of invocations the provider failed to handle\n\
+             # TYPE wasmcloud_provider_invocation_errors_total counter"
+        );
+        for (method, metrics) in methods.iter() {
+            let _ = writeln!(
+                out,
+                "wasmcloud_provider_invocation_errors_total{{method=\"{}\"}} {}",
+                escape_label(method),
+                metrics.errors
+            );
+        }
+        let _ = writeln!(
+            out,
+            "# HELP wasmcloud_provider_invocation_duration_seconds Time taken to handle invocations\n\
+             # TYPE wasmcloud_provider_invocation_duration_seconds histogram"
+        );
+        for (method, metrics) in methods.iter() {
+            let method = escape_label(method);
+            for (count, le) in metrics.duration_buckets.iter().zip(DURATION_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "wasmcloud_provider_invocation_duration_seconds_bucket{{method=\"{method}\",le=\"{le}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "wasmcloud_provider_invocation_duration_seconds_bucket{{method=\"{method}\",le=\"+Inf\"}} {}\n\
+                 wasmcloud_provider_invocation_duration_seconds_sum{{method=\"{method}\"}} {}\n\
+                 wasmcloud_provider_invocation_duration_seconds_count{{method=\"{method}\"}} {}",
+                metrics.invocations, metrics.duration_sum, metrics.invocations
+            );
+        }
+        out
+    }
+}
+
+/// Escapes a label value as required by the Prometheus text format
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Serves `metrics` followed by those of `collectors` on [`METRICS_PATH`] on `port` on all
+/// interfaces, until a signal is received on the quit channel
+pub(crate) async fn serve(
+    port: u16,
+    metrics: Arc<InvocationMetrics>,
+    collectors: Arc<[Arc<dyn MetricsCollector>]>,
+    mut quit: QuitSignal,
+) -> ProviderResult<JoinHandle<()>> {
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    let listener = TcpListener::bind(addr).await.map_err(|e| {
+        ProviderError::Initialization(format!("failed to listen for metrics on {addr}: {e}"))
+    })?;
+    info!(%addr, "serving metrics");
+    Ok(tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = quit.recv() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, peer)) => {
+                        let metrics = metrics.clone();
+                        let collectors = collectors.clone();
+                        tokio::spawn(async move {
+                            if let Err(err) = handle_scrape(stream, &metrics, &collectors).await {
+                                debug!(%err, %peer, "failed to serve metrics");
+                            }
+                        });
+                    }
+                    Err(err) => error!(%err, "failed to accept metrics connection"),
+                },
+            }
+        }
+    }))
+}
+
+/// Responds to a single HTTP request for metrics, closing the connection afterwards
+async fn handle_scrape(
+    mut stream: TcpStream,
+    metrics: &InvocationMetrics,
+    collectors: &[Arc<dyn MetricsCollector>],
+) -> std::io::Result<()> {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    let read = async {
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_LEN {
+            let n = stream.read(&mut buf).await?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        std::io::Result::Ok(())
+    };
+    tokio::time::timeout(REQUEST_TIMEOUT, read)
+        .await
+        .map_err(|_| std::io::ErrorKind::TimedOut)??;
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .split_whitespace();
+    let method = request_line.next();
+    let path = request_line
+        .next()
+        .map(|path| path.split('?').next().unwrap_or_default());
+    let (status, content_type, body) = match (method, path) {
+        (Some("GET"), Some(METRICS_PATH)) => {
+            let mut body = metrics.render();
+            for collector in collectors {
+                collector.render(&mut body);
+            }
+            ("200 OK", "text/plain; version=0.0.4; charset=utf-8", body)
+        }
+        (Some("GET"), _) => ("404 Not Found", "text/plain", "not found\n".to_string()),
+        _ => (
+            "405 Method Not Allowed",
+            "text/plain",
+            "method not allowed\n".to_string(),
+        ),
+    };
+    let response = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
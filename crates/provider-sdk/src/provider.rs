@@ -1,9 +1,15 @@
-use std::{borrow::Cow, collections::HashMap, fmt::Formatter, sync::Arc, time::Duration};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Formatter,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
 
 use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use tokio::{
-    sync::{Mutex, RwLock},
+    sync::{Mutex, OwnedSemaphorePermit, RwLock, Semaphore},
     task::JoinHandle,
 };
 use tracing::{debug, error, info, instrument, trace, warn};
@@ -14,23 +20,40 @@ use wascap::{
 };
 
 use wasmcloud_core::{
-    HealthCheckRequest, HostData, Invocation, InvocationResponse, LinkDefinition,
+    body_stream::BodyStreamEndpoint,
+    negotiate_schema_version, provider_config_subject, provider_links_subject,
+    redact::{Redactor, REDACTED},
+    ClusterIssuers, ClusterIssuersUpdate, HealthCheckRequest, HealthCheckResponse, HostData,
+    Invocation, InvocationErrorKind, InvocationResponse, LinkDefinition, ProviderConfigUpdate,
 };
 #[cfg(feature = "otel")]
 use wasmcloud_tracing::context::attach_span_context;
 
 use crate::{
+    codec::{self, Codec},
     deserialize,
     error::{
         InvocationError, ProviderError, ProviderInvocationError, ProviderResult, ValidationError,
     },
+    event,
+    link_store::LinkStore,
+    metrics::{self, InvocationMetrics, MetricsCollector},
+    provider_main::ConnectionConfig,
+    rate_limit::RateLimiter,
     rpc_client::RpcClient,
-    serialize, Context, Provider,
+    secrets::SecretsClient,
+    serialize,
+    supervisor::TaskSupervisor,
+    Context, InvocationValidator, Provider,
 };
 
 // name of nats queue group for rpc subscription
 const RPC_SUBSCRIPTION_QUEUE_GROUP: &str = "rpc";
 
+/// Maximum length of a payload recorded in a trace when payload capture is enabled, longer
+/// payloads are truncated
+const MAX_CAPTURED_PAYLOAD_LEN: usize = 4096;
+
 pub type QuitSignal = tokio::sync::broadcast::Receiver<bool>;
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -71,10 +94,26 @@ macro_rules! process_until_quit {
 
 #[derive(Clone)]
 pub struct ProviderConnection {
-    links: Arc<RwLock<HashMap<String, LinkDefinition>>>,
+    links: LinkStore,
     rpc_client: RpcClient,
     lattice_prefix: String,
     host_data: Arc<HostData>,
+    redactor: Arc<Redactor>,
+    /// Cluster issuers to accept invocations from, initially those in [`HostData`], which the
+    /// host updates when the cluster key is rotated
+    cluster_issuers: Arc<RwLock<ClusterIssuers>>,
+    /// Limits the number of received invocations handled concurrently, if configured
+    invocation_limiter: Option<Arc<Semaphore>>,
+    /// Metrics of handled invocations, collected if a metrics port is set in [`HostData`]
+    metrics: Option<Arc<InvocationMetrics>>,
+    /// Metrics of the provider, which are served along with the invocation metrics
+    metrics_collectors: Arc<[Arc<dyn MetricsCollector>]>,
+    /// Validators of received invocations, in addition to the validation performed by the SDK
+    invocation_validators: Arc<[Arc<dyn InvocationValidator>]>,
+    /// Limits the rate of invocations received from each actor, as set by its link definition
+    rate_limiter: Arc<RateLimiter>,
+    /// Background tasks of the provider, which are cancelled on shutdown
+    task_supervisor: TaskSupervisor,
     // We keep these around so they can drop
     _listener_handles: Arc<Mutex<Vec<tokio::task::JoinHandle<()>>>>,
 }
@@ -91,14 +130,24 @@ impl std::fmt::Debug for ProviderConnection {
 }
 
 impl ProviderConnection {
+    /// Constructs a connection sending and receiving over the NATS clients in `pool`, the first of
+    /// which is used for everything else (ex. link definitions, chunking)
     pub(crate) fn new(
-        nats: async_nats::Client,
+        pool: Vec<async_nats::Client>,
         host_data: &HostData,
+        config: &ConnectionConfig,
     ) -> ProviderResult<ProviderConnection> {
+        let mut pool = pool.into_iter();
+        let nats = pool.next().ok_or_else(|| {
+            ProviderError::Initialization("no NATS connection to the lattice".to_string())
+        })?;
         let key = Arc::new(
             KeyPair::from_seed(&host_data.invocation_seed)
                 .map_err(|e| ProviderError::Initialization(format!("key failure: {e}")))?,
         );
+        let schema_version =
+            negotiate_schema_version(host_data.schema_version, host_data.min_schema_version)
+                .map_err(|e| ProviderError::Initialization(format!("incompatible host: {e:#}")))?;
 
         let rpc_client = RpcClient::new(
             nats,
@@ -106,38 +155,192 @@ impl ProviderConnection {
             host_data.default_rpc_timeout_ms.map(Duration::from_millis),
             key,
             &host_data.lattice_rpc_prefix,
-        );
+        )
+        .with_invocation_validity(host_data.invocation_validity)
+        .with_schema_version(schema_version)
+        .with_inline_chunking(host_data.inline_chunking)
+        .with_connection_pool(pool);
 
         Ok(ProviderConnection {
-            links: Arc::new(RwLock::new(HashMap::new())),
+            links: LinkStore::default(),
             rpc_client,
             lattice_prefix: host_data.lattice_rpc_prefix.to_owned(),
             host_data: Arc::new(host_data.to_owned()),
+            redactor: Arc::new(Redactor::default().with_patterns(&host_data.secret_patterns)),
+            cluster_issuers: Arc::new(RwLock::new(host_data.cluster_issuers.clone())),
+            invocation_limiter: config
+                .max_concurrent_invocations
+                .map(|max| Arc::new(Semaphore::new(max.get()))),
+            metrics: host_data.metrics_port.map(|_| Arc::default()),
+            metrics_collectors: config.metrics_collectors.iter().cloned().collect(),
+            invocation_validators: config.invocation_validators.iter().cloned().collect(),
+            rate_limiter: Arc::default(),
+            task_supervisor: TaskSupervisor::default(),
             _listener_handles: Default::default(),
         })
     }
 
+    /// Sends the requests and messages of this connection on the fake lattice `lattice` instead of
+    /// NATS, see [`crate::testkit`]
+    #[cfg(any(test, feature = "testkit"))]
+    #[must_use]
+    pub(crate) fn with_fake_lattice(mut self, lattice: crate::testkit::FakeLattice) -> Self {
+        self.rpc_client = self.rpc_client.with_fake_lattice(lattice);
+        self
+    }
+
     /// Used for fetching the RPC client in order to make RPC calls
     pub fn get_rpc_client(&self) -> RpcClient {
         self.rpc_client.clone()
     }
 
-    /// Stores actor with link definition
-    pub async fn put_link(&self, ld: LinkDefinition) {
-        let mut update = self.links.write().await;
-        update.insert(ld.actor_id.to_string(), ld);
+    /// Used for fetching the RPC client in order to make RPC calls in the lattice with prefix
+    /// `lattice`, or the lattice of the host if `None`. Invocations of actors linked in an
+    /// additional lattice must be sent in that lattice
+    pub fn get_lattice_rpc_client(&self, lattice: Option<&str>) -> RpcClient {
+        match lattice {
+            Some(lattice) if lattice != self.lattice_prefix => {
+                self.rpc_client.with_lattice(lattice)
+            }
+            _ => self.rpc_client.clone(),
+        }
+    }
+
+    /// Used for streaming bodies to and from actors, instead of buffering them in invocations
+    pub fn body_stream_endpoint(&self) -> BodyStreamEndpoint {
+        BodyStreamEndpoint::new(&self.lattice_prefix, self.rpc_client.client())
+    }
+
+    /// Used for resolving secret references in link definition values, e.g. in
+    /// [`ProviderHandler::put_link`](crate::ProviderHandler::put_link)
+    pub fn secrets_client(&self) -> SecretsClient {
+        let client = SecretsClient::new(
+            self.rpc_client.client(),
+            &self.lattice_prefix,
+            &self.host_data.provider_key,
+            self.rpc_client.cluster_key(),
+            self.host_data
+                .default_rpc_timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(crate::DEFAULT_RPC_TIMEOUT_MILLIS),
+        );
+        #[cfg(any(test, feature = "testkit"))]
+        if let Some(lattice) = self.rpc_client.fake_lattice() {
+            return client.with_fake_lattice(lattice.clone());
+        }
+        client
+    }
+
+    /// Used for redacting sensitive link definition values before logging them
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    /// Used for spawning background tasks, which are cancelled and awaited when the provider shuts
+    /// down
+    pub fn task_supervisor(&self) -> &TaskSupervisor {
+        &self.task_supervisor
+    }
+
+    /// Returns the cluster issuers invocations are currently accepted from
+    pub async fn cluster_issuers(&self) -> ClusterIssuers {
+        self.cluster_issuers.read().await.clone()
+    }
+
+    /// Used for looking up the link definitions of actors linked to the provider
+    pub fn link_store(&self) -> &LinkStore {
+        &self.links
+    }
+
+    /// Stores actor with link definition put in the lattice `lattice`, and limits the rate of its
+    /// invocations as set by the link definition
+    pub async fn put_link(&self, lattice: &str, ld: LinkDefinition) {
+        self.rate_limiter.set_limit(lattice, &ld);
+        self.links.insert(lattice, ld).await
     }
 
-    /// Deletes link
-    pub async fn delete_link(&self, actor_id: &str) {
-        let mut update = self.links.write().await;
-        update.remove(actor_id);
+    /// Deletes link of the actor in the lattice `lattice`
+    pub async fn delete_link(&self, lattice: &str, actor_id: &str) {
+        self.rate_limiter.remove_limit(lattice, actor_id);
+        self.links.remove(lattice, actor_id).await
     }
 
-    /// Returns true if the actor is linked
+    /// Returns the link definition of the actor in the lattice of the host, if it is linked. See
+    /// [`LinkStore::get`] for links in additional lattices
+    pub async fn get_link(&self, actor_id: &str) -> Option<LinkDefinition> {
+        self.links.get(&self.lattice_prefix, actor_id).await
+    }
+
+    /// Returns true if the actor is linked in the lattice of the host. See
+    /// [`LinkStore::contains`] for links in additional lattices
     pub async fn is_linked(&self, actor_id: &str) -> bool {
-        let read = self.links.read().await;
-        read.contains_key(actor_id)
+        self.links.contains(&self.lattice_prefix, actor_id).await
+    }
+
+    /// Fetches the current link definitions of the provider from the host, and puts those of
+    /// actors that are not linked yet. This closes the gap between the host preparing the
+    /// initial link definitions in [`HostData`] and the provider subscribing for link updates.
+    ///
+    /// Failing to fetch the link definitions (ex. from hosts which do not serve them) is not
+    /// fatal, in which case the provider relies on the initial link definitions alone
+    async fn sync_links<P>(&self, provider: &P, lattice: &str)
+    where
+        P: Provider,
+    {
+        let topic = provider_links_subject(
+            lattice,
+            &self.host_data.provider_key,
+            &self.host_data.link_name,
+        );
+        debug!(%topic, "fetching current link definitions");
+        let timeout = self
+            .host_data
+            .default_rpc_timeout_ms
+            .map(Duration::from_millis)
+            .unwrap_or(crate::DEFAULT_RPC_TIMEOUT_MILLIS);
+        let links = match tokio::time::timeout(
+            timeout,
+            self.rpc_client.client().request(topic, Default::default()),
+        )
+        .await
+        {
+            Ok(Ok(msg)) => match deserialize::<Vec<LinkDefinition>>(&msg.payload) {
+                Ok(links) => links,
+                Err(err) => {
+                    warn!(%err, "received invalid link definitions from host");
+                    return;
+                }
+            },
+            Ok(Err(err)) => {
+                warn!(%err, "failed to fetch link definitions from host");
+                return;
+            }
+            Err(_) => {
+                warn!("timed out fetching link definitions from host");
+                return;
+            }
+        };
+        for ld in links {
+            if self.links.contains(lattice, &ld.actor_id).await {
+                continue;
+            }
+            // The host redacts sensitive values, which are not secret references
+            if ld.values.iter().any(|(_, v)| v == REDACTED) {
+                warn!(
+                    link_definition = ?self.redactor.link(&ld),
+                    "Skipping link fetched during provider startup with redacted values, use secret references for sensitive values",
+                );
+                continue;
+            }
+            if provider.put_lattice_link(lattice, &ld).await {
+                self.put_link(lattice, ld).await;
+            } else {
+                error!(
+                    link_definition = ?self.redactor.link(&ld),
+                    "Failed to initialize link fetched during provider startup",
+                );
+            }
+        }
     }
 
     /// Implement subscriber listener threads and provider callbacks
@@ -150,18 +353,36 @@ impl ProviderConnection {
     where
         P: Provider + Clone,
     {
-        let lattice = lattice.to_string();
         let mut handles = Vec::new();
+        // Links and invocations are served on the lattice of the host, and any further lattices
+        // the provider was asked to join
+        let mut lattices = vec![lattice.to_string()];
+        for additional in &self.host_data.additional_lattice_prefixes {
+            if !lattices.contains(additional) {
+                lattices.push(additional.clone());
+            }
+        }
+        for lattice in lattices {
+            // Subscribe for link updates before fetching the current link definitions, and fetch
+            // them before subscribing for invocations, so that no invocation is received from an
+            // actor whose link is yet unknown
+            handles.push(
+                self.subscribe_link_put(provider.clone(), shutdown_tx.subscribe(), &lattice)
+                    .await?,
+            );
+            handles.push(
+                self.subscribe_link_del(provider.clone(), shutdown_tx.subscribe(), &lattice)
+                    .await?,
+            );
+            self.sync_links(&provider, &lattice).await;
+            handles.push(
+                self.subscribe_rpc(provider.clone(), shutdown_tx.subscribe(), lattice)
+                    .await?,
+            );
+        }
+        handles.push(self.subscribe_issuers(shutdown_tx.subscribe()).await?);
         handles.push(
-            self.subscribe_rpc(provider.clone(), shutdown_tx.subscribe(), lattice)
-                .await?,
-        );
-        handles.push(
-            self.subscribe_link_put(provider.clone(), shutdown_tx.subscribe())
-                .await?,
-        );
-        handles.push(
-            self.subscribe_link_del(provider.clone(), shutdown_tx.subscribe())
+            self.subscribe_config(provider.clone(), shutdown_tx.subscribe())
                 .await?,
         );
         handles.push(
@@ -172,6 +393,18 @@ impl ProviderConnection {
             self.subscribe_health(provider, shutdown_tx.subscribe())
                 .await?,
         );
+        if let (Some(port), Some(invocation_metrics)) = (self.host_data.metrics_port, &self.metrics)
+        {
+            handles.push(
+                metrics::serve(
+                    port,
+                    invocation_metrics.clone(),
+                    self.metrics_collectors.clone(),
+                    shutdown_tx.subscribe(),
+                )
+                .await?,
+            );
+        }
         let mut lock = self._listener_handles.lock().await;
         *lock = handles;
         Ok(())
@@ -190,28 +423,57 @@ impl ProviderConnection {
         )
     }
 
-    /// Subscribe to a nats topic for rpc messages.
-    /// This method starts a separate async task and returns immediately.
-    /// It will exit if the nats client disconnects, or if a signal is received on the quit channel.
+    /// Subscribe to a nats topic for rpc messages of the lattice with prefix `lattice`, on each
+    /// connection of the pool. This method starts a separate async task and returns immediately.
+    /// It will exit if the nats clients disconnect, or if a signal is received on the quit channel.
     pub async fn subscribe_rpc<P>(
         &self,
         provider: P,
-        mut quit: QuitSignal,
+        quit: QuitSignal,
         lattice: String,
     ) -> ProviderResult<JoinHandle<()>>
     where
         P: Provider + Clone,
     {
-        let mut sub = self
-            .rpc_client
-            .client()
-            .queue_subscribe(
-                self.provider_rpc_topic(),
-                RPC_SUBSCRIPTION_QUEUE_GROUP.to_string(),
-            )
-            .await?;
+        let mut listeners = Vec::new();
+        for client in self.rpc_client.connections() {
+            // All subscriptions are part of the same queue group, so each invocation is only
+            // received by one of them
+            let sub = client
+                .queue_subscribe(
+                    format!(
+                        "wasmbus.rpc.{lattice}.{}.{}",
+                        self.host_data.provider_key, self.host_data.link_name
+                    ),
+                    RPC_SUBSCRIPTION_QUEUE_GROUP.to_string(),
+                )
+                .await?;
+            listeners.push(self.process_rpc(
+                sub,
+                provider.clone(),
+                quit.resubscribe(),
+                lattice.clone(),
+            ));
+        }
+        Ok(tokio::spawn(async move {
+            futures::future::join_all(listeners).await;
+        }))
+    }
+
+    /// Handle the rpc messages received by `sub` in a separate async task, until the subscription
+    /// is closed or a signal is received on the quit channel
+    fn process_rpc<P>(
+        &self,
+        mut sub: async_nats::Subscriber,
+        provider: P,
+        mut quit: QuitSignal,
+        lattice: String,
+    ) -> JoinHandle<()>
+    where
+        P: Provider + Clone,
+    {
         let this = self.clone();
-        let handle = tokio::spawn(async move {
+        tokio::spawn(async move {
             loop {
                 tokio::select! {
                     _ = quit.recv() => {
@@ -220,10 +482,14 @@ impl ProviderConnection {
                     },
                     nats_msg = sub.next() => {
                         let msg = if let Some(msg) = nats_msg { msg } else { break; };
+                        // Once the limit of concurrent invocations is reached, wait for one of
+                        // them to complete, leaving further messages buffered in the subscription
+                        let permit = this.invocation_permit().await;
                         let this = this.clone();
                         let provider = provider.clone();
                         let lattice = lattice.clone();
-                        let span = tracing::debug_span!("rpc",
+                        // NOTE: see `attach_span_context` for why this span is enabled at info
+                        let span = tracing::info_span!("rpc",
                             operation = tracing::field::Empty,
                             lattice_id = tracing::field::Empty,
                             actor_id = tracing::field::Empty,
@@ -232,14 +498,21 @@ impl ProviderConnection {
                             provider_id = tracing::field::Empty,
                             contract_id = tracing::field::Empty,
                             link_name = tracing::field::Empty,
-                            payload_size = tracing::field::Empty
+                            payload_size = tracing::field::Empty,
+                            response_size = tracing::field::Empty,
+                            payload = tracing::field::Empty,
+                            response_payload = tracing::field::Empty
                         );
                         tokio::spawn( async move {
+                            let _permit = permit;
                             match deserialize::<Invocation>(&msg.payload) {
                                 Ok(inv) => {
                                     #[cfg(feature = "otel")]
                                     if !inv.trace_context.is_empty() {
                                         attach_span_context(&inv.trace_context);
+                                    } else {
+                                        // fall back on message headers set by older hosts
+                                        opentelemetry_nats::attach_span_context(&msg);
                                     }
                                     let current = tracing::Span::current();
                                     current.record("operation", &tracing::field::display(&inv.operation));
@@ -251,30 +524,12 @@ impl ProviderConnection {
                                     current.record("contract_id", &tracing::field::display(&inv.target.contract_id));
                                     current.record("link_name", &tracing::field::display(&inv.target.link_name));
                                     current.record("payload_size", &tracing::field::display(&inv.content_length));
-                                    let inv_id = inv.id.clone();
-                                    let inv_operation = inv.operation.clone();
-                                    let resp = match this.handle_rpc(provider.clone(), inv).in_current_span().await {
-                                        Err(err) => {
-                                            error!(%err, operation = %inv_operation, "Invocation failed");
-                                            InvocationResponse{
-                                                invocation_id: inv_id,
-                                                error: Some(format!("Error when handling invocation: {err}")),
-                                                ..Default::default()
-                                            }
-                                        },
-                                        Ok(bytes) => {
-                                            InvocationResponse{
-                                                invocation_id: inv_id,
-                                                content_length: bytes.len() as u64,
-                                                msg: bytes,
-                                                ..Default::default()
-                                            }
-                                        }
-                                    };
+                                    let origin = inv.origin.public_key.clone();
+                                    let resp = this.handle_invocation(provider.clone(), inv, &lattice).await;
                                     if let Some(reply) = msg.reply {
-                                        // send reply
-                                        if let Err(err) = this.rpc_client
-                                            .publish_invocation_response(reply, resp).in_current_span().await {
+                                        // send reply, chunking it in the lattice the invocation was received in
+                                        if let Err(err) = this.get_lattice_rpc_client(Some(&lattice))
+                                            .publish_invocation_response(reply, resp, &origin).in_current_span().await {
                                             error!(%err, "rpc sending response");
                                         }
                                     }
@@ -285,8 +540,10 @@ impl ProviderConnection {
                                         if let Err(err) = this.rpc_client.publish_invocation_response(reply,
                                             InvocationResponse{
                                                 error: Some(format!("Error when attempting to deserialize invocation: {err}")),
+                                                error_kind: Some(InvocationErrorKind::Malformed),
                                                 ..Default::default()
                                             },
+                                            "",
                                         ).in_current_span().await {
                                             error!(%err, "unable to publish invocation response error");
                                         }
@@ -297,39 +554,173 @@ impl ProviderConnection {
                     } /* next */
                 }
             } /* loop */
-        });
-        Ok(handle)
+        })
+    }
+
+    /// Returns a permit to handle a received invocation, once fewer than the configured maximum of
+    /// invocations are handled concurrently. Returns `None` if the number is not limited
+    pub(crate) async fn invocation_permit(&self) -> Option<OwnedSemaphorePermit> {
+        match &self.invocation_limiter {
+            Some(limiter) => limiter.clone().acquire_owned().await.ok(),
+            None => None,
+        }
+    }
+
+    /// Handles an invocation received in the lattice `lattice`, recording its metrics, and returns
+    /// the response to send to the caller
+    pub(crate) async fn handle_invocation<P>(
+        &self,
+        provider: P,
+        inv: Invocation,
+        lattice: &str,
+    ) -> InvocationResponse
+    where
+        P: Provider + Clone,
+    {
+        let inv_id = inv.id.clone();
+        let inv_operation = inv.operation.clone();
+        let inv_content_type = inv.content_type.clone();
+        let start = Instant::now();
+        let res = self
+            .handle_rpc(provider, inv, lattice)
+            .in_current_span()
+            .await;
+        if let Some(metrics) = &self.metrics {
+            metrics.record(&inv_operation, start.elapsed(), res.is_err());
+        }
+        match res {
+            Err(err) => {
+                error!(%err, operation = %inv_operation, "Invocation failed");
+                InvocationResponse {
+                    invocation_id: inv_id,
+                    error: Some(format!("Error when handling invocation: {err}")),
+                    error_kind: Some(err.kind()),
+                    ..Default::default()
+                }
+            }
+            Ok(bytes) => {
+                let current = tracing::Span::current();
+                current.record("response_size", bytes.len());
+                if self.host_data.capture_payloads {
+                    current.record(
+                        "response_payload",
+                        &tracing::field::display(capture_payload(&self.redactor, &bytes)),
+                    );
+                }
+                InvocationResponse {
+                    invocation_id: inv_id,
+                    content_length: bytes.len() as u64,
+                    msg: bytes,
+                    content_type: inv_content_type,
+                    ..Default::default()
+                }
+            }
+        }
     }
 
     async fn handle_rpc<P>(
         &self,
         provider: P,
         inv: Invocation,
+        lattice: &str,
     ) -> Result<Vec<u8>, ProviderInvocationError>
     where
         P: Provider + Clone,
     {
-        let inv = self.rpc_client.dechunk(inv).await?;
-        let (inv, claims) = self
-            .rpc_client
-            .validate_invocation(inv)
-            .await
-            .map_err(InvocationError::from)?;
-        self.validate_provider_invocation(&inv, &claims)
-            .await
-            .map_err(InvocationError::from)?;
-        let span = tracing::debug_span!("dispatch", public_key = %inv.origin.public_key, method = %inv.operation);
-        provider
-            .dispatch(
-                Context {
-                    actor: Some(inv.origin.public_key.clone()),
-                    tracing: inv.trace_context.into_iter().collect(),
-                },
-                inv.operation,
-                Cow::Owned(inv.msg),
-            )
+        let inv = self
+            .get_lattice_rpc_client(Some(lattice))
+            .dechunk(inv)
+            .await?;
+        if self.host_data.capture_payloads {
+            tracing::Span::current().record(
+                "payload",
+                &tracing::field::display(capture_payload(&self.redactor, &inv.msg)),
+            );
+        }
+        // The invocation is consumed by the validation, so the fields identifying it in the event
+        // published if it is rejected are kept beforehand
+        let (origin, target, operation, id) = (
+            inv.origin.clone(),
+            inv.target.clone(),
+            inv.operation.clone(),
+            inv.id.clone(),
+        );
+        let (inv, claims) = match self.verify_invocation(inv, lattice).await {
+            Ok(res) => res,
+            Err(err) => {
+                warn!(%err, "rejecting invocation that failed validation");
+                if let Err(err) = event::publish(
+                    &self.get_lattice_rpc_client(Some(lattice)),
+                    lattice,
+                    &self.host_data.provider_key,
+                    event::INVOCATION_AUTH_FAILED,
+                    event::invocation_auth_failed(&origin, &target, operation, id, &err),
+                )
+                .await
+                {
+                    warn!(%err, "failed to publish invocation auth failed event");
+                }
+                return Err(InvocationError::from(err).into());
+            }
+        };
+        if let Err(err) = self.rate_limiter.check(lattice, &inv.origin.public_key) {
+            warn!("rejecting invocation exceeding the rate limit of the actor");
+            return Err(err);
+        }
+        // Transcode payloads encoded differently than the provider expects, answering in the
+        // encoding of the invocation
+        let codecs = match (inv.content_type.as_deref(), provider.codec()) {
+            (Some(content_type), Some(provider_codec)) => {
+                let Some(inv_codec) = Codec::from_content_type(content_type) else {
+                    return Err(InvocationError::Malformed(format!(
+                        "unsupported content type `{content_type}`"
+                    ))
+                    .into());
+                };
+                (inv_codec != provider_codec).then_some((inv_codec, provider_codec))
+            }
+            _ => None,
+        };
+        let msg = match codecs {
+            Some((inv_codec, provider_codec)) => {
+                trace!(from = %inv_codec, to = %provider_codec, "transcoding invocation payload");
+                codec::transcode(&inv.msg, inv_codec, provider_codec)?
+            }
+            None => inv.msg,
+        };
+        // Repeat the identifying fields of the invocation, so that they are attached to every
+        // event logged by the provider while handling it
+        let span = tracing::debug_span!("dispatch",
+            public_key = %inv.origin.public_key,
+            method = %inv.operation,
+            provider_id = %self.host_data.provider_key,
+            link_name = %self.host_data.link_name,
+            lattice_id = %lattice,
+            inv_id = %inv.id,
+        );
+        let ctx = Context {
+            actor: Some(inv.origin.public_key.clone()),
+            tracing: inv.trace_context.into_iter().collect(),
+            actor_claims: claims
+                .metadata
+                .and_then(|md| md.origin_claims)
+                .unwrap_or_default(),
+            invocation_id: Some(inv.id),
+            lattice: Some(lattice.to_string()),
+        };
+        // Propagate the dispatch span, so that the trace continues from the provider to the
+        // services it calls while handling the invocation
+        let ctx = span.in_scope(|| ctx.with_current_span());
+        let resp = provider
+            .dispatch(ctx, inv.operation, Cow::Owned(msg))
             .instrument(span)
-            .await
+            .await?;
+        match codecs {
+            Some((inv_codec, provider_codec)) => {
+                Ok(codec::transcode(&resp, provider_codec, inv_codec)?)
+            }
+            None => Ok(resp),
+        }
     }
 
     async fn subscribe_shutdown<P>(
@@ -346,7 +737,7 @@ impl ProviderConnection {
         );
         debug!("subscribing for shutdown : {}", &shutdown_topic);
         let mut sub = self.rpc_client.client().subscribe(shutdown_topic).await?;
-        let rpc_client = self.rpc_client.clone();
+        let this = self.clone();
         let host_id = self.host_data.host_id.clone();
         let handle = tokio::spawn(
             async move {
@@ -365,9 +756,9 @@ impl ProviderConnection {
                             info!("Received termination signal and stopping");
                             // Tell provider to shutdown - before we shut down nats subscriptions,
                             // in case it needs to do any message passing during shutdown
-                            provider.shutdown().await;
+                            this.shutdown_provider(&provider).await;
                             let data = b"shutting down".to_vec();
-                            if let Err(err) = rpc_client.publish(reply_to, data).await {
+                            if let Err(err) = this.rpc_client.publish(reply_to, data).await {
                                 warn!(%err, "failed to send shutdown ack");
                             }
                             // unsubscribe from shutdown topic
@@ -393,31 +784,125 @@ impl ProviderConnection {
         Ok(handle)
     }
 
+    async fn subscribe_issuers(&self, mut quit: QuitSignal) -> ProviderResult<JoinHandle<()>> {
+        let topic = format!(
+            "wasmbus.rpc.{}.{}.{}.issuers.put",
+            &self.lattice_prefix, &self.host_data.provider_key, &self.host_data.link_name
+        );
+        debug!(%topic, "subscribing for cluster issuer updates");
+        let mut sub = self.rpc_client.client().subscribe(topic).await?;
+        let this = self.clone();
+        // Updates issued before the subscription are already reflected in the host data, so only
+        // newer ones are applied, which discards replayed updates
+        let mut issued_after = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|since_epoch| u64::try_from(since_epoch.as_nanos()).unwrap_or(u64::MAX))
+            .unwrap_or_default();
+        let handle = tokio::spawn(
+            async move {
+                process_until_quit!(sub, quit, msg, {
+                    match serde_json::from_slice::<ClusterIssuersUpdate>(&msg.payload) {
+                        Ok(update) if update.host_id == this.host_data.host_id => {
+                            if let Err(err) = update.verify(&this.host_data.host_id) {
+                                error!(%err, "rejected unverified cluster issuers update");
+                            } else if update.issued_at <= issued_after {
+                                warn!(
+                                    issued_at = update.issued_at,
+                                    "ignoring stale cluster issuers update"
+                                );
+                            } else {
+                                issued_after = update.issued_at;
+                                info!(
+                                    cluster_issuers = ?update.cluster_issuers,
+                                    "updating cluster issuers"
+                                );
+                                *this.cluster_issuers.write().await = update.cluster_issuers;
+                            }
+                        }
+                        Ok(_) => {
+                            trace!("Ignoring cluster issuers update (request targeted for different host)");
+                        }
+                        Err(err) => {
+                            error!(%err, "received invalid cluster issuers update");
+                        }
+                    }
+                });
+            }
+            .instrument(tracing::debug_span!("subscribe_issuers")),
+        );
+
+        Ok(handle)
+    }
+
+    async fn subscribe_config<P>(
+        &self,
+        provider: P,
+        mut quit: QuitSignal,
+    ) -> ProviderResult<JoinHandle<()>>
+    where
+        P: Provider,
+    {
+        let topic = provider_config_subject(&self.lattice_prefix, &self.host_data.provider_key);
+        debug!(%topic, "subscribing for configuration updates");
+        let mut sub = self.rpc_client.client().subscribe(topic).await?;
+        let this = self.clone();
+        let handle = tokio::spawn(
+            async move {
+                process_until_quit!(sub, quit, msg, {
+                    match serde_json::from_slice::<ProviderConfigUpdate>(&msg.payload) {
+                        Ok(ProviderConfigUpdate { host_id, values })
+                            if host_id.is_empty() || host_id == this.host_data.host_id =>
+                        {
+                            info!(
+                                values = ?this.redactor.redact_values::<HashMap<_, _>>(&values),
+                                "updating provider configuration"
+                            );
+                            if !provider.update_config(&values).await {
+                                warn!("provider rejected configuration update");
+                            }
+                        }
+                        Ok(_) => {
+                            trace!("Ignoring configuration update (request targeted for different host)");
+                        }
+                        Err(err) => {
+                            error!(%err, "received invalid configuration update");
+                        }
+                    }
+                });
+            }
+            .instrument(tracing::debug_span!("subscribe_config")),
+        );
+
+        Ok(handle)
+    }
+
     async fn subscribe_link_put<P>(
         &self,
         provider: P,
         mut quit: QuitSignal,
+        lattice: &str,
     ) -> ProviderResult<JoinHandle<()>>
     where
         P: Provider + Clone,
     {
         let ldput_topic = format!(
-            "wasmbus.rpc.{}.{}.{}.linkdefs.put",
-            &self.lattice_prefix, &self.host_data.provider_key, &self.host_data.link_name
+            "wasmbus.rpc.{lattice}.{}.{}.linkdefs.put",
+            &self.host_data.provider_key, &self.host_data.link_name
         );
 
         let mut sub = self.rpc_client.client().subscribe(ldput_topic).await?;
         let (this, provider) = (self.clone(), provider.clone());
+        let lattice = lattice.to_string();
         let handle = tokio::spawn(async move {
             process_until_quit!(sub, quit, msg, {
-                this.handle_link_put(msg, &provider).await
+                this.handle_link_put(msg, &provider, &lattice).await
             });
         });
         Ok(handle)
     }
 
-    #[instrument(level = "debug", skip_all, fields(actor_id = tracing::field::Empty, provider_id = tracing::field::Empty, contract_id = tracing::field::Empty, link_name = tracing::field::Empty))]
-    async fn handle_link_put<P>(&self, msg: async_nats::Message, provider: &P)
+    #[instrument(level = "debug", skip(self, msg, provider), fields(actor_id = tracing::field::Empty, provider_id = tracing::field::Empty, contract_id = tracing::field::Empty, link_name = tracing::field::Empty))]
+    async fn handle_link_put<P>(&self, msg: async_nats::Message, provider: &P, lattice: &str)
     where
         P: Provider,
     {
@@ -428,16 +913,7 @@ impl ProviderConnection {
                 span.record("provider_id", &tracing::field::display(&ld.provider_id));
                 span.record("contract_id", &tracing::field::display(&ld.contract_id));
                 span.record("link_name", &tracing::field::display(&ld.link_name));
-                if self.is_linked(&ld.actor_id).await {
-                    warn!("Ignoring duplicate link put");
-                } else {
-                    info!("Linking actor with provider");
-                    if provider.put_link(&ld).await {
-                        self.put_link(ld).await;
-                    } else {
-                        warn!("put_link denied");
-                    }
-                }
+                self.accept_link(provider, lattice, ld).await;
             }
             Err(err) => {
                 error!(%err, "received invalid link def data on message");
@@ -445,18 +921,81 @@ impl ProviderConnection {
         }
     }
 
+    /// Puts the link definition `ld` received in the lattice `lattice`, unless the actor is
+    /// linked already. Returns true if the link is put, i.e. the provider accepted it
+    pub(crate) async fn accept_link<P>(
+        &self,
+        provider: &P,
+        lattice: &str,
+        ld: LinkDefinition,
+    ) -> bool
+    where
+        P: Provider,
+    {
+        if self.links.contains(lattice, &ld.actor_id).await {
+            warn!("Ignoring duplicate link put");
+            return false;
+        }
+        info!("Linking actor with provider");
+        if provider.put_lattice_link(lattice, &ld).await {
+            self.put_link(lattice, ld).await;
+            true
+        } else {
+            warn!("put_link denied");
+            false
+        }
+    }
+
+    /// Deletes the link of the actor with ID `actor_id` in the lattice `lattice`, and notifies
+    /// the provider
+    pub(crate) async fn remove_link<P>(&self, provider: &P, lattice: &str, actor_id: &str)
+    where
+        P: Provider,
+    {
+        self.delete_link(lattice, actor_id).await;
+        provider.delete_lattice_link(lattice, actor_id).await;
+    }
+
+    /// Asks the provider for its health, which is degraded by panicked background tasks
+    pub(crate) async fn check_health<P>(&self, provider: &P) -> HealthCheckResponse
+    where
+        P: Provider,
+    {
+        let mut resp = provider.health_request(&HealthCheckRequest {}).await;
+        let panicked = self.task_supervisor.panicked_tasks();
+        if !panicked.is_empty() {
+            let panicked = format!("background tasks panicked: {}", panicked.join(", "));
+            resp.healthy = false;
+            resp.message = Some(match resp.message {
+                Some(message) => format!("{message}; {panicked}"),
+                None => panicked,
+            });
+        }
+        resp
+    }
+
+    /// Shuts the provider down, and then cancels its background tasks
+    pub(crate) async fn shutdown_provider<P>(&self, provider: &P)
+    where
+        P: Provider,
+    {
+        provider.shutdown().await;
+        self.task_supervisor.shutdown().await;
+    }
+
     async fn subscribe_link_del<P>(
         &self,
         provider: P,
         mut quit: QuitSignal,
+        lattice: &str,
     ) -> ProviderResult<JoinHandle<()>>
     where
         P: Provider + Clone,
     {
         // Link Delete
         let link_del_topic = format!(
-            "wasmbus.rpc.{}.{}.{}.linkdefs.del",
-            &self.lattice_prefix, &self.host_data.provider_key, &self.host_data.link_name
+            "wasmbus.rpc.{lattice}.{}.{}.linkdefs.del",
+            &self.host_data.provider_key, &self.host_data.link_name
         );
         debug!(topic = %link_del_topic, "subscribing for link del");
         let mut sub = self
@@ -465,15 +1004,14 @@ impl ProviderConnection {
             .subscribe(link_del_topic.clone())
             .await?;
         let (this, provider) = (self.clone(), provider.clone());
+        let lattice = lattice.to_string();
         let handle = tokio::spawn(async move {
             process_until_quit!(sub, quit, msg, {
                 let span = tracing::trace_span!("subscribe_link_del", topic = %link_del_topic);
                 if let Ok(ld) = deserialize::<LinkDefinition>(&msg.payload) {
-                    this.delete_link(&ld.actor_id)
-                        .instrument(span.clone())
+                    this.remove_link(&provider, &lattice, &ld.actor_id)
+                        .instrument(span)
                         .await;
-                    // notify provider that link is deleted
-                    provider.delete_link(&ld.actor_id).instrument(span).await;
                 }
             });
         });
@@ -499,7 +1037,10 @@ impl ProviderConnection {
         let handle = tokio::spawn(
             async move {
                 process_until_quit!(sub, quit, msg, {
-                    let resp = provider.health_request(&HealthCheckRequest {}).await;
+                    let resp = this.check_health(&provider).await;
+                    if !resp.healthy {
+                        warn!(message = ?resp.message, "provider reported unhealthy");
+                    }
                     let buf = serialize(&resp);
                     match buf {
                         Ok(t) => {
@@ -522,13 +1063,32 @@ impl ProviderConnection {
         Ok(handle)
     }
 
+    /// Verifies an invocation received in the lattice `lattice` before it is dispatched: its
+    /// signed claims (issuer, target and hash of the payload), that it was sent by an actor
+    /// linked to the provider in that lattice, and the configured [`InvocationValidator`]s.
+    /// Returns the invocation with its decoded claims
+    pub async fn verify_invocation(
+        &self,
+        inv: Invocation,
+        lattice: &str,
+    ) -> Result<(Invocation, Claims<jwt::Invocation>), ValidationError> {
+        let (inv, claims) = self.rpc_client.validate_invocation(inv).await?;
+        self.validate_provider_invocation(&inv, &claims, lattice)
+            .await?;
+        for validator in self.invocation_validators.iter() {
+            validator.validate(&inv, &claims).await?;
+        }
+        Ok((inv, claims))
+    }
+
     /// extra validation performed by providers
     async fn validate_provider_invocation(
         &self,
         inv: &Invocation,
         claims: &Claims<jwt::Invocation>,
+        lattice: &str,
     ) -> Result<(), ValidationError> {
-        if !self.host_data.cluster_issuers.contains(&claims.issuer) {
+        if !self.cluster_issuers.read().await.contains(&claims.issuer) {
             return Err(ValidationError::InvalidIssuer);
         }
 
@@ -541,10 +1101,396 @@ impl ProviderConnection {
         }
 
         // verify that the sending actor is linked with this provider
-        if !self.is_linked(&inv.origin.public_key).await {
+        if !self.links.contains(lattice, &inv.origin.public_key).await {
             return Err(ValidationError::InvalidActor(inv.origin.public_key.clone()));
         }
 
         Ok(())
     }
 }
+
+/// Renders a MessagePack `payload` for recording in a trace, replacing values of sensitive map
+/// keys with [`REDACTED`]. Payloads, which are not valid MessagePack, are only described by size
+fn capture_payload(redactor: &Redactor, payload: &[u8]) -> String {
+    let Ok(mut value) = rmpv::decode::read_value(&mut &payload[..]) else {
+        return format!("<{} bytes, not MessagePack>", payload.len());
+    };
+    redact_value(redactor, &mut value);
+    let mut captured = value.to_string();
+    if captured.len() > MAX_CAPTURED_PAYLOAD_LEN {
+        let mut end = MAX_CAPTURED_PAYLOAD_LEN;
+        while !captured.is_char_boundary(end) {
+            end -= 1;
+        }
+        captured.truncate(end);
+        captured.push_str("...");
+    }
+    captured
+}
+
+fn redact_value(redactor: &Redactor, value: &mut rmpv::Value) {
+    match value {
+        rmpv::Value::Map(entries) => {
+            for (k, v) in entries {
+                if k.as_str().is_some_and(|k| redactor.is_sensitive(k)) {
+                    *v = rmpv::Value::from(REDACTED);
+                } else {
+                    redact_value(redactor, v);
+                }
+            }
+        }
+        rmpv::Value::Array(values) => {
+            for v in values {
+                redact_value(redactor, v);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::num::NonZeroUsize;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use wasmcloud_core::{WasmCloudEntity, MIN_SCHEMA_VERSION, SCHEMA_VERSION};
+
+    use super::*;
+    use crate::testkit::detached_client;
+
+    fn host_data() -> HostData {
+        let cluster_key = KeyPair::new_cluster();
+        HostData {
+            schema_version: SCHEMA_VERSION,
+            min_schema_version: MIN_SCHEMA_VERSION,
+            host_id: KeyPair::new_server().public_key(),
+            lattice_rpc_prefix: "default".to_string(),
+            link_name: "default".to_string(),
+            provider_key: KeyPair::new_service().public_key(),
+            invocation_seed: cluster_key.seed().unwrap(),
+            cluster_issuers: vec![cluster_key.public_key()],
+            ..Default::default()
+        }
+    }
+
+    async fn connection(pool_size: usize, config: &ConnectionConfig) -> ProviderConnection {
+        let mut pool = Vec::with_capacity(pool_size);
+        for _ in 0..pool_size {
+            pool.push(detached_client().await.unwrap());
+        }
+        ProviderConnection::new(pool, &host_data(), config).unwrap()
+    }
+
+    #[test]
+    fn connection_config_defaults() {
+        let config = ConnectionConfig::default();
+        assert_eq!(config.pool_size.get(), 1);
+        assert!(config.max_concurrent_invocations.is_none());
+        assert!(config.invocation_validators.is_empty());
+        assert!(config.metrics_collectors.is_empty());
+    }
+
+    #[tokio::test]
+    async fn all_pooled_connections_are_used() {
+        let config = ConnectionConfig::default();
+        assert_eq!(
+            connection(1, &config)
+                .await
+                .get_rpc_client()
+                .connections()
+                .len(),
+            1
+        );
+        assert_eq!(
+            connection(3, &config)
+                .await
+                .get_rpc_client()
+                .connections()
+                .len(),
+            3
+        );
+        // At least one connection is required
+        assert!(matches!(
+            ProviderConnection::new(Vec::new(), &host_data(), &config),
+            Err(ProviderError::Initialization(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn invocations_are_limited() {
+        let connection = connection(
+            1,
+            &ConnectionConfig {
+                max_concurrent_invocations: NonZeroUsize::new(2),
+                ..Default::default()
+            },
+        )
+        .await;
+        let first = connection.invocation_permit().await;
+        let second = connection.invocation_permit().await;
+        assert!(first.is_some() && second.is_some());
+        // Further invocations wait for a handled one to complete
+        assert!(
+            tokio::time::timeout(Duration::from_millis(50), connection.invocation_permit())
+                .await
+                .is_err()
+        );
+        drop(first);
+        let third = tokio::time::timeout(Duration::from_secs(1), connection.invocation_permit())
+            .await
+            .expect("permit should be available once an invocation completed");
+        assert!(third.is_some());
+    }
+
+    #[derive(Clone)]
+    struct NoopProvider;
+
+    #[async_trait::async_trait]
+    impl crate::MessageDispatch for NoopProvider {
+        async fn dispatch<'a>(
+            &'a self,
+            _ctx: Context,
+            method: String,
+            _body: Cow<'a, [u8]>,
+        ) -> Result<Vec<u8>, ProviderInvocationError> {
+            Err(ProviderInvocationError::Malformed(format!(
+                "unknown method {method}"
+            )))
+        }
+    }
+
+    impl crate::ProviderHandler for NoopProvider {}
+
+    impl Provider for NoopProvider {}
+
+    #[tokio::test]
+    async fn panicked_tasks_degrade_health() {
+        let connection = connection(1, &ConnectionConfig::default()).await;
+        assert!(connection.check_health(&NoopProvider).await.healthy);
+
+        connection
+            .task_supervisor()
+            .spawn("token-renewer", async { panic!("renewal failed") });
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while connection.task_supervisor().panicked_tasks().is_empty() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("panic of background task was not recorded");
+        let health = connection.check_health(&NoopProvider).await;
+        assert!(!health.healthy);
+        assert!(health.message.unwrap().contains("token-renewer"));
+    }
+
+    #[tokio::test]
+    async fn invocations_are_unlimited_by_default() {
+        let connection = connection(1, &ConnectionConfig::default()).await;
+        for _ in 0..100 {
+            assert!(connection.invocation_permit().await.is_none());
+        }
+    }
+
+    const ACTOR_ID: &str = "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5";
+
+    /// Counts the invocations dispatched to it
+    #[derive(Clone, Default)]
+    struct CountingProvider(Arc<AtomicUsize>);
+
+    #[async_trait::async_trait]
+    impl crate::MessageDispatch for CountingProvider {
+        async fn dispatch<'a>(
+            &'a self,
+            _ctx: Context,
+            _method: String,
+            _body: Cow<'a, [u8]>,
+        ) -> Result<Vec<u8>, ProviderInvocationError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(Vec::new())
+        }
+    }
+
+    impl crate::ProviderHandler for CountingProvider {}
+
+    impl Provider for CountingProvider {}
+
+    /// Records its name when it validates an invocation, rejecting it if `reject` is set
+    struct RecordingValidator {
+        name: &'static str,
+        reject: bool,
+        validated: Arc<std::sync::Mutex<Vec<&'static str>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl InvocationValidator for RecordingValidator {
+        async fn validate(
+            &self,
+            _inv: &Invocation,
+            _claims: &Claims<jwt::Invocation>,
+        ) -> Result<(), ValidationError> {
+            self.validated.lock().unwrap().push(self.name);
+            if self.reject {
+                Err(ValidationError::Rejected(format!(
+                    "denied by {}",
+                    self.name
+                )))
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns a connection with validators named `validators`, rejecting if the flag is set,
+    /// along with the names of the validators in the order they validated invocations
+    async fn validated_connection(
+        validators: &[(&'static str, bool)],
+    ) -> (ProviderConnection, Arc<std::sync::Mutex<Vec<&'static str>>>) {
+        let validated = Arc::<std::sync::Mutex<Vec<_>>>::default();
+        let config =
+            validators
+                .iter()
+                .fold(ConnectionConfig::default(), |config, &(name, reject)| {
+                    config.with_invocation_validator(RecordingValidator {
+                        name,
+                        reject,
+                        validated: Arc::clone(&validated),
+                    })
+                });
+        (connection(1, &config).await, validated)
+    }
+
+    /// Returns an invocation of the provider of `connection` by [`ACTOR_ID`], signed by `issuer`
+    fn invocation(connection: &ProviderConnection, issuer: &KeyPair) -> Invocation {
+        Invocation::new(
+            issuer,
+            &KeyPair::new_server(),
+            WasmCloudEntity {
+                public_key: ACTOR_ID.to_string(),
+                ..Default::default()
+            },
+            WasmCloudEntity {
+                public_key: connection.host_data.provider_key.clone(),
+                link_name: connection.host_data.link_name.clone(),
+                contract_id: "wasmcloud:test".to_string(),
+            },
+            "wasmcloud:test/Test.Call",
+            Vec::new(),
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    fn cluster_key(connection: &ProviderConnection) -> KeyPair {
+        KeyPair::from_seed(&connection.host_data.invocation_seed).unwrap()
+    }
+
+    async fn link_actor(connection: &ProviderConnection) {
+        connection
+            .links
+            .insert(
+                "default",
+                LinkDefinition {
+                    actor_id: ACTOR_ID.to_string(),
+                    provider_id: connection.host_data.provider_key.clone(),
+                    link_name: connection.host_data.link_name.clone(),
+                    contract_id: "wasmcloud:test".to_string(),
+                    ..Default::default()
+                },
+            )
+            .await;
+    }
+
+    #[tokio::test]
+    async fn rejected_invocations_are_not_dispatched() {
+        let (connection, validated) = validated_connection(&[("deny", true)]).await;
+        link_actor(&connection).await;
+        let provider = CountingProvider::default();
+
+        let inv = invocation(&connection, &cluster_key(&connection));
+        let resp = connection
+            .handle_invocation(provider.clone(), inv, "default")
+            .await;
+        assert_eq!(resp.error_kind, Some(InvocationErrorKind::PermissionDenied));
+        assert!(resp.error.unwrap().contains("denied by deny"));
+        assert_eq!(*validated.lock().unwrap(), ["deny"]);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn rejected_invocations_are_reported() {
+        let (connection, _) = validated_connection(&[]).await;
+        let lattice = crate::testkit::FakeLattice::default();
+        let connection = connection.with_fake_lattice(lattice.clone());
+        link_actor(&connection).await;
+
+        let inv = invocation(&connection, &KeyPair::new_cluster());
+        let inv_id = inv.id.clone();
+        let resp = connection
+            .handle_invocation(CountingProvider::default(), inv, "default")
+            .await;
+        assert_eq!(resp.error_kind, Some(InvocationErrorKind::PermissionDenied));
+
+        let events = lattice.messages_on("wasmbus.evt.default.invocation_auth_failed");
+        assert_eq!(events.len(), 1);
+        let ev: serde_json::Value = serde_json::from_slice(&events[0].payload).unwrap();
+        assert_eq!(ev["type"], "com.wasmcloud.lattice.invocation_auth_failed");
+        assert_eq!(ev["source"], connection.host_data.provider_key.as_str());
+        assert_eq!(ev["data"]["invocation_id"], inv_id.as_str());
+        assert_eq!(ev["data"]["source"]["public_key"], ACTOR_ID);
+        assert_eq!(
+            ev["data"]["error"],
+            ValidationError::InvalidIssuer.to_string().as_str()
+        );
+    }
+
+    #[tokio::test]
+    async fn validated_invocations_are_dispatched() {
+        let (connection, validated) =
+            validated_connection(&[("first", false), ("second", false)]).await;
+        link_actor(&connection).await;
+        let provider = CountingProvider::default();
+
+        let inv = invocation(&connection, &cluster_key(&connection));
+        let resp = connection
+            .handle_invocation(provider.clone(), inv, "default")
+            .await;
+        assert!(resp.error.is_none(), "{:?}", resp.error);
+        // Validators run in the order they were registered
+        assert_eq!(*validated.lock().unwrap(), ["first", "second"]);
+        assert_eq!(provider.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn validators_run_in_order_after_builtin_checks() {
+        let (connection, validated) =
+            validated_connection(&[("first", false), ("deny", true), ("last", false)]).await;
+
+        // Invocations failing the built-in checks are rejected before any validator runs
+        let inv = invocation(&connection, &cluster_key(&connection));
+        assert!(matches!(
+            connection.verify_invocation(inv, "default").await,
+            Err(ValidationError::InvalidActor(actor)) if actor == ACTOR_ID
+        ));
+        link_actor(&connection).await;
+        let inv = invocation(&connection, &KeyPair::new_cluster());
+        assert!(matches!(
+            connection.verify_invocation(inv, "default").await,
+            Err(ValidationError::InvalidIssuer)
+        ));
+        let mut inv = invocation(&connection, &cluster_key(&connection));
+        inv.msg = b"tampered".to_vec();
+        assert!(matches!(
+            connection.verify_invocation(inv, "default").await,
+            Err(ValidationError::HashMismatch)
+        ));
+        assert!(validated.lock().unwrap().is_empty());
+
+        // A rejecting validator stops validation, so later validators do not run
+        let inv = invocation(&connection, &cluster_key(&connection));
+        assert!(matches!(
+            connection.verify_invocation(inv, "default").await,
+            Err(ValidationError::Rejected(reason)) if reason == "denied by deny"
+        ));
+        assert_eq!(*validated.lock().unwrap(), ["first", "deny"]);
+    }
+}
@@ -1,22 +1,104 @@
 //! Functions for starting and running a provider
 
 use std::io::BufRead;
+use std::num::NonZeroUsize;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use async_nats::{AuthError, ConnectOptions};
 use base64::Engine;
 use once_cell::sync::OnceCell;
+use serde::Deserialize;
 use tracing::{error, info};
 
 use crate::error::{ProviderError, ProviderResult};
+use crate::log_forwarding::{self, ForwardingLayer};
+use crate::logging;
+use crate::metrics::MetricsCollector;
 use crate::provider::ProviderConnection;
-use crate::Provider;
+use crate::{InvocationValidator, Provider};
 
-use wasmcloud_core::HostData;
+use wasmcloud_core::logging::forwarded_logs_subject;
+use wasmcloud_core::{negotiate_schema_version, HostData};
+use wasmcloud_tracing::ExtraLayer;
 
 static HOST_DATA: OnceCell<HostData> = OnceCell::new();
 static CONNECTION: OnceCell<ProviderConnection> = OnceCell::new();
 
+/// Schema version fields of [`HostData`], which are parsed ahead of the rest of it
+#[derive(Default, Deserialize)]
+struct HostDataSchema {
+    #[serde(default)]
+    schema_version: u32,
+    #[serde(default)]
+    min_schema_version: u32,
+}
+
+/// Settings of the connections of a provider to the lattice, see [`start_provider_with_config`]
+#[derive(Clone)]
+pub struct ConnectionConfig {
+    /// Number of NATS connections to the lattice. Each connection has its own subscription to the
+    /// provider's RPC topic (in the same queue group, so every invocation is received once), and
+    /// invocations sent by the provider are spread across the connections in turn.
+    ///
+    /// Defaults to a single connection
+    pub pool_size: NonZeroUsize,
+    /// Maximum number of received invocations handled concurrently. Once reached, subscriptions
+    /// wait for a handled invocation to complete before receiving the next one, leaving further
+    /// invocations buffered instead of spawning a task for each of them.
+    ///
+    /// Unbounded by default
+    pub max_concurrent_invocations: Option<NonZeroUsize>,
+    /// Validators received invocations must pass, in order, before they are dispatched
+    pub invocation_validators: Vec<Arc<dyn InvocationValidator>>,
+    /// Metrics of the provider, which are served along with the invocation metrics if a metrics
+    /// port is set in [`HostData`]
+    pub metrics_collectors: Vec<Arc<dyn MetricsCollector>>,
+}
+
+impl ConnectionConfig {
+    /// Rejects received invocations that fail validation by `validator`, in addition to the
+    /// validation performed by the SDK and previously added validators
+    #[must_use]
+    pub fn with_invocation_validator(mut self, validator: impl InvocationValidator) -> Self {
+        self.invocation_validators.push(Arc::new(validator));
+        self
+    }
+
+    /// Serves the metrics of `collector` along with the invocation metrics and those of
+    /// previously added collectors
+    #[must_use]
+    pub fn with_metrics_collector(mut self, collector: Arc<dyn MetricsCollector>) -> Self {
+        self.metrics_collectors.push(collector);
+        self
+    }
+}
+
+impl Default for ConnectionConfig {
+    fn default() -> Self {
+        Self {
+            pool_size: NonZeroUsize::MIN,
+            max_concurrent_invocations: None,
+            invocation_validators: Vec::new(),
+            metrics_collectors: Vec::new(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ConnectionConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionConfig")
+            .field("pool_size", &self.pool_size)
+            .field(
+                "max_concurrent_invocations",
+                &self.max_concurrent_invocations,
+            )
+            .field("invocation_validators", &self.invocation_validators.len())
+            .field("metrics_collectors", &self.metrics_collectors.len())
+            .finish()
+    }
+}
+
 /// Retrieves the currently configured connection to the lattice. DO NOT call this method until
 /// after the provider is running (meaning [`start_provider`] or [`run_provider`] have been called)
 /// or this method will panic. Only in extremely rare cases should this be called manually and it
@@ -32,6 +114,18 @@ pub fn get_connection() -> &'static ProviderConnection {
 
 /// Starts a provider, reading all of the host data and starting the process
 pub fn start_provider<P>(provider: P, friendly_name: Option<String>) -> ProviderResult<()>
+where
+    P: Provider + Clone,
+{
+    start_provider_with_config(provider, friendly_name, ConnectionConfig::default())
+}
+
+/// Starts a provider like [`start_provider`], connecting to the lattice as configured by `config`
+pub fn start_provider_with_config<P>(
+    provider: P,
+    friendly_name: Option<String>,
+    config: ConnectionConfig,
+) -> ProviderResult<()>
 where
     P: Provider + Clone,
 {
@@ -40,7 +134,7 @@ where
         .build()
         .map_err(|e| ProviderError::Initialization(e.to_string()))?;
 
-    runtime.block_on(run_provider(provider, friendly_name))?;
+    runtime.block_on(run_provider_with_config(provider, friendly_name, config))?;
     // in the unlikely case there are any stuck threads,
     // close them so the process has a clean exit
     runtime.shutdown_timeout(std::time::Duration::from_secs(10));
@@ -50,17 +144,35 @@ where
 /// Runs the provider. You can use this method instead of [`start_provider`] if you are already in
 /// an async context
 pub async fn run_provider<P>(provider: P, friendly_name: Option<String>) -> ProviderResult<()>
+where
+    P: Provider + Clone,
+{
+    run_provider_with_config(provider, friendly_name, ConnectionConfig::default()).await
+}
+
+/// Runs the provider like [`run_provider`], connecting to the lattice as configured by `config`
+pub async fn run_provider_with_config<P>(
+    provider: P,
+    friendly_name: Option<String>,
+    config: ConnectionConfig,
+) -> ProviderResult<()>
 where
     P: Provider + Clone,
 {
     let host_data = tokio::task::spawn_blocking(load_host_data)
         .await
         .map_err(|e| ProviderError::Initialization(format!("Unable to load host data: {e}")))??;
-    if let Err(e) = wasmcloud_tracing::configure_tracing(
+    let (forwarding_layer, forwarded_logs) = if host_data.forward_logs {
+        let (layer, rx) = ForwardingLayer::new(host_data);
+        (Some(Box::new(layer) as ExtraLayer), Some(rx))
+    } else {
+        (None, None)
+    };
+    if let Err(e) = logging::configure(
+        host_data,
         friendly_name.unwrap_or(host_data.provider_key.clone()),
-        &host_data.otel_config,
         host_data.structured_logging,
-        host_data.log_level.as_ref(),
+        forwarding_layer,
     ) {
         eprintln!("Failed to configure tracing: {e}");
     }
@@ -72,36 +184,21 @@ where
         &host_data.provider_key, &host_data.instance_id, &host_data.lattice_rpc_url,
     );
 
-    let nats_addr = if !host_data.lattice_rpc_url.is_empty() {
-        host_data.lattice_rpc_url.as_str()
-    } else {
-        crate::DEFAULT_NATS_ADDR
-    };
-    let nats_server = async_nats::ServerAddr::from_str(nats_addr).map_err(|e| {
-        ProviderError::Initialization(format!("Invalid nats server url '{nats_addr}': {e}"))
-    })?;
+    let mut pool = Vec::with_capacity(config.pool_size.get());
+    for _ in 0..config.pool_size.get() {
+        pool.push(connect_lattice(host_data).await?);
+    }
 
-    let nc = crate::with_connection_event_logging(
-        match (
-            host_data.lattice_rpc_user_jwt.trim(),
-            host_data.lattice_rpc_user_seed.trim(),
-        ) {
-            ("", "") => ConnectOptions::default(),
-            (rpc_jwt, rpc_seed) => {
-                let key_pair = std::sync::Arc::new(nkeys::KeyPair::from_seed(rpc_seed).unwrap());
-                let jwt = rpc_jwt.to_owned();
-                ConnectOptions::with_jwt(jwt, move |nonce| {
-                    let key_pair = key_pair.clone();
-                    async move { key_pair.sign(&nonce).map_err(AuthError::new) }
-                })
-            }
-        },
-    )
-    .connect(nats_server)
-    .await?;
+    if let Some(records) = forwarded_logs {
+        tokio::spawn(log_forwarding::forward(
+            pool[0].clone(),
+            forwarded_logs_subject(&host_data.lattice_rpc_prefix, &host_data.host_id),
+            records,
+        ));
+    }
 
     // initialize HostBridge
-    let connection = ProviderConnection::new(nc, host_data)?;
+    let connection = ProviderConnection::new(pool, host_data, &config)?;
     CONNECTION.set(connection).map_err(|_| {
         ProviderError::Initialization("Provider connection was already initialized".to_string())
     })?;
@@ -111,13 +208,16 @@ where
     // initialization of any link is fatal for provider startup
     let initial_links = host_data.link_definitions.clone();
     for ld in initial_links.into_iter() {
-        if !provider.put_link(&ld).await {
+        if !provider
+            .put_lattice_link(&host_data.lattice_rpc_prefix, &ld)
+            .await
+        {
             error!(
-                link_definition = ?ld,
+                link_definition = ?connection.redactor().link(&ld),
                 "Failed to initialize link during provider startup",
             );
         } else {
-            connection.put_link(ld).await;
+            connection.put_link(&host_data.lattice_rpc_prefix, ld).await;
         }
     }
 
@@ -135,6 +235,48 @@ where
     Ok(())
 }
 
+/// Connects to the lattice RPC NATS server with the credentials and TLS settings in `host_data`
+async fn connect_lattice(host_data: &HostData) -> ProviderResult<async_nats::Client> {
+    let nats_addr = if !host_data.lattice_rpc_url.is_empty() {
+        host_data.lattice_rpc_url.as_str()
+    } else {
+        crate::DEFAULT_NATS_ADDR
+    };
+    let nats_server = async_nats::ServerAddr::from_str(nats_addr).map_err(|e| {
+        ProviderError::Initialization(format!("Invalid nats server url '{nats_addr}': {e}"))
+    })?;
+
+    let opts = match (
+        host_data.lattice_rpc_user_jwt.trim(),
+        host_data.lattice_rpc_user_seed.trim(),
+    ) {
+        ("", "") => ConnectOptions::default(),
+        // Authenticate using the nkey alone, proving possession of it by signing the server nonce
+        ("", rpc_seed) => ConnectOptions::with_nkey(rpc_seed.to_owned()),
+        (rpc_jwt, rpc_seed) => {
+            let key_pair =
+                std::sync::Arc::new(nkeys::KeyPair::from_seed(rpc_seed).map_err(|e| {
+                    ProviderError::Initialization(format!("Invalid RPC nkey seed: {e}"))
+                })?);
+            let jwt = rpc_jwt.to_owned();
+            ConnectOptions::with_jwt(jwt, move |nonce| {
+                let key_pair = key_pair.clone();
+                async move { key_pair.sign(&nonce).map_err(AuthError::new) }
+            })
+        }
+    };
+    let opts = host_data
+        .lattice_rpc_tls_config
+        .apply(opts.require_tls(host_data.lattice_rpc_tls))
+        .map_err(|e| {
+            ProviderError::Initialization(format!("Invalid RPC TLS configuration: {e}"))
+        })?;
+    let nc = crate::with_connection_event_logging(opts)
+        .connect(nats_server)
+        .await?;
+    Ok(nc)
+}
+
 /// Loads configuration data sent from the host over stdin. The returned host data contains all the
 /// configuration information needed to connect to the lattice and any additional configuration
 /// provided to this provider (like `config_json`).
@@ -172,9 +314,18 @@ fn _load_host_data() -> ProviderResult<HostData> {
              {e}"
         ))
         })?;
+    // Negotiate the schema version before parsing the whole host data, so that an incompatible
+    // host is reported as such rather than by an opaque parsing error
+    let schema: HostDataSchema = serde_json::from_slice(&bytes).unwrap_or_default();
+    negotiate_schema_version(schema.schema_version, schema.min_schema_version).map_err(|e| {
+        ProviderError::Initialization(format!(
+            "host data schema is incompatible with this provider: {e:#}"
+        ))
+    })?;
     let host_data: HostData = serde_json::from_slice(&bytes).map_err(|e| {
         ProviderError::Initialization(format!(
-            "parsing host data: {}:\n{}",
+            "parsing host data of schema version {}: {}:\n{}",
+            schema.schema_version,
             e,
             String::from_utf8_lossy(&bytes)
         ))
@@ -0,0 +1,283 @@
+//! Rate limiting of invocations per linked actor, configured by the `max_rps` and `burst` values of
+//! the link definition of the actor. Actors whose link does not set `max_rps` are not limited.
+//! Links of the same actor in different lattices are limited separately
+
+use std::{
+    collections::HashMap,
+    sync::{Mutex, PoisonError},
+    time::Instant,
+};
+
+use tracing::warn;
+
+use crate::{
+    core::LinkDefinition,
+    error::{ProviderInvocationError, ProviderInvocationResult},
+    link_config::lookup,
+};
+
+/// Link value setting the sustained number of invocations per second allowed from the actor
+pub const MAX_RPS_LINK_VALUE: &str = "max_rps";
+/// Link value setting the number of invocations allowed from the actor in a burst, which defaults
+/// to `max_rps` rounded up
+pub const BURST_LINK_VALUE: &str = "burst";
+
+/// Rate of invocations allowed from an actor
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Sustained number of invocations per second
+    pub max_rps: f64,
+    /// Number of invocations allowed in a burst
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Parses the rate limit set by the values of a link definition, if any.
+    ///
+    /// Returns an error if the values are invalid
+    pub fn from_values(values: &HashMap<String, String>) -> Result<Option<Self>, String> {
+        let Some(max_rps) = lookup(values, MAX_RPS_LINK_VALUE, None) else {
+            return Ok(None);
+        };
+        let max_rps = max_rps
+            .trim()
+            .parse::<f64>()
+            .map_err(|e| format!("invalid `{MAX_RPS_LINK_VALUE}`: {e}"))?;
+        if !max_rps.is_finite() || max_rps <= 0.0 {
+            return Err(format!("`{MAX_RPS_LINK_VALUE}` must be a positive number"));
+        }
+        let burst = match lookup(values, BURST_LINK_VALUE, None) {
+            Some(burst) => burst
+                .trim()
+                .parse::<u32>()
+                .map_err(|e| format!("invalid `{BURST_LINK_VALUE}`: {e}"))?,
+            None => max_rps.ceil() as u32,
+        };
+        if burst == 0 {
+            return Err(format!("`{BURST_LINK_VALUE}` must be at least 1"));
+        }
+        Ok(Some(Self { max_rps, burst }))
+    }
+}
+
+/// Token bucket of a single actor
+#[derive(Debug)]
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: f64::from(limit.burst),
+            refilled_at: Instant::now(),
+        }
+    }
+
+    /// Takes a token from the bucket, if there is one
+    fn try_take(&mut self) -> bool {
+        self.try_take_at(Instant::now())
+    }
+
+    /// Takes a token from the bucket at the time `now`, after refilling it for the time elapsed
+    /// since the last refill
+    fn try_take_at(&mut self, now: Instant) -> bool {
+        let elapsed = now.duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.limit.max_rps).min(f64::from(self.limit.burst));
+        self.refilled_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Token bucket rate limiter of the invocations received from each linked actor, keyed by
+/// lattice and actor ID
+#[derive(Debug, Default)]
+pub(crate) struct RateLimiter {
+    buckets: Mutex<HashMap<(String, String), TokenBucket>>,
+}
+
+impl RateLimiter {
+    /// Limits the invocations from the actor linked by `ld` in the lattice `lattice` as set by the
+    /// values of the link definition, replacing any previous limit
+    pub(crate) fn set_limit(&self, lattice: &str, ld: &LinkDefinition) {
+        let actor_id = ld.actor_id.as_str();
+        let key = (lattice.to_string(), actor_id.to_string());
+        let values = ld.values.iter().cloned().collect();
+        let limit = RateLimit::from_values(&values).unwrap_or_else(|err| {
+            warn!(actor_id, %err, "ignoring invalid rate limit of link");
+            None
+        });
+        let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        match limit {
+            // Keep the tokens of the bucket if the limit did not change
+            Some(limit) if buckets.get(&key).is_some_and(|b| b.limit == limit) => {}
+            Some(limit) => {
+                buckets.insert(key, TokenBucket::new(limit));
+            }
+            None => {
+                buckets.remove(&key);
+            }
+        }
+    }
+
+    /// Removes the limit of the actor with ID `actor_id` in the lattice `lattice`
+    pub(crate) fn remove_limit(&self, lattice: &str, actor_id: &str) {
+        self.buckets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&(lattice.to_string(), actor_id.to_string()));
+    }
+
+    /// Returns true if an invocation from the actor with ID `actor_id` in the lattice `lattice`
+    /// is allowed by its limit
+    pub(crate) fn try_acquire(&self, lattice: &str, actor_id: &str) -> bool {
+        self.buckets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_mut(&(lattice.to_string(), actor_id.to_string()))
+            .map_or(true, TokenBucket::try_take)
+    }
+
+    /// Fails with [`ProviderInvocationError::Throttled`] if an invocation from the actor with ID
+    /// `actor_id` in the lattice `lattice` exceeds its limit
+    pub(crate) fn check(&self, lattice: &str, actor_id: &str) -> ProviderInvocationResult<()> {
+        if self.try_acquire(lattice, actor_id) {
+            Ok(())
+        } else {
+            Err(ProviderInvocationError::Throttled(format!(
+                "actor {actor_id} exceeded the rate of invocations allowed by its link"
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit {
+        max_rps: 10.0,
+        burst: 3,
+    };
+
+    fn link(actor_id: &str, values: &[(&str, &str)]) -> LinkDefinition {
+        LinkDefinition {
+            actor_id: actor_id.to_string(),
+            values: values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn burst_is_allowed_then_exhausted() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(LIMIT);
+        bucket.refilled_at = start;
+        for _ in 0..LIMIT.burst {
+            assert!(bucket.try_take_at(start));
+        }
+        assert!(!bucket.try_take_at(start));
+    }
+
+    #[test]
+    fn tokens_refill_at_max_rps_up_to_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(LIMIT);
+        bucket.refilled_at = start;
+        for _ in 0..LIMIT.burst {
+            assert!(bucket.try_take_at(start));
+        }
+        // Half a token is not enough
+        let now = start + Duration::from_millis(50);
+        assert!(!bucket.try_take_at(now));
+        // Another 50ms complete the token
+        let now = now + Duration::from_millis(50);
+        assert!(bucket.try_take_at(now));
+        assert!(!bucket.try_take_at(now));
+        // A long pause refills no more than the burst
+        let now = now + Duration::from_secs(60);
+        for _ in 0..LIMIT.burst {
+            assert!(bucket.try_take_at(now));
+        }
+        assert!(!bucket.try_take_at(now));
+    }
+
+    #[test]
+    fn parses_limits_from_link_values() {
+        let values = |pairs: &[(&str, &str)]| {
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect::<HashMap<_, _>>()
+        };
+        assert_eq!(RateLimit::from_values(&values(&[])), Ok(None));
+        assert_eq!(
+            RateLimit::from_values(&values(&[("max_rps", "2.5")])),
+            Ok(Some(RateLimit {
+                max_rps: 2.5,
+                burst: 3
+            }))
+        );
+        assert_eq!(
+            RateLimit::from_values(&values(&[("MAX_RPS", "1"), ("burst", "10")])),
+            Ok(Some(RateLimit {
+                max_rps: 1.0,
+                burst: 10
+            }))
+        );
+        assert!(RateLimit::from_values(&values(&[("max_rps", "0")])).is_err());
+        assert!(RateLimit::from_values(&values(&[("max_rps", "fast")])).is_err());
+        assert!(RateLimit::from_values(&values(&[("max_rps", "1"), ("burst", "0")])).is_err());
+    }
+
+    #[test]
+    fn exceeding_the_limit_is_throttled() {
+        let limiter = RateLimiter::default();
+        limiter.set_limit(
+            "default",
+            &link("actor", &[("max_rps", "0.001"), ("burst", "1")]),
+        );
+        assert!(limiter.check("default", "actor").is_ok());
+        assert!(matches!(
+            limiter.check("default", "actor"),
+            Err(ProviderInvocationError::Throttled(message)) if message.contains("actor")
+        ));
+        // Other actors are not limited, nor is the same actor in other lattices
+        assert!(limiter.check("default", "other").is_ok());
+        assert!(limiter.check("default", "other").is_ok());
+        assert!(limiter.check("other-lattice", "actor").is_ok());
+        assert!(limiter.check("other-lattice", "actor").is_ok());
+        // Nor are actors whose limit was removed
+        limiter.remove_limit("default", "actor");
+        assert!(limiter.check("default", "actor").is_ok());
+    }
+
+    #[test]
+    fn unchanged_limit_keeps_tokens() {
+        let limiter = RateLimiter::default();
+        let ld = link("actor", &[("max_rps", "0.001"), ("burst", "1")]);
+        limiter.set_limit("default", &ld);
+        assert!(limiter.try_acquire("default", "actor"));
+        limiter.set_limit("default", &ld);
+        assert!(!limiter.try_acquire("default", "actor"));
+        // A changed limit starts with a full bucket
+        limiter.set_limit(
+            "default",
+            &link("actor", &[("max_rps", "0.001"), ("burst", "2")]),
+        );
+        assert!(limiter.try_acquire("default", "actor"));
+    }
+}
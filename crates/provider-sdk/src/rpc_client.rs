@@ -1,27 +1,41 @@
 use crate::{
+    codec::Codec,
     error::{InvocationError, InvocationResult, NetworkError, ValidationError},
     rpc_topic,
 };
 
-use std::{fmt, sync::Arc, time::Duration};
+use std::{
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use async_nats::{Client, Subject};
 use futures::{Future, TryFutureExt};
+#[cfg(feature = "otel")]
+use opentelemetry_nats::NatsHeaderInjector;
 use sha2::Digest;
 use tracing::{
     debug, error,
     field::{display, Empty},
-    instrument,
+    instrument, warn,
 };
 use uuid::Uuid;
 use wascap::{jwt, prelude::Claims};
 use wasmcloud_core::{
     chunking::{ChunkEndpoint, CHUNK_RPC_EXTRA_TIME, CHUNK_THRESHOLD_BYTES},
-    Invocation, InvocationResponse, WasmCloudEntity,
+    ensure_schema_version, Invocation, InvocationResponse, InvocationValidity, WasmCloudEntity,
+    SCHEMA_VERSION,
 };
 #[cfg(feature = "otel")]
 use wasmcloud_tracing::context::TraceContextInjector;
 
+/// Amount of time to wait before retrying an rpc message in [`RpcClient::send_with_retries`]
+const RPC_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
 /// Send wasmbus rpc messages
 ///
 /// The primary use of RpcClient is providers sending to actors, however providers don't need to
@@ -29,6 +43,9 @@ use wasmcloud_tracing::context::TraceContextInjector;
 #[derive(Clone)]
 pub struct RpcClient {
     client: Client,
+    /// connections requests and messages are sent over in turn, including `client`
+    pool: Arc<[Client]>,
+    next_client: Arc<AtomicUsize>,
     key: Arc<wascap::prelude::KeyPair>,
     /// host id (public key) for invocations
     host_id: String,
@@ -36,6 +53,14 @@ pub struct RpcClient {
     timeout: Option<Duration>,
     lattice: String,
     chonky: ChunkEndpoint,
+    invocation_validity: InvocationValidity,
+    /// schema version of sent invocations
+    schema_version: u32,
+    /// encoding of sent payloads
+    codec: Codec,
+    /// fake lattice requests and messages are sent on instead of NATS, see [`crate::testkit`]
+    #[cfg(any(test, feature = "testkit"))]
+    fake_lattice: Option<crate::testkit::FakeLattice>,
 }
 
 // just so RpcClient can be included in other Debug structs
@@ -65,23 +90,127 @@ impl RpcClient {
         // touch it without a second opinion as this code is some of our most tempermental.
         let chonky = ChunkEndpoint::with_client(lattice_id, nats.clone(), None::<&str>);
         RpcClient {
+            pool: Arc::from([nats.clone()]),
+            next_client: Arc::default(),
             client: nats,
             host_id,
             timeout,
             key: key_pair,
             lattice: lattice_id.to_string(),
             chonky,
+            invocation_validity: InvocationValidity::default(),
+            schema_version: SCHEMA_VERSION,
+            codec: Codec::default(),
+            #[cfg(any(test, feature = "testkit"))]
+            fake_lattice: None,
+        }
+    }
+
+    /// Sets the time-based validation rules applied to the claims of received invocations
+    #[must_use]
+    pub fn with_invocation_validity(mut self, invocation_validity: InvocationValidity) -> Self {
+        self.invocation_validity = invocation_validity;
+        self
+    }
+
+    /// Sets the schema version of sent invocations, which defaults to [`SCHEMA_VERSION`]. This
+    /// should be the version negotiated with the host, so that it is able to process them
+    #[must_use]
+    pub fn with_schema_version(mut self, schema_version: u32) -> Self {
+        self.schema_version = schema_version;
+        self
+    }
+
+    /// Sets the encoding of the payloads sent with this client, which defaults to
+    /// [`Codec::MsgPack`]. Receivers expecting another encoding transcode payloads based on the
+    /// content type of the invocation
+    #[must_use]
+    pub fn with_codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    /// Chunks large payloads inline over NATS instead of through a JetStream object store, if
+    /// `inline` is set and the receiver supports it, see
+    /// [`HostData::inline_chunking`](wasmcloud_core::HostData::inline_chunking)
+    #[must_use]
+    pub fn with_inline_chunking(mut self, inline: bool) -> Self {
+        self.chonky = self.chonky.with_inline_chunking(inline);
+        self
+    }
+
+    /// Returns a client sending invocations to, and chunking payloads in, the lattice with prefix
+    /// `lattice` instead of the lattice this client was constructed with, over the same
+    /// connections
+    #[must_use]
+    pub fn with_lattice(&self, lattice: &str) -> Self {
+        RpcClient {
+            lattice: lattice.to_string(),
+            chonky: self.chonky.with_lattice(lattice),
+            ..self.clone()
         }
     }
 
+    /// Sends requests and messages on the fake lattice `lattice` instead of NATS. Payloads are not
+    /// chunked, since messages on a fake lattice are not limited in size
+    #[cfg(any(test, feature = "testkit"))]
+    #[must_use]
+    pub(crate) fn with_fake_lattice(mut self, lattice: crate::testkit::FakeLattice) -> Self {
+        self.fake_lattice = Some(lattice);
+        self
+    }
+
+    /// Returns the fake lattice requests and messages are sent on instead of NATS, if any
+    #[cfg(any(test, feature = "testkit"))]
+    pub(crate) fn fake_lattice(&self) -> Option<&crate::testkit::FakeLattice> {
+        self.fake_lattice.as_ref()
+    }
+
+    /// Returns true if a payload of `len` bytes is sent in chunks instead of in the message
+    fn needs_chunking(&self, len: usize) -> bool {
+        #[cfg(any(test, feature = "testkit"))]
+        if self.fake_lattice.is_some() {
+            return false;
+        }
+        len > CHUNK_THRESHOLD_BYTES
+    }
+
+    /// Sends requests and messages over `connections` in turn, in addition to the NATS client
+    /// this client was constructed with, so that they are not all sent over a single connection
+    #[must_use]
+    pub fn with_connection_pool(mut self, connections: impl IntoIterator<Item = Client>) -> Self {
+        self.pool = std::iter::once(self.client.clone())
+            .chain(connections)
+            .collect();
+        self
+    }
+
+    /// Returns all connections of this client, starting with the one returned by [`Self::client`]
+    pub(crate) fn connections(&self) -> &[Client] {
+        &self.pool
+    }
+
+    /// Returns the next connection of the pool to send a request or message over
+    fn pooled_client(&self) -> &Client {
+        let next = self.next_client.fetch_add(1, Ordering::Relaxed);
+        &self.pool[next % self.pool.len()]
+    }
+
     /// convenience method for returning the underlying NATS client
     pub fn client(&self) -> Client {
         self.client.clone()
     }
 
+    /// Returns the cluster key invocations are signed with
+    pub(crate) fn cluster_key(&self) -> Arc<wascap::prelude::KeyPair> {
+        Arc::clone(&self.key)
+    }
+
     pub async fn flush(&self) {
-        if let Err(err) = self.client.flush().await {
-            error!(%err, "error flushing NATS client");
+        for client in self.pool.iter() {
+            if let Err(err) = client.flush().await {
+                error!(%err, "error flushing NATS client");
+            }
         }
     }
 
@@ -117,6 +246,44 @@ impl RpcClient {
             .await
     }
 
+    /// Send a wasmbus rpc message, retrying up to `retries` times if it times out or fails to be
+    /// delivered.
+    ///
+    /// `timeout` applies to each attempt and overrides the configured timeout for this client if
+    /// set. Other failures are not retried, since the invocation may have been processed already.
+    pub async fn send_with_retries(
+        &self,
+        origin: WasmCloudEntity,
+        target: WasmCloudEntity,
+        method: impl Into<String>,
+        data: Vec<u8>,
+        timeout: Option<Duration>,
+        retries: u32,
+    ) -> InvocationResult<InvocationResponse> {
+        let method = method.into();
+        let timeout = timeout.or(self.timeout);
+        let mut attempt = 0;
+        loop {
+            match self
+                .inner_rpc(
+                    origin.clone(),
+                    target.clone(),
+                    method.as_str(),
+                    data.clone(),
+                    timeout,
+                )
+                .await
+            {
+                Err(err) if attempt < retries && err.is_retryable() => {
+                    attempt += 1;
+                    warn!(%err, %method, attempt, retries, "retrying rpc message");
+                    tokio::time::sleep(RPC_RETRY_BACKOFF).await;
+                }
+                res => return res,
+            }
+        }
+    }
+
     /// request or publish an rpc invocation
     #[instrument(level = "debug", skip(self, origin, target, method, data), fields( data_len = %data.len(), lattice_id = %self.lattice, method = Empty, subject = Empty, issuer = Empty, sender_key = Empty, contract_id = Empty, link_name = Empty, target_key = Empty, method = Empty, topic = Empty ))]
     async fn inner_rpc(
@@ -163,10 +330,13 @@ impl RpcClient {
         );
 
         let len = data.len();
-        let needs_chunking = len > CHUNK_THRESHOLD_BYTES;
+        let needs_chunking = self.needs_chunking(len);
+        let chunk_transport = self.chonky.transport_for(&target.public_key);
+        let peer = target.public_key.clone();
 
         let (invocation, body) = {
             let mut inv = Invocation {
+                schema_version: self.schema_version,
                 origin,
                 target,
                 operation: method.clone(),
@@ -174,11 +344,14 @@ impl RpcClient {
                 encoded_claims: claims.encode(&self.key).unwrap_or_default(),
                 host_id: self.host_id.clone(),
                 content_length: len as u64,
+                content_type: Some(self.codec.content_type().to_string()),
+                inline_chunking: self.chonky.supports_inline(),
                 #[cfg(feature = "otel")]
                 trace_context: TraceContextInjector::default_with_span().into(),
                 ..Default::default()
             };
             if needs_chunking {
+                inv.chunk_transport = chunk_transport;
                 (inv, Some(data))
             } else {
                 inv.msg = data;
@@ -191,7 +364,7 @@ impl RpcClient {
 
             if let Err(err) = self
                 .chonky
-                .chunkify(&invocation.id, &mut body.as_slice())
+                .chunkify_via(chunk_transport, &invocation.id, &mut body.as_slice())
                 .await
             {
                 error!(%err, "chunking error");
@@ -227,11 +400,15 @@ impl RpcClient {
         })?;
 
         let mut inv_response = crate::deserialize::<InvocationResponse>(&payload)?;
+        self.chonky.record_peer(&peer, inv_response.inline_chunking);
         if inv_response.error.is_none() {
             // was response chunked?
             let msg = if inv_response.content_length > inv_response.msg.len() as u64 {
                 self.chonky
-                    .get_unchunkified_response(&inv_response.invocation_id)
+                    .get_unchunkified_response_via(
+                        inv_response.chunk_transport,
+                        &inv_response.invocation_id,
+                    )
                     .await
                     .map_err(|e| InvocationError::Chunking(e.to_string()))?
             } else {
@@ -249,11 +426,28 @@ impl RpcClient {
     /// the appropriate time, an error will be returned.
     #[instrument(level = "debug", skip_all, fields(subject = %subject))]
     pub async fn request(&self, subject: String, payload: Vec<u8>) -> InvocationResult<Vec<u8>> {
+        #[cfg(any(test, feature = "testkit"))]
+        if let Some(lattice) = &self.fake_lattice {
+            let timeout = self.timeout.unwrap_or(crate::testkit::DEFAULT_TEST_TIMEOUT);
+            return Ok(lattice
+                .send_request(subject, payload, timeout)
+                .await?
+                .payload);
+        }
+        // The trace context is also sent in the headers, so that it is propagated to recipients,
+        // which do not parse the invocation, e.g. non-wasmbus NATS subscribers
+        let client = self.pooled_client();
+        #[cfg(feature = "otel")]
+        let request = client.request_with_headers(
+            subject,
+            NatsHeaderInjector::default_with_span().into(),
+            payload.into(),
+        );
+        #[cfg(not(feature = "otel"))]
+        let request = client.request(subject, payload.into());
         match maybe_timeout(
             self.timeout,
-            self.client
-                .request(subject, payload.into())
-                .map_err(|e| InvocationError::from(NetworkError::from(e))),
+            request.map_err(|e| InvocationError::from(NetworkError::from(e))),
         )
         .await
         {
@@ -269,14 +463,18 @@ impl RpcClient {
     /// This can be used for general nats messages, not just wasmbus actor/provider messages.
     #[instrument(level = "trace", skip(self, payload))]
     pub(crate) async fn publish(&self, subject: Subject, payload: Vec<u8>) -> InvocationResult<()> {
+        #[cfg(any(test, feature = "testkit"))]
+        if let Some(lattice) = &self.fake_lattice {
+            lattice.publish(subject.to_string(), None, payload);
+            return Ok(());
+        }
+        let nc = self.pooled_client().clone();
         maybe_timeout(
             self.timeout,
-            self.client
-                .publish(subject, payload.into())
+            nc.publish(subject, payload.into())
                 .map_err(|e| InvocationError::from(NetworkError::from(e))),
         )
         .await?;
-        let nc = self.client();
         // TODO: revisit after doing some performance tuning and review of callers of pubish().
         // For high throughput use cases, it may be better to change the flush interval timer
         // instead of flushing after every publish.
@@ -289,26 +487,38 @@ impl RpcClient {
         Ok(())
     }
 
+    /// Publishes the response to an invocation sent by the peer with public key `peer`, chunking
+    /// it if needed in the way the peer supports
     pub(crate) async fn publish_invocation_response(
         &self,
         reply_to: Subject,
         response: InvocationResponse,
+        peer: &str,
     ) -> InvocationResult<()> {
         let content_length = response.msg.len() as u64;
+        let inline_chunking = self.chonky.supports_inline();
         let response = {
-            if response.msg.len() > CHUNK_THRESHOLD_BYTES {
+            if self.needs_chunking(response.msg.len()) {
+                let chunk_transport = self.chonky.transport_for(peer);
                 self.chonky
-                    .chunkify_response(&response.invocation_id, std::io::Cursor::new(response.msg))
+                    .chunkify_response_via(
+                        chunk_transport,
+                        &response.invocation_id,
+                        std::io::Cursor::new(response.msg),
+                    )
                     .await
                     .map_err(|e| InvocationError::Chunking(e.to_string()))?;
                 InvocationResponse {
                     msg: Vec::new(),
                     content_length,
+                    chunk_transport,
+                    inline_chunking,
                     ..response
                 }
             } else {
                 InvocationResponse {
                     content_length,
+                    inline_chunking,
                     ..response
                 }
             }
@@ -319,10 +529,12 @@ impl RpcClient {
     }
 
     pub async fn dechunk(&self, mut inv: Invocation) -> InvocationResult<Invocation> {
+        self.chonky
+            .record_peer(&inv.origin.public_key, inv.inline_chunking);
         if inv.content_length > inv.msg.len() as u64 {
             inv.msg = self
                 .chonky
-                .get_unchunkified(&inv.id)
+                .get_unchunkified_via(inv.chunk_transport, &inv.id)
                 .await
                 .map_err(|e| InvocationError::Chunking(e.to_string()))?;
         }
@@ -334,17 +546,13 @@ impl RpcClient {
         &self,
         inv: Invocation,
     ) -> Result<(Invocation, Claims<jwt::Invocation>), ValidationError> {
+        ensure_schema_version(inv.schema_version)
+            .map_err(|e| ValidationError::IncompatibleSchema(format!("{e:#}")))?;
         let vr = jwt::validate_token::<jwt::Invocation>(&inv.encoded_claims)
             .map_err(|e| ValidationError::InvalidJson(e.to_string()))?;
-        if vr.expired {
-            return Err(ValidationError::Expired);
-        }
         if !vr.signature_valid {
             return Err(ValidationError::InvalidSignature);
         }
-        if vr.cannot_use_yet {
-            return Err(ValidationError::NotValidYet);
-        }
         let target_url = crate::url(&inv.target, Some(&inv.operation));
         let hash = invocation_hash(
             &target_url,
@@ -354,6 +562,15 @@ impl RpcClient {
         );
         let claims = Claims::<jwt::Invocation>::decode(&inv.encoded_claims)
             .map_err(|e| ValidationError::InvalidJson(e.to_string()))?;
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        if self.invocation_validity.is_expired(&claims, now) {
+            return Err(ValidationError::Expired);
+        }
+        if self.invocation_validity.is_not_valid_yet(&claims, now) {
+            return Err(ValidationError::NotValidYet);
+        }
         let inv_claims = claims
             .metadata
             .as_ref()
@@ -0,0 +1,265 @@
+//! Resolution of secret references in link definition values, so that providers do not need
+//! credentials to be stored in plaintext in link definitions. See [`wasmcloud_core::secrets`] for
+//! the format of references and the protocol used to retrieve them
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use tracing::{debug, instrument};
+use wascap::prelude::KeyPair;
+use wasmcloud_core::secrets::{secrets_subject, SecretReference, SecretRequest, SecretResponse};
+
+use crate::{core::LinkDefinition, error::SecretsError};
+
+/// Client retrieving secrets from the secrets backends of the lattice. Requests are signed with
+/// the cluster key, see the [trust model](wasmcloud_core::secrets#trust-model).
+///
+/// The client of a running provider is available via
+/// [`ProviderConnection::secrets_client`](crate::ProviderConnection::secrets_client)
+#[derive(Clone, Debug)]
+pub struct SecretsClient {
+    nats: async_nats::Client,
+    lattice: String,
+    provider_id: String,
+    cluster_key: Arc<KeyPair>,
+    timeout: Duration,
+    /// fake lattice requests are sent on instead of NATS, see [`crate::testkit`]
+    #[cfg(any(test, feature = "testkit"))]
+    fake_lattice: Option<crate::testkit::FakeLattice>,
+}
+
+impl SecretsClient {
+    /// Constructs a client requesting secrets over `nats` on behalf of the provider with public
+    /// key `provider_id`, signing requests with `cluster_key` and waiting at most `timeout` for
+    /// each secret
+    pub fn new(
+        nats: async_nats::Client,
+        lattice: &str,
+        provider_id: &str,
+        cluster_key: Arc<KeyPair>,
+        timeout: Duration,
+    ) -> Self {
+        Self {
+            nats,
+            lattice: lattice.to_string(),
+            provider_id: provider_id.to_string(),
+            cluster_key,
+            timeout,
+            #[cfg(any(test, feature = "testkit"))]
+            fake_lattice: None,
+        }
+    }
+
+    /// Sends requests for secrets on the fake lattice `lattice` instead of NATS
+    #[cfg(any(test, feature = "testkit"))]
+    #[must_use]
+    pub(crate) fn with_fake_lattice(mut self, lattice: crate::testkit::FakeLattice) -> Self {
+        self.fake_lattice = Some(lattice);
+        self
+    }
+
+    /// Sends `request` to `backend` and returns the payload of the response
+    async fn request(&self, backend: &str, request: Vec<u8>) -> Result<Vec<u8>, SecretsError> {
+        let unavailable = |reason: String| SecretsError::Unavailable {
+            backend: backend.to_string(),
+            reason,
+        };
+        let subject = secrets_subject(&self.lattice, backend);
+        #[cfg(any(test, feature = "testkit"))]
+        if let Some(lattice) = &self.fake_lattice {
+            return match lattice.send_request(subject, request, self.timeout).await {
+                Ok(msg) => Ok(msg.payload),
+                Err(crate::error::InvocationError::Timeout) => {
+                    Err(unavailable("timed out waiting for secret".to_string()))
+                }
+                Err(e) => Err(unavailable(e.to_string())),
+            };
+        }
+        let msg = tokio::time::timeout(self.timeout, self.nats.request(subject, request.into()))
+            .await
+            .map_err(|_| unavailable("timed out waiting for secret".to_string()))?
+            .map_err(|e| unavailable(e.to_string()))?;
+        Ok(msg.payload.into())
+    }
+
+    /// Retrieves the secret referenced by `reference` from its backend
+    #[instrument(level = "debug", skip(self), fields(reference = %reference))]
+    pub async fn get(&self, reference: &SecretReference) -> Result<String, SecretsError> {
+        let retrieval = |reason: String| SecretsError::Retrieval {
+            reference: reference.to_string(),
+            reason,
+        };
+        let request = SecretRequest::new(&self.cluster_key, reference, &self.provider_id)
+            .map_err(|e| retrieval(format!("failed to sign request: {e:#}")))?;
+        let request = serde_json::to_vec(&request)
+            .map_err(|e| retrieval(format!("failed to encode request: {e}")))?;
+        let payload = self.request(&reference.backend, request).await?;
+        match serde_json::from_slice::<SecretResponse>(&payload)
+            .map_err(|e| retrieval(format!("invalid response from backend: {e}")))?
+        {
+            SecretResponse {
+                value: Some(value), ..
+            } => {
+                debug!("retrieved secret");
+                Ok(value)
+            }
+            SecretResponse {
+                error: Some(error), ..
+            } => Err(retrieval(error)),
+            SecretResponse { .. } => Err(retrieval("backend returned no value".to_string())),
+        }
+    }
+
+    /// Resolves `value` if it is a secret reference, or returns it as-is otherwise
+    pub async fn resolve(&self, value: &str) -> Result<String, SecretsError> {
+        if !SecretReference::is_reference(value) {
+            return Ok(value.to_string());
+        }
+        let reference = value
+            .parse::<SecretReference>()
+            .map_err(|e| SecretsError::InvalidReference(format!("{e:#}")))?;
+        self.get(&reference).await
+    }
+
+    /// Returns a copy of `ld` with all secret references in its values resolved. Fails if any
+    /// of the references cannot be resolved
+    pub async fn resolve_link(&self, ld: &LinkDefinition) -> Result<LinkDefinition, SecretsError> {
+        let mut values = Vec::with_capacity(ld.values.len());
+        for (key, value) in &ld.values {
+            values.push((key.clone(), self.resolve(value).await?));
+        }
+        Ok(LinkDefinition {
+            values,
+            ..ld.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testkit::{detached_client, FakeLattice};
+
+    const LATTICE: &str = "default";
+
+    async fn client(lattice: &FakeLattice, timeout: Duration) -> SecretsClient {
+        SecretsClient::new(
+            detached_client().await.unwrap(),
+            LATTICE,
+            &KeyPair::new_service().public_key(),
+            Arc::new(KeyPair::new_cluster()),
+            timeout,
+        )
+        .with_fake_lattice(lattice.clone())
+    }
+
+    /// Answers all requests for secrets of the `vault` backend with `response`
+    fn serve_vault(lattice: &FakeLattice, response: SecretResponse) {
+        let mut requests = lattice.subscribe(secrets_subject(LATTICE, "vault"));
+        let lattice = lattice.clone();
+        tokio::spawn(async move {
+            while let Some(msg) = requests.recv().await {
+                if let Some(reply) = msg.reply {
+                    lattice.publish(reply, None, serde_json::to_vec(&response).unwrap());
+                }
+            }
+        });
+    }
+
+    fn link(values: &[(&str, &str)]) -> LinkDefinition {
+        LinkDefinition {
+            actor_id: "actor".to_string(),
+            values: values
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn references_are_resolved_from_their_backend() {
+        let lattice = FakeLattice::default();
+        serve_vault(
+            &lattice,
+            SecretResponse {
+                value: Some("redis://secret".to_string()),
+                error: None,
+            },
+        );
+        let client = client(&lattice, Duration::from_secs(1)).await;
+
+        let ld = link(&[("URL", "secret://vault/apps/redis#url"), ("DB", "0")]);
+        let resolved = client.resolve_link(&ld).await.unwrap();
+
+        assert_eq!(resolved.actor_id, "actor");
+        assert_eq!(
+            resolved.values,
+            vec![
+                ("URL".to_string(), "redis://secret".to_string()),
+                ("DB".to_string(), "0".to_string()),
+            ]
+        );
+        let requests = lattice.messages_on(&secrets_subject(LATTICE, "vault"));
+        assert_eq!(requests.len(), 1, "only the reference is requested");
+        let request: SecretRequest = serde_json::from_slice(&requests[0].payload).unwrap();
+        assert_eq!(request.path, "apps/redis");
+        assert_eq!(request.key.as_deref(), Some("url"));
+        assert_eq!(request.provider_id, client.provider_id);
+    }
+
+    #[tokio::test]
+    async fn backend_errors_are_returned() {
+        let lattice = FakeLattice::default();
+        serve_vault(
+            &lattice,
+            SecretResponse {
+                value: None,
+                error: Some("secret not found".to_string()),
+            },
+        );
+        let client = client(&lattice, Duration::from_secs(1)).await;
+
+        let err = client
+            .resolve_link(&link(&[("URL", "secret://vault/apps/redis#url")]))
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, SecretsError::Retrieval { reference, reason }
+                if reference == "secret://vault/apps/redis#url" && reason == "secret not found"),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn unresponsive_backends_are_unavailable() {
+        let lattice = FakeLattice::default();
+        // subscribed, but never responding
+        let _requests = lattice.subscribe(secrets_subject(LATTICE, "vault"));
+        let client = client(&lattice, Duration::from_millis(50)).await;
+
+        let err = client
+            .get(&"secret://vault/apps/redis#url".parse().unwrap())
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(&err, SecretsError::Unavailable { backend, reason }
+                if backend == "vault" && reason.contains("timed out")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn plain_values_are_passed_through() {
+        let lattice = FakeLattice::default();
+        let client = client(&lattice, Duration::from_secs(1)).await;
+
+        let ld = link(&[("URL", "redis://127.0.0.1:6379"), ("DB", "0")]);
+        let resolved = client.resolve_link(&ld).await.unwrap();
+
+        assert_eq!(resolved.values, ld.values);
+        assert!(lattice.messages().is_empty(), "no secrets are requested");
+    }
+}
@@ -0,0 +1,212 @@
+//! Supervision of background tasks spawned by providers (e.g. watchers or token renewers), which
+//! are tied to the lifecycle of the provider
+
+use std::{
+    future::Future,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use tokio::task::{AbortHandle, JoinHandle};
+use tracing::{debug, error, warn};
+
+/// A supervised task, and the task waiting for it to complete
+#[derive(Debug)]
+struct SupervisedTask {
+    abort: AbortHandle,
+    watcher: JoinHandle<()>,
+}
+
+#[derive(Debug, Default)]
+struct SupervisorState {
+    tasks: Vec<SupervisedTask>,
+    /// Names of tasks that panicked
+    panicked: Vec<String>,
+    /// Whether the supervisor was shut down, after which no tasks are spawned
+    shut_down: bool,
+}
+
+/// Spawns background tasks of a provider, which are cancelled and awaited when the provider shuts
+/// down. Panics of supervised tasks are reported as degraded provider health.
+///
+/// The supervisor of a running provider is available via
+/// [`ProviderConnection::task_supervisor`](crate::ProviderConnection::task_supervisor)
+#[derive(Clone, Debug, Default)]
+pub struct TaskSupervisor {
+    state: Arc<Mutex<SupervisorState>>,
+}
+
+impl TaskSupervisor {
+    /// Spawns `task` on the tokio runtime, identified by `name` in logs and health reports.
+    /// Returns a handle to cancel the task before the provider shuts down, or `None` if the task
+    /// was not spawned, because the provider shut down already
+    pub fn spawn<F>(&self, name: impl Into<String>, task: F) -> Option<AbortHandle>
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let name = name.into();
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        if state.shut_down {
+            warn!(task = %name, "not spawning background task after provider shutdown");
+            return None;
+        }
+        // Forget tasks that completed already
+        state.tasks.retain(|task| !task.watcher.is_finished());
+
+        let handle = tokio::spawn(task);
+        let abort = handle.abort_handle();
+        let supervisor = Arc::downgrade(&self.state);
+        let watcher = tokio::spawn(async move {
+            match handle.await {
+                Ok(()) => debug!(task = %name, "background task completed"),
+                Err(err) if err.is_panic() => {
+                    error!(task = %name, "background task panicked");
+                    if let Some(state) = supervisor.upgrade() {
+                        state
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .panicked
+                            .push(name);
+                    }
+                }
+                Err(_) => debug!(task = %name, "background task cancelled"),
+            }
+        });
+        state.tasks.push(SupervisedTask {
+            abort: abort.clone(),
+            watcher,
+        });
+        Some(abort)
+    }
+
+    /// Returns the names of supervised tasks that panicked
+    pub fn panicked_tasks(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .panicked
+            .clone()
+    }
+
+    /// Cancels all supervised tasks and waits for them to complete. Tasks spawned afterwards are
+    /// not run
+    pub async fn shutdown(&self) {
+        let tasks = {
+            let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+            state.shut_down = true;
+            std::mem::take(&mut state.tasks)
+        };
+        for task in &tasks {
+            task.abort.abort();
+        }
+        for task in tasks {
+            let _ = task.watcher.await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Sets the flag when dropped, i.e. when the task owning it completes or is cancelled
+    struct DropFlag(Arc<AtomicBool>);
+
+    impl Drop for DropFlag {
+        fn drop(&mut self) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    async fn wait_for_panic(supervisor: &TaskSupervisor) -> Vec<String> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let panicked = supervisor.panicked_tasks();
+                if !panicked.is_empty() {
+                    return panicked;
+                }
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("panic of background task was not recorded")
+    }
+
+    #[tokio::test]
+    async fn tasks_are_cancelled_and_awaited_on_shutdown() {
+        let supervisor = TaskSupervisor::default();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let started = Arc::new(tokio::sync::Notify::new());
+        supervisor.spawn("watcher", {
+            let guard = DropFlag(Arc::clone(&dropped));
+            let started = Arc::clone(&started);
+            async move {
+                let _guard = guard;
+                started.notify_one();
+                std::future::pending::<()>().await;
+            }
+        });
+        started.notified().await;
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        // The task is dropped by the time shutdown returns
+        supervisor.shutdown().await;
+        assert!(dropped.load(Ordering::SeqCst));
+        // Cancellation is not reported as a panic
+        assert!(supervisor.panicked_tasks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn tasks_are_not_spawned_after_shutdown() {
+        let supervisor = TaskSupervisor::default();
+        supervisor.shutdown().await;
+
+        let ran = Arc::new(AtomicBool::new(false));
+        let handle = supervisor.spawn("late", {
+            let ran = Arc::clone(&ran);
+            async move { ran.store(true, Ordering::SeqCst) }
+        });
+        assert!(handle.is_none());
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!ran.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn tasks_are_cancelled_individually() {
+        let supervisor = TaskSupervisor::default();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let handle = supervisor
+            .spawn("renewer", {
+                let guard = DropFlag(Arc::clone(&dropped));
+                async move {
+                    let _guard = guard;
+                    std::future::pending::<()>().await;
+                }
+            })
+            .expect("task was not spawned");
+        handle.abort();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !dropped.load(Ordering::SeqCst) {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("task was not cancelled");
+        assert!(supervisor.panicked_tasks().is_empty());
+        supervisor.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn panics_are_reported() {
+        let supervisor = TaskSupervisor::default();
+        supervisor.spawn("completes", async {});
+        supervisor.spawn("fails", async { panic!("background task failed") });
+        assert_eq!(wait_for_panic(&supervisor).await, ["fails"]);
+
+        // Panics are still reported after the remaining tasks are shut down
+        supervisor.shutdown().await;
+        assert_eq!(supervisor.panicked_tasks(), ["fails"]);
+    }
+}
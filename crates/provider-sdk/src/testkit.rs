@@ -0,0 +1,1057 @@
+//! Test kit for exercising providers in-process, without a host or NATS server.
+//!
+//! A [`TestHost`] serves the provider under test on a [`FakeLattice`], in which in-process
+//! channels stand in for NATS. The test host puts and deletes links and sends signed invocations
+//! over the fake lattice the same way a host would, and records the invocations and the responses
+//! of the provider for assertions:
+//!
+//! ```ignore
+//! let host = TestHost::start(MyProvider::default()).await;
+//! assert!(host.put_link(host.link_definition(ACTOR_ID, [("url", "redis://...")])).await?);
+//! let resp = host.invoke(ACTOR_ID, "KeyValue.Get", serialize(&"key")?).await?;
+//! host.assert_invoked("KeyValue.Get");
+//! ```
+//!
+//! Received messages are handled by a [`ProviderConnection`] like those of providers started by
+//! the SDK, so invocations pass through the same validation (including the
+//! [`InvocationValidator`](crate::InvocationValidator)s set in [`TestHostConfig::connection`]),
+//! rate limiting, transcoding and dispatch. Payloads are not chunked, since messages on the fake
+//! lattice are not limited in size.
+//!
+//! Invocations the provider sends through the RPC client of [`TestHost::connection`] are published
+//! on the fake lattice as well, and answered by actors mocked with [`TestHost::mock_actor`]:
+//!
+//! ```ignore
+//! host.mock_actor(ACTOR_ID, |inv| Ok(serialize(&format!("handled {}", inv.operation))?));
+//! provider.notify(host.connection(), ACTOR_ID).await?;
+//! host.assert_sent("Handler.HandleMessage");
+//! ```
+//!
+//! Providers under test must not rely on [`get_connection`](crate::provider_main::get_connection),
+//! which is only set for providers started by the SDK, and should be passed the connection of the
+//! test host instead.
+
+use std::{
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+    time::Duration,
+};
+
+use async_nats::{RequestError, RequestErrorKind};
+use tokio::{sync::mpsc, task::JoinHandle};
+use wascap::prelude::KeyPair;
+
+use crate::{
+    codec::Codec,
+    core::{
+        HealthCheckResponse, HostData, Invocation, InvocationErrorKind, InvocationResponse,
+        LinkDefinition, TraceContext, WasmCloudEntity, MIN_SCHEMA_VERSION, SCHEMA_VERSION,
+    },
+    deserialize,
+    error::{
+        InvocationError, InvocationResult, NetworkError, ProviderInvocationError, ProviderResult,
+    },
+    link_store::LinkStore,
+    provider_main::ConnectionConfig,
+    serialize, Provider, ProviderConnection,
+};
+
+/// Time a [`TestHost`] waits for the provider under test to respond, unless configured otherwise
+pub const DEFAULT_TEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A message published on a [`FakeLattice`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FakeMessage {
+    /// Subject the message was published on
+    pub subject: String,
+    /// Subject responses to the message are expected on, if any
+    pub reply: Option<String>,
+    /// Payload of the message
+    pub payload: Vec<u8>,
+}
+
+/// In-process stand-in for the NATS connection of a lattice.
+///
+/// Messages are delivered to all subscribers of their subject at the time they are published, and
+/// recorded for assertions. Like in NATS, subscriptions to subjects ending with `>` receive the
+/// messages published on all subjects starting with the rest of the subscription subject
+#[derive(Clone, Debug, Default)]
+pub struct FakeLattice {
+    state: Arc<Mutex<FakeLatticeState>>,
+}
+
+#[derive(Debug, Default)]
+struct FakeLatticeState {
+    subscriptions: Vec<(String, mpsc::UnboundedSender<FakeMessage>)>,
+    messages: Vec<FakeMessage>,
+    next_inbox: u64,
+}
+
+/// Returns true if `subject` matches the subscription subject `pattern`
+fn subject_matches(pattern: &str, subject: &str) -> bool {
+    match pattern.strip_suffix('>') {
+        Some(prefix) => subject.len() > prefix.len() && subject.starts_with(prefix),
+        None => pattern == subject,
+    }
+}
+
+impl FakeLattice {
+    fn state(&self) -> MutexGuard<'_, FakeLatticeState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Subscribes to the messages published on `subject` from now on
+    pub fn subscribe(&self, subject: impl Into<String>) -> mpsc::UnboundedReceiver<FakeMessage> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.state().subscriptions.push((subject.into(), tx));
+        rx
+    }
+
+    /// Publishes `payload` on `subject`, asking for responses on `reply` if set. Returns the
+    /// number of subscribers the message was delivered to
+    pub fn publish(
+        &self,
+        subject: impl Into<String>,
+        reply: Option<String>,
+        payload: Vec<u8>,
+    ) -> usize {
+        let msg = FakeMessage {
+            subject: subject.into(),
+            reply,
+            payload,
+        };
+        let mut state = self.state();
+        let mut delivered = 0;
+        state.subscriptions.retain(|(pattern, tx)| {
+            if !subject_matches(pattern, &msg.subject) {
+                return !tx.is_closed();
+            }
+            let sent = tx.send(msg.clone()).is_ok();
+            if sent {
+                delivered += 1;
+            }
+            sent
+        });
+        state.messages.push(msg);
+        delivered
+    }
+
+    /// Publishes `payload` on `subject` and waits at most `timeout` for the first response
+    pub async fn request(
+        &self,
+        subject: impl Into<String>,
+        payload: Vec<u8>,
+        timeout: Duration,
+    ) -> Result<FakeMessage, ProviderInvocationError> {
+        let subject = subject.into();
+        self.send_request(subject.clone(), payload, timeout)
+            .await
+            .map_err(|err| match err {
+                InvocationError::Timeout => ProviderInvocationError::Timeout(format!(
+                    "no response on `{subject}` within {timeout:?}"
+                )),
+                _ => ProviderInvocationError::BackendUnavailable(format!(
+                    "no responders on `{subject}`"
+                )),
+            })
+    }
+
+    /// Like [`FakeLattice::request`], failing like a NATS request of an [`RpcClient`](crate::RpcClient) would
+    pub(crate) async fn send_request(
+        &self,
+        subject: String,
+        payload: Vec<u8>,
+        timeout: Duration,
+    ) -> InvocationResult<FakeMessage> {
+        let inbox = {
+            let mut state = self.state();
+            state.next_inbox += 1;
+            format!("_INBOX.{}", state.next_inbox)
+        };
+        let mut responses = self.subscribe(inbox.clone());
+        if self.publish(subject, Some(inbox), payload) == 0 {
+            return Err(
+                NetworkError::from(RequestError::from(RequestErrorKind::NoResponders)).into(),
+            );
+        }
+        match tokio::time::timeout(timeout, responses.recv()).await {
+            Ok(Some(msg)) => Ok(msg),
+            Ok(None) | Err(_) => Err(InvocationError::Timeout),
+        }
+    }
+
+    /// Returns all messages published so far, in order
+    pub fn messages(&self) -> Vec<FakeMessage> {
+        self.state().messages.clone()
+    }
+
+    /// Returns the messages published so far on subjects matching `subject`, in order
+    pub fn messages_on(&self, subject: &str) -> Vec<FakeMessage> {
+        self.state()
+            .messages
+            .iter()
+            .filter(|msg| subject_matches(subject, &msg.subject))
+            .cloned()
+            .collect()
+    }
+}
+
+/// An invocation sent to or by the provider under test, and the response to it
+#[derive(Clone, Debug)]
+pub struct RecordedRpc {
+    /// The invocation
+    pub invocation: Invocation,
+    /// The response to the invocation
+    pub response: InvocationResponse,
+}
+
+/// Settings of a [`TestHost`]
+#[derive(Clone, Debug)]
+pub struct TestHostConfig {
+    /// Prefix of the lattice the provider is served on
+    pub lattice_prefix: String,
+    /// Public key of the provider under test
+    pub provider_id: String,
+    /// Link name of the provider under test
+    pub link_name: String,
+    /// Contract ID of the provider under test
+    pub contract_id: String,
+    /// Time to wait for the provider to respond to each message, and for responses to the
+    /// invocations it sends
+    pub timeout: Duration,
+    /// Settings of the connection of the provider, e.g. its invocation validators. The pool size
+    /// is ignored, since the fake lattice is not reached over NATS connections
+    pub connection: ConnectionConfig,
+}
+
+impl Default for TestHostConfig {
+    fn default() -> Self {
+        Self {
+            lattice_prefix: "default".to_string(),
+            provider_id: KeyPair::new_service().public_key(),
+            link_name: "default".to_string(),
+            contract_id: "wasmcloud:test".to_string(),
+            timeout: DEFAULT_TEST_TIMEOUT,
+            connection: ConnectionConfig::default(),
+        }
+    }
+}
+
+impl TestHostConfig {
+    /// Subject the provider receives invocations on
+    fn rpc_subject(&self) -> String {
+        format!(
+            "wasmbus.rpc.{}.{}.{}",
+            self.lattice_prefix, self.provider_id, self.link_name
+        )
+    }
+}
+
+/// Stand-in for the host of a provider under test, see the [module documentation](self)
+pub struct TestHost<P> {
+    provider: P,
+    config: TestHostConfig,
+    lattice: FakeLattice,
+    connection: ProviderConnection,
+    cluster_key: Arc<KeyPair>,
+    host_key: Arc<KeyPair>,
+    rpcs: Mutex<Vec<RecordedRpc>>,
+    sent_rpcs: Arc<Mutex<Vec<RecordedRpc>>>,
+    handles: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl<P: Provider + Clone> TestHost<P> {
+    /// Starts serving `provider` on a new fake lattice with the default [`TestHostConfig`]
+    ///
+    /// # Panics
+    ///
+    /// Panics if the connection of the provider cannot be constructed, see
+    /// [`TestHost::try_start_with_config`]
+    pub async fn start(provider: P) -> Self {
+        Self::start_with_config(provider, TestHostConfig::default()).await
+    }
+
+    /// Starts serving `provider` on a new fake lattice as configured by `config`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the connection of the provider cannot be constructed, see
+    /// [`TestHost::try_start_with_config`]
+    pub async fn start_with_config(provider: P, config: TestHostConfig) -> Self {
+        Self::try_start_with_config(provider, config)
+            .await
+            .expect("failed to start test host")
+    }
+
+    /// Starts serving `provider` on a new fake lattice as configured by `config`, failing if the
+    /// connection of the provider cannot be constructed
+    pub async fn try_start_with_config(
+        provider: P,
+        config: TestHostConfig,
+    ) -> ProviderResult<Self> {
+        let lattice = FakeLattice::default();
+        let cluster_key = Arc::new(KeyPair::new_cluster());
+        let host_key = Arc::new(KeyPair::new_server());
+        let host_data = HostData {
+            schema_version: SCHEMA_VERSION,
+            min_schema_version: MIN_SCHEMA_VERSION,
+            host_id: host_key.public_key(),
+            lattice_rpc_prefix: config.lattice_prefix.clone(),
+            link_name: config.link_name.clone(),
+            provider_key: config.provider_id.clone(),
+            invocation_seed: cluster_key.seed().unwrap_or_default(),
+            cluster_issuers: vec![cluster_key.public_key()],
+            default_rpc_timeout_ms: Some(config.timeout.as_millis() as u64),
+            ..Default::default()
+        };
+        let connection = ProviderConnection::new(
+            vec![detached_client().await?],
+            &host_data,
+            &config.connection,
+        )?
+        .with_fake_lattice(lattice.clone());
+
+        let rpc_subject = config.rpc_subject();
+        let rpcs = lattice.subscribe(rpc_subject.clone());
+        let control = lattice.subscribe(format!("{rpc_subject}.>"));
+        let handles = vec![
+            tokio::spawn(serve_rpc(
+                provider.clone(),
+                connection.clone(),
+                rpcs,
+                config.lattice_prefix.clone(),
+            )),
+            tokio::spawn(serve_control(
+                provider.clone(),
+                connection.clone(),
+                lattice.clone(),
+                control,
+                rpc_subject,
+                config.lattice_prefix.clone(),
+            )),
+        ];
+        Ok(Self {
+            provider,
+            config,
+            lattice,
+            connection,
+            cluster_key,
+            host_key,
+            rpcs: Mutex::default(),
+            sent_rpcs: Arc::default(),
+            handles: Mutex::new(handles),
+        })
+    }
+
+    /// Returns the provider under test
+    pub fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Returns the fake lattice the provider is served on
+    pub fn lattice(&self) -> &FakeLattice {
+        &self.lattice
+    }
+
+    /// Returns the connection handling the messages received by the provider, whose RPC client
+    /// sends invocations on the fake lattice
+    pub fn connection(&self) -> &ProviderConnection {
+        &self.connection
+    }
+
+    /// Returns the link definitions the provider accepted
+    pub fn link_store(&self) -> &LinkStore {
+        self.connection.link_store()
+    }
+
+    /// Returns a link definition linking the actor with ID `actor_id` to the provider with
+    /// `values`
+    pub fn link_definition<K, V>(
+        &self,
+        actor_id: &str,
+        values: impl IntoIterator<Item = (K, V)>,
+    ) -> LinkDefinition
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        LinkDefinition {
+            actor_id: actor_id.to_string(),
+            provider_id: self.config.provider_id.clone(),
+            link_name: self.config.link_name.clone(),
+            contract_id: self.config.contract_id.clone(),
+            values: values
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+
+    /// Puts the link definition `ld`, returning whether the provider accepted it
+    pub async fn put_link(&self, ld: LinkDefinition) -> Result<bool, ProviderInvocationError> {
+        let resp = self
+            .control_request("linkdefs.put", serialize(&ld)?)
+            .await?;
+        Ok(deserialize(&resp.payload)?)
+    }
+
+    /// Deletes the link of the actor with ID `actor_id`
+    pub async fn delete_link(&self, actor_id: &str) -> Result<(), ProviderInvocationError> {
+        let ld = match self.connection.get_link(actor_id).await {
+            Some(ld) => ld,
+            None => self.link_definition(actor_id, Vec::<(String, String)>::new()),
+        };
+        self.control_request("linkdefs.del", serialize(&ld)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Sends a health check request to the provider
+    pub async fn health_check(&self) -> Result<HealthCheckResponse, ProviderInvocationError> {
+        let resp = self.control_request("health", Vec::new()).await?;
+        Ok(deserialize(&resp.payload)?)
+    }
+
+    /// Asks the provider to shut down, after which it no longer handles link definitions and
+    /// health checks
+    pub async fn shutdown(&self) -> Result<(), ProviderInvocationError> {
+        self.control_request("shutdown", Vec::new()).await?;
+        Ok(())
+    }
+
+    /// Invokes `operation` of the provider with the MessagePack-encoded `payload` on behalf of the
+    /// actor with ID `actor_id`, returning the response payload. Like the SDK, the provider only
+    /// handles invocations of linked actors
+    pub async fn invoke(
+        &self,
+        actor_id: &str,
+        operation: &str,
+        payload: Vec<u8>,
+    ) -> Result<Vec<u8>, ProviderInvocationError> {
+        self.invoke_with_codec(actor_id, operation, payload, Codec::MsgPack)
+            .await
+    }
+
+    /// Like [`TestHost::invoke`], for a `payload` encoded with `codec`, which the response is
+    /// encoded with as well
+    pub async fn invoke_with_codec(
+        &self,
+        actor_id: &str,
+        operation: &str,
+        payload: Vec<u8>,
+        codec: Codec,
+    ) -> Result<Vec<u8>, ProviderInvocationError> {
+        let mut invocation = Invocation::new(
+            &self.cluster_key,
+            &self.host_key,
+            WasmCloudEntity {
+                public_key: actor_id.to_string(),
+                ..Default::default()
+            },
+            WasmCloudEntity {
+                public_key: self.config.provider_id.clone(),
+                link_name: self.config.link_name.clone(),
+                contract_id: self.config.contract_id.clone(),
+            },
+            format!("{}/{operation}", self.config.contract_id),
+            payload,
+            TraceContext::default(),
+        )
+        .map_err(|err| ProviderInvocationError::Provider(format!("{err:#}")))?;
+        invocation.content_type = Some(codec.content_type().to_string());
+        let resp = self
+            .lattice
+            .request(
+                self.config.rpc_subject(),
+                serialize(&invocation)?,
+                self.config.timeout,
+            )
+            .await?;
+        let response: InvocationResponse = deserialize(&resp.payload)?;
+        lock(&self.rpcs).push(RecordedRpc {
+            invocation,
+            response: response.clone(),
+        });
+        match response.error {
+            Some(error) => Err(ProviderInvocationError::from_response(
+                response.error_kind,
+                error,
+            )),
+            None => Ok(response.msg),
+        }
+    }
+
+    /// Returns the invocations sent to the provider so far, with their responses, in order
+    pub fn rpcs(&self) -> Vec<RecordedRpc> {
+        lock(&self.rpcs).clone()
+    }
+
+    /// Asserts that `operation` was invoked, returning the last invocation of it
+    ///
+    /// # Panics
+    ///
+    /// Panics if `operation` was not invoked
+    pub fn assert_invoked(&self, operation: &str) -> RecordedRpc {
+        find_last(&self.rpcs(), operation).unwrap_or_else(|operations| {
+            panic!("expected `{operation}` to be invoked, but got invocations of {operations:?}")
+        })
+    }
+
+    /// Asserts that `operation` was not invoked
+    ///
+    /// # Panics
+    ///
+    /// Panics if `operation` was invoked
+    pub fn assert_not_invoked(&self, operation: &str) {
+        let count = count(&self.rpcs(), operation);
+        assert!(
+            count == 0,
+            "expected `{operation}` not to be invoked, but it was invoked {count} time(s)"
+        );
+    }
+
+    /// Answers the invocations the provider sends to the actor with ID `actor_id` with the
+    /// payload returned by `handler`, or with its error. Invocations answered by the mocked actor
+    /// are recorded, see [`TestHost::sent_rpcs`]
+    pub fn mock_actor<F>(&self, actor_id: &str, handler: F)
+    where
+        F: Fn(&Invocation) -> Result<Vec<u8>, String> + Send + 'static,
+    {
+        let mut sub = self.lattice.subscribe(format!(
+            "wasmbus.rpc.{}.{actor_id}",
+            self.config.lattice_prefix
+        ));
+        let lattice = self.lattice.clone();
+        let sent_rpcs = Arc::clone(&self.sent_rpcs);
+        let handle = tokio::spawn(async move {
+            while let Some(msg) = sub.recv().await {
+                let Ok(invocation) = deserialize::<Invocation>(&msg.payload) else {
+                    continue;
+                };
+                let response = match handler(&invocation) {
+                    Ok(payload) => InvocationResponse {
+                        invocation_id: invocation.id.clone(),
+                        content_length: payload.len() as u64,
+                        msg: payload,
+                        content_type: invocation.content_type.clone(),
+                        ..Default::default()
+                    },
+                    Err(error) => InvocationResponse {
+                        invocation_id: invocation.id.clone(),
+                        error: Some(error),
+                        error_kind: Some(InvocationErrorKind::Custom),
+                        ..Default::default()
+                    },
+                };
+                respond(
+                    &lattice,
+                    &msg,
+                    serialize(&response).expect("failed to encode invocation response"),
+                );
+                lock(&sent_rpcs).push(RecordedRpc {
+                    invocation,
+                    response,
+                });
+            }
+        });
+        lock(&self.handles).push(handle);
+    }
+
+    /// Returns the invocations the provider sent to mocked actors so far, with their responses,
+    /// in order
+    pub fn sent_rpcs(&self) -> Vec<RecordedRpc> {
+        lock(&self.sent_rpcs).clone()
+    }
+
+    /// Asserts that the provider sent an invocation of `operation` to a mocked actor, returning
+    /// the last one
+    ///
+    /// # Panics
+    ///
+    /// Panics if no invocation of `operation` was sent
+    pub fn assert_sent(&self, operation: &str) -> RecordedRpc {
+        find_last(&self.sent_rpcs(), operation).unwrap_or_else(|operations| {
+            panic!("expected `{operation}` to be sent, but got invocations of {operations:?}")
+        })
+    }
+
+    /// Sends a request to the provider on the control subject ending with `suffix`
+    async fn control_request(
+        &self,
+        suffix: &str,
+        payload: Vec<u8>,
+    ) -> Result<FakeMessage, ProviderInvocationError> {
+        self.lattice
+            .request(
+                format!("{}.{suffix}", self.config.rpc_subject()),
+                payload,
+                self.config.timeout,
+            )
+            .await
+    }
+}
+
+impl<P> Drop for TestHost<P> {
+    fn drop(&mut self) {
+        for handle in lock(&self.handles).iter() {
+            handle.abort();
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the last of `rpcs` invoking `operation`, or the operations of all of them if there is
+/// none
+fn find_last(rpcs: &[RecordedRpc], operation: &str) -> Result<RecordedRpc, Vec<String>> {
+    rpcs.iter()
+        .rev()
+        .find(|rpc| rpc.invocation.operation == operation)
+        .cloned()
+        .ok_or_else(|| {
+            rpcs.iter()
+                .map(|rpc| rpc.invocation.operation.clone())
+                .collect()
+        })
+}
+
+/// Returns the number of `rpcs` invoking `operation`
+fn count(rpcs: &[RecordedRpc], operation: &str) -> usize {
+    rpcs.iter()
+        .filter(|rpc| rpc.invocation.operation == operation)
+        .count()
+}
+
+/// Returns a NATS client, which never connects, for the parts of a [`ProviderConnection`] the
+/// fake lattice does not stand in for
+pub(crate) async fn detached_client() -> ProviderResult<async_nats::Client> {
+    Ok(async_nats::ConnectOptions::new()
+        .retry_on_initial_connect()
+        .connect("nats://127.0.0.1:0")
+        .await?)
+}
+
+/// Responds to `msg` with `payload`, if a response is expected
+fn respond(lattice: &FakeLattice, msg: &FakeMessage, payload: Vec<u8>) {
+    if let Some(reply) = &msg.reply {
+        lattice.publish(reply.clone(), None, payload);
+    }
+}
+
+/// Handles invocations received by the provider, concurrently like the SDK
+async fn serve_rpc<P: Provider + Clone>(
+    provider: P,
+    connection: ProviderConnection,
+    mut sub: mpsc::UnboundedReceiver<FakeMessage>,
+    lattice_prefix: String,
+) {
+    while let Some(msg) = sub.recv().await {
+        let permit = connection.invocation_permit().await;
+        let provider = provider.clone();
+        let connection = connection.clone();
+        let lattice_prefix = lattice_prefix.clone();
+        tokio::spawn(async move {
+            let _permit = permit;
+            let (response, origin) = match deserialize::<Invocation>(&msg.payload) {
+                Ok(inv) => {
+                    let origin = inv.origin.public_key.clone();
+                    let response = connection
+                        .handle_invocation(provider, inv, &lattice_prefix)
+                        .await;
+                    (response, origin)
+                }
+                Err(err) => (
+                    InvocationResponse {
+                        error: Some(format!(
+                            "Error when attempting to deserialize invocation: {err}"
+                        )),
+                        error_kind: Some(InvocationErrorKind::Malformed),
+                        ..Default::default()
+                    },
+                    String::new(),
+                ),
+            };
+            if let Some(reply) = msg.reply {
+                connection
+                    .get_rpc_client()
+                    .publish_invocation_response(reply.into(), response, &origin)
+                    .await
+                    .expect("failed to publish invocation response");
+            }
+        });
+    }
+}
+
+/// Handles link definitions, health checks and shutdown requests received by the provider, in
+/// the order they are sent
+async fn serve_control<P: Provider>(
+    provider: P,
+    connection: ProviderConnection,
+    lattice: FakeLattice,
+    mut sub: mpsc::UnboundedReceiver<FakeMessage>,
+    rpc_subject: String,
+    lattice_prefix: String,
+) {
+    while let Some(msg) = sub.recv().await {
+        let Some(suffix) = msg
+            .subject
+            .strip_prefix(rpc_subject.as_str())
+            .and_then(|s| s.strip_prefix('.'))
+        else {
+            continue;
+        };
+        match suffix {
+            "linkdefs.put" => {
+                let ld: LinkDefinition =
+                    deserialize(&msg.payload).expect("failed to decode link definition");
+                let accepted = connection.accept_link(&provider, &lattice_prefix, ld).await;
+                respond(
+                    &lattice,
+                    &msg,
+                    serialize(&accepted).expect("failed to encode response"),
+                );
+            }
+            "linkdefs.del" => {
+                let ld: LinkDefinition =
+                    deserialize(&msg.payload).expect("failed to decode link definition");
+                connection
+                    .remove_link(&provider, &lattice_prefix, &ld.actor_id)
+                    .await;
+                respond(&lattice, &msg, Vec::new());
+            }
+            "health" => {
+                let resp = connection.check_health(&provider).await;
+                respond(
+                    &lattice,
+                    &msg,
+                    serialize(&resp).expect("failed to encode health check response"),
+                );
+            }
+            "shutdown" => {
+                connection.shutdown_provider(&provider).await;
+                // Fail further requests right away, instead of leaving them unanswered
+                sub.close();
+                respond(&lattice, &msg, Vec::new());
+                break;
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    use async_trait::async_trait;
+    use wascap::{jwt, prelude::Claims};
+
+    use super::{TestHost, TestHostConfig};
+    use crate::{
+        codec::Codec,
+        core::{Invocation, WasmCloudEntity},
+        deserialize,
+        error::{ProviderInvocationError, ValidationError},
+        provider_main::ConnectionConfig,
+        rate_limit::{BURST_LINK_VALUE, MAX_RPS_LINK_VALUE},
+        serialize, Context, InvocationValidator, MessageDispatch, Provider, ProviderHandler,
+    };
+
+    const ACTOR_ID: &str = "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5";
+    const OTHER_ACTOR_ID: &str = "MB2ZQB6ROOMAYBO4ZCTFYWN7YIVBWA3MTKZYAQKJMTIHE2ELLRW2E3ZW";
+
+    #[derive(Clone, Default)]
+    struct EchoProvider {
+        shut_down: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl ProviderHandler for EchoProvider {
+        async fn put_link(&self, ld: &crate::core::LinkDefinition) -> bool {
+            !ld.values.iter().any(|(k, _)| k == "deny")
+        }
+
+        async fn shutdown(&self) {
+            self.shut_down.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[async_trait]
+    impl MessageDispatch for EchoProvider {
+        async fn dispatch<'a>(
+            &'a self,
+            ctx: Context,
+            method: String,
+            body: Cow<'a, [u8]>,
+        ) -> Result<Vec<u8>, ProviderInvocationError> {
+            match method.as_str() {
+                "Echo.Echo" => Ok(body.into_owned()),
+                "Echo.Actor" => Ok(serialize(&ctx.actor)?),
+                "Echo.Fail" => Err(ProviderInvocationError::BackendUnavailable(
+                    "backend is down".to_string(),
+                )),
+                _ => Err(ProviderInvocationError::Malformed(format!(
+                    "unknown method {method}"
+                ))),
+            }
+        }
+
+        fn codec(&self) -> Option<Codec> {
+            Some(Codec::MsgPack)
+        }
+    }
+
+    impl Provider for EchoProvider {}
+
+    struct DenyOperation(&'static str);
+
+    #[async_trait]
+    impl InvocationValidator for DenyOperation {
+        async fn validate(
+            &self,
+            inv: &Invocation,
+            _claims: &Claims<jwt::Invocation>,
+        ) -> Result<(), ValidationError> {
+            if inv.operation == self.0 {
+                Err(ValidationError::InvalidSignature)
+            } else {
+                Ok(())
+            }
+        }
+    }
+
+    async fn linked_host(config: TestHostConfig) -> TestHost<EchoProvider> {
+        let host = TestHost::start_with_config(EchoProvider::default(), config).await;
+        assert!(host
+            .put_link(host.link_definition(ACTOR_ID, Vec::<(String, String)>::new()))
+            .await
+            .expect("failed to put link"));
+        host
+    }
+
+    #[tokio::test]
+    async fn invocations_are_dispatched_and_recorded() {
+        let host = linked_host(TestHostConfig::default()).await;
+        let resp = host
+            .invoke(ACTOR_ID, "Echo.Echo", serialize(&"hello").unwrap())
+            .await
+            .expect("failed to invoke provider");
+        assert_eq!(deserialize::<String>(&resp).unwrap(), "hello");
+
+        let resp = host
+            .invoke(ACTOR_ID, "Echo.Actor", Vec::new())
+            .await
+            .expect("failed to invoke provider");
+        assert_eq!(
+            deserialize::<Option<String>>(&resp).unwrap().as_deref(),
+            Some(ACTOR_ID)
+        );
+
+        let rpc = host.assert_invoked("Echo.Echo");
+        assert_eq!(rpc.invocation.origin.public_key, ACTOR_ID);
+        assert!(rpc.response.error.is_none());
+        host.assert_not_invoked("Echo.Fail");
+        assert_eq!(host.rpcs().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn invocations_of_unlinked_actors_are_rejected() {
+        let host = linked_host(TestHostConfig::default()).await;
+        let err = host
+            .invoke(OTHER_ACTOR_ID, "Echo.Echo", serialize(&"hello").unwrap())
+            .await
+            .expect_err("invocation of an unlinked actor should fail");
+        assert!(
+            matches!(err, ProviderInvocationError::NotLinked(_)),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn links_are_put_and_deleted() {
+        let host = TestHost::start(EchoProvider::default()).await;
+        assert!(!host
+            .put_link(host.link_definition(ACTOR_ID, [("deny", "true")]))
+            .await
+            .unwrap());
+        assert!(!host.connection().is_linked(ACTOR_ID).await);
+
+        assert!(host
+            .put_link(host.link_definition(ACTOR_ID, [("key", "value")]))
+            .await
+            .unwrap());
+        assert!(host.connection().is_linked(ACTOR_ID).await);
+        assert!(host.link_store().contains("default", ACTOR_ID).await);
+
+        host.delete_link(ACTOR_ID).await.unwrap();
+        assert!(!host.connection().is_linked(ACTOR_ID).await);
+        assert!(host
+            .invoke(ACTOR_ID, "Echo.Echo", serialize(&"hello").unwrap())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn errors_are_returned_with_their_kind() {
+        let host = linked_host(TestHostConfig::default()).await;
+        let err = host
+            .invoke(ACTOR_ID, "Echo.Fail", Vec::new())
+            .await
+            .expect_err("invocation should fail");
+        assert!(
+            matches!(err, ProviderInvocationError::BackendUnavailable(_)),
+            "{err:?}"
+        );
+        assert!(host.assert_invoked("Echo.Fail").response.error.is_some());
+    }
+
+    #[tokio::test]
+    async fn invocation_validators_are_applied() {
+        let host = linked_host(TestHostConfig {
+            connection: ConnectionConfig::default()
+                .with_invocation_validator(DenyOperation("Echo.Echo")),
+            ..Default::default()
+        })
+        .await;
+        let err = host
+            .invoke(ACTOR_ID, "Echo.Echo", serialize(&"hello").unwrap())
+            .await
+            .expect_err("invocation should be rejected by the validator");
+        assert!(
+            matches!(err, ProviderInvocationError::PermissionDenied(_)),
+            "{err:?}"
+        );
+        host.invoke(ACTOR_ID, "Echo.Actor", Vec::new())
+            .await
+            .expect("invocation should pass the validator");
+    }
+
+    #[tokio::test]
+    async fn invocations_are_rate_limited() {
+        let host = TestHost::start(EchoProvider::default()).await;
+        assert!(host
+            .put_link(host.link_definition(
+                ACTOR_ID,
+                [(MAX_RPS_LINK_VALUE, "0.001"), (BURST_LINK_VALUE, "1")]
+            ))
+            .await
+            .unwrap());
+        host.invoke(ACTOR_ID, "Echo.Actor", Vec::new())
+            .await
+            .expect("first invocation should be allowed");
+        let err = host
+            .invoke(ACTOR_ID, "Echo.Actor", Vec::new())
+            .await
+            .expect_err("second invocation should be throttled");
+        assert!(
+            matches!(err, ProviderInvocationError::Throttled(_)),
+            "{err:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn payloads_are_transcoded_to_the_codec_of_the_provider() {
+        let host = linked_host(TestHostConfig::default()).await;
+        let resp = host
+            .invoke_with_codec(
+                ACTOR_ID,
+                "Echo.Echo",
+                br#"{"key":"value"}"#.to_vec(),
+                Codec::Json,
+            )
+            .await
+            .expect("failed to invoke provider");
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(&resp).unwrap(),
+            serde_json::json!({ "key": "value" })
+        );
+    }
+
+    #[tokio::test]
+    async fn sent_invocations_are_answered_by_mocked_actors() {
+        let host = linked_host(TestHostConfig::default()).await;
+        host.mock_actor(ACTOR_ID, |inv| match inv.operation.as_str() {
+            "Handler.Handle" => Ok(inv.msg.clone()),
+            operation => Err(format!("unexpected operation {operation}")),
+        });
+        let client = host.connection().get_rpc_client();
+        let origin = WasmCloudEntity {
+            public_key: host.config.provider_id.clone(),
+            link_name: host.config.link_name.clone(),
+            contract_id: host.config.contract_id.clone(),
+        };
+        let target = WasmCloudEntity {
+            public_key: ACTOR_ID.to_string(),
+            ..Default::default()
+        };
+
+        let resp = client
+            .send(
+                origin.clone(),
+                target.clone(),
+                "Handler.Handle",
+                serialize(&"event").unwrap(),
+            )
+            .await
+            .expect("failed to send invocation");
+        assert!(resp.error.is_none());
+        assert_eq!(deserialize::<String>(&resp.msg).unwrap(), "event");
+        let rpc = host.assert_sent("Handler.Handle");
+        assert_eq!(rpc.invocation.target.public_key, ACTOR_ID);
+
+        let resp = client
+            .send(origin.clone(), target, "Handler.Other", Vec::new())
+            .await
+            .expect("failed to send invocation");
+        assert!(resp.error.is_some());
+        assert_eq!(host.sent_rpcs().len(), 2);
+
+        // Nothing answers invocations of actors, which are not mocked
+        let unmocked = WasmCloudEntity {
+            public_key: OTHER_ACTOR_ID.to_string(),
+            ..Default::default()
+        };
+        let err = client
+            .send(origin, unmocked, "Handler.Handle", Vec::new())
+            .await
+            .expect_err("invocation of an actor, which is not mocked, should fail");
+        assert!(err.is_retryable(), "{err:?}");
+    }
+
+    #[tokio::test]
+    async fn health_checks_and_shutdown_are_handled() {
+        let host = TestHost::start(EchoProvider::default()).await;
+        assert!(host.health_check().await.unwrap().healthy);
+
+        host.connection()
+            .task_supervisor()
+            .spawn("failing", async { panic!("background task failed") });
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while host
+                .connection()
+                .task_supervisor()
+                .panicked_tasks()
+                .is_empty()
+            {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("panic of background task was not recorded");
+        let health = host.health_check().await.unwrap();
+        assert!(!health.healthy);
+        assert!(health.message.unwrap().contains("failing"));
+
+        host.shutdown().await.unwrap();
+        assert!(host.provider().shut_down.load(Ordering::Relaxed));
+        assert!(host.health_check().await.is_err());
+    }
+}
@@ -25,16 +25,43 @@
 //!
 //! For more information on the options available to underlying bindgen, see the [wasmtime-component-bindgen documentation](https://docs.rs/wasmtime/latest/wasmtime/component/macro.bindgen.html).
 //!
+//! Instead of a world name, `wit_bindgen_cfg` may also be the WIT source defining the world, which is useful for
+//! single-file providers and examples that have no `wit` directory:
+//!
+//! ```rust,ignore
+//! wasmcloud_provider_wit_bindgen::generate!({
+//!     impl_struct: EchoProvider,
+//!     contract: "wasmcloud:echo",
+//!     wit_bindgen_cfg: "
+//!         package wasmcloud:provider-echo;
+//!
+//!         interface echo {
+//!             echo: func(msg: string) -> string;
+//!         }
+//!
+//!         world provider-echo {
+//!             import echo;
+//!         }
+//!     "
+//! });
+//! ```
+//!
 
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::{Duration, SystemTime},
+};
 
 use anyhow::{bail, ensure, Context};
-use heck::{ToSnakeCase, ToUpperCamelCase};
+use heck::{ToKebabCase, ToShoutySnakeCase, ToSnakeCase, ToUpperCamelCase};
 use proc_macro2::{Ident, Punct, Spacing, Span, TokenStream, TokenTree};
 use quote::{format_ident, ToTokens, TokenStreamExt};
+use sha2::{Digest, Sha256};
 use syn::{
     braced, bracketed,
-    parse::Parse,
+    parse::{Parse, Parser},
     parse_macro_input, parse_quote,
     punctuated::Punctuated,
     visit_mut::{visit_item_mut, VisitMut},
@@ -46,7 +73,8 @@ use tracing_subscriber::EnvFilter;
 
 mod vendor;
 use vendor::wasmtime_component_macro::bindgen::{
-    expand as expand_wasmtime_component, Config as WitBindgenConfig,
+    expand as expand_wasmtime_component, expand_source as expand_wasmtime_component_source,
+    generate as generate_wasmtime_component, Config as WitBindgenConfig,
 };
 use wit_parser::{Handle, Result_, Stream, Tuple, TypeDefKind};
 
@@ -62,18 +90,26 @@ type WitPackageName = String;
 /// '.' delimited module path to an existing WIT interface (ex. 'wasmcloud.keyvalue.key_value')
 type WitInterfacePath = String;
 type WitFunctionName = String;
+type WitInterfaceName = String;
 type FullModulePath = String;
 type WasmcloudContract = String;
 type LatticeExposedInterface = (WitNamespaceName, WitPackageName, WitFunctionName);
+/// Fully qualified WIT function (ex. 'wasi:keyvalue/eventual.get')
+type QualifiedWitFunction = (
+    WitNamespaceName,
+    WitPackageName,
+    WitInterfaceName,
+    WitFunctionName,
+);
 
 type StructName = String;
-type StructLookup = HashMap<StructName, (Punctuated<PathSegment, Token![::]>, ItemStruct)>;
+type StructLookup = BTreeMap<StructName, (Punctuated<PathSegment, Token![::]>, ItemStruct)>;
 
 type EnumName = String;
-type EnumLookup = HashMap<EnumName, (Punctuated<PathSegment, Token![::]>, ItemEnum)>;
+type EnumLookup = BTreeMap<EnumName, (Punctuated<PathSegment, Token![::]>, ItemEnum)>;
 
 type TypeName = String;
-type TypeLookup = HashMap<TypeName, (Punctuated<PathSegment, Token![::]>, ItemType)>;
+type TypeLookup = BTreeMap<TypeName, (Punctuated<PathSegment, Token![::]>, ItemType)>;
 
 type FunctionTokenStream = TokenStream;
 type StructTokenStream = TokenStream;
@@ -84,8 +120,13 @@ struct ProviderBindgenConfig {
     pub(crate) impl_struct: ImplStructName,
 
     /// The wasmCloud contract that the provider fulfills
+    ///
+    /// Used for all interfaces that are not mapped to a contract in `contract_interfaces`
     pub(crate) contract: WasmcloudContract,
 
+    /// Additional wasmCloud contracts that the provider fulfills, with the interfaces that belong to each
+    pub(crate) contract_interfaces: Vec<(WasmcloudContract, Vec<LatticeExposedInterface>)>,
+
     /// WIT namespace of the provider WIT
     pub(crate) wit_ns: Option<WitNamespaceName>,
 
@@ -99,6 +140,10 @@ struct ProviderBindgenConfig {
     /// Interfaces that must explicitly not be exposed onto the lattice
     pub(crate) exposed_interface_deny_list: Vec<LatticeExposedInterface>,
 
+    /// Exported interfaces that actors may also call on the provider, for which lattice receivers
+    /// are generated in addition to the `InvocationHandler` methods used to call actors
+    pub(crate) export_interface_receivers: Vec<LatticeExposedInterface>,
+
     /// wit-bindgen configuration that is passed straight through (uses vendored wit-bindgen)
     ///
     /// During an actual parse run, configuration to pass through to wit bindgen *must* be provided
@@ -116,26 +161,700 @@ struct ProviderBindgenConfig {
 
     /// Whether to replace WIT-ified maps (`list<tuple<T, T>>`) with a Map type (`std::collections::HashMap`)
     pub(crate) replace_witified_maps: bool,
+
+    /// Whether the provider is stateless, in which case `impl_struct` is generated as a zero-sized
+    /// struct, and methods of generated traits are associated functions that do not take `&self`
+    pub(crate) stateless: bool,
+
+    /// Whether to generate a `main` function that starts the provider, constructed via `Default`
+    pub(crate) generate_main: bool,
+
+    /// Whether to handle each dispatched invocation within a span identifying it, which continues
+    /// the trace propagated with the invocation
+    pub(crate) tracing: bool,
+
+    /// Whether to generate mock implementations of the traits of imported interfaces for tests
+    pub(crate) generate_mocks: bool,
+
+    /// Whether to log the steps of expansion at debug level to stderr
+    pub(crate) verbose: bool,
+
+    /// Visibility of generated invocation structs and their members (ex. `pub(crate)`).
+    ///
+    /// If not set, invocation structs of imported interfaces are private, and argument structs of
+    /// exported interfaces (used with the `InvocationHandler`) are public with private members
+    pub(crate) invocation_struct_visibility: Option<syn::Visibility>,
+
+    /// Derives added to generated invocation structs, in addition to `Debug`, `Serialize` and `Deserialize`
+    pub(crate) invocation_struct_derives: Vec<syn::Path>,
+
+    /// Derives added to all generated structs and enums (including those generated for WIT types),
+    /// in addition to `Debug`, `Serialize` and `Deserialize`
+    pub(crate) struct_derives: Vec<syn::Path>,
+
+    /// Path to the module containing bindings generated by `wasmtime::component::bindgen!` for
+    /// the same world (ex. `crate::bindings`), which `From` conversions are generated for
+    pub(crate) component_types: Option<syn::Path>,
+
+    /// Module all generated items are placed in (and re-exported from), so that multiple
+    /// invocations of the macro can be made in the same crate
+    pub(crate) prefix_module: Option<syn::Ident>,
+
+    /// Codec used to serialize invocation payloads sent and received across the lattice
+    pub(crate) codec: Codec,
+
+    /// How WIT variants with payloads are tagged when serialized
+    pub(crate) variant_tagging: VariantTagging,
+
+    /// Whether versions of WIT packages are part of generated lattice method names and contract IDs
+    pub(crate) package_versions: PackageVersions,
+
+    /// Whether received invocations are dispatched regardless of whether their lattice method
+    /// includes the version of the WIT package, so actors using either naming keep working
+    pub(crate) package_version_compat: bool,
+
+    /// Overrides of how individual WIT functions are exposed on the lattice, which take precedence
+    /// over the lattice method names and translation strategies used for all other functions
+    pub(crate) interface_overrides: Vec<(QualifiedWitFunction, FunctionOverride)>,
+
+    /// File the generated code is written to for inspection, if [`DUMP_GENERATED_ENV_VAR`] is set
+    pub(crate) dump_generated: Option<PathBuf>,
+
+    /// Spans of configuration options, which compiler errors produced during expansion point at
+    pub(crate) spans: ConfigSpans,
+}
+
+/// Codec used to serialize invocation payloads sent and received across the lattice
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+enum Codec {
+    /// MessagePack, which is understood by all wasmCloud actors and hosts
+    #[default]
+    MsgPack,
+
+    /// JSON, for interoperating with actors that cannot use MessagePack
+    Json,
+
+    /// CBOR
+    Cbor,
+}
+
+impl Codec {
+    /// Path of the provider SDK function serializing payloads with this codec
+    fn serialize_fn(&self) -> TokenStream {
+        match self {
+            Codec::MsgPack => quote::quote!(::wasmcloud_provider_sdk::serialize),
+            Codec::Json => quote::quote!(::wasmcloud_provider_sdk::serialize_json),
+            Codec::Cbor => quote::quote!(::wasmcloud_provider_sdk::serialize_cbor),
+        }
+    }
+
+    /// Path of the provider SDK function deserializing payloads with this codec
+    fn deserialize_fn(&self) -> TokenStream {
+        match self {
+            Codec::MsgPack => quote::quote!(::wasmcloud_provider_sdk::deserialize),
+            Codec::Json => quote::quote!(::wasmcloud_provider_sdk::deserialize_json),
+            Codec::Cbor => quote::quote!(::wasmcloud_provider_sdk::deserialize_cbor),
+        }
+    }
+
+    /// The provider SDK codec of payloads, which the SDK transcodes invocations to
+    fn sdk_codec(&self) -> TokenStream {
+        match self {
+            Codec::MsgPack => quote::quote!(::wasmcloud_provider_sdk::codec::Codec::MsgPack),
+            Codec::Json => quote::quote!(::wasmcloud_provider_sdk::codec::Codec::Json),
+            Codec::Cbor => quote::quote!(::wasmcloud_provider_sdk::codec::Codec::Cbor),
+        }
+    }
+}
+
+impl FromStr for Codec {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "msgpack" => Ok(Self::MsgPack),
+            "json" => Ok(Self::Json),
+            "cbor" => Ok(Self::Cbor),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid codec [{s}], expected one of 'msgpack', 'json' or 'cbor'"),
+            )),
+        }
+    }
+}
+
+impl Parse for Codec {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let key = input.parse::<LitStr>()?;
+        Self::from_str(key.value().as_str())
+            .map_err(|e| syn::Error::new::<std::io::Error>(key.span(), e))
+    }
+}
+
+/// Representation of the cases of WIT variants with payloads in serialized invocation payloads,
+/// which must match the encoding used by the actors the provider communicates with.
+///
+/// WIT enums (variants without any payloads) are always serialized as the name of their case.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+enum VariantTagging {
+    /// The case is the key of a single-entry map holding the payload (ex. `{"timeout": 5}`),
+    /// which is the default representation of `serde`
+    #[default]
+    Externally,
+
+    /// The case is stored in a `tag` field alongside the fields of the payload (ex.
+    /// `{"tag": "timeout", "secs": 5}`). Only payloads that are records are supported
+    Internally,
+
+    /// The case and the payload are stored in separate `tag` and `val` fields
+    /// (ex. `{"tag": "timeout", "val": 5}`)
+    Adjacently,
+}
+
+impl VariantTagging {
+    /// `serde` container attribute that applies this tagging to an enum, if any
+    fn serde_attr(&self) -> Option<syn::Attribute> {
+        match self {
+            VariantTagging::Externally => None,
+            VariantTagging::Internally => Some(parse_quote!(#[serde(tag = "tag")])),
+            VariantTagging::Adjacently => {
+                Some(parse_quote!(#[serde(tag = "tag", content = "val")]))
+            }
+        }
+    }
+}
+
+impl FromStr for VariantTagging {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "externally" => Ok(Self::Externally),
+            "internally" => Ok(Self::Internally),
+            "adjacently" => Ok(Self::Adjacently),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "invalid variant tagging [{s}], expected one of 'externally', 'internally' or 'adjacently'"
+                ),
+            )),
+        }
+    }
+}
+
+impl Parse for VariantTagging {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let key = input.parse::<LitStr>()?;
+        Self::from_str(key.value().as_str())
+            .map_err(|e| syn::Error::new::<std::io::Error>(key.span(), e))
+    }
+}
+
+/// Handling of the versions of WIT packages (ex. `0.2.0-draft` for `wasi:keyvalue@0.2.0-draft`)
+/// in generated lattice method names and contract IDs
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+enum PackageVersions {
+    /// Versions are left out (ex. `Eventual.Get` and `wasi:keyvalue`), which is what actors
+    /// generated before WIT packages were versioned use
+    #[default]
+    Strip,
+
+    /// Versions are appended (ex. `Eventual.Get@0.2.0-draft` and `wasi:keyvalue@0.2.0-draft`)
+    Include,
+}
+
+impl FromStr for PackageVersions {
+    type Err = std::io::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strip" => Ok(Self::Strip),
+            "include" => Ok(Self::Include),
+            _ => Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("invalid package versions [{s}], expected one of 'strip' or 'include'"),
+            )),
+        }
+    }
+}
+
+impl Parse for PackageVersions {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let key = input.parse::<LitStr>()?;
+        Self::from_str(key.value().as_str())
+            .map_err(|e| syn::Error::new::<std::io::Error>(key.span(), e))
+    }
+}
+
+/// Spans of [`ProviderBindgenConfig`] options that failures during expansion can be attributed to
+#[derive(Debug, Default, Clone, Copy)]
+struct ConfigSpans {
+    wit_bindgen_cfg: Option<Span>,
+    import_fn_lattice_translation_strategy: Option<Span>,
+    export_fn_lattice_translation_strategy: Option<Span>,
+    export_interface_receivers: Option<Span>,
+    dump_generated: Option<Span>,
+}
+
+impl ConfigSpans {
+    /// Span for failures caused by the WIT itself
+    fn wit(&self) -> Span {
+        self.wit_bindgen_cfg.unwrap_or_else(Span::call_site)
+    }
+
+    /// Span for failures translating functions of imported interfaces
+    fn import_fn(&self) -> Span {
+        self.import_fn_lattice_translation_strategy
+            .unwrap_or_else(|| self.wit())
+    }
+
+    /// Span for failures translating functions of exported interfaces
+    fn export_fn(&self) -> Span {
+        self.export_fn_lattice_translation_strategy
+            .unwrap_or_else(|| self.wit())
+    }
+}
+
+/// Failures collected during expansion, which are reported together as compiler errors
+#[derive(Default)]
+struct Errors(Option<syn::Error>);
+
+impl Errors {
+    /// Record a failure, pointing at `span`
+    fn push(&mut self, span: Span, message: impl std::fmt::Display) {
+        let err = syn::Error::new(span, message);
+        match &mut self.0 {
+            Some(errors) => errors.combine(err),
+            None => self.0 = Some(err),
+        }
+    }
+
+    /// Fail with all recorded failures, if there are any
+    fn finish(self) -> syn::Result<()> {
+        self.0.map_or(Ok(()), Err)
+    }
+}
+
+impl ProviderBindgenConfig {
+    /// Look up the wasmCloud contract that a WIT interface belongs to, falling back to `contract`
+    /// if it is not mapped to any contract in `contract_interfaces`
+    fn contract_for(&self, wit_ns: &str, wit_pkg: &str, wit_iface: &str) -> &str {
+        // Compare in snake case, since interface names may come from Rust module paths
+        let target = (
+            wit_ns.to_snake_case(),
+            wit_pkg.to_snake_case(),
+            wit_iface.to_snake_case(),
+        );
+        self.contract_interfaces
+            .iter()
+            .find(|(_, ifaces)| {
+                ifaces.iter().any(|(ns, pkg, iface)| {
+                    (
+                        ns.to_snake_case(),
+                        pkg.to_snake_case(),
+                        iface.to_snake_case(),
+                    ) == target
+                })
+            })
+            .map_or(self.contract.as_str(), |(contract, _)| contract.as_str())
+    }
+
+    /// Look up the ID of the wasmCloud contract that a parsed WIT interface belongs to
+    fn contract_for_wit_iface(&self, iface: &wit_parser::Interface) -> String {
+        let contract = match self.wit_iface_names(iface) {
+            Some((ns, pkg, iface)) => self.contract_for(ns, pkg, iface),
+            None => self.contract.as_str(),
+        };
+        self.contract_id(contract, self.wit_iface_version(iface))
+    }
+
+    /// Look up the ID of the wasmCloud contract that the WIT interface at a '.' delimited module
+    /// path (ex. 'wasmcloud.keyvalue.key_value') belongs to
+    fn contract_for_iface_path(&self, path: &str) -> String {
+        let contract = match split_iface_path(path) {
+            Some((ns, pkg, iface)) => self.contract_for(ns, pkg, iface),
+            None => self.contract.as_str(),
+        };
+        self.contract_id(contract, self.iface_path_version(path))
+    }
+
+    /// ID of a contract, with the version of the WIT package it is used for included or stripped
+    /// as configured. Versions that are part of the configured contract are kept when included
+    fn contract_id(&self, contract: &str, version: Option<String>) -> String {
+        match (self.package_versions, version) {
+            (PackageVersions::Include, Some(version)) if !contract.contains('@') => {
+                format!("{contract}@{version}")
+            }
+            (PackageVersions::Include, _) => contract.into(),
+            (PackageVersions::Strip, _) => contract
+                .split_once('@')
+                .map_or(contract, |(contract, _)| contract)
+                .into(),
+        }
+    }
+
+    /// Version of a WIT package, if it is versioned
+    fn package_version(&self, wit_ns: &str, wit_pkg: &str) -> Option<String> {
+        // Compare in snake case, since names may come from Rust module paths
+        let target = (wit_ns.to_snake_case(), wit_pkg.to_snake_case());
+        self.wit_bindgen_cfg
+            .as_ref()?
+            .resolve
+            .packages
+            .iter()
+            .map(|(_, pkg)| &pkg.name)
+            .find(|name| (name.namespace.to_snake_case(), name.name.to_snake_case()) == target)?
+            .version
+            .as_ref()
+            .map(ToString::to_string)
+    }
+
+    /// Version of the WIT package of a parsed WIT interface, if it is versioned
+    fn wit_iface_version(&self, iface: &wit_parser::Interface) -> Option<String> {
+        let (ns, pkg, _) = self.wit_iface_names(iface)?;
+        self.package_version(ns, pkg)
+    }
+
+    /// Version of the WIT package of the WIT interface at a '.' delimited module path
+    /// (ex. 'wasmcloud.keyvalue.key_value'), if it is versioned
+    fn iface_path_version(&self, path: &str) -> Option<String> {
+        let (ns, pkg, _) = split_iface_path(path)?;
+        self.package_version(ns, pkg)
+    }
+
+    /// Lattice method name with the version of the WIT package it belongs to, if configured
+    fn versioned_lattice_method(&self, method: String, version: Option<String>) -> String {
+        match (self.package_versions, version) {
+            (PackageVersions::Include, Some(version)) => format!("{method}@{version}"),
+            _ => method,
+        }
+    }
+
+    /// Pattern matching a lattice method in `dispatch`, which also matches the lattice method with
+    /// (or without) the version of the WIT package if `package_version_compat` is enabled
+    fn lattice_method_pattern(
+        &self,
+        lattice_method: &LitStr,
+        version: Option<&str>,
+    ) -> TokenStream {
+        let Some(version) = version.filter(|_| self.package_version_compat) else {
+            return lattice_method.to_token_stream();
+        };
+        let method = lattice_method.value();
+        let suffix = format!("@{version}");
+        let alternative = match method.strip_suffix(&suffix) {
+            Some(unversioned) => unversioned.to_string(),
+            None => format!("{method}{suffix}"),
+        };
+        let alternative = LitStr::new(&alternative, lattice_method.span());
+        quote::quote!(#lattice_method | #alternative)
+    }
+
+    /// Look up the overrides configured for a WIT function, if any
+    fn function_override(
+        &self,
+        wit_ns: &str,
+        wit_pkg: &str,
+        wit_iface: &str,
+        wit_fn: &str,
+    ) -> Option<&FunctionOverride> {
+        // Compare in snake case, since names may come from Rust module paths and identifiers
+        let target = (
+            wit_ns.to_snake_case(),
+            wit_pkg.to_snake_case(),
+            wit_iface.to_snake_case(),
+            wit_fn.to_snake_case(),
+        );
+        self.interface_overrides
+            .iter()
+            .find(|((ns, pkg, iface, func), _)| {
+                (
+                    ns.to_snake_case(),
+                    pkg.to_snake_case(),
+                    iface.to_snake_case(),
+                    func.to_snake_case(),
+                ) == target
+            })
+            .map(|(_, o)| o)
+    }
+
+    /// Look up the overrides configured for a function of a parsed WIT interface, if any
+    fn function_override_for_wit_iface(
+        &self,
+        iface: &wit_parser::Interface,
+        wit_fn: &str,
+    ) -> Option<&FunctionOverride> {
+        let (ns, pkg, iface) = self.wit_iface_names(iface)?;
+        self.function_override(ns, pkg, iface, wit_fn)
+    }
+
+    /// Look up the overrides configured for a function of the WIT interface at a '.' delimited
+    /// module path (ex. 'wasmcloud.keyvalue.key_value'), if any
+    fn function_override_for_iface_path(
+        &self,
+        path: &str,
+        wit_fn: &str,
+    ) -> Option<&FunctionOverride> {
+        let (ns, pkg, iface) = split_iface_path(path)?;
+        self.function_override(ns, pkg, iface, wit_fn)
+    }
+
+    /// Lattice method that invocations of a function of an exported WIT interface are sent on
+    fn export_lattice_method(&self, iface: &wit_parser::Interface, iface_fn_name: &str) -> String {
+        self.function_override_for_wit_iface(iface, iface_fn_name)
+            .and_then(|o| o.method.clone())
+            .unwrap_or_else(|| {
+                self.versioned_lattice_method(
+                    format!("Message.{}", iface_fn_name.to_upper_camel_case()),
+                    self.wit_iface_version(iface),
+                )
+            })
+    }
+
+    /// Friendly name of the provider, derived from its contract (ex. 'keyvalue-provider' for 'wasmcloud:keyvalue')
+    fn provider_friendly_name(&self) -> String {
+        let contract = self.contract_id(&self.contract, None);
+        let name = contract
+            .rsplit_once(':')
+            .map_or(contract.as_str(), |(_, name)| name);
+        format!("{}-provider", name.to_kebab_case())
+    }
+
+    /// Derives added to generated invocation structs, combining `struct_derives` and
+    /// `invocation_struct_derives` without duplicates
+    fn invocation_derives(&self) -> Vec<&syn::Path> {
+        let mut derives: Vec<&syn::Path> = Vec::new();
+        for derive in self
+            .struct_derives
+            .iter()
+            .chain(&self.invocation_struct_derives)
+        {
+            if !derives.contains(&derive) {
+                derives.push(derive);
+            }
+        }
+        derives
+    }
+
+    /// Receiver parameter of methods of generated traits, which stateless providers do without
+    fn self_param(&self) -> TokenStream {
+        if self.stateless {
+            TokenStream::new()
+        } else {
+            quote::quote!(&self,)
+        }
+    }
+
+    /// Receiver argument passed to methods of generated traits, matching [`Self::self_param`]
+    fn self_arg(&self) -> TokenStream {
+        if self.stateless {
+            TokenStream::new()
+        } else {
+            quote::quote!(self,)
+        }
+    }
+
+    /// Body of the dispatch match arm for a lattice method, which is run within a span
+    /// identifying the invocation if `tracing` is enabled
+    fn dispatch_match_arm_body(
+        &self,
+        contract: &LitStr,
+        wit_iface: &str,
+        lattice_method: &LitStr,
+        body: TokenStream,
+    ) -> TokenStream {
+        if !self.tracing {
+            return body;
+        }
+        quote::quote!(
+            let span = ctx.dispatch_span(#contract, #wit_iface, #lattice_method);
+            ::wasmcloud_provider_sdk::dispatch_instrumented(span, async move { #body }).await
+        )
+    }
+
+    /// Namespace, package and name of a parsed WIT interface, if it is not anonymous
+    fn wit_iface_names<'a>(
+        &'a self,
+        iface: &'a wit_parser::Interface,
+    ) -> Option<(&'a str, &'a str, &'a str)> {
+        let wit_bindgen_cfg = self.wit_bindgen_cfg.as_ref()?;
+        let pkg = &wit_bindgen_cfg.resolve.packages[iface.package?].name;
+        Some((&pkg.namespace, &pkg.name, iface.name.as_deref()?))
+    }
+}
+
+/// Split a '.' delimited module path to a WIT interface (ex. 'wasmcloud.keyvalue.key_value')
+/// into its namespace, package and interface name
+fn split_iface_path(path: &str) -> Option<(&str, &str, &str)> {
+    match path.rsplitn(3, '.').collect::<Vec<&str>>()[..] {
+        [iface, pkg, ns_path] => {
+            let ns = ns_path.rsplit('.').next().unwrap_or(ns_path);
+            Some((ns, pkg, iface))
+        }
+        _ => None,
+    }
+}
+
+/// Name of the constant holding the lattice method of a WIT function
+/// (ex. 'WASI_KEYVALUE_EVENTUAL_GET' for 'wasi:keyvalue/eventual.get')
+fn lattice_method_const_name(wit_fn: &str) -> String {
+    wit_fn.to_shouty_snake_case()
+}
+
+/// Name of a WIT interface as written in WIT (ex. 'wasmcloud:keyvalue/key-value')
+fn wit_iface_label(ns: &str, pkg: &str, iface: &str) -> String {
+    format!(
+        "{}:{}/{}",
+        ns.to_kebab_case(),
+        pkg.to_kebab_case(),
+        iface.to_kebab_case()
+    )
 }
 
 /// Keywords that are used by this macro
 mod keywords {
     syn::custom_keyword!(contract);
+    syn::custom_keyword!(contracts);
     syn::custom_keyword!(wit_namespace);
     syn::custom_keyword!(wit_package);
     syn::custom_keyword!(impl_struct);
+    syn::custom_keyword!(prefix_module);
     syn::custom_keyword!(wit_bindgen_cfg);
     syn::custom_keyword!(import_fn_lattice_translation_strategy);
     syn::custom_keyword!(export_fn_lattice_translation_strategy);
     syn::custom_keyword!(exposed_interface_allow_list);
     syn::custom_keyword!(exposed_interface_deny_list);
+    syn::custom_keyword!(export_interface_receivers);
     syn::custom_keyword!(replace_witified_maps);
+    syn::custom_keyword!(stateless);
+    syn::custom_keyword!(generate_main);
+    syn::custom_keyword!(tracing);
+    syn::custom_keyword!(generate_mocks);
+    syn::custom_keyword!(verbose);
+    syn::custom_keyword!(invocation_struct_visibility);
+    syn::custom_keyword!(invocation_struct_derives);
+    syn::custom_keyword!(struct_derives);
+    syn::custom_keyword!(component_types);
+    syn::custom_keyword!(codec);
+    syn::custom_keyword!(variant_tagging);
+    syn::custom_keyword!(package_versions);
+    syn::custom_keyword!(package_version_compat);
+    syn::custom_keyword!(dump_generated);
+    syn::custom_keyword!(interface_overrides);
+    syn::custom_keyword!(method);
+    syn::custom_keyword!(strategy);
+    syn::custom_keyword!(skip);
 }
 
 /// Wrapper for a list of qualified WIT function names
 #[derive(Debug, Default)]
 struct WitFnList {
     inner: Vec<LatticeExposedInterface>,
+
+    /// Spans of the entries in `inner`, which errors about them point at
+    spans: Vec<Span>,
+}
+
+impl WitFnList {
+    /// Record an error for every entry that does not match any of the interfaces of a world
+    fn validate(
+        &self,
+        option: &str,
+        world_ifaces: &[LatticeExposedInterface],
+        errors: &mut Errors,
+    ) {
+        for (entry, span) in self.inner.iter().zip(&self.spans) {
+            if !world_ifaces
+                .iter()
+                .any(|iface| interface_list_contains(std::slice::from_ref(entry), iface))
+            {
+                let (ns, pkg, iface) = entry;
+                errors.push(
+                    *span,
+                    format!("[{ns}:{pkg}/{iface}] listed in {option} does not match any interface imported or exported by the world"),
+                );
+            }
+        }
+    }
+}
+
+/// Whether an interface is included in a list of '<ns>:<package>/<interface>' combinations, in which
+/// '*' may be used in place of the interface to include all interfaces of a package
+fn interface_list_contains(
+    list: &[LatticeExposedInterface],
+    (ns, pkg, iface): &LatticeExposedInterface,
+) -> bool {
+    // Compare in snake case, since interface names may come from Rust module paths
+    list.iter().any(|(list_ns, list_pkg, list_iface)| {
+        list_ns.to_snake_case() == ns.to_snake_case()
+            && list_pkg.to_snake_case() == pkg.to_snake_case()
+            && (list_iface == "*" || list_iface.to_snake_case() == iface.to_snake_case())
+    })
+}
+
+/// '<ns>:<package>/<interface>' combinations of the interfaces imported or exported by the world
+/// that bindings are generated for
+fn world_interfaces(wit_bindgen_cfg: &WitBindgenConfig) -> Vec<LatticeExposedInterface> {
+    let resolve = &wit_bindgen_cfg.resolve;
+    let world = &resolve.worlds[wit_bindgen_cfg.world];
+    world
+        .imports
+        .values()
+        .chain(world.exports.values())
+        .filter_map(|item| match item {
+            wit_parser::WorldItem::Interface(iface_id) => {
+                let iface = &resolve.interfaces[*iface_id];
+                let pkg = &resolve.packages[iface.package?].name;
+                Some((pkg.namespace.clone(), pkg.name.clone(), iface.name.clone()?))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Documentation of an interface imported or exported by the world that bindings are generated
+/// for, identified by namespace, package and interface name in any case
+fn world_interface_docs<'a>(
+    wit_bindgen_cfg: &'a WitBindgenConfig,
+    (wit_ns, wit_pkg, wit_iface): (&str, &str, &str),
+) -> Option<&'a wit_parser::Docs> {
+    let resolve = &wit_bindgen_cfg.resolve;
+    let world = &resolve.worlds[wit_bindgen_cfg.world];
+    world
+        .imports
+        .values()
+        .chain(world.exports.values())
+        .find_map(|item| match item {
+            wit_parser::WorldItem::Interface(iface_id) => {
+                let iface = &resolve.interfaces[*iface_id];
+                let pkg = &resolve.packages[iface.package?].name;
+                (pkg.namespace.to_snake_case() == wit_ns.to_snake_case()
+                    && pkg.name.to_snake_case() == wit_pkg.to_snake_case()
+                    && iface.name.as_ref()?.to_snake_case() == wit_iface.to_snake_case())
+                .then_some(&iface.docs)
+            }
+            _ => None,
+        })
+}
+
+/// Doc comments among `attrs` of wit-bindgen generated code, which are carried over to the
+/// corresponding generated code
+fn doc_attrs(attrs: &[syn::Attribute]) -> Vec<syn::Attribute> {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .cloned()
+        .collect()
+}
+
+/// Doc comment attributes for documentation parsed from WIT
+fn wit_doc_tokens(docs: &wit_parser::Docs) -> TokenStream {
+    let lines = docs
+        .contents
+        .iter()
+        .flat_map(|contents| contents.lines())
+        .map(|line| format!(" {line}"));
+    quote::quote!(#( #[doc = #lines] )*)
 }
 
 impl From<WitFnList> for Vec<LatticeExposedInterface> {
@@ -147,6 +866,7 @@ impl From<WitFnList> for Vec<LatticeExposedInterface> {
 impl Parse for WitFnList {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut inner = Vec::new();
+        let mut spans = Vec::new();
         let names;
         bracketed!(names in input);
         let fns = Punctuated::<LitStr, Token![,]>::parse_terminated(&names)?;
@@ -159,17 +879,128 @@ impl Parse for WitFnList {
                 (Some(ns), Some((pkg, fn_name))) => {
                     debug!("successfully parsed interface {ns}:{pkg}/{fn_name}");
                     inner.push((ns.into(), pkg.into(), fn_name.into()));
+                    spans.push(name_ident.span());
                 }
                 _ => {
                     return syn::Result::Err(
                         syn::Error::new(
-                            Span::call_site(),
+                            name_ident.span(),
                             format!("allow/deny list entries must be of the form \"<ns>:<package>/<interface>\", failed to process [\"{name}\"]")
                         )
                     );
                 }
             }
         }
+        Ok(Self { inner, spans })
+    }
+}
+
+/// Mapping of wasmCloud contracts to the '<ns>:<package>/<interface>' combinations that belong to them
+#[derive(Debug, Default)]
+struct ContractInterfaceMap {
+    inner: Vec<(WasmcloudContract, Vec<LatticeExposedInterface>)>,
+}
+
+impl Parse for ContractInterfaceMap {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut inner = Vec::new();
+        let entries;
+        braced!(entries in input);
+        while !entries.is_empty() {
+            let contract = entries.parse::<LitStr>()?.value();
+            entries.parse::<Token![:]>()?;
+            let ifaces = entries.parse::<WitFnList>()?;
+            debug!("successfully parsed interfaces for contract {contract}");
+            inner.push((contract, ifaces.into()));
+            if entries.is_empty() {
+                break;
+            }
+            entries.parse::<Token![,]>()?;
+        }
+        Ok(Self { inner })
+    }
+}
+
+/// Overrides of how a single WIT function is exposed on the lattice
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct FunctionOverride {
+    /// Lattice method name to use instead of the generated one (ex. `"Eventual.Fetch"`)
+    method: Option<String>,
+
+    /// Translation strategy to use instead of the one configured for all imported or exported functions
+    strategy: Option<WitFunctionLatticeTranslationStrategy>,
+
+    /// Whether the function should not be exposed on the lattice at all
+    skip: bool,
+}
+
+impl Parse for FunctionOverride {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut fn_override = Self::default();
+        let fields;
+        braced!(fields in input);
+        while !fields.is_empty() {
+            let l = fields.lookahead1();
+            if l.peek(keywords::method) {
+                fields.parse::<keywords::method>()?;
+                fields.parse::<Token![:]>()?;
+                fn_override.method = Some(fields.parse::<LitStr>()?.value());
+            } else if l.peek(keywords::strategy) {
+                fields.parse::<keywords::strategy>()?;
+                fields.parse::<Token![:]>()?;
+                fn_override.strategy = Some(fields.parse()?);
+            } else if l.peek(keywords::skip) {
+                fields.parse::<keywords::skip>()?;
+                fields.parse::<Token![:]>()?;
+                fn_override.skip = fields.parse::<syn::LitBool>()?.value();
+            } else {
+                return Err(l.error());
+            }
+            if fields.is_empty() {
+                break;
+            }
+            fields.parse::<Token![,]>()?;
+        }
+        Ok(fn_override)
+    }
+}
+
+/// Mapping of '<ns>:<package>/<interface>.<function>' combinations to the overrides of how they
+/// are exposed on the lattice
+#[derive(Debug, Default)]
+struct InterfaceOverrideMap {
+    inner: Vec<(QualifiedWitFunction, FunctionOverride)>,
+}
+
+impl Parse for InterfaceOverrideMap {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut inner = Vec::new();
+        let entries;
+        braced!(entries in input);
+        while !entries.is_empty() {
+            let key = entries.parse::<LitStr>()?;
+            let name = key.value();
+            let (ns, rest) = name.split_once(':').unzip();
+            let (pkg, rest) = rest.and_then(|rest| rest.split_once('/')).unzip();
+            let (iface, func) = rest.and_then(|rest| rest.rsplit_once('.')).unzip();
+            let (Some(ns), Some(pkg), Some(iface), Some(func)) = (ns, pkg, iface, func) else {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("interface override entries must be of the form \"<ns>:<package>/<interface>.<function>\", failed to process [\"{name}\"]"),
+                ));
+            };
+            entries.parse::<Token![:]>()?;
+            let fn_override = entries.parse::<FunctionOverride>()?;
+            debug!("successfully parsed override for function {ns}:{pkg}/{iface}.{func}");
+            inner.push((
+                (ns.into(), pkg.into(), iface.into(), func.into()),
+                fn_override,
+            ));
+            if entries.is_empty() {
+                break;
+            }
+            entries.parse::<Token![,]>()?;
+        }
         Ok(Self { inner })
     }
 }
@@ -180,9 +1011,16 @@ enum ProviderBindgenConfigOption {
     /// Wasmcloud contract that should be the generated provider
     Contract(syn::LitStr),
 
+    /// Wasmcloud contracts that the generated provider fulfills in addition to (or instead of) `contract`,
+    /// mapped to the '<namespace>:<package>/<interface>' combinations that belong to them
+    Contracts(ContractInterfaceMap),
+
     /// Struct that will implement the WIT world
     ImplStruct(syn::Ident),
 
+    /// Module to place all generated items in
+    PrefixModule(syn::Ident),
+
     /// WIT namespace name
     WitNamespace(syn::LitStr),
 
@@ -190,7 +1028,7 @@ enum ProviderBindgenConfigOption {
     WitPackage(syn::LitStr),
 
     /// Wit Bindgen configuration (mostly passed on directly to vendored bindgen)
-    WitBindgenCfg(WitBindgenConfig),
+    WitBindgenCfg(WitBindgenConfig, Span),
 
     /// '<namespace>:<package>/<interface>' combinations that are allowed to be exposed over the lattice
     ///
@@ -198,6 +1036,9 @@ enum ProviderBindgenConfigOption {
     /// If one or more interfaces are specified, then only those interfaces will be exposed over the lattice.
     ///
     /// If combined with the deny list, this listing will be used first (creating the list of allowed fns).
+    ///
+    /// '*' may be used in place of the interface to match all interfaces of a package (ex. 'wasi:keyvalue/*'),
+    /// and every entry must match an interface of the world.
     ExposedFnAllowList(WitFnList),
 
     /// '<namespace>:<package>/<interface>' combinations that are explicitly disallowed from being exposed over the lattice.
@@ -205,23 +1046,79 @@ enum ProviderBindgenConfigOption {
     /// If combined with the allow list, this listing will be used last (filtering the list of allowed fns).
     ExposedFnDenyList(WitFnList),
 
+    /// '<namespace>:<package>/<interface>' combinations of exported interfaces that actors may also call on the provider.
+    ///
+    /// Calls to these interfaces are received using the same calling convention (lattice method names and
+    /// argument bundling) that the `InvocationHandler` uses to call actors.
+    ExportInterfaceReceivers(WitFnList, Span),
+
     /// Strategy (e.x. first argument, bundle arguments into struct) to use
     /// when serializing imported WIT interfaces to be sent across the lattice
-    ImportFnLatticeTranslationStrategy(WitFunctionLatticeTranslationStrategy),
+    ImportFnLatticeTranslationStrategy(WitFunctionLatticeTranslationStrategy, Span),
 
     /// Strategy (e.x. first argument, bundle arguments into struct) to use
     /// when serializing exported WIT interfaces to be sent across the lattice
-    ExportFnLatticeTranslationStrategy(WitFunctionLatticeTranslationStrategy),
+    ExportFnLatticeTranslationStrategy(WitFunctionLatticeTranslationStrategy, Span),
 
     /// Strategy (e.x. first argument, bundle arguments into struct) to use
     /// when serializing exported WIT interfaces to be sent across the lattice
     ReplaceWitifiedMaps(syn::LitBool),
+
+    /// Whether to generate `impl_struct` and trait methods without `&self` for providers without state
+    Stateless(syn::LitBool),
+
+    /// Whether to generate a `main` function that starts the provider
+    GenerateMain(syn::LitBool),
+
+    /// Whether to instrument dispatched invocations with tracing spans
+    Tracing(syn::LitBool),
+
+    /// Whether to generate mock implementations of the traits of imported interfaces
+    GenerateMocks(syn::LitBool),
+
+    /// Whether to log the steps of expansion
+    Verbose(syn::LitBool),
+
+    /// Visibility of generated invocation structs and their members (ex. `"pub"`, `"pub(crate)"`)
+    InvocationStructVisibility(syn::Visibility),
+
+    /// Additional derives for generated invocation structs (ex. `[Clone, PartialEq]`)
+    InvocationStructDerives(Vec<syn::Path>),
+
+    /// Additional derives for all generated structs and enums (ex. `[Clone, PartialEq]`)
+    StructDerives(Vec<syn::Path>),
+
+    /// Module containing wasmtime component bindings of the same world (ex. `"crate::bindings"`)
+    ComponentTypes(syn::Path),
+
+    /// Codec used to serialize invocation payloads (ex. `"msgpack"`, `"json"`, `"cbor"`)
+    Codec(Codec),
+
+    /// Tagging of serialized WIT variants with payloads (ex. `"externally"`, `"internally"`, `"adjacently"`)
+    VariantTagging(VariantTagging),
+
+    /// Handling of WIT package versions in lattice method names and contract IDs (ex. `"strip"`, `"include"`)
+    PackageVersions(PackageVersions),
+
+    /// Whether to dispatch lattice methods both with and without WIT package versions
+    PackageVersionCompat(syn::LitBool),
+
+    /// File to write the generated code to for inspection (ex. `"target/provider-bindgen.rs"`)
+    DumpGenerated(LitStr, Span),
+
+    /// Overrides of how individual WIT functions are exposed on the lattice
+    /// (ex. `{ "wasi:keyvalue/eventual.get": { strategy: "first-argument" } }`)
+    InterfaceOverrides(InterfaceOverrideMap),
 }
 
 impl Parse for ProviderBindgenConfigOption {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let l = input.lookahead1();
-        if l.peek(keywords::contract) {
+        if l.peek(keywords::contracts) {
+            input.parse::<keywords::contracts>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::Contracts(input.parse()?))
+        } else if l.peek(keywords::contract) {
             input.parse::<keywords::contract>()?;
             input.parse::<Token![:]>()?;
             Ok(ProviderBindgenConfigOption::Contract(input.parse()?))
@@ -229,6 +1126,10 @@ impl Parse for ProviderBindgenConfigOption {
             input.parse::<keywords::impl_struct>()?;
             input.parse::<Token![:]>()?;
             Ok(ProviderBindgenConfigOption::ImplStruct(input.parse()?))
+        } else if l.peek(keywords::prefix_module) {
+            input.parse::<keywords::prefix_module>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::PrefixModule(input.parse()?))
         } else if l.peek(keywords::exposed_interface_allow_list) {
             input.parse::<keywords::exposed_interface_allow_list>()?;
             input.parse::<Token![:]>()?;
@@ -241,10 +1142,20 @@ impl Parse for ProviderBindgenConfigOption {
             Ok(ProviderBindgenConfigOption::ExposedFnDenyList(
                 input.parse()?,
             ))
+        } else if l.peek(keywords::export_interface_receivers) {
+            let kw = input.parse::<keywords::export_interface_receivers>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::ExportInterfaceReceivers(
+                input.parse()?,
+                kw.span,
+            ))
         } else if l.peek(keywords::wit_bindgen_cfg) {
-            input.parse::<keywords::wit_bindgen_cfg>()?;
+            let kw = input.parse::<keywords::wit_bindgen_cfg>()?;
             input.parse::<Token![:]>()?;
-            Ok(ProviderBindgenConfigOption::WitBindgenCfg(input.parse()?))
+            Ok(ProviderBindgenConfigOption::WitBindgenCfg(
+                input.parse()?,
+                kw.span,
+            ))
         } else if l.peek(keywords::wit_namespace) {
             input.parse::<keywords::wit_namespace>()?;
             input.parse::<Token![:]>()?;
@@ -254,23 +1165,116 @@ impl Parse for ProviderBindgenConfigOption {
             input.parse::<Token![:]>()?;
             Ok(ProviderBindgenConfigOption::WitPackage(input.parse()?))
         } else if l.peek(keywords::import_fn_lattice_translation_strategy) {
-            input.parse::<keywords::import_fn_lattice_translation_strategy>()?;
+            let kw = input.parse::<keywords::import_fn_lattice_translation_strategy>()?;
             input.parse::<Token![:]>()?;
-            Ok(ProviderBindgenConfigOption::ImportFnLatticeTranslationStrategy(input.parse()?))
+            Ok(
+                ProviderBindgenConfigOption::ImportFnLatticeTranslationStrategy(
+                    input.parse()?,
+                    kw.span,
+                ),
+            )
         } else if l.peek(keywords::export_fn_lattice_translation_strategy) {
-            input.parse::<keywords::import_fn_lattice_translation_strategy>()?;
+            let kw = input.parse::<keywords::export_fn_lattice_translation_strategy>()?;
             input.parse::<Token![:]>()?;
-            Ok(ProviderBindgenConfigOption::ExportFnLatticeTranslationStrategy(input.parse()?))
+            Ok(
+                ProviderBindgenConfigOption::ExportFnLatticeTranslationStrategy(
+                    input.parse()?,
+                    kw.span,
+                ),
+            )
         } else if l.peek(keywords::replace_witified_maps) {
             input.parse::<keywords::replace_witified_maps>()?;
             input.parse::<Token![:]>()?;
             Ok(ProviderBindgenConfigOption::ReplaceWitifiedMaps(
                 input.parse()?,
             ))
+        } else if l.peek(keywords::stateless) {
+            input.parse::<keywords::stateless>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::Stateless(input.parse()?))
+        } else if l.peek(keywords::generate_main) {
+            input.parse::<keywords::generate_main>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::GenerateMain(input.parse()?))
+        } else if l.peek(keywords::tracing) {
+            input.parse::<keywords::tracing>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::Tracing(input.parse()?))
+        } else if l.peek(keywords::generate_mocks) {
+            input.parse::<keywords::generate_mocks>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::GenerateMocks(input.parse()?))
+        } else if l.peek(keywords::verbose) {
+            input.parse::<keywords::verbose>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::Verbose(input.parse()?))
+        } else if l.peek(keywords::invocation_struct_visibility) {
+            input.parse::<keywords::invocation_struct_visibility>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::InvocationStructVisibility(
+                input.parse::<LitStr>()?.parse()?,
+            ))
+        } else if l.peek(keywords::invocation_struct_derives) {
+            input.parse::<keywords::invocation_struct_derives>()?;
+            input.parse::<Token![:]>()?;
+            let derives;
+            bracketed!(derives in input);
+            Ok(ProviderBindgenConfigOption::InvocationStructDerives(
+                Punctuated::<syn::Path, Token![,]>::parse_terminated(&derives)?
+                    .into_iter()
+                    .collect(),
+            ))
+        } else if l.peek(keywords::struct_derives) {
+            input.parse::<keywords::struct_derives>()?;
+            input.parse::<Token![:]>()?;
+            let derives;
+            bracketed!(derives in input);
+            Ok(ProviderBindgenConfigOption::StructDerives(
+                Punctuated::<syn::Path, Token![,]>::parse_terminated(&derives)?
+                    .into_iter()
+                    .collect(),
+            ))
+        } else if l.peek(keywords::component_types) {
+            input.parse::<keywords::component_types>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::ComponentTypes(
+                input.parse::<LitStr>()?.parse()?,
+            ))
+        } else if l.peek(keywords::codec) {
+            input.parse::<keywords::codec>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::Codec(input.parse()?))
+        } else if l.peek(keywords::variant_tagging) {
+            input.parse::<keywords::variant_tagging>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::VariantTagging(input.parse()?))
+        } else if l.peek(keywords::package_versions) {
+            input.parse::<keywords::package_versions>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::PackageVersions(input.parse()?))
+        } else if l.peek(keywords::package_version_compat) {
+            input.parse::<keywords::package_version_compat>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::PackageVersionCompat(
+                input.parse()?,
+            ))
+        } else if l.peek(keywords::dump_generated) {
+            let kw = input.parse::<keywords::dump_generated>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::DumpGenerated(
+                input.parse()?,
+                kw.span,
+            ))
+        } else if l.peek(keywords::interface_overrides) {
+            input.parse::<keywords::interface_overrides>()?;
+            input.parse::<Token![:]>()?;
+            Ok(ProviderBindgenConfigOption::InterfaceOverrides(
+                input.parse()?,
+            ))
         } else {
             Err(syn::Error::new(
                 Span::call_site(),
-                "unrecognized keyword provided to wamscloud_provider_wit_bindgen",
+                "unrecognized keyword provided to wasmcloud_provider_wit_bindgen",
             ))
         }
     }
@@ -348,19 +1352,28 @@ impl WitFunctionLatticeTranslationStrategy {
         struct_lookup: &StructLookup,
         type_lookup: &TypeLookup,
     ) -> anyhow::Result<(WitInterfacePath, LatticeMethod)> {
+        let method_override = bindgen_cfg
+            .function_override_for_iface_path(&wit_iface_path, &trait_method.sig.ident.to_string())
+            .and_then(|o| o.method.clone());
         let lattice_method_name = LitStr::new(
-            format!(
-                "{}.{}",
-                wit_iface_path
-                    .split('.')
-                    .last()
-                    .map(ToUpperCamelCase::to_upper_camel_case)
-                    .with_context(|| format!(
-                        "failed to retrieve WIT iface name from path [{}]",
+            match method_override {
+                Some(method) => method,
+                None => bindgen_cfg.versioned_lattice_method(
+                    format!(
+                        "{}.{}",
                         wit_iface_path
-                    ))?,
-                trait_method.sig.ident.to_string().to_upper_camel_case()
-            )
+                            .split('.')
+                            .last()
+                            .map(ToUpperCamelCase::to_upper_camel_case)
+                            .with_context(|| format!(
+                                "failed to retrieve WIT iface name from path [{}]",
+                                wit_iface_path
+                            ))?,
+                        trait_method.sig.ident.to_string().to_upper_camel_case()
+                    ),
+                    bindgen_cfg.iface_path_version(&wit_iface_path),
+                ),
+            }
             .as_ref(),
             trait_method.sig.ident.span(),
         );
@@ -432,6 +1445,7 @@ impl WitFunctionLatticeTranslationStrategy {
                     struct_members: None,
                     invocation_arg_names: Vec::new(),
                     invocation_return: trait_method.sig.output.clone(),
+                    docs: doc_attrs(&trait_method.attrs),
                 },
             ));
         }
@@ -455,6 +1469,7 @@ impl WitFunctionLatticeTranslationStrategy {
                 struct_members: None,
                 invocation_arg_names: vec![arg_name],
                 invocation_return: trait_method.sig.output.clone(),
+                docs: doc_attrs(&trait_method.attrs),
             },
         ))
     }
@@ -495,6 +1510,11 @@ impl WitFunctionLatticeTranslationStrategy {
                         tokens.append_all([&TokenTree::Punct(Punct::new(',', proc_macro2::Spacing::Alone))]);
                     }
 
+                    // Prefix the member with the configured visibility, if any
+                    if let Some(vis) = &bindgen_cfg.invocation_struct_visibility {
+                        tokens.append_all(vis.to_token_stream());
+                    }
+
                     // Match on a single input argument in the function signature,
                     // converting known types to ones that can be used as invocation struct members.
                     match &arg
@@ -667,6 +1687,7 @@ impl WitFunctionLatticeTranslationStrategy {
                 func_name: trait_method.sig.ident.clone(),
                 invocation_arg_names,
                 invocation_return: trait_method.sig.output.clone(),
+                docs: doc_attrs(&trait_method.attrs),
             },
         ))
     }
@@ -684,20 +1705,28 @@ impl WitFunctionLatticeTranslationStrategy {
                 match &iface_fn.params.as_slice() {
                     // Handle the no-parameter case
                     [] => {
+                        let fn_name =
+                            Ident::new(iface_fn_name.to_snake_case().as_str(), Span::call_site());
                         let lattice_method = LitStr::new(
-                            format!("Message.{}", iface_fn_name.to_upper_camel_case()).as_str(),
+                            cfg.export_lattice_method(iface, iface_fn_name).as_str(),
                             Span::call_site(),
                         );
-                        let contract_ident = LitStr::new(&cfg.contract, Span::call_site());
+                        let contract_ident =
+                            LitStr::new(&cfg.contract_for_wit_iface(iface), Span::call_site());
+                        let serialize = cfg.codec.serialize_fn();
+                        let deserialize = cfg.codec.deserialize_fn();
+                        let sdk_codec = cfg.codec.sdk_codec();
 
                         let func_ts = quote::quote!(
-                            async fn #iface_fn_name(
+                            async fn #fn_name(
                                 &self,
                             ) -> ::wasmcloud_provider_sdk::error::ProviderInvocationResult<()> {
                                 let connection = ::wasmcloud_provider_sdk::provider_main::get_connection();
-                                let client = connection.get_rpc_client();
+                                let client = connection
+                                    .get_lattice_rpc_client(self.lattice.as_deref())
+                                    .with_codec(#sdk_codec);
                                 let response = client
-                                    .send(
+                                    .send_with_retries(
                                         ::wasmcloud_provider_sdk::core::WasmCloudEntity {
                                             public_key: self.ld.provider_id.clone(),
                                             link_name: self.ld.link_name.clone(),
@@ -708,14 +1737,16 @@ impl WitFunctionLatticeTranslationStrategy {
                                             ..Default::default()
                                         },
                                         #lattice_method,
-                                        ::wasmcloud_provider_sdk::serialize(())?
+                                        #serialize(&())?,
+                                        self.timeout,
+                                        self.retries,
                                     )
                                     .await?;
 
                                 if let Some(err) = response.error {
-                                    Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::Provider(err.to_string()))
+                                    Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::from_response(response.error_kind, err))
                                 } else {
-                                    Ok(::wasmcloud_provider_sdk::deserialize(&response.msg)?)
+                                    Ok(#deserialize(&response.msg)?)
                                 }
                             }
                         );
@@ -776,13 +1807,27 @@ impl WitFunctionLatticeTranslationStrategy {
         let rust_type = convert_wit_type(arg_type, cfg)?;
         let fn_name = Ident::new(iface_fn_name.to_snake_case().as_str(), Span::call_site());
         let lattice_method = LitStr::new(
-            format!("Message.{}", iface_fn_name.to_upper_camel_case()).as_str(),
+            cfg.export_lattice_method(iface, iface_fn_name).as_str(),
             Span::call_site(),
         );
 
-        let arg_name_ident = Ident::new(arg_name, Span::call_site());
+        let arg_name_ident = Ident::new(&arg_name.to_snake_case(), Span::call_site());
 
-        let contract_ident = LitStr::new(&cfg.contract, Span::call_site());
+        let contract_ident = LitStr::new(&cfg.contract_for_wit_iface(iface), Span::call_site());
+        let serialize = cfg.codec.serialize_fn();
+        let deserialize = cfg.codec.deserialize_fn();
+        let sdk_codec = cfg.codec.sdk_codec();
+
+        // Optional arguments that are `None` are sent as empty payloads
+        let payload = if is_option_type(&rust_type) {
+            quote::quote!(if #arg_name_ident.is_some() {
+                #serialize(&#arg_name_ident)?
+            } else {
+                Vec::new()
+            })
+        } else {
+            quote::quote!(#serialize(&#arg_name_ident)?)
+        };
 
         // Convert the WIT result type into a Rust type
         let result_rust_type = results.to_rust_type(cfg).with_context(|| {
@@ -799,9 +1844,11 @@ impl WitFunctionLatticeTranslationStrategy {
                 #arg_name_ident: #rust_type
             ) -> Result<#result_rust_type, ::wasmcloud_provider_sdk::error::ProviderInvocationError> {
                 let connection = ::wasmcloud_provider_sdk::provider_main::get_connection();
-                let client = connection.get_rpc_client();
+                let client = connection
+                    .get_lattice_rpc_client(self.lattice.as_deref())
+                    .with_codec(#sdk_codec);
                 let response = client
-                    .send(
+                    .send_with_retries(
                         ::wasmcloud_provider_sdk::core::WasmCloudEntity {
                             public_key: self.ld.provider_id.clone(),
                             link_name: self.ld.link_name.clone(),
@@ -812,14 +1859,16 @@ impl WitFunctionLatticeTranslationStrategy {
                             ..Default::default()
                         },
                         #lattice_method,
-                        ::wasmcloud_provider_sdk::serialize(&#arg_name_ident)?
+                        #payload,
+                        self.timeout,
+                        self.retries,
                     )
                     .await?;
 
                 if let Some(err) = response.error {
-                    Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::Provider(err.to_string()))
+                    Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::from_response(response.error_kind, err))
                 } else {
-                    Ok(::wasmcloud_provider_sdk::deserialize(&response.msg)?)
+                    Ok(#deserialize(&response.msg)?)
                 }
             }
         );
@@ -836,21 +1885,25 @@ impl WitFunctionLatticeTranslationStrategy {
     ) -> anyhow::Result<(Vec<StructTokenStream>, Vec<FunctionTokenStream>)> {
         let fn_params = &iface_fn.params;
         let fn_results = &iface_fn.results;
-        let contract_ident = LitStr::new(&cfg.contract, Span::call_site());
+        let contract_ident = LitStr::new(&cfg.contract_for_wit_iface(iface), Span::call_site());
+        let serialize = cfg.codec.serialize_fn();
+        let deserialize = cfg.codec.deserialize_fn();
+        let sdk_codec = cfg.codec.sdk_codec();
         let fn_name = Ident::new(iface_fn_name.to_snake_case().as_str(), Span::call_site());
         let lattice_method = LitStr::new(
-            format!("Message.{}", iface_fn_name.to_upper_camel_case()).as_str(),
+            cfg.export_lattice_method(iface, iface_fn_name).as_str(),
             Span::call_site(),
         );
         // Build the invocation struct that will be used
         let invocation_struct_name = format_ident!("{}Args", iface_fn_name.to_upper_camel_case());
 
         // Build an Args struct for the arguments to this interface function
+        let member_vis = cfg.invocation_struct_visibility.as_ref();
         let mut struct_member_tokens: TokenStream = TokenStream::new();
         for (idx, (name, ty_id)) in fn_params.iter().enumerate() {
             let raw_type = convert_wit_type(ty_id, cfg)?;
-            let name = format_ident!("{}", name);
-            struct_member_tokens.append_all(quote::quote!(#name: #raw_type));
+            let name = format_ident!("{}", name.to_snake_case());
+            struct_member_tokens.append_all(quote::quote!(#member_vis #name: #raw_type));
             if idx != fn_params.len() - 1 {
                 struct_member_tokens.append(TokenTree::Punct(Punct::new(
                     ',',
@@ -862,9 +1915,14 @@ impl WitFunctionLatticeTranslationStrategy {
         // Build a struct that will be used to send args across the lattice
         //
         // This struct will eventually be written out, before the InvocationHandlers
+        let struct_vis = cfg
+            .invocation_struct_visibility
+            .clone()
+            .unwrap_or_else(|| parse_quote!(pub));
+        let extra_derives = cfg.invocation_derives();
         let invocation_struct_tokens = quote::quote!(
-            #[derive(Debug, ::serde::Serialize, ::serde::Deserialize)]
-            pub struct #invocation_struct_name {
+            #[derive(Debug, ::serde::Serialize, ::serde::Deserialize #(, #extra_derives)*)]
+            #struct_vis struct #invocation_struct_name {
                 #struct_member_tokens
             }
         );
@@ -887,9 +1945,11 @@ impl WitFunctionLatticeTranslationStrategy {
             ) -> Result<#result_rust_type, ::wasmcloud_provider_sdk::error::ProviderInvocationError> {
 
                 let connection = ::wasmcloud_provider_sdk::provider_main::get_connection();
-                let client = connection.get_rpc_client();
+                let client = connection
+                    .get_lattice_rpc_client(self.lattice.as_deref())
+                    .with_codec(#sdk_codec);
                 let response = client
-                    .send(
+                    .send_with_retries(
                         ::wasmcloud_provider_sdk::core::WasmCloudEntity {
                             public_key: self.ld.provider_id.clone(),
                             link_name: self.ld.link_name.clone(),
@@ -900,20 +1960,141 @@ impl WitFunctionLatticeTranslationStrategy {
                             ..Default::default()
                         },
                         #lattice_method,
-                        ::wasmcloud_provider_sdk::serialize(&args)?
+                        #serialize(&args)?,
+                        self.timeout,
+                        self.retries,
                     )
                     .await?;
 
                 if let Some(err) = response.error {
-                    Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::Provider(err.to_string()))
+                    Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::from_response(response.error_kind, err))
                 } else {
-                    Ok(::wasmcloud_provider_sdk::deserialize(&response.msg)?)
+                    Ok(#deserialize(&response.msg)?)
                 }
             }
         );
 
         Ok((vec![invocation_struct_tokens], vec![func_tokens]))
     }
+
+    /// Translate an exported WIT function into a method of the receiver trait of its interface,
+    /// and the `MessageDispatch` match arm that calls it when the function is invoked over the lattice
+    ///
+    /// Arguments are expected to arrive the same way they are sent by the `InvocationHandler`
+    /// (see [`Self::translate_export_fn_for_lattice`]), so the generated `*Args` structs are shared
+    /// by both directions.
+    fn translate_export_fn_for_receiver(
+        &self,
+        iface: &wit_parser::Interface,
+        receiver_trait: &Ident,
+        iface_fn_name: &str,
+        iface_fn: &wit_parser::Function,
+        cfg: &ProviderBindgenConfig,
+    ) -> anyhow::Result<(FunctionTokenStream, TokenStream)> {
+        let serialize = cfg.codec.serialize_fn();
+        let deserialize = cfg.codec.deserialize_fn();
+        let fn_name = Ident::new(iface_fn_name.to_snake_case().as_str(), Span::call_site());
+        let lattice_method = LitStr::new(
+            cfg.export_lattice_method(iface, iface_fn_name).as_str(),
+            Span::call_site(),
+        );
+
+        // Convert the WIT result type into a Rust type
+        let result_rust_type = iface_fn.results.to_rust_type(cfg).with_context(|| {
+            format!(
+                "Failed to convert WIT function results (returns) while parsing interface [{}]",
+                iface.name.clone().unwrap_or("<unknown>".into()),
+            )
+        })?;
+
+        // Build the parameters of the receiver method
+        let mut param_names: Vec<Ident> = Vec::new();
+        let mut param_types: Vec<TokenStream> = Vec::new();
+        for (name, ty) in iface_fn.params.iter() {
+            param_names.push(format_ident!("{}", name.to_snake_case()));
+            param_types.push(convert_wit_type(ty, cfg)?);
+        }
+
+        // Determine how the arguments were sent across the lattice, mirroring the sending side
+        let bundled = match (self, iface_fn.params.len()) {
+            (WitFunctionLatticeTranslationStrategy::Auto, 0 | 1)
+            | (WitFunctionLatticeTranslationStrategy::FirstArgument, 1) => false,
+            (WitFunctionLatticeTranslationStrategy::FirstArgument, _) => {
+                bail!("function parameters for interface function {iface_fn_name} have more than one argument")
+            }
+            _ => true,
+        };
+
+        // Build the statement parsing the input from the lattice, and the arguments it provides
+        let (input_parsing_statement, call_args) = match (bundled, &param_names[..]) {
+            (true, _) => {
+                let invocation_struct_name =
+                    format_ident!("{}Args", iface_fn_name.to_upper_camel_case());
+                (
+                    quote::quote!(let input: #invocation_struct_name = #deserialize(&body)?;),
+                    quote::quote!(#( input.#param_names ),*),
+                )
+            }
+            // Optional arguments that are `None` may be sent as empty payloads
+            (false, [name]) if is_option_type(&param_types[0]) => {
+                let ty = &param_types[0];
+                (
+                    quote::quote!(
+                        let #name: #ty = if body.is_empty() {
+                            None
+                        } else {
+                            #deserialize(&body)?
+                        };
+                    ),
+                    quote::quote!(#name),
+                )
+            }
+            (false, [name]) => {
+                let ty = &param_types[0];
+                (
+                    quote::quote!(let #name: #ty = #deserialize(&body)?;),
+                    quote::quote!(#name),
+                )
+            }
+            (false, _) => (TokenStream::new(), TokenStream::new()),
+        };
+
+        let self_param = cfg.self_param();
+        let method_tokens = quote::quote!(
+            async fn #fn_name(
+                #self_param
+                ctx: ::wasmcloud_provider_sdk::Context,
+                #( #param_names: #param_types ),*
+            ) -> ::wasmcloud_provider_sdk::error::ProviderInvocationResult<#result_rust_type>;
+        );
+
+        let self_arg = cfg.self_arg();
+        let contract = LitStr::new(&cfg.contract_for_wit_iface(iface), Span::call_site());
+        let wit_iface = cfg.wit_iface_names(iface).map_or_else(
+            || iface.name.clone().unwrap_or_default(),
+            |(ns, pkg, name)| wit_iface_label(ns, pkg, name),
+        );
+        let match_arm_body = cfg.dispatch_match_arm_body(
+            &contract,
+            &wit_iface,
+            &lattice_method,
+            quote::quote!(
+                #input_parsing_statement
+                let result = <Self as #receiver_trait>::#fn_name(#self_arg ctx, #call_args)
+                    .await?;
+                Ok(#serialize(&result)?)
+            ),
+        );
+        let lattice_method_pattern =
+            cfg.lattice_method_pattern(&lattice_method, cfg.wit_iface_version(iface).as_deref());
+        let match_arm_tokens = quote::quote!(
+            #lattice_method_pattern => {
+                #match_arm_body
+            }
+        );
+
+        Ok((method_tokens, match_arm_tokens))
+    }
 }
 
 impl FromStr for WitFunctionLatticeTranslationStrategy {
@@ -926,7 +2107,7 @@ impl FromStr for WitFunctionLatticeTranslationStrategy {
             "first-argument" => Ok(Self::FirstArgument),
             _ => Err(std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
-                "failed",
+                format!("invalid lattice translation strategy [{s}], expected one of 'auto', 'bundle-arguments' or 'first-argument'"),
             )),
         }
     }
@@ -936,7 +2117,7 @@ impl Parse for WitFunctionLatticeTranslationStrategy {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         let key = input.parse::<LitStr>()?;
         Self::from_str(key.value().as_str())
-            .map_err(|e| syn::Error::new::<std::io::Error>(Span::call_site(), e))
+            .map_err(|e| syn::Error::new::<std::io::Error>(key.span(), e))
     }
 }
 
@@ -959,7 +2140,10 @@ impl Parse for ProviderBindgenConfig {
 
         // Gather members of bindgen
         let mut contract: Option<WasmcloudContract> = None;
+        let mut contract_interfaces: Vec<(WasmcloudContract, Vec<LatticeExposedInterface>)> =
+            Vec::new();
         let mut impl_struct: Option<ImplStructName> = None;
+        let mut prefix_module: Option<syn::Ident> = None;
         let mut wit_ns: Option<WitNamespaceName> = None;
         let mut wit_pkg: Option<WitPackageName> = None;
         let mut wit_bindgen_cfg: Option<WitBindgenConfig> = None;
@@ -971,7 +2155,24 @@ impl Parse for ProviderBindgenConfig {
         > = None;
         let mut exposed_interface_allow_list: Option<WitFnList> = None;
         let mut exposed_interface_deny_list: Option<WitFnList> = None;
+        let mut export_interface_receivers: Option<WitFnList> = None;
         let mut replace_witified_maps: bool = false;
+        let mut stateless: bool = false;
+        let mut generate_main: bool = false;
+        let mut tracing: bool = false;
+        let mut generate_mocks: Option<syn::LitBool> = None;
+        let mut verbose: bool = false;
+        let mut invocation_struct_visibility: Option<syn::Visibility> = None;
+        let mut invocation_struct_derives: Vec<syn::Path> = Vec::new();
+        let mut struct_derives: Vec<syn::Path> = Vec::new();
+        let mut component_types: Option<syn::Path> = None;
+        let mut codec = Codec::default();
+        let mut variant_tagging = VariantTagging::default();
+        let mut package_versions = PackageVersions::default();
+        let mut package_version_compat: bool = false;
+        let mut dump_generated: Option<PathBuf> = None;
+        let mut interface_overrides = Vec::new();
+        let mut spans = ConfigSpans::default();
 
         // For each successfully parsed configuration entry in the map, build the appropriate bindgen option
         for entry in entries.into_pairs() {
@@ -979,6 +2180,9 @@ impl Parse for ProviderBindgenConfig {
                 ProviderBindgenConfigOption::Contract(c) => {
                     contract = Some(c.value());
                 }
+                ProviderBindgenConfigOption::Contracts(map) => {
+                    contract_interfaces = map.inner;
+                }
                 ProviderBindgenConfigOption::WitNamespace(ns) => {
                     wit_ns = Some(ns.value());
                 }
@@ -991,20 +2195,105 @@ impl Parse for ProviderBindgenConfig {
                 ProviderBindgenConfigOption::ExposedFnDenyList(list) => {
                     exposed_interface_deny_list = Some(list)
                 }
+                ProviderBindgenConfigOption::ExportInterfaceReceivers(list, span) => {
+                    export_interface_receivers = Some(list);
+                    spans.export_interface_receivers = Some(span);
+                }
                 ProviderBindgenConfigOption::ImplStruct(s) => impl_struct = Some(s.to_string()),
-                ProviderBindgenConfigOption::WitBindgenCfg(cfg) => {
+                ProviderBindgenConfigOption::PrefixModule(m) => prefix_module = Some(m),
+                ProviderBindgenConfigOption::WitBindgenCfg(cfg, span) => {
                     wit_bindgen_cfg = Some(cfg);
+                    spans.wit_bindgen_cfg = Some(span);
                 }
-                ProviderBindgenConfigOption::ImportFnLatticeTranslationStrategy(strat) => {
+                ProviderBindgenConfigOption::ImportFnLatticeTranslationStrategy(strat, span) => {
                     import_fn_lattice_translation_strategy = Some(strat);
+                    spans.import_fn_lattice_translation_strategy = Some(span);
                 }
-                ProviderBindgenConfigOption::ExportFnLatticeTranslationStrategy(strat) => {
+                ProviderBindgenConfigOption::ExportFnLatticeTranslationStrategy(strat, span) => {
                     export_fn_lattice_translation_strategy = Some(strat);
+                    spans.export_fn_lattice_translation_strategy = Some(span);
                 }
                 ProviderBindgenConfigOption::ReplaceWitifiedMaps(opt) => {
                     replace_witified_maps = opt.value();
                 }
+                ProviderBindgenConfigOption::Stateless(opt) => {
+                    stateless = opt.value();
+                }
+                ProviderBindgenConfigOption::GenerateMain(opt) => {
+                    generate_main = opt.value();
+                }
+                ProviderBindgenConfigOption::Tracing(opt) => {
+                    tracing = opt.value();
+                }
+                ProviderBindgenConfigOption::GenerateMocks(opt) => {
+                    generate_mocks = Some(opt);
+                }
+                ProviderBindgenConfigOption::Verbose(opt) => {
+                    verbose = opt.value();
+                }
+                ProviderBindgenConfigOption::InvocationStructVisibility(vis) => {
+                    invocation_struct_visibility = Some(vis);
+                }
+                ProviderBindgenConfigOption::InvocationStructDerives(derives) => {
+                    invocation_struct_derives = derives;
+                }
+                ProviderBindgenConfigOption::StructDerives(derives) => {
+                    struct_derives = derives;
+                }
+                ProviderBindgenConfigOption::ComponentTypes(path) => {
+                    component_types = Some(path);
+                }
+                ProviderBindgenConfigOption::Codec(c) => {
+                    codec = c;
+                }
+                ProviderBindgenConfigOption::VariantTagging(t) => {
+                    variant_tagging = t;
+                }
+                ProviderBindgenConfigOption::PackageVersions(v) => {
+                    package_versions = v;
+                }
+                ProviderBindgenConfigOption::PackageVersionCompat(opt) => {
+                    package_version_compat = opt.value();
+                }
+                ProviderBindgenConfigOption::DumpGenerated(path, span) => {
+                    dump_generated = Some(PathBuf::from(path.value()));
+                    spans.dump_generated = Some(span);
+                }
+                ProviderBindgenConfigOption::InterfaceOverrides(map) => {
+                    interface_overrides = map.inner;
+                }
+            }
+        }
+
+        // Mocks record calls and respond to them through `&self`, which methods of the traits of
+        // stateless providers do not take
+        if let Some(opt) = generate_mocks
+            .as_ref()
+            .filter(|opt| opt.value() && stateless)
+        {
+            return Err(syn::Error::new(
+                opt.span(),
+                "'generate_mocks' cannot be used with stateless providers",
+            ));
+        }
+
+        // Reject allow/deny list entries that can never match (ex. due to typos), rather than
+        // silently exposing more (or fewer) interfaces than intended
+        if let Some(wit_bindgen_cfg) = &wit_bindgen_cfg {
+            let world_ifaces = world_interfaces(wit_bindgen_cfg);
+            let mut errors = Errors::default();
+            for (option, list) in [
+                (
+                    "exposed_interface_allow_list",
+                    &exposed_interface_allow_list,
+                ),
+                ("exposed_interface_deny_list", &exposed_interface_deny_list),
+            ] {
+                if let Some(list) = list {
+                    list.validate(option, &world_ifaces, &mut errors);
+                }
             }
+            errors.finish()?;
         }
 
         // Build the bindgen configuration from the parsed parts
@@ -1018,19 +2307,24 @@ impl Parse for ProviderBindgenConfig {
                     ),
                 )
             })?,
-            contract: contract.ok_or_else(|| {
-                syn::Error::new(
-                    call_site,
-                    std::io::Error::new(
-                        std::io::ErrorKind::InvalidInput,
-                        "missing/invalid 'contract' bindgen option",
-                    ),
-                )
-            })?,
+            // If no default contract was specified, the first mapped contract is used
+            contract: contract
+                .or_else(|| contract_interfaces.first().map(|(c, _)| c.clone()))
+                .ok_or_else(|| {
+                    syn::Error::new(
+                        call_site,
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "missing/invalid 'contract' bindgen option",
+                        ),
+                    )
+                })?,
+            contract_interfaces,
             wit_ns,
             wit_pkg,
             exposed_interface_allow_list: exposed_interface_allow_list.unwrap_or_default().into(),
             exposed_interface_deny_list: exposed_interface_deny_list.unwrap_or_default().into(),
+            export_interface_receivers: export_interface_receivers.unwrap_or_default().into(),
             wit_bindgen_cfg: Some(wit_bindgen_cfg.ok_or_else(|| {
                 syn::Error::new(
                     call_site,
@@ -1045,99 +2339,298 @@ impl Parse for ProviderBindgenConfig {
             export_fn_lattice_translation_strategy: export_fn_lattice_translation_strategy
                 .unwrap_or_default(),
             replace_witified_maps,
+            stateless,
+            generate_main,
+            tracing,
+            generate_mocks: generate_mocks.is_some_and(|opt| opt.value()),
+            verbose,
+            invocation_struct_visibility,
+            invocation_struct_derives,
+            struct_derives,
+            component_types,
+            prefix_module,
+            codec,
+            variant_tagging,
+            package_versions,
+            package_version_compat,
+            interface_overrides,
+            dump_generated,
+            spans,
         })
     }
 }
 
-/// This macro generates functionality necessary to use a WIT-enabled Rust providers (binaries that are managed by the host)
-#[proc_macro]
-pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+/// This macro generates functionality necessary to use a WIT-enabled Rust providers (binaries that are managed by the host)
+#[proc_macro]
+pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    init_logging(false);
+    let cfg = parse_macro_input!(input as ProviderBindgenConfig);
+    if cfg.verbose {
+        init_logging(true);
+    }
+    expand(&cfg)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Environment variable holding the filter (ex. `debug`) of logs emitted during expansion
+const LOG_ENV_VAR: &str = "WASMCLOUD_BINDGEN_LOG";
+
+/// Log to stderr during expansion if enabled via [`LOG_ENV_VAR`] or, if `verbose`, at debug level
+/// for this crate.
+///
+/// Logging is never enabled otherwise, and a subscriber that is already set (ex. by a previous
+/// expansion within the same compiler process) is left in place
+fn init_logging(verbose: bool) {
+    let filter = match std::env::var(LOG_ENV_VAR) {
+        Ok(filter) => EnvFilter::try_new(&filter).unwrap_or_else(|e| {
+            eprintln!("ignoring invalid {LOG_ENV_VAR} filter [{filter}]: {e}");
+            EnvFilter::new("debug")
+        }),
+        Err(_) if verbose => EnvFilter::new(format!("{}=debug", env!("CARGO_CRATE_NAME"))),
+        Err(_) => return,
+    };
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// Expand the provider code for a parsed configuration
+///
+/// Failures are collected rather than aborting expansion at the first one, and reported together
+/// as compiler errors pointing at the configuration option they can be attributed to
+fn expand(cfg: &ProviderBindgenConfig) -> syn::Result<TokenStream> {
+    let mut errors = Errors::default();
+    let serialize = cfg.codec.serialize_fn();
+    let deserialize = cfg.codec.deserialize_fn();
+
+    // Parse the WIT for files (a second time, in addition to what has been done to generate)
+    // and build up a list of exported iface invocation methods and structs related to them
+    let mut exported_iface_invocation_methods: Vec<TokenStream> = Vec::new();
+    let mut exported_iface_invocation_structs: Vec<TokenStream> = Vec::new();
+
+    // Exported interfaces that actors may also call on the provider get a receiver trait
+    // and dispatch match arms, similar to imported interfaces
+    let mut exported_iface_receiver_traits: Vec<TokenStream> = Vec::new();
+    let mut exported_iface_dispatch_match_arms: Vec<TokenStream> = Vec::new();
+
+    // Constants naming the lattice methods of all functions (ex. `WASI_KEYVALUE_EVENTUAL_GET`),
+    // along with the WIT functions they belong to
+    let mut lattice_method_consts: BTreeMap<String, (String, String)> = BTreeMap::new();
+
+    // Resolve the WIT bindgen configuration, which at this point should definitely be present
+    let wit_bindgen_cfg = cfg.wit_bindgen_cfg.as_ref().ok_or_else(|| {
+        syn::Error::new(
+            cfg.spans.wit(),
+            "configuration to pass to WIT bindgen is missing",
+        )
+    })?;
+
+    // Only the world that bindings are generated for is walked, as is done for its imports
+    let world = &wit_bindgen_cfg.resolve.worlds[wit_bindgen_cfg.world];
+    for (world_key, world_item) in world.exports.iter() {
+        let iface_id = match world_item {
+            wit_parser::WorldItem::Interface(iface_id) => *iface_id,
+            wit_parser::WorldItem::Function(func) => {
+                warn!(
+                    "skipping freestanding exported function [{}], only functions of exported interfaces can be invoked on actors",
+                    func.name
+                );
+                continue;
+            }
+            wit_parser::WorldItem::Type(_) => continue,
+        };
+        let iface = &wit_bindgen_cfg.resolve.interfaces[iface_id];
+
+        // Interfaces exported by name (ex. `export handler: interface { ... }`) are anonymous,
+        // so the name they are exported under is used instead
+        let iface_name = match world_key {
+            wit_parser::WorldKey::Name(name) => Some(name),
+            wit_parser::WorldKey::Interface(_) => iface.name.as_ref(),
+        };
+
+        // If the interface is in a namespace that we know can't be used coming in from the lattice
+        // then we should ignore it and not generate invocation handlers for it
+        let pkg = iface
+            .package
+            .map(|p| &wit_bindgen_cfg.resolve.packages[p].name);
+        if let Some(pkg) = pkg {
+            if pkg.namespace == "wasmcloud" && pkg.name == "bus" {
+                continue;
+            }
+        }
+
+        // Determine whether actors may also call this interface on the provider
+        let receiver_trait = match (pkg, iface_name) {
+            (Some(pkg), Some(name))
+                if cfg.export_interface_receivers.contains(&(
+                    pkg.namespace.clone(),
+                    pkg.name.clone(),
+                    name.clone(),
+                )) =>
+            {
+                Some(format_ident!(
+                    "{}",
+                    format!("{}-{}-{name}", pkg.namespace, pkg.name).to_upper_camel_case()
+                ))
+            }
+            _ => None,
+        };
+        let mut receiver_methods: Vec<TokenStream> = Vec::new();
+
+        for (iface_fn_name, iface_fn) in iface.functions.iter() {
+            // For each function in an exported interface,
+            // we'll need to generate a method on the eventual InvocationHandler
+            // that will be built later.
+            //
+            // We expect functions on exported interface to consist of *one* argument which is
+            // normally a struct (WIT record type) what represents the information to be sent out on the lattice, ex.:
+            //
+            //  ```
+            //  interface handler {
+            //       use types.{some-message}
+            //       handle-message: func(msg: some-message) -> result<_, string>
+            //   }
+            //  ```
+            let fn_override = cfg.function_override_for_wit_iface(iface, iface_fn_name);
+            if fn_override.is_some_and(|o| o.skip) {
+                debug!("skipping exported function [{iface_fn_name}]");
+                continue;
+            }
 
-    let cfg = parse_macro_input!(input as ProviderBindgenConfig);
-    let contract_ident = LitStr::new(&cfg.contract, Span::call_site());
+            let wit_fn = match (pkg, iface_name) {
+                (Some(pkg), Some(name)) => {
+                    format!(
+                        "{}.{iface_fn_name}",
+                        wit_iface_label(&pkg.namespace, &pkg.name, name)
+                    )
+                }
+                (None, Some(name)) => format!("{name}.{iface_fn_name}"),
+                (_, None) => iface_fn_name.clone(),
+            };
+            lattice_method_consts
+                .entry(lattice_method_const_name(&wit_fn))
+                .or_insert_with(|| (cfg.export_lattice_method(iface, iface_fn_name), wit_fn));
+            let strategy = fn_override
+                .and_then(|o| o.strategy.as_ref())
+                .unwrap_or(&cfg.export_fn_lattice_translation_strategy);
+
+            let (invocation_struct_tokens, invocation_method_tokens) = match strategy
+                .translate_export_fn_for_lattice(iface, iface_fn_name, iface_fn, cfg)
+            {
+                Ok(translated) => translated,
+                Err(err) => {
+                    errors.push(
+                        cfg.spans.export_fn(),
+                        format!(
+                            "failed to translate exported function [{}/{iface_fn_name}]: {err:#}",
+                            iface_name.map_or("<unknown>", String::as_str),
+                        ),
+                    );
+                    continue;
+                }
+            };
 
-    // Parse the WIT for files (a second time, in addition to what has been done to generate)
-    // and build up a list of exported iface invocation methods and structs related to them
-    let mut exported_iface_invocation_methods: Vec<TokenStream> = Vec::new();
-    let mut exported_iface_invocation_structs: Vec<TokenStream> = Vec::new();
+            // Augment the list of invocation methods that have to be fulfilled
+            let fn_docs = wit_doc_tokens(&iface_fn.docs);
+            exported_iface_invocation_methods.extend(
+                invocation_method_tokens
+                    .into_iter()
+                    .map(|method| quote::quote!(#fn_docs #method)),
+            );
+            exported_iface_invocation_structs.extend(invocation_struct_tokens.into_iter());
 
-    // Resolve the WIT bindgen configuration, which at this point should definitely be present
-    let wit_bindgen_cfg = cfg
-        .wit_bindgen_cfg
-        .as_ref()
-        .context("configuration to pass to WIT bindgen is missing")
-        .expect("failed to parse WIT bindgen configuration");
-
-    for (_, world) in wit_bindgen_cfg.resolve.worlds.iter() {
-        for (world_item, _) in world.exports.iter() {
-            if let wit_parser::WorldKey::Interface(iface_id) = world_item {
-                let iface = &wit_bindgen_cfg.resolve.interfaces[*iface_id];
-
-                // If the interface is in a namespace that we know can't be used coming in from the lattice
-                // then we should ignore it and not generate invocation handlers for it
-                if let Some(pkg) = iface
-                    .package
-                    .map(|p| &wit_bindgen_cfg.resolve.packages[p].name)
-                {
-                    if pkg.namespace == "wasmcloud" && pkg.name == "bus" {
-                        continue;
+            // Generate the receiving side for interfaces that actors may also call
+            if let Some(receiver_trait) = &receiver_trait {
+                match strategy.translate_export_fn_for_receiver(
+                        iface,
+                        receiver_trait,
+                        iface_fn_name,
+                        iface_fn,
+                        cfg,
+                    ) {
+                    Ok((receiver_method_tokens, match_arm_tokens)) => {
+                        receiver_methods.push(quote::quote!(#fn_docs #receiver_method_tokens));
+                        exported_iface_dispatch_match_arms.push(match_arm_tokens);
                     }
+                    Err(err) => errors.push(
+                        cfg.spans
+                            .export_interface_receivers
+                            .unwrap_or_else(|| cfg.spans.export_fn()),
+                        format!(
+                            "failed to generate receiver for exported function [{}/{iface_fn_name}]: {err:#}",
+                            iface_name.map_or("<unknown>", String::as_str),
+                        ),
+                    ),
                 }
+            }
+        }
 
-                for (iface_fn_name, iface_fn) in iface.functions.iter() {
-                    // For each function in an exported interface,
-                    // we'll need to generate a method on the eventual InvocationHandler
-                    // that will be built later.
-                    //
-                    // We expect functions on exported interface to consist of *one* argument which is
-                    // normally a struct (WIT record type) what represents the information to be sent out on the lattice, ex.:
-                    //
-                    //  ```
-                    //  interface handler {
-                    //       use types.{some-message}
-                    //       handle-message: func(msg: some-message) -> result<_, string>
-                    //   }
-                    //  ```
-                    let (invocation_struct_tokens, invocation_method_tokens) = cfg
-                        .export_fn_lattice_translation_strategy
-                        .translate_export_fn_for_lattice(iface, iface_fn_name, iface_fn, &cfg)
-                        .expect("failed to translate export fn");
+        if let Some(receiver_trait) = receiver_trait {
+            let contract_ident = LitStr::new(&cfg.contract_for_wit_iface(iface), Span::call_site());
+            let iface_docs = wit_doc_tokens(&iface.docs);
+            exported_iface_receiver_traits.push(quote::quote!(
+                #iface_docs
+                #[::async_trait::async_trait]
+                pub trait #receiver_trait {
+                    fn contract_id() -> &'static str {
+                        #contract_ident
+                    }
 
-                    // Augment the list of invocation methods that have to be fulfilled
-                    exported_iface_invocation_methods.extend(invocation_method_tokens.into_iter());
-                    exported_iface_invocation_structs.extend(invocation_struct_tokens.into_iter());
+                    #( #receiver_methods )*
                 }
-            }
+            ));
         }
     }
 
+    if exported_iface_receiver_traits.len() < cfg.export_interface_receivers.len() {
+        warn!("some interfaces listed in export_interface_receivers are not exported by the world");
+    }
+
     // Expand the wasmtime::component macro with the given arguments
-    let bindgen_tokens: TokenStream =
-        expand_wasmtime_component(wit_bindgen_cfg).unwrap_or_else(syn::Error::into_compile_error);
+    let bindgen_tokens: TokenStream = expand_wasmtime_component_cached(wit_bindgen_cfg)?;
 
     // Parse the bindgen-generated tokens into an AST
     // that will be used in the output (combined with other wasmcloud-specific generated code)
-    let mut bindgen_ast: syn::File =
-        syn::parse2(bindgen_tokens).expect("failed to parse wit-bindgen generated code as file");
+    let mut bindgen_ast: syn::File = syn::parse2(bindgen_tokens).map_err(|e| {
+        syn::Error::new(
+            cfg.spans.wit(),
+            format!("failed to parse wit-bindgen generated code as file: {e}"),
+        )
+    })?;
 
     // Visit the code that has been generated, to extract information we'll need to modify it
-    let mut visitor = WitBindgenOutputVisitor::new(&cfg);
+    let mut visitor = WitBindgenOutputVisitor::new(cfg);
     visitor.visit_file_mut(&mut bindgen_ast);
+    for message in visitor.errors.drain(..) {
+        errors.push(cfg.spans.wit(), message);
+    }
 
     // Turn the function calls into object declarations for receiving from lattice
-    let methods_by_iface = build_lattice_methods_by_wit_interface(
+    let methods_by_iface = match build_lattice_methods_by_wit_interface(
         &visitor.serde_extended_structs,
         &visitor.type_lookup,
         &visitor.import_trait_methods,
-        &cfg,
-    )
-    .expect("failed to build lattice methods from WIT interfaces");
+        cfg,
+    ) {
+        Ok(methods_by_iface) => methods_by_iface,
+        Err(err) => {
+            errors.push(cfg.spans.import_fn(), format!("{err:#}"));
+            BTreeMap::new()
+        }
+    };
 
     // Create the implementation struct name as an Ident
     let impl_struct_name = Ident::new_raw(cfg.impl_struct.as_str(), Span::call_site());
+    let sdk_codec = cfg.codec.sdk_codec();
+
+    // Look up the module path of each imported interface by the name of its generated trait
+    let import_iface_paths: BTreeMap<String, &str> = visitor
+        .import_trait_methods
+        .keys()
+        .map(|path| (path.to_upper_camel_case(), path.as_str()))
+        .collect();
 
     // Build a list of match arms for the interfaces
     let mut interface_dispatch_match_arms: Vec<TokenStream> = Vec::new();
@@ -1145,6 +2638,28 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let mut iface_tokens = TokenStream::new();
     for (wit_iface_name, methods) in methods_by_iface.iter() {
         let wit_iface = Ident::new(wit_iface_name, Span::call_site());
+        let iface_path = import_iface_paths.get(wit_iface_name).copied();
+        let contract_ident = LitStr::new(
+            &iface_path.map_or_else(
+                || cfg.contract_id(&cfg.contract, None),
+                |path| cfg.contract_for_iface_path(path),
+            ),
+            Span::call_site(),
+        );
+        let package_version = iface_path.and_then(|path| cfg.iface_path_version(path));
+        let wit_iface_name_label = iface_path.and_then(split_iface_path).map_or_else(
+            || wit_iface_name.clone(),
+            |(ns, pkg, iface)| wit_iface_label(ns, pkg, iface),
+        );
+        for lm in methods.iter() {
+            let wit_fn = format!(
+                "{wit_iface_name_label}.{}",
+                lm.func_name.to_string().to_kebab_case()
+            );
+            lattice_method_consts
+                .entry(lattice_method_const_name(&wit_fn))
+                .or_insert_with(|| (lm.lattice_method_name.value(), wit_fn));
+        }
 
         // Add generated code for new XInvocation structs
 
@@ -1161,11 +2676,13 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         );
 
         // Add generated struct code for the current interface
+        let struct_vis = cfg.invocation_struct_visibility.as_ref();
+        let extra_derives = cfg.invocation_derives();
         iface_tokens.append_all(quote::quote!(
             // START: *Invocation structs & trait for #wit_iface
             #(
-                #[derive(Debug, ::serde::Serialize, ::serde::Deserialize)]
-                struct #struct_type_names {
+                #[derive(Debug, ::serde::Serialize, ::serde::Deserialize #(, #extra_derives)*)]
+                #struct_vis struct #struct_type_names {
                     #struct_members
                 }
             )*
@@ -1212,7 +2729,10 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         quote::quote!(#first: #type_name)
                     },
                     // All other combinations are invalid (ex. forcing first-argument parsing when there are muiltiple args to the fn),
-                    _ => panic!("unexpectedly found more than 1 invocation arg in function [{}] name, wit_function_lattice_translation-strategy should likely not be set to 'first-argument'", lm.func_name),
+                    _ => {
+                        errors.push(cfg.spans.import_fn(), format!("unexpectedly found more than 1 invocation arg in function [{}], import_fn_lattice_translation_strategy should likely not be set to 'first-argument'", lm.func_name));
+                        TokenStream::new()
+                    },
                 }
             })
             .collect::<Vec<TokenStream>>();
@@ -1223,9 +2743,22 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             .map(|lm| lm.invocation_return)
             .collect::<Vec<ReturnType>>();
 
+        // Doc comments of the WIT interface and its functions
+        let iface_docs = iface_path
+            .and_then(split_iface_path)
+            .and_then(|names| world_interface_docs(wit_bindgen_cfg, names))
+            .map(wit_doc_tokens)
+            .unwrap_or_default();
+        let func_docs = methods
+            .iter()
+            .map(|lm| lm.docs.clone())
+            .collect::<Vec<Vec<syn::Attribute>>>();
+
         // Create and append the trait for the iface along with
         // the functions that should be implemented by the provider
+        let self_param = cfg.self_param();
         iface_tokens.append_all(quote::quote!(
+            #iface_docs
             #[::async_trait::async_trait]
             pub trait #wit_iface {
                 fn contract_id() -> &'static str {
@@ -1233,8 +2766,9 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                 }
 
                 #(
+                    #( #func_docs )*
                     async fn #func_names (
-                        &self,
+                        #self_param
                         ctx: ::wasmcloud_provider_sdk::Context,
                         #invocation_args_with_types
                     ) #invocation_returns;
@@ -1258,7 +2792,20 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         //  - a pre-existing type (ex. `String`)
                         //
                         // We can use this to generate lines for
-                        acc.0.push(quote::quote!(let input: #type_name = ::wasmcloud_provider_sdk::deserialize(&body)?;));
+                        acc.0.push(
+                            if lm.struct_members.is_none() && is_option_type(&type_name) {
+                                // Optional arguments that are `None` may be sent as empty payloads
+                                quote::quote!(
+                                    let input: #type_name = if body.is_empty() {
+                                        None
+                                    } else {
+                                        #deserialize(&body)?
+                                    };
+                                )
+                            } else {
+                                quote::quote!(let input: #type_name = #deserialize(&body)?;)
+                            },
+                        );
 
                         let invocation_arg_names = lm.invocation_arg_names;
                         acc.1.push(if invocation_arg_names.len() == 1 {
@@ -1269,14 +2816,20 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                             // If there is more than one arg name, we have a bundle of arguments that was sent over the wire
                             // we must pass the *fields* of that struct in
                             let mut tokens = TokenStream::new();
-                            invocation_arg_names.iter().enumerate().fold(&mut tokens, |ts, (idx, i)| {
-                                // Append input since if we have multiple arguments they'll be coming in as one envelope over the lattice
-                                ts.append_all(quote::quote!(input.#i));
-                                if idx != invocation_arg_names.len() - 1 {
-                                    ts.append(TokenTree::Punct(Punct::new(',', proc_macro2::Spacing::Alone)));
-                                }
-                                ts
-                            });
+                            invocation_arg_names.iter().enumerate().fold(
+                                &mut tokens,
+                                |ts, (idx, i)| {
+                                    // Append input since if we have multiple arguments they'll be coming in as one envelope over the lattice
+                                    ts.append_all(quote::quote!(input.#i));
+                                    if idx != invocation_arg_names.len() - 1 {
+                                        ts.append(TokenTree::Punct(Punct::new(
+                                            ',',
+                                            proc_macro2::Spacing::Alone,
+                                        )));
+                                    }
+                                    ts
+                                },
+                            );
                             quote::quote!(ctx, #tokens)
                         });
                     } else {
@@ -1284,29 +2837,70 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         //
                         // This means that there's no input to be parsed, and only ctx as a post-self argument
                         acc.0.push(TokenStream::new());
-                        acc.1.push(Ident::new("ctx", Span::call_site()).to_token_stream());
+                        acc.1
+                            .push(Ident::new("ctx", Span::call_site()).to_token_stream());
                     }
                     acc
                 });
 
         // After building individual invocation structs and traits for each interface
         // we must build & hold on to the usage of these inside the match for the MessageDispatch trait
-        interface_dispatch_match_arms.push(quote::quote!(
-            #(
-                #lattice_method_names => {
-                    #input_parsing_statements
-                    let result = #wit_iface::#func_names(
-                        self,
-                        #post_self_args
+        let self_arg = cfg.self_arg();
+        let match_arm_bodies = lattice_method_names
+            .iter()
+            .zip(func_names.iter())
+            .zip(input_parsing_statements.iter().zip(post_self_args.iter()))
+            .map(
+                |((lattice_method, func_name), (input_parsing_statement, post_self_args))| {
+                    cfg.dispatch_match_arm_body(
+                        &contract_ident,
+                        &wit_iface_name_label,
+                        lattice_method,
+                        quote::quote!(
+                            #input_parsing_statement
+                            let result = <Self as #wit_iface>::#func_name(
+                                #self_arg
+                                #post_self_args
+                            )
+                                .await?;
+                            Ok(#serialize(&result)?)
+                        ),
                     )
-                        .await
-                        .map_err(|e| {
-                            ::wasmcloud_provider_sdk::error::ProviderInvocationError::Provider(e.to_string())
-                        })?;
-                    Ok(::wasmcloud_provider_sdk::serialize(&result)?)
+                },
+            )
+            .collect::<Vec<TokenStream>>();
+        let lattice_method_patterns = lattice_method_names
+            .iter()
+            .map(|lattice_method| {
+                cfg.lattice_method_pattern(lattice_method, package_version.as_deref())
+            })
+            .collect::<Vec<TokenStream>>();
+        let iface_match_arms = quote::quote!(
+            #(
+                #lattice_method_patterns => {
+                    #match_arm_bodies
                 }
             )*
-        ));
+        );
+
+        // Generate a mock of the trait, which dispatches the same lattice methods
+        if cfg.generate_mocks {
+            match mock_tokens(
+                &wit_iface,
+                methods,
+                &invocation_args_with_types,
+                &iface_match_arms,
+                cfg.codec,
+            ) {
+                Ok(tokens) => iface_tokens.append_all(tokens),
+                Err(err) => errors.push(
+                    cfg.spans.import_fn(),
+                    format!("failed to generate mock of [{wit_iface}]: {err:#}"),
+                ),
+            }
+        }
+
+        interface_dispatch_match_arms.push(iface_match_arms);
     }
 
     // Build a list of types that should be included
@@ -1341,6 +2935,173 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         .map(|(_, (_, s))| s.to_token_stream())
         .collect();
 
+    // Name all lattice methods, so that invocations can be crafted without string literals
+    let lattice_method_const_tokens = lattice_method_consts
+        .iter()
+        .map(|(name, (method, wit_fn))| {
+            let name = Ident::new(name, Span::call_site());
+            let doc = format!("Lattice method of `{wit_fn}`");
+            quote::quote!(
+                #[doc = #doc]
+                pub const #name: &str = #method;
+            )
+        })
+        .collect::<Vec<TokenStream>>();
+    let lattice_methods_tokens = quote::quote!(
+        /// Names of the lattice methods that functions of the WIT interfaces of the provider are
+        /// invoked on
+        pub mod lattice_methods {
+            #( #lattice_method_const_tokens )*
+        }
+    );
+
+    // Convert generated structs and enums from and to their wasmtime component counterparts
+    let component_conversions = match &cfg.component_types {
+        Some(root) => component_conversion_tokens(root, &visitor),
+        None => TokenStream::new(),
+    };
+
+    // The trait must be reachable from outside the prefix module for providers to implement it
+    let provider_trait_vis = if cfg.prefix_module.is_some() {
+        quote::quote!(pub(crate))
+    } else {
+        TokenStream::new()
+    };
+
+    // Health check hook of WasmcloudCapabilityProvider, which the generated ProviderHandler
+    // forwards the host's periodic health checks to
+    let health_check_hook = quote::quote!(
+        /// Perform health check. Called at regular intervals by host
+        /// Default implementation always returns healthy, override it to report the
+        /// state of backends (ex. a database being unreachable) instead
+        async fn health_request(
+            &self,
+            _arg: &::wasmcloud_provider_sdk::core::HealthCheckRequest,
+        ) -> ::wasmcloud_provider_sdk::core::HealthCheckResponse {
+            ::wasmcloud_provider_sdk::core::HealthCheckResponse {
+                healthy: true,
+                message: None,
+            }
+        }
+    );
+    let health_check_handler = quote::quote!(
+        async fn health_request(
+            &self,
+            arg: &::wasmcloud_provider_sdk::core::HealthCheckRequest,
+        ) -> ::wasmcloud_provider_sdk::core::HealthCheckResponse {
+            WasmcloudCapabilityProvider::health_request(self, arg).await
+        }
+    );
+
+    // Stateless providers have no links to keep track of, so the SDK defaults are used for them,
+    // while other providers must implement WasmcloudCapabilityProvider
+    let provider_handler_tokens = if cfg.stateless {
+        quote::quote!(
+            /// Zero-sized provider, which handles invocations with associated functions of the
+            /// generated traits
+            #[derive(Debug, Default, Clone, Copy)]
+            pub struct #impl_struct_name;
+
+            /// ProviderHandler ensures that your provider handles the basic
+            /// required functionality of all Providers on a wasmCloud lattice.
+            ///
+            /// Stateless providers accept all links and are always healthy
+            impl ::wasmcloud_provider_sdk::ProviderHandler for #impl_struct_name {}
+        )
+    } else {
+        quote::quote!(
+            /// This trait categorizes all wasmCloud lattice compatible providers.
+            ///
+            /// It is a mirror of ProviderHandler for the purposes of ensuring that
+            /// at least the following members are is supported.
+            #[::async_trait::async_trait]
+            #provider_trait_vis trait WasmcloudCapabilityProvider {
+                async fn put_link(&self, ld: &::wasmcloud_provider_sdk::core::LinkDefinition) -> bool;
+                async fn delete_link(&self, actor_id: &str);
+                async fn shutdown(&self);
+
+                #health_check_hook
+
+                /// Apply updated provider-level configuration delivered at runtime. Default
+                /// implementation accepts and ignores it
+                async fn update_config(
+                    &self,
+                    _values: &::std::collections::HashMap<String, String>,
+                ) -> bool {
+                    true
+                }
+            }
+
+            /// ProviderHandler ensures that your provider handles the basic
+            /// required functionality of all Providers on a wasmCloud lattice.
+            ///
+            /// This implementation is a stub and must be filled out by implementers
+            #[::async_trait::async_trait]
+            impl ::wasmcloud_provider_sdk::ProviderHandler for #impl_struct_name {
+                async fn put_link(&self, ld: &::wasmcloud_provider_sdk::core::LinkDefinition) -> bool {
+                    WasmcloudCapabilityProvider::put_link(self, ld).await
+                }
+
+                async fn delete_link(&self, actor_id: &str) {
+                    WasmcloudCapabilityProvider::delete_link(self, actor_id).await
+                }
+
+                async fn shutdown(&self) {
+                    WasmcloudCapabilityProvider::shutdown(self).await
+                }
+
+                #health_check_handler
+
+                async fn update_config(
+                    &self,
+                    values: &::std::collections::HashMap<String, String>,
+                ) -> bool {
+                    WasmcloudCapabilityProvider::update_config(self, values).await
+                }
+            }
+        )
+    };
+
+    // Generate the boilerplate `main` that every provider binary would otherwise copy
+    let main_tokens = if cfg.generate_main {
+        let friendly_name = LitStr::new(&cfg.provider_friendly_name(), Span::call_site());
+        quote::quote!(
+            fn main() -> Result<(), Box<dyn std::error::Error>> {
+                // Fail early if the host did not provide valid host data
+                ::wasmcloud_provider_sdk::load_host_data()?;
+
+                // start_provider initializes the threaded tokio executor,
+                // listens to lattice rpcs, handles actor links,
+                // and returns only when it receives a shutdown message
+                ::wasmcloud_provider_sdk::start_provider(
+                    <#impl_struct_name as ::core::default::Default>::default(),
+                    Some(#friendly_name.to_string()),
+                )?;
+
+                ::wasmcloud_provider_sdk::tracing::info!("{} exiting", #friendly_name);
+                Ok(())
+            }
+        )
+    } else {
+        TokenStream::new()
+    };
+
+    // Calls recorded by generated mocks are shared by the mocks of all interfaces
+    let mock_call_tokens = if cfg.generate_mocks {
+        quote::quote!(
+            /// Call of a method of a generated mock, recorded for assertions in tests
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            pub struct MockCall {
+                /// Lattice method that was called (ex. `KeyValue.Get`)
+                pub method: &'static str,
+                /// Debug representation of the arguments of the call, without the context
+                pub args: String,
+            }
+        )
+    } else {
+        TokenStream::new()
+    };
+
     // Build the final chunk of code
     let tokens = quote::quote!(
         // START: per-interface codegen
@@ -1365,6 +3126,10 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         )*
         // END: wit-bindgen generated enums
 
+        #component_conversions
+
+        #lattice_methods_tokens
+
         /// MessageDispatch ensures that your provider can receive and
         /// process messages sent to it over the lattice
         ///
@@ -1383,73 +3148,716 @@ pub fn generate(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     #(
                         #interface_dispatch_match_arms
                     )*
+                    #(
+                        #exported_iface_dispatch_match_arms
+                    )*
+                    _ => Err(::wasmcloud_provider_sdk::error::InvocationError::Malformed(format!(
+                        "Invalid method name {method}"
+                    )).into())
+                }
+            }
+
+            fn codec(&self) -> Option<::wasmcloud_provider_sdk::codec::Codec> {
+                Some(#sdk_codec)
+            }
+        }
+
+        // START: general provider
+
+        #provider_handler_tokens
+
+        /// Given the implementation of ProviderHandler and MessageDispatch,
+        /// the implementation for your struct is a guaranteed
+        impl ::wasmcloud_provider_sdk::Provider for #impl_struct_name {}
+
+        // Structs that are used at Invocation Handling time
+        #( #exported_iface_invocation_structs )*
+
+        // Traits for exported interfaces that actors may also call on the provider
+        #( #exported_iface_receiver_traits )*
+
+        /// This handler serves to be used for individual invocations of the actor
+        /// as performed by the host runtime
+        ///
+        /// Interfaces exported by the provider can use this to send traffic across the lattice
+        #[derive(Clone)]
+        pub struct InvocationHandler<'a> {
+            ld: ::std::borrow::Cow<'a, ::wasmcloud_provider_sdk::core::LinkDefinition>,
+            timeout: Option<::std::time::Duration>,
+            retries: u32,
+            lattice: Option<String>,
+        }
+
+        impl<'a> InvocationHandler<'a> {
+            pub fn new(ld: &'a ::wasmcloud_provider_sdk::core::LinkDefinition) -> Self {
+                Self {
+                    ld: ::std::borrow::Cow::Borrowed(ld),
+                    timeout: None,
+                    retries: 0,
+                    lattice: None,
+                }
+            }
+
+            /// Build a handler for invoking the actor with ID `actor_id`, using the link
+            /// definition of the actor in the lattice of the host, in the link store of the
+            /// provider connection (see [`::wasmcloud_provider_sdk::provider_main::get_connection`]).
+            ///
+            /// Fails if the actor is not linked to the provider
+            pub async fn for_actor(
+                connection: &::wasmcloud_provider_sdk::ProviderConnection,
+                actor_id: &str,
+            ) -> ::wasmcloud_provider_sdk::error::InvocationResult<Self> {
+                let ld = connection.get_link(actor_id).await.ok_or_else(|| {
+                    ::wasmcloud_provider_sdk::error::ValidationError::InvalidActor(
+                        actor_id.to_string(),
+                    )
+                })?;
+                Ok(Self {
+                    ld: ::std::borrow::Cow::Owned(ld),
+                    timeout: None,
+                    retries: 0,
+                    lattice: None,
+                })
+            }
+
+            /// Like [`InvocationHandler::for_actor`], for the actor with ID `actor_id` linked
+            /// in the lattice with prefix `lattice`, which the actor is then invoked in
+            pub async fn for_actor_in_lattice(
+                connection: &::wasmcloud_provider_sdk::ProviderConnection,
+                lattice: &str,
+                actor_id: &str,
+            ) -> ::wasmcloud_provider_sdk::error::InvocationResult<Self> {
+                let ld = connection
+                    .link_store()
+                    .get(lattice, actor_id)
+                    .await
+                    .ok_or_else(|| {
+                        ::wasmcloud_provider_sdk::error::ValidationError::InvalidActor(
+                            actor_id.to_string(),
+                        )
+                    })?;
+                Ok(Self {
+                    ld: ::std::borrow::Cow::Owned(ld),
+                    timeout: None,
+                    retries: 0,
+                    lattice: Some(lattice.to_string()),
+                })
+            }
+
+            /// Invoke the actor in the lattice with prefix `lattice` instead of the lattice of
+            /// the host, for actors linked in an additional lattice
+            pub fn in_lattice(mut self, lattice: impl Into<String>) -> Self {
+                self.lattice = Some(lattice.into());
+                self
+            }
+
+            /// Wait at most `timeout` for each attempt of an invocation, instead of the
+            /// timeout configured for the provider's RPC client
+            pub fn with_timeout(mut self, timeout: ::std::time::Duration) -> Self {
+                self.timeout = Some(timeout);
+                self
+            }
+
+            /// Retry invocations that time out or fail to be delivered up to `retries` times
+            pub fn with_retries(mut self, retries: u32) -> Self {
+                self.retries = retries;
+                self
+            }
+
+            #(
+                #exported_iface_invocation_methods
+            )*
+        }
+
+        #mock_call_tokens
+    );
+
+    // Place generated items in the prefix module if one was specified, re-exporting them so
+    // they can be used as if the module was not there (unless names clash with another invocation)
+    let tokens = match &cfg.prefix_module {
+        Some(module) => quote::quote!(
+            /// Items generated for the provider
+            #[allow(unused_imports)]
+            pub mod #module {
+                use super::*;
+
+                #tokens
+            }
+
+            #[allow(unused_imports)]
+            pub use #module::*;
+
+            #main_tokens
+        ),
+        None => quote::quote!(
+            #tokens
+
+            #main_tokens
+        ),
+    };
+
+    errors.finish()?;
+
+    if let Some(path) = &cfg.dump_generated {
+        if std::env::var_os(DUMP_GENERATED_ENV_VAR).is_some() {
+            dump_generated(path, &tokens).map_err(|e| {
+                syn::Error::new(
+                    cfg.spans.dump_generated.unwrap_or_else(Span::call_site),
+                    format!(
+                        "failed to dump generated code to [{}]: {e:#}",
+                        path.display()
+                    ),
+                )
+            })?;
+        }
+    }
+
+    Ok(tokens)
+}
+
+/// Generate a mock implementation of the trait `wit_iface` of an imported interface
+/// (ex. `MockWasiKeyvalueEventual`), which records calls and responds to them with programmable
+/// functions, along with a `MessageDispatch` implementation using `match_arms`
+fn mock_tokens(
+    wit_iface: &Ident,
+    methods: &[LatticeMethod],
+    invocation_args_with_types: &[TokenStream],
+    match_arms: &TokenStream,
+    codec: Codec,
+) -> anyhow::Result<TokenStream> {
+    let mock_name = format_ident!("Mock{}", wit_iface);
+    let sdk_codec = codec.sdk_codec();
+    let mut responder_fields = Vec::new();
+    let mut responder_setters = Vec::new();
+    let mut trait_methods = Vec::new();
+    for (lm, args) in methods.iter().zip(invocation_args_with_types) {
+        // Arguments are either those of the trait method or the members of an invocation struct
+        let args = (|input: syn::parse::ParseStream| {
+            Punctuated::<syn::Field, Token![,]>::parse_terminated_with(
+                input,
+                syn::Field::parse_named,
+            )
+        })
+        .parse2(args.clone())
+        .with_context(|| format!("failed to parse arguments of [{}]", lm.func_name))?;
+        let arg_names = args
+            .iter()
+            .filter_map(|f| f.ident.as_ref())
+            .collect::<Vec<&Ident>>();
+        let arg_types = args.iter().map(|f| &f.ty).collect::<Vec<&Type>>();
+        let ReturnType::Type(_, return_type) = &lm.invocation_return else {
+            bail!("missing return type of [{}]", lm.func_name);
+        };
+
+        let func_name = &lm.func_name;
+        let lattice_method = &lm.lattice_method_name;
+        let setter_name = format_ident!("on_{}", func_name);
+        responder_fields.push(quote::quote!(
+            #func_name: Option<
+                ::std::sync::Arc<
+                    dyn Fn(::wasmcloud_provider_sdk::Context, #( #arg_types ),*) -> #return_type
+                        + Send
+                        + Sync,
+                >,
+            >
+        ));
+        responder_setters.push(quote::quote!(
+            #[doc = concat!("Respond to calls of `", #lattice_method, "` with `respond`")]
+            pub fn #setter_name(
+                mut self,
+                respond: impl Fn(::wasmcloud_provider_sdk::Context, #( #arg_types ),*) -> #return_type
+                    + Send
+                    + Sync
+                    + 'static,
+            ) -> Self {
+                self.#func_name = Some(::std::sync::Arc::new(respond));
+                self
+            }
+        ));
+        trait_methods.push(quote::quote!(
+            async fn #func_name(
+                &self,
+                ctx: ::wasmcloud_provider_sdk::Context,
+                #( #arg_names: #arg_types ),*
+            ) -> #return_type {
+                self.calls
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .push(MockCall {
+                        method: #lattice_method,
+                        args: format!("{:?}", ( #( &#arg_names, )* )),
+                    });
+                match &self.#func_name {
+                    Some(respond) => respond(ctx, #( #arg_names ),*),
+                    None => Err(::wasmcloud_provider_sdk::error::ProviderInvocationError::Provider(
+                        format!("no response programmed for [{}]", #lattice_method),
+                    )),
+                }
+            }
+        ));
+    }
+
+    let doc = format!(
+        "Mock implementation of [`{wit_iface}`] for tests, which records all calls and responds \
+         to them as programmed, failing calls without a programmed response"
+    );
+    Ok(quote::quote!(
+        #[doc = #doc]
+        #[derive(Clone, Default)]
+        pub struct #mock_name {
+            calls: ::std::sync::Arc<::std::sync::Mutex<Vec<MockCall>>>,
+            #( #responder_fields, )*
+        }
+
+        impl #mock_name {
+            #( #responder_setters )*
+
+            /// Calls received so far, in order
+            pub fn calls(&self) -> Vec<MockCall> {
+                self.calls
+                    .lock()
+                    .unwrap_or_else(::std::sync::PoisonError::into_inner)
+                    .clone()
+            }
+        }
+
+        #[::async_trait::async_trait]
+        impl #wit_iface for #mock_name {
+            #( #trait_methods )*
+        }
+
+        #[::async_trait::async_trait]
+        impl ::wasmcloud_provider_sdk::MessageDispatch for #mock_name {
+            async fn dispatch<'a>(
+                &'a self,
+                ctx: ::wasmcloud_provider_sdk::Context,
+                method: String,
+                body: std::borrow::Cow<'a, [u8]>,
+            ) -> Result<Vec<u8>, ::wasmcloud_provider_sdk::error::ProviderInvocationError> {
+                match method.as_str() {
+                    #match_arms
                     _ => Err(::wasmcloud_provider_sdk::error::InvocationError::Malformed(format!(
                         "Invalid method name {method}"
                     )).into())
                 }
             }
+
+            fn codec(&self) -> Option<::wasmcloud_provider_sdk::codec::Codec> {
+                Some(#sdk_codec)
+            }
+        }
+    ))
+}
+
+/// Environment variable that must be set for `dump_generated` to write generated code to disk
+const DUMP_GENERATED_ENV_VAR: &str = "WASMCLOUD_PROVIDER_BINDGEN_DUMP";
+
+/// Format generated code and write it to `path`, relative to the root of the crate being built
+fn dump_generated(path: &Path, tokens: &TokenStream) -> anyhow::Result<()> {
+    let file =
+        syn::parse2::<syn::File>(tokens.clone()).context("failed to parse generated code")?;
+    let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(root) => PathBuf::from(root).join(path),
+        None => path.to_path_buf(),
+    };
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("failed to create directory [{}]", parent.display()))?;
+    }
+    std::fs::write(&path, prettyplease::unparse(&file)).context("failed to write file")
+}
+
+/// Generate `From` conversions in both directions between the structs and enums generated for
+/// WIT types and their counterparts generated by `wasmtime::component::bindgen!` under `root`.
+///
+/// Types that cannot be converted (ex. enums holding resources, which are sent across the lattice
+/// as their representation) are skipped, along with the types that contain them
+fn component_conversion_tokens(root: &syn::Path, visitor: &WitBindgenOutputVisitor) -> TokenStream {
+    // Find all types that cannot be converted, including those that (transitively) contain them
+    let mut skipped: BTreeSet<String> = visitor.resource_enums.clone();
+    loop {
+        let unconvertible = |fields: &syn::Fields| {
+            fields.iter().any(|f| {
+                component_conversion(&quote::quote!(value), &f.ty).is_err()
+                    || f.ty
+                        .to_token_stream()
+                        .to_string()
+                        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .any(|word| skipped.contains(word))
+            })
+        };
+        let newly_skipped = visitor
+            .serde_extended_structs
+            .iter()
+            .filter(|(_, (_, s))| unconvertible(&s.fields))
+            .map(|(name, _)| name)
+            .chain(
+                visitor
+                    .serde_extended_enums
+                    .iter()
+                    .filter(|(_, (_, e))| e.variants.iter().any(|v| unconvertible(&v.fields)))
+                    .map(|(name, _)| name),
+            )
+            .filter(|name| !skipped.contains(*name))
+            .cloned()
+            .collect::<Vec<String>>();
+        if newly_skipped.is_empty() {
+            break;
+        }
+        skipped.extend(newly_skipped);
+    }
+
+    let mut tokens = TokenStream::new();
+    for (name, (path, s)) in visitor.serde_extended_structs.iter() {
+        if skipped.contains(name) {
+            debug!("skipping component conversions of struct [{name}]");
+            continue;
+        }
+        match struct_conversion_tokens(root, path, s) {
+            Ok(conversions) => tokens.append_all(conversions),
+            Err(e) => debug!("skipping component conversions of struct [{name}]: {e:#}"),
+        }
+    }
+    for (name, (path, e)) in visitor.serde_extended_enums.iter() {
+        if skipped.contains(name) {
+            debug!("skipping component conversions of enum [{name}]");
+            continue;
         }
+        match enum_conversion_tokens(root, path, e) {
+            Ok(conversions) => tokens.append_all(conversions),
+            Err(e) => debug!("skipping component conversions of enum [{name}]: {e:#}"),
+        }
+    }
+    tokens
+}
 
-        // START: general provider
-
-        /// This trait categorizes all wasmCloud lattice compatible providers.
-        ///
-        /// It is a mirror of ProviderHandler for the purposes of ensuring that
-        /// at least the following members are is supported.
-        #[::async_trait::async_trait]
-        trait WasmcloudCapabilityProvider {
-            async fn put_link(&self, ld: &::wasmcloud_provider_sdk::core::LinkDefinition) -> bool;
-            async fn delete_link(&self, actor_id: &str);
-            async fn shutdown(&self);
+/// `From` conversions between a generated struct and its wasmtime component counterpart
+fn struct_conversion_tokens(
+    root: &syn::Path,
+    path: &Punctuated<PathSegment, Token![::]>,
+    s: &ItemStruct,
+) -> anyhow::Result<TokenStream> {
+    let name = &s.ident;
+    let mut fields = Vec::new();
+    let mut component_fields = Vec::new();
+    let mut from_component = Vec::new();
+    let mut to_component = Vec::new();
+    for f in s.fields.iter() {
+        let field = f.ident.as_ref().context("unexpected tuple struct")?;
+        // WIT-ified maps were renamed after dropping their `_map` suffix
+        let component_field = if is_map_type(&f.ty) {
+            format_ident!("{field}_map")
+        } else {
+            field.clone()
+        };
+        let component_value = quote::quote!(value.#component_field);
+        let value = quote::quote!(value.#field);
+        from_component
+            .push(component_conversion(&component_value, &f.ty)?.unwrap_or(component_value));
+        to_component.push(component_conversion(&value, &f.ty)?.unwrap_or(value));
+        fields.push(field);
+        component_fields.push(component_field);
+    }
+    Ok(quote::quote!(
+        impl From<#root::#path> for #name {
+            fn from(value: #root::#path) -> Self {
+                Self { #( #fields: #from_component ),* }
+            }
         }
 
-        /// ProviderHandler ensures that your provider handles the basic
-        /// required functionality of all Providers on a wasmCloud lattice.
-        ///
-        /// This implementation is a stub and must be filled out by implementers
-        #[::async_trait::async_trait]
-        impl ::wasmcloud_provider_sdk::ProviderHandler for #impl_struct_name {
-            async fn put_link(&self, ld: &::wasmcloud_provider_sdk::core::LinkDefinition) -> bool {
-                WasmcloudCapabilityProvider::put_link(self, ld).await
+        impl From<#name> for #root::#path {
+            fn from(value: #name) -> Self {
+                Self { #( #component_fields: #to_component ),* }
             }
+        }
+    ))
+}
 
-            async fn delete_link(&self, actor_id: &str) {
-                WasmcloudCapabilityProvider::delete_link(self, actor_id).await
+/// `From` conversions between a generated enum and its wasmtime component counterpart
+fn enum_conversion_tokens(
+    root: &syn::Path,
+    path: &Punctuated<PathSegment, Token![::]>,
+    e: &ItemEnum,
+) -> anyhow::Result<TokenStream> {
+    let name = &e.ident;
+    let mut from_component_arms = Vec::new();
+    let mut to_component_arms = Vec::new();
+    for v in e.variants.iter() {
+        let variant = &v.ident;
+        match &v.fields {
+            syn::Fields::Unit => {
+                from_component_arms.push(quote::quote!(#root::#path::#variant => Self::#variant));
+                to_component_arms.push(quote::quote!(#name::#variant => Self::#variant));
+            }
+            syn::Fields::Unnamed(fields) => {
+                let bindings = (0..fields.unnamed.len())
+                    .map(|idx| format_ident!("v{idx}"))
+                    .collect::<Vec<Ident>>();
+                let conversions = bindings
+                    .iter()
+                    .zip(fields.unnamed.iter())
+                    .map(|(binding, f)| {
+                        let binding = binding.to_token_stream();
+                        Ok(component_conversion(&binding, &f.ty)?.unwrap_or(binding))
+                    })
+                    .collect::<anyhow::Result<Vec<TokenStream>>>()?;
+                from_component_arms.push(quote::quote!(
+                    #root::#path::#variant(#( #bindings ),*) => Self::#variant(#( #conversions ),*)
+                ));
+                to_component_arms.push(quote::quote!(
+                    #name::#variant(#( #bindings ),*) => Self::#variant(#( #conversions ),*)
+                ));
+            }
+            syn::Fields::Named(_) => bail!("unexpected struct variant [{variant}]"),
+        }
+    }
+    Ok(quote::quote!(
+        impl From<#root::#path> for #name {
+            fn from(value: #root::#path) -> Self {
+                match value {
+                    #( #from_component_arms, )*
+                }
             }
+        }
 
-            async fn shutdown(&self) {
-                WasmcloudCapabilityProvider::shutdown(self).await
+        impl From<#name> for #root::#path {
+            fn from(value: #name) -> Self {
+                match value {
+                    #( #to_component_arms, )*
+                }
             }
         }
+    ))
+}
 
-        /// Given the implementation of ProviderHandler and MessageDispatch,
-        /// the implementation for your struct is a guaranteed
-        impl ::wasmcloud_provider_sdk::Provider for #impl_struct_name {}
+/// Whether a Rust type is an `Option`, which is sent across the lattice as an empty payload if it
+/// is the single argument of a function and `None`
+fn is_option_type(ty: &TokenStream) -> bool {
+    matches!(
+        syn::parse2::<Type>(ty.clone()),
+        Ok(Type::Path(p)) if p.path.segments.last().is_some_and(|s| s.ident == "Option")
+    )
+}
 
-        // Structs that are used at Invocation Handling time
-        #( #exported_iface_invocation_structs )*
+/// Whether a type is a map that replaced a WIT-ified map (see `replace_witified_maps`)
+fn is_map_type(ty: &Type) -> bool {
+    matches!(ty, Type::Path(p) if p.path.segments.last().is_some_and(|s| s.ident == "HashMap"))
+}
 
-        /// This handler serves to be used for individual invocations of the actor
-        /// as performed by the host runtime
-        ///
-        /// Interfaces exported by the provider can use this to send traffic across the lattice
-        pub struct InvocationHandler<'a> {
-            ld: &'a ::wasmcloud_provider_sdk::core::LinkDefinition,
+/// Build an expression converting `expr` of type `ty` (as used by generated structs and enums)
+/// from or to the type used by wasmtime component bindings, which is the same in both directions.
+///
+/// Returns `None` if the types are identical and no conversion is necessary
+fn component_conversion(expr: &TokenStream, ty: &Type) -> anyhow::Result<Option<TokenStream>> {
+    match ty {
+        Type::Tuple(tuple) if tuple.elems.is_empty() => Ok(None),
+        Type::Tuple(tuple) => {
+            let bindings = (0..tuple.elems.len())
+                .map(|idx| format_ident!("t{idx}"))
+                .collect::<Vec<Ident>>();
+            let mut conversions = Vec::new();
+            let mut converted = false;
+            for (binding, ty) in bindings.iter().zip(tuple.elems.iter()) {
+                let binding = binding.to_token_stream();
+                match component_conversion(&binding, ty)? {
+                    Some(conversion) => {
+                        converted = true;
+                        conversions.push(conversion);
+                    }
+                    None => conversions.push(binding),
+                }
+            }
+            Ok(converted.then(|| {
+                quote::quote!({
+                    let (#( #bindings, )*) = #expr;
+                    (#( #conversions, )*)
+                })
+            }))
+        }
+        Type::Path(p) if p.qself.is_none() => {
+            let segment = p
+                .path
+                .segments
+                .last()
+                .context("unexpectedly empty type path")?;
+            let args = match &segment.arguments {
+                syn::PathArguments::None => Vec::new(),
+                syn::PathArguments::AngleBracketed(args) => args
+                    .args
+                    .iter()
+                    .filter_map(|arg| match arg {
+                        syn::GenericArgument::Type(ty) => Some(ty),
+                        _ => None,
+                    })
+                    .collect(),
+                syn::PathArguments::Parenthesized(_) => {
+                    bail!("unsupported type [{}]", quote::quote!(#ty))
+                }
+            };
+            match (segment.ident.to_string().as_str(), &args[..]) {
+                (
+                    "bool" | "u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64" | "f32"
+                    | "f64" | "char" | "String",
+                    [],
+                ) => Ok(None),
+                ("Vec", [inner]) => Ok(component_conversion(&quote::quote!(v), inner)?.map(
+                    |conversion| quote::quote!(#expr.into_iter().map(|v| #conversion).collect()),
+                )),
+                ("Option", [inner]) => Ok(component_conversion(&quote::quote!(v), inner)?
+                    .map(|conversion| quote::quote!(#expr.map(|v| #conversion)))),
+                ("Result", [ok, err]) => {
+                    let ok = component_conversion(&quote::quote!(v), ok)?;
+                    let err = component_conversion(&quote::quote!(e), err)?;
+                    Ok((ok.is_some() || err.is_some()).then(|| {
+                        let ok = ok.unwrap_or_else(|| quote::quote!(v));
+                        let err = err.unwrap_or_else(|| quote::quote!(e));
+                        quote::quote!(#expr.map(|v| #ok).map_err(|e| #err))
+                    }))
+                }
+                // Maps are lists of key value pairs in wasmtime component bindings
+                ("HashMap", [key, value]) => {
+                    let key = component_conversion(&quote::quote!(k), key)?
+                        .unwrap_or_else(|| quote::quote!(k));
+                    let value = component_conversion(&quote::quote!(v), value)?
+                        .unwrap_or_else(|| quote::quote!(v));
+                    Ok(Some(quote::quote!(
+                        #expr.into_iter().map(|(k, v)| (#key, #value)).collect()
+                    )))
+                }
+                // Other generated types are converted by their own `From` conversions
+                (_, []) if p.path.segments.len() == 1 => Ok(Some(quote::quote!(#expr.into()))),
+                _ => bail!("unsupported type [{}]", quote::quote!(#ty)),
+            }
         }
+        _ => bail!("unsupported type [{}]", quote::quote!(#ty)),
+    }
+}
 
-        impl<'a> InvocationHandler<'a> {
-            pub fn new(ld: &'a ::wasmcloud_provider_sdk::core::LinkDefinition) -> Self {
-                Self { ld }
+/// Environment variable that disables the cache of wasmtime bindgen expansions when set
+const NO_EXPANSION_CACHE_ENV_VAR: &str = "WASMCLOUD_PROVIDER_BINDGEN_NO_CACHE";
+
+/// Cached expansions, which have not been used for this long, are evicted from the cache
+const EXPANSION_CACHE_MAX_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// Expand the wasmtime::component macro, reusing the bindings generated for the same WIT world
+/// during a previous build if they are cached under the target directory.
+///
+/// Failures to read or write the cache are not fatal, bindings are generated again instead
+fn expand_wasmtime_component_cached(cfg: &WitBindgenConfig) -> syn::Result<TokenStream> {
+    let Some(path) = expansion_cache_path(cfg) else {
+        return expand_wasmtime_component(cfg);
+    };
+
+    if let Ok(src) = std::fs::read_to_string(&path) {
+        match expand_wasmtime_component_source(cfg, &src) {
+            Ok(tokens) => {
+                debug!(path = %path.display(), "reusing cached wasmtime bindgen expansion");
+                // Mark the expansion as used, so that it is not evicted
+                if let Err(e) = std::fs::File::options()
+                    .append(true)
+                    .open(&path)
+                    .and_then(|file| file.set_modified(SystemTime::now()))
+                {
+                    warn!(path = %path.display(), "failed to mark cached expansion as used: {e}");
+                }
+                return Ok(tokens);
             }
+            Err(e) => warn!(path = %path.display(), "ignoring invalid cached expansion: {e}"),
+        }
+    }
 
-            #(
-                #exported_iface_invocation_methods
-            )*
+    let src = generate_wasmtime_component(cfg)?;
+    if let Err(e) = write_expansion_cache(&path, &src) {
+        warn!(path = %path.display(), "failed to cache wasmtime bindgen expansion: {e:#}");
+    }
+    // Expansions are only ever added when a WIT world changes, which is when stale ones pile up
+    if let Some(dir) = path.parent() {
+        evict_expansion_cache(dir, SystemTime::now());
+    }
+    expand_wasmtime_component_source(cfg, &src)
+}
+
+/// Path that the expansion of a bindgen config is cached at, which is derived from a SHA-256
+/// digest of the config and the contents of the WIT files it was resolved from (so that changes
+/// to any of them lead to a different path). The WIT files are the ones already read while
+/// parsing the config, so computing the path does not resolve the WIT again.
+///
+/// Returns `None` if caching is disabled via [`NO_EXPANSION_CACHE_ENV_VAR`], or if the config
+/// cannot be hashed (ex. because one of its files cannot be read)
+fn expansion_cache_path(cfg: &WitBindgenConfig) -> Option<PathBuf> {
+    if std::env::var_os(NO_EXPANSION_CACHE_ENV_VAR).is_some() {
+        return None;
+    }
+
+    // Every input is length-prefixed, so that distinct inputs never digest the same bytes
+    let mut hasher = Sha256::new();
+    let mut update = |bytes: &[u8]| {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    };
+    update(env!("CARGO_PKG_VERSION").as_bytes());
+    update(cfg.source.as_bytes());
+    for file in cfg.files.iter() {
+        update(file.to_string_lossy().as_bytes());
+        update(&std::fs::read(file).ok()?);
+    }
+
+    // Proc macros are not told where the target directory is, so look for the closest one to the
+    // crate being built (which is at the root of the workspace for workspace members)
+    let target_dir = match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) => PathBuf::from(dir),
+        None => {
+            let root = PathBuf::from(std::env::var_os("CARGO_MANIFEST_DIR")?);
+            root.ancestors()
+                .find(|dir| dir.join("target").is_dir())
+                .unwrap_or(&root)
+                .join("target")
         }
+    };
+    Some(
+        target_dir
+            .join("wasmcloud-provider-bindgen")
+            .join(format!("{:x}.rs", hasher.finalize())),
+    )
+}
 
-    );
+/// Remove expansions from the cache directory `dir`, which have not been used within
+/// [`EXPANSION_CACHE_MAX_AGE`] of `now`, along with temporary files left behind by interrupted
+/// builds. Failures are logged and otherwise ignored, since they only waste disk space
+fn evict_expansion_cache(dir: &Path, now: SystemTime) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            warn!(dir = %dir.display(), "failed to read expansion cache directory: {e}");
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) else {
+            continue;
+        };
+        let stale = now
+            .duration_since(modified)
+            .is_ok_and(|age| age > EXPANSION_CACHE_MAX_AGE);
+        if stale {
+            trace!(path = %path.display(), "evicting cached wasmtime bindgen expansion");
+            if let Err(e) = std::fs::remove_file(&path) {
+                warn!(path = %path.display(), "failed to evict cached expansion: {e}");
+            }
+        }
+    }
+}
 
-    tokens.into()
+/// Write generated bindings to the expansion cache, via a temporary file so that concurrent builds
+/// never read partially written bindings
+fn write_expansion_cache(path: &Path, src: &str) -> anyhow::Result<()> {
+    let parent = path.parent().context("missing cache directory")?;
+    std::fs::create_dir_all(parent)
+        .with_context(|| format!("failed to create directory [{}]", parent.display()))?;
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    std::fs::write(&tmp, src).context("failed to write temporary file")?;
+    std::fs::rename(&tmp, path).context("failed to move temporary file into place")
 }
 
 /// A struct for visiting the output of wit-bindgen
@@ -1486,11 +3894,24 @@ struct WitBindgenOutputVisitor {
     /// Enums that were modified and extended to derive Serialize/Deserialize
     serde_extended_enums: EnumLookup,
 
+    /// Enums in which resources were replaced with their representation, which therefore cannot
+    /// be converted back to their wasmtime component counterparts
+    resource_enums: BTreeSet<EnumName>,
+
+    /// How generated enums for WIT variants with payloads are tagged when serialized
+    variant_tagging: VariantTagging,
+
+    /// Derives added to structs and enums generated for WIT types
+    struct_derives: Vec<syn::Path>,
+
     /// Lookup of encountered types that were produced by bindgen, with their fully qualified names
     type_lookup: TypeLookup,
 
     /// Functions in traits that we'll have to stub eventually
-    import_trait_methods: HashMap<WitInterfacePath, Vec<TraitItemFn>>,
+    import_trait_methods: BTreeMap<WitInterfacePath, Vec<TraitItemFn>>,
+
+    /// Failures encountered while visiting, which are reported as compiler errors
+    errors: Vec<String>,
 }
 
 impl WitBindgenOutputVisitor {
@@ -1502,6 +3923,8 @@ impl WitBindgenOutputVisitor {
             exposed_interface_allow_list: cfg.exposed_interface_allow_list.clone(),
             exposed_interface_deny_list: cfg.exposed_interface_deny_list.clone(),
             replace_witified_maps: cfg.replace_witified_maps,
+            variant_tagging: cfg.variant_tagging,
+            struct_derives: cfg.struct_derives.clone(),
             ..Default::default()
         }
     }
@@ -1672,30 +4095,17 @@ impl VisitMut for WitBindgenOutputVisitor {
                     break 'visit_trait;
                 }
 
-                // Retrieve the interface name from the module hierarchy (immediate parent)
-                let iface = if let Some(iface) = self.parents.last() {
-                    iface
-                } else {
-                    panic!(
-                        "unexpectedly missing parent while processing trait {}",
+                // Retrieve the interface name, package and namespace from the module hierarchy
+                let [.., wit_ns, wit_pkg, iface] = &self.parents[..] else {
+                    self.errors.push(format!(
+                        "unexpectedly missing interface, package or namespace module while processing trait [{}] in generated bindgen code",
                         t.ident
-                    )
+                    ));
+                    break 'visit_trait;
                 };
-
-                let wit_ns = self
-                    .parents
-                    .get(self.parents.len() - 3)
-                    .unwrap_or_else(|| {
-                        panic!("unexpectedly missing ns level package (2 up from [{iface}] in generated bindgen code)")
-                    })
-                    .to_string();
-                let wit_pkg = self
-                    .parents
-                    .get(self.parents.len() - 2)
-                    .unwrap_or_else(|| {
-                        panic!("unexpectedly missing ns level package (1 up from [{iface}] in generated bindgen code)")
-                    })
-                    .to_string();
+                let wit_ns = wit_ns.to_string();
+                let wit_pkg = wit_pkg.to_string();
+                let iface = iface.clone();
                 let full_iface_name = format!("{wit_ns}:{wit_pkg}/{iface}");
 
                 // Build the (ns,pkg,interface) triples used to control lattice-exposed interfaces
@@ -1712,7 +4122,7 @@ impl VisitMut for WitBindgenOutputVisitor {
                     }
                     // If allow list is present (and deny missing), process only allow list
                     (allow, []) => {
-                        if allow.contains(iface_triple) {
+                        if interface_list_contains(allow, iface_triple) {
                             debug!(
                                 "processing interface [{full_iface_name}], included in allow list"
                             );
@@ -1725,7 +4135,7 @@ impl VisitMut for WitBindgenOutputVisitor {
                     }
                     // If deny list is present (and allow missing), process only deny list
                     ([], deny) => {
-                        if deny.contains(iface_triple) {
+                        if interface_list_contains(deny, iface_triple) {
                             warn!("skipping interface [{full_iface_name}], included in deny list");
                             return;
                         } else {
@@ -1734,7 +4144,9 @@ impl VisitMut for WitBindgenOutputVisitor {
                     }
                     // If both allow and deny are present, process allow then deny
                     (allow, deny) => {
-                        if allow.contains(iface_triple) && !deny.contains(iface_triple) {
+                        if interface_list_contains(allow, iface_triple)
+                            && !interface_list_contains(deny, iface_triple)
+                        {
                             debug!("processing interface [{full_iface_name}], included in allow and not in deny");
                         } else {
                             warn!("[warn] skipping interface [{full_iface_name}], not included in allow or missing from deny");
@@ -1781,13 +4193,31 @@ impl VisitMut for WitBindgenOutputVisitor {
                                         acc
                                     });
 
+                                    // Returned WIT-ified maps have no '_map' suffixed name to detect them by,
+                                    // so only those keyed by strings are replaced with a proper hash map type
+                                    if self.replace_witified_maps {
+                                        if let Some(map_type) = syn::parse2::<Type>(inner_tokens.clone())
+                                            .ok()
+                                            .and_then(|ty| extract_witified_map_return(&ty))
+                                        {
+                                            inner_tokens = map_type.to_token_stream();
+                                        }
+                                    }
+
                                     let result_tokens = quote::quote!(
                                             -> ::wasmcloud_provider_sdk::error::ProviderInvocationResult<#inner_tokens>
                                         );
 
-                                    trimmed.sig.output = syn::parse2::<ReturnType>(result_tokens.clone())
-                                        .expect("failed to purge wasmtime::Result from method return");
-                                    trace!("successfully converted type [{inner_tokens}] into ProivderInvocationResult<T>");
+                                    match syn::parse2::<ReturnType>(result_tokens.clone()) {
+                                        Ok(output) => {
+                                            trimmed.sig.output = output;
+                                            trace!("successfully converted type [{inner_tokens}] into ProivderInvocationResult<T>");
+                                        }
+                                        Err(e) => self.errors.push(format!(
+                                            "failed to purge wasmtime::Result from return of method [{}]: {e}",
+                                            trimmed.sig.ident
+                                        )),
+                                    }
 
                                     },
                                     _ => {},
@@ -1895,12 +4325,13 @@ impl VisitMut for WitBindgenOutputVisitor {
                     // enums that are aliases have types that are already defined elsewhere
                     && !self.type_lookup.contains_key(&e.ident.to_string())
                 {
-                    // Clear all pre-existing attributes (i.e. [component])
-                    e.attrs.clear();
+                    // Clear all pre-existing attributes (i.e. [component]), except for docs
+                    e.attrs = doc_attrs(&e.attrs);
 
-                    // Clear all pre-existing attributes from fields (mostly [component])
+                    // Clear all pre-existing attributes from fields (mostly [component]), except for docs
+                    let mut has_resources = false;
                     for v in &mut e.variants {
-                        v.attrs.clear();
+                        v.attrs = doc_attrs(&v.attrs);
 
                         // Process all fields in every variant to perform standard replacements
                         for f in &mut v.fields {
@@ -1937,6 +4368,7 @@ impl VisitMut for WitBindgenOutputVisitor {
                                         && b1.to_string() == "<"
                                         && b2.to_string() == ">" => {
                                         f.ty = syn::parse_str::<Type>("u32").expect("failed to parse");
+                                        has_resources = true;
                                     }
                                     _ => {}
                                 }
@@ -1963,11 +4395,22 @@ impl VisitMut for WitBindgenOutputVisitor {
                         }
                     }
 
+                    if has_resources {
+                        self.resource_enums.insert(e.ident.to_string());
+                    }
+
                     // Add the attributes we want to be present to the enum
+                    let extra_derives = &self.struct_derives;
                     e.attrs.append(&mut vec![parse_quote!(
-                        #[derive(Debug, ::serde::Serialize, ::serde::Deserialize)]
+                        #[derive(Debug, ::serde::Serialize, ::serde::Deserialize #(, #extra_derives)*)]
                     )]);
 
+                    // Enums generated for WIT variants (rather than WIT enums) carry payloads,
+                    // which must be tagged the same way as by the actors on the other end
+                    if e.variants.iter().any(|v| !v.fields.is_empty()) {
+                        e.attrs.extend(self.variant_tagging.serde_attr());
+                    }
+
                     // Save the enum by name to the tally of structs that have been extended
                     // this is used later to generate interfaces, when generating interfaces, as a import path lookup
                     // so that types can be resolved (i.e. T -> path::to::T)
@@ -1979,7 +4422,9 @@ impl VisitMut for WitBindgenOutputVisitor {
 
                     // Disallow the case where two identically named enums exist under different paths
                     if self.serde_extended_enums.contains_key(&e.ident.to_string()) {
-                        panic!("found duplicate instances of enum [${}]", e.ident);
+                        self.errors
+                            .push(format!("found duplicate instances of enum [{}]", e.ident));
+                        return;
                     }
 
                     self.serde_extended_enums
@@ -2005,12 +4450,12 @@ impl VisitMut for WitBindgenOutputVisitor {
                     // which cannot be Serialized
                     && !self.current_module_name().is_some_and(|m| s.ident == m.to_upper_camel_case())
                 {
-                    // Clear all pre-existing attributes (i.e. [component])
-                    s.attrs.clear();
+                    // Clear all pre-existing attributes (i.e. [component]), except for docs
+                    s.attrs = doc_attrs(&s.attrs);
 
-                    // Clear all pre-existing attributes from fields (mostly [component])
+                    // Clear all pre-existing attributes from fields (mostly [component]), except for docs
                     for f in &mut s.fields {
-                        f.attrs.clear();
+                        f.attrs = doc_attrs(&f.attrs);
 
                         // If the type of a particular field is a Vec<u8>,
                         // opt in to serde's specialized handling since this is what the
@@ -2041,9 +4486,10 @@ impl VisitMut for WitBindgenOutputVisitor {
                     }
 
                     // Add the attributes we want to be present
+                    let extra_derives = &self.struct_derives;
                     s.attrs.append(&mut vec![
                         parse_quote!(
-                            #[derive(Debug, ::serde::Serialize, ::serde::Deserialize)]
+                            #[derive(Debug, ::serde::Serialize, ::serde::Deserialize #(, #extra_derives)*)]
                         ),
                         parse_quote!(
                             #[serde(rename_all = "camelCase")]
@@ -2064,7 +4510,9 @@ impl VisitMut for WitBindgenOutputVisitor {
                         .serde_extended_structs
                         .contains_key(&s.ident.to_string())
                     {
-                        panic!("found duplicate instances of struct [${}]", s.ident);
+                        self.errors
+                            .push(format!("found duplicate instances of struct [{}]", s.ident));
+                        return;
                     }
 
                     self.serde_extended_structs
@@ -2112,6 +4560,9 @@ struct LatticeMethod {
 
     /// Return type of the invocation
     invocation_return: ReturnType,
+
+    /// Doc comments of the WIT function, carried over to the generated trait method
+    docs: Vec<syn::Attribute>,
 }
 
 /// Build [`LatticeMethod`]s (including related information to facilitate invocations)
@@ -2119,25 +4570,44 @@ struct LatticeMethod {
 fn build_lattice_methods_by_wit_interface(
     struct_lookup: &StructLookup,
     type_lookup: &TypeLookup,
-    map: &HashMap<WitInterfacePath, Vec<TraitItemFn>>,
+    map: &BTreeMap<WitInterfacePath, Vec<TraitItemFn>>,
     bindgen_cfg: &ProviderBindgenConfig,
-) -> anyhow::Result<HashMap<WitInterfacePath, Vec<LatticeMethod>>> {
-    let mut methods_by_name: HashMap<WitInterfacePath, Vec<LatticeMethod>> = HashMap::new();
+) -> anyhow::Result<BTreeMap<WitInterfacePath, Vec<LatticeMethod>>> {
+    let mut methods_by_name: BTreeMap<WitInterfacePath, Vec<LatticeMethod>> = BTreeMap::new();
 
     // For every trait item generated by an imported WIT interface we must generate the appropriate
     // structures that are expected from incoming messages on the lattice.
     for (wit_iface_name, funcs) in map.iter() {
         for trait_method in funcs.iter() {
+            let fn_override = bindgen_cfg.function_override_for_iface_path(
+                wit_iface_name,
+                &trait_method.sig.ident.to_string(),
+            );
+            if fn_override.is_some_and(|o| o.skip) {
+                debug!(
+                    "skipping imported function [{wit_iface_name}.{}]",
+                    trait_method.sig.ident
+                );
+                continue;
+            }
+
             // Convert the trait method to code that can be used on the lattice
-            let (name, lattice_method) = bindgen_cfg
-                .import_fn_lattice_translation_strategy
+            let (name, lattice_method) = fn_override
+                .and_then(|o| o.strategy.as_ref())
+                .unwrap_or(&bindgen_cfg.import_fn_lattice_translation_strategy)
                 .translate_import_fn_for_lattice(
                     bindgen_cfg,
                     wit_iface_name.into(),
                     trait_method,
                     struct_lookup,
                     type_lookup,
-                )?;
+                )
+                .with_context(|| {
+                    format!(
+                        "failed to translate imported function [{wit_iface_name}.{}]",
+                        trait_method.sig.ident
+                    )
+                })?;
 
             // Add the struct and its members to a list that will be used in another quote
             // it cannot be added directly/composed to a TokenStream here to avoid import conflicts
@@ -2165,10 +4635,10 @@ fn convert_wit_type(
         wit_parser::Type::U16 => Ok(quote::quote!(u16)),
         wit_parser::Type::U32 => Ok(quote::quote!(u32)),
         wit_parser::Type::U64 => Ok(quote::quote!(u64)),
-        wit_parser::Type::S8 => Ok(quote::quote!(s8)),
-        wit_parser::Type::S16 => Ok(quote::quote!(s16)),
-        wit_parser::Type::S32 => Ok(quote::quote!(s32)),
-        wit_parser::Type::S64 => Ok(quote::quote!(s64)),
+        wit_parser::Type::S8 => Ok(quote::quote!(i8)),
+        wit_parser::Type::S16 => Ok(quote::quote!(i16)),
+        wit_parser::Type::S32 => Ok(quote::quote!(i32)),
+        wit_parser::Type::S64 => Ok(quote::quote!(i64)),
         wit_parser::Type::Float32 => Ok(quote::quote!(f32)),
         wit_parser::Type::Float64 => Ok(quote::quote!(f64)),
         wit_parser::Type::Char => Ok(quote::quote!(char)),
@@ -2258,7 +4728,8 @@ fn convert_wit_typedef(
                 .enumerate()
             {
                 tuple_types.append_all(quote::quote!(#tokens));
-                if idx != types.len() - 1 {
+                // Single element tuples need a trailing comma (ex. `(u64,)`)
+                if idx != types.len() - 1 || types.len() == 1 {
                     tuple_types.append(TokenTree::Punct(Punct::new(
                         ',',
                         proc_macro2::Spacing::Alone,
@@ -2368,6 +4839,54 @@ fn extract_witified_map(input: &[TokenTree]) -> Option<TokenStream> {
     }
 }
 
+/// Attempt to convert a return type that is a WIT-ified map with string keys (i.e. `Vec<(String, V)>`)
+/// into a map type, looking through `Option` and the success type of `Result`
+///
+/// For example, the following Rust type would be converted into `Result<HashMap<String, u32>, String>`:
+///
+/// ```rust,ignore
+/// Result<Vec<(String, u32)>, String>
+/// ```
+fn extract_witified_map_return(ty: &Type) -> Option<Type> {
+    let Type::Path(type_path) = ty else {
+        return None;
+    };
+    let segment = type_path.path.segments.last()?;
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    let Some(syn::GenericArgument::Type(inner)) = args.args.first() else {
+        return None;
+    };
+    match segment.ident.to_string().as_str() {
+        // Re-wrap the converted map in the original container
+        "Option" | "Result" => {
+            let map_type = extract_witified_map_return(inner)?;
+            let mut converted = type_path.clone();
+            if let Some(syn::PathArguments::AngleBracketed(args)) = converted
+                .path
+                .segments
+                .last_mut()
+                .map(|segment| &mut segment.arguments)
+            {
+                args.args[0] = syn::GenericArgument::Type(map_type);
+            }
+            Some(Type::Path(converted))
+        }
+        "Vec" => match inner {
+            Type::Tuple(tuple)
+                if tuple.elems.len() == 2
+                    && matches!(&tuple.elems[0], Type::Path(key) if key.path.is_ident("String")) =>
+            {
+                let (key_type, value_type) = (&tuple.elems[0], &tuple.elems[1]);
+                Some(parse_quote!(::std::collections::HashMap<#key_type, #value_type>))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
 /// Process a first argument to retreive the argument name and type name used
 fn process_fn_arg(arg: &FnArg) -> anyhow::Result<(Ident, TokenStream)> {
     // Retrieve the type pattern ascription (i.e. 'arg: Type') out of the first arg
@@ -2427,18 +4946,16 @@ impl ToRustType for wit_parser::Results {
                     [] => Ok(quote::quote!(())),
                     // One or more results:
                     //
-                    // e.x. `func(arg: string) -> string`
-                    // e.x. `func(arg: string) -> ( string, string )`
+                    // e.x. `func(arg: string) -> (value: string)`
+                    // e.x. `func(arg: string) -> (first: string, second: u64)`
                     ref params => {
-                        let mut types: Vec<Ident> = Vec::new();
+                        let mut types: Vec<TokenStream> = Vec::new();
                         for (_, ty) in params.iter() {
-                            types.push(syn::parse2::<Ident>(
-                                convert_wit_type(ty, cfg).with_context(|| {
-                                    format!("failed to convert WIT type [{ty:?}] to rust type]")
-                                })?,
-                            )?);
+                            types.push(convert_wit_type(ty, cfg).with_context(|| {
+                                format!("failed to convert WIT type [{ty:?}] to rust type]")
+                            })?);
                         }
-                        Ok(quote::quote!(( #( #types )* )))
+                        Ok(quote::quote!(( #( #types ),* )))
                     }
                 }
             }
@@ -2466,14 +4983,17 @@ fn count_preceeding_supers(t: &Type) -> usize {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use std::collections::BTreeMap;
 
     use anyhow::{Context, Result};
-    use proc_macro2::TokenTree;
+    use proc_macro2::{Span, TokenTree};
+    use quote::ToTokens;
     use syn::{parse_quote, LitStr, TraitItemFn};
 
     use crate::{
-        extract_witified_map, ProviderBindgenConfig, WitFunctionLatticeTranslationStrategy,
+        expand, expansion_cache_path, extract_witified_map, extract_witified_map_return,
+        wit_doc_tokens, FunctionOverride, InterfaceOverrideMap, PackageVersions,
+        ProviderBindgenConfig, WitBindgenConfig, WitFunctionLatticeTranslationStrategy,
     };
 
     /// Token trees that we expect to parse into WIT-ified maps should parse
@@ -2488,6 +5008,44 @@ mod tests {
         Ok(())
     }
 
+    /// Returned WIT-ified maps with string keys should be converted, including when wrapped
+    #[test]
+    fn parse_witified_map_return_type() -> Result<()> {
+        for (ty, expected) in [
+            (
+                quote::quote!(Vec<(String, String)>),
+                quote::quote!(::std::collections::HashMap<String, String>),
+            ),
+            (
+                quote::quote!(Option<Vec<(String, u32)>>),
+                quote::quote!(Option<::std::collections::HashMap<String, u32>>),
+            ),
+            (
+                quote::quote!(Result<Option<Vec<(String, Vec<u8>)>>, String>),
+                quote::quote!(Result<Option<::std::collections::HashMap<String, Vec<u8>>>, String>),
+            ),
+        ] {
+            let converted = extract_witified_map_return(&syn::parse2(ty.clone())?)
+                .with_context(|| format!("failed to convert return type {ty}"))?;
+            assert_eq!(
+                converted.to_token_stream().to_string(),
+                syn::parse2::<syn::Type>(expected)?
+                    .to_token_stream()
+                    .to_string()
+            );
+        }
+
+        // Only maps keyed by strings are detected, and errors are never converted
+        for ty in [
+            quote::quote!(Vec<(u32, String)>),
+            quote::quote!(Vec<String>),
+            quote::quote!(Result<String, Vec<(String, String)>>),
+        ] {
+            assert!(extract_witified_map_return(&syn::parse2(ty)?).is_none());
+        }
+        Ok(())
+    }
+
     /// Ensure WIT-ified maps parse correctly in functions
     #[test]
     fn parse_witified_map_in_fn() -> Result<()> {
@@ -2497,14 +5055,33 @@ mod tests {
         let bindgen_cfg = ProviderBindgenConfig {
             impl_struct: "None".into(),
             contract: "wasmcloud:test".into(),
+            contract_interfaces: Vec::new(),
             wit_ns: Some("test".into()),
             wit_pkg: Some("foo".into()),
             exposed_interface_allow_list: Default::default(),
             exposed_interface_deny_list: Default::default(),
+            export_interface_receivers: Default::default(),
             wit_bindgen_cfg: None, // We won't actually run bindgen
             import_fn_lattice_translation_strategy: Default::default(),
             export_fn_lattice_translation_strategy: Default::default(),
             replace_witified_maps: true,
+            stateless: false,
+            generate_main: false,
+            tracing: false,
+            generate_mocks: false,
+            verbose: false,
+            invocation_struct_visibility: None,
+            invocation_struct_derives: Vec::new(),
+            struct_derives: Vec::new(),
+            component_types: None,
+            prefix_module: None,
+            codec: Default::default(),
+            variant_tagging: Default::default(),
+            package_versions: Default::default(),
+            package_version_compat: false,
+            interface_overrides: Vec::new(),
+            dump_generated: None,
+            spans: Default::default(),
         };
         let (wit_iface_name, lm) =
             WitFunctionLatticeTranslationStrategy::translate_import_fn_via_bundled_args(
@@ -2512,8 +5089,8 @@ mod tests {
                 "TestFoo".into(),
                 LitStr::new("Foo", proc_macro2::Span::call_site()),
                 &trait_fn,
-                &HashMap::new(), // structs
-                &HashMap::new(), // types
+                &BTreeMap::new(), // structs
+                &BTreeMap::new(), // types
             )?;
 
         assert_eq!(wit_iface_name, "TestFoo");
@@ -2552,4 +5129,404 @@ mod tests {
 
         Ok(())
     }
+
+    /// Ensure interfaces are mapped to their contracts, falling back to the default contract
+    #[test]
+    fn lookup_contract_for_interface() {
+        let bindgen_cfg = ProviderBindgenConfig {
+            impl_struct: "None".into(),
+            contract: "wasmcloud:test".into(),
+            contract_interfaces: vec![(
+                "wasmcloud:keyvalue".into(),
+                vec![("wasmcloud".into(), "keyvalue".into(), "key-value".into())],
+            )],
+            wit_ns: None,
+            wit_pkg: None,
+            exposed_interface_allow_list: Default::default(),
+            exposed_interface_deny_list: Default::default(),
+            export_interface_receivers: Default::default(),
+            wit_bindgen_cfg: None, // We won't actually run bindgen
+            import_fn_lattice_translation_strategy: Default::default(),
+            export_fn_lattice_translation_strategy: Default::default(),
+            replace_witified_maps: false,
+            stateless: false,
+            generate_main: false,
+            tracing: false,
+            generate_mocks: false,
+            verbose: false,
+            invocation_struct_visibility: None,
+            invocation_struct_derives: Vec::new(),
+            struct_derives: Vec::new(),
+            component_types: None,
+            prefix_module: None,
+            codec: Default::default(),
+            variant_tagging: Default::default(),
+            package_versions: Default::default(),
+            package_version_compat: false,
+            interface_overrides: Vec::new(),
+            dump_generated: None,
+            spans: Default::default(),
+        };
+
+        assert_eq!(
+            bindgen_cfg.contract_for("wasmcloud", "keyvalue", "key-value"),
+            "wasmcloud:keyvalue"
+        );
+        assert_eq!(
+            bindgen_cfg.contract_for_iface_path("wasmcloud.keyvalue.key_value"),
+            "wasmcloud:keyvalue"
+        );
+        assert_eq!(
+            bindgen_cfg.contract_for("wasmcloud", "messaging", "consumer"),
+            "wasmcloud:test"
+        );
+        assert_eq!(
+            bindgen_cfg.contract_for_iface_path("invalid"),
+            "wasmcloud:test"
+        );
+    }
+
+    /// Multi-line WIT docs should become one doc attribute per line
+    #[test]
+    fn convert_wit_docs() {
+        let docs = wit_parser::Docs {
+            contents: Some("Get a value\n\nFails if `key` is missing".into()),
+        };
+        assert_eq!(
+            wit_doc_tokens(&docs).to_string(),
+            quote::quote!(
+                #[doc = " Get a value"]
+                #[doc = " "]
+                #[doc = " Fails if `key` is missing"]
+            )
+            .to_string()
+        );
+        assert!(wit_doc_tokens(&wit_parser::Docs::default()).is_empty());
+    }
+
+    /// Provider friendly names should be derived from the name of the contract
+    #[test]
+    fn derive_provider_friendly_name() {
+        let mut bindgen_cfg = ProviderBindgenConfig {
+            impl_struct: "None".into(),
+            contract: "wasmcloud:keyvalue".into(),
+            contract_interfaces: Vec::new(),
+            wit_ns: None,
+            wit_pkg: None,
+            exposed_interface_allow_list: Default::default(),
+            exposed_interface_deny_list: Default::default(),
+            export_interface_receivers: Default::default(),
+            wit_bindgen_cfg: None, // We won't actually run bindgen
+            import_fn_lattice_translation_strategy: Default::default(),
+            export_fn_lattice_translation_strategy: Default::default(),
+            replace_witified_maps: false,
+            stateless: false,
+            generate_main: true,
+            tracing: false,
+            generate_mocks: false,
+            verbose: false,
+            invocation_struct_visibility: None,
+            invocation_struct_derives: Vec::new(),
+            struct_derives: Vec::new(),
+            component_types: None,
+            prefix_module: None,
+            codec: Default::default(),
+            variant_tagging: Default::default(),
+            package_versions: Default::default(),
+            package_version_compat: false,
+            interface_overrides: Vec::new(),
+            dump_generated: None,
+            spans: Default::default(),
+        };
+        assert_eq!(bindgen_cfg.provider_friendly_name(), "keyvalue-provider");
+
+        bindgen_cfg.contract = "wasmcloud:httpserver".into();
+        assert_eq!(bindgen_cfg.provider_friendly_name(), "httpserver-provider");
+
+        bindgen_cfg.contract = "blobStore".into();
+        assert_eq!(bindgen_cfg.provider_friendly_name(), "blob-store-provider");
+
+        bindgen_cfg.contract = "wasi:keyvalue@0.2.0-draft".into();
+        assert_eq!(bindgen_cfg.provider_friendly_name(), "keyvalue-provider");
+    }
+
+    /// Package versions should be included in or stripped from lattice methods and contract IDs
+    #[test]
+    fn apply_package_versions() {
+        let mut bindgen_cfg = ProviderBindgenConfig {
+            impl_struct: "None".into(),
+            contract: "wasi:keyvalue".into(),
+            contract_interfaces: Vec::new(),
+            wit_ns: None,
+            wit_pkg: None,
+            exposed_interface_allow_list: Default::default(),
+            exposed_interface_deny_list: Default::default(),
+            export_interface_receivers: Default::default(),
+            wit_bindgen_cfg: None, // We won't actually run bindgen
+            import_fn_lattice_translation_strategy: Default::default(),
+            export_fn_lattice_translation_strategy: Default::default(),
+            replace_witified_maps: false,
+            stateless: false,
+            generate_main: false,
+            tracing: false,
+            generate_mocks: false,
+            verbose: false,
+            invocation_struct_visibility: None,
+            invocation_struct_derives: Vec::new(),
+            struct_derives: Vec::new(),
+            component_types: None,
+            prefix_module: None,
+            codec: Default::default(),
+            variant_tagging: Default::default(),
+            package_versions: Default::default(),
+            package_version_compat: false,
+            interface_overrides: Vec::new(),
+            dump_generated: None,
+            spans: Default::default(),
+        };
+        let version = || Some("0.2.0-draft".to_string());
+        let lattice_method = LitStr::new("Eventual.Get", Span::call_site());
+
+        // Versions are stripped by default
+        assert_eq!(
+            bindgen_cfg.versioned_lattice_method("Eventual.Get".into(), version()),
+            "Eventual.Get"
+        );
+        assert_eq!(
+            bindgen_cfg.contract_id("wasi:keyvalue@0.2.0-draft", version()),
+            "wasi:keyvalue"
+        );
+        assert_eq!(
+            bindgen_cfg
+                .lattice_method_pattern(&lattice_method, Some("0.2.0-draft"))
+                .to_string(),
+            quote::quote!("Eventual.Get").to_string()
+        );
+
+        bindgen_cfg.package_versions = PackageVersions::Include;
+        assert_eq!(
+            bindgen_cfg.versioned_lattice_method("Eventual.Get".into(), version()),
+            "Eventual.Get@0.2.0-draft"
+        );
+        assert_eq!(
+            bindgen_cfg.versioned_lattice_method("Eventual.Get".into(), None),
+            "Eventual.Get"
+        );
+        assert_eq!(
+            bindgen_cfg.contract_id("wasi:keyvalue", version()),
+            "wasi:keyvalue@0.2.0-draft"
+        );
+        assert_eq!(
+            bindgen_cfg.contract_id("wasi:keyvalue@0.1.0", version()),
+            "wasi:keyvalue@0.1.0"
+        );
+
+        // Both names are dispatched in compatibility mode
+        bindgen_cfg.package_version_compat = true;
+        let versioned_method = LitStr::new("Eventual.Get@0.2.0-draft", Span::call_site());
+        assert_eq!(
+            bindgen_cfg
+                .lattice_method_pattern(&versioned_method, Some("0.2.0-draft"))
+                .to_string(),
+            quote::quote!("Eventual.Get@0.2.0-draft" | "Eventual.Get").to_string()
+        );
+        assert_eq!(
+            bindgen_cfg
+                .lattice_method_pattern(&lattice_method, Some("0.2.0-draft"))
+                .to_string(),
+            quote::quote!("Eventual.Get" | "Eventual.Get@0.2.0-draft").to_string()
+        );
+        assert_eq!(
+            bindgen_cfg
+                .lattice_method_pattern(&lattice_method, None)
+                .to_string(),
+            quote::quote!("Eventual.Get").to_string()
+        );
+    }
+
+    /// Ensure function overrides parse and are found regardless of the case of names
+    #[test]
+    fn lookup_function_override() -> Result<()> {
+        let overrides: InterfaceOverrideMap = syn::parse2(quote::quote!({
+            "wasi:keyvalue/eventual.get": { strategy: "first-argument" },
+            "wasi:keyvalue/eventual.set": { method: "Eventual.Put", strategy: "bundle-arguments" },
+            "wasi:keyvalue/eventual.delete": { skip: true },
+        }))?;
+        let bindgen_cfg = ProviderBindgenConfig {
+            impl_struct: "None".into(),
+            contract: "wasmcloud:test".into(),
+            contract_interfaces: Vec::new(),
+            wit_ns: None,
+            wit_pkg: None,
+            exposed_interface_allow_list: Default::default(),
+            exposed_interface_deny_list: Default::default(),
+            export_interface_receivers: Default::default(),
+            wit_bindgen_cfg: None, // We won't actually run bindgen
+            import_fn_lattice_translation_strategy: Default::default(),
+            export_fn_lattice_translation_strategy: Default::default(),
+            replace_witified_maps: false,
+            stateless: false,
+            generate_main: false,
+            tracing: false,
+            generate_mocks: false,
+            verbose: false,
+            invocation_struct_visibility: None,
+            invocation_struct_derives: Vec::new(),
+            struct_derives: Vec::new(),
+            component_types: None,
+            prefix_module: None,
+            codec: Default::default(),
+            variant_tagging: Default::default(),
+            package_versions: Default::default(),
+            package_version_compat: false,
+            interface_overrides: overrides.inner,
+            dump_generated: None,
+            spans: Default::default(),
+        };
+
+        assert_eq!(
+            bindgen_cfg.function_override("wasi", "keyvalue", "eventual", "get"),
+            Some(&FunctionOverride {
+                method: None,
+                strategy: Some(WitFunctionLatticeTranslationStrategy::FirstArgument),
+                skip: false,
+            })
+        );
+        assert_eq!(
+            bindgen_cfg.function_override_for_iface_path("wasi.keyvalue.eventual", "set"),
+            Some(&FunctionOverride {
+                method: Some("Eventual.Put".into()),
+                strategy: Some(WitFunctionLatticeTranslationStrategy::BundleArguments),
+                skip: false,
+            })
+        );
+        assert!(bindgen_cfg
+            .function_override_for_iface_path("wasi.keyvalue.eventual", "delete")
+            .is_some_and(|o| o.skip));
+        assert_eq!(
+            bindgen_cfg.function_override("wasi", "keyvalue", "eventual", "exists"),
+            None
+        );
+        assert!(syn::parse2::<InterfaceOverrideMap>(quote::quote!({
+            "wasi:keyvalue/eventual": { skip: true },
+        }))
+        .is_err());
+        Ok(())
+    }
+
+    /// Cached expansions should only be reused for identical WIT
+    #[test]
+    fn expansion_cache_path_tracks_wit() -> Result<()> {
+        let parse = |wit: &str| {
+            syn::parse2::<WitBindgenConfig>(quote::quote!({
+                inline: #wit,
+                world: "provider-kv",
+            }))
+        };
+        let wit = "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            world provider-kv {
+                import store;
+            }
+        ";
+
+        let path = expansion_cache_path(&parse(wit)?).context("missing cache path")?;
+        assert_eq!(Some(&path), expansion_cache_path(&parse(wit)?).as_ref());
+        let changed = wit.replace("option<string>", "option<u64>");
+        assert_ne!(Some(&path), expansion_cache_path(&parse(&changed)?).as_ref());
+        // The file name is the hex-encoded SHA-256 digest of the inputs
+        let name = path.file_stem().and_then(|name| name.to_str()).unwrap_or_default();
+        assert_eq!(name.len(), 64);
+        assert!(name.chars().all(|c| c.is_ascii_hexdigit()));
+        Ok(())
+    }
+
+    /// Only expansions, which have not been used recently, are evicted
+    #[test]
+    fn expansion_cache_eviction() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!(
+            "wasmcloud-provider-bindgen-eviction-{}",
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir)?;
+        let fresh = dir.join("fresh.rs");
+        let stale = dir.join("stale.rs");
+        let tmp = dir.join("stale.rs.123.tmp");
+        for path in [&fresh, &stale, &tmp] {
+            std::fs::write(path, "")?;
+        }
+        let now = SystemTime::now();
+        let old = now - EXPANSION_CACHE_MAX_AGE - Duration::from_secs(60);
+        for path in [&stale, &tmp] {
+            std::fs::File::options()
+                .append(true)
+                .open(path)?
+                .set_modified(old)?;
+        }
+
+        evict_expansion_cache(&dir, now);
+        assert!(fresh.exists());
+        assert!(!stale.exists());
+        assert!(!tmp.exists());
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+
+    /// Ensure that expanding the same configuration always produces identical code
+    #[test]
+    fn expansion_is_deterministic() -> Result<()> {
+        let cfg: ProviderBindgenConfig = syn::parse2(quote::quote!({
+            impl_struct: TestProvider,
+            contract: "wasmcloud:test",
+            wit_bindgen_cfg: {
+                inline: "
+                    package test:kv;
+
+                    interface store {
+                        record entry {
+                            key: string,
+                            value: string,
+                        }
+
+                        variant failure {
+                            missing(string),
+                            denied,
+                        }
+
+                        get: func(key: string) -> result<entry, failure>;
+                        set: func(key: string, value: string);
+                    }
+
+                    interface watcher {
+                        record change {
+                            key: string,
+                        }
+
+                        on-change: func(change: change);
+                    }
+
+                    interface notifier {
+                        notify: func(key: string, value: string);
+                    }
+
+                    world provider-kv {
+                        import store;
+                        import watcher;
+                        export notifier;
+                    }
+                ",
+                world: "provider-kv",
+            }
+        }))?;
+        let expected = expand(&cfg)?.to_string();
+        for _ in 0..8 {
+            assert_eq!(expand(&cfg)?.to_string(), expected);
+        }
+        Ok(())
+    }
 }
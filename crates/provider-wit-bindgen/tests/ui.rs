@@ -0,0 +1,8 @@
+/// Ensure that invalid `generate!` invocations are rejected with helpful errors, and that
+/// valid ones expand into code that compiles against the provider SDK
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/fail/*.rs");
+    t.pass("tests/ui/pass/*.rs");
+}
@@ -0,0 +1,3 @@
+wasmcloud_provider_wit_bindgen::generate!({ exposed_interface_allow_list: ["bad"] });
+
+fn main() {}
@@ -0,0 +1,21 @@
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    export_fn_lattice_translation_strategy: "first-argument",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:notify;
+
+            interface handler {
+                handle-pair: func(first: string, second: u32) -> result<_, string>;
+            }
+
+            world provider-notify {
+                export handler;
+            }
+        ",
+        world: "provider-notify",
+    }
+});
+
+fn main() {}
@@ -0,0 +1,3 @@
+wasmcloud_provider_wit_bindgen::generate!({ import_fn_lattice_translation_strategy: "everything" });
+
+fn main() {}
@@ -0,0 +1,3 @@
+wasmcloud_provider_wit_bindgen::generate!({ contract: "wasmcloud:test" });
+
+fn main() {}
@@ -0,0 +1,22 @@
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    stateless: true,
+    generate_mocks: true,
+    wit_bindgen_cfg: {
+        inline: "
+            package test:echo;
+
+            interface echo {
+                echo: func(msg: string) -> string;
+            }
+
+            world provider-echo {
+                import echo;
+            }
+        ",
+        world: "provider-echo",
+    }
+});
+
+fn main() {}
@@ -0,0 +1,21 @@
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    exposed_interface_allow_list: ["test:echo/ecoh", "test:other/*"],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:echo;
+
+            interface echo {
+                echo: func(msg: string) -> string;
+            }
+
+            world provider-echo {
+                import echo;
+            }
+        ",
+        world: "provider-echo",
+    }
+});
+
+fn main() {}
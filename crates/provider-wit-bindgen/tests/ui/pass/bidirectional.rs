@@ -0,0 +1,98 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    export_interface_receivers: ["test:chat/handler"],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:chat;
+
+            interface rooms {
+                join: func(room: string) -> result<_, string>;
+            }
+
+            interface handler {
+                handle-message: func(msg: string) -> result<_, string>;
+                handle-reaction: func(msg-id: string, emoji: string) -> result<_, string>;
+                ping: func();
+            }
+
+            world provider-chat {
+                import rooms;
+                export handler;
+            }
+        ",
+        world: "provider-chat",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestChatRooms for TestProvider {
+    async fn join(
+        &self,
+        _ctx: Context,
+        _room: String,
+    ) -> ProviderInvocationResult<Result<(), String>> {
+        Ok(Ok(()))
+    }
+}
+
+#[async_trait::async_trait]
+impl TestChatHandler for TestProvider {
+    async fn handle_message(
+        &self,
+        _ctx: Context,
+        _msg: String,
+    ) -> ProviderInvocationResult<Result<(), String>> {
+        Ok(Ok(()))
+    }
+
+    async fn handle_reaction(
+        &self,
+        _ctx: Context,
+        _msg_id: String,
+        _emoji: String,
+    ) -> ProviderInvocationResult<Result<(), String>> {
+        Ok(Ok(()))
+    }
+
+    async fn ping(&self, _ctx: Context) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    let _: Result<(), String> = handler.handle_message("hello".to_string()).await?;
+    let _: Result<(), String> = handler
+        .handle_reaction(HandleReactionArgs {
+            msg_id: "1".to_string(),
+            emoji: "+1".to_string(),
+        })
+        .await?;
+    handler.ping().await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+fn main() {
+    assert_provider::<TestProvider>();
+}
@@ -0,0 +1,113 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::Context;
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    replace_witified_maps: true,
+    component_types: "crate::bindings",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:shapes;
+
+            interface canvas {
+                record point {
+                    x: s32,
+                    y: s32,
+                }
+
+                variant shape {
+                    circle(u32),
+                    polygon(list<point>),
+                }
+
+                record layer {
+                    name: option<string>,
+                    shapes: list<shape>,
+                    anchors-map: list<tuple<string, point>>,
+                }
+
+                draw: func(l: layer) -> result<point, string>;
+            }
+
+            world provider-shapes {
+                import canvas;
+            }
+        ",
+        world: "provider-shapes",
+    }
+});
+
+/// Types as generated by `wasmtime::component::bindgen!` for the same world
+mod bindings {
+    pub mod test {
+        pub mod shapes {
+            pub mod canvas {
+                #[derive(Debug, Clone, PartialEq)]
+                pub struct Point {
+                    pub x: i32,
+                    pub y: i32,
+                }
+
+                #[derive(Debug, Clone, PartialEq)]
+                pub enum Shape {
+                    Circle(u32),
+                    Polygon(Vec<Point>),
+                }
+
+                #[derive(Debug, Clone, PartialEq)]
+                pub struct Layer {
+                    pub name: Option<String>,
+                    pub shapes: Vec<Shape>,
+                    pub anchors_map: Vec<(String, Point)>,
+                }
+            }
+        }
+    }
+}
+
+use bindings::test::shapes::canvas as component;
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestShapesCanvas for TestProvider {
+    async fn draw(
+        &self,
+        _ctx: Context,
+        _l: Layer,
+    ) -> ProviderInvocationResult<Result<Point, String>> {
+        Ok(Ok(Point { x: 0, y: 0 }))
+    }
+}
+
+fn main() {
+    let layer = component::Layer {
+        name: Some("background".to_string()),
+        shapes: vec![
+            component::Shape::Circle(3),
+            component::Shape::Polygon(vec![component::Point { x: 1, y: 2 }]),
+        ],
+        anchors_map: vec![("origin".to_string(), component::Point { x: 0, y: 0 })],
+    };
+
+    let converted = Layer::from(layer.clone());
+    assert_eq!(converted.name.as_deref(), Some("background"));
+    assert_eq!(converted.shapes.len(), 2);
+    assert!(matches!(&converted.shapes[1], Shape::Polygon(points) if points[0].y == 2));
+    assert_eq!(converted.anchors.get("origin").map(|p| p.x), Some(0));
+
+    assert_eq!(component::Layer::from(converted), layer);
+}
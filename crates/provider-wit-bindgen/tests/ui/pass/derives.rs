@@ -0,0 +1,47 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    export_fn_lattice_translation_strategy: "auto",
+    invocation_struct_visibility: "pub(crate)",
+    invocation_struct_derives: [Clone, PartialEq],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:notify;
+
+            interface handler {
+                handle-pair: func(first: string, second: u32) -> result<_, string>;
+            }
+
+            world provider-notify {
+                export handler;
+            }
+        ",
+        world: "provider-notify",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+fn main() {
+    let args = HandlePairArgs {
+        first: "hello".to_string(),
+        second: 42,
+    };
+    let copy = args.clone();
+    assert!(args == copy);
+    assert_eq!(copy.first, "hello");
+    assert_eq!(copy.second, 42);
+}
@@ -0,0 +1,103 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    tracing: true,
+    export_interface_receivers: ["test:traced/handler"],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:traced;
+
+            interface greeter {
+                greet: func(name: string) -> string;
+                greet-both: func(first: string, second: string) -> string;
+                ping: func();
+            }
+
+            interface handler {
+                handle-message: func(msg: string) -> result<_, string>;
+            }
+
+            world provider-traced {
+                import greeter;
+                export handler;
+            }
+        ",
+        world: "provider-traced",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestTracedGreeter for TestProvider {
+    async fn greet(&self, _ctx: Context, name: String) -> ProviderInvocationResult<String> {
+        Ok(format!("hello {name}"))
+    }
+
+    async fn greet_both(
+        &self,
+        _ctx: Context,
+        first: String,
+        second: String,
+    ) -> ProviderInvocationResult<String> {
+        Ok(format!("hello {first} and {second}"))
+    }
+
+    async fn ping(&self, _ctx: Context) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl TestTracedHandler for TestProvider {
+    async fn handle_message(
+        &self,
+        _ctx: Context,
+        _msg: String,
+    ) -> ProviderInvocationResult<Result<(), String>> {
+        Ok(Ok(()))
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    let response = TestProvider
+        .dispatch(
+            Context {
+                invocation_id: Some("test-invocation".to_string()),
+                ..Default::default()
+            },
+            "Greeter.Greet".to_string(),
+            std::borrow::Cow::Borrowed(br#""wasmCloud""#),
+        )
+        .await
+        .expect("failed to dispatch traced invocation");
+    assert_eq!(response, br#""hello wasmCloud""#);
+    assert!(TestProvider
+        .dispatch(
+            Context::default(),
+            "Greeter.Greet".to_string(),
+            std::borrow::Cow::Borrowed(b"invalid"),
+        )
+        .await
+        .is_err());
+}
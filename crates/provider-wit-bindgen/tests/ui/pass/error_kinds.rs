@@ -0,0 +1,89 @@
+use wasmcloud_provider_sdk::core::{InvocationErrorKind, LinkDefinition};
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            world provider-kv {
+                import store;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, key: String) -> ProviderInvocationResult<Option<String>> {
+        match key.as_str() {
+            "denied" => Err(ProviderInvocationError::PermissionDenied(
+                "key is not readable".to_string(),
+            )),
+            _ => Err(ProviderInvocationError::BackendUnavailable(
+                "store is unreachable".to_string(),
+            )),
+        }
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+
+    // Errors returned by the provider keep their kind, which is sent to the caller
+    let err = TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Get".to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .expect_err("dispatch should fail");
+    assert_eq!(err.kind(), InvocationErrorKind::BackendUnavailable);
+    assert_eq!(err.to_string(), "store is unreachable");
+    let err = TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Get".to_string(),
+            std::borrow::Cow::Borrowed(br#""denied""#),
+        )
+        .await
+        .expect_err("dispatch should fail");
+    assert_eq!(err.kind(), InvocationErrorKind::PermissionDenied);
+
+    // Malformed payloads are reported as such
+    let err = TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Get".to_string(),
+            std::borrow::Cow::Borrowed(b"not json"),
+        )
+        .await
+        .expect_err("dispatch should fail");
+    assert_eq!(err.kind(), InvocationErrorKind::Malformed);
+}
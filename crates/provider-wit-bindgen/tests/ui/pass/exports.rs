@@ -0,0 +1,64 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationError;
+use wasmcloud_provider_sdk::ProviderConnection;
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    export_fn_lattice_translation_strategy: "auto",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:notify;
+
+            interface handler {
+                handle-message: func(msg: string) -> result<_, string>;
+                handle-pair: func(first: string, second: u32) -> result<_, string>;
+            }
+
+            world provider-notify {
+                export handler;
+            }
+        ",
+        world: "provider-notify",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    let _: Result<(), String> = handler.handle_message("hello".to_string()).await?;
+    let _: Result<(), String> = handler
+        .with_timeout(std::time::Duration::from_secs(5))
+        .with_retries(2)
+        .handle_pair(HandlePairArgs {
+            first: "hello".to_string(),
+            second: 42,
+        })
+        .await?;
+    Ok(())
+}
+
+#[allow(dead_code)]
+async fn notify_actor(
+    connection: &ProviderConnection,
+    actor_id: &str,
+) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::for_actor(connection, actor_id).await?;
+    let _: Result<(), String> = handler.handle_message("hello".to_string()).await?;
+    Ok(())
+}
+
+fn main() {}
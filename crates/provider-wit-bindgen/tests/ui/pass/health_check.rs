@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use wasmcloud_provider_sdk::core::{HealthCheckRequest, HealthCheckResponse, LinkDefinition};
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, Provider, ProviderHandler};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            world provider-kv {
+                import store;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+#[derive(Default)]
+struct TestProvider {
+    backend_down: AtomicBool,
+}
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+
+    async fn health_request(&self, _arg: &HealthCheckRequest) -> HealthCheckResponse {
+        if self.backend_down.load(Ordering::Relaxed) {
+            HealthCheckResponse {
+                healthy: false,
+                message: Some("backend unreachable".to_string()),
+            }
+        } else {
+            HealthCheckResponse {
+                healthy: true,
+                message: None,
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, _key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(None)
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+
+    // The SDK answers health checks of the host through `ProviderHandler`
+    let provider = TestProvider::default();
+    let response = ProviderHandler::health_request(&provider, &HealthCheckRequest {}).await;
+    assert!(response.healthy);
+
+    provider.backend_down.store(true, Ordering::Relaxed);
+    let response = ProviderHandler::health_request(&provider, &HealthCheckRequest {}).await;
+    assert!(!response.healthy);
+    assert_eq!(response.message.as_deref(), Some("backend unreachable"));
+}
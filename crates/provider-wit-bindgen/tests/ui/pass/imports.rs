@@ -0,0 +1,78 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    replace_witified_maps: true,
+    wit_bindgen_cfg: {
+        inline: "
+            package test:echo;
+
+            interface echo {
+                record message {
+                    body: string,
+                    headers-map: list<tuple<string, string>>,
+                }
+
+                echo: func(msg: message) -> message;
+                concat: func(left: string, right: string) -> string;
+                ping: func();
+                headers: func() -> result<list<tuple<string, string>>, string>;
+            }
+
+            world provider-echo {
+                import echo;
+            }
+        ",
+        world: "provider-echo",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestEchoEcho for TestProvider {
+    async fn echo(&self, _ctx: Context, msg: Message) -> ProviderInvocationResult<Message> {
+        let _: &std::collections::HashMap<String, String> = &msg.headers;
+        Ok(msg)
+    }
+
+    async fn concat(
+        &self,
+        _ctx: Context,
+        left: String,
+        right: String,
+    ) -> ProviderInvocationResult<String> {
+        Ok(format!("{left}{right}"))
+    }
+
+    async fn ping(&self, _ctx: Context) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+
+    async fn headers(
+        &self,
+        _ctx: Context,
+    ) -> ProviderInvocationResult<Result<std::collections::HashMap<String, String>, String>> {
+        Ok(Ok(std::collections::HashMap::new()))
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+fn main() {
+    assert_provider::<TestProvider>();
+}
@@ -0,0 +1,46 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, Provider};
+
+// WIT source can be given directly, instead of a world name resolved from the `wit` directory
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    wit_bindgen_cfg: "
+        package test:inline;
+
+        interface echo {
+            echo: func(msg: string) -> string;
+        }
+
+        world provider-inline {
+            import echo;
+        }
+    "
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestInlineEcho for TestProvider {
+    async fn echo(&self, _ctx: Context, msg: String) -> ProviderInvocationResult<String> {
+        Ok(msg)
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+fn main() {
+    assert_provider::<TestProvider>();
+}
@@ -0,0 +1,56 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, Provider};
+
+// Only `test:kv/store` is exposed, so no implementation of `TestKvAdmin` is required
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    exposed_interface_allow_list: ["test:kv/*"],
+    exposed_interface_deny_list: ["test:kv/admin"],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            interface admin {
+                clear: func();
+            }
+
+            world provider-kv {
+                import store;
+                import admin;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, _key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(None)
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+fn main() {
+    assert_provider::<TestProvider>();
+}
@@ -0,0 +1,104 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    import_fn_lattice_translation_strategy: "first-argument",
+    interface_overrides: {
+        "test:kv/store.set": { method: "Store.Put", strategy: "bundle-arguments" },
+        "test:kv/store.delete": { skip: true },
+        "test:kv/watcher.on-change": { method: "Watcher.Changed" },
+    },
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+                set: func(key: string, value: string);
+                delete: func(key: string);
+            }
+
+            interface watcher {
+                on-change: func(key: string);
+            }
+
+            world provider-kv {
+                import store;
+                export watcher;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+// `delete` is skipped, so it is neither part of the trait nor dispatched
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(Some(key))
+    }
+
+    async fn set(
+        &self,
+        _ctx: Context,
+        _key: String,
+        _value: String,
+    ) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    handler.on_change("key".to_string()).await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Put".to_string(),
+            std::borrow::Cow::Borrowed(br#"{"key":"k","value":"v"}"#),
+        )
+        .await
+        .expect("failed to dispatch renamed invocation");
+    assert!(TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Set".to_string(),
+            std::borrow::Cow::Borrowed(br#"{"key":"k","value":"v"}"#),
+        )
+        .await
+        .is_err());
+    assert!(TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Delete".to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .is_err());
+}
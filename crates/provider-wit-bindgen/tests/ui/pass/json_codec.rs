@@ -0,0 +1,75 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:json;
+
+            interface greeter {
+                greet: func(name: string) -> string;
+            }
+
+            interface handler {
+                handle-message: func(msg: string) -> result<_, string>;
+            }
+
+            world provider-json {
+                import greeter;
+                export handler;
+            }
+        ",
+        world: "provider-json",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestJsonGreeter for TestProvider {
+    async fn greet(&self, _ctx: Context, name: String) -> ProviderInvocationResult<String> {
+        Ok(format!("hello {name}"))
+    }
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    let _: Result<(), String> = handler.handle_message("hello".to_string()).await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    let response = TestProvider
+        .dispatch(
+            Context::default(),
+            "Greeter.Greet".to_string(),
+            std::borrow::Cow::Borrowed(br#""wasmCloud""#),
+        )
+        .await
+        .expect("failed to dispatch JSON invocation");
+    assert_eq!(response, br#""hello wasmCloud""#);
+    assert_eq!(
+        TestProvider.codec(),
+        Some(wasmcloud_provider_sdk::codec::Codec::Json)
+    );
+}
@@ -0,0 +1,84 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    interface_overrides: {
+        "test:kv/store.set": { method: "Store.Put" },
+    },
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+                set: func(key: string, value: string);
+            }
+
+            interface watcher {
+                on-change: func(key: string);
+            }
+
+            world provider-kv {
+                import store;
+                export watcher;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(Some(key))
+    }
+
+    async fn set(
+        &self,
+        _ctx: Context,
+        _key: String,
+        _value: String,
+    ) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    assert_eq!(lattice_methods::TEST_KV_STORE_GET, "Store.Get");
+    assert_eq!(lattice_methods::TEST_KV_STORE_SET, "Store.Put");
+    assert_eq!(
+        lattice_methods::TEST_KV_WATCHER_ON_CHANGE,
+        "Message.OnChange"
+    );
+
+    let response = TestProvider
+        .dispatch(
+            Context::default(),
+            lattice_methods::TEST_KV_STORE_GET.to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .expect("failed to dispatch invocation");
+    assert_eq!(response, br#""k""#);
+}
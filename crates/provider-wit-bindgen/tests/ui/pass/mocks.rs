@@ -0,0 +1,103 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    generate_mocks: true,
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+                set: func(key: string, value: string);
+                clear: func();
+            }
+
+            world provider-kv {
+                import store;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, _key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(None)
+    }
+
+    async fn set(
+        &self,
+        _ctx: Context,
+        _key: String,
+        _value: String,
+    ) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+
+    async fn clear(&self, _ctx: Context) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+
+    let mock = MockTestKvStore::default()
+        .on_get(|_ctx, key| Ok(Some(format!("value of {key}"))))
+        .on_set(|_ctx, _key, _value| Ok(()));
+
+    let response = mock
+        .dispatch(
+            Context::default(),
+            "Store.Get".to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .expect("failed to dispatch to mock");
+    assert_eq!(response, br#""value of k""#);
+    mock.set(Context::default(), "k".to_string(), "v".to_string())
+        .await
+        .expect("failed to call mock");
+
+    // Calls without a programmed response fail, but are recorded nonetheless
+    assert!(mock.clear(Context::default()).await.is_err());
+    assert_eq!(
+        mock.calls(),
+        vec![
+            MockCall {
+                method: "Store.Get",
+                args: r#"("k",)"#.to_string(),
+            },
+            MockCall {
+                method: "Store.Set",
+                args: r#"("k", "v")"#.to_string(),
+            },
+            MockCall {
+                method: "Store.Clear",
+                args: "()".to_string(),
+            },
+        ]
+    );
+}
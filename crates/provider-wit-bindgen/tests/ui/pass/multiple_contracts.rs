@@ -0,0 +1,76 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contracts: {
+        "wasmcloud:keyvalue": ["test:multi/store"],
+        "wasmcloud:messaging": ["test:multi/broker"],
+    },
+    wit_bindgen_cfg: {
+        inline: "
+            package test:multi;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            interface broker {
+                publish: func(subject: string, body: string);
+            }
+
+            world provider-multi {
+                import store;
+                import broker;
+            }
+        ",
+        world: "provider-multi",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestMultiStore for TestProvider {
+    async fn get(&self, _ctx: Context, _key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(None)
+    }
+}
+
+#[async_trait::async_trait]
+impl TestMultiBroker for TestProvider {
+    async fn publish(
+        &self,
+        _ctx: Context,
+        _subject: String,
+        _body: String,
+    ) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+fn main() {
+    assert_provider::<TestProvider>();
+    assert_eq!(
+        <TestProvider as TestMultiStore>::contract_id(),
+        "wasmcloud:keyvalue"
+    );
+    assert_eq!(
+        <TestProvider as TestMultiBroker>::contract_id(),
+        "wasmcloud:messaging"
+    );
+}
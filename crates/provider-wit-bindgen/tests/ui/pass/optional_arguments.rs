@@ -0,0 +1,108 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+// Single optional arguments that are `None` are sent as empty payloads
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    export_interface_receivers: ["test:config/watcher"],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:config;
+
+            interface store {
+                record settings {
+                    verbose: bool,
+                }
+
+                configure: func(settings: option<settings>) -> bool;
+            }
+
+            interface watcher {
+                on-reset: func(reason: option<string>) -> string;
+            }
+
+            world provider-config {
+                import store;
+                export watcher;
+            }
+        ",
+        world: "provider-config",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestConfigStore for TestProvider {
+    async fn configure(
+        &self,
+        _ctx: Context,
+        settings: Option<Settings>,
+    ) -> ProviderInvocationResult<bool> {
+        Ok(settings.is_some_and(|s| s.verbose))
+    }
+}
+
+#[async_trait::async_trait]
+impl TestConfigWatcher for TestProvider {
+    async fn on_reset(
+        &self,
+        _ctx: Context,
+        reason: Option<String>,
+    ) -> ProviderInvocationResult<String> {
+        Ok(reason.unwrap_or_else(|| "unknown".to_string()))
+    }
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    handler.on_reset(None).await?;
+    handler.on_reset(Some("restarted".to_string())).await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+async fn round_trip(method: &str, body: &'static [u8]) -> Vec<u8> {
+    TestProvider
+        .dispatch(
+            Context::default(),
+            method.to_string(),
+            std::borrow::Cow::Borrowed(body),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("failed to dispatch [{method}]: {e}"))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+
+    assert_eq!(round_trip("Store.Configure", b"").await, b"false");
+    assert_eq!(round_trip("Store.Configure", b"null").await, b"false");
+    assert_eq!(
+        round_trip("Store.Configure", br#"{"verbose":true}"#).await,
+        b"true"
+    );
+
+    assert_eq!(round_trip("Message.OnReset", b"").await, br#""unknown""#);
+    assert_eq!(
+        round_trip("Message.OnReset", br#""restarted""#).await,
+        br#""restarted""#
+    );
+}
@@ -0,0 +1,86 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    package_versions: "include",
+    package_version_compat: true,
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv@0.2.0-draft;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            interface watcher {
+                on-change: func(key: string);
+            }
+
+            world provider-kv {
+                import store;
+                export watcher;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestKvStore for TestProvider {
+    async fn get(&self, _ctx: Context, key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(Some(key))
+    }
+}
+
+// Sent as `Message.OnChange@0.2.0-draft`, on behalf of the `wasmcloud:test@0.2.0-draft` contract
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    handler.on_change("key".to_string()).await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+
+    // Actors using either the versioned or the unversioned lattice method are served
+    for method in ["Store.Get@0.2.0-draft", "Store.Get"] {
+        let response = TestProvider
+            .dispatch(
+                Context::default(),
+                method.to_string(),
+                std::borrow::Cow::Borrowed(br#""k""#),
+            )
+            .await
+            .unwrap_or_else(|e| panic!("failed to dispatch [{method}]: {e}"));
+        assert_eq!(response, br#""k""#);
+    }
+    assert!(TestProvider
+        .dispatch(
+            Context::default(),
+            "Store.Get@0.1.0".to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .is_err());
+}
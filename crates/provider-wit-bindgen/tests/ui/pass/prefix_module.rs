@@ -0,0 +1,112 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: KvProvider,
+    contract: "wasmcloud:keyvalue",
+    codec: "json",
+    prefix_module: kv,
+    wit_bindgen_cfg: {
+        inline: "
+            package test:kv;
+
+            interface store {
+                get: func(key: string) -> option<string>;
+            }
+
+            world provider-kv {
+                import store;
+            }
+        ",
+        world: "provider-kv",
+    }
+});
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: CounterProvider,
+    contract: "wasmcloud:counter",
+    codec: "json",
+    prefix_module: counter,
+    wit_bindgen_cfg: {
+        inline: "
+            package test:counter;
+
+            interface store {
+                increment: func(key: string) -> u64;
+            }
+
+            world provider-counter {
+                import store;
+            }
+        ",
+        world: "provider-counter",
+    }
+});
+
+struct KvProvider;
+
+struct CounterProvider;
+
+#[async_trait::async_trait]
+impl kv::WasmcloudCapabilityProvider for KvProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl counter::WasmcloudCapabilityProvider for CounterProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+// Items with distinct names can be used without their prefix module
+#[async_trait::async_trait]
+impl TestKvStore for KvProvider {
+    async fn get(&self, _ctx: Context, key: String) -> ProviderInvocationResult<Option<String>> {
+        Ok(Some(key))
+    }
+}
+
+#[async_trait::async_trait]
+impl counter::TestCounterStore for CounterProvider {
+    async fn increment(&self, _ctx: Context, _key: String) -> ProviderInvocationResult<u64> {
+        Ok(1)
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<KvProvider>();
+    assert_provider::<CounterProvider>();
+    let response = KvProvider
+        .dispatch(
+            Context::default(),
+            "Store.Get".to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .expect("failed to dispatch to kv provider");
+    assert_eq!(response, br#""k""#);
+    let response = CounterProvider
+        .dispatch(
+            Context::default(),
+            "Store.Increment".to_string(),
+            std::borrow::Cow::Borrowed(br#""k""#),
+        )
+        .await
+        .expect("failed to dispatch to counter provider");
+    assert_eq!(response, br#"1"#);
+}
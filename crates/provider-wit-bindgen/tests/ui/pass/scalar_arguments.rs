@@ -0,0 +1,116 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+// Scalar and tuple arguments are sent as-is with the first argument strategy, without
+// being bundled into invocation structs
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    import_fn_lattice_translation_strategy: "first-argument",
+    export_fn_lattice_translation_strategy: "first-argument",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:counter;
+
+            interface atomic {
+                increment: func(delta: u64) -> u64;
+                offset: func(delta: s32) -> s32;
+                scale: func(factor: f64) -> f64;
+                next-letter: func(letter: char) -> char;
+                toggle: func(enabled: bool) -> bool;
+                swap: func(pair: tuple<u8, u8>) -> tuple<u8, u8>;
+                wrap: func(single: tuple<s64>) -> tuple<s64>;
+            }
+
+            interface watcher {
+                on-change: func(new-value: s64);
+                bounds: func(step: u8) -> (lower: u64, upper: u64);
+            }
+
+            world provider-counter {
+                import atomic;
+                export watcher;
+            }
+        ",
+        world: "provider-counter",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestCounterAtomic for TestProvider {
+    async fn increment(&self, _ctx: Context, delta: u64) -> ProviderInvocationResult<u64> {
+        Ok(delta + 1)
+    }
+
+    async fn offset(&self, _ctx: Context, delta: i32) -> ProviderInvocationResult<i32> {
+        Ok(delta - 10)
+    }
+
+    async fn scale(&self, _ctx: Context, factor: f64) -> ProviderInvocationResult<f64> {
+        Ok(factor * 2.0)
+    }
+
+    async fn next_letter(&self, _ctx: Context, letter: char) -> ProviderInvocationResult<char> {
+        Ok(char::from_u32(letter as u32 + 1).unwrap_or(letter))
+    }
+
+    async fn toggle(&self, _ctx: Context, enabled: bool) -> ProviderInvocationResult<bool> {
+        Ok(!enabled)
+    }
+
+    async fn swap(&self, _ctx: Context, pair: (u8, u8)) -> ProviderInvocationResult<(u8, u8)> {
+        Ok((pair.1, pair.0))
+    }
+
+    async fn wrap(&self, _ctx: Context, single: (i64,)) -> ProviderInvocationResult<(i64,)> {
+        Ok((-single.0,))
+    }
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    handler.on_change(-5).await?;
+    let (_lower, _upper): (u64, u64) = handler.bounds(2).await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+async fn round_trip(method: &str, body: &'static [u8]) -> Vec<u8> {
+    TestProvider
+        .dispatch(
+            Context::default(),
+            method.to_string(),
+            std::borrow::Cow::Borrowed(body),
+        )
+        .await
+        .unwrap_or_else(|e| panic!("failed to dispatch [{method}]: {e}"))
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    assert_eq!(round_trip("Atomic.Increment", b"5").await, b"6");
+    assert_eq!(round_trip("Atomic.Offset", b"3").await, b"-7");
+    assert_eq!(round_trip("Atomic.Scale", b"1.5").await, b"3.0");
+    assert_eq!(round_trip("Atomic.NextLetter", br#""a""#).await, br#""b""#);
+    assert_eq!(round_trip("Atomic.Toggle", b"true").await, b"false");
+    assert_eq!(round_trip("Atomic.Swap", b"[1,2]").await, b"[2,1]");
+    assert_eq!(round_trip("Atomic.Wrap", b"[4]").await, b"[-4]");
+}
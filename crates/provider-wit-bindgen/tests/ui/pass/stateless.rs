@@ -0,0 +1,65 @@
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+// `TestProvider` is generated as a zero-sized struct, with default link and health handling
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    stateless: true,
+    codec: "json",
+    export_interface_receivers: ["test:clock/alarm"],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:clock;
+
+            interface clock {
+                now: func() -> u64;
+                add: func(a: u64, b: u64) -> u64;
+            }
+
+            interface alarm {
+                ring: func(at: u64);
+            }
+
+            world provider-clock {
+                import clock;
+                export alarm;
+            }
+        ",
+        world: "provider-clock",
+    }
+});
+
+#[async_trait::async_trait]
+impl TestClockClock for TestProvider {
+    async fn now(_ctx: Context) -> ProviderInvocationResult<u64> {
+        Ok(42)
+    }
+
+    async fn add(_ctx: Context, a: u64, b: u64) -> ProviderInvocationResult<u64> {
+        Ok(a + b)
+    }
+}
+
+#[async_trait::async_trait]
+impl TestClockAlarm for TestProvider {
+    async fn ring(_ctx: Context, _at: u64) -> ProviderInvocationResult<()> {
+        Ok(())
+    }
+}
+
+fn assert_provider<P: Provider + Default + Copy>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    let response = TestProvider
+        .dispatch(
+            Context::default(),
+            "Clock.Now".to_string(),
+            std::borrow::Cow::Borrowed(b"null"),
+        )
+        .await
+        .expect("failed to dispatch stateless invocation");
+    assert_eq!(response, b"42");
+}
@@ -0,0 +1,73 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::Context;
+
+// Derives listed in both options are only added once
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    struct_derives: [Clone, PartialEq],
+    invocation_struct_derives: [PartialEq],
+    wit_bindgen_cfg: {
+        inline: "
+            package test:shapes;
+
+            interface canvas {
+                variant shape {
+                    circle(u32),
+                    square(u32),
+                }
+
+                record point {
+                    x: s32,
+                    y: s32,
+                }
+
+                draw: func(origin: point, s: shape) -> bool;
+            }
+
+            world provider-shapes {
+                import canvas;
+            }
+        ",
+        world: "provider-shapes",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestShapesCanvas for TestProvider {
+    async fn draw(
+        &self,
+        _ctx: Context,
+        _origin: Point,
+        _s: Shape,
+    ) -> ProviderInvocationResult<bool> {
+        Ok(true)
+    }
+}
+
+fn main() {
+    let origin = Point { x: 1, y: -1 };
+    assert!(origin.clone() == origin);
+    assert!(Shape::Circle(2).clone() == Shape::Circle(2));
+
+    // Invocation structs of imported functions can be compared as well
+    let invocation = TestShapesCanvasDrawInvocation {
+        origin: origin.clone(),
+        s: Shape::Square(3),
+    };
+    assert!(invocation.clone() == invocation);
+}
@@ -0,0 +1,77 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::ProviderInvocationResult;
+use wasmcloud_provider_sdk::{Context, MessageDispatch, Provider};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    codec: "json",
+    variant_tagging: "adjacently",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:http;
+
+            interface outgoing {
+                variant error-code {
+                    connection-refused,
+                    connection-timeout(u32),
+                }
+
+                retry: func(code: error-code) -> error-code;
+            }
+
+            world provider-http {
+                import outgoing;
+            }
+        ",
+        world: "provider-http",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestHttpOutgoing for TestProvider {
+    async fn retry(&self, _ctx: Context, code: ErrorCode) -> ProviderInvocationResult<ErrorCode> {
+        Ok(match code {
+            ErrorCode::ConnectionTimeout(secs) => ErrorCode::ConnectionTimeout(secs * 2),
+            ErrorCode::ConnectionRefused => ErrorCode::ConnectionRefused,
+        })
+    }
+}
+
+fn assert_provider<P: Provider>() {}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() {
+    assert_provider::<TestProvider>();
+    let response = TestProvider
+        .dispatch(
+            Context::default(),
+            "Outgoing.Retry".to_string(),
+            std::borrow::Cow::Borrowed(br#"{"tag":"ConnectionTimeout","val":5}"#),
+        )
+        .await
+        .expect("failed to dispatch adjacently tagged variant");
+    assert_eq!(response, br#"{"tag":"ConnectionTimeout","val":10}"#);
+    let response = TestProvider
+        .dispatch(
+            Context::default(),
+            "Outgoing.Retry".to_string(),
+            std::borrow::Cow::Borrowed(br#"{"tag":"ConnectionRefused"}"#),
+        )
+        .await
+        .expect("failed to dispatch adjacently tagged variant without payload");
+    assert_eq!(response, br#"{"tag":"ConnectionRefused"}"#);
+}
@@ -0,0 +1,86 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::{Context, Provider};
+
+// Doc comments in WIT are carried over to the generated traits, methods, structs and fields
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:docs;
+
+            /// Storage of values by key
+            interface store {
+                /// Entry of the store
+                record entry {
+                    /// Key of the entry
+                    key: string,
+                    /// Value of the entry
+                    value: string,
+                }
+
+                /// Reasons for failed operations
+                variant failure {
+                    /// The key does not exist
+                    missing(string),
+                    /// Access to the key was denied
+                    denied,
+                }
+
+                /// Get the entry stored under `key`
+                get: func(key: string) -> result<entry, failure>;
+            }
+
+            /// Notifications about changes
+            interface watcher {
+                /// Called whenever `key` changes
+                on-change: func(key: string);
+            }
+
+            world provider-docs {
+                import store;
+                export watcher;
+            }
+        ",
+        world: "provider-docs",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestDocsStore for TestProvider {
+    async fn get(
+        &self,
+        _ctx: Context,
+        key: String,
+    ) -> ProviderInvocationResult<Result<Entry, Failure>> {
+        Ok(Err(Failure::Missing(key)))
+    }
+}
+
+#[allow(dead_code)]
+async fn notify(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    InvocationHandler::new(ld)
+        .on_change("key".to_string())
+        .await?;
+    Ok(())
+}
+
+fn assert_provider<P: Provider>() {}
+
+fn main() {
+    assert_provider::<TestProvider>();
+}
@@ -0,0 +1,71 @@
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::Context;
+
+// Only exports of the selected world produce `InvocationHandler` methods, so functions of
+// interfaces exported by other worlds in the same package do not clash with them
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: TestProvider,
+    contract: "wasmcloud:test",
+    wit_bindgen_cfg: {
+        inline: "
+            package test:messaging;
+
+            interface consumer {
+                publish: func(subject: string) -> result<_, string>;
+            }
+
+            interface handler {
+                handle-message: func(msg: string) -> result<_, string>;
+            }
+
+            interface legacy-handler {
+                handle-message: func(subject: string, body: list<u8>) -> result<_, string>;
+            }
+
+            world provider-messaging {
+                import consumer;
+                export handler;
+            }
+
+            world provider-messaging-legacy {
+                import consumer;
+                export legacy-handler;
+            }
+        ",
+        world: "provider-messaging",
+    }
+});
+
+struct TestProvider;
+
+#[async_trait::async_trait]
+impl WasmcloudCapabilityProvider for TestProvider {
+    async fn put_link(&self, _ld: &LinkDefinition) -> bool {
+        true
+    }
+
+    async fn delete_link(&self, _actor_id: &str) {}
+
+    async fn shutdown(&self) {}
+}
+
+#[async_trait::async_trait]
+impl TestMessagingConsumer for TestProvider {
+    async fn publish(
+        &self,
+        _ctx: Context,
+        _subject: String,
+    ) -> ProviderInvocationResult<Result<(), String>> {
+        Ok(Ok(()))
+    }
+}
+
+#[allow(dead_code)]
+async fn deliver(ld: &LinkDefinition) -> Result<(), ProviderInvocationError> {
+    let handler = InvocationHandler::new(ld);
+    let _: Result<(), String> = handler.handle_message("hello".to_string()).await?;
+    Ok(())
+}
+
+fn main() {}
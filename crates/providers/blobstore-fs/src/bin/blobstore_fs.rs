@@ -9,6 +9,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some("blobstore-fs-provider".to_string()),
     )?;
 
-    eprintln!("Blobstore FS Provider exiting");
+    tracing::info!("Blobstore FS Provider exiting");
     Ok(())
 }
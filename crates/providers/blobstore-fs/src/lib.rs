@@ -22,12 +22,16 @@ use tracing::{error, info};
 use wasmcloud_provider_sdk::{
     core::LinkDefinition,
     error::{ProviderInvocationError, ProviderInvocationResult},
+    provider_main::get_connection,
     Context,
 };
 
 mod fs_utils;
 use fs_utils::all_dirs;
 
+mod watch;
+use watch::ObjectWatcher;
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: FsProvider,
     contract: "wasmcloud:blobstore",
@@ -53,6 +57,7 @@ pub struct FsProvider {
     config: Arc<RwLock<HashMap<String, FsProviderConfig>>>,
     upload_chunks: Arc<RwLock<HashMap<String, u64>>>, // keep track of the next offset for chunks to be uploaded
     download_chunks: Arc<RwLock<HashMap<ChunkOffsetKey, Chunk>>>,
+    watchers: Arc<RwLock<HashMap<String, ObjectWatcher>>>, // filesystem watchers of links with notifications enabled
 }
 
 impl FsProvider {
@@ -97,6 +102,7 @@ impl Default for FsProvider {
             config: Arc::new(RwLock::new(HashMap::new())),
             upload_chunks: Arc::new(RwLock::new(HashMap::new())),
             download_chunks: Arc::new(RwLock::new(HashMap::new())),
+            watchers: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -305,20 +311,43 @@ impl WasmcloudCapabilityProvider for FsProvider {
         };
 
         // Create directory for the individual actor
-        match create_dir_all(actor_dir.as_path()).await {
-            Ok(()) => true,
-            Err(e) => {
-                error!("Could not create actor directory: {:?}", e);
-                false
+        if let Err(e) = create_dir_all(actor_dir.as_path()).await {
+            error!("Could not create actor directory: {:?}", e);
+            return false;
+        }
+
+        // Notify the actor about changes to its objects, if requested
+        let watch = ld
+            .values
+            .iter()
+            .find(|(key, _)| key == "WATCH")
+            .is_some_and(|(_, value)| value.eq_ignore_ascii_case("true"));
+        if watch {
+            match ObjectWatcher::new(ld.clone(), actor_dir, get_connection().task_supervisor()) {
+                Ok(watcher) => {
+                    self.watchers
+                        .write()
+                        .await
+                        .insert(ld.actor_id.clone(), watcher);
+                }
+                Err(e) => {
+                    error!("Could not watch actor directory: {e}");
+                    return false;
+                }
             }
+        } else if self.watchers.write().await.remove(&ld.actor_id).is_some() {
+            info!("Stopped watching directory of actor {}", ld.actor_id);
         }
+        true
     }
 
     async fn delete_link(&self, actor_id: &str) {
+        self.watchers.write().await.remove(actor_id);
         self.config.write().await.remove(actor_id);
     }
 
     async fn shutdown(&self) {
+        self.watchers.write().await.drain();
         self.config.write().await.drain();
     }
 }
@@ -726,6 +755,12 @@ mod tests {
     use super::FsProvider;
     use std::io::ErrorKind as IoErrorKind;
     use std::path::PathBuf;
+    use wasmcloud_provider_sdk::{
+        deserialize, error::ProviderInvocationError, serialize, testkit::TestHost,
+    };
+
+    const ACTOR_ID: &str = "MBCFOPM6JW2APJLXJD3Z5O4CN7CPYJ2B4FTKLJUR5YR5MITIU7HD3WD5";
+    const OTHER_ACTOR_ID: &str = "MB2ZQB6ROOMAYBO4ZCTFYWN7YIVBWA3MTKZYAQKJMTIHE2ELLRW2E3ZW";
 
     /// Ensure that only safe subpaths are resolved
     #[tokio::test]
@@ -747,4 +782,56 @@ mod tests {
             .unwrap_err();
         assert_eq!(res.kind(), IoErrorKind::PermissionDenied);
     }
+
+    /// Ensure that containers are created below the root set by the link of the actor
+    #[tokio::test]
+    async fn create_container_in_link_root() {
+        let root = std::env::temp_dir().join(format!("blobstore-fs-test-{}", std::process::id()));
+        let host = TestHost::start(FsProvider::default()).await;
+        assert!(host
+            .put_link(host.link_definition(ACTOR_ID, [("ROOT", root.display().to_string())]))
+            .await
+            .unwrap());
+
+        let exists = host
+            .invoke(
+                ACTOR_ID,
+                "Blobstore.ContainerExists",
+                serialize(&"photos").unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(!deserialize::<bool>(&exists).unwrap());
+
+        host.invoke(
+            ACTOR_ID,
+            "Blobstore.CreateContainer",
+            serialize(&"photos").unwrap(),
+        )
+        .await
+        .unwrap();
+        assert!(root.join(ACTOR_ID).join("photos").is_dir());
+        let exists = host
+            .invoke(
+                ACTOR_ID,
+                "Blobstore.ContainerExists",
+                serialize(&"photos").unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(deserialize::<bool>(&exists).unwrap());
+        host.assert_invoked("Blobstore.CreateContainer");
+
+        let err = host
+            .invoke(
+                OTHER_ACTOR_ID,
+                "Blobstore.CreateContainer",
+                serialize(&"photos").unwrap(),
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderInvocationError::NotLinked(_)));
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
 }
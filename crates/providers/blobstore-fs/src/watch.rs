@@ -0,0 +1,382 @@
+//! Notification of linked actors about changes made to their objects on the filesystem
+
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::time::Duration;
+
+use notify::event::{CreateKind, ModifyKind, RemoveKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tokio::task::AbortHandle;
+use tokio::time::Instant;
+use tracing::{debug, error, warn};
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::TaskSupervisor;
+
+use crate::{InvocationHandler, ObjectEvent, ObjectEventKind};
+
+/// Time without further modifications of an object, after which the actor is notified about its
+/// modification. Writing a file usually results in several modify events
+const MODIFY_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Watches the root of a single link and notifies the linked actor about changes to objects
+/// until dropped
+pub(crate) struct ObjectWatcher {
+    _watcher: RecommendedWatcher,
+    task: Option<AbortHandle>,
+}
+
+impl ObjectWatcher {
+    /// Start watching `root`, which contains the containers of the actor linked by `ld`, notifying
+    /// the actor from a task supervised by `supervisor`
+    pub(crate) fn new(
+        ld: LinkDefinition,
+        root: PathBuf,
+        supervisor: &TaskSupervisor,
+    ) -> notify::Result<Self> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |res| {
+            // The receiver is only closed once the watcher is dropped
+            let _ = tx.send(res);
+        })?;
+        watcher.watch(&root, RecursiveMode::Recursive)?;
+        let task = supervisor.spawn(format!("watch-{}", ld.actor_id), async move {
+            let receiver = InvocationHandler::new(&ld);
+            let mut modified = ModifyDebouncer::default();
+            loop {
+                let res = match modified.next_due() {
+                    Some(due) => tokio::select! {
+                        res = rx.recv() => res,
+                        () = tokio::time::sleep_until(due) => {
+                            for event in modified.take_due(Instant::now()) {
+                                notify_actor(&receiver, &ld, event).await;
+                            }
+                            continue;
+                        }
+                    },
+                    None => rx.recv().await,
+                };
+                let Some(res) = res else {
+                    break;
+                };
+                let event = match res {
+                    Ok(event) => event,
+                    Err(e) => {
+                        error!("failed to watch `{}`: {e}", root.display());
+                        continue;
+                    }
+                };
+                for event in object_events(&root, event) {
+                    if let Some(event) = modified.push(event, Instant::now()) {
+                        notify_actor(&receiver, &ld, event).await;
+                    }
+                }
+            }
+        });
+        Ok(Self {
+            _watcher: watcher,
+            task,
+        })
+    }
+}
+
+impl Drop for ObjectWatcher {
+    fn drop(&mut self) {
+        if let Some(task) = &self.task {
+            task.abort();
+        }
+    }
+}
+
+/// Notifies the actor linked by `ld` about `event`
+async fn notify_actor(receiver: &InvocationHandler<'_>, ld: &LinkDefinition, event: ObjectEvent) {
+    debug!(
+        "notifying actor {} about {:?} object {}/{}",
+        ld.actor_id, event.kind, event.container_id, event.object_id
+    );
+    if let Err(e) = receiver.handle_object_event(event).await {
+        warn!(
+            "failed to notify actor {} about object event: {e}",
+            ld.actor_id
+        );
+    }
+}
+
+/// Delays notifications about modified objects until [`MODIFY_DEBOUNCE`] passed without further
+/// modifications, so that the actor is notified once per write
+#[derive(Debug, Default)]
+struct ModifyDebouncer {
+    /// Time at which the actor is notified about each modified object, by container and object ID
+    pending: HashMap<(String, String), Instant>,
+}
+
+impl ModifyDebouncer {
+    /// Returns `event` if the actor is notified about it right away. Modifications are held back
+    /// until they are due, while other events discard pending modifications of their object
+    fn push(&mut self, event: ObjectEvent, now: Instant) -> Option<ObjectEvent> {
+        let key = (event.container_id.clone(), event.object_id.clone());
+        if matches!(event.kind, ObjectEventKind::Modified) {
+            self.pending.insert(key, now + MODIFY_DEBOUNCE);
+            None
+        } else {
+            self.pending.remove(&key);
+            Some(event)
+        }
+    }
+
+    /// Returns the earliest time a held back modification is due
+    fn next_due(&self) -> Option<Instant> {
+        self.pending.values().min().copied()
+    }
+
+    /// Removes and returns the modifications, which are due at `now`
+    fn take_due(&mut self, now: Instant) -> Vec<ObjectEvent> {
+        let mut due = Vec::new();
+        self.pending.retain(|(container_id, object_id), at| {
+            if *at > now {
+                return true;
+            }
+            due.push(ObjectEvent {
+                container_id: container_id.clone(),
+                object_id: object_id.clone(),
+                kind: ObjectEventKind::Modified,
+            });
+            false
+        });
+        due
+    }
+}
+
+/// Translate a filesystem event under `root` into object events. Events on directories and on
+/// files placed directly in `root`, outside of any container, are ignored
+fn object_events(root: &Path, event: Event) -> Vec<ObjectEvent> {
+    let kinds: &[ObjectEventKind] = match event.kind {
+        EventKind::Create(CreateKind::Folder) | EventKind::Remove(RemoveKind::Folder) => &[],
+        EventKind::Create(_) => &[ObjectEventKind::Created],
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => &[ObjectEventKind::Removed],
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => &[ObjectEventKind::Created],
+        // Both the source and the destination path are reported, in that order
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+            &[ObjectEventKind::Removed, ObjectEventKind::Created]
+        }
+        EventKind::Modify(ModifyKind::Metadata(_)) => &[],
+        EventKind::Modify(_) => &[ObjectEventKind::Modified],
+        EventKind::Remove(_) => &[ObjectEventKind::Removed],
+        EventKind::Any | EventKind::Access(_) | EventKind::Other => &[],
+    };
+    event
+        .paths
+        .iter()
+        .zip(kinds.iter().cycle())
+        // Removed paths cannot be inspected anymore, removal of folders is filtered out above
+        .filter(|(path, kind)| matches!(kind, ObjectEventKind::Removed) || !path.is_dir())
+        .filter_map(|(path, kind)| {
+            let (container_id, object_id) = object_selector(root, path)?;
+            Some(ObjectEvent {
+                container_id,
+                object_id,
+                kind: *kind,
+            })
+        })
+        .collect()
+}
+
+/// Split `path` into the container ID and the object ID relative to `root`
+fn object_selector(root: &Path, path: &Path) -> Option<(String, String)> {
+    let mut components = path.strip_prefix(root).ok()?.components().map(|c| match c {
+        Component::Normal(name) => name.to_str(),
+        _ => None,
+    });
+    let container_id = components.next()??.to_string();
+    let object_id = components.collect::<Option<Vec<_>>>()?.join("/");
+    if object_id.is_empty() {
+        return None;
+    }
+    Some((container_id, object_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{DataChange, MetadataKind};
+
+    use super::*;
+
+    fn event(kind: EventKind, paths: &[&str]) -> Event {
+        paths
+            .iter()
+            .fold(Event::new(kind), |event, path| event.add_path(path.into()))
+    }
+
+    fn selectors(events: &[ObjectEvent]) -> Vec<(&str, &str, ObjectEventKind)> {
+        events
+            .iter()
+            .map(|e| (e.container_id.as_str(), e.object_id.as_str(), e.kind))
+            .collect()
+    }
+
+    fn modified(container_id: &str, object_id: &str) -> ObjectEvent {
+        ObjectEvent {
+            container_id: container_id.to_string(),
+            object_id: object_id.to_string(),
+            kind: ObjectEventKind::Modified,
+        }
+    }
+
+    #[test]
+    fn object_selectors_are_relative_to_root() {
+        let root = Path::new("/data/actor");
+        assert_eq!(
+            object_selector(root, Path::new("/data/actor/photos/cat.png")),
+            Some(("photos".to_string(), "cat.png".to_string()))
+        );
+        // Objects in nested directories are identified by their path within the container
+        assert_eq!(
+            object_selector(root, Path::new("/data/actor/photos/2024/01/cat.png")),
+            Some(("photos".to_string(), "2024/01/cat.png".to_string()))
+        );
+        // Containers and files outside of any container are not objects
+        assert_eq!(object_selector(root, Path::new("/data/actor/photos")), None);
+        assert_eq!(object_selector(root, Path::new("/data/actor")), None);
+        // Paths outside of the root are ignored
+        assert_eq!(
+            object_selector(root, Path::new("/data/other/photos/cat.png")),
+            None
+        );
+        assert_eq!(
+            object_selector(root, Path::new("/data/actor/../other/cat.png")),
+            None
+        );
+    }
+
+    #[test]
+    fn filesystem_events_are_translated() {
+        let root = Path::new("/data/actor");
+        let events = object_events(
+            root,
+            event(
+                EventKind::Create(CreateKind::File),
+                &["/data/actor/photos/2024/cat.png"],
+            ),
+        );
+        assert!(matches!(
+            selectors(&events).as_slice(),
+            [("photos", "2024/cat.png", ObjectEventKind::Created)]
+        ));
+        let events = object_events(
+            root,
+            event(
+                EventKind::Modify(ModifyKind::Data(DataChange::Content)),
+                &["/data/actor/photos/cat.png"],
+            ),
+        );
+        assert!(matches!(
+            selectors(&events).as_slice(),
+            [("photos", "cat.png", ObjectEventKind::Modified)]
+        ));
+        // Folders, metadata changes and paths outside of the root do not concern objects
+        for (kind, path) in [
+            (EventKind::Create(CreateKind::Folder), "/data/actor/photos"),
+            (
+                EventKind::Modify(ModifyKind::Metadata(MetadataKind::Any)),
+                "/data/actor/photos/cat.png",
+            ),
+            (
+                EventKind::Remove(RemoveKind::File),
+                "/data/other/photos/cat.png",
+            ),
+            (EventKind::Create(CreateKind::File), "/data/actor/stray.txt"),
+        ] {
+            assert!(object_events(root, event(kind, &[path])).is_empty());
+        }
+    }
+
+    #[test]
+    fn renames_are_translated() {
+        let root = Path::new("/data/actor");
+        let events = object_events(
+            root,
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &[
+                    "/data/actor/photos/cat.png",
+                    "/data/actor/archive/2024/cat.png",
+                ],
+            ),
+        );
+        assert!(matches!(
+            selectors(&events).as_slice(),
+            [
+                ("photos", "cat.png", ObjectEventKind::Removed),
+                ("archive", "2024/cat.png", ObjectEventKind::Created),
+            ]
+        ));
+        // Renames are reported separately on some platforms
+        let events = object_events(
+            root,
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::From)),
+                &["/data/actor/photos/cat.png"],
+            ),
+        );
+        assert!(matches!(
+            selectors(&events).as_slice(),
+            [("photos", "cat.png", ObjectEventKind::Removed)]
+        ));
+        // Moving an object out of the root removes it
+        let events = object_events(
+            root,
+            event(
+                EventKind::Modify(ModifyKind::Name(RenameMode::Both)),
+                &["/data/actor/photos/cat.png", "/tmp/cat.png"],
+            ),
+        );
+        assert!(matches!(
+            selectors(&events).as_slice(),
+            [("photos", "cat.png", ObjectEventKind::Removed)]
+        ));
+    }
+
+    #[test]
+    fn modifications_are_debounced() {
+        let mut debouncer = ModifyDebouncer::default();
+        let start = Instant::now();
+        assert!(debouncer
+            .push(modified("photos", "cat.png"), start)
+            .is_none());
+        assert!(debouncer
+            .push(modified("photos", "cat.png"), start + MODIFY_DEBOUNCE / 2)
+            .is_none());
+        assert!(debouncer
+            .push(modified("photos", "dog.png"), start)
+            .is_none());
+        assert_eq!(debouncer.next_due(), Some(start + MODIFY_DEBOUNCE));
+
+        // Each object is reported once, after it was not modified for the debounce period
+        let due = debouncer.take_due(start + MODIFY_DEBOUNCE);
+        assert!(matches!(
+            selectors(&due).as_slice(),
+            [("photos", "dog.png", ObjectEventKind::Modified)]
+        ));
+        assert_eq!(
+            debouncer.next_due(),
+            Some(start + MODIFY_DEBOUNCE / 2 + MODIFY_DEBOUNCE)
+        );
+        let due = debouncer.take_due(start + 2 * MODIFY_DEBOUNCE);
+        assert!(matches!(
+            selectors(&due).as_slice(),
+            [("photos", "cat.png", ObjectEventKind::Modified)]
+        ));
+        assert_eq!(debouncer.next_due(), None);
+
+        // Other events are reported right away, discarding pending modifications of the object
+        assert!(debouncer
+            .push(modified("photos", "cat.png"), start)
+            .is_none());
+        let removed = ObjectEvent {
+            kind: ObjectEventKind::Removed,
+            ..modified("photos", "cat.png")
+        };
+        assert!(debouncer.push(removed, start).is_some());
+        assert_eq!(debouncer.next_due(), None);
+    }
+}
@@ -9,6 +9,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some("blobstore-s3-provider".to_string()),
     )?;
 
-    eprintln!("Blobstore S3 Provider exiting");
+    tracing::info!("Blobstore S3 Provider exiting");
     Ok(())
 }
@@ -0,0 +1,173 @@
+//! cron capability provider
+//!
+//! Delivers scheduled invocations to linked actors, based on a cron expression or a fixed
+//! interval configured in the link definition
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tracing::{debug, error, info, instrument, warn};
+use wasmcloud_provider_sdk::core::LinkDefinition;
+
+mod schedule;
+use schedule::{Config, MissedPolicy, OverlapPolicy};
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: CronProvider,
+    contract: "wasmcloud:cron",
+    wit_bindgen_cfg: "provider-cron"
+});
+
+/// Upper bound on missed invocations counted at once, to avoid iterating over huge numbers of
+/// fire times of very frequent schedules, e.g. after the host was suspended
+const MAX_COUNTED_MISSED: u32 = 10_000;
+
+/// cron capability provider implementation
+#[derive(Clone, Default)]
+pub struct CronProvider {
+    /// Scheduler tasks of all links, keyed by actor ID
+    schedulers: Arc<RwLock<HashMap<String, JoinHandle<()>>>>,
+}
+
+#[async_trait]
+impl WasmcloudCapabilityProvider for CronProvider {
+    /// Start delivering invocations to the linked actor according to the link's schedule
+    #[instrument(level = "debug", skip_all, fields(actor_id = %ld.actor_id))]
+    async fn put_link(&self, ld: &LinkDefinition) -> bool {
+        let config = match Config::from_values(&ld.values) {
+            Ok(config) => config,
+            Err(e) => {
+                error!(error = %e, "invalid cron link configuration");
+                return false;
+            }
+        };
+        info!(schedule = ?config.schedule, "starting scheduler for actor");
+        let scheduler = tokio::spawn(run_scheduler(ld.clone(), config));
+        if let Some(previous) = self
+            .schedulers
+            .write()
+            .await
+            .insert(ld.actor_id.clone(), scheduler)
+        {
+            previous.abort();
+        }
+        true
+    }
+
+    /// Stop delivering invocations to the actor
+    #[instrument(level = "debug", skip(self))]
+    async fn delete_link(&self, actor_id: &str) {
+        if let Some(scheduler) = self.schedulers.write().await.remove(actor_id) {
+            info!("stopping scheduler for actor");
+            scheduler.abort();
+        }
+    }
+
+    /// Stop all schedulers
+    async fn shutdown(&self) {
+        for (_, scheduler) in self.schedulers.write().await.drain() {
+            scheduler.abort();
+        }
+    }
+}
+
+/// Deliver a single invocation to the actor
+#[instrument(level = "debug", skip(ld, payload), fields(actor_id = %ld.actor_id))]
+async fn invoke(
+    ld: Arc<LinkDefinition>,
+    scheduled_at: DateTime<Utc>,
+    missed: u32,
+    payload: String,
+) {
+    let invocation = ScheduledInvocation {
+        scheduled_at: scheduled_at
+            .timestamp_millis()
+            .try_into()
+            .unwrap_or_default(),
+        missed,
+        payload,
+    };
+    if let Err(e) = InvocationHandler::new(&ld)
+        .handle_scheduled(invocation)
+        .await
+    {
+        warn!(error = %e, "scheduled invocation failed");
+    }
+}
+
+/// Count fire times of `config` after `last` up to and including `now` and return the count
+/// along with the first fire time after `now`
+fn skip_elapsed(
+    config: &Config,
+    last: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> (u32, Option<DateTime<Utc>>) {
+    let mut elapsed = 0;
+    let mut next = config.schedule.next_after(last);
+    while let Some(at) = next {
+        if at > now {
+            break;
+        }
+        if elapsed >= MAX_COUNTED_MISSED {
+            return (elapsed, config.schedule.next_after(now));
+        }
+        elapsed += 1;
+        next = config.schedule.next_after(at);
+    }
+    (elapsed, next)
+}
+
+/// Deliver invocations to the actor linked by `ld` according to `config`, until aborted
+async fn run_scheduler(ld: LinkDefinition, config: Config) {
+    let ld = Arc::new(ld);
+    let mut running: Option<JoinHandle<()>> = None;
+    // Missed invocations, which are yet to be reported
+    let mut missed: u32 = 0;
+    // Whether a catch-up invocation is to be delivered once the running one finishes
+    let mut catch_up = false;
+    let mut next = config.schedule.next_after(Utc::now());
+    loop {
+        let Some(scheduled_at) = next else {
+            info!(actor_id = %ld.actor_id, "schedule has no upcoming invocations, stopping");
+            return;
+        };
+        let until = (scheduled_at - Utc::now()).to_std().unwrap_or_default();
+        tokio::select! {
+            () = tokio::time::sleep(until) => {
+                // Invocations, which were due while the scheduler was not able to run, are missed
+                let (elapsed, upcoming) = skip_elapsed(&config, scheduled_at, Utc::now());
+                next = upcoming;
+                missed = missed.saturating_add(elapsed);
+                if config.overlap == OverlapPolicy::Skip
+                    && running.as_ref().is_some_and(|task| !task.is_finished())
+                {
+                    debug!(actor_id = %ld.actor_id, %scheduled_at, "previous invocation still running, skipping");
+                    missed = missed.saturating_add(1);
+                    catch_up = config.missed == MissedPolicy::FireOnce;
+                    continue;
+                }
+                catch_up = false;
+                running = Some(tokio::spawn(invoke(
+                    Arc::clone(&ld),
+                    scheduled_at,
+                    std::mem::take(&mut missed),
+                    config.payload.clone(),
+                )));
+            }
+            _ = async { running.as_mut().unwrap().await }, if catch_up && running.is_some() => {
+                debug!(actor_id = %ld.actor_id, missed, "delivering catch-up invocation");
+                catch_up = false;
+                running = Some(tokio::spawn(invoke(
+                    Arc::clone(&ld),
+                    Utc::now(),
+                    std::mem::take(&mut missed),
+                    config.payload.clone(),
+                )));
+            }
+        }
+    }
+}
@@ -0,0 +1,199 @@
+//! Parsing of link values into schedules and their policies
+
+use core::str::FromStr;
+use core::time::Duration;
+
+use chrono::{DateTime, Utc};
+
+/// Link value containing a cron expression
+pub(crate) const CRON_KEY: &str = "CRON";
+/// Link value containing a fixed interval, e.g. `30s` or `1h 30m`
+pub(crate) const INTERVAL_KEY: &str = "INTERVAL";
+/// Link value containing the payload delivered with each invocation
+pub(crate) const PAYLOAD_KEY: &str = "PAYLOAD";
+/// Link value containing the [`OverlapPolicy`]
+pub(crate) const OVERLAP_KEY: &str = "OVERLAP";
+/// Link value containing the [`MissedPolicy`]
+pub(crate) const MISSED_KEY: &str = "MISSED";
+
+/// When invocations should be delivered
+#[derive(Clone, Debug)]
+pub(crate) enum Schedule {
+    /// According to a cron expression, evaluated in UTC
+    Cron(Box<cron::Schedule>),
+    /// At a fixed interval, starting when the link is put
+    Interval(Duration),
+}
+
+impl Schedule {
+    /// Return the first time an invocation is scheduled for strictly after `after`, if any
+    pub(crate) fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Self::Cron(schedule) => schedule.after(&after).next(),
+            Self::Interval(interval) => chrono::Duration::from_std(*interval)
+                .ok()
+                .and_then(|interval| after.checked_add_signed(interval)),
+        }
+    }
+}
+
+/// What to do when an invocation is due while the previous one is still running
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum OverlapPolicy {
+    /// Skip the invocation, it is considered missed
+    #[default]
+    Skip,
+    /// Deliver the invocation concurrently with the running one
+    Allow,
+}
+
+impl FromStr for OverlapPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "allow" => Ok(Self::Allow),
+            _ => Err(format!(
+                "invalid overlap policy `{s}`, expected one of `skip` or `allow`"
+            )),
+        }
+    }
+}
+
+/// What to do about invocations, which were missed, because the previous invocation was still
+/// running or the provider was unable to deliver them on time
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum MissedPolicy {
+    /// Drop missed invocations, the next delivered invocation reports how many were missed
+    #[default]
+    Skip,
+    /// Deliver a single catch-up invocation as soon as possible, reporting how many were missed
+    FireOnce,
+}
+
+impl FromStr for MissedPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "skip" => Ok(Self::Skip),
+            "fire-once" => Ok(Self::FireOnce),
+            _ => Err(format!(
+                "invalid missed policy `{s}`, expected one of `skip` or `fire-once`"
+            )),
+        }
+    }
+}
+
+/// Schedule configuration of a single link
+#[derive(Clone, Debug)]
+pub(crate) struct Config {
+    pub(crate) schedule: Schedule,
+    pub(crate) payload: String,
+    pub(crate) overlap: OverlapPolicy,
+    pub(crate) missed: MissedPolicy,
+}
+
+impl Config {
+    /// Parse the configuration from link definition values. Keys are case-insensitive
+    pub(crate) fn from_values(values: &[(String, String)]) -> Result<Self, String> {
+        let get = |key: &str| {
+            values
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.as_str())
+        };
+        let schedule = match (get(CRON_KEY), get(INTERVAL_KEY)) {
+            (Some(expr), None) => cron::Schedule::from_str(expr)
+                .map(|schedule| Schedule::Cron(Box::new(schedule)))
+                .map_err(|e| format!("invalid cron expression `{expr}`: {e}"))?,
+            (None, Some(interval)) => {
+                let interval = humantime::parse_duration(interval)
+                    .map_err(|e| format!("invalid interval `{interval}`: {e}"))?;
+                if interval.is_zero() {
+                    return Err("interval must not be zero".into());
+                }
+                Schedule::Interval(interval)
+            }
+            (Some(_), Some(_)) => {
+                return Err(format!(
+                    "only one of `{CRON_KEY}` or `{INTERVAL_KEY}` may be specified"
+                ))
+            }
+            (None, None) => {
+                return Err(format!(
+                    "one of `{CRON_KEY}` or `{INTERVAL_KEY}` must be specified"
+                ))
+            }
+        };
+        Ok(Self {
+            schedule,
+            payload: get(PAYLOAD_KEY).unwrap_or_default().to_string(),
+            overlap: get(OVERLAP_KEY)
+                .map(OverlapPolicy::from_str)
+                .transpose()?
+                .unwrap_or_default(),
+            missed: get(MISSED_KEY)
+                .map(MissedPolicy::from_str)
+                .transpose()?
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_cron() {
+        let config = Config::from_values(&values(&[
+            ("cron", "0 */5 * * * *"),
+            ("PAYLOAD", "hello"),
+            ("overlap", "Allow"),
+            ("missed", "fire-once"),
+        ]))
+        .expect("valid config");
+        assert!(matches!(config.schedule, Schedule::Cron(_)));
+        assert_eq!(config.payload, "hello");
+        assert_eq!(config.overlap, OverlapPolicy::Allow);
+        assert_eq!(config.missed, MissedPolicy::FireOnce);
+
+        let start = DateTime::parse_from_rfc3339("2023-01-01T00:01:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let next = config.schedule.next_after(start).unwrap();
+        assert_eq!(next.to_rfc3339(), "2023-01-01T00:05:00+00:00");
+    }
+
+    #[test]
+    fn parse_interval() {
+        let config = Config::from_values(&values(&[("INTERVAL", "1m 30s")])).expect("valid config");
+        assert_eq!(config.payload, "");
+        assert_eq!(config.overlap, OverlapPolicy::Skip);
+        assert_eq!(config.missed, MissedPolicy::Skip);
+
+        let start = Utc::now();
+        let next = config.schedule.next_after(start).unwrap();
+        assert_eq!((next - start).num_seconds(), 90);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Config::from_values(&[]).is_err());
+        assert!(
+            Config::from_values(&values(&[("CRON", "* * * * * *"), ("INTERVAL", "1s")])).is_err()
+        );
+        assert!(Config::from_values(&values(&[("CRON", "not cron")])).is_err());
+        assert!(Config::from_values(&values(&[("INTERVAL", "0s")])).is_err());
+        assert!(Config::from_values(&values(&[("INTERVAL", "1s"), ("OVERLAP", "queue")])).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Configuration of the SMTP connection of a link
+
+use core::str::FromStr;
+use core::time::Duration;
+
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, Tokio1Executor};
+
+/// Link value containing the host name of the SMTP server
+pub(crate) const HOST_KEY: &str = "HOST";
+/// Link value containing the port of the SMTP server
+pub(crate) const PORT_KEY: &str = "PORT";
+/// Link value containing the [`TlsMode`]
+pub(crate) const TLS_KEY: &str = "TLS";
+/// Link value containing the user name to authenticate with
+pub(crate) const USERNAME_KEY: &str = "USERNAME";
+/// Link value containing the password to authenticate with
+pub(crate) const PASSWORD_KEY: &str = "PASSWORD";
+/// Link value containing the default sender address
+pub(crate) const FROM_KEY: &str = "FROM";
+/// Link value containing the timeout of SMTP commands, e.g. `30s`
+pub(crate) const TIMEOUT_KEY: &str = "TIMEOUT";
+
+/// Default timeout of SMTP commands
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How the connection to the SMTP server is secured
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum TlsMode {
+    /// Connect using TLS, by default on port 465
+    Implicit,
+    /// Connect in plaintext and upgrade the connection using `STARTTLS`, by default on port 587.
+    /// Sending fails if the server does not support `STARTTLS`
+    #[default]
+    StartTls,
+    /// Do not use TLS, by default on port 25. Only meant for local development and testing
+    None,
+}
+
+impl FromStr for TlsMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "implicit" => Ok(Self::Implicit),
+            "starttls" => Ok(Self::StartTls),
+            "none" => Ok(Self::None),
+            _ => Err(format!(
+                "invalid TLS mode `{s}`, expected one of `implicit`, `starttls` or `none`"
+            )),
+        }
+    }
+}
+
+/// SMTP configuration of a single link
+#[derive(Clone)]
+pub(crate) struct Config {
+    pub(crate) host: String,
+    pub(crate) port: Option<u16>,
+    pub(crate) tls: TlsMode,
+    pub(crate) credentials: Option<Credentials>,
+    pub(crate) from: Option<Mailbox>,
+    pub(crate) timeout: Duration,
+}
+
+impl Config {
+    /// Parse the configuration from link definition values. Keys are case-insensitive
+    pub(crate) fn from_values(values: &[(String, String)]) -> Result<Self, String> {
+        let get = |key: &str| {
+            values
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.as_str())
+        };
+        let host = get(HOST_KEY)
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| format!("`{HOST_KEY}` must be specified"))?
+            .to_string();
+        let port = get(PORT_KEY)
+            .map(|port| {
+                port.parse()
+                    .map_err(|e| format!("invalid port `{port}`: {e}"))
+            })
+            .transpose()?;
+        let tls = get(TLS_KEY)
+            .map(TlsMode::from_str)
+            .transpose()?
+            .unwrap_or_default();
+        let credentials = match (get(USERNAME_KEY), get(PASSWORD_KEY)) {
+            (Some(username), Some(password)) => {
+                Some(Credentials::new(username.into(), password.into()))
+            }
+            (None, None) => None,
+            _ => {
+                return Err(format!(
+                    "`{USERNAME_KEY}` and `{PASSWORD_KEY}` must be specified together"
+                ))
+            }
+        };
+        let from = get(FROM_KEY)
+            .map(|from| {
+                from.parse()
+                    .map_err(|e| format!("invalid sender address `{from}`: {e}"))
+            })
+            .transpose()?;
+        let timeout = get(TIMEOUT_KEY)
+            .map(|timeout| {
+                humantime::parse_duration(timeout)
+                    .map_err(|e| format!("invalid timeout `{timeout}`: {e}"))
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_TIMEOUT);
+        Ok(Self {
+            host,
+            port,
+            tls,
+            credentials,
+            from,
+            timeout,
+        })
+    }
+
+    /// Build a pooled transport sending messages to the configured SMTP server.
+    /// Connections are established lazily, when the first message is sent
+    pub(crate) fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>, String> {
+        let mut builder = match self.tls {
+            TlsMode::Implicit => AsyncSmtpTransport::<Tokio1Executor>::relay(&self.host)
+                .map_err(|e| format!("failed to configure TLS: {e}"))?,
+            TlsMode::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&self.host)
+                .map_err(|e| format!("failed to configure TLS: {e}"))?,
+            TlsMode::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&self.host),
+        };
+        if let Some(port) = self.port {
+            builder = builder.port(port);
+        }
+        if let Some(credentials) = self.credentials.clone() {
+            builder = builder.credentials(credentials);
+        }
+        Ok(builder.timeout(Some(self.timeout)).build())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_config() {
+        let config = Config::from_values(&values(&[
+            ("host", "smtp.example.com"),
+            ("PORT", "2525"),
+            ("tls", "Implicit"),
+            ("username", "user"),
+            ("password", "secret"),
+            ("from", "Notifications <noreply@example.com>"),
+            ("timeout", "10s"),
+        ]))
+        .expect("valid config");
+        assert_eq!(config.host, "smtp.example.com");
+        assert_eq!(config.port, Some(2525));
+        assert_eq!(config.tls, TlsMode::Implicit);
+        assert!(config.credentials.is_some());
+        assert_eq!(
+            config.from.map(|from| from.email.to_string()).as_deref(),
+            Some("noreply@example.com")
+        );
+        assert_eq!(config.timeout, Duration::from_secs(10));
+
+        let config = Config::from_values(&values(&[("HOST", "localhost")])).expect("valid config");
+        assert_eq!(config.port, None);
+        assert_eq!(config.tls, TlsMode::StartTls);
+        assert!(config.credentials.is_none());
+        assert!(config.from.is_none());
+        assert_eq!(config.timeout, DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Config::from_values(&[]).is_err());
+        assert!(Config::from_values(&values(&[("HOST", "localhost"), ("PORT", "smtp")])).is_err());
+        assert!(Config::from_values(&values(&[("HOST", "localhost"), ("TLS", "ssl")])).is_err());
+        assert!(
+            Config::from_values(&values(&[("HOST", "localhost"), ("USERNAME", "user")])).is_err()
+        );
+        assert!(Config::from_values(&values(&[
+            ("HOST", "localhost"),
+            ("FROM", "not an address")
+        ]))
+        .is_err());
+    }
+}
@@ -0,0 +1,286 @@
+//! email-smtp capability provider
+//!
+//! Sends email messages on behalf of linked actors over SMTP, using the server and credentials
+//! configured in the link definition
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment as AttachmentPart, Mailbox, MultiPart, SinglePart};
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, instrument};
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::Context;
+
+mod config;
+use config::Config;
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: EmailSmtpProvider,
+    contract: "wasmcloud:email",
+    wit_bindgen_cfg: "provider-email-smtp"
+});
+
+/// SMTP client of a single link
+#[derive(Clone)]
+struct Sender {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    /// Sender address used for messages, which do not specify one
+    from: Option<Mailbox>,
+}
+
+/// email-smtp capability provider implementation
+#[derive(Clone, Default)]
+pub struct EmailSmtpProvider {
+    /// SMTP clients of all links, keyed by actor ID
+    senders: Arc<RwLock<HashMap<String, Sender>>>,
+}
+
+impl EmailSmtpProvider {
+    /// Retrieve the SMTP client of the actor sending the invocation
+    async fn sender(&self, ctx: &Context) -> ProviderInvocationResult<Sender> {
+        let actor_id = ctx.actor.as_ref().ok_or_else(|| {
+            ProviderInvocationError::Provider("invalid parameter: no actor in request".into())
+        })?;
+        self.senders
+            .read()
+            .await
+            .get(actor_id)
+            .cloned()
+            .ok_or_else(|| {
+                ProviderInvocationError::Provider(format!(
+                    "invalid parameter: actor [{actor_id}] not linked"
+                ))
+            })
+    }
+}
+
+#[async_trait]
+impl WasmcloudCapabilityProvider for EmailSmtpProvider {
+    /// Configure the SMTP client of the linked actor. Connections to the server are established
+    /// when the first message is sent, so an unreachable server does not fail the link
+    #[instrument(level = "debug", skip_all, fields(actor_id = %ld.actor_id))]
+    async fn put_link(&self, ld: &LinkDefinition) -> bool {
+        let (config, transport) = match Config::from_values(&ld.values).and_then(|config| {
+            let transport = config.transport()?;
+            Ok((config, transport))
+        }) {
+            Ok(config) => config,
+            Err(e) => {
+                error!(error = %e, "invalid email-smtp link configuration");
+                return false;
+            }
+        };
+        info!(host = %config.host, tls = ?config.tls, "configured SMTP server for actor");
+        self.senders.write().await.insert(
+            ld.actor_id.clone(),
+            Sender {
+                transport,
+                from: config.from,
+            },
+        );
+        true
+    }
+
+    /// Drop the SMTP client of the actor, closing pooled connections
+    #[instrument(level = "debug", skip(self))]
+    async fn delete_link(&self, actor_id: &str) {
+        self.senders.write().await.remove(actor_id);
+    }
+
+    /// Drop all SMTP clients
+    async fn shutdown(&self) {
+        self.senders.write().await.clear();
+    }
+}
+
+#[async_trait]
+impl WasmcloudEmailSender for EmailSmtpProvider {
+    /// Send a message using the SMTP server configured for the actor
+    #[instrument(level = "debug", skip(self, ctx, message), fields(actor_id = ?ctx.actor, subject = %message.subject))]
+    async fn send(
+        &self,
+        ctx: Context,
+        message: EmailMessage,
+    ) -> ProviderInvocationResult<SendResponse> {
+        let sender = self.sender(&ctx).await?;
+        let message = build_message(message, sender.from)
+            .map_err(|e| ProviderInvocationError::Provider(format!("invalid message: {e}")))?;
+        let message_id = message
+            .headers()
+            .get_raw("Message-ID")
+            .unwrap_or_default()
+            .to_string();
+        let response = sender.transport.send(message).await.map_err(|e| {
+            error!(error = %e, "failed to send message");
+            ProviderInvocationError::Provider(format!("failed to send message: {e}"))
+        })?;
+        let reply = format!(
+            "{} {}",
+            response.code(),
+            response.message().collect::<Vec<_>>().join(" ")
+        );
+        debug!(%message_id, %reply, "message sent");
+        Ok(SendResponse { message_id, reply })
+    }
+}
+
+/// Body of a message, without attachments
+enum Body {
+    /// Either plain text or HTML
+    Single(SinglePart),
+    /// Both plain text and HTML
+    Alternative(MultiPart),
+}
+
+/// Parse an address, which may include a display name
+fn parse_mailbox(address: &str) -> Result<Mailbox, String> {
+    address
+        .parse()
+        .map_err(|e| format!("invalid address `{address}`: {e}"))
+}
+
+/// Build the MIME message to send. `default_from` is used, if the message does not specify a sender
+fn build_message(message: EmailMessage, default_from: Option<Mailbox>) -> Result<Message, String> {
+    let from = match message.sender {
+        Some(sender) => parse_mailbox(&sender)?,
+        None => default_from.ok_or("no sender specified and no default sender configured")?,
+    };
+    if message.to.is_empty() && message.cc.is_empty() && message.bcc.is_empty() {
+        return Err("no recipients specified".into());
+    }
+    let mut builder = Message::builder()
+        .from(from)
+        .subject(message.subject)
+        .message_id(None);
+    for to in &message.to {
+        builder = builder.to(parse_mailbox(to)?);
+    }
+    for cc in &message.cc {
+        builder = builder.cc(parse_mailbox(cc)?);
+    }
+    for bcc in &message.bcc {
+        builder = builder.bcc(parse_mailbox(bcc)?);
+    }
+    if let Some(reply_to) = message.reply_to {
+        builder = builder.reply_to(parse_mailbox(&reply_to)?);
+    }
+
+    let body = match (message.text, message.html) {
+        (Some(text), Some(html)) => {
+            Body::Alternative(MultiPart::alternative_plain_html(text, html))
+        }
+        (text, None) => Body::Single(SinglePart::plain(text.unwrap_or_default())),
+        (None, Some(html)) => Body::Single(SinglePart::html(html)),
+    };
+    let message = if message.attachments.is_empty() {
+        match body {
+            Body::Single(part) => builder.singlepart(part),
+            Body::Alternative(parts) => builder.multipart(parts),
+        }
+    } else {
+        let mut parts = match body {
+            Body::Single(part) => MultiPart::mixed().singlepart(part),
+            Body::Alternative(parts) => MultiPart::mixed().multipart(parts),
+        };
+        for attachment in message.attachments {
+            let content_type = ContentType::parse(&attachment.content_type).map_err(|e| {
+                format!(
+                    "invalid content type `{}` of attachment `{}`: {e}",
+                    attachment.content_type, attachment.filename
+                )
+            })?;
+            parts = parts.singlepart(
+                AttachmentPart::new(attachment.filename).body(attachment.data, content_type),
+            );
+        }
+        builder.multipart(parts)
+    };
+    message.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn message() -> EmailMessage {
+        EmailMessage {
+            sender: None,
+            to: vec!["Jane Doe <jane@example.com>".into()],
+            cc: vec![],
+            bcc: vec![],
+            reply_to: None,
+            subject: "Hello".into(),
+            text: Some("Hello, world".into()),
+            html: None,
+            attachments: vec![],
+        }
+    }
+
+    #[test]
+    fn build_messages() {
+        let default_from: Mailbox = "noreply@example.com".parse().unwrap();
+        assert!(build_message(message(), None).is_err());
+
+        let built = build_message(message(), Some(default_from.clone())).expect("valid message");
+        let formatted = String::from_utf8(built.formatted()).unwrap();
+        assert!(formatted.contains("From: noreply@example.com"));
+        assert!(formatted.contains("Message-ID: "));
+
+        let built = build_message(
+            EmailMessage {
+                sender: Some("sender@example.com".into()),
+                html: Some("<p>Hello, world</p>".into()),
+                attachments: vec![Attachment {
+                    filename: "report.csv".into(),
+                    content_type: "text/csv".into(),
+                    data: b"a,b\n1,2\n".to_vec(),
+                }],
+                ..message()
+            },
+            Some(default_from),
+        )
+        .expect("valid message");
+        let formatted = String::from_utf8(built.formatted()).unwrap();
+        assert!(formatted.contains("From: sender@example.com"));
+        assert!(formatted.contains("multipart/alternative"));
+        assert!(formatted.contains("filename=\"report.csv\""));
+    }
+
+    #[test]
+    fn build_invalid_messages() {
+        let default_from: Mailbox = "noreply@example.com".parse().unwrap();
+        assert!(build_message(
+            EmailMessage {
+                to: vec![],
+                ..message()
+            },
+            Some(default_from.clone())
+        )
+        .is_err());
+        assert!(build_message(
+            EmailMessage {
+                to: vec!["not an address".into()],
+                ..message()
+            },
+            Some(default_from.clone())
+        )
+        .is_err());
+        assert!(build_message(
+            EmailMessage {
+                attachments: vec![Attachment {
+                    filename: "file".into(),
+                    content_type: "invalid".into(),
+                    data: vec![],
+                }],
+                ..message()
+            },
+            Some(default_from)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,14 @@
+use wasmcloud_provider_grpc_client::GrpcClientProvider;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // start_provider initializes the threaded tokio executor,
+    // listens to lattice rpcs, handles actor links,
+    // and returns only when it receives a shutdown message
+    wasmcloud_provider_sdk::start_provider(
+        GrpcClientProvider::default(),
+        Some("grpc-client-provider".to_string()),
+    )?;
+
+    tracing::info!("grpc-client provider exiting");
+    Ok(())
+}
@@ -0,0 +1,220 @@
+//! gRPC client of a single link, sending requests with opaque payloads
+
+use bytes::{Buf, BufMut, Bytes};
+use prost::Message;
+use prost_reflect::{DescriptorPool, DynamicMessage, MethodDescriptor};
+use tonic::codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder};
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::{Ascii, MetadataKey, MetadataMap, MetadataValue};
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+use tonic::{Request, Status};
+
+use crate::config::Config;
+use crate::{GrpcRequest, GrpcResponse, PayloadEncoding};
+
+/// Codec passing already encoded messages through as-is
+#[derive(Clone, Copy, Debug, Default)]
+struct BytesCodec;
+
+impl Codec for BytesCodec {
+    type Encode = Bytes;
+    type Decode = Bytes;
+    type Encoder = Self;
+    type Decoder = Self;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        *self
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        *self
+    }
+}
+
+impl Encoder for BytesCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn encode(&mut self, item: Self::Item, dst: &mut EncodeBuf<'_>) -> Result<(), Self::Error> {
+        dst.put(item);
+        Ok(())
+    }
+}
+
+impl Decoder for BytesCodec {
+    type Item = Bytes;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<Self::Item>, Self::Error> {
+        Ok(Some(src.copy_to_bytes(src.remaining())))
+    }
+}
+
+/// Convert ASCII metadata to key/value pairs, omitting binary metadata
+fn metadata_pairs(metadata: MetadataMap) -> Vec<(String, String)> {
+    metadata
+        .into_headers()
+        .iter()
+        .filter(|(name, _)| !name.as_str().ends_with("-bin"))
+        .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+        .collect()
+}
+
+/// gRPC client of a single link
+#[derive(Clone)]
+pub(crate) struct GrpcClient {
+    channel: Channel,
+    /// Value of the `authorization` metadata sent with every request
+    authorization: Option<MetadataValue<Ascii>>,
+    /// Descriptors used for JSON transcoding
+    descriptors: Option<DescriptorPool>,
+}
+
+impl GrpcClient {
+    /// Create a client for the configured endpoint. Connections are established lazily,
+    /// so that a temporarily unreachable server does not fail the link
+    pub(crate) async fn new(config: &Config) -> Result<Self, String> {
+        let mut endpoint = Endpoint::from_shared(config.endpoint.clone())
+            .map_err(|e| format!("invalid endpoint `{}`: {e}", config.endpoint))?
+            .timeout(config.timeout)
+            .connect_timeout(config.timeout);
+        if endpoint.uri().scheme_str() == Some("https") {
+            let mut tls = ClientTlsConfig::new();
+            if let Some(path) = &config.tls_ca {
+                let ca = tokio::fs::read(path).await.map_err(|e| {
+                    format!("failed to read CA certificate `{}`: {e}", path.display())
+                })?;
+                tls = tls.ca_certificate(Certificate::from_pem(ca));
+            }
+            if let Some(domain) = &config.tls_domain {
+                tls = tls.domain_name(domain);
+            }
+            if let Some((cert_path, key_path)) = &config.tls_identity {
+                let cert = tokio::fs::read(cert_path).await.map_err(|e| {
+                    format!(
+                        "failed to read client certificate `{}`: {e}",
+                        cert_path.display()
+                    )
+                })?;
+                let key = tokio::fs::read(key_path).await.map_err(|e| {
+                    format!("failed to read client key `{}`: {e}", key_path.display())
+                })?;
+                tls = tls.identity(Identity::from_pem(cert, key));
+            }
+            endpoint = endpoint
+                .tls_config(tls)
+                .map_err(|e| format!("invalid TLS configuration: {e}"))?;
+        } else if config.tls_ca.is_some() || config.tls_identity.is_some() {
+            return Err("TLS settings require an `https` endpoint".into());
+        }
+        let authorization = config
+            .token
+            .as_ref()
+            .map(|token| {
+                format!("Bearer {token}")
+                    .parse()
+                    .map_err(|e| format!("invalid token: {e}"))
+            })
+            .transpose()?;
+        let descriptors = match &config.descriptors {
+            Some(path) => {
+                let descriptors = tokio::fs::read(path)
+                    .await
+                    .map_err(|e| format!("failed to read descriptors `{}`: {e}", path.display()))?;
+                Some(
+                    DescriptorPool::decode(descriptors.as_slice())
+                        .map_err(|e| format!("invalid descriptors `{}`: {e}", path.display()))?,
+                )
+            }
+            None => None,
+        };
+        Ok(Self {
+            channel: endpoint.connect_lazy(),
+            authorization,
+            descriptors,
+        })
+    }
+
+    /// Look up the descriptor of a method, used for JSON transcoding
+    fn method(&self, service: &str, method: &str) -> Result<MethodDescriptor, String> {
+        self.descriptors
+            .as_ref()
+            .ok_or("JSON encoding requires descriptors to be configured for the link")?
+            .get_service_by_name(service)
+            .ok_or_else(|| format!("unknown service `{service}`"))?
+            .methods()
+            .find(|m| m.name() == method)
+            .ok_or_else(|| format!("unknown method `{method}` of service `{service}`"))
+    }
+
+    /// Call a unary method. Returns an error if the request could not be sent,
+    /// failed calls are reported in the response
+    pub(crate) async fn call(&self, req: GrpcRequest) -> Result<GrpcResponse, String> {
+        let path = PathAndQuery::try_from(format!("/{}/{}", req.service, req.method))
+            .map_err(|e| format!("invalid service or method name: {e}"))?;
+        let method = match req.encoding {
+            PayloadEncoding::Protobuf => None,
+            PayloadEncoding::Json => Some(self.method(&req.service, &req.method)?),
+        };
+        let payload = match &method {
+            None => Bytes::from(req.payload),
+            Some(method) => {
+                let mut deserializer = serde_json::Deserializer::from_slice(&req.payload);
+                let message = DynamicMessage::deserialize(method.input(), &mut deserializer)
+                    .and_then(|message| deserializer.end().map(|()| message))
+                    .map_err(|e| format!("invalid JSON request: {e}"))?;
+                Bytes::from(message.encode_to_vec())
+            }
+        };
+
+        let mut request = Request::new(payload);
+        let metadata = request.metadata_mut();
+        for (key, value) in req.metadata {
+            let value = value
+                .parse()
+                .map_err(|e| format!("invalid value of metadata `{key}`: {e}"))?;
+            let key = MetadataKey::<Ascii>::from_bytes(key.as_bytes())
+                .map_err(|e| format!("invalid metadata key `{key}`: {e}"))?;
+            metadata.append(key, value);
+        }
+        if let Some(authorization) = &self.authorization {
+            metadata.insert("authorization", authorization.clone());
+        }
+
+        let mut grpc = tonic::client::Grpc::new(self.channel.clone());
+        if let Err(e) = grpc.ready().await {
+            return Ok(status_response(Status::unavailable(format!(
+                "service was not ready: {e}"
+            ))));
+        }
+        match grpc.unary(request, path, BytesCodec).await {
+            Ok(response) => {
+                let (metadata, payload, _) = response.into_parts();
+                let payload = match &method {
+                    None => payload.to_vec(),
+                    Some(method) => DynamicMessage::decode(method.output(), payload)
+                        .map_err(|e| e.to_string())
+                        .and_then(|message| serde_json::to_vec(&message).map_err(|e| e.to_string()))
+                        .map_err(|e| format!("failed to transcode response to JSON: {e}"))?,
+                };
+                Ok(GrpcResponse {
+                    code: 0,
+                    message: String::new(),
+                    payload,
+                    metadata: metadata_pairs(metadata),
+                })
+            }
+            Err(status) => Ok(status_response(status)),
+        }
+    }
+}
+
+/// Build the response of a failed call
+fn status_response(status: Status) -> GrpcResponse {
+    GrpcResponse {
+        code: status.code() as u32,
+        message: status.message().to_string(),
+        payload: Vec::new(),
+        metadata: metadata_pairs(status.metadata().clone()),
+    }
+}
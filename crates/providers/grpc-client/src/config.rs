@@ -0,0 +1,127 @@
+//! Configuration of the gRPC endpoint of a link
+
+use core::time::Duration;
+use std::path::PathBuf;
+
+/// Link value containing the URI of the gRPC server, e.g. `https://api.example.com`
+pub(crate) const ENDPOINT_KEY: &str = "ENDPOINT";
+/// Link value containing the path to a PEM file with the CA certificate(s) of the server
+pub(crate) const TLS_CA_KEY: &str = "TLS_CA";
+/// Link value containing the domain name the server certificate is verified against
+pub(crate) const TLS_DOMAIN_KEY: &str = "TLS_DOMAIN";
+/// Link value containing the path to a PEM file with the client certificate
+pub(crate) const TLS_CERT_KEY: &str = "TLS_CERT";
+/// Link value containing the path to a PEM file with the client private key
+pub(crate) const TLS_KEY_KEY: &str = "TLS_KEY";
+/// Link value containing a bearer token sent with every request
+pub(crate) const TOKEN_KEY: &str = "TOKEN";
+/// Link value containing the path to a protobuf file descriptor set, used for JSON transcoding
+pub(crate) const DESCRIPTORS_KEY: &str = "DESCRIPTORS";
+/// Link value containing the timeout of calls, e.g. `10s`
+pub(crate) const TIMEOUT_KEY: &str = "TIMEOUT";
+
+/// Default timeout of calls
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// gRPC configuration of a single link
+#[derive(Clone)]
+pub(crate) struct Config {
+    pub(crate) endpoint: String,
+    pub(crate) tls_ca: Option<PathBuf>,
+    pub(crate) tls_domain: Option<String>,
+    /// Paths to the client certificate and private key, used for mutual TLS
+    pub(crate) tls_identity: Option<(PathBuf, PathBuf)>,
+    pub(crate) token: Option<String>,
+    pub(crate) descriptors: Option<PathBuf>,
+    pub(crate) timeout: Duration,
+}
+
+impl Config {
+    /// Parse the configuration from link definition values. Keys are case-insensitive
+    pub(crate) fn from_values(values: &[(String, String)]) -> Result<Self, String> {
+        let get = |key: &str| {
+            values
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(key))
+                .map(|(_, v)| v.as_str())
+        };
+        let endpoint = get(ENDPOINT_KEY)
+            .filter(|endpoint| !endpoint.is_empty())
+            .ok_or_else(|| format!("`{ENDPOINT_KEY}` must be specified"))?
+            .to_string();
+        let tls_identity = match (get(TLS_CERT_KEY), get(TLS_KEY_KEY)) {
+            (Some(cert), Some(key)) => Some((cert.into(), key.into())),
+            (None, None) => None,
+            _ => {
+                return Err(format!(
+                    "`{TLS_CERT_KEY}` and `{TLS_KEY_KEY}` must be specified together"
+                ))
+            }
+        };
+        let timeout = get(TIMEOUT_KEY)
+            .map(|timeout| {
+                humantime::parse_duration(timeout)
+                    .map_err(|e| format!("invalid timeout `{timeout}`: {e}"))
+            })
+            .transpose()?
+            .unwrap_or(DEFAULT_TIMEOUT);
+        Ok(Self {
+            endpoint,
+            tls_ca: get(TLS_CA_KEY).map(PathBuf::from),
+            tls_domain: get(TLS_DOMAIN_KEY).map(String::from),
+            tls_identity,
+            token: get(TOKEN_KEY).map(String::from),
+            descriptors: get(DESCRIPTORS_KEY).map(PathBuf::from),
+            timeout,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_config() {
+        let config = Config::from_values(&values(&[
+            ("endpoint", "https://api.example.com"),
+            ("TLS_CERT", "client.pem"),
+            ("tls_key", "client.key"),
+            ("Token", "secret"),
+            ("timeout", "5s"),
+        ]))
+        .expect("valid config");
+        assert_eq!(config.endpoint, "https://api.example.com");
+        assert!(config.tls_identity.is_some());
+        assert_eq!(config.token.as_deref(), Some("secret"));
+        assert_eq!(config.timeout, Duration::from_secs(5));
+
+        let config = Config::from_values(&values(&[("ENDPOINT", "http://localhost:50051")]))
+            .expect("valid config");
+        assert!(config.tls_identity.is_none());
+        assert!(config.descriptors.is_none());
+        assert_eq!(config.timeout, DEFAULT_TIMEOUT);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Config::from_values(&[]).is_err());
+        assert!(Config::from_values(&values(&[
+            ("ENDPOINT", "https://api.example.com"),
+            ("TLS_CERT", "client.pem")
+        ]))
+        .is_err());
+        assert!(Config::from_values(&values(&[
+            ("ENDPOINT", "https://api.example.com"),
+            ("TIMEOUT", "soon")
+        ]))
+        .is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! grpc-client capability provider
+//!
+//! Invokes unary methods of external gRPC services on behalf of linked actors, using the
+//! endpoint, TLS settings and credentials configured in the link definition
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::RwLock;
+use tracing::{debug, error, info, instrument};
+use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::Context;
+
+mod client;
+mod config;
+use client::GrpcClient;
+use config::Config;
+
+wasmcloud_provider_wit_bindgen::generate!({
+    impl_struct: GrpcClientProvider,
+    contract: "wasmcloud:grpc",
+    wit_bindgen_cfg: "provider-grpc-client"
+});
+
+/// grpc-client capability provider implementation
+#[derive(Clone, Default)]
+pub struct GrpcClientProvider {
+    /// Clients of all links, keyed by actor ID
+    clients: Arc<RwLock<HashMap<String, GrpcClient>>>,
+}
+
+impl GrpcClientProvider {
+    /// Retrieve the client of the actor sending the invocation
+    async fn client(&self, ctx: &Context) -> ProviderInvocationResult<GrpcClient> {
+        let actor_id = ctx.actor.as_ref().ok_or_else(|| {
+            ProviderInvocationError::Provider("invalid parameter: no actor in request".into())
+        })?;
+        self.clients
+            .read()
+            .await
+            .get(actor_id)
+            .cloned()
+            .ok_or_else(|| {
+                ProviderInvocationError::Provider(format!(
+                    "invalid parameter: actor [{actor_id}] not linked"
+                ))
+            })
+    }
+}
+
+#[async_trait]
+impl WasmcloudCapabilityProvider for GrpcClientProvider {
+    /// Configure the client of the linked actor
+    #[instrument(level = "debug", skip_all, fields(actor_id = %ld.actor_id))]
+    async fn put_link(&self, ld: &LinkDefinition) -> bool {
+        let config = match Config::from_values(&ld.values) {
+            Ok(config) => config,
+            Err(e) => {
+                error!(error = %e, "invalid grpc-client link configuration");
+                return false;
+            }
+        };
+        let client = match GrpcClient::new(&config).await {
+            Ok(client) => client,
+            Err(e) => {
+                error!(error = %e, endpoint = %config.endpoint, "failed to create gRPC client");
+                return false;
+            }
+        };
+        info!(endpoint = %config.endpoint, "configured gRPC endpoint for actor");
+        self.clients
+            .write()
+            .await
+            .insert(ld.actor_id.clone(), client);
+        true
+    }
+
+    /// Drop the client of the actor, closing its connection
+    #[instrument(level = "debug", skip(self))]
+    async fn delete_link(&self, actor_id: &str) {
+        self.clients.write().await.remove(actor_id);
+    }
+
+    /// Drop all clients
+    async fn shutdown(&self) {
+        self.clients.write().await.clear();
+    }
+}
+
+#[async_trait]
+impl WasmcloudGrpcClient for GrpcClientProvider {
+    /// Call a unary method of the gRPC service configured for the actor
+    #[instrument(level = "debug", skip(self, ctx, request), fields(actor_id = ?ctx.actor, service = %request.service, method = %request.method))]
+    async fn call(
+        &self,
+        ctx: Context,
+        request: GrpcRequest,
+    ) -> ProviderInvocationResult<GrpcResponse> {
+        let client = self.client(&ctx).await?;
+        let response = client
+            .call(request)
+            .await
+            .map_err(ProviderInvocationError::Provider)?;
+        debug!(code = response.code, "gRPC call completed");
+        Ok(response)
+    }
+}
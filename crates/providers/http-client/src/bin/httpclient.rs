@@ -5,10 +5,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // listens to lattice rpcs, handles actor links,
     // and returns only when it receives a shutdown message
     wasmcloud_provider_sdk::start_provider(
-        HttpClientProvider{},
+        HttpClientProvider {},
         Some("http-client-provider".to_string()),
     )?;
 
-    eprintln!("HttpClient provider exiting");
+    tracing::info!("HttpClient provider exiting");
     Ok(())
 }
@@ -12,6 +12,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some("http-server-provider".to_string()),
     )?;
 
-    eprintln!("HttpServer provider exiting");
+    tracing::info!("HttpServer provider exiting");
     Ok(())
 }
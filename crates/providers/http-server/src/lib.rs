@@ -17,6 +17,8 @@
 //!   - logging level
 //!   - TLS
 //!   - Cors
+//!   - request/response header manipulation and path rewrites
+//!   - streaming of request and response bodies
 //! - Flexible confiuration loading: from host, or from local toml or json file.
 //! - Fully asynchronous, using tokio lightweight "green" threads
 //! - Thread pool (for managing a pool of OS threads). The default
@@ -32,27 +34,36 @@
 //! by the all of the server green threads.
 //!
 
+use std::net::SocketAddr;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 use flume::{bounded, Receiver, Sender};
-use futures::Future;
+use futures::{Future, Stream, StreamExt, TryStreamExt};
 use http::HeaderMap;
 use serde::{Deserialize, Serialize};
-use tokio::task::JoinHandle;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::task::{AbortHandle, JoinHandle};
+use tokio_util::io::StreamReader;
 use tracing::{debug, error, info, instrument, trace, warn, Instrument};
+use uuid::Uuid;
+use warp::hyper::Body;
 use warp::path::FullPath;
 use warp::Filter;
 
 use wasmcloud_provider_sdk::core::{LinkDefinition, WasmCloudEntity};
 use wasmcloud_provider_sdk::error::{InvocationError, ProviderInvocationError};
+use wasmcloud_provider_sdk::provider_main::get_connection;
 
 mod hashmap_ci;
 pub(crate) use hashmap_ci::make_case_insensitive;
 
+mod rewrite;
+use rewrite::Rewriter;
+
 mod settings;
 pub use settings::{load_settings, ServiceSettings, CONTENT_LEN_LIMIT, DEFAULT_MAX_CONTENT_LEN};
 
@@ -90,7 +101,7 @@ impl WasmcloudCapabilityProvider for HttpServerProvider {
         let settings = match load_settings(&ld.values) {
             Ok(s) => s,
             Err(e) => {
-                error!(%e, ?ld, "httpserver failed to load settings for actor");
+                error!(%e, ld = ?get_connection().redactor().link(ld), "httpserver failed to load settings for actor");
                 return false;
             }
         };
@@ -98,7 +109,7 @@ impl WasmcloudCapabilityProvider for HttpServerProvider {
         // Start a server instance that calls the given actor
         let http_server = HttpServerCore::new(settings.clone(), call_actor);
         if let Err(e) = http_server.start(ld).await {
-            error!(%e, ?ld, "httpserver failed to start listener for actor");
+            error!(%e, ld = ?get_connection().redactor().link(ld), "httpserver failed to start listener for actor");
             return false;
         }
 
@@ -129,6 +140,10 @@ impl WasmcloudCapabilityProvider for HttpServerProvider {
 
 const HANDLE_REQUEST_METHOD: &str = "HttpServer.HandleRequest";
 
+/// Time the request body is still served for after the actor responded with a complete response
+/// body, since actors may respond before reading the whole request body
+const REQUEST_BODY_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct HttpRequest {
@@ -138,6 +153,9 @@ pub struct HttpRequest {
     pub header: ::std::collections::HashMap<String, Vec<String>>,
     #[serde(with = "::serde_bytes")]
     pub body: Vec<u8>,
+    /// ID of the body stream the actor pulls the request body from, instead of `body`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_stream: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -147,6 +165,9 @@ pub struct HttpResponse {
     pub header: ::std::collections::HashMap<String, Vec<String>>,
     #[serde(with = "::serde_bytes")]
     pub body: Vec<u8>,
+    /// ID of the body stream the actor pushes the response body to, instead of `body`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body_stream: Option<String>,
 }
 
 pub struct Server<'a> {
@@ -215,6 +236,7 @@ async fn call_actor(
                 status_code: 503,
                 body: Default::default(),
                 header: Default::default(),
+                body_stream: None,
             })
         }
 
@@ -235,6 +257,70 @@ async fn call_actor(
     }
 }
 
+/// Adapt a warp request body stream into an [`AsyncRead`]
+fn body_reader(
+    body: impl Stream<Item = Result<impl Buf, warp::Error>> + Send + 'static,
+) -> impl AsyncRead + Send + 'static {
+    StreamReader::new(
+        body.map_ok(|mut buf| buf.copy_to_bytes(buf.remaining()))
+            .map_err(std::io::Error::other),
+    )
+}
+
+/// Start serving the request body `body` to the actor and receiving the response body from it.
+/// Returns the ID of the body stream, the serving task and the response body stream.
+async fn start_body_streams(
+    body: impl AsyncRead + Send + Unpin + 'static,
+) -> anyhow::Result<(
+    String,
+    JoinHandle<anyhow::Result<()>>,
+    impl Stream<Item = anyhow::Result<Bytes>> + Send + 'static,
+)> {
+    let endpoint = get_connection().body_stream_endpoint();
+    let stream_id = Uuid::new_v4().to_string();
+    let receive = endpoint.receive(&stream_id).await?;
+    let serve = endpoint.serve(&stream_id, body).await?;
+    Ok((stream_id, serve, receive))
+}
+
+/// Aborts serving the request body once dropped
+struct AbortOnDrop(AbortHandle);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Returns the response body stream `receive`, which stops serving the request body with `serve`
+/// once it ends or is dropped. The actor may read the request body until it is done writing the
+/// response body, as an echo handler does
+fn stream_response_body(
+    serve: JoinHandle<anyhow::Result<()>>,
+    receive: impl Stream<Item = anyhow::Result<Bytes>> + Send + 'static,
+) -> Body {
+    let serving = AbortOnDrop(serve.abort_handle());
+    Body::wrap_stream(receive.map(move |frame| {
+        let _ = &serving;
+        frame
+    }))
+}
+
+/// Lets `serve` finish serving the request body in the background after the actor responded,
+/// aborting it if it does not complete within [`REQUEST_BODY_GRACE_PERIOD`]
+fn finish_serving(serve: JoinHandle<anyhow::Result<()>>) {
+    tokio::spawn(async move {
+        let serving = AbortOnDrop(serve.abort_handle());
+        match tokio::time::timeout(REQUEST_BODY_GRACE_PERIOD, serve).await {
+            Ok(Ok(Ok(()))) => {}
+            Ok(Ok(Err(e))) => debug!(error = %e, "request body was not served completely"),
+            Ok(Err(e)) => error!(error = %e, "request body serving task failed"),
+            Err(_) => debug!("actor did not read the request body within the grace period"),
+        }
+        drop(serving);
+    });
+}
+
 //////////
 // Util //
 //////////
@@ -359,6 +445,9 @@ impl HttpServerCore {
             .timeout_ms
             .map(std::time::Duration::from_millis);
 
+        let rewriter = Arc::new(Rewriter::new(&self.settings.headers)?);
+        let tls = self.settings.tls.is_set();
+
         let ld = Arc::new(ld.clone());
         let linkdefs = ld.clone();
         let trace_ld = ld.clone();
@@ -366,35 +455,66 @@ impl HttpServerCore {
         let route = warp::any()
             .and(warp::header::headers_cloned())
             .and(warp::method())
-            .and(warp::body::bytes())
+            .and(warp::body::stream())
             .and(warp::path::full())
             .and(opt_raw_query())
+            .and(warp::addr::remote())
             .and_then(
                 move |
-                      headers: HeaderMap,
+                      mut headers: HeaderMap,
                       method: http::method::Method,
-                      body: Bytes,
+                      body,
                       path: FullPath,
-                      query: String| {
+                      query: String,
+                      remote_addr: Option<SocketAddr>| {
                     let span = tracing::debug_span!("http request", %method, path = %path.as_str(), %query);
                     let ld = linkdefs.clone();
                     let arc_inner = arc_inner.clone();
+                    let rewriter = rewriter.clone();
                     async move{
                         if let Some(readonly_mode) = arc_inner.settings.readonly_mode{
                             if readonly_mode && method!= http::method::Method::GET && method!= http::method::Method::HEAD {
                                 debug!("Cannot use other methods in Read Only Mode");
                                 // If this fails it is developer error, so unwrap is okay
-                                let resp = http::Response::builder().status(http::StatusCode::METHOD_NOT_ALLOWED).body(Vec::with_capacity(0)).unwrap();
+                                let resp = http::Response::builder().status(http::StatusCode::METHOD_NOT_ALLOWED).body(Body::empty()).unwrap();
                                 return Ok::<_, warp::Rejection>(resp)
                             }
                         }
+                        let body = Box::pin(body_reader(body));
+                        rewriter.rewrite_request(&mut headers, remote_addr, tls);
                         let hmap = convert_request_headers(&headers);
-                        let req = HttpRequest {
-                            body: Vec::from(body),
+                        let mut req = HttpRequest {
+                            body: Vec::default(),
                             header: hmap,
                             method: method.as_str().to_ascii_uppercase(),
-                            path: path.as_str().to_string(),
+                            path: rewriter.rewrite_path(path.as_str()),
                             query_string: query,
+                            body_stream: None,
+                        };
+                        // When streaming, the request body is served to the actor as it reads it
+                        // and the response body is received as the actor writes it. Both
+                        // subscriptions are established before the actor is called.
+                        let streams = if arc_inner.settings.stream_body.unwrap_or_default() {
+                            match start_body_streams(body).await {
+                                Ok((stream_id, serve, receive)) => {
+                                    req.body_stream = Some(stream_id);
+                                    Some((serve, receive))
+                                }
+                                Err(e) => {
+                                    error!(error = %e, "failed to set up body streams");
+                                    let resp = http::Response::builder().status(http::StatusCode::INTERNAL_SERVER_ERROR).body(Body::empty()).unwrap();
+                                    return Ok::<_, warp::Rejection>(resp)
+                                }
+                            }
+                        } else {
+                            let mut buf = Vec::new();
+                            if let Err(e) = body.read_to_end(&mut buf).await {
+                                error!(error = %e, "failed to read request body");
+                                let resp = http::Response::builder().status(http::StatusCode::BAD_REQUEST).body(Body::empty()).unwrap();
+                                return Ok::<_, warp::Rejection>(resp)
+                            }
+                            req.body = buf;
+                            None
                         };
                         trace!(
                             ?req,
@@ -411,6 +531,7 @@ impl HttpServerCore {
                                     status_code: http::StatusCode::INTERNAL_SERVER_ERROR.as_u16(),
                                     body: Default::default(),
                                     header: Default::default(),
+                                    body_stream: None,
                                 }
                             }
                         };
@@ -425,6 +546,14 @@ impl HttpServerCore {
                                 http::StatusCode::INTERNAL_SERVER_ERROR
                             }
                         };
+                        let body = match (streams, response.body_stream) {
+                            (Some((serve, receive)), Some(_)) => stream_response_body(serve, receive),
+                            (Some((serve, _)), None) => {
+                                finish_serving(serve);
+                                Body::from(response.body)
+                            }
+                            (None, _) => Body::from(response.body),
+                        };
                         let http_builder = http::Response::builder()
                         .status(status);
                         let http_builder = if let Some(cache_control_header) = arc_inner.settings.cache_control.as_ref(){
@@ -435,8 +564,9 @@ impl HttpServerCore {
                             http_builder
                         };
                         // Unwrapping here because validation takes place for the linkdef
-                        let mut http_response = http_builder.body(response.body).unwrap();
+                        let mut http_response = http_builder.body(body).unwrap();
                         convert_response_headers(response.header, http_response.headers_mut());
+                        rewriter.rewrite_response(http_response.headers_mut());
                         Ok::<_, warp::Rejection>(http_response)
                     }.instrument(span)
                 },
@@ -0,0 +1,253 @@
+//! Header manipulation and path rewriting, as configured by [`Headers`] settings
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+
+use http::header::{HeaderName, HeaderValue, HOST};
+use http::HeaderMap;
+use regex::Regex;
+
+use crate::settings::{HeaderRules, Headers};
+use crate::HttpServerError;
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_FORWARDED_HOST: &str = "x-forwarded-host";
+const X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+
+/// Parsed [`HeaderRules`]
+#[derive(Debug, Default)]
+struct Rules {
+    remove: Vec<HeaderName>,
+    set: Vec<(HeaderName, HeaderValue)>,
+    add: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl Rules {
+    fn new(rules: &HeaderRules) -> Result<Self, HttpServerError> {
+        let remove = rules
+            .remove
+            .iter()
+            .flatten()
+            .map(|name| parse_name(name))
+            .collect::<Result<_, _>>()?;
+        let set = rules
+            .set
+            .iter()
+            .flatten()
+            .map(|(name, value)| parse_header(name, value))
+            .collect::<Result<_, _>>()?;
+        let add = rules
+            .add
+            .iter()
+            .flatten()
+            .map(|(name, value)| parse_header(name, value))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { remove, set, add })
+    }
+
+    fn apply(&self, headers: &mut HeaderMap) {
+        for name in &self.remove {
+            headers.remove(name);
+        }
+        for (name, value) in &self.set {
+            headers.insert(name, value.clone());
+        }
+        for (name, value) in &self.add {
+            headers.append(name, value.clone());
+        }
+    }
+}
+
+fn parse_name(name: &str) -> Result<HeaderName, HttpServerError> {
+    HeaderName::from_str(name).map_err(|e| {
+        HttpServerError::InvalidParameter(format!("invalid header name '{name}': {e}"))
+    })
+}
+
+fn parse_header(name: &str, value: &str) -> Result<(HeaderName, HeaderValue), HttpServerError> {
+    let value = HeaderValue::from_str(value).map_err(|e| {
+        HttpServerError::InvalidParameter(format!("invalid value for header '{name}': {e}"))
+    })?;
+    Ok((parse_name(name)?, value))
+}
+
+/// Applies the header and path rules of a single link
+#[derive(Debug, Default)]
+pub(crate) struct Rewriter {
+    request: Rules,
+    response: Rules,
+    path_rewrites: Vec<(Regex, String)>,
+    forwarded: bool,
+}
+
+impl Rewriter {
+    /// Parse the rules in `settings`
+    pub(crate) fn new(settings: &Headers) -> Result<Self, HttpServerError> {
+        let request = settings
+            .request
+            .as_ref()
+            .map(Rules::new)
+            .transpose()?
+            .unwrap_or_default();
+        let response = settings
+            .response
+            .as_ref()
+            .map(Rules::new)
+            .transpose()?
+            .unwrap_or_default();
+        let path_rewrites = settings
+            .path_rewrites
+            .iter()
+            .flatten()
+            .map(|rewrite| {
+                let pattern = Regex::new(&rewrite.pattern).map_err(|e| {
+                    HttpServerError::InvalidParameter(format!(
+                        "invalid path rewrite pattern '{}': {e}",
+                        rewrite.pattern
+                    ))
+                })?;
+                Ok((pattern, rewrite.replacement.clone()))
+            })
+            .collect::<Result<_, HttpServerError>>()?;
+        Ok(Self {
+            request,
+            response,
+            path_rewrites,
+            forwarded: settings.forwarded.unwrap_or(false),
+        })
+    }
+
+    /// Apply all path rewrites to `path`, in order
+    pub(crate) fn rewrite_path(&self, path: &str) -> String {
+        self.path_rewrites
+            .iter()
+            .fold(path.to_string(), |path, (pattern, replacement)| {
+                pattern.replace(&path, replacement.as_str()).into_owned()
+            })
+    }
+
+    /// Apply request rules to `headers` of a request received from `remote_addr`
+    pub(crate) fn rewrite_request(
+        &self,
+        headers: &mut HeaderMap,
+        remote_addr: Option<SocketAddr>,
+        tls: bool,
+    ) {
+        if self.forwarded {
+            if let Some(addr) = remote_addr {
+                let ip = addr.ip().to_string();
+                let forwarded_for = match headers.get(X_FORWARDED_FOR).map(HeaderValue::to_str) {
+                    Some(Ok(prev)) => format!("{prev}, {ip}"),
+                    _ => ip,
+                };
+                if let Ok(value) = HeaderValue::from_str(&forwarded_for) {
+                    headers.insert(X_FORWARDED_FOR, value);
+                }
+            }
+            if let Some(host) = headers.get(HOST).cloned() {
+                headers.insert(X_FORWARDED_HOST, host);
+            }
+            headers.insert(
+                X_FORWARDED_PROTO,
+                HeaderValue::from_static(if tls { "https" } else { "http" }),
+            );
+        }
+        self.request.apply(headers);
+    }
+
+    /// Apply response rules to `headers`
+    pub(crate) fn rewrite_response(&self, headers: &mut HeaderMap) {
+        self.response.apply(headers);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::settings::PathRewrite;
+
+    #[test]
+    fn rewrite_headers() {
+        let rewriter = Rewriter::new(&Headers {
+            request: Some(HeaderRules {
+                remove: Some(vec!["cookie".to_string()]),
+                set: None,
+                add: Some(HashMap::from([(
+                    "x-custom".to_string(),
+                    "added".to_string(),
+                )])),
+            }),
+            response: Some(HeaderRules {
+                remove: Some(vec!["server".to_string()]),
+                set: Some(HashMap::from([(
+                    "strict-transport-security".to_string(),
+                    "max-age=63072000".to_string(),
+                )])),
+                add: None,
+            }),
+            path_rewrites: None,
+            forwarded: Some(true),
+        })
+        .expect("valid rules");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("cookie", HeaderValue::from_static("secret"));
+        headers.insert("x-custom", HeaderValue::from_static("original"));
+        headers.insert(HOST, HeaderValue::from_static("example.com"));
+        headers.insert(X_FORWARDED_FOR, HeaderValue::from_static("10.0.0.1"));
+        rewriter.rewrite_request(&mut headers, Some(([127, 0, 0, 1], 8000).into()), true);
+        assert!(headers.get("cookie").is_none());
+        assert_eq!(headers.get_all("x-custom").iter().count(), 2);
+        assert_eq!(headers[X_FORWARDED_FOR], "10.0.0.1, 127.0.0.1");
+        assert_eq!(headers[X_FORWARDED_HOST], "example.com");
+        assert_eq!(headers[X_FORWARDED_PROTO], "https");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("server", HeaderValue::from_static("actor"));
+        rewriter.rewrite_response(&mut headers);
+        assert!(headers.get("server").is_none());
+        assert_eq!(headers["strict-transport-security"], "max-age=63072000");
+    }
+
+    #[test]
+    fn rewrite_path() {
+        let rewriter = Rewriter::new(&Headers {
+            path_rewrites: Some(vec![
+                PathRewrite {
+                    pattern: "^/api/v1/(.*)$".to_string(),
+                    replacement: "/$1".to_string(),
+                },
+                PathRewrite {
+                    pattern: "^/users/(?P<id>[0-9]+)$".to_string(),
+                    replacement: "/user/${id}".to_string(),
+                },
+            ]),
+            ..Default::default()
+        })
+        .expect("valid rules");
+        assert_eq!(rewriter.rewrite_path("/api/v1/users/42"), "/user/42");
+        assert_eq!(rewriter.rewrite_path("/other"), "/other");
+    }
+
+    #[test]
+    fn invalid_rules() {
+        assert!(Rewriter::new(&Headers {
+            request: Some(HeaderRules {
+                remove: Some(vec!["invalid name".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .is_err());
+        assert!(Rewriter::new(&Headers {
+            path_rewrites: Some(vec![PathRewrite {
+                pattern: "(".to_string(),
+                replacement: String::new(),
+            }]),
+            ..Default::default()
+        })
+        .is_err());
+    }
+}
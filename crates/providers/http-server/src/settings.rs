@@ -74,6 +74,10 @@ pub struct ServiceSettings {
     /// Flag for read only mode
     pub readonly_mode: Option<bool>,
 
+    /// Flag for streaming request and response bodies to and from the actor,
+    /// instead of buffering them in the provider
+    pub stream_body: Option<bool>,
+
     /// Max content length. Default "10m" (10MiB = 10485760 bytes)
     /// Can be overridden by link def value max_content_len
     /// Accepts number (bytes), or number with suffix 'k', 'm', or 'g', (upper or lower case)
@@ -85,6 +89,10 @@ pub struct ServiceSettings {
     /// The value may not be higher than i32::MAX
     pub max_content_len: Option<String>,
 
+    /// header manipulation and path rewrite rules
+    #[serde(default)]
+    pub headers: Headers,
+
     /// capture any other configuration values
     #[serde(flatten)]
     extra: HashMap<String, serde_json::Value>,
@@ -100,7 +108,9 @@ impl Default for ServiceSettings {
             timeout_ms: None,
             cache_control: None,
             readonly_mode: Some(false),
+            stream_body: Some(false),
             max_content_len: Some(DEFAULT_MAX_CONTENT_LEN.to_string()),
+            headers: Headers::default(),
             extra: Default::default(),
         }
     }
@@ -149,10 +159,18 @@ impl ServiceSettings {
 
     /// Merge settings from other into self
     fn merge(&mut self, other: ServiceSettings) {
-        merge!(self, other, address, cache_control, readonly_mode);
+        merge!(
+            self,
+            other,
+            address,
+            cache_control,
+            readonly_mode,
+            stream_body
+        );
         self.tls.merge(other.tls);
         self.cors.merge(other.cors);
         self.log.merge(other.log);
+        self.headers.merge(other.headers);
     }
 
     /// perform additional validation checks on settings.
@@ -278,6 +296,11 @@ pub fn load_settings(values: &[(String, String)]) -> Result<ServiceSettings, Htt
         settings.readonly_mode = Some(readonly_mode.to_string().parse().unwrap_or(false));
     }
 
+    // accept body streaming flag
+    if let Some(stream_body) = values.get("stream_body") {
+        settings.stream_body = Some(stream_body.to_string().parse().unwrap_or(false));
+    }
+
     settings.validate()?;
     Ok(settings)
 }
@@ -503,6 +526,52 @@ impl Default for ExposedHeaders {
     }
 }
 
+/// Header manipulation and path rewrite rules, applied to every request and response
+/// of the link
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Headers {
+    /// rules applied to request headers before the request is sent to the actor
+    pub request: Option<HeaderRules>,
+
+    /// rules applied to response headers before the response is returned to the http client
+    pub response: Option<HeaderRules>,
+
+    /// regex rewrites of the request path, applied in order before the request is sent
+    /// to the actor
+    pub path_rewrites: Option<Vec<PathRewrite>>,
+
+    /// if true, `X-Forwarded-For`, `X-Forwarded-Host` and `X-Forwarded-Proto` request headers
+    /// are added, describing the connection of the http client
+    pub forwarded: Option<bool>,
+}
+
+impl Headers {
+    fn merge(&mut self, other: Headers) {
+        merge!(self, other, request, response, path_rewrites, forwarded);
+    }
+}
+
+/// Header manipulation rules. Headers are removed first, then set, then added
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HeaderRules {
+    /// names of headers to remove
+    pub remove: Option<Vec<String>>,
+
+    /// headers to set, replacing any existing values
+    pub set: Option<HashMap<String, String>>,
+
+    /// headers to add, in addition to any existing values
+    pub add: Option<HashMap<String, String>>,
+}
+
+/// Rewrite of the request path matching `pattern` to `replacement`.
+/// `replacement` may refer to capture groups of the pattern, e.g. `$1` or `${name}`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PathRewrite {
+    pub pattern: String,
+    pub replacement: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
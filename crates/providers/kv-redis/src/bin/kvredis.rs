@@ -9,19 +9,22 @@
 //!
 //!
 use std::collections::HashMap;
+use std::fmt::Write;
 use std::ops::DerefMut;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
 
 use async_trait::async_trait;
 use redis::aio::ConnectionManager;
-use redis::FromRedisValue;
+use redis::{ErrorKind, FromRedisValue, Script, Value};
 use serde::Deserialize;
 use tokio::sync::RwLock;
-use tracing::{info, instrument, warn};
+use tracing::{debug, info, instrument, warn};
 use wasmcloud_provider_sdk::core::LinkDefinition;
 use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
-use wasmcloud_provider_sdk::provider_main::start_provider;
-use wasmcloud_provider_sdk::{load_host_data, Context};
+use wasmcloud_provider_sdk::metrics::MetricsCollector;
+use wasmcloud_provider_sdk::provider_main::{get_connection, start_provider_with_config};
+use wasmcloud_provider_sdk::{load_host_data, ConnectionConfig, Context};
 
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KvRedisProvider,
@@ -31,6 +34,26 @@ wasmcloud_provider_wit_bindgen::generate!({
 
 const REDIS_URL_KEY: &str = "URL";
 const DEFAULT_CONNECT_URL: &str = "redis://127.0.0.1:6379/";
+/// Number of keys `get-keys` requests per `SCAN` iteration
+const SCAN_COUNT: usize = 1000;
+
+/// Lua script executing a single command of a pipelined batch. A failure of the command is
+/// returned as `{0, error}` instead of an error reply, which would fail the whole pipeline.
+/// Successful commands return `{1, reply}`. Invoked by its hash, see [`pcall_script`]
+const PCALL_SCRIPT: &str = r#"local reply = redis.pcall(ARGV[1], KEYS[1], unpack(ARGV, 2))
+if type(reply) == "table" and reply.err then
+    return {0, reply.err}
+end
+return {1, reply}"#;
+
+/// Inclusive upper bounds of the batch size histogram buckets
+const BATCH_SIZE_BUCKETS: [usize; 8] = [1, 2, 5, 10, 25, 50, 100, 250];
+
+/// Returns [`PCALL_SCRIPT`], along with its hash
+fn pcall_script() -> &'static Script {
+    static SCRIPT: OnceLock<Script> = OnceLock::new();
+    SCRIPT.get_or_init(|| Script::new(PCALL_SCRIPT))
+}
 
 #[derive(Deserialize)]
 struct KvRedisConfig {
@@ -61,12 +84,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         DEFAULT_CONNECT_URL.to_string()
     };
 
-    start_provider(
-        KvRedisProvider::new(&default_connect_url),
+    let provider = KvRedisProvider::new(&default_connect_url);
+    let batch_metrics = provider.batch_metrics.clone();
+    start_provider_with_config(
+        provider,
         Some("kv-redis-provider".to_string()),
+        ConnectionConfig::default().with_metrics_collector(batch_metrics),
     )?;
 
-    eprintln!("KVRedis provider exiting");
+    info!("KVRedis provider exiting");
     Ok(())
 }
 
@@ -77,6 +103,67 @@ struct KvRedisProvider {
     actors: Arc<RwLock<HashMap<String, RwLock<ConnectionManager>>>>,
     // Default connection URL for actors without a `URL` link value
     default_connect_url: String,
+    // metrics of batches executed via `execute`, served by the SDK along with invocation metrics
+    batch_metrics: Arc<BatchMetrics>,
+}
+
+/// Metrics of pipelined batches
+#[derive(Debug, Default)]
+struct BatchMetrics {
+    batches: AtomicU64,
+    operations: AtomicU64,
+    failed_operations: AtomicU64,
+    /// Non-cumulative counts per bucket, the last element counts batches exceeding all bounds
+    sizes: [AtomicU64; BATCH_SIZE_BUCKETS.len() + 1],
+}
+
+impl BatchMetrics {
+    /// Record the execution of a batch of `size` operations, `failed` of which failed
+    fn record(&self, size: usize, failed: usize) {
+        let bucket = BATCH_SIZE_BUCKETS
+            .iter()
+            .position(|le| size <= *le)
+            .unwrap_or(BATCH_SIZE_BUCKETS.len());
+        self.batches.fetch_add(1, Ordering::Relaxed);
+        self.operations.fetch_add(size as u64, Ordering::Relaxed);
+        self.failed_operations
+            .fetch_add(failed as u64, Ordering::Relaxed);
+        self.sizes[bucket].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl MetricsCollector for BatchMetrics {
+    fn render(&self, out: &mut String) {
+        let batches = self.batches.load(Ordering::Relaxed);
+        let operations = self.operations.load(Ordering::Relaxed);
+        // Writing to a `String` cannot fail
+        let _ = writeln!(
+            out,
+            "# HELP wasmcloud_kvredis_batch_operation_errors_total Number of operations of executed batches that failed\n\
+             # TYPE wasmcloud_kvredis_batch_operation_errors_total counter\n\
+             wasmcloud_kvredis_batch_operation_errors_total {}",
+            self.failed_operations.load(Ordering::Relaxed)
+        );
+        let _ = writeln!(
+            out,
+            "# HELP wasmcloud_kvredis_batch_size Number of operations of executed batches\n\
+             # TYPE wasmcloud_kvredis_batch_size histogram"
+        );
+        let mut cumulative = 0;
+        for (count, le) in self.sizes.iter().zip(BATCH_SIZE_BUCKETS) {
+            cumulative += count.load(Ordering::Relaxed);
+            let _ = writeln!(
+                out,
+                "wasmcloud_kvredis_batch_size_bucket{{le=\"{le}\"}} {cumulative}"
+            );
+        }
+        let _ = writeln!(
+            out,
+            "wasmcloud_kvredis_batch_size_bucket{{le=\"+Inf\"}} {batches}\n\
+             wasmcloud_kvredis_batch_size_sum {operations}\n\
+             wasmcloud_kvredis_batch_size_count {batches}"
+        );
+    }
 }
 
 impl KvRedisProvider {
@@ -97,18 +184,28 @@ impl WasmcloudCapabilityProvider for KvRedisProvider {
     /// If the link is allowed, return true, otherwise return false to deny the link.
     #[instrument(level = "debug", skip(self, ld), fields(actor_id = %ld.actor_id))]
     async fn put_link(&self, ld: &LinkDefinition) -> bool {
+        // The URL may hold credentials, in which case it is referenced as a secret. Only the URL
+        // as configured on the link is logged, which does not include the resolved secret
+        let configured_url = get_redis_url(&ld.values, &self.default_connect_url);
+        let ld = match get_connection().secrets_client().resolve_link(ld).await {
+            Ok(ld) => ld,
+            Err(err) => {
+                warn!(%err, "Could not resolve secrets of link for actor {}", ld.actor_id);
+                return false;
+            }
+        };
         let redis_url = get_redis_url(&ld.values, &self.default_connect_url);
 
         match redis::Client::open(redis_url.clone()) {
             Ok(client) => match client.get_tokio_connection_manager().await {
                 Ok(conn_manager) => {
-                    info!(redis_url, "established link");
+                    info!(redis_url = configured_url, "established link");
                     let mut update_map = self.actors.write().await;
                     update_map.insert(ld.actor_id.to_string(), RwLock::new(conn_manager));
                 }
                 Err(err) => {
                     warn!(
-                        redis_url,
+                        redis_url = configured_url,
                         ?err,
                     "Could not create Redis connection manager for actor {}, keyvalue operations will fail",
                     ld.actor_id
@@ -331,6 +428,164 @@ impl WasmcloudKeyvalueKeyValue for KvRedisProvider {
     }
 }
 
+/// Handle batch KeyValue methods, each of which is executed as a single redis command or, for
+/// `execute`, a single pipeline of commands
+#[async_trait]
+impl WasmcloudKeyvalueBatch for KvRedisProvider {
+    /// Gets the values of multiple keys. The returned list contains an entry for every requested
+    /// key, in order, which is `None` if the key does not exist
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, keys = ?arg))]
+    async fn get_many(
+        &self,
+        ctx: Context,
+        arg: Vec<String>,
+    ) -> ProviderInvocationResult<Vec<Option<KeyValuePair>>> {
+        if arg.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut cmd = redis::cmd("MGET");
+        cmd.arg(&arg);
+        let values: Vec<Option<String>> = self
+            .exec(&ctx, &mut cmd)
+            .await
+            .map_err(ProviderInvocationError::Provider)?;
+        Ok(arg
+            .into_iter()
+            .zip(values)
+            .map(|(key, value)| value.map(|value| KeyValuePair { key, value }))
+            .collect())
+    }
+
+    /// Returns all keys of the database, which are iterated using `SCAN`, so that Redis is not
+    /// blocked for the duration of the call
+    #[instrument(level = "debug", skip(self, ctx), fields(actor_id = ?ctx.actor))]
+    async fn get_keys(&self, ctx: Context) -> ProviderInvocationResult<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut cursor = 0_u64;
+        loop {
+            let mut cmd = redis::cmd("SCAN");
+            cmd.arg(cursor).arg("COUNT").arg(SCAN_COUNT);
+            let (next, batch): (u64, Vec<String>) = self
+                .exec(&ctx, &mut cmd)
+                .await
+                .map_err(ProviderInvocationError::Provider)?;
+            keys.extend(batch);
+            if next == 0 {
+                // `SCAN` may return a key more than once
+                keys.sort_unstable();
+                keys.dedup();
+                return Ok(keys);
+            }
+            cursor = next;
+        }
+    }
+
+    /// Sets the values of multiple keys atomically
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, keys = ?arg.iter().map(|pair| &pair.key).collect::<Vec<_>>()))]
+    async fn set_many(&self, ctx: Context, arg: Vec<KeyValuePair>) -> ProviderInvocationResult<()> {
+        if arg.is_empty() {
+            return Ok(());
+        }
+        let pairs: Vec<(String, String)> = arg
+            .into_iter()
+            .map(|KeyValuePair { key, value }| (key, value))
+            .collect();
+        let mut cmd = redis::Cmd::set_multiple(&pairs);
+        let _value: Option<String> = self
+            .exec(&ctx, &mut cmd)
+            .await
+            .map_err(ProviderInvocationError::Provider)?;
+        Ok(())
+    }
+
+    /// Deletes multiple keys, ignoring those that do not exist
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, keys = ?arg))]
+    async fn delete_many(&self, ctx: Context, arg: Vec<String>) -> ProviderInvocationResult<()> {
+        if arg.is_empty() {
+            return Ok(());
+        }
+        let mut cmd = redis::Cmd::del(arg);
+        let _deleted: u32 = self
+            .exec(&ctx, &mut cmd)
+            .await
+            .map_err(ProviderInvocationError::Provider)?;
+        Ok(())
+    }
+
+    /// Executes all operations in a single pipelined round trip. Every command is wrapped in
+    /// [`PCALL_SCRIPT`], which is invoked by its hash, so that a failing command only fails its
+    /// own operation
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, batch_size = arg.len()))]
+    async fn execute(
+        &self,
+        ctx: Context,
+        arg: Vec<Operation>,
+    ) -> ProviderInvocationResult<Vec<Result<OperationOutput, String>>> {
+        if arg.is_empty() {
+            return Ok(Vec::new());
+        }
+        let script = pcall_script();
+        let mut pipe = redis::pipe();
+        for op in &arg {
+            let cmd = pipe.cmd("EVALSHA").arg(script.get_hash()).arg(1);
+            match op {
+                Operation::Get(key) => cmd.arg(key).arg("GET"),
+                Operation::Set(KeyValuePair { key, value }) => cmd.arg(key).arg("SET").arg(value),
+                Operation::Delete(key) => cmd.arg(key).arg("DEL"),
+                Operation::Contains(key) => cmd.arg(key).arg("EXISTS"),
+                Operation::Increment(KeyDelta { key, delta }) => {
+                    cmd.arg(key).arg("INCRBY").arg(*delta)
+                }
+            };
+        }
+        let replies: Vec<Value> = self
+            .exec_pipeline(&ctx, script, &pipe)
+            .await
+            .map_err(ProviderInvocationError::Provider)?;
+        if replies.len() != arg.len() {
+            return Err(ProviderInvocationError::Provider(format!(
+                "expected {} replies to batch, got {}",
+                arg.len(),
+                replies.len()
+            )));
+        }
+        let outputs: Vec<_> = arg
+            .iter()
+            .zip(replies)
+            .map(|(op, reply)| operation_output(op, reply))
+            .collect();
+        let failed = outputs.iter().filter(|output| output.is_err()).count();
+        self.batch_metrics.record(outputs.len(), failed);
+        debug!(batch_size = outputs.len(), failed, "executed batch");
+        Ok(outputs)
+    }
+}
+
+/// Parse the reply of a single command executed by [`PCALL_SCRIPT`] as the output of `op`
+fn operation_output(op: &Operation, reply: Value) -> Result<OperationOutput, String> {
+    let (status, reply) = match reply {
+        Value::Bulk(reply) => match <[Value; 2]>::try_from(reply) {
+            Ok([status, reply]) => (status, reply),
+            Err(reply) => return Err(format!("unexpected reply to batch operation: {reply:?}")),
+        },
+        reply => return Err(format!("unexpected reply to batch operation: {reply:?}")),
+    };
+    if status != Value::Int(1) {
+        return Err(String::from_redis_value(&reply).unwrap_or_else(|_| format!("{reply:?}")));
+    }
+    match op {
+        Operation::Get(_) => FromRedisValue::from_redis_value(&reply).map(OperationOutput::Value),
+        Operation::Set(_) => Ok(OperationOutput::Done),
+        Operation::Delete(_) | Operation::Contains(_) => {
+            i64::from_redis_value(&reply).map(|n| OperationOutput::Found(n > 0))
+        }
+        Operation::Increment(_) => {
+            FromRedisValue::from_redis_value(&reply).map(OperationOutput::Number)
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
 impl KvRedisProvider {
     /// Helper function to execute redis async command while holding onto a mutable connection.
     ///
@@ -368,6 +623,39 @@ impl KvRedisProvider {
             .await
             .map_err(|e| e.to_string())
     }
+
+    /// Like [`Self::exec`], but for a pipeline of commands invoking `script` by its hash, which is
+    /// sent in a single round trip. If Redis does not know the script yet (or flushed its script
+    /// cache), the script is loaded and the pipeline is sent again
+    async fn exec_pipeline<T: FromRedisValue>(
+        &self,
+        ctx: &Context,
+        script: &Script,
+        pipe: &redis::Pipeline,
+    ) -> Result<T, String> {
+        let actor_id = ctx
+            .actor
+            .as_ref()
+            .ok_or_else(|| "no actor in request".to_string())?;
+        let rd = self.actors.read().await;
+        let rc = rd
+            .get(actor_id)
+            .ok_or_else(||format!("No Redis connection found for {}. Please ensure the URL supplied in the link definition is a valid Redis URL", actor_id))?;
+        let mut con = rc.write().await;
+        match pipe.query_async(con.deref_mut()).await {
+            Err(err) if err.kind() == ErrorKind::NoScriptError => {
+                debug!("loading batch script");
+                script
+                    .prepare_invoke()
+                    .load_async(con.deref_mut())
+                    .await
+                    .map_err(|e| e.to_string())?;
+                pipe.query_async(con.deref_mut()).await
+            }
+            res => res,
+        }
+        .map_err(|e| e.to_string())
+    }
 }
 
 fn get_redis_url(link_values: &[(String, String)], default_connect_url: &str) -> String {
@@ -380,7 +668,20 @@ fn get_redis_url(link_values: &[(String, String)], default_connect_url: &str) ->
 
 #[cfg(test)]
 mod test {
-    use super::{get_redis_url, KvRedisConfig};
+    use std::env;
+    use std::process::Stdio;
+    use std::time::Duration;
+
+    use redis::Value;
+    use tokio::process::{Child, Command};
+    use tokio::sync::RwLock;
+    use wasmcloud_provider_sdk::metrics::MetricsCollector;
+    use wasmcloud_provider_sdk::Context;
+
+    use super::{
+        get_redis_url, operation_output, BatchMetrics, KeyDelta, KeyValuePair, KvRedisConfig,
+        KvRedisProvider, Operation, OperationOutput, WasmcloudKeyvalueBatch,
+    };
 
     const PROPER_URL: &str = "redis://127.0.0.1:6379";
 
@@ -432,4 +733,174 @@ mod test {
             PROPER_URL
         );
     }
+
+    #[test]
+    fn can_parse_batch_operation_outputs() {
+        let ok = |reply| Value::Bulk(vec![Value::Int(1), reply]);
+        let increment = Operation::Increment(KeyDelta {
+            key: "a".into(),
+            delta: 2,
+        });
+        assert!(matches!(
+            operation_output(&Operation::Get("a".into()), ok(Value::Data(b"b".to_vec()))),
+            Ok(OperationOutput::Value(Some(value))) if value == "b"
+        ));
+        assert!(matches!(
+            operation_output(&Operation::Get("a".into()), ok(Value::Nil)),
+            Ok(OperationOutput::Value(None))
+        ));
+        assert!(matches!(
+            operation_output(&Operation::Contains("a".into()), ok(Value::Int(0))),
+            Ok(OperationOutput::Found(false))
+        ));
+        assert!(matches!(
+            operation_output(&increment, ok(Value::Int(3))),
+            Ok(OperationOutput::Number(3))
+        ));
+        assert!(matches!(
+            operation_output(
+                &increment,
+                Value::Bulk(vec![
+                    Value::Int(0),
+                    Value::Data(b"ERR value is not an integer or out of range".to_vec())
+                ])
+            ),
+            Err(err) if err == "ERR value is not an integer or out of range"
+        ));
+        assert!(operation_output(&Operation::Delete("a".into()), Value::Nil).is_err());
+    }
+
+    #[test]
+    fn can_render_batch_metrics() {
+        let metrics = BatchMetrics::default();
+        metrics.record(1, 0);
+        metrics.record(3, 1);
+        metrics.record(1000, 0);
+        let mut out = String::new();
+        metrics.render(&mut out);
+        for line in [
+            "wasmcloud_kvredis_batch_operation_errors_total 1",
+            "wasmcloud_kvredis_batch_size_bucket{le=\"1\"} 1",
+            "wasmcloud_kvredis_batch_size_bucket{le=\"2\"} 1",
+            "wasmcloud_kvredis_batch_size_bucket{le=\"5\"} 2",
+            "wasmcloud_kvredis_batch_size_bucket{le=\"250\"} 2",
+            "wasmcloud_kvredis_batch_size_bucket{le=\"+Inf\"} 3",
+            "wasmcloud_kvredis_batch_size_sum 1004",
+            "wasmcloud_kvredis_batch_size_count 3",
+        ] {
+            assert!(
+                out.lines().any(|l| l == line),
+                "missing `{line}` in:\n{out}"
+            );
+        }
+    }
+
+    /// Starts a Redis server on a free port, which is killed when the returned process is dropped
+    async fn start_redis() -> (Child, String) {
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("failed to find free port")
+            .port();
+        let server = Command::new(
+            env::var("WASMCLOUD_REDIS")
+                .as_deref()
+                .unwrap_or("redis-server"),
+        )
+        .args(["--port", &port.to_string(), "--save", ""])
+        .stdout(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .expect("failed to start Redis");
+        let url = format!("redis://127.0.0.1:{port}");
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while tokio::net::TcpStream::connect(("127.0.0.1", port))
+                .await
+                .is_err()
+            {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        })
+        .await
+        .expect("Redis did not start");
+        (server, url)
+    }
+
+    #[tokio::test]
+    async fn failed_operations_do_not_fail_batch() {
+        let (_server, url) = start_redis().await;
+        let provider = KvRedisProvider::new(&url);
+        let conn = redis::Client::open(url)
+            .unwrap()
+            .get_tokio_connection_manager()
+            .await
+            .unwrap();
+        provider
+            .actors
+            .write()
+            .await
+            .insert("actor".to_string(), RwLock::new(conn));
+        let ctx = Context {
+            actor: Some("actor".to_string()),
+            ..Default::default()
+        };
+
+        let outputs = provider
+            .execute(
+                ctx.clone(),
+                vec![
+                    Operation::Set(KeyValuePair {
+                        key: "text".into(),
+                        value: "not a number".into(),
+                    }),
+                    Operation::Increment(KeyDelta {
+                        key: "text".into(),
+                        delta: 1,
+                    }),
+                    Operation::Increment(KeyDelta {
+                        key: "counter".into(),
+                        delta: 2,
+                    }),
+                    Operation::Get("text".into()),
+                    Operation::Contains("missing".into()),
+                ],
+            )
+            .await
+            .expect("failed to execute batch");
+        assert!(
+            matches!(
+                outputs.as_slice(),
+                [
+                    Ok(OperationOutput::Done),
+                    Err(_),
+                    Ok(OperationOutput::Number(2)),
+                    Ok(OperationOutput::Value(Some(value))),
+                    Ok(OperationOutput::Found(false)),
+                ] if value == "not a number"
+            ),
+            "{outputs:?}"
+        );
+
+        // The script is loaded again, if Redis flushed its script cache
+        redis::cmd("SCRIPT")
+            .arg("FLUSH")
+            .query_async::<_, ()>(&mut *provider.actors.read().await["actor"].write().await)
+            .await
+            .unwrap();
+        let outputs = provider
+            .execute(ctx, vec![Operation::Get("counter".into())])
+            .await
+            .expect("failed to execute batch");
+        assert!(
+            matches!(
+                outputs.as_slice(),
+                [Ok(OperationOutput::Value(Some(value)))] if value == "2"
+            ),
+            "{outputs:?}"
+        );
+
+        let mut out = String::new();
+        provider.batch_metrics.render(&mut out);
+        assert!(out.contains("wasmcloud_kvredis_batch_operation_errors_total 1\n"));
+        assert!(out.contains("wasmcloud_kvredis_batch_size_count 2\n"));
+    }
 }
@@ -0,0 +1,239 @@
+//! Signed `sts:GetCallerIdentity` requests, which prove possession of AWS IAM credentials to the
+//! Vault AWS auth method without sending the credentials themselves
+//!
+
+use std::collections::BTreeMap;
+
+use aws_config::default_provider::credentials::DefaultCredentialsChain;
+use aws_config::Region;
+use aws_credential_types::provider::ProvideCredentials;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::error::VaultError;
+
+/// Body of the signed request
+const GET_CALLER_IDENTITY_BODY: &str = "Action=GetCallerIdentity&Version=2011-06-15";
+
+/// Header carrying the server ID configured for the Vault AWS auth method, if any
+const SERVER_ID_HEADER: &str = "x-vault-aws-iam-server-id";
+
+/// A signed request, with the URL, headers and body base64-encoded as expected by the Vault AWS
+/// auth method
+pub struct SignedIamRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: String,
+    pub body: String,
+}
+
+/// AWS credentials to sign requests with
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl Credentials {
+    /// Resolves credentials with the default AWS credentials provider chain, which reads them
+    /// from the environment, the shared config and credentials files, web identity tokens, and
+    /// the ECS and EC2 instance metadata services
+    pub async fn load(region: Option<&str>) -> Result<Self, VaultError> {
+        let credentials = DefaultCredentialsChain::builder()
+            .region(region.map(|region| Region::new(region.to_string())))
+            .build()
+            .await
+            .provide_credentials()
+            .await
+            .map_err(|e| VaultError::Auth(format!("failed to load AWS credentials: {e}")))?;
+        Ok(Self {
+            access_key_id: credentials.access_key_id().to_string(),
+            secret_access_key: credentials.secret_access_key().to_string(),
+            session_token: credentials.session_token().map(ToString::to_string),
+        })
+    }
+}
+
+/// Signs a `sts:GetCallerIdentity` request with the credentials resolved by
+/// [`Credentials::load`], for the STS endpoint of `region` or the global endpoint
+pub async fn sign_get_caller_identity(
+    region: Option<&str>,
+    server_id: Option<&str>,
+) -> Result<SignedIamRequest, VaultError> {
+    let credentials = Credentials::load(region).await?;
+    let (host, region) = match region {
+        Some(region) => (format!("sts.{region}.amazonaws.com"), region),
+        None => ("sts.amazonaws.com".to_string(), "us-east-1"),
+    };
+
+    // Header names are lowercase, and sorted as required for signing
+    let mut headers = BTreeMap::from([
+        (
+            "content-type".to_string(),
+            "application/x-www-form-urlencoded; charset=utf-8".to_string(),
+        ),
+        ("host".to_string(), host.clone()),
+    ]);
+    if let Some(server_id) = server_id {
+        headers.insert(SERVER_ID_HEADER.to_string(), server_id.to_string());
+    }
+    sign(
+        "POST",
+        &mut headers,
+        GET_CALLER_IDENTITY_BODY,
+        &credentials,
+        region,
+        "sts",
+        Utc::now(),
+    );
+
+    // Vault expects the headers as a JSON object of header names to lists of values
+    let headers: BTreeMap<String, Vec<String>> =
+        headers.into_iter().map(|(k, v)| (k, vec![v])).collect();
+    let headers = serde_json::to_vec(&headers)
+        .map_err(|e| VaultError::Auth(format!("failed to encode signed headers: {e}")))?;
+    let b64 = base64::engine::general_purpose::STANDARD;
+    Ok(SignedIamRequest {
+        method: "POST".to_string(),
+        url: b64.encode(format!("https://{host}/")),
+        headers: b64.encode(headers),
+        body: b64.encode(GET_CALLER_IDENTITY_BODY),
+    })
+}
+
+/// Signs a request for the path `/` without a query string, which is all STS requires, with AWS
+/// Signature Version 4 at `time`. The `x-amz-date`, `x-amz-security-token` and `authorization`
+/// headers are added to the lowercase `headers` of the request
+fn sign(
+    method: &str,
+    headers: &mut BTreeMap<String, String>,
+    body: &str,
+    credentials: &Credentials,
+    region: &str,
+    service: &str,
+    time: DateTime<Utc>,
+) {
+    let amz_date = time.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = time.format("%Y%m%d").to_string();
+    headers.insert("x-amz-date".to_string(), amz_date.clone());
+    if let Some(token) = &credentials.session_token {
+        headers.insert("x-amz-security-token".to_string(), token.clone());
+    }
+
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(name, value)| format!("{name}:{}\n", value.trim()))
+        .collect();
+    let canonical_request = format!(
+        "{method}\n/\n\n{canonical_headers}\n{signed_headers}\n{}",
+        hex::encode(Sha256::digest(body))
+    );
+    let scope = format!("{date}/{region}/{service}/aws4_request");
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{amz_date}\n{scope}\n{}",
+        hex::encode(Sha256::digest(canonical_request))
+    );
+    let signing_key = [region, service, "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, part| hmac_sha256(&key, part.as_bytes()),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+    headers.insert(
+        "authorization".to_string(),
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={signed_headers}, Signature={signature}",
+            credentials.access_key_id
+        ),
+    );
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    /// Signs a request of the AWS Signature Version 4 test suite, which all use the same
+    /// credentials, time, region and service, returning the `authorization` header
+    fn sign_test_request(method: &str, content_type: Option<&str>, body: &str) -> String {
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "wJalrXUtnFEMI/K7MDENG+bPxRfiCYEXAMPLEKEY".to_string(),
+            session_token: None,
+        };
+        let mut headers =
+            BTreeMap::from([("host".to_string(), "example.amazonaws.com".to_string())]);
+        if let Some(content_type) = content_type {
+            headers.insert("content-type".to_string(), content_type.to_string());
+        }
+        let time = Utc.with_ymd_and_hms(2015, 8, 30, 12, 36, 0).unwrap();
+        sign(
+            method,
+            &mut headers,
+            body,
+            &credentials,
+            "us-east-1",
+            "service",
+            time,
+        );
+        assert_eq!(headers["x-amz-date"], "20150830T123600Z");
+        headers.remove("authorization").expect("request not signed")
+    }
+
+    #[test]
+    fn signature_v4_test_suite() {
+        // get-vanilla
+        assert_eq!(
+            sign_test_request("GET", None, ""),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5fa00fa31553b73ebf1942676e86291e8372ff2a2260956d9b8aae1d763fbf31"
+        );
+        // post-vanilla
+        assert_eq!(
+            sign_test_request("POST", None, ""),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=host;x-amz-date, Signature=5da7c1a2acd57cee7505fc6676e4e544621c30862966e37dddb68e92efbe5d6b"
+        );
+        // post-x-www-form-urlencoded
+        assert_eq!(
+            sign_test_request(
+                "POST",
+                Some("application/x-www-form-urlencoded"),
+                "Param1=value1"
+            ),
+            "AWS4-HMAC-SHA256 Credential=AKIDEXAMPLE/20150830/us-east-1/service/aws4_request, SignedHeaders=content-type;host;x-amz-date, Signature=ff11897932ad3f4e8b18135d722051e5ac45fc38421b1da7b9d196a0fe09473a"
+        );
+    }
+
+    #[test]
+    fn session_token_signed() {
+        let credentials = Credentials {
+            access_key_id: "AKIDEXAMPLE".to_string(),
+            secret_access_key: "secret".to_string(),
+            session_token: Some("token".to_string()),
+        };
+        let mut headers = BTreeMap::from([("host".to_string(), "sts.amazonaws.com".to_string())]);
+        sign(
+            "POST",
+            &mut headers,
+            GET_CALLER_IDENTITY_BODY,
+            &credentials,
+            "us-east-1",
+            "sts",
+            Utc::now(),
+        );
+        assert_eq!(headers["x-amz-security-token"], "token");
+        assert!(headers["authorization"]
+            .contains("SignedHeaders=host;x-amz-date;x-amz-security-token,"));
+    }
+}
@@ -10,6 +10,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some("kv-vault-provider".to_string()),
     )?;
 
-    eprintln!("KvVault provider exiting");
+    tracing::info!("KvVault provider exiting");
     Ok(())
 }
@@ -1,21 +1,139 @@
 //! Hashicorp vault client
 //!
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::time::{Duration, Instant};
 use std::{string::ToString, sync::Arc};
 
 use serde::{de::DeserializeOwned, Serialize};
-use vaultrs::api::kv2::responses::SecretVersionMetadata;
+use tokio::sync::{Mutex, RwLock};
+use tokio::task::AbortHandle;
+use tracing::{debug, warn};
+use vaultrs::api::kv2::requests::SetSecretMetadataRequest;
+use vaultrs::api::kv2::responses::{ReadSecretMetadataResponse, SecretVersionMetadata};
 use vaultrs::client::{VaultClient, VaultClientSettings};
+use vaultrs::error::ClientError;
+use vaultrs::sys::ServerStatus;
+use wasmcloud_provider_sdk::TaskSupervisor;
 
-use crate::{config::Config, error::VaultError};
+use crate::{
+    aws,
+    config::{AuthMethod, Config, KvVersion},
+    error::VaultError,
+};
 
 /// Vault HTTP api version. As of Vault 1.9.x (Feb 2022), all http api calls use version 1
 const API_VERSION: u8 = 1;
 
+/// Duration for which the result of a Vault health check is reused
+const HEALTH_CACHE_TTL: Duration = Duration::from_secs(10);
+
+/// Time before the expiry of a token, at which the client renews it or logs in again
+const TOKEN_RENEWAL_MARGIN: Duration = Duration::from_secs(30);
+
+/// Minimum time between renewals of a token, so that tokens with short TTLs are not renewed in a
+/// tight loop
+const MIN_RENEWAL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Time after which renewing a token is retried, if it failed
+const RENEWAL_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// State of the Vault server, as reported by `sys/health`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Health {
+    /// The server is initialized, unsealed and able to serve requests, possibly as a standby
+    Healthy,
+    /// The server is sealed
+    Sealed,
+    /// The server is not initialized
+    Uninitialized,
+    /// The server is in recovery mode
+    Recovery,
+    /// The server could not be reached or returned an unexpected response
+    Unreachable,
+}
+
+impl Health {
+    /// Returns true if the server is able to serve requests
+    pub fn is_healthy(self) -> bool {
+        self == Health::Healthy
+    }
+}
+
+impl From<ServerStatus> for Health {
+    fn from(status: ServerStatus) -> Self {
+        match status {
+            ServerStatus::OK | ServerStatus::STANDBY | ServerStatus::PERFSTANDBY => Health::Healthy,
+            ServerStatus::SEALED => Health::Sealed,
+            ServerStatus::UNINITIALIZED => Health::Uninitialized,
+            ServerStatus::RECOVERY => Health::Recovery,
+            _ => Health::Unreachable,
+        }
+    }
+}
+
+impl fmt::Display for Health {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Health::Healthy => "healthy",
+            Health::Sealed => "sealed",
+            Health::Uninitialized => "uninitialized",
+            Health::Recovery => "in recovery mode",
+            Health::Unreachable => "unreachable",
+        })
+    }
+}
+
+/// Token obtained by logging in with the configured auth method
+#[derive(Clone, Copy, Debug)]
+struct Login {
+    /// Time at which the client logs in again, if the token expires
+    renew_at: Option<Instant>,
+}
+
 /// Vault client connection information.
 #[derive(Clone)]
 pub struct Client {
-    inner: Arc<vaultrs::client::VaultClient>,
+    inner: Arc<RwLock<Arc<VaultClient>>>,
+    config: Arc<Config>,
     namespace: String,
+    /// Current login, if the auth method requires logging in. Shared by all clones, and locked
+    /// while logging in so that concurrent requests log in only once
+    login: Arc<Mutex<Option<Login>>>,
+    /// Result of the last health check, shared by all clones
+    health: Arc<Mutex<Option<(Instant, Health)>>>,
+    /// Reason the token could not be renewed in the last attempt, if it failed
+    renewal_error: Arc<Mutex<Option<String>>>,
+    /// Background task renewing the token, see [`Client::start_token_renewal`]
+    renewal_task: Arc<std::sync::Mutex<Option<AbortHandle>>>,
+}
+
+/// Creates a Vault client for `config`, which authenticates with `token`
+fn vault_client(config: &Config, token: String) -> Result<VaultClient, VaultError> {
+    Ok(VaultClient::new(VaultClientSettings {
+        token,
+        address: config.addr.clone(),
+        ca_certs: config.certs.clone(),
+        verify: false,
+        version: API_VERSION,
+        wrapping: false,
+        timeout: None,
+        namespace: config.namespace.clone(),
+    })?)
+}
+
+/// Returns the time to wait before renewing a token with the remaining TTL `ttl`, or `None` if the
+/// token does not expire. Renewal is retried after [`RENEWAL_RETRY_INTERVAL`] if it failed
+fn renewal_delay(ttl: &Result<Option<Duration>, VaultError>) -> Option<Duration> {
+    match ttl {
+        Ok(Some(ttl)) => Some(
+            ttl.saturating_sub(TOKEN_RENEWAL_MARGIN)
+                .max(MIN_RENEWAL_INTERVAL),
+        ),
+        Ok(None) => None,
+        Err(_) => Some(RENEWAL_RETRY_INTERVAL),
+    }
 }
 
 impl Client {
@@ -23,69 +141,539 @@ impl Client {
     ///
     /// Note that this constructor does not attempt to connect to the vault server,
     /// so the vault server does not need to be running at the time a LinkDefinition to this provider is created.
+    /// Auth methods other than a static token log in on the first request.
     pub fn new(config: Config) -> Result<Self, VaultError> {
+        let token = match &config.auth {
+            AuthMethod::Token(token) => token.clone(),
+            _ => String::new(),
+        };
         Ok(Client {
-            inner: Arc::new(VaultClient::new(VaultClientSettings {
-                token: config.token,
-                address: config.addr,
-                ca_certs: config.certs,
-                verify: false,
-                version: API_VERSION,
-                wrapping: false,
-                timeout: None,
-                namespace: None,
-            })?),
-            namespace: config.mount,
+            inner: Arc::new(RwLock::new(Arc::new(vault_client(&config, token)?))),
+            namespace: config.mount.clone(),
+            config: Arc::new(config),
+            login: Arc::default(),
+            health: Arc::default(),
+            renewal_error: Arc::default(),
+            renewal_task: Arc::default(),
         })
     }
 
+    /// Starts a background task supervised by `supervisor`, keeping the token of the client
+    /// valid, which renews the token ahead of its expiry and logs in again if renewal fails,
+    /// until [`Client::stop_token_renewal`] is called or the provider shuts down
+    pub fn start_token_renewal(&self, supervisor: &TaskSupervisor) {
+        let task = supervisor.spawn("vault-token-renewal", self.clone().manage_token());
+        let previous = std::mem::replace(
+            &mut *self
+                .renewal_task
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+            task,
+        );
+        if let Some(previous) = previous {
+            previous.abort();
+        }
+    }
+
+    /// Stops the task started by [`Client::start_token_renewal`]
+    pub fn stop_token_renewal(&self) {
+        if let Some(task) = self
+            .renewal_task
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .take()
+        {
+            task.abort();
+        }
+    }
+
+    /// Returns the reason the token could not be renewed, if the last attempt failed
+    pub async fn renewal_error(&self) -> Option<String> {
+        self.renewal_error.lock().await.clone()
+    }
+
+    /// Renews the token whenever it is about to expire, until the token turns out not to expire
+    async fn manage_token(self) {
+        let mut ttl = self.token_ttl().await;
+        loop {
+            *self.renewal_error.lock().await = match &ttl {
+                Ok(_) => None,
+                Err(err) => {
+                    warn!(%err, "failed to renew Vault token");
+                    Some(err.to_string())
+                }
+            };
+            let Some(wait) = renewal_delay(&ttl) else {
+                debug!("Vault token does not expire, stopping renewal");
+                return;
+            };
+            tokio::time::sleep(wait).await;
+            ttl = self.renew_token().await;
+        }
+    }
+
+    /// Returns the remaining TTL of the token, or `None` if it does not expire
+    async fn token_ttl(&self) -> Result<Option<Duration>, VaultError> {
+        let client = self.authenticated().await?;
+        let token = vaultrs::token::lookup_self(client.as_ref()).await?;
+        Ok((token.ttl > 0).then(|| Duration::from_secs(token.ttl)))
+    }
+
+    /// Renews the token, or logs in again if it cannot be renewed (any longer) and the auth
+    /// method allows it. Returns the new TTL of the token
+    async fn renew_token(&self) -> Result<Option<Duration>, VaultError> {
+        let client = self.authenticated().await?;
+        match vaultrs::token::renew_self(client.as_ref(), None).await {
+            Ok(auth) if Duration::from_secs(auth.lease_duration) > TOKEN_RENEWAL_MARGIN => {
+                let ttl = Duration::from_secs(auth.lease_duration);
+                debug!(ttl = auth.lease_duration, "renewed Vault token");
+                if self.config.auth.requires_login() {
+                    *self.login.lock().await = Some(Login {
+                        renew_at: Some(Instant::now() + ttl.saturating_sub(TOKEN_RENEWAL_MARGIN)),
+                    });
+                }
+                Ok(Some(ttl))
+            }
+            res if self.config.auth.requires_login() => {
+                match res {
+                    Err(err) => warn!(%err, "failed to renew Vault token, logging in again"),
+                    Ok(_) => debug!("Vault token reached its maximum TTL, logging in again"),
+                }
+                *self.login.lock().await = None;
+                self.token_ttl().await
+            }
+            Ok(_) => Err(VaultError::Auth(
+                "token reached its maximum TTL and cannot be renewed".to_string(),
+            )),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Returns the client to send requests with, logging in first if the auth method requires it
+    /// and there is no current token, or the current token is about to expire
+    async fn authenticated(&self) -> Result<Arc<VaultClient>, VaultError> {
+        if !self.config.auth.requires_login() {
+            return Ok(self.inner.read().await.clone());
+        }
+        let mut login = self.login.lock().await;
+        match *login {
+            Some(Login { renew_at: None }) => {}
+            Some(Login {
+                renew_at: Some(renew_at),
+            }) if Instant::now() < renew_at => {}
+            _ => *login = Some(self.log_in().await?),
+        }
+        Ok(self.inner.read().await.clone())
+    }
+
+    /// Logs in with the configured auth method, replacing the client with one using the obtained
+    /// token
+    async fn log_in(&self) -> Result<Login, VaultError> {
+        let client = vault_client(&self.config, String::new())?;
+        let auth = match &self.config.auth {
+            AuthMethod::Token(_) => return Ok(Login { renew_at: None }),
+            AuthMethod::AppRole {
+                mount,
+                role_id,
+                secret_id,
+            } => vaultrs::auth::approle::login(&client, mount, role_id, secret_id).await,
+            AuthMethod::Kubernetes {
+                mount,
+                role,
+                jwt_path,
+            } => {
+                let jwt = tokio::fs::read_to_string(jwt_path).await.map_err(|e| {
+                    VaultError::Auth(format!(
+                        "failed to read service account token from {}: {e}",
+                        jwt_path.display()
+                    ))
+                })?;
+                vaultrs::auth::kubernetes::login(&client, mount, role, jwt.trim()).await
+            }
+            AuthMethod::AwsIam {
+                mount,
+                role,
+                region,
+                server_id,
+            } => {
+                let request =
+                    aws::sign_get_caller_identity(region.as_deref(), server_id.as_deref()).await?;
+                vaultrs::auth::aws::iam_login(
+                    &client,
+                    mount,
+                    &request.method,
+                    &request.url,
+                    &request.headers,
+                    &request.body,
+                    role.as_deref(),
+                )
+                .await
+            }
+        }
+        .map_err(|e| VaultError::Auth(e.to_string()))?;
+        let renew_at = (auth.lease_duration > 0).then(|| {
+            Instant::now()
+                + Duration::from_secs(auth.lease_duration).saturating_sub(TOKEN_RENEWAL_MARGIN)
+        });
+        debug!(lease_duration = auth.lease_duration, "logged in to Vault");
+        *self.inner.write().await = Arc::new(vault_client(&self.config, auth.client_token)?);
+        Ok(Login { renew_at })
+    }
+
+    /// Sends a request with `op`, logging in again and retrying once if Vault denies the request
+    /// with a token obtained by logging in, which may have been revoked or expired early
+    async fn call<T, F, Fut>(&self, op: F) -> Result<T, VaultError>
+    where
+        F: Fn(Arc<VaultClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        match op(self.authenticated().await?).await {
+            Err(ClientError::APIError { code: 403, .. }) if self.config.auth.requires_login() => {
+                warn!("Vault denied request, logging in again");
+                *self.login.lock().await = None;
+                Ok(op(self.authenticated().await?).await?)
+            }
+            res => Ok(res?),
+        }
+    }
+
+    /// Returns the state of the Vault server, querying `sys/health` at most once per
+    /// [`HEALTH_CACHE_TTL`]
+    pub async fn health(&self) -> Health {
+        let mut cached = self.health.lock().await;
+        match *cached {
+            Some((checked_at, health)) if checked_at.elapsed() < HEALTH_CACHE_TTL => health,
+            _ => {
+                let client = self.inner.read().await.clone();
+                let health = vaultrs::sys::status(client.as_ref()).await.into();
+                *cached = Some((Instant::now(), health));
+                health
+            }
+        }
+    }
+
     /// Reads value of secret using namespace and key path
     pub async fn read_secret<D: DeserializeOwned>(&self, path: &str) -> Result<D, VaultError> {
-        match vaultrs::kv2::read(self.inner.as_ref(), &self.namespace, path).await {
-            Err(vaultrs::error::ClientError::APIError {
-                code: 404,
-                errors: _,
+        let namespace = self.namespace.as_str();
+        let kv_version = self.config.kv_version;
+        match self
+            .call(|client| async move {
+                match kv_version {
+                    KvVersion::V1 => vaultrs::kv1::get(client.as_ref(), namespace, path).await,
+                    KvVersion::V2 => vaultrs::kv2::read(client.as_ref(), namespace, path).await,
+                }
+            })
+            .await
+        {
+            Err(VaultError::Client {
+                source: ClientError::APIError { code: 404, .. },
             }) => Err(VaultError::NotFound {
                 namespace: self.namespace.clone(),
                 path: path.to_string(),
             }),
-            Err(e) => Err(e.into()),
-            Ok(val) => Ok(val),
+            res => res,
         }
     }
 
-    /// Writes value of secret using namespace and key path
+    /// Writes value of secret using namespace and key path. Returns the metadata of the new
+    /// version of the secret, or `None` for KV v1, which does not version secrets
     pub async fn write_secret<T: Serialize>(
         &self,
         path: &str,
         data: &T,
-    ) -> Result<SecretVersionMetadata, VaultError> {
-        vaultrs::kv2::set(self.inner.as_ref(), &self.namespace, path, data)
-            .await
-            .map_err(VaultError::from)
+    ) -> Result<Option<SecretVersionMetadata>, VaultError> {
+        let namespace = self.namespace.as_str();
+        match self.config.kv_version {
+            KvVersion::V1 => {
+                // KV v1 takes the fields of the secret, rather than any serializable value
+                let value = serde_json::to_value(data)
+                    .map_err(|e| VaultError::InvalidValue(e.to_string()))?;
+                let serde_json::Value::Object(fields) = value else {
+                    return Err(VaultError::InvalidValue(
+                        "KV v1 secrets must be JSON objects".to_string(),
+                    ));
+                };
+                let fields: &HashMap<&str, &serde_json::Value> =
+                    &fields.iter().map(|(k, v)| (k.as_str(), v)).collect();
+                self.call(|client| async move {
+                    vaultrs::kv1::set(client.as_ref(), namespace, path, fields).await
+                })
+                .await?;
+                Ok(None)
+            }
+            KvVersion::V2 => self
+                .call(|client| async move {
+                    vaultrs::kv2::set(client.as_ref(), namespace, path, data).await
+                })
+                .await
+                .map(Some),
+        }
     }
 
     /// Deletes the latest version of the secret. Note that if versions are in use, only the latest is deleted
+    /// With KV v1, which does not version secrets, the secret is deleted permanently
     /// Returns Ok if the key was deleted, or Err for any other error including key not found
     pub async fn delete_latest(&self, path: impl AsRef<str>) -> Result<(), VaultError> {
-        let path = path.as_ref();
-        vaultrs::kv2::delete_latest(self.inner.as_ref(), &self.namespace, path)
-            .await
-            .map_err(VaultError::from)
+        let (namespace, path) = (self.namespace.as_str(), path.as_ref());
+        let kv_version = self.config.kv_version;
+        self.call(|client| async move {
+            match kv_version {
+                KvVersion::V1 => vaultrs::kv1::delete(client.as_ref(), namespace, path).await,
+                KvVersion::V2 => {
+                    vaultrs::kv2::delete_latest(client.as_ref(), namespace, path).await
+                }
+            }
+        })
+        .await
     }
 
     /// Lists keys at the path
     pub async fn list_secrets(&self, path: &str) -> Result<Vec<String>, VaultError> {
-        match vaultrs::kv2::list(self.inner.as_ref(), &self.namespace, path).await {
-            Err(vaultrs::error::ClientError::APIError {
-                code: 404,
-                errors: _,
+        let namespace = self.namespace.as_str();
+        let kv_version = self.config.kv_version;
+        match self
+            .call(|client| async move {
+                match kv_version {
+                    KvVersion::V1 => vaultrs::kv1::list(client.as_ref(), namespace, path)
+                        .await
+                        .map(|res| res.keys),
+                    KvVersion::V2 => vaultrs::kv2::list(client.as_ref(), namespace, path).await,
+                }
+            })
+            .await
+        {
+            Err(VaultError::Client {
+                source: ClientError::APIError { code: 404, .. },
             }) => Err(VaultError::NotFound {
                 namespace: self.namespace.clone(),
                 path: path.to_string(),
             }),
-            Err(e) => Err(e.into()),
-            Ok(secret_list) => Ok(secret_list),
+            res => res,
         }
     }
+
+    /// Fails for operations on versions of secrets if the KV secrets engine does not version them
+    fn require_versions(&self, operation: &str) -> Result<(), VaultError> {
+        match self.config.kv_version {
+            KvVersion::V1 => Err(VaultError::Unsupported(format!(
+                "{operation} requires the KV v2 secrets engine"
+            ))),
+            KvVersion::V2 => Ok(()),
+        }
+    }
+
+    /// Sets the time after which versions of the secret are deleted, which applies to the
+    /// versions written after it is set. `delete_after` is a Vault duration string, e.g. `"60s"`,
+    /// and `"0s"` disables deletion
+    pub async fn set_delete_version_after(
+        &self,
+        path: &str,
+        delete_after: &str,
+    ) -> Result<(), VaultError> {
+        self.require_versions("setting a TTL")?;
+        let namespace = self.namespace.as_str();
+        self.call(|client| async move {
+            vaultrs::kv2::set_metadata(
+                client.as_ref(),
+                namespace,
+                path,
+                Some(SetSecretMetadataRequest::builder().delete_version_after(delete_after)),
+            )
+            .await
+        })
+        .await
+    }
+
+    /// Soft-deletes the given versions of the secret, which can be restored with
+    /// [`Client::undelete_versions`]
+    pub async fn delete_versions(&self, path: &str, versions: &[u64]) -> Result<(), VaultError> {
+        self.require_versions("deleting versions")?;
+        let namespace = self.namespace.as_str();
+        self.call(|client| async move {
+            vaultrs::kv2::delete_versions(client.as_ref(), namespace, path, versions.to_vec()).await
+        })
+        .await
+    }
+
+    /// Restores soft-deleted versions of the secret
+    pub async fn undelete_versions(&self, path: &str, versions: &[u64]) -> Result<(), VaultError> {
+        self.require_versions("undeleting versions")?;
+        let namespace = self.namespace.as_str();
+        self.call(|client| async move {
+            vaultrs::kv2::undelete_versions(client.as_ref(), namespace, path, versions.to_vec())
+                .await
+        })
+        .await
+    }
+
+    /// Permanently destroys the data of the given versions of the secret
+    pub async fn destroy_versions(&self, path: &str, versions: &[u64]) -> Result<(), VaultError> {
+        self.require_versions("destroying versions")?;
+        let namespace = self.namespace.as_str();
+        self.call(|client| async move {
+            vaultrs::kv2::destroy_versions(client.as_ref(), namespace, path, versions.to_vec())
+                .await
+        })
+        .await
+    }
+
+    /// Reads the metadata of the secret and its versions
+    pub async fn read_metadata(
+        &self,
+        path: &str,
+    ) -> Result<ReadSecretMetadataResponse, VaultError> {
+        self.require_versions("reading metadata")?;
+        let namespace = self.namespace.as_str();
+        match self
+            .call(|client| async move {
+                vaultrs::kv2::read_metadata(client.as_ref(), namespace, path).await
+            })
+            .await
+        {
+            Err(VaultError::Client {
+                source: ClientError::APIError { code: 404, .. },
+            }) => Err(VaultError::NotFound {
+                namespace: self.namespace.clone(),
+                path: path.to_string(),
+            }),
+            res => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a config for a Vault server, which refuses connections
+    fn unreachable_config(auth: AuthMethod) -> Config {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("failed to bind port");
+        let addr = listener.local_addr().expect("failed to get address");
+        drop(listener);
+        Config {
+            auth,
+            addr: format!("http://{addr}").parse().expect("invalid address"),
+            mount: "secret".to_string(),
+            kv_version: KvVersion::V2,
+            namespace: None,
+            certs: Vec::new(),
+        }
+    }
+
+    async fn wait_for_renewal_error(client: &Client) -> String {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Some(err) = client.renewal_error().await {
+                    return err;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("renewal failure was not recorded")
+    }
+
+    fn renewal_finished(client: &Client) -> bool {
+        client
+            .renewal_task
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|task| !task.is_finished())
+            .is_none()
+    }
+
+    #[test]
+    fn renewal_is_scheduled_ahead_of_expiry() {
+        let ttl = Duration::from_secs(3600);
+        assert_eq!(
+            renewal_delay(&Ok(Some(ttl))),
+            Some(ttl - TOKEN_RENEWAL_MARGIN)
+        );
+        // Tokens expiring within the margin are not renewed in a tight loop
+        assert_eq!(
+            renewal_delay(&Ok(Some(Duration::from_secs(1)))),
+            Some(MIN_RENEWAL_INTERVAL)
+        );
+        assert_eq!(
+            renewal_delay(&Ok(Some(TOKEN_RENEWAL_MARGIN))),
+            Some(MIN_RENEWAL_INTERVAL)
+        );
+        // Tokens, which do not expire, are not renewed
+        assert_eq!(renewal_delay(&Ok(None)), None);
+        // Failed renewals are retried
+        assert_eq!(
+            renewal_delay(&Err(VaultError::Auth("denied".to_string()))),
+            Some(RENEWAL_RETRY_INTERVAL)
+        );
+    }
+
+    #[tokio::test]
+    async fn renewal_fails_without_vault() {
+        let client = Client::new(unreachable_config(AuthMethod::Token("root".to_string())))
+            .expect("failed to create client");
+        assert!(matches!(
+            client.renew_token().await,
+            Err(VaultError::Client { .. })
+        ));
+
+        // Logging in again fails as well, if the auth method requires it
+        let client = Client::new(unreachable_config(AuthMethod::AppRole {
+            mount: "approle".to_string(),
+            role_id: "role".to_string(),
+            secret_id: "secret".to_string(),
+        }))
+        .expect("failed to create client");
+        assert!(matches!(
+            client.renew_token().await,
+            Err(VaultError::Auth(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn renewal_failures_are_recorded() {
+        let supervisor = TaskSupervisor::default();
+        let client = Client::new(unreachable_config(AuthMethod::Token("root".to_string())))
+            .expect("failed to create client");
+        assert_eq!(client.renewal_error().await, None);
+
+        client.start_token_renewal(&supervisor);
+        wait_for_renewal_error(&client).await;
+        // The task keeps retrying until it is stopped
+        assert!(!renewal_finished(&client));
+
+        // The renewal task stops with the provider
+        supervisor.shutdown().await;
+        assert!(renewal_finished(&client));
+        assert!(supervisor.panicked_tasks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn renewal_is_stopped_and_restarted() {
+        let supervisor = TaskSupervisor::default();
+        let client = Client::new(unreachable_config(AuthMethod::Token("root".to_string())))
+            .expect("failed to create client");
+        client.start_token_renewal(&supervisor);
+        let first = client
+            .renewal_task
+            .lock()
+            .unwrap()
+            .clone()
+            .expect("renewal task was not spawned");
+
+        // Starting renewal again replaces the running task
+        client.start_token_renewal(&supervisor);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !first.is_finished() {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .expect("previous renewal task was not cancelled");
+        assert!(!renewal_finished(&client));
+
+        client.stop_token_renewal();
+        assert!(client.renewal_task.lock().unwrap().is_none());
+        supervisor.shutdown().await;
+    }
 }
@@ -1,7 +1,8 @@
 //! Configuration for kv-vault capability provider
 //!
 
-use std::{collections::HashMap, env};
+use std::{collections::HashMap, env, path::PathBuf};
+use tracing::warn;
 use url::Url;
 use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
 
@@ -9,18 +10,93 @@ use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationR
 /// used if unspecified by configuration
 const DEFAULT_VAULT_ADDR: &str = "http://127.0.0.1:8200";
 
+/// Path of the service account token mounted into Kubernetes pods, used for Kubernetes auth if
+/// unspecified by configuration
+const DEFAULT_K8S_JWT_PATH: &str = "/var/run/secrets/kubernetes.io/serviceaccount/token";
+
+/// Method used to authenticate with Vault, selected by the linkdef value `auth_method` or the
+/// environment variable `VAULT_AUTH_METHOD`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AuthMethod {
+    /// A static token (`token`), which is used as-is. This is the default
+    Token(String),
+    /// The AppRole auth method (`approle`), logging in with a role ID and secret ID
+    AppRole {
+        /// Mount point of the auth method, defaults to "approle"
+        mount: String,
+        role_id: String,
+        secret_id: String,
+    },
+    /// The Kubernetes auth method (`kubernetes`), logging in with the JWT of the service account
+    /// of the pod
+    Kubernetes {
+        /// Mount point of the auth method, defaults to "kubernetes"
+        mount: String,
+        /// Vault role to log in as
+        role: String,
+        /// File containing the service account JWT, read on every login so that rotated tokens
+        /// are picked up
+        jwt_path: PathBuf,
+    },
+    /// The AWS auth method using IAM credentials (`aws_iam`), logging in with a signed
+    /// `sts:GetCallerIdentity` request. Credentials are resolved with the default AWS credentials
+    /// provider chain on every login
+    AwsIam {
+        /// Mount point of the auth method, defaults to "aws"
+        mount: String,
+        /// Vault role to log in as, defaults to the name of the IAM principal
+        role: Option<String>,
+        /// Region of the STS endpoint to sign the request for. The global endpoint is used if
+        /// unset
+        region: Option<String>,
+        /// Value of the `X-Vault-AWS-IAM-Server-ID` header, if the auth method requires one
+        server_id: Option<String>,
+    },
+}
+
+/// Version of the KV secrets engine enabled at the mount point, selected by the linkdef value
+/// `kv_version` or the environment variable `VAULT_KV_VERSION`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KvVersion {
+    /// KV version 1, which stores a single, unversioned value per key
+    V1,
+    /// KV version 2, which keeps multiple versions of each key. This is the default
+    #[default]
+    V2,
+}
+
+impl KvVersion {
+    /// initialize from linkdef values and environment
+    fn from_values(values: &HashMap<String, String>) -> ProviderInvocationResult<KvVersion> {
+        match setting(values, "kv_version", "VAULT_KV_VERSION")
+            .map(|version| version.trim().to_lowercase())
+            .as_deref()
+        {
+            None | Some("2" | "v2") => Ok(KvVersion::V2),
+            Some("1" | "v1") => Ok(KvVersion::V1),
+            Some(other) => Err(ProviderInvocationError::Provider(format!(
+                "invalid kv_version '{other}', expected '1' or '2'"
+            ))),
+        }
+    }
+}
+
 /// KV-Vault configuration
 #[derive(Clone, Debug)]
 pub struct Config {
-    /// Token for connecting to vault, can be set in environment with VAULT_TOKEN.
-    /// Required
-    pub token: String,
+    /// Method of authenticating with vault, see [`AuthMethod`]
+    pub auth: AuthMethod,
     /// Url for connecting to vault, can be set in environment with VAULT_ADDR.
     /// Defaults to 'http://127.0.0.1:8200'
     pub addr: Url,
     /// Vault mount point, can be set with in environment with VAULT_MOUNT.
     /// Defaults to "secret/"
     pub mount: String,
+    /// Version of the KV secrets engine at `mount`, see [`KvVersion`]
+    pub kv_version: KvVersion,
+    /// Vault Enterprise namespace, sent in the `X-Vault-Namespace` header of every request,
+    /// can be set in environment with VAULT_NAMESPACE. Requests use the root namespace if unset
+    pub namespace: Option<String>,
     /// certificate files - path to CA certificate file(s). Setting this enables TLS
     /// The linkdef value `certs` and the environment variable `VAULT_CERTS`
     /// are parsed as a comma-separated string of file paths to generate this list.
@@ -34,46 +110,137 @@ impl Default for Config {
     }
 }
 
+/// Looks up a setting in the environment variable `env_var`, which takes precedence, and then
+/// the linkdef value `name` in lowercase or uppercase
+fn setting(values: &HashMap<String, String>, name: &str, env_var: &str) -> Option<String> {
+    env::var(env_var)
+        .ok()
+        .or_else(|| values.get(name).cloned())
+        .or_else(|| values.get(&name.to_uppercase()).cloned())
+}
+
+/// Looks up a required setting like [`setting`], failing if it is not set
+fn required_setting(
+    values: &HashMap<String, String>,
+    name: &str,
+    env_var: &str,
+) -> ProviderInvocationResult<String> {
+    setting(values, name, env_var).ok_or_else(|| {
+        ProviderInvocationError::Provider(format!("missing setting for '{name}' or {env_var}"))
+    })
+}
+
+impl AuthMethod {
+    /// initialize from linkdef values and environment
+    fn from_values(values: &HashMap<String, String>) -> ProviderInvocationResult<AuthMethod> {
+        let method = setting(values, "auth_method", "VAULT_AUTH_METHOD")
+            .unwrap_or_else(|| "token".to_string());
+        let mount = |default: &str| {
+            setting(values, "auth_mount", "VAULT_AUTH_MOUNT").unwrap_or_else(|| default.to_string())
+        };
+        match method.trim().to_lowercase().as_str() {
+            "token" => Ok(AuthMethod::Token(required_setting(
+                values,
+                "token",
+                "VAULT_TOKEN",
+            )?)),
+            "approle" => Ok(AuthMethod::AppRole {
+                mount: mount("approle"),
+                role_id: required_setting(values, "role_id", "VAULT_ROLE_ID")?,
+                secret_id: required_setting(values, "secret_id", "VAULT_SECRET_ID")?,
+            }),
+            "kubernetes" => Ok(AuthMethod::Kubernetes {
+                mount: mount("kubernetes"),
+                role: required_setting(values, "role", "VAULT_ROLE")?,
+                jwt_path: setting(values, "jwt_path", "VAULT_K8S_JWT_PATH")
+                    .unwrap_or_else(|| DEFAULT_K8S_JWT_PATH.to_string())
+                    .into(),
+            }),
+            "aws_iam" | "aws" => Ok(AuthMethod::AwsIam {
+                mount: mount("aws"),
+                role: setting(values, "role", "VAULT_ROLE"),
+                region: setting(values, "aws_region", "VAULT_AWS_REGION"),
+                server_id: setting(values, "iam_server_id", "VAULT_AWS_IAM_SERVER_ID"),
+            }),
+            other => Err(ProviderInvocationError::Provider(format!(
+                "invalid auth_method '{other}', expected one of 'token', 'approle', 'kubernetes' or 'aws_iam'"
+            ))),
+        }
+    }
+
+    /// Returns true if the method obtains tokens by logging in, which expire and must be renewed
+    pub fn requires_login(&self) -> bool {
+        !matches!(self, AuthMethod::Token(_))
+    }
+}
+
 impl Config {
     /// initialize from linkdef values, environment, and defaults
     pub fn from_values(values: &HashMap<String, String>) -> ProviderInvocationResult<Config> {
-        let addr = env::var("VAULT_ADDR")
-            .ok()
-            .or_else(|| values.get("addr").cloned())
-            .or_else(|| values.get("ADDR").cloned())
-            .unwrap_or_else(|| DEFAULT_VAULT_ADDR.to_string());
+        let addr =
+            setting(values, "addr", "VAULT_ADDR").unwrap_or_else(|| DEFAULT_VAULT_ADDR.to_string());
         let addr = addr.parse().unwrap_or_else(|_| {
-            eprintln!(
-                "Could not parse VAULT_ADDR [{addr}] as Url, using default of {}",
-                DEFAULT_VAULT_ADDR
+            warn!(
+                %addr,
+                "Could not parse VAULT_ADDR as Url, using default of {DEFAULT_VAULT_ADDR}"
             );
             DEFAULT_VAULT_ADDR.parse().unwrap()
         });
-        let token = env::var("VAULT_TOKEN")
-            .ok()
-            .or_else(|| values.get("token").cloned())
-            .or_else(|| values.get("TOKEN").cloned())
-            .ok_or_else(|| {
-                ProviderInvocationError::Provider(
-                    "missing setting for 'token' or VAULT_TOKEN".to_string(),
-                )
-            })?;
-        let mount = env::var("VAULT_MOUNT")
-            .ok()
-            .or_else(|| values.get("mount").cloned())
-            .or_else(|| values.get("MOUNT").cloned())
-            .unwrap_or_else(|| "secret".to_string());
-        let certs = env::var("VAULT_CERTS")
-            .ok()
-            .or_else(|| values.get("certs").cloned())
-            .or_else(|| values.get("CERTS").cloned())
+        let auth = AuthMethod::from_values(values)?;
+        let mount = setting(values, "mount", "VAULT_MOUNT").unwrap_or_else(|| "secret".to_string());
+        let kv_version = KvVersion::from_values(values)?;
+        let namespace = setting(values, "namespace", "VAULT_NAMESPACE")
+            .map(|namespace| namespace.trim().trim_matches('/').to_string())
+            .filter(|namespace| !namespace.is_empty());
+        let certs = setting(values, "certs", "VAULT_CERTS")
             .map(|certs| certs.split(',').map(|s| s.trim().to_string()).collect())
             .unwrap_or_default();
         Ok(Config {
             addr,
-            token,
+            auth,
             mount,
+            kv_version,
+            namespace,
             certs,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kv_version(value: Option<&str>) -> ProviderInvocationResult<KvVersion> {
+        let values = value
+            .map(|value| HashMap::from([("kv_version".to_string(), value.to_string())]))
+            .unwrap_or_default();
+        KvVersion::from_values(&values)
+    }
+
+    #[test]
+    fn kv_version_defaults_to_v2() {
+        assert_eq!(kv_version(None).unwrap(), KvVersion::V2);
+        assert_eq!(KvVersion::default(), KvVersion::V2);
+    }
+
+    #[test]
+    fn kv_version_is_parsed() {
+        for value in ["1", "v1", "V1", " 1 "] {
+            assert_eq!(kv_version(Some(value)).unwrap(), KvVersion::V1, "{value}");
+        }
+        for value in ["2", "v2", "V2"] {
+            assert_eq!(kv_version(Some(value)).unwrap(), KvVersion::V2, "{value}");
+        }
+        // The setting is also looked up in uppercase, like other linkdef values
+        let values = HashMap::from([("KV_VERSION".to_string(), "1".to_string())]);
+        assert_eq!(KvVersion::from_values(&values).unwrap(), KvVersion::V1);
+    }
+
+    #[test]
+    fn invalid_kv_versions_are_rejected() {
+        for value in ["3", "", "kv1", "v"] {
+            let err = kv_version(Some(value)).expect_err(value);
+            assert!(err.to_string().contains("invalid kv_version"), "{err}");
+        }
+    }
+}
@@ -11,6 +11,18 @@ pub enum VaultError {
     #[error("Key not found: namespace/key {namespace}/{path}")]
     NotFound { namespace: String, path: String },
 
+    /// Logging in with the configured auth method failed
+    #[error("Failed to authenticate with Vault: {0}")]
+    Auth(String),
+
+    /// The value cannot be stored in the KV secrets engine
+    #[error("Invalid value: {0}")]
+    InvalidValue(String),
+
+    /// The operation is not supported by the configured KV secrets engine version
+    #[error("Unsupported operation: {0}")]
+    Unsupported(String),
+
     /// All other errors
     #[error("An error occurred with the request")]
     Client {
@@ -3,12 +3,14 @@ use std::collections::HashMap;
 use async_trait::async_trait;
 use serde_json::Value;
 use tokio::sync::RwLock;
-use tracing::{debug, error, info, instrument};
+use tracing::{debug, error, info, instrument, warn};
 
-use wasmcloud_provider_sdk::core::LinkDefinition;
+use wasmcloud_provider_sdk::core::{HealthCheckRequest, HealthCheckResponse, LinkDefinition};
 use wasmcloud_provider_sdk::error::{ProviderInvocationError, ProviderInvocationResult};
+use wasmcloud_provider_sdk::provider_main::get_connection;
 use wasmcloud_provider_sdk::Context;
 
+pub(crate) mod aws;
 pub(crate) mod client;
 pub(crate) mod config;
 pub(crate) mod error;
@@ -20,6 +22,9 @@ use crate::error::VaultError;
 /// Token to indicate string data was passed during set
 pub const STRING_VALUE_MARKER: &str = "string_data___";
 
+/// Vault duration disabling the deletion of secret versions
+const NO_TTL: &str = "0s";
+
 wasmcloud_provider_wit_bindgen::generate!({
     impl_struct: KvVaultProvider,
     contract: "wasmcloud:keyvalue",
@@ -97,7 +102,10 @@ impl WasmcloudCapabilityProvider for KvVaultProvider {
             link_name = %ld.link_name,
             "adding link for actor",
         );
-        update_map.insert(ld.actor_id.to_string(), RwLock::new(client));
+        client.start_token_renewal(get_connection().task_supervisor());
+        if let Some(previous) = update_map.insert(ld.actor_id.to_string(), RwLock::new(client)) {
+            previous.read().await.stop_token_renewal();
+        }
         true
     }
 
@@ -107,6 +115,7 @@ impl WasmcloudCapabilityProvider for KvVaultProvider {
         let mut aw = self.actors.write().await;
         if let Some(client) = aw.remove(actor_id) {
             info!("deleting link for actor [{actor_id}]");
+            client.read().await.stop_token_renewal();
             drop(client)
         }
     }
@@ -116,9 +125,49 @@ impl WasmcloudCapabilityProvider for KvVaultProvider {
         let mut aw = self.actors.write().await;
         // Empty the actor link data and stop all servers
         for (_, client) in aw.drain() {
+            client.read().await.stop_token_renewal();
             drop(client)
         }
     }
+
+    /// Check the health of the Vault server of every link. The provider is reported unhealthy if
+    /// any of them is sealed, uninitialized or unreachable, or the Vault token of a link could not
+    /// be renewed, listing the affected actors
+    #[instrument(level = "trace", skip_all)]
+    async fn health_request(&self, _arg: &HealthCheckRequest) -> HealthCheckResponse {
+        let clients: Vec<(String, Client)> = {
+            let actors = self.actors.read().await;
+            let mut clients = Vec::with_capacity(actors.len());
+            for (actor_id, client) in actors.iter() {
+                clients.push((actor_id.clone(), client.read().await.clone()));
+            }
+            clients
+        };
+        let mut unhealthy = Vec::new();
+        for (actor_id, client) in clients {
+            let health = client.health().await;
+            if !health.is_healthy() {
+                warn!(%actor_id, %health, "vault health check failed");
+                unhealthy.push(format!("{actor_id}: vault {health}"));
+            }
+            if let Some(err) = client.renewal_error().await {
+                warn!(%actor_id, %err, "vault token renewal failed");
+                unhealthy.push(format!("{actor_id}: token renewal failed: {err}"));
+            }
+        }
+        if unhealthy.is_empty() {
+            HealthCheckResponse {
+                healthy: true,
+                message: None,
+            }
+        } else {
+            unhealthy.sort();
+            HealthCheckResponse {
+                healthy: false,
+                message: Some(unhealthy.join(", ")),
+            }
+        }
+    }
 }
 
 /// Handle KeyValue methods that interact with redis
@@ -251,10 +300,33 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
     }
 
     /// Sets the value of a key.
-    /// expiration times are not supported by this api and should be 0.
-    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.key))]
+    /// A non-zero expiration time (in seconds) sets `delete_version_after` in the metadata of the
+    /// key, so that this and later versions are deleted after that time. This requires KV v2.
+    /// Without an expiration time, only the value is written and the metadata of the key is left
+    /// untouched, so that links whose Vault policy only covers `<mount>/data/` can write values
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.key, expires = arg.expires))]
     async fn set(&self, ctx: Context, arg: SetRequest) -> ProviderInvocationResult<()> {
         let client = self.get_client(&ctx).await?;
+        // Vault computes the deletion time of a version when the version is written, so the TTL
+        // has to be set on the secret before the write. It is restored below if the write fails,
+        // so that the TTL of the secret only changes if the write succeeds
+        let previous_ttl = if arg.expires > 0 {
+            let ttl = format!("{}s", arg.expires);
+            let previous_ttl = match client.read_metadata(&arg.key).await {
+                Ok(metadata) if metadata.delete_version_after != ttl => {
+                    Some(metadata.delete_version_after)
+                }
+                Ok(_) => None,
+                Err(VaultError::NotFound { .. }) => Some(NO_TTL.to_string()),
+                Err(e) => return Err(e.into()),
+            };
+            if previous_ttl.is_some() {
+                client.set_delete_version_after(&arg.key, &ttl).await?;
+            }
+            previous_ttl
+        } else {
+            None
+        };
         let value: Value = serde_json::from_str(&arg.value).unwrap_or_else(|_| {
             let mut map = serde_json::Map::new();
             map.insert(
@@ -263,7 +335,16 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
             );
             Value::Object(map)
         });
-        match client.write_secret(&arg.key, &value).await {
+        let res = client.write_secret(&arg.key, &value).await;
+        if let (Err(_), Some(previous_ttl)) = (&res, previous_ttl) {
+            if let Err(e) = client
+                .set_delete_version_after(&arg.key, &previous_ttl)
+                .await
+            {
+                warn!(error = %e, "failed to restore TTL after failed write");
+            }
+        }
+        match res {
             Ok(metadata) => {
                 debug!(?metadata, "set returned metadata");
                 Ok(())
@@ -345,3 +426,83 @@ impl WasmcloudKeyvalueKeyValue for KvVaultProvider {
         ))
     }
 }
+
+/// Handle methods managing the versions of secrets, which require KV v2
+#[async_trait]
+impl WasmcloudKeyvalueVersioned for KvVaultProvider {
+    /// Soft-deletes versions of a secret, which can be restored by `undelete_versions`
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.key, versions = ?arg.versions))]
+    async fn delete_versions(
+        &self,
+        ctx: Context,
+        arg: KeyVersions,
+    ) -> ProviderInvocationResult<()> {
+        let client = self.get_client(&ctx).await?;
+        Ok(client.delete_versions(&arg.key, &arg.versions).await?)
+    }
+
+    /// Restores soft-deleted versions of a secret
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.key, versions = ?arg.versions))]
+    async fn undelete_versions(
+        &self,
+        ctx: Context,
+        arg: KeyVersions,
+    ) -> ProviderInvocationResult<()> {
+        let client = self.get_client(&ctx).await?;
+        Ok(client.undelete_versions(&arg.key, &arg.versions).await?)
+    }
+
+    /// Permanently destroys the data of versions of a secret
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, key = %arg.key, versions = ?arg.versions))]
+    async fn destroy_versions(
+        &self,
+        ctx: Context,
+        arg: KeyVersions,
+    ) -> ProviderInvocationResult<()> {
+        let client = self.get_client(&ctx).await?;
+        Ok(client.destroy_versions(&arg.key, &arg.versions).await?)
+    }
+
+    /// Returns the metadata of a secret and its versions, or `None` if the secret does not exist
+    #[instrument(level = "debug", skip(self, ctx, arg), fields(actor_id = ?ctx.actor, arg = %arg))]
+    async fn get_metadata(
+        &self,
+        ctx: Context,
+        arg: String,
+    ) -> ProviderInvocationResult<Option<KeyMetadata>> {
+        let client = self.get_client(&ctx).await?;
+        let metadata = match client.read_metadata(&arg).await {
+            Ok(metadata) => metadata,
+            Err(VaultError::NotFound { namespace, path }) => {
+                debug!(%namespace, %path, "vault metadata NotFound error");
+                return Ok(None);
+            }
+            Err(e) => {
+                debug!(error = %e, "vault read metadata: other error");
+                return Err(e.into());
+            }
+        };
+        // Vault keys the metadata of versions by the version number as a string
+        let mut versions: Vec<VersionMetadata> = metadata
+            .versions
+            .into_iter()
+            .filter_map(|(version, meta)| {
+                Some(VersionMetadata {
+                    version: version.parse().ok()?,
+                    created_time: meta.created_time,
+                    deletion_time: (!meta.deletion_time.is_empty()).then_some(meta.deletion_time),
+                    destroyed: meta.destroyed,
+                })
+            })
+            .collect();
+        versions.sort_by_key(|version| version.version);
+        Ok(Some(KeyMetadata {
+            key: arg,
+            created_time: metadata.created_time,
+            updated_time: metadata.updated_time,
+            current_version: metadata.current_version,
+            oldest_version: metadata.oldest_version,
+            versions,
+        }))
+    }
+}
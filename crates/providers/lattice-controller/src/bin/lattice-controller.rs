@@ -6,6 +6,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         Some("lattice-control-provider".to_string()),
     )?;
 
-    eprintln!("Lattice Controller capability provider exiting");
+    tracing::info!("Lattice Controller capability provider exiting");
     Ok(())
 }
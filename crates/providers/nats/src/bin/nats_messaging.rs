@@ -13,7 +13,7 @@ use opentelemetry_nats::{attach_span_context, NatsHeaderInjector};
 use serde::{Deserialize, Serialize};
 use tokio::sync::{OwnedSemaphorePermit, RwLock, Semaphore};
 use tokio::task::JoinHandle;
-use tracing::{debug, error, instrument, warn};
+use tracing::{debug, error, info, instrument, warn};
 use tracing_futures::Instrument;
 use wascap::prelude::KeyPair;
 use wasmcloud_compat::messaging::{PubMessage, ReplyMessage, RequestMessage, SubMessage};
@@ -34,7 +34,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let provider = generate_provider(host_data);
     start_provider(provider, Some("NATS Messaging Provider".to_string()))?;
 
-    eprintln!("NATS messaging provider exiting");
+    info!("NATS messaging provider exiting");
     Ok(())
 }
 
@@ -258,7 +258,13 @@ impl NatsMessagingProvider {
             // Listen for NATS message(s)
             while let Some(msg) = subscriber.next().await {
                 // Set up tracing context for the NATS message
-                let span = tracing::debug_span!("handle_message", actor_id = %link_def.actor_id);
+                let span = tracing::debug_span!(
+                    "handle_message",
+                    actor_id = %link_def.actor_id,
+                    otel.kind = "consumer",
+                    messaging.system = "nats",
+                    messaging.destination.name = %msg.subject,
+                );
 
                 span.in_scope(|| {
                     attach_span_context(&msg);
@@ -402,7 +408,7 @@ impl ProviderHandler for NatsMessagingProvider {
 
 /// Handle Messaging methods that interact with redis
 impl NatsMessagingProvider {
-    #[instrument(level = "debug", skip(self, ctx, msg), fields(actor_id = ?ctx.actor, subject = %msg.subject, reply_to = ?msg.reply_to, body_len = %msg.body.len()))]
+    #[instrument(level = "debug", skip(self, ctx, msg), fields(actor_id = ?ctx.actor, subject = %msg.subject, reply_to = ?msg.reply_to, body_len = %msg.body.len(), otel.kind = "producer", messaging.system = "nats", messaging.destination.name = %msg.subject))]
     async fn publish(&self, ctx: Context, msg: PubMessage) -> Result<(), String> {
         let actor_id = ctx
             .actor
@@ -420,6 +426,7 @@ impl NatsMessagingProvider {
             nats_bundle.client.clone()
         };
 
+        // Inject OTEL headers, so that subscribers can continue the trace
         let headers = NatsHeaderInjector::default_with_span().into();
 
         let res = match msg.reply_to.clone() {
@@ -447,7 +454,7 @@ impl NatsMessagingProvider {
         res
     }
 
-    #[instrument(level = "debug", skip(self, ctx, msg), fields(actor_id = ?ctx.actor, subject = %msg.subject))]
+    #[instrument(level = "debug", skip(self, ctx, msg), fields(actor_id = ?ctx.actor, subject = %msg.subject, otel.kind = "client", messaging.system = "nats", messaging.destination.name = %msg.subject))]
     async fn request(&self, ctx: Context, msg: RequestMessage) -> Result<ReplyMessage, String> {
         let actor_id = ctx
             .actor
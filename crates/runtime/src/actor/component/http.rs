@@ -118,18 +118,13 @@ impl Instance {
     pub async fn into_incoming_http(
         mut self,
     ) -> anyhow::Result<InterfaceInstance<incoming_http_bindings::IncomingHttp>> {
-        let bindings = if let Ok((bindings, _)) =
-            incoming_http_bindings::IncomingHttp::instantiate_async(
-                &mut self.store,
-                &self.component,
-                &self.linker,
-            )
-            .await
+        let instance = self.take_instance().await?;
+        let bindings = if let Ok(bindings) =
+            incoming_http_bindings::IncomingHttp::new(&mut self.store, &instance)
         {
             InterfaceBindings::Interface(bindings)
         } else {
-            self.as_guest_bindings()
-                .await
+            self.guest_bindings(&instance)
                 .map(InterfaceBindings::Guest)
                 .context("failed to instantiate `wasi:http/incoming-handler` interface")?
         };
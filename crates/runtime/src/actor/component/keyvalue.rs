@@ -1,6 +1,6 @@
 use super::{Ctx, Instance, TableResult};
 
-use crate::capability::keyvalue::{atomic, readwrite, types, wasi_cloud_error};
+use crate::capability::keyvalue::{atomic, batch, readwrite, types, wasi_cloud_error};
 use crate::capability::{KeyValueAtomic, KeyValueReadWrite};
 use crate::io::AsyncVec;
 
@@ -214,6 +214,100 @@ impl readwrite::Host for Ctx {
     }
 }
 
+#[async_trait]
+impl batch::Host for Ctx {
+    #[instrument]
+    async fn get_many(
+        &mut self,
+        bucket: types::Bucket,
+        keys: types::Keys,
+    ) -> anyhow::Result<Result<Vec<types::IncomingValue>>> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.get_many(bucket, keys).await {
+            Ok(values) => {
+                let mut incoming_values = Vec::with_capacity(values.len());
+                for (stream, size) in values {
+                    let value = self
+                        .table
+                        .push((stream, size))
+                        .context("failed to push stream and size")?;
+                    incoming_values.push(value.rep());
+                }
+                Ok(Ok(incoming_values))
+            }
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+
+    #[instrument]
+    async fn get_keys(&mut self, bucket: types::Bucket) -> anyhow::Result<types::Keys> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        // `get-keys` cannot return an error, so failures trap
+        self.handler
+            .get_keys(bucket)
+            .await
+            .context("failed to get keys")
+    }
+
+    #[instrument]
+    async fn set_many(
+        &mut self,
+        bucket: types::Bucket,
+        key_values: Vec<(types::Key, types::OutgoingValue)>,
+    ) -> anyhow::Result<Result<()>> {
+        let mut values: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)> =
+            Vec::with_capacity(key_values.len());
+        for (key, outgoing_value) in key_values {
+            let mut stream = self
+                .table
+                .get_outgoing_value(outgoing_value)
+                .context("failed to get outgoing value")?
+                .clone();
+            stream.rewind().await.context("failed to rewind stream")?;
+            values.push((key, Box::new(stream)));
+        }
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.set_many(bucket, values).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+
+    #[instrument]
+    async fn delete_many(
+        &mut self,
+        bucket: types::Bucket,
+        keys: types::Keys,
+    ) -> anyhow::Result<Result<()>> {
+        let bucket = self
+            .table
+            .get_bucket(bucket)
+            .context("failed to get bucket")?;
+        match self.handler.delete_many(bucket, keys).await {
+            Ok(()) => Ok(Ok(())),
+            Err(err) => {
+                let err = self.table.push_error(err).context("failed to push error")?;
+                Ok(Err(err))
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl types::Host for Ctx {
     #[instrument]
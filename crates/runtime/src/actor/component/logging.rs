@@ -38,20 +38,15 @@ impl Instance {
     pub async fn into_logging(
         mut self,
     ) -> anyhow::Result<InterfaceInstance<logging_bindings::Logging>> {
-        let bindings = if let Ok((bindings, _)) = logging_bindings::Logging::instantiate_async(
-            &mut self.store,
-            &self.component,
-            &self.linker,
-        )
-        .await
-        {
-            InterfaceBindings::Interface(bindings)
-        } else {
-            self.as_guest_bindings()
-                .await
-                .map(InterfaceBindings::Guest)
-                .context("failed to instantiate `wasi:logging/logging` interface")?
-        };
+        let instance = self.take_instance().await?;
+        let bindings =
+            if let Ok(bindings) = logging_bindings::Logging::new(&mut self.store, &instance) {
+                InterfaceBindings::Interface(bindings)
+            } else {
+                self.guest_bindings(&instance)
+                    .map(InterfaceBindings::Guest)
+                    .context("failed to instantiate `wasi:logging/logging` interface")?
+            };
         Ok(InterfaceInstance {
             store: Mutex::new(self.store),
             bindings,
@@ -1,4 +1,4 @@
-use crate::actor::claims;
+use crate::actor::{claims, AllowedNetwork};
 use crate::capability::{builtin, Bus, Interfaces, TargetInterface};
 use crate::Runtime;
 
@@ -15,7 +15,7 @@ use tokio::io::{AsyncRead, AsyncWrite};
 use tokio::sync::Mutex;
 use tracing::{error, instrument, trace};
 use wascap::jwt;
-use wasmtime::component::{Linker, Val};
+use wasmtime::component::{InstancePre, Linker, Val};
 use wasmtime_wasi::preview2::command::{self, Command};
 use wasmtime_wasi::preview2::pipe::{
     AsyncReadStream, AsyncWriteStream, ClosedInputStream, ClosedOutputStream,
@@ -33,6 +33,7 @@ mod http;
 mod keyvalue;
 mod logging;
 mod messaging;
+mod state;
 
 pub(crate) use self::http::incoming_http_bindings;
 pub(crate) use self::logging::logging_bindings;
@@ -203,6 +204,8 @@ struct Ctx {
     stdin: StdioStream<Box<dyn HostInputStream>>,
     stdout: StdioStream<Box<dyn HostOutputStream>>,
     stderr: StdioStream<Box<dyn HostOutputStream>>,
+    limits: wasmtime::StoreLimits,
+    allowed_network: Arc<[AllowedNetwork]>,
 }
 
 impl WasiView for Ctx {
@@ -232,11 +235,15 @@ impl Debug for Ctx {
 /// Pre-compiled actor [Component], which is cheapily-[Cloneable](Clone)
 #[derive(Clone)]
 pub struct Component {
-    component: wasmtime::component::Component,
     engine: wasmtime::Engine,
-    linker: Linker<Ctx>,
+    /// Component with all of its imports resolved, ready to be instantiated in a store
+    instance_pre: InstancePre<Ctx>,
     claims: Option<jwt::Claims<jwt::Actor>>,
     handler: builtin::HandlerBuilder,
+    /// Maximum amount of linear memory in bytes each instance may allocate
+    max_memory: Option<usize>,
+    /// Network destinations each instance may connect to
+    allowed_network: Arc<[AllowedNetwork]>,
 }
 
 impl Debug for Component {
@@ -244,6 +251,8 @@ impl Debug for Component {
         f.debug_struct("Component")
             .field("claims", &self.claims)
             .field("handler", &self.handler)
+            .field("max_memory", &self.max_memory)
+            .field("allowed_network", &self.allowed_network)
             .field("runtime", &"wasmtime")
             .finish_non_exhaustive()
     }
@@ -489,25 +498,43 @@ fn wasifill(
     }
 }
 
+/// Builds a [WasiCtx], which only permits outbound TCP connections to `allowed_network`
+fn build_wasi(builder: &mut WasiCtxBuilder, allowed_network: &[AllowedNetwork]) -> WasiCtx {
+    for AllowedNetwork { net, ports } in allowed_network {
+        // the end of the port range is exclusive, `None` allows all ports from the start
+        builder.insert_ip_net_port_range(*net, *ports.start(), ports.end().checked_add(1));
+    }
+    builder
+        .allow_ip_name_lookup(!allowed_network.is_empty())
+        .build()
+}
+
 #[instrument(level = "trace", skip_all)]
 fn instantiate(
-    component: wasmtime::component::Component,
     engine: &wasmtime::Engine,
-    linker: Linker<Ctx>,
+    instance_pre: InstancePre<Ctx>,
     handler: impl Into<builtin::Handler>,
+    max_memory: Option<usize>,
+    allowed_network: Arc<[AllowedNetwork]>,
 ) -> anyhow::Result<Instance> {
     let stdin = StdioStream::default();
     let stdout = StdioStream::default();
     let stderr = StdioStream::default();
 
     let table = Table::new();
-    let wasi = WasiCtxBuilder::new()
-        .args(&["main.wasm"]) // TODO: Configure argv[0]
-        .stdin(stdin.clone())
-        .stdout(stdout.clone())
-        .stderr(stderr.clone())
-        .build();
+    let wasi = build_wasi(
+        WasiCtxBuilder::new()
+            .args(&["main.wasm"]) // TODO: Configure argv[0]
+            .stdin(stdin.clone())
+            .stdout(stdout.clone())
+            .stderr(stderr.clone()),
+        &allowed_network,
+    );
     let handler = handler.into();
+    let mut limits = wasmtime::StoreLimitsBuilder::new();
+    if let Some(max_memory) = max_memory {
+        limits = limits.memory_size(max_memory);
+    }
     let ctx = Ctx {
         wasi,
         http: WasiHttpCtx,
@@ -516,12 +543,20 @@ fn instantiate(
         stdin,
         stdout,
         stderr,
+        limits: limits.build(),
+        allowed_network,
     };
-    let store = wasmtime::Store::new(engine, ctx);
+    let mut store = wasmtime::Store::new(engine, ctx);
+    if max_memory.is_some() {
+        store.limiter(|ctx| &mut ctx.limits);
+    }
+    // yield to the executor on every epoch increment, so that calls can be cancelled. This applies
+    // to all actors, see `EPOCH_INTERVAL`
+    store.epoch_deadline_async_yield_and_update(1);
     Ok(Instance {
-        component,
-        linker,
+        instance_pre,
         store,
+        instance: None,
     })
 }
 
@@ -558,15 +593,33 @@ impl Component {
 
         wasifill(&component, &resolve, world, &mut linker);
 
+        let instance_pre = linker
+            .instantiate_pre(&component)
+            .context("failed to pre-instantiate component")?;
         Ok(Self {
-            component,
             engine,
-            linker,
+            instance_pre,
             claims,
             handler: rt.handler.clone(),
+            max_memory: None,
+            allowed_network: Arc::default(),
         })
     }
 
+    /// Limit the amount of linear memory in bytes each [Instance] of this [Component] may
+    /// allocate. `None` removes the limit.
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>) -> &mut Self {
+        self.max_memory = max_memory;
+        self
+    }
+
+    /// Allow each [Instance] of this [Component] to connect to `allowed_network` using
+    /// `wasi:sockets`. Outbound connections are denied by default.
+    pub fn set_allowed_network(&mut self, allowed_network: Vec<AllowedNetwork>) -> &mut Self {
+        self.allowed_network = allowed_network.into();
+        self
+    }
+
     /// [Claims](jwt::Claims) associated with this [Component].
     #[instrument(level = "trace")]
     pub fn claims(&self) -> Option<&jwt::Claims<jwt::Actor>> {
@@ -584,7 +637,13 @@ impl Component {
     pub fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(self.component, &self.engine, self.linker, self.handler)?;
+        let instance = instantiate(
+            &self.engine,
+            self.instance_pre,
+            self.handler,
+            self.max_memory,
+            self.allowed_network,
+        )?;
         Ok((instance, self.claims))
     }
 
@@ -592,10 +651,11 @@ impl Component {
     #[instrument]
     pub fn instantiate(&self) -> anyhow::Result<Instance> {
         instantiate(
-            self.component.clone(),
             &self.engine,
-            self.linker.clone(),
+            self.instance_pre.clone(),
             self.handler.clone(),
+            self.max_memory,
+            Arc::clone(&self.allowed_network),
         )
     }
 
@@ -622,15 +682,17 @@ impl From<Component> for Option<jwt::Claims<jwt::Actor>> {
 
 /// An instance of a [Component]
 pub struct Instance {
-    component: wasmtime::component::Component,
-    linker: wasmtime::component::Linker<Ctx>,
+    instance_pre: InstancePre<Ctx>,
     store: wasmtime::Store<Ctx>,
+    /// Component instantiated ahead of time by [Instance::prepare], used by the next call
+    instance: Option<wasmtime::component::Instance>,
 }
 
 impl Debug for Instance {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("Instance")
             .field("runtime", &"wasmtime")
+            .field("prepared", &self.instance.is_some())
             .finish()
     }
 }
@@ -668,24 +730,59 @@ impl Instance {
         Ok(self)
     }
 
+    /// Instantiates the component ahead of the next call on this [`Instance`], so that the call
+    /// does not have to. Does nothing if the [`Instance`] is already prepared.
+    ///
+    /// # Errors
+    ///
+    /// Fails if instantiation of the component fails
+    #[instrument(level = "trace", skip_all)]
+    pub async fn prepare(&mut self) -> anyhow::Result<&mut Self> {
+        if self.instance.is_none() {
+            let instance = self
+                .instance_pre
+                .instantiate_async(&mut self.store)
+                .await
+                .context("failed to instantiate component")?;
+            self.instance = Some(instance);
+        }
+        Ok(self)
+    }
+
+    /// Whether the component was instantiated ahead of the next call by [`Self::prepare`]
+    #[must_use]
+    pub fn is_prepared(&self) -> bool {
+        self.instance.is_some()
+    }
+
+    /// Takes the component instance prepared by [`Self::prepare`] or instantiates the component
+    /// if there is none.
+    async fn take_instance(&mut self) -> anyhow::Result<wasmtime::component::Instance> {
+        self.prepare().await?;
+        self.instance.take().context("component instance missing")
+    }
+
     /// Instantiates and returns [`GuestBindings`] if exported by the [`Instance`].
     async fn as_guest_bindings(&mut self) -> anyhow::Result<GuestBindings> {
-        // Attempt to instantiate using guest bindings
-        let guest_err = match guest_bindings::Guest::instantiate_async(
-            &mut self.store,
-            &self.component,
-            &self.linker,
-        )
-        .await
-        {
-            Ok((bindings, _)) => return Ok(GuestBindings::Interface(bindings)),
+        let instance = self.take_instance().await?;
+        self.guest_bindings(&instance)
+    }
+
+    /// Returns [`GuestBindings`] if exported by the component `instance`.
+    fn guest_bindings(
+        &mut self,
+        instance: &wasmtime::component::Instance,
+    ) -> anyhow::Result<GuestBindings> {
+        // Attempt to bind guest exports
+        let guest_err = match guest_bindings::Guest::new(&mut self.store, instance) {
+            Ok(bindings) => return Ok(GuestBindings::Interface(bindings)),
             Err(e) => e,
         };
 
-        // Attempt to instantiate using only bindings available in command
-        match Command::instantiate_async(&mut self.store, &self.component, &self.linker).await {
-            Ok((bindings, _)) => Ok(GuestBindings::Command(bindings)),
-            // If neither of the above instantiations worked, the instance cannot be run
+        // Attempt to bind only exports available in command
+        match Command::new(&mut self.store, instance) {
+            Ok(bindings) => Ok(GuestBindings::Command(bindings)),
+            // If neither of the above bindings matched, the instance cannot be run
             Err(command_err) => bail!(
                 r#"failed to instantiate instance (no bindings satisfied exports):
 
@@ -750,12 +847,14 @@ impl GuestBindings {
         let res = match self {
             GuestBindings::Command(bindings) => {
                 let operation = operation.as_ref();
-                let wasi = WasiCtxBuilder::new()
-                    .args(&["main.wasm", operation]) // TODO: Configure argv[0]
-                    .stdin(ctx.stdin.clone())
-                    .stdout(ctx.stdout.clone())
-                    .stderr(ctx.stderr.clone())
-                    .build();
+                let wasi = build_wasi(
+                    WasiCtxBuilder::new()
+                        .args(&["main.wasm", operation]) // TODO: Configure argv[0]
+                        .stdin(ctx.stdin.clone())
+                        .stdout(ctx.stdout.clone())
+                        .stderr(ctx.stderr.clone()),
+                    &ctx.allowed_network,
+                );
                 let wasi = replace(&mut ctx.wasi, wasi);
                 trace!(operation, "call `wasi:command/command.run`");
                 let res = bindings
@@ -0,0 +1,62 @@
+use super::{Ctx, Instance};
+
+use crate::capability::state::store;
+use crate::capability::State;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::instrument;
+
+impl Instance {
+    /// Set [`State`] handler for this [Instance].
+    pub fn state(&mut self, state: Arc<dyn State + Send + Sync>) -> &mut Self {
+        self.handler_mut().replace_state(state);
+        self
+    }
+}
+
+#[async_trait]
+impl store::Host for Ctx {
+    #[instrument]
+    async fn get(&mut self, key: String) -> anyhow::Result<Result<Option<Vec<u8>>, String>> {
+        Ok(State::get(&self.handler, &key)
+            .await
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    #[instrument(skip(value))]
+    async fn set(&mut self, key: String, value: Vec<u8>) -> anyhow::Result<Result<(), String>> {
+        Ok(State::set(&self.handler, &key, value)
+            .await
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    #[instrument]
+    async fn delete(&mut self, key: String) -> anyhow::Result<Result<(), String>> {
+        Ok(State::delete(&self.handler, &key)
+            .await
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    #[instrument]
+    async fn exists(&mut self, key: String) -> anyhow::Result<Result<bool, String>> {
+        Ok(State::exists(&self.handler, &key)
+            .await
+            .map_err(|err| format!("{err:#}")))
+    }
+
+    #[instrument]
+    async fn keys(&mut self) -> anyhow::Result<Result<Vec<String>, String>> {
+        Ok(self.handler.keys().await.map_err(|err| format!("{err:#}")))
+    }
+
+    #[instrument]
+    async fn increment(&mut self, key: String, delta: i64) -> anyhow::Result<Result<i64, String>> {
+        Ok(self
+            .handler
+            .increment(&key, delta)
+            .await
+            .map_err(|err| format!("{err:#}")))
+    }
+}
@@ -13,11 +13,12 @@ pub use module::{
 use crate::capability::logging::logging;
 use crate::capability::{
     Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging, Messaging,
-    OutgoingHttp,
+    OutgoingHttp, State,
 };
 use crate::Runtime;
 
 use core::fmt::Debug;
+use core::ops::RangeInclusive;
 
 use std::sync::Arc;
 
@@ -35,6 +36,15 @@ pub struct Config {
     pub require_signature: bool,
 }
 
+/// Network destinations an actor is allowed to establish outbound connections to
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AllowedNetwork {
+    /// Allowed destination addresses
+    pub net: ipnet::IpNet,
+    /// Allowed destination ports
+    pub ports: RangeInclusive<u16>,
+}
+
 /// Extracts and validates claims contained within `WebAssembly` binary, if such are found
 fn claims(wasm: impl AsRef<[u8]>) -> Result<Option<jwt::Claims<jwt::Actor>>> {
     let Some(claims) = extract_claims(wasm).context("failed to extract module claims")? else {
@@ -117,6 +127,30 @@ impl Actor {
         }
     }
 
+    /// Limit the amount of linear memory in bytes each [Instance] of this [Actor] may allocate.
+    /// `None` removes the limit.
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.set_max_memory(max_memory);
+            }
+            Self::Component(component) => {
+                component.set_max_memory(max_memory);
+            }
+        }
+        self
+    }
+
+    /// Allow each [Instance] of this [Actor] to connect to `allowed_network` using `wasi:sockets`.
+    /// Outbound connections are denied by default. Module actors have no network access and
+    /// ignore this setting.
+    pub fn set_allowed_network(&mut self, allowed_network: Vec<AllowedNetwork>) -> &mut Self {
+        if let Self::Component(component) = self {
+            component.set_allowed_network(allowed_network);
+        }
+        self
+    }
+
     /// Like [Self::instantiate], but moves the [Actor].
     #[instrument]
     pub async fn into_instance(self) -> anyhow::Result<Instance> {
@@ -431,6 +465,19 @@ impl Instance {
         self
     }
 
+    /// Set [`State`] handler for this [Instance].
+    pub fn state(&mut self, state: Arc<dyn State + Send + Sync>) -> &mut Self {
+        match self {
+            Self::Module(module) => {
+                module.state(state);
+            }
+            Self::Component(component) => {
+                component.state(state);
+            }
+        }
+        self
+    }
+
     /// Set actor stderr stream. If another stderr was set, it is replaced and the old one is flushed and shut down if supported by underlying actor implementation.
     ///
     /// # Errors
@@ -451,6 +498,28 @@ impl Instance {
         Ok(self)
     }
 
+    /// Instantiate the underlying component ahead of the next [`Instance::call`], so that the
+    /// call does not have to. Modules are instantiated on construction, so this is a no-op for them.
+    ///
+    /// # Errors
+    ///
+    /// Fails if instantiation of the component fails
+    pub async fn prepare(&mut self) -> anyhow::Result<&mut Self> {
+        if let Self::Component(component) = self {
+            component.prepare().await?;
+        }
+        Ok(self)
+    }
+
+    /// Whether the next [`Instance::call`] can skip instantiation
+    #[must_use]
+    pub fn is_prepared(&self) -> bool {
+        match self {
+            Self::Module(..) => true,
+            Self::Component(component) => component.is_prepared(),
+        }
+    }
+
     /// Invoke an operation on an [Instance] producing a response
     ///
     /// # Errors
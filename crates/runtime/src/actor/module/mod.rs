@@ -6,7 +6,7 @@ use crate::actor::claims;
 use crate::capability::logging::logging;
 use crate::capability::{
     builtin, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging, Messaging,
-    OutgoingHttp,
+    OutgoingHttp, State,
 };
 use crate::io::AsyncVec;
 use crate::Runtime;
@@ -122,6 +122,7 @@ impl<T: AsyncWrite + Sync + Send + Unpin + 'static> WasiFile for AsyncWritePipe<
 struct Ctx {
     wasi: wasmtime_wasi::WasiCtx,
     wasmbus: wasmbus::Ctx,
+    limits: wasmtime::StoreLimits,
 }
 
 impl Debug for Ctx {
@@ -147,6 +148,8 @@ pub struct Module {
     claims: Option<jwt::Claims<jwt::Actor>>,
     config: Config,
     handler: builtin::HandlerBuilder,
+    /// Maximum amount of linear memory in bytes each instance may allocate
+    max_memory: Option<usize>,
 }
 
 impl Debug for Module {
@@ -154,6 +157,7 @@ impl Debug for Module {
         f.debug_struct("Module")
             .field("claims", &self.claims)
             .field("config", &self.config)
+            .field("max_memory", &self.max_memory)
             .field("handler", &self.handler)
             .field("runtime", &"wasmtime")
             .finish_non_exhaustive()
@@ -165,18 +169,30 @@ async fn instantiate(
     mut linker: Linker<Ctx>,
     config: &Config,
     handler: impl Into<builtin::Handler>,
+    max_memory: Option<usize>,
 ) -> anyhow::Result<Instance> {
     let mut wasi = WasiCtxBuilder::new();
     let wasi = wasi
         .arg("main.wasm")
         .context("failed to set argv[0]")?
         .build();
+    let mut limits = wasmtime::StoreLimitsBuilder::new();
+    if let Some(max_memory) = max_memory {
+        limits = limits.memory_size(max_memory);
+    }
     let ctx = Ctx {
         wasi,
         wasmbus: wasmbus::Ctx::new(handler),
+        limits: limits.build(),
     };
 
     let mut store = wasmtime::Store::new(module.engine(), ctx);
+    if max_memory.is_some() {
+        store.limiter(|ctx| &mut ctx.limits);
+    }
+    // yield to the executor on every epoch increment, so that calls can be cancelled. This applies
+    // to all actors, see `EPOCH_INTERVAL`
+    store.epoch_deadline_async_yield_and_update(1);
     let memory = wasmtime::Memory::new(
         &mut store,
         wasmtime::MemoryType::new(config.min_memory_pages, config.max_memory_pages),
@@ -228,9 +244,17 @@ impl Module {
             claims,
             handler: rt.handler.clone(),
             config: rt.module_config,
+            max_memory: None,
         })
     }
 
+    /// Limit the amount of linear memory in bytes each [Instance] of this [Module] may allocate.
+    /// `None` removes the limit.
+    pub fn set_max_memory(&mut self, max_memory: Option<usize>) -> &mut Self {
+        self.max_memory = max_memory;
+        self
+    }
+
     /// [Claims](jwt::Claims) associated with this [Module].
     #[instrument(level = "trace")]
     pub fn claims(&self) -> Option<&jwt::Claims<jwt::Actor>> {
@@ -240,7 +264,14 @@ impl Module {
     /// Like [Self::instantiate], but moves the [Module].
     #[instrument]
     pub async fn into_instance(self) -> anyhow::Result<Instance> {
-        instantiate(&self.module, self.linker, &self.config, self.handler).await
+        instantiate(
+            &self.module,
+            self.linker,
+            &self.config,
+            self.handler,
+            self.max_memory,
+        )
+        .await
     }
 
     /// Like [Self::instantiate], but moves the [Module] and returns the associated [jwt::Claims].
@@ -248,7 +279,14 @@ impl Module {
     pub async fn into_instance_claims(
         self,
     ) -> anyhow::Result<(Instance, Option<jwt::Claims<jwt::Actor>>)> {
-        let instance = instantiate(&self.module, self.linker, &self.config, self.handler).await?;
+        let instance = instantiate(
+            &self.module,
+            self.linker,
+            &self.config,
+            self.handler,
+            self.max_memory,
+        )
+        .await?;
         Ok((instance, self.claims))
     }
 
@@ -260,6 +298,7 @@ impl Module {
             self.linker.clone(),
             &self.config,
             self.handler.clone(),
+            self.max_memory,
         )
         .await
     }
@@ -373,6 +412,12 @@ impl Instance {
         self
     }
 
+    /// Set [`State`] handler for this [Instance].
+    pub fn state(&mut self, state: Arc<dyn State + Send + Sync>) -> &mut Self {
+        self.handler_mut().replace_state(state);
+        self
+    }
+
     /// Set actor stderr stream. If another stderr was set, it is replaced.
     pub fn stderr(&mut self, stderr: impl AsyncWrite + Send + Sync + Unpin + 'static) -> &mut Self {
         let stderr = AsyncWritePipe(Arc::new(Mutex::new(stderr)));
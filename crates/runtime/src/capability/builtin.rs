@@ -28,6 +28,7 @@ pub struct Handler {
     keyvalue_readwrite: Option<Arc<dyn KeyValueReadWrite + Sync + Send>>,
     logging: Option<Arc<dyn Logging + Sync + Send>>,
     messaging: Option<Arc<dyn Messaging + Sync + Send>>,
+    state: Option<Arc<dyn State + Sync + Send>>,
 }
 
 impl Debug for Handler {
@@ -41,6 +42,7 @@ impl Debug for Handler {
             .field("logging", &format_opt(&self.logging))
             .field("messaging", &format_opt(&self.messaging))
             .field("outgoing_http", &format_opt(&self.outgoing_http))
+            .field("state", &format_opt(&self.state))
             .finish()
     }
 }
@@ -83,6 +85,10 @@ impl Handler {
         proxy(&self.messaging, "Messaging", method)
     }
 
+    fn proxy_state(&self, method: &str) -> anyhow::Result<&Arc<dyn State + Sync + Send>> {
+        proxy(&self.state, "State", method)
+    }
+
     /// Replace [`Blobstore`] handler returning the old one, if such was set
     pub fn replace_blobstore(
         &mut self,
@@ -146,6 +152,14 @@ impl Handler {
     ) -> Option<Arc<dyn OutgoingHttp + Send + Sync>> {
         self.outgoing_http.replace(outgoing_http)
     }
+
+    /// Replace [`State`] handler returning the old one, if such was set
+    pub fn replace_state(
+        &mut self,
+        state: Arc<dyn State + Send + Sync>,
+    ) -> Option<Arc<dyn State + Send + Sync>> {
+        self.state.replace(state)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -430,7 +444,7 @@ pub trait KeyValueAtomic {
 }
 
 #[async_trait]
-/// `wasi:keyvalue/readwrite` implementation
+/// `wasi:keyvalue/readwrite` and `wasi:keyvalue/batch` implementation
 pub trait KeyValueReadWrite {
     /// Handle `wasi:keyvalue/readwrite.get`
     async fn get(
@@ -452,6 +466,57 @@ pub trait KeyValueReadWrite {
 
     /// Handle `wasi:keyvalue/readwrite.exists`
     async fn exists(&self, bucket: &str, key: String) -> anyhow::Result<bool>;
+
+    /// Handle `wasi:keyvalue/batch.get-many`, which fails if any of the `keys` does not exist.
+    /// By default, every key is read using [`Self::get`]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        let mut values = Vec::with_capacity(keys.len());
+        for key in keys {
+            let value = self
+                .get(bucket, key.clone())
+                .await
+                .with_context(|| format!("failed to get `{key}`"))?;
+            values.push(value);
+        }
+        Ok(values)
+    }
+
+    /// Handle `wasi:keyvalue/batch.get-keys`. Not supported by default
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        _ = bucket;
+        bail!("listing keys is not supported")
+    }
+
+    /// Handle `wasi:keyvalue/batch.set-many`. By default, every value is written using [`Self::set`]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        values: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        for (key, value) in values {
+            self.set(bucket, key.clone(), value)
+                .await
+                .with_context(|| format!("failed to set `{key}`"))?;
+        }
+        Ok(())
+    }
+
+    /// Handle `wasi:keyvalue/batch.delete-many`, which skips keys that do not exist. By default,
+    /// every existing key is deleted using [`Self::delete`]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        for key in keys {
+            if self.exists(bucket, key.clone()).await? {
+                self.delete(bucket, key.clone())
+                    .await
+                    .with_context(|| format!("failed to delete `{key}`"))?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -500,6 +565,31 @@ pub trait OutgoingHttp {
     ) -> anyhow::Result<::http::Response<Box<dyn AsyncRead + Sync + Send + Unpin>>>;
 }
 
+#[async_trait]
+/// `wasmcloud:state/store` implementation. Implementations are expected to scope the state to a
+/// single actor
+pub trait State {
+    /// Handle `wasmcloud:state/store.get`
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    /// Handle `wasmcloud:state/store.set`
+    async fn set(&self, key: &str, value: Vec<u8>) -> anyhow::Result<()>;
+
+    /// Handle `wasmcloud:state/store.delete`
+    async fn delete(&self, key: &str) -> anyhow::Result<()>;
+
+    /// Handle `wasmcloud:state/store.exists`
+    async fn exists(&self, key: &str) -> anyhow::Result<bool> {
+        self.get(key).await.map(|value| value.is_some())
+    }
+
+    /// Handle `wasmcloud:state/store.keys`
+    async fn keys(&self) -> anyhow::Result<Vec<String>>;
+
+    /// Handle `wasmcloud:state/store.increment`
+    async fn increment(&self, key: &str, delta: i64) -> anyhow::Result<i64>;
+}
+
 #[async_trait]
 impl Blobstore for Handler {
     #[instrument]
@@ -763,6 +853,42 @@ impl KeyValueReadWrite for Handler {
             .exists(bucket, key)
             .await
     }
+
+    #[instrument]
+    async fn get_many(
+        &self,
+        bucket: &str,
+        keys: Vec<String>,
+    ) -> anyhow::Result<Vec<(Box<dyn AsyncRead + Sync + Send + Unpin>, u64)>> {
+        self.proxy_keyvalue_readwrite("wasi:keyvalue/batch.get-many")?
+            .get_many(bucket, keys)
+            .await
+    }
+
+    #[instrument]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        self.proxy_keyvalue_readwrite("wasi:keyvalue/batch.get-keys")?
+            .get_keys(bucket)
+            .await
+    }
+
+    #[instrument(skip(values))]
+    async fn set_many(
+        &self,
+        bucket: &str,
+        values: Vec<(String, Box<dyn AsyncRead + Sync + Send + Unpin>)>,
+    ) -> anyhow::Result<()> {
+        self.proxy_keyvalue_readwrite("wasi:keyvalue/batch.set-many")?
+            .set_many(bucket, values)
+            .await
+    }
+
+    #[instrument]
+    async fn delete_many(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        self.proxy_keyvalue_readwrite("wasi:keyvalue/batch.delete-many")?
+            .delete_many(bucket, keys)
+            .await
+    }
 }
 
 #[async_trait]
@@ -834,6 +960,49 @@ impl OutgoingHttp for Handler {
     }
 }
 
+#[async_trait]
+impl State for Handler {
+    #[instrument]
+    async fn get(&self, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.proxy_state("wasmcloud:state/store.get")?
+            .get(key)
+            .await
+    }
+
+    #[instrument(skip(value))]
+    async fn set(&self, key: &str, value: Vec<u8>) -> anyhow::Result<()> {
+        self.proxy_state("wasmcloud:state/store.set")?
+            .set(key, value)
+            .await
+    }
+
+    #[instrument]
+    async fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.proxy_state("wasmcloud:state/store.delete")?
+            .delete(key)
+            .await
+    }
+
+    #[instrument]
+    async fn exists(&self, key: &str) -> anyhow::Result<bool> {
+        self.proxy_state("wasmcloud:state/store.exists")?
+            .exists(key)
+            .await
+    }
+
+    #[instrument]
+    async fn keys(&self) -> anyhow::Result<Vec<String>> {
+        self.proxy_state("wasmcloud:state/store.keys")?.keys().await
+    }
+
+    #[instrument]
+    async fn increment(&self, key: &str, delta: i64) -> anyhow::Result<i64> {
+        self.proxy_state("wasmcloud:state/store.increment")?
+            .increment(key, delta)
+            .await
+    }
+}
+
 /// A [Handler] builder used to configure it
 #[derive(Clone, Default)]
 pub(crate) struct HandlerBuilder {
@@ -853,6 +1022,8 @@ pub(crate) struct HandlerBuilder {
     pub messaging: Option<Arc<dyn Messaging + Sync + Send>>,
     /// [`OutgoingHttp`] handler
     pub outgoing_http: Option<Arc<dyn OutgoingHttp + Sync + Send>>,
+    /// [`State`] handler
+    pub state: Option<Arc<dyn State + Sync + Send>>,
 }
 
 impl HandlerBuilder {
@@ -931,6 +1102,14 @@ impl HandlerBuilder {
             ..self
         }
     }
+
+    /// Set [`State`] handler
+    pub fn state(self, state: Arc<impl State + Sync + Send + 'static>) -> Self {
+        Self {
+            state: Some(state),
+            ..self
+        }
+    }
 }
 
 impl Debug for HandlerBuilder {
@@ -944,6 +1123,7 @@ impl Debug for HandlerBuilder {
             .field("logging", &format_opt(&self.logging))
             .field("messaging", &format_opt(&self.messaging))
             .field("outgoing_http", &format_opt(&self.outgoing_http))
+            .field("state", &format_opt(&self.state))
             .finish()
     }
 }
@@ -959,6 +1139,7 @@ impl From<Handler> for HandlerBuilder {
             logging,
             messaging,
             outgoing_http,
+            state,
         }: Handler,
     ) -> Self {
         Self {
@@ -970,6 +1151,7 @@ impl From<Handler> for HandlerBuilder {
             logging,
             messaging,
             outgoing_http,
+            state,
         }
     }
 }
@@ -985,6 +1167,7 @@ impl From<HandlerBuilder> for Handler {
             logging,
             messaging,
             outgoing_http,
+            state,
         }: HandlerBuilder,
     ) -> Self {
         Self {
@@ -996,6 +1179,7 @@ impl From<HandlerBuilder> for Handler {
             keyvalue_readwrite,
             logging,
             messaging,
+            state,
         }
     }
 }
@@ -5,7 +5,7 @@ pub mod provider;
 
 pub use builtin::{
     ActorIdentifier, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging,
-    Messaging, OutgoingHttp, OutgoingHttpRequest, TargetEntity, TargetInterface,
+    Messaging, OutgoingHttp, OutgoingHttpRequest, State, TargetEntity, TargetInterface,
 };
 
 #[allow(clippy::doc_markdown)]
@@ -42,7 +42,7 @@ mod bindgen {
 pub use bindgen::wasi::{blobstore, keyvalue, logging};
 pub use bindgen::wasmcloud::{
     bus::{self, guest_config},
-    messaging,
+    messaging, state,
 };
 pub use bindgen::Interfaces;
 pub use wasmtime_wasi_http::bindings::http;
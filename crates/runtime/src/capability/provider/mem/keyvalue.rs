@@ -183,4 +183,14 @@ impl KeyValueReadWrite for KeyValue {
         let bucket = kv.get(bucket).context("bucket not found")?.read().await;
         Ok(bucket.contains_key(&key))
     }
+
+    #[instrument]
+    async fn get_keys(&self, bucket: &str) -> anyhow::Result<Vec<String>> {
+        let kv = self.0.read().await;
+        let Some(bucket) = kv.get(bucket) else {
+            return Ok(Vec::default());
+        };
+        let keys = bucket.read().await.keys().cloned().collect();
+        Ok(keys)
+    }
 }
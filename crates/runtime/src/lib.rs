@@ -17,7 +17,7 @@ pub mod runtime;
 /// wasmCloud I/O functionality
 pub mod io;
 
-pub use actor::{Actor, Config as ActorConfig, Instance as ActorInstance};
+pub use actor::{Actor, AllowedNetwork, Config as ActorConfig, Instance as ActorInstance};
 pub use runtime::*;
 
 pub use async_trait::async_trait;
@@ -1,17 +1,57 @@
 use crate::actor::ModuleConfig;
 use crate::capability::{
     builtin, Blobstore, Bus, IncomingHttp, KeyValueAtomic, KeyValueReadWrite, Logging, Messaging,
-    OutgoingHttp,
+    OutgoingHttp, State,
 };
 use crate::ActorConfig;
 
 use core::fmt;
 use core::fmt::Debug;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
 
 use std::sync::Arc;
+use std::thread;
 
 use anyhow::Context;
 
+/// Interval, at which the epoch of the engine is incremented. Running actors yield to the async
+/// executor on every increment, so that their calls can be cancelled, e.g. on timeout.
+///
+/// The epoch deadline is set on the stores of all actors, whether or not a maximum execution time
+/// is configured for them, so every actor is interrupted at least every 10 ms of execution. This
+/// only yields, calls are never trapped on the deadline, and allows a single engine to be shared
+/// by actors with and without limits
+const EPOCH_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Increments the epoch of an engine in a background thread until dropped
+struct EpochTicker(Arc<AtomicBool>);
+
+impl EpochTicker {
+    fn spawn(engine: wasmtime::Engine) -> anyhow::Result<Self> {
+        let stop = Arc::new(AtomicBool::new(false));
+        thread::Builder::new()
+            .name("wasmtime-epoch".into())
+            .spawn({
+                let stop = Arc::clone(&stop);
+                move || {
+                    while !stop.load(Ordering::Relaxed) {
+                        thread::sleep(EPOCH_INTERVAL);
+                        engine.increment_epoch();
+                    }
+                }
+            })
+            .context("failed to spawn epoch thread")?;
+        Ok(Self(stop))
+    }
+}
+
+impl Drop for EpochTicker {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
 /// [`RuntimeBuilder`] used to configure and build a [Runtime]
 #[derive(Clone, Default)]
 pub struct RuntimeBuilder {
@@ -28,6 +68,7 @@ impl RuntimeBuilder {
         let mut engine_config = wasmtime::Config::default();
         engine_config.async_support(true);
         engine_config.wasm_component_model(true);
+        engine_config.epoch_interruption(true);
         Self {
             engine_config,
             handler: builtin::HandlerBuilder::default(),
@@ -138,6 +179,15 @@ impl RuntimeBuilder {
         }
     }
 
+    /// Set a [`State`] handler to use for all actor instances unless overriden for the instance
+    #[must_use]
+    pub fn state(self, state: Arc<impl State + Sync + Send + 'static>) -> Self {
+        Self {
+            handler: self.handler.state(state),
+            ..self
+        }
+    }
+
     /// Turns this builder into a [`Runtime`]
     ///
     /// # Errors
@@ -146,8 +196,10 @@ impl RuntimeBuilder {
     pub fn build(self) -> anyhow::Result<Runtime> {
         let engine =
             wasmtime::Engine::new(&self.engine_config).context("failed to construct engine")?;
+        let epoch_ticker = EpochTicker::spawn(engine.clone())?;
         Ok(Runtime {
             engine,
+            _epoch_ticker: Arc::new(epoch_ticker),
             handler: self.handler,
             actor_config: self.actor_config,
             module_config: self.module_config,
@@ -167,6 +219,8 @@ impl TryFrom<RuntimeBuilder> for Runtime {
 #[derive(Clone)]
 pub struct Runtime {
     pub(crate) engine: wasmtime::Engine,
+    /// Stops incrementing the epoch of `engine` once the last clone of the [Runtime] is dropped
+    _epoch_ticker: Arc<EpochTicker>,
     pub(crate) handler: builtin::HandlerBuilder,
     pub(crate) actor_config: ActorConfig,
     pub(crate) module_config: ModuleConfig,
@@ -118,6 +118,9 @@ impl From<TraceContextInjector> for TraceContext {
 /// the current tracing Span.  If you want to do something more advanced, use the
 /// [`TraceContextExtractor`] type directly
 ///
+/// The parent of a disabled span cannot be set, so spans continuing incoming traces must be enabled
+/// at the default `info` level, even if they are otherwise only of interest when debugging.
+///
 /// **WARNING**: To avoid performance issues, this function does not check if you have empty tracing
 /// headers. **If you pass an empty Extractor to this function, you will orphan the current span
 /// hierarchy.**
@@ -51,12 +51,19 @@ impl<'a> Drop for LockedWriter<'a> {
 
 static STDERR: OnceCell<std::io::Stderr> = OnceCell::new();
 
+/// An additional layer to install by [`configure_tracing_with_layer`], which receives all events
+/// and spans enabled by the level filter, e.g. to forward logs to a remote destination
+pub type ExtraLayer = Box<dyn Layer<Layered<EnvFilter, Registry>> + Send + Sync>;
+
 #[cfg(feature = "otel")]
 const TRACING_PATH: &str = "/v1/traces";
 
 #[cfg(feature = "otel")]
 const DEFAULT_TRACING_ENDPOINT: &str = "http://localhost:55681/v1/traces";
 
+#[cfg(feature = "otel")]
+const DEFAULT_GRPC_TRACING_ENDPOINT: &str = "http://localhost:4317";
+
 /// A struct that allows us to dynamically choose JSON formatting without using dynamic dispatch.
 /// This is just so we avoid any sort of possible slow down in logging code
 enum JsonOrNot {
@@ -85,12 +92,31 @@ where
     }
 }
 
-#[cfg(not(feature = "otel"))]
+#[allow(clippy::missing_errors_doc)] // TODO: Document errors
 pub fn configure_tracing(
+    service_name: String,
+    otel_config: &OtelConfig,
+    structured_logging_enabled: bool,
+    log_level_override: Option<&Level>,
+) -> anyhow::Result<()> {
+    configure_tracing_with_layer(
+        service_name,
+        otel_config,
+        structured_logging_enabled,
+        log_level_override,
+        None,
+    )
+}
+
+/// Like [`configure_tracing`], additionally installing `extra_layer`, if set
+#[cfg(not(feature = "otel"))]
+#[allow(clippy::missing_errors_doc)] // TODO: Document errors
+pub fn configure_tracing_with_layer(
     _: String,
     _: &OtelConfig,
     structured_logging_enabled: bool,
     log_level_override: Option<&Level>,
+    extra_layer: Option<ExtraLayer>,
 ) -> anyhow::Result<()> {
     STDERR
         .set(std::io::stderr())
@@ -101,24 +127,32 @@ pub fn configure_tracing(
 
     let res = if structured_logging_enabled {
         let log_layer = get_json_log_layer()?;
-        let layered = base_reg.with(level_filter).with(log_layer);
+        let layered = base_reg
+            .with(level_filter)
+            .with(extra_layer)
+            .with(log_layer);
         tracing::subscriber::set_global_default(layered)
     } else {
         let log_layer = get_default_log_layer()?;
-        let layered = base_reg.with(level_filter).with(log_layer);
+        let layered = base_reg
+            .with(level_filter)
+            .with(extra_layer)
+            .with(log_layer);
         tracing::subscriber::set_global_default(layered)
     };
 
     res.map_err(|e| anyhow::anyhow!(e).context("Logger was already created"))
 }
 
+/// Like [`configure_tracing`], additionally installing `extra_layer`, if set
 #[cfg(feature = "otel")]
 #[allow(clippy::missing_errors_doc)] // TODO: Document errors
-pub fn configure_tracing(
+pub fn configure_tracing_with_layer(
     service_name: String,
     otel_config: &OtelConfig,
     structured_logging_enabled: bool,
     log_level_override: Option<&Level>,
+    extra_layer: Option<ExtraLayer>,
 ) -> anyhow::Result<()> {
     STDERR
         .set(std::io::stderr())
@@ -132,17 +166,7 @@ pub fn configure_tracing(
         .as_ref()
         .map(|s| s.to_ascii_lowercase());
     let maybe_tracer = match exporter.as_deref() {
-        Some("otlp") => {
-            let endpoint = if let Some(ref endpoint) = otel_config.exporter_otlp_endpoint {
-                endpoint.clone()
-            } else {
-                eprintln!(
-                    "OTEL exporter endpoint not set, defaulting to '{DEFAULT_TRACING_ENDPOINT}'"
-                );
-                DEFAULT_TRACING_ENDPOINT.to_string()
-            };
-            Some(get_tracer(endpoint, service_name))
-        }
+        Some("otlp") => Some(get_tracer(otel_config, service_name)),
         Some(exporter) => {
             eprintln!("unsupported OTEL exporter: '{exporter}'");
             None
@@ -156,6 +180,7 @@ pub fn configure_tracing(
             let tracing_layer = tracing_opentelemetry::layer().with_tracer(t);
             let layered = base_reg
                 .with(level_filter)
+                .with(extra_layer)
                 .with(log_layer)
                 .with(tracing_layer);
             tracing::subscriber::set_global_default(layered)
@@ -165,6 +190,7 @@ pub fn configure_tracing(
             let tracing_layer = tracing_opentelemetry::layer().with_tracer(t);
             let layered = base_reg
                 .with(level_filter)
+                .with(extra_layer)
                 .with(log_layer)
                 .with(tracing_layer);
             tracing::subscriber::set_global_default(layered)
@@ -172,23 +198,35 @@ pub fn configure_tracing(
         (Some(Err(err)), true) => {
             eprintln!("Unable to configure OTEL tracing, defaulting to logging only: {err:?}");
             let log_layer = get_json_log_layer()?;
-            let layered = base_reg.with(level_filter).with(log_layer);
+            let layered = base_reg
+                .with(level_filter)
+                .with(extra_layer)
+                .with(log_layer);
             tracing::subscriber::set_global_default(layered)
         }
         (Some(Err(err)), false) => {
             eprintln!("Unable to configure OTEL tracing, defaulting to logging only: {err:?}");
             let log_layer = get_default_log_layer()?;
-            let layered = base_reg.with(level_filter).with(log_layer);
+            let layered = base_reg
+                .with(level_filter)
+                .with(extra_layer)
+                .with(log_layer);
             tracing::subscriber::set_global_default(layered)
         }
         (None, true) => {
             let log_layer = get_json_log_layer()?;
-            let layered = base_reg.with(level_filter).with(log_layer);
+            let layered = base_reg
+                .with(level_filter)
+                .with(extra_layer)
+                .with(log_layer);
             tracing::subscriber::set_global_default(layered)
         }
         (None, false) => {
             let log_layer = get_default_log_layer()?;
-            let layered = base_reg.with(level_filter).with(log_layer);
+            let layered = base_reg
+                .with(level_filter)
+                .with(extra_layer)
+                .with(log_layer);
             tracing::subscriber::set_global_default(layered)
         }
     };
@@ -196,39 +234,127 @@ pub fn configure_tracing(
     res.map_err(|e| anyhow::anyhow!(e).context("Logger/tracer was already created"))
 }
 
+/// Attribute of a span, which overrides the ratio of traces to sample, if the span starts a trace.
+/// Spans with a parent always follow the sampling decision of the parent, which is propagated in
+/// the trace context.
+pub const SAMPLER_RATIO_ATTRIBUTE: &str = "sampling.ratio";
+
+/// Samples root spans by trace ID with the ratio set in the [`SAMPLER_RATIO_ATTRIBUTE`] of the
+/// span, if any, or the configured default
+#[cfg(feature = "otel")]
+#[derive(Clone, Debug)]
+struct ComponentSampler {
+    ratio: f64,
+}
+
+#[cfg(feature = "otel")]
+impl opentelemetry::sdk::trace::ShouldSample for ComponentSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&opentelemetry::Context>,
+        trace_id: opentelemetry::trace::TraceId,
+        name: &str,
+        span_kind: &opentelemetry::trace::SpanKind,
+        attributes: &[opentelemetry::KeyValue],
+        links: &[opentelemetry::trace::Link],
+    ) -> opentelemetry::trace::SamplingResult {
+        use opentelemetry::Value;
+
+        let ratio = attributes
+            .iter()
+            .find(|kv| kv.key.as_str() == SAMPLER_RATIO_ATTRIBUTE)
+            .and_then(|kv| match &kv.value {
+                Value::F64(ratio) => Some(*ratio),
+                Value::String(ratio) => ratio.as_str().parse().ok(),
+                _ => None,
+            })
+            .map_or(self.ratio, |ratio| ratio.clamp(0.0, 1.0));
+        opentelemetry::sdk::trace::Sampler::TraceIdRatioBased(ratio).should_sample(
+            parent_context,
+            trace_id,
+            name,
+            span_kind,
+            attributes,
+            links,
+        )
+    }
+}
+
 #[cfg(feature = "otel")]
 fn get_tracer(
-    mut tracing_endpoint: String,
+    otel_config: &OtelConfig,
     service_name: String,
 ) -> Result<opentelemetry::sdk::trace::Tracer, opentelemetry::trace::TraceError> {
-    use opentelemetry_otlp::WithExportConfig;
+    use opentelemetry::sdk::trace::Sampler;
+    use opentelemetry_otlp::{SpanExporterBuilder, WithExportConfig};
 
-    if !tracing_endpoint.ends_with(TRACING_PATH) {
-        tracing_endpoint.push_str(TRACING_PATH);
+    let protocol = otel_config
+        .exporter_otlp_protocol
+        .as_deref()
+        .map(str::to_ascii_lowercase);
+    let exporter: SpanExporterBuilder = match protocol.as_deref() {
+        Some("grpc") => {
+            let endpoint = otel_config.exporter_otlp_endpoint.clone().unwrap_or_else(|| {
+                eprintln!(
+                    "OTEL exporter endpoint not set, defaulting to '{DEFAULT_GRPC_TRACING_ENDPOINT}'"
+                );
+                DEFAULT_GRPC_TRACING_ENDPOINT.to_string()
+            });
+            opentelemetry_otlp::new_exporter()
+                .tonic()
+                .with_endpoint(endpoint)
+                .into()
+        }
+        protocol => {
+            if let Some(protocol) = protocol.filter(|p| *p != "http/protobuf") {
+                eprintln!("unsupported OTLP protocol: '{protocol}', defaulting to 'http/protobuf'");
+            }
+            let mut endpoint = otel_config.exporter_otlp_endpoint.clone().unwrap_or_else(|| {
+                eprintln!("OTEL exporter endpoint not set, defaulting to '{DEFAULT_TRACING_ENDPOINT}'");
+                DEFAULT_TRACING_ENDPOINT.to_string()
+            });
+            if !endpoint.ends_with(TRACING_PATH) {
+                endpoint.push_str(TRACING_PATH);
+            };
+            opentelemetry_otlp::new_exporter()
+                .http()
+                .with_endpoint(endpoint)
+                .with_protocol(opentelemetry_otlp::Protocol::HttpBinary)
+                .into()
+        }
     };
+    let sampler = Sampler::ParentBased(Box::new(ComponentSampler {
+        ratio: otel_config
+            .traces_sampler_ratio
+            .map_or(1.0, |ratio| ratio.clamp(0.0, 1.0)),
+    }));
+    let mut resource = vec![opentelemetry::KeyValue::new("service.name", service_name)];
+    resource.extend(
+        otel_config
+            .resource_attributes
+            .iter()
+            .filter(|(k, _)| *k != "service.name")
+            .map(|(k, v)| opentelemetry::KeyValue::new(k.clone(), v.clone())),
+    );
     opentelemetry_otlp::new_pipeline()
         .tracing()
-        .with_exporter(
-            opentelemetry_otlp::new_exporter()
-                .http()
-                .with_endpoint(tracing_endpoint)
-                .with_protocol(opentelemetry_otlp::Protocol::HttpBinary),
-        )
+        .with_exporter(exporter)
         .with_trace_config(
             opentelemetry::sdk::trace::config()
-                .with_sampler(opentelemetry::sdk::trace::Sampler::AlwaysOn)
+                .with_sampler(sampler)
                 .with_id_generator(opentelemetry::sdk::trace::RandomIdGenerator::default())
                 .with_max_events_per_span(64)
                 .with_max_attributes_per_span(16)
                 .with_max_events_per_span(16)
-                .with_resource(opentelemetry::sdk::Resource::new(vec![
-                    opentelemetry::KeyValue::new("service.name", service_name),
-                ])),
+                .with_resource(opentelemetry::sdk::Resource::new(resource)),
         )
         .install_batch(opentelemetry::runtime::Tokio)
 }
 
-fn get_default_log_layer() -> anyhow::Result<impl Layer<Layered<EnvFilter, Registry>>> {
+fn get_default_log_layer<S>() -> anyhow::Result<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
     let stderr = STDERR.get().context("stderr not initialized")?;
     Ok(tracing_subscriber::fmt::layer()
         .with_writer(LockedWriter::new)
@@ -237,7 +363,10 @@ fn get_default_log_layer() -> anyhow::Result<impl Layer<Layered<EnvFilter, Regis
         .fmt_fields(DefaultFields::new()))
 }
 
-fn get_json_log_layer() -> anyhow::Result<impl Layer<Layered<EnvFilter, Registry>>> {
+fn get_json_log_layer<S>() -> anyhow::Result<impl Layer<S>>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
     let stderr = STDERR.get().context("stderr not initialized")?;
     Ok(tracing_subscriber::fmt::layer()
         .with_writer(LockedWriter::new)
@@ -79,6 +79,11 @@ pub struct Actor {
     /// Indicates whether this module is a capability provider
     #[serde(rename = "prov", default = "default_as_false")]
     pub provider: bool,
+
+    /// Arbitrary custom claims, e.g. the team, tier or tenant the actor belongs to. Hosts can be
+    /// configured to forward selected custom claims to capability providers the actor invokes
+    #[serde(rename = "custom", default, skip_serializing_if = "Option::is_none")]
+    pub custom: Option<HashMap<String, String>>,
 }
 
 /// The claims metadata corresponding to a capability provider
@@ -142,6 +147,9 @@ pub struct Invocation {
     /// Hash of the invocation to which these claims belong
     #[serde(rename = "hash")]
     pub invocation_hash: String,
+    /// Custom claims of the originating actor forwarded by the host, which signed this invocation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub origin_claims: Option<HashMap<String, String>>,
 }
 
 /// Represents a set of [RFC 7519](https://tools.ietf.org/html/rfc7519) compliant JSON Web Token
@@ -560,6 +568,7 @@ impl Claims<Invocation> {
                 target_url: target_url.to_string(),
                 origin_url: origin_url.to_string(),
                 invocation_hash: hash.to_string(),
+                origin_claims: None,
             }),
             expires,
             id: nuid::next(),
@@ -801,6 +810,7 @@ impl Actor {
             rev,
             ver,
             call_alias: normalize_call_alias(call_alias),
+            custom: None,
         }
     }
 }
@@ -864,6 +874,7 @@ impl Invocation {
             target_url: target_url.to_string(),
             origin_url: origin_url.to_string(),
             invocation_hash: hash.to_string(),
+            origin_claims: None,
         }
     }
 }
@@ -995,6 +1006,44 @@ mod test {
         assert!(vres.is_ok());
     }
 
+    #[test]
+    fn invocation_origin_claims_roundtrip() {
+        let issuer = KeyPair::new_cluster();
+        let origin_claims = HashMap::from([("tenant".to_string(), "acme".to_string())]);
+        let claims = Claims {
+            id: nuid::next(),
+            metadata: Some(Invocation {
+                origin_claims: Some(origin_claims.clone()),
+                ..Invocation::new(
+                    "wasmbus://M1234/DeliverMessage",
+                    "wasmbus://wasmcloud/messaging/default",
+                    "abc",
+                )
+            }),
+            expires: None,
+            not_before: None,
+            issued_at: 0,
+            issuer: issuer.public_key(),
+            subject: "invocation1".to_string(),
+            wascap_revision: Some(WASCAP_INTERNAL_REVISION),
+        };
+        let encoded = claims.encode(&issuer).unwrap();
+        assert!(validate_token::<Invocation>(&encoded).is_ok());
+        let decoded = Claims::<Invocation>::decode(&encoded).unwrap();
+        assert_eq!(decoded.metadata.unwrap().origin_claims, Some(origin_claims));
+
+        // Claims encoded without origin claims must not contain the field
+        let plain = Claims::<Invocation>::new(
+            issuer.public_key(),
+            nuid::next(),
+            "wasmbus://M1234/DeliverMessage",
+            "wasmbus://wasmcloud/messaging/default",
+            "abc",
+        );
+        let decoded = Claims::<Invocation>::decode(&plain.encode(&issuer).unwrap()).unwrap();
+        assert_eq!(decoded.metadata.unwrap().origin_claims, None);
+    }
+
     #[test]
     fn full_validation() {
         let kp = KeyPair::new_account();
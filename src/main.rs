@@ -1,26 +1,37 @@
 #![warn(clippy::pedantic)]
 
 use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{self, bail, Context};
-use clap::Parser;
+use clap::{ArgGroup, Parser};
 use nkeys::KeyPair;
 use tokio::time::{timeout, timeout_at};
 use tokio::{select, signal};
 use tracing::Level as TracingLogLevel;
 use wasmcloud_core::logging::Level as WasmcloudLogLevel;
-use wasmcloud_core::OtelConfig;
+use wasmcloud_core::{parse_resource_attributes, InvocationValidity, OtelConfig, TlsConfig};
+use wasmcloud_host::audit::{DEFAULT_MAX_FILES, DEFAULT_MAX_FILE_BYTES};
+use wasmcloud_host::cosign::{
+    Config as CosignConfig, Identity as CosignIdentity, Policy as CosignPolicy,
+};
 use wasmcloud_host::oci::Config as OciConfig;
+use wasmcloud_host::secrets::DEFAULT_BACKEND_NAME as DEFAULT_SECRETS_BACKEND_NAME;
 use wasmcloud_host::url::Url;
-use wasmcloud_host::wasmbus::config::PolicyService as PolicyServiceConfig;
-use wasmcloud_host::WasmbusHostConfig;
+use wasmcloud_host::wasmbus::config::{ActorNetworkRule, PolicyService as PolicyServiceConfig};
+use wasmcloud_host::{
+    AuditConfig, AuditSink, EventSink, EventSinkConfig, ProfilingConfig, SecretsConfig,
+    WasmbusHostConfig,
+};
 use wasmcloud_tracing::configure_tracing;
 
 #[derive(Debug, Parser)]
 #[allow(clippy::struct_excessive_bools)]
 #[command(version, about, long_about = None)]
+#[command(group(ArgGroup::new("policy").args(["policy_topic", "policy_opa_url"])))]
 struct Args {
     /// Controls the verbosity of logs from the wasmCloud host
     #[clap(long = "log-level", alias = "structured-log-level", default_value_t = TracingLogLevel::INFO, env = "WASMCLOUD_LOG_LEVEL")]
@@ -61,13 +72,98 @@ struct Args {
     /// The seed key (a printable 256-bit Ed25519 private key) used by this host to sign all invocations
     #[clap(long = "cluster-seed", env = "WASMCLOUD_CLUSTER_SEED")]
     cluster_seed: Option<String>,
-    /// A comma-delimited list of public keys that can be used as issuers on signed invocations
+    /// A comma-delimited list of public keys that can be used as issuers on signed invocations. Issuers can be added and retired at runtime on the `issuers.put` and `issuers.del` control interface subjects to rotate the cluster key
     #[clap(
         long = "cluster-issuers",
         env = "WASMCLOUD_CLUSTER_ISSUERS",
         value_delimiter = ','
     )]
     cluster_issuers: Option<Vec<String>>,
+    /// Tolerated clock skew, in milliseconds, when checking expiration and not-before times of invocation claims
+    #[clap(long = "invocation-clock-skew-ms", default_value = "0", env = "WASMCLOUD_INVOCATION_CLOCK_SKEW_MS", value_parser = parse_duration)]
+    invocation_clock_skew_ms: Duration,
+    /// If provided, invocations whose claims were issued longer than this many milliseconds ago are rejected
+    #[clap(long = "invocation-max-age-ms", env = "WASMCLOUD_INVOCATION_MAX_AGE_MS", value_parser = parse_duration)]
+    invocation_max_age_ms: Option<Duration>,
+    /// A comma-delimited list of additional link definition value key patterns (e.g. `*_dsn`) whose values are redacted from logs, events and inventory responses
+    #[clap(
+        long = "secret-patterns",
+        env = "WASMCLOUD_SECRET_PATTERNS",
+        value_delimiter = ','
+    )]
+    secret_patterns: Vec<String>,
+    /// If provided, the host serves the secrets in this JSON file to providers of the lattice, which reference them in link definition values as `secret://<secrets-backend-name>/<path>#<key>`
+    #[clap(long = "secrets-file", env = "WASMCLOUD_SECRETS_FILE")]
+    secrets_file: Option<PathBuf>,
+    /// The name of the secrets backend serving --secrets-file
+    #[clap(
+        long = "secrets-backend-name",
+        env = "WASMCLOUD_SECRETS_BACKEND_NAME",
+        default_value = DEFAULT_SECRETS_BACKEND_NAME,
+        requires = "secrets_file"
+    )]
+    secrets_backend_name: String,
+    /// A comma-delimited list of custom actor claims (e.g. `tenant`) to forward to capability providers in signed invocation claims
+    #[clap(
+        long = "forwarded-actor-claims",
+        env = "WASMCLOUD_FORWARDED_ACTOR_CLAIMS",
+        value_delimiter = ','
+    )]
+    forwarded_actor_claims: Vec<String>,
+    /// If provided, invocations handled by actors on this host are recorded as JSON lines to the file at this path
+    #[clap(
+        long = "audit-log-file",
+        env = "WASMCLOUD_AUDIT_LOG_FILE",
+        conflicts_with = "audit_log_subject"
+    )]
+    audit_log_file: Option<PathBuf>,
+    /// If provided, invocations handled by actors on this host are recorded as JSON messages published on this NATS subject using the control interface connection
+    #[clap(long = "audit-log-subject", env = "WASMCLOUD_AUDIT_LOG_SUBJECT")]
+    audit_log_subject: Option<String>,
+    /// Size, in bytes, after which the audit log file is rotated
+    #[clap(long = "audit-log-max-bytes", default_value_t = DEFAULT_MAX_FILE_BYTES, env = "WASMCLOUD_AUDIT_LOG_MAX_BYTES")]
+    audit_log_max_bytes: u64,
+    /// Number of rotated audit log files to keep
+    #[clap(long = "audit-log-max-files", default_value_t = DEFAULT_MAX_FILES, env = "WASMCLOUD_AUDIT_LOG_MAX_FILES")]
+    audit_log_max_files: usize,
+    /// Fraction, between 0 and 1, of successful invocations to record in the audit log
+    #[clap(
+        long = "audit-sample-rate",
+        default_value = "1.0",
+        env = "WASMCLOUD_AUDIT_SAMPLE_RATE"
+    )]
+    audit_sample_rate: f64,
+    /// If set, failed and denied invocations are sampled as well, otherwise they are always recorded in the audit log
+    #[clap(
+        long = "audit-sample-failures",
+        env = "WASMCLOUD_AUDIT_SAMPLE_FAILURES"
+    )]
+    audit_sample_failures: bool,
+    /// If provided, lattice events are sent as JSON CloudEvents in `POST` requests to this URL
+    #[clap(long = "event-webhook-url", env = "WASMCLOUD_EVENT_WEBHOOK_URL")]
+    event_webhook_url: Option<Url>,
+    /// If provided, lattice events are recorded as JSON lines to the file at this path, rotated like the audit log
+    #[clap(long = "event-file", env = "WASMCLOUD_EVENT_FILE")]
+    event_file: Option<PathBuf>,
+    /// If set, lattice events are written as JSON lines to stdout
+    #[clap(long = "event-stdout", env = "WASMCLOUD_EVENT_STDOUT")]
+    event_stdout: bool,
+    /// Comma-separated names of lattice events, e.g. `actor_started`, to send to the configured event sinks. All events are sent if not set
+    #[clap(
+        long = "event-types",
+        env = "WASMCLOUD_EVENT_TYPES",
+        value_delimiter = ','
+    )]
+    event_types: Vec<String>,
+    /// Enable on-demand CPU profiling of the host via the control interface
+    #[clap(long = "enable-profiling", env = "WASMCLOUD_PROFILING_ENABLED")]
+    enable_profiling: bool,
+    /// An address to serve pprof-compatible CPU profiling endpoints on, e.g. `127.0.0.1:6060`. Implies --enable-profiling
+    #[clap(long = "profiling-address", env = "WASMCLOUD_PROFILING_ADDRESS")]
+    profiling_address: Option<SocketAddr>,
+    /// Maximum duration, in milliseconds, of a single CPU profile
+    #[clap(long = "profiling-max-duration", default_value = "300000", env = "WASMCLOUD_PROFILING_MAX_DURATION_MS", value_parser = parse_duration)]
+    profiling_max_duration: Duration,
     /// Delay, in milliseconds, between requesting a provider shut down and forcibly terminating its process
     #[clap(long = "provider-shutdown-delay", default_value = "300", env = "WASMCLOUD_PROV_SHUTDOWN_DELAY_MS", value_parser = parse_duration)]
     provider_shutdown_delay: Duration,
@@ -81,6 +177,36 @@ struct Args {
         value_delimiter = ','
     )]
     allowed_insecure: Vec<String>,
+    /// A comma-separated list of paths to PEM-encoded cosign public keys. If set, actors and providers fetched from OCI registries must be signed by one of these keys or a certificate identity
+    #[clap(
+        long = "cosign-public-keys",
+        env = "WASMCLOUD_COSIGN_PUBLIC_KEYS",
+        value_delimiter = ','
+    )]
+    cosign_public_keys: Vec<PathBuf>,
+    /// A comma-separated list of certificate identities (email addresses or URIs) of keyless cosign signatures. If set, actors and providers fetched from OCI registries must be signed by one of these identities or a public key
+    #[clap(
+        long = "cosign-certificate-identity",
+        env = "WASMCLOUD_COSIGN_CERTIFICATE_IDENTITY",
+        value_delimiter = ',',
+        requires = "cosign_certificate_oidc_issuer"
+    )]
+    cosign_certificate_identity: Vec<String>,
+    /// The OIDC issuer of keyless cosign signatures, e.g. `https://token.actions.githubusercontent.com`
+    #[clap(
+        long = "cosign-certificate-oidc-issuer",
+        env = "WASMCLOUD_COSIGN_CERTIFICATE_OIDC_ISSUER",
+        requires = "cosign_certificate_identity"
+    )]
+    cosign_certificate_oidc_issuer: Option<String>,
+    /// How actors and providers fetched from OCI registries without a valid cosign signature are handled if cosign public keys or identities are set. `require` rejects them, `warn` accepts them and logs a warning, `ignore` disables signature verification
+    #[clap(
+        long = "cosign-policy",
+        default_value = "require",
+        env = "WASMCLOUD_COSIGN_POLICY",
+        value_parser = parse_cosign_policy
+    )]
+    cosign_policy: CosignPolicy,
     /// NATS Jetstream domain name
     #[clap(
         long = "js-domain",
@@ -98,12 +224,37 @@ struct Args {
         env = "WASMCLOUD_ALLOW_FILE_LOAD"
     )]
     allow_file_load: bool,
+    /// Directory to store durable per-actor state in. If not set, state is stored in JetStream key-value buckets shared by the lattice
+    #[clap(long = "state-dir", env = "WASMCLOUD_STATE_DIR")]
+    state_dir: Option<PathBuf>,
+    /// A comma-separated list of destinations actors may open outbound TCP connections to, in the form `[<actor public key>=]<IP address or CIDR>[:<port>|:<start>-<end>]`, e.g. `10.0.0.0/8:5432`. Actors have no network access by default
+    #[clap(
+        long = "allow-actor-network",
+        env = "WASMCLOUD_ALLOW_ACTOR_NETWORK",
+        value_delimiter = ','
+    )]
+    allow_actor_network: Vec<ActorNetworkRule>,
     /// Enable JSON structured logging from the wasmCloud host
     #[clap(
         long = "enable-structured-logging",
         env = "WASMCLOUD_STRUCTURED_LOGGING_ENABLED"
     )]
     enable_structured_logging: bool,
+    /// Forward logs of capability providers to the wasmCloud host, which emits them in its own log stream, attributed to the provider
+    #[clap(
+        long = "forward-provider-logs",
+        env = "WASMCLOUD_FORWARD_PROVIDER_LOGS"
+    )]
+    forward_provider_logs: bool,
+    /// Record the contents of invocation payloads handled by capability providers in traces, with values of keys matching --secret-patterns redacted. Payload sizes are always recorded. Intended for debugging only
+    #[clap(
+        long = "capture-provider-payloads",
+        env = "WASMCLOUD_CAPTURE_PROVIDER_PAYLOADS"
+    )]
+    capture_provider_payloads: bool,
+    /// Chunk invocation payloads exceeding the NATS max payload inline over NATS, instead of through a JetStream object store. Payloads sent to hosts and providers not supporting inline chunking are still chunked through JetStream
+    #[clap(long = "inline-chunking", env = "WASMCLOUD_INLINE_CHUNKING")]
+    inline_chunking: bool,
     #[clap(short = 'l', long = "label")]
     label: Option<Vec<String>>,
     /// An IP address or DNS name to use to connect to NATS for Control Interface (CTL) messages, defaults to the value supplied to --nats-host if not supplied
@@ -154,20 +305,45 @@ struct Args {
         hide = true
     )]
     rpc_jwt: Option<String>,
-    /// A seed nkey to use to authenticate to NATS for RPC messages, defaults to the value supplied to --nats-seed if not supplied
+    /// A seed nkey to use to authenticate to NATS for RPC messages, defaults to the value supplied to --nats-seed if not supplied. If no JWT is supplied, the host and its providers authenticate using the nkey alone
+    #[clap(long = "rpc-seed", env = "WASMCLOUD_RPC_SEED", hide = true)]
+    rpc_seed: Option<String>,
+    /// An account signing seed nkey used to issue each capability provider its own NATS user JWT for RPC messages, scoped to the lattices it serves. Requires the RPC JWT to be issued by the same account. If not supplied, providers authenticate using the RPC credentials of the host
     #[clap(
-        long = "rpc-seed",
-        env = "WASMCLOUD_RPC_SEED",
-        requires = "rpc_jwt",
+        long = "provider-rpc-signing-seed",
+        env = "WASMCLOUD_PROVIDER_RPC_SIGNING_SEED",
         hide = true
     )]
-    rpc_seed: Option<String>,
+    provider_rpc_signing_seed: Option<String>,
     /// Timeout in milliseconds for all RPC calls
     #[clap(long = "rpc-timeout-ms", default_value = "2000", env = "WASMCLOUD_RPC_TIMEOUT_MS", value_parser = parse_duration, hide = true)]
     rpc_timeout_ms: Duration,
     /// Optional flag to require host communication over TLS with a NATS server for RPC messages
     #[clap(long = "rpc-tls", env = "WASMCLOUD_RPC_TLS", hide = true)]
     rpc_tls: bool,
+    /// A PEM file containing root certificates used by the host and providers to verify the NATS server certificate for RPC messages
+    #[clap(
+        long = "rpc-tls-ca-file",
+        env = "WASMCLOUD_RPC_TLS_CA_FILE",
+        hide = true
+    )]
+    rpc_tls_ca_file: Option<PathBuf>,
+    /// A PEM file containing a client certificate the host and providers present to the NATS server for RPC messages
+    #[clap(
+        long = "rpc-tls-client-cert",
+        env = "WASMCLOUD_RPC_TLS_CLIENT_CERT",
+        requires = "rpc_tls_client_key",
+        hide = true
+    )]
+    rpc_tls_client_cert: Option<PathBuf>,
+    /// A PEM file containing the private key of the client certificate supplied to --rpc-tls-client-cert
+    #[clap(
+        long = "rpc-tls-client-key",
+        env = "WASMCLOUD_RPC_TLS_CLIENT_KEY",
+        requires = "rpc_tls_client_cert",
+        hide = true
+    )]
+    rpc_tls_client_key: Option<PathBuf>,
 
     /// If provided, enables policy checks on start actions and actor invocations
     #[clap(long = "policy-topic", env = "WASMCLOUD_POLICY_TOPIC")]
@@ -179,11 +355,18 @@ struct Args {
         requires = "policy_topic"
     )]
     policy_changes_topic: Option<String>,
-    /// If provided, allows setting a custom timeout for requesting policy decisions. Defaults to one second. Requires `policy_topic` to be set.
+    /// If provided, policy decisions, including link definition changes, are requested from the OPA data API at this URL instead of `policy_topic`
+    #[clap(
+        long = "policy-opa-url",
+        env = "WASMCLOUD_POLICY_OPA_URL",
+        conflicts_with = "policy_topic"
+    )]
+    policy_opa_url: Option<Url>,
+    /// If provided, allows setting a custom timeout for requesting policy decisions. Defaults to one second. Requires `policy_topic` or `policy_opa_url` to be set.
     #[clap(
         long = "policy-timeout-ms",
         env = "WASMCLOUD_POLICY_TIMEOUT",
-        requires = "policy_topic",
+        requires = "policy",
         value_parser = parse_duration,
     )]
     policy_timeout_ms: Option<Duration>,
@@ -226,6 +409,21 @@ struct Args {
         env = "OTEL_EXPORTER_OTLP_ENDPOINT"
     )]
     otel_exporter_otlp_endpoint: Option<String>,
+
+    /// Specifies the protocol to use for the OTLP exporter, either "http/protobuf" (default) or "grpc"
+    #[clap(
+        long = "otel-exporter-otlp-protocol",
+        env = "OTEL_EXPORTER_OTLP_PROTOCOL"
+    )]
+    otel_exporter_otlp_protocol: Option<String>,
+
+    /// Specifies the ratio, between 0 and 1, of traces to sample. All traces are sampled if not set
+    #[clap(long = "otel-traces-sampler-arg", env = "OTEL_TRACES_SAMPLER_ARG")]
+    otel_traces_sampler_arg: Option<f64>,
+
+    /// A comma-separated list of `key=value` resource attributes to attach to traces of the host and its providers
+    #[clap(long = "otel-resource-attributes", env = "OTEL_RESOURCE_ATTRIBUTES")]
+    otel_resource_attributes: Option<String>,
 }
 
 const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
@@ -238,6 +436,13 @@ async fn main() -> anyhow::Result<()> {
     let otel_config = OtelConfig {
         traces_exporter: args.otel_traces_exporter,
         exporter_otlp_endpoint: args.otel_exporter_otlp_endpoint,
+        exporter_otlp_protocol: args.otel_exporter_otlp_protocol,
+        traces_sampler_ratio: args.otel_traces_sampler_arg,
+        resource_attributes: args
+            .otel_resource_attributes
+            .as_deref()
+            .map(parse_resource_attributes)
+            .unwrap_or_default(),
     };
     let log_level = WasmcloudLogLevel::from(args.log_level);
     if let Err(e) = configure_tracing(
@@ -297,6 +502,13 @@ async fn main() -> anyhow::Result<()> {
         .transpose()
         .context("failed to construct RPC key pair from seed")?
         .map(Arc::new);
+    let provider_rpc_signing_key = args
+        .provider_rpc_signing_seed
+        .as_deref()
+        .map(KeyPair::from_seed)
+        .transpose()
+        .context("failed to construct provider RPC signing key pair from seed")?
+        .map(Arc::new);
     let oci_opts = OciConfig {
         allow_latest: args.allow_latest,
         allowed_insecure: args.allowed_insecure,
@@ -304,11 +516,69 @@ async fn main() -> anyhow::Result<()> {
         oci_user: args.oci_user,
         oci_password: args.oci_password,
     };
+    let signature_verification =
+        if args.cosign_public_keys.is_empty() && args.cosign_certificate_identity.is_empty() {
+            None
+        } else {
+            let mut public_keys = Vec::with_capacity(args.cosign_public_keys.len());
+            for path in &args.cosign_public_keys {
+                let key = std::fs::read_to_string(path).with_context(|| {
+                    format!("failed to read cosign public key `{}`", path.display())
+                })?;
+                public_keys.push(key);
+            }
+            let issuer = args.cosign_certificate_oidc_issuer.unwrap_or_default();
+            let identities = args
+                .cosign_certificate_identity
+                .into_iter()
+                .map(|subject| CosignIdentity {
+                    subject,
+                    issuer: issuer.clone(),
+                })
+                .collect();
+            Some(CosignConfig {
+                public_keys,
+                identities,
+                policy: args.cosign_policy,
+            })
+        };
     let policy_service_config = PolicyServiceConfig {
         policy_topic: args.policy_topic,
         policy_changes_topic: args.policy_changes_topic,
+        policy_opa_url: args.policy_opa_url,
         policy_timeout_ms: args.policy_timeout_ms,
     };
+    let audit_sink = match (args.audit_log_file, args.audit_log_subject) {
+        (Some(path), _) => Some(AuditSink::File {
+            path,
+            max_bytes: args.audit_log_max_bytes,
+            max_files: args.audit_log_max_files,
+        }),
+        (None, Some(subject)) => Some(AuditSink::Nats { subject }),
+        (None, None) => None,
+    };
+    let audit_log = audit_sink.map(|sink| AuditConfig {
+        sink,
+        sample_rate: args.audit_sample_rate,
+        sample_failures: args.audit_sample_failures,
+    });
+    let event_sinks = args
+        .event_webhook_url
+        .map(EventSink::webhook)
+        .into_iter()
+        .chain(args.event_file.map(EventSink::file))
+        .chain(args.event_stdout.then_some(EventSink::Stdout))
+        .map(|sink| EventSinkConfig {
+            sink,
+            event_types: args.event_types.clone(),
+        })
+        .collect();
+    let profiling =
+        (args.enable_profiling || args.profiling_address.is_some()).then(|| ProfilingConfig {
+            listen_address: args.profiling_address,
+            max_duration: args.profiling_max_duration,
+            ..Default::default()
+        });
     let labels = args
         .label
         .unwrap_or_default()
@@ -322,11 +592,25 @@ async fn main() -> anyhow::Result<()> {
         host_key,
         cluster_key,
         cluster_issuers: args.cluster_issuers,
+        invocation_validity: InvocationValidity {
+            clock_skew: args.invocation_clock_skew_ms,
+            max_age: args.invocation_max_age_ms,
+        },
+        secret_patterns: args.secret_patterns,
+        secrets_backend: args.secrets_file.map(|path| SecretsConfig {
+            name: args.secrets_backend_name,
+            path,
+        }),
+        forwarded_actor_claims: args.forwarded_actor_claims,
+        audit_log,
+        event_sinks,
+        profiling,
         config_service_enabled: args.config_service_enabled,
         js_domain: args.js_domain,
         labels,
         provider_shutdown_delay: Some(args.provider_shutdown_delay),
         oci_opts,
+        signature_verification,
         ctl_jwt: args.ctl_jwt.or_else(|| args.nats_jwt.clone()),
         ctl_key: ctl_key.or_else(|| nats_key.clone()),
         ctl_tls: args.ctl_tls,
@@ -336,9 +620,20 @@ async fn main() -> anyhow::Result<()> {
         rpc_jwt: args.rpc_jwt.or_else(|| args.nats_jwt.clone()),
         rpc_key: rpc_key.or_else(|| nats_key.clone()),
         rpc_tls: args.rpc_tls,
+        provider_rpc_signing_key,
+        rpc_tls_config: TlsConfig {
+            ca_file: args.rpc_tls_ca_file,
+            client_cert_file: args.rpc_tls_client_cert,
+            client_key_file: args.rpc_tls_client_key,
+        },
         allow_file_load: args.allow_file_load,
+        state_dir: args.state_dir,
+        actor_network_allowlist: args.allow_actor_network,
         log_level,
         enable_structured_logging: args.enable_structured_logging,
+        forward_provider_logs: args.forward_provider_logs,
+        capture_provider_payloads: args.capture_provider_payloads,
+        inline_chunking: args.inline_chunking,
         otel_config,
         policy_service_config,
     }))
@@ -375,6 +670,10 @@ async fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
+fn parse_cosign_policy(arg: &str) -> anyhow::Result<CosignPolicy> {
+    arg.parse()
+}
+
 fn parse_duration(arg: &str) -> anyhow::Result<Duration> {
     arg.parse()
         .map(Duration::from_millis)
@@ -387,3 +686,41 @@ fn parse_label(labelpair: &str) -> anyhow::Result<(String, String)> {
         _ => bail!("invalid label format `{labelpair}`. Expected `key=value`"),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn policy_timeout_requires_policy_service() {
+        let args = Args::try_parse_from([
+            "wasmcloud",
+            "--policy-opa-url",
+            "http://localhost:8181/v1/data/wasmcloud/allow",
+            "--policy-timeout-ms",
+            "500",
+        ])
+        .expect("failed to parse OPA policy arguments");
+        assert_eq!(args.policy_timeout_ms, Some(Duration::from_millis(500)));
+
+        let args = Args::try_parse_from([
+            "wasmcloud",
+            "--policy-topic",
+            "wasmcloud.policy",
+            "--policy-timeout-ms",
+            "500",
+        ])
+        .expect("failed to parse policy topic arguments");
+        assert_eq!(args.policy_timeout_ms, Some(Duration::from_millis(500)));
+
+        assert!(Args::try_parse_from(["wasmcloud", "--policy-timeout-ms", "500"]).is_err());
+        assert!(Args::try_parse_from([
+            "wasmcloud",
+            "--policy-topic",
+            "wasmcloud.policy",
+            "--policy-opa-url",
+            "http://localhost:8181/v1/data/wasmcloud/allow",
+        ])
+        .is_err());
+    }
+}
@@ -287,6 +287,64 @@ impl exports::wasi::http::incoming_handler::Guest for Actor {
             .map_err(keyvalue::wasi_cloud_error::trace)
             .expect("failed to set `result`");
 
+        // Batch operations are handled by the target of `wasi:keyvalue/readwrite`
+        let batch_keys = vec![String::from("batch-a"), String::from("batch-b")];
+        let a_value = keyvalue::types::new_outgoing_value();
+        keyvalue::types::outgoing_value_write_body_sync(a_value, b"a")
+            .expect("failed to write outgoing value");
+        let b_value = keyvalue::types::new_outgoing_value();
+        keyvalue::types::outgoing_value_write_body_sync(b_value, b"b")
+            .expect("failed to write outgoing value");
+        keyvalue::batch::set_many(
+            bucket,
+            &[
+                (batch_keys[0].clone(), a_value),
+                (batch_keys[1].clone(), b_value),
+            ],
+        )
+        .map_err(keyvalue::wasi_cloud_error::trace)
+        .expect("failed to set batch keys");
+
+        let values = keyvalue::batch::get_many(bucket, &batch_keys)
+            .map_err(keyvalue::wasi_cloud_error::trace)
+            .expect("failed to get batch keys")
+            .into_iter()
+            .map(|value| {
+                keyvalue::types::incoming_value_consume_sync(value)
+                    .map_err(keyvalue::wasi_cloud_error::trace)
+                    .expect("failed to get incoming value buffer")
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(values, [b"a", b"b"]);
+
+        let keys = keyvalue::batch::get_keys(bucket);
+        assert!(batch_keys.iter().all(|key| keys.contains(key)));
+
+        // `get-many` fails if any of the keys does not exist
+        keyvalue::batch::get_many(
+            bucket,
+            &[batch_keys[0].clone(), String::from("batch-missing")],
+        )
+        .map_err(keyvalue::wasi_cloud_error::trace)
+        .expect_err("`get-many` should have failed for `batch-missing` key, which does not exist");
+
+        // `delete-many` skips keys that do not exist
+        keyvalue::batch::delete_many(
+            bucket,
+            &[
+                batch_keys[0].clone(),
+                batch_keys[1].clone(),
+                String::from("batch-missing"),
+            ],
+        )
+        .map_err(keyvalue::wasi_cloud_error::trace)
+        .expect("failed to delete batch keys");
+        for key in &batch_keys {
+            keyvalue::readwrite::exists(bucket, key)
+                .map_err(keyvalue::wasi_cloud_error::trace)
+                .expect_err("batch key was not deleted");
+        }
+
         bus::lattice::set_target(
             Some(&TargetEntity::Link(Some("keyvalue".into()))),
             vec![bus::lattice::TargetInterface::wasi_keyvalue_atomic()],
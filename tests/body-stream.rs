@@ -0,0 +1,121 @@
+use std::io::Cursor;
+
+use anyhow::{Context, Result};
+use futures::TryStreamExt;
+use tokio::io::AsyncReadExt;
+use wasmcloud_core::body_stream::{BodyStreamEndpoint, FRAME_SIZE_BYTES};
+
+pub mod common;
+
+use crate::common::nats::start_nats;
+use crate::common::stop_server;
+
+const TEST_LATTICE_PREFIX: &str = "test-body-stream";
+
+/// Returns a body of `len` bytes, which does not repeat at frame boundaries
+fn body(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i % 251) as u8).collect()
+}
+
+/// Test serving, pulling, pushing and receiving body streams over NATS
+#[tokio::test(flavor = "multi_thread")]
+async fn body_stream_suite() -> Result<()> {
+    let (nats_server, stop_nats_tx, _nats_url, nats_client) = start_nats()
+        .await
+        .context("failed to start backing services")?;
+    let endpoint = BodyStreamEndpoint::new(TEST_LATTICE_PREFIX, nats_client);
+
+    // A body spanning multiple frames is pulled in full
+    let request = body(3 * FRAME_SIZE_BYTES + 7);
+    let serve = endpoint
+        .serve("request", Cursor::new(request.clone()))
+        .await
+        .context("failed to serve request body")?;
+    let mut pulled = Vec::new();
+    endpoint
+        .pull("request")
+        .read_to_end(&mut pulled)
+        .await
+        .context("failed to pull request body")?;
+    assert_eq!(pulled, request);
+    serve
+        .await
+        .context("serving task panicked")?
+        .context("failed to serve request body")?;
+
+    // An empty body is terminated right away
+    let serve = endpoint
+        .serve("empty", Cursor::new(Vec::new()))
+        .await
+        .context("failed to serve empty body")?;
+    let mut pulled = Vec::new();
+    endpoint
+        .pull("empty")
+        .read_to_end(&mut pulled)
+        .await
+        .context("failed to pull empty body")?;
+    assert!(pulled.is_empty());
+    serve
+        .await
+        .context("serving task panicked")?
+        .context("failed to serve empty body")?;
+
+    // A pushed body is received frame by frame
+    let response = body(2 * FRAME_SIZE_BYTES + 1);
+    let received = endpoint
+        .receive("response")
+        .await
+        .context("failed to receive response body")?;
+    let push = tokio::spawn({
+        let endpoint = endpoint.clone();
+        let response = response.clone();
+        async move { endpoint.push("response", Cursor::new(response)).await }
+    });
+    let frames: Vec<_> = received
+        .try_collect()
+        .await
+        .context("failed to receive response frames")?;
+    assert!(frames.len() >= 3);
+    assert!(frames.iter().all(|frame| frame.len() <= FRAME_SIZE_BYTES));
+    assert_eq!(frames.concat(), response);
+    push.await
+        .context("pushing task panicked")?
+        .context("failed to push response body")?;
+
+    // The request body may still be pulled while the response body is pushed, as an echo
+    // handler does
+    let request = body(4 * FRAME_SIZE_BYTES);
+    let serve = endpoint
+        .serve("echo-request", Cursor::new(request.clone()))
+        .await
+        .context("failed to serve echo request body")?;
+    let received = endpoint
+        .receive("echo-response")
+        .await
+        .context("failed to receive echo response body")?;
+    let echo = tokio::spawn({
+        let endpoint = endpoint.clone();
+        async move {
+            endpoint
+                .push("echo-response", endpoint.pull("echo-request"))
+                .await
+        }
+    });
+    let frames: Vec<_> = received
+        .try_collect()
+        .await
+        .context("failed to receive echo response frames")?;
+    assert_eq!(frames.concat(), request);
+    echo.await
+        .context("echo task panicked")?
+        .context("failed to echo body")?;
+    serve
+        .await
+        .context("serving task panicked")?
+        .context("failed to serve echo request body")?;
+
+    stop_server(nats_server, stop_nats_tx)
+        .await
+        .context("failed to stop NATS")?;
+    Ok(())
+}
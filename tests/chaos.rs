@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use nkeys::KeyPair;
+use tokio::time::Duration;
+use wasmcloud_control_interface::{Client as CtlClient, ClientBuilder};
+use wasmcloud_host::wasmbus::HostConfig;
+
+pub mod common;
+
+use crate::common::chaos::{
+    assert_eventually, wait_connected, MessageRecorder, RestartableHost, RestartableNats,
+};
+
+const TEST_LATTICE_PREFIX: &str = "test-chaos";
+
+/// Assert that the host with ID `host_id` responds to the control interface
+async fn assert_host_responds(ctl_client: &CtlClient, host_id: &str) -> Result<()> {
+    assert_eventually(
+        Duration::from_secs(30),
+        Duration::from_millis(500),
+        || async {
+            let hosts = ctl_client
+                .get_hosts()
+                .await
+                .map_err(|e| anyhow!(e).context("failed to get hosts"))?;
+            ensure!(
+                hosts.iter().any(|host| host.id == host_id),
+                "host `{host_id}` did not respond"
+            );
+            Ok(())
+        },
+    )
+    .await
+}
+
+/// Test that the host recovers from NATS and host restarts
+#[tokio::test(flavor = "multi_thread")]
+async fn host_recovers_from_restarts() -> Result<()> {
+    let (mut nats, nats_client) = RestartableNats::start()
+        .await
+        .context("failed to start NATS")?;
+
+    let cluster_key = Arc::new(KeyPair::new_cluster());
+    let host_key = Arc::new(KeyPair::new_server());
+    let mut host = RestartableHost::start(HostConfig {
+        ctl_nats_url: nats.url.clone(),
+        rpc_nats_url: nats.url.clone(),
+        lattice_prefix: TEST_LATTICE_PREFIX.into(),
+        cluster_key: Some(Arc::clone(&cluster_key)),
+        cluster_issuers: Some(vec![cluster_key.public_key()]),
+        host_key: Some(Arc::clone(&host_key)),
+        provider_shutdown_delay: Some(Duration::from_millis(300)),
+        ..Default::default()
+    })
+    .await
+    .context("failed to start host")?;
+
+    let ctl_client = ClientBuilder::new(nats_client.clone())
+        .lattice_prefix(TEST_LATTICE_PREFIX.to_string())
+        .build();
+    let host_id = host_key.public_key();
+    assert_host_responds(&ctl_client, &host_id).await?;
+
+    // The host reconnects after NATS was down
+    nats.bounce(Duration::from_secs(1))
+        .await
+        .context("failed to bounce NATS")?;
+    ensure!(nats.is_running());
+    wait_connected(&nats_client, Duration::from_secs(10))
+        .await
+        .context("client did not reconnect to NATS")?;
+    assert_host_responds(&ctl_client, &host_id)
+        .await
+        .context("host did not recover from NATS restart")?;
+
+    // A restarted host keeps its identity and announces itself again
+    let started = MessageRecorder::subscribe(
+        &nats_client,
+        format!("wasmbus.evt.{TEST_LATTICE_PREFIX}.host_started"),
+    )
+    .await?;
+    host.bounce(Duration::from_millis(500))
+        .await
+        .context("failed to bounce host")?;
+    ensure!(host.host().is_some());
+    started
+        .assert_received(1, Duration::from_secs(10))
+        .await
+        .context("restarted host did not publish `host_started`")?;
+    assert_host_responds(&ctl_client, &host_id)
+        .await
+        .context("host did not respond after restart")?;
+
+    host.stop().await.context("failed to stop host")?;
+    nats.stop().await.context("failed to stop NATS")?;
+    Ok(())
+}
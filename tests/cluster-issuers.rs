@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use nkeys::KeyPair;
+use tokio::time::Duration;
+use wasmcloud_control_interface::{Client as CtlClient, ClientBuilder};
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+
+pub mod common;
+
+use crate::common::chaos::MessageRecorder;
+use crate::common::nats::start_nats;
+use crate::common::stop_server;
+
+const TEST_LATTICE_PREFIX: &str = "test-cluster-issuers";
+
+/// Returns the cluster issuers the host with ID `host_id` reports
+async fn cluster_issuers(ctl_client: &CtlClient, host_id: &str) -> Result<Vec<String>> {
+    let hosts = ctl_client
+        .get_hosts()
+        .await
+        .map_err(|e| anyhow!(e).context("failed to get hosts"))?;
+    let host = hosts
+        .into_iter()
+        .find(|host| host.id == host_id)
+        .context("host did not respond")?;
+    Ok(host
+        .cluster_issuers
+        .unwrap_or_default()
+        .split(',')
+        .filter(|issuer| !issuer.is_empty())
+        .map(ToString::to_string)
+        .collect())
+}
+
+/// Test that cluster issuers are trusted and retired via the control interface
+#[tokio::test(flavor = "multi_thread")]
+async fn cluster_issuers_rotate() -> Result<()> {
+    let (nats_server, stop_nats_tx, nats_url, nats_client) =
+        start_nats().await.context("failed to start NATS")?;
+
+    let cluster_key = Arc::new(KeyPair::new_cluster());
+    let host_key = Arc::new(KeyPair::new_server());
+    let (_host, shutdown_host) = Host::new(HostConfig {
+        ctl_nats_url: nats_url.clone(),
+        rpc_nats_url: nats_url.clone(),
+        lattice_prefix: TEST_LATTICE_PREFIX.into(),
+        cluster_key: Some(Arc::clone(&cluster_key)),
+        cluster_issuers: Some(vec![cluster_key.public_key()]),
+        host_key: Some(Arc::clone(&host_key)),
+        provider_shutdown_delay: Some(Duration::from_millis(300)),
+        ..Default::default()
+    })
+    .await
+    .context("failed to initialize host")?;
+
+    let ctl_client = ClientBuilder::new(nats_client.clone())
+        .lattice_prefix(TEST_LATTICE_PREFIX.to_string())
+        .auction_timeout(Duration::from_secs(2))
+        .build();
+    let host_id = host_key.public_key();
+    ensure!(cluster_issuers(&ctl_client, &host_id).await? == [cluster_key.public_key()]);
+
+    let updates = MessageRecorder::subscribe(
+        &nats_client,
+        format!("wasmbus.evt.{TEST_LATTICE_PREFIX}.cluster_issuers_updated"),
+    )
+    .await?;
+
+    // A new cluster key is trusted in addition to the current one
+    let new_key = KeyPair::new_cluster();
+    let acks = ctl_client
+        .put_cluster_issuer(&new_key.public_key())
+        .await
+        .map_err(|e| anyhow!(e).context("failed to put cluster issuer"))?;
+    ensure!(
+        acks.len() == 1,
+        "expected one acknowledgement, got {acks:?}"
+    );
+    ensure!(acks[0].accepted, "put was not accepted: {}", acks[0].error);
+    ensure!(
+        cluster_issuers(&ctl_client, &host_id).await?
+            == [cluster_key.public_key(), new_key.public_key()]
+    );
+    updates
+        .assert_received(1, Duration::from_secs(5))
+        .await
+        .context("host did not publish `cluster_issuers_updated`")?;
+
+    // Trusting an issuer again has no effect
+    let acks = ctl_client
+        .put_cluster_issuer(&new_key.public_key())
+        .await
+        .map_err(|e| anyhow!(e).context("failed to put cluster issuer"))?;
+    ensure!(acks.iter().all(|ack| ack.accepted));
+    ensure!(cluster_issuers(&ctl_client, &host_id).await?.len() == 2);
+
+    // Only cluster keys can be trusted
+    let acks = ctl_client
+        .put_cluster_issuer(&host_id)
+        .await
+        .map_err(|e| anyhow!(e).context("failed to put cluster issuer"))?;
+    ensure!(
+        acks.len() == 1 && !acks[0].accepted,
+        "server key was trusted"
+    );
+    ensure!(ctl_client.put_cluster_issuer(" ").await.is_err());
+
+    // The key the host signs invocations with cannot be retired
+    let acks = ctl_client
+        .delete_cluster_issuer(&cluster_key.public_key())
+        .await
+        .map_err(|e| anyhow!(e).context("failed to delete cluster issuer"))?;
+    ensure!(
+        acks.len() == 1 && !acks[0].accepted,
+        "signing key was retired"
+    );
+
+    // Other keys are retired
+    let acks = ctl_client
+        .delete_cluster_issuer(&new_key.public_key())
+        .await
+        .map_err(|e| anyhow!(e).context("failed to delete cluster issuer"))?;
+    ensure!(acks.iter().all(|ack| ack.accepted));
+    ensure!(cluster_issuers(&ctl_client, &host_id).await? == [cluster_key.public_key()]);
+    updates
+        .assert_received(2, Duration::from_secs(5))
+        .await
+        .context("host did not publish `cluster_issuers_updated`")?;
+
+    shutdown_host.await.context("failed to shutdown host")?;
+    stop_server(nats_server, stop_nats_tx)
+        .await
+        .context("failed to stop NATS")?;
+    Ok(())
+}
@@ -0,0 +1,358 @@
+//! Fault injection utilities for lattice resilience tests
+//!
+//! These helpers allow tests to kill and restart NATS, capability providers and hosts in the
+//! middle of an invocation and to assert on the resulting retry and dead-letter behavior.
+
+use core::future::Future;
+use core::pin::Pin;
+
+use std::env::temp_dir;
+use std::process::ExitStatus;
+use std::sync::{Arc, Mutex};
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use async_nats::connection::State;
+use async_nats::Client as NatsClient;
+use tempfile::TempDir;
+use tokio::process::Command;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Duration, Instant};
+use tokio_stream::StreamExt;
+use url::Url;
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+
+use super::nats::nats_command;
+use super::{reserve_port, spawn_server, spawn_server_on, stop_server, tempdir};
+
+/// A NATS server, which can be killed and restarted on the same port
+///
+/// JetStream data is kept in the same directory across restarts, so streams and KV buckets
+/// survive a [`RestartableNats::restart`].
+pub struct RestartableNats {
+    /// URL the server is listening on
+    pub url: Url,
+    port: u16,
+    jetstream_dir: TempDir,
+    server: Option<(JoinHandle<Result<ExitStatus>>, oneshot::Sender<()>)>,
+}
+
+impl RestartableNats {
+    /// Start NATS as a subprocess on a random port and wait until it accepts connections
+    pub async fn start() -> Result<(Self, NatsClient)> {
+        let reservation = reserve_port().await?;
+        let port = reservation.port();
+        let url =
+            Url::parse(&format!("nats://localhost:{port}")).context("failed to parse NATS URL")?;
+        let jetstream_dir = tempdir()?;
+        let server = spawn_server_on(
+            &mut nats_command(port, jetstream_dir.path(), None),
+            reservation,
+        )
+        .await
+        .context("failed to start NATS")?;
+        let nats = Self {
+            url,
+            port,
+            jetstream_dir,
+            server: Some(server),
+        };
+        let nats_client = async_nats::connect_with_options(
+            nats.url.as_str(),
+            async_nats::ConnectOptions::new().retry_on_initial_connect(),
+        )
+        .await
+        .context("failed to build nats client")?;
+        wait_connected(&nats_client, Duration::from_secs(3))
+            .await
+            .context("failed to ensure connection to NATS server")?;
+        Ok((nats, nats_client))
+    }
+
+    /// Returns `true` if the server is currently running
+    pub fn is_running(&self) -> bool {
+        self.server.is_some()
+    }
+
+    /// Kill the server, dropping all client connections
+    pub async fn kill(&mut self) -> Result<()> {
+        let (server, stop_tx) = self.server.take().context("NATS is not running")?;
+        stop_server(server, stop_tx)
+            .await
+            .context("failed to stop NATS")
+    }
+
+    /// Start the server again on the same port
+    pub async fn restart(&mut self) -> Result<()> {
+        ensure!(self.server.is_none(), "NATS is already running");
+        let server = spawn_server(&mut nats_command(
+            self.port,
+            self.jetstream_dir.path(),
+            None,
+        ))
+        .await
+        .context("failed to restart NATS")?;
+        self.server = Some(server);
+        Ok(())
+    }
+
+    /// Kill the server, wait for `downtime` and start it again
+    pub async fn bounce(&mut self, downtime: Duration) -> Result<()> {
+        self.kill().await?;
+        sleep(downtime).await;
+        self.restart().await
+    }
+
+    /// Stop the server for good
+    pub async fn stop(mut self) -> Result<()> {
+        if self.server.is_some() {
+            self.kill().await
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Wait until `nats_client` is (re)connected to the server
+pub async fn wait_connected(nats_client: &NatsClient, within: Duration) -> Result<()> {
+    assert_eventually(within, Duration::from_millis(100), || async {
+        ensure!(
+            nats_client.connection_state() == State::Connected,
+            "NATS client is not connected"
+        );
+        Ok(())
+    })
+    .await
+}
+
+/// A host, which can be killed and restarted with the same configuration
+///
+/// Since the host key is part of the configuration, the restarted host has the same identity.
+pub struct RestartableHost {
+    config: HostConfig,
+    host: Option<(Arc<Host>, Pin<Box<dyn Future<Output = Result<()>>>>)>,
+}
+
+impl RestartableHost {
+    /// Start a host using `config`
+    ///
+    /// `config` should contain a host key, otherwise the host will have a different identity
+    /// after each restart.
+    pub async fn start(config: HostConfig) -> Result<Self> {
+        let mut host = Self { config, host: None };
+        host.restart().await?;
+        Ok(host)
+    }
+
+    /// The currently running host, if any
+    pub fn host(&self) -> Option<&Arc<Host>> {
+        self.host.as_ref().map(|(host, _)| host)
+    }
+
+    /// Shut down the host, stopping all actors and providers running on it
+    pub async fn kill(&mut self) -> Result<()> {
+        let (_, shutdown) = self.host.take().context("host is not running")?;
+        shutdown.await.context("failed to shutdown host")
+    }
+
+    /// Start the host again using the same configuration
+    pub async fn restart(&mut self) -> Result<()> {
+        ensure!(self.host.is_none(), "host is already running");
+        let (host, shutdown) = Host::new(self.config.clone())
+            .await
+            .context("failed to initialize host")?;
+        self.host = Some((host, Box::pin(shutdown)));
+        Ok(())
+    }
+
+    /// Shut down the host, wait for `downtime` and start it again
+    pub async fn bounce(&mut self, downtime: Duration) -> Result<()> {
+        self.kill().await?;
+        sleep(downtime).await;
+        self.restart().await
+    }
+
+    /// Shut down the host for good
+    pub async fn stop(mut self) -> Result<()> {
+        if self.host.is_some() {
+            self.kill().await
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Kill all provider processes with public key `provider_id` started by hosts on this machine
+/// using `SIGKILL`, without notifying the host
+pub async fn kill_provider(provider_id: &str) -> Result<()> {
+    let pattern = temp_dir()
+        .join("wasmcloudcache")
+        .join(provider_id)
+        .display()
+        .to_string();
+    let status = Command::new("pkill")
+        .args(["-KILL", "-f", &pattern])
+        .status()
+        .await
+        .context("failed to run `pkill`")?;
+    // `pkill` exits with 1 if no processes matched
+    match status.code() {
+        Some(0) => Ok(()),
+        Some(1) => bail!("no provider process with ID `{provider_id}` found"),
+        _ => bail!("`pkill` failed with {status}"),
+    }
+}
+
+/// Repeatedly call `f` every `interval` until it succeeds, failing with the last error once
+/// `within` has elapsed
+pub async fn assert_eventually<F, Fut, T>(
+    within: Duration,
+    interval: Duration,
+    mut f: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let deadline = Instant::now() + within;
+    loop {
+        match f().await {
+            Ok(v) => return Ok(v),
+            Err(e) if Instant::now() >= deadline => {
+                return Err(e.context(format!("condition not met within {within:?}")))
+            }
+            Err(_) => sleep(interval).await,
+        }
+    }
+}
+
+/// Assert that `f` keeps succeeding for the whole `period`, checked every `interval`
+pub async fn assert_consistently<F, Fut>(
+    period: Duration,
+    interval: Duration,
+    mut f: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<()>>,
+{
+    let deadline = Instant::now() + period;
+    while Instant::now() < deadline {
+        f().await.context("condition violated")?;
+        sleep(interval).await;
+    }
+    Ok(())
+}
+
+/// Records all messages published on a NATS subject, used to assert how many times a message
+/// was (re)delivered or whether it ended up on a dead-letter subject
+pub struct MessageRecorder {
+    messages: Arc<Mutex<Vec<async_nats::Message>>>,
+    task: JoinHandle<()>,
+}
+
+impl MessageRecorder {
+    /// Start recording messages published on `subject`, which may contain wildcards
+    pub async fn subscribe(nats_client: &NatsClient, subject: impl Into<String>) -> Result<Self> {
+        let subject = subject.into();
+        let mut sub = nats_client
+            .subscribe(subject.clone())
+            .await
+            .with_context(|| format!("failed to subscribe to `{subject}`"))?;
+        nats_client
+            .flush()
+            .await
+            .context("failed to flush subscription")?;
+        let messages = Arc::default();
+        let task = tokio::spawn({
+            let messages = Arc::clone(&messages);
+            async move {
+                while let Some(msg) = sub.next().await {
+                    if let Ok(mut messages) = messages.lock() {
+                        messages.push(msg);
+                    }
+                }
+            }
+        });
+        Ok(Self { messages, task })
+    }
+
+    /// All messages recorded so far
+    pub fn messages(&self) -> Result<Vec<async_nats::Message>> {
+        self.messages
+            .lock()
+            .map(|messages| messages.clone())
+            .map_err(|_| anyhow!("message recorder lock poisoned"))
+    }
+
+    /// Number of messages recorded so far
+    pub fn count(&self) -> Result<usize> {
+        self.messages
+            .lock()
+            .map(|messages| messages.len())
+            .map_err(|_| anyhow!("message recorder lock poisoned"))
+    }
+
+    /// Wait until at least `n` messages have been recorded, returning them
+    pub async fn assert_received(
+        &self,
+        n: usize,
+        within: Duration,
+    ) -> Result<Vec<async_nats::Message>> {
+        assert_eventually(within, Duration::from_millis(50), || async {
+            let messages = self.messages()?;
+            ensure!(
+                messages.len() >= n,
+                "expected at least {n} messages, got {}",
+                messages.len()
+            );
+            Ok(messages)
+        })
+        .await
+    }
+
+    /// Assert that a message was delivered exactly `attempts` times, i.e. that it was retried
+    /// `attempts - 1` times and not retried any further within `settle`
+    pub async fn assert_attempts(&self, attempts: usize, settle: Duration) -> Result<()> {
+        self.assert_received(attempts, settle).await?;
+        sleep(settle).await;
+        let n = self.count()?;
+        ensure!(
+            n == attempts,
+            "expected exactly {attempts} attempts, got {n}"
+        );
+        Ok(())
+    }
+
+    /// Assert that no message is recorded within `period`, e.g. that nothing was dead-lettered
+    pub async fn assert_none(&self, period: Duration) -> Result<()> {
+        assert_consistently(period, Duration::from_millis(50), || async {
+            let n = self.count()?;
+            ensure!(n == 0, "expected no messages, got {n}");
+            Ok(())
+        })
+        .await
+    }
+}
+
+impl Drop for MessageRecorder {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Run `fut` concurrently with `fault`, which is started after `delay`, returning the output
+/// of both. This is used to inject a fault while an invocation is in flight.
+pub async fn with_fault<T, U>(
+    fut: impl Future<Output = T>,
+    delay: Duration,
+    fault: impl Future<Output = Result<U>>,
+) -> Result<(T, U)> {
+    let fault = async move {
+        sleep(delay).await;
+        fault.await.context("failed to inject fault")
+    };
+    let (res, fault) = tokio::join!(timeout(Duration::from_secs(60), fut), fault);
+    let res = res.context("timed out waiting for operation under fault")?;
+    Ok((res, fault?))
+}
@@ -7,7 +7,7 @@ use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use url::Url;
 
-use super::{free_port, spawn_server};
+use super::{reserve_port, spawn_server_on};
 
 /// Spawn a minio server to use for testing
 pub async fn start_minio() -> Result<(
@@ -16,17 +16,19 @@ pub async fn start_minio() -> Result<(
     tempfile::TempDir,
     Url,
 )> {
-    let port = free_port().await?;
+    let reservation = reserve_port().await?;
+    let port = reservation.port();
     let host = "127.0.0.1";
     let address = format!("{host}:{port}");
     let data_dir = tempfile::tempdir().context("failed to create temp dir for minio")?;
-    let (server, stop_tx) = spawn_server(
+    let (server, stop_tx) = spawn_server_on(
         Command::new(env::var("TEST_MINIO_BIN").as_deref().unwrap_or("minio")).args([
             "server",
             "--address",
             address.as_ref(),
             format!("{}", data_dir.path().display()).as_ref(),
         ]),
+        reservation,
     )
     .await
     .context("failed to start minio")?;
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::net::Ipv6Addr;
 use std::path::Path;
 use std::pin::pin;
 use std::process::ExitStatus;
+use std::sync::{Mutex, OnceLock};
 use std::time::Duration;
 
 use anyhow::{anyhow, bail, ensure, Context, Result};
@@ -22,8 +23,10 @@ use url::Url;
 use wascap::jwt;
 use wasmcloud_control_interface::CtlOperationAck;
 
+pub mod chaos;
 pub mod minio;
 pub mod nats;
+pub mod provider;
 pub mod redis;
 pub mod vault;
 
@@ -31,13 +34,58 @@ pub fn tempdir() -> Result<TempDir> {
     tempfile::tempdir().context("failed to create temporary directory")
 }
 
+/// Ports handed out by [`reserve_port`] during the lifetime of the test process
+static RESERVED_PORTS: OnceLock<Mutex<HashSet<u16>>> = OnceLock::new();
+
+/// A TCP port reserved for a single test server
+///
+/// The port stays bound until [`PortReservation::release`] is called, which should happen right
+/// before the process that will bind it is spawned. The port is never handed out again by
+/// [`reserve_port`] or [`free_port`] within the same test process, even once released.
+#[derive(Debug)]
+pub struct PortReservation {
+    port: u16,
+    listener: TcpListener,
+}
+
+impl PortReservation {
+    /// The reserved port
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Stop holding the port, so that it can be bound by a server
+    pub fn release(self) -> u16 {
+        drop(self.listener);
+        self.port
+    }
+}
+
+/// Reserve a free TCP port, which is held until the reservation is released
+pub async fn reserve_port() -> Result<PortReservation> {
+    loop {
+        let listener = TcpListener::bind((Ipv6Addr::UNSPECIFIED, 0))
+            .await
+            .context("failed to start TCP listener")?;
+        let port = listener
+            .local_addr()
+            .context("failed to query listener local address")?
+            .port();
+        let mut reserved = RESERVED_PORTS
+            .get_or_init(Mutex::default)
+            .lock()
+            .map_err(|_| anyhow!("reserved port registry lock poisoned"))?;
+        // The OS may hand out a port previously reserved and released by another test, keep the
+        // listener bound while looking for one that has not been used yet
+        if reserved.insert(port) {
+            return Ok(PortReservation { port, listener });
+        }
+    }
+}
+
+/// Find a free TCP port, which will not be handed out again within this test process
 pub async fn free_port() -> Result<u16> {
-    TcpListener::bind((Ipv6Addr::UNSPECIFIED, 0))
-        .await
-        .context("failed to start TCP listener")?
-        .local_addr()
-        .context("failed to query listener local address")
-        .map(|v| v.port())
+    reserve_port().await.map(PortReservation::release)
 }
 
 pub async fn assert_start_actor(
@@ -250,6 +298,15 @@ pub async fn assert_delete_label(
         .map_err(|e| anyhow!(e).context("failed to put label"))
 }
 
+/// Spawn a server bound to a reserved port, releasing the reservation right before spawning
+pub async fn spawn_server_on(
+    cmd: &mut Command,
+    port: PortReservation,
+) -> Result<(JoinHandle<Result<ExitStatus>>, oneshot::Sender<()>)> {
+    port.release();
+    spawn_server(cmd).await
+}
+
 pub async fn spawn_server(
     cmd: &mut Command,
 ) -> Result<(JoinHandle<Result<ExitStatus>>, oneshot::Sender<()>)> {
@@ -1,16 +1,74 @@
 use std::env;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use anyhow::{Context, Result};
 use async_nats::connection::State;
 use async_nats::Client as NatsClient;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use nkeys::KeyPair;
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa};
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::fs;
 use tokio::process::Command;
 use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use tokio::time::{sleep, timeout, Duration};
 use url::Url;
 
-use super::{free_port, spawn_server, tempdir};
+use super::{reserve_port, spawn_server_on, tempdir};
+
+/// Options controlling how a NATS server is started by [`start_nats_with`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NatsOptions {
+    /// Serve connections over TLS using a freshly generated CA and server certificate
+    pub tls: bool,
+    /// Run the server in operator mode, requiring clients to authenticate with a generated user
+    /// JWT and seed
+    pub auth: bool,
+}
+
+/// Throwaway TLS and authentication material generated for a NATS server started by
+/// [`start_nats_with`]
+///
+/// All files live in a temporary directory, which is removed once this value is dropped.
+pub struct NatsCredentials {
+    /// PEM-encoded CA certificate that signed the server certificate, if TLS is enabled
+    pub ca_cert: Option<PathBuf>,
+    /// User JWT clients must authenticate with, if authentication is enabled
+    pub user_jwt: Option<String>,
+    /// User key pair clients must sign the server nonce with, if authentication is enabled
+    pub user_key: Option<Arc<KeyPair>>,
+    /// `.creds` file containing both the user JWT and seed, if authentication is enabled
+    pub creds_file: Option<PathBuf>,
+    _dir: TempDir,
+}
+
+impl NatsCredentials {
+    /// Apply the generated TLS and authentication material to [`async_nats::ConnectOptions`]
+    pub fn apply(&self, opts: async_nats::ConnectOptions) -> async_nats::ConnectOptions {
+        let opts = if let Some(ca_cert) = &self.ca_cert {
+            opts.require_tls(true)
+                .add_root_certificates(ca_cert.clone())
+        } else {
+            opts
+        };
+        match (&self.user_jwt, &self.user_key) {
+            (Some(jwt), Some(key)) => {
+                let key = Arc::clone(key);
+                opts.jwt(jwt.clone(), move |nonce| {
+                    let key = Arc::clone(&key);
+                    async move { key.sign(&nonce).map_err(async_nats::AuthError::new) }
+                })
+            }
+            _ => opts,
+        }
+    }
+}
 
 pub async fn start_nats() -> Result<(
     JoinHandle<Result<ExitStatus>>,
@@ -18,33 +76,79 @@ pub async fn start_nats() -> Result<(
     Url,
     NatsClient,
 )> {
-    let port = free_port().await?;
+    let (server, stop_tx, url, nats_client, _) = start_nats_with(NatsOptions::default()).await?;
+    Ok((server, stop_tx, url, nats_client))
+}
+
+/// Start NATS as a subprocess on a random port, optionally with TLS and/or JWT authentication
+/// enabled.
+///
+/// The returned client is already configured with the generated credentials. Other clients can
+/// be configured using [`NatsCredentials::apply`]. Note, that clients relying on the platform
+/// trust store (e.g. the host) will only trust the generated CA if `SSL_CERT_FILE` points to
+/// [`NatsCredentials::ca_cert`].
+pub async fn start_nats_with(
+    NatsOptions { tls, auth }: NatsOptions,
+) -> Result<(
+    JoinHandle<Result<ExitStatus>>,
+    oneshot::Sender<()>,
+    Url,
+    NatsClient,
+    NatsCredentials,
+)> {
+    let reservation = reserve_port().await?;
+    let port = reservation.port();
     let url =
         Url::parse(&format!("nats://localhost:{port}")).context("failed to parse NATS URL")?;
     let jetstream_dir = tempdir()?;
-    let (server, stop_tx) = spawn_server(
-        Command::new(
-            env::var("WASMCLOUD_NATS")
-                .as_deref()
-                .unwrap_or("nats-server"),
-        )
-        .args([
-            "-js",
-            "-D",
-            "-T=false",
-            "-p",
-            &port.to_string(),
-            "-sd",
-            jetstream_dir.path().display().to_string().as_str(),
-        ]),
-    )
-    .await
-    .context("failed to start NATS")?;
+    let dir = tempdir()?;
+
+    let mut config = String::new();
+    let ca_cert = if tls {
+        let ca_cert = generate_certificates(dir.path()).await?;
+        config.push_str(&format!(
+            "tls {{\n  cert_file: {:?}\n  key_file: {:?}\n}}\n",
+            dir.path().join("server.pem"),
+            dir.path().join("server-key.pem"),
+        ));
+        Some(ca_cert)
+    } else {
+        None
+    };
+    let (user_jwt, user_key, creds_file) = if auth {
+        let (operator_config, user_jwt, user_key, creds_file) =
+            generate_operator(dir.path()).await?;
+        config.push_str(&operator_config);
+        (Some(user_jwt), Some(Arc::new(user_key)), Some(creds_file))
+    } else {
+        (None, None, None)
+    };
+    let config_path = dir.path().join("nats-server.conf");
+    fs::write(&config_path, config)
+        .await
+        .context("failed to write NATS server config")?;
+
+    let mut cmd = nats_command(
+        port,
+        jetstream_dir.path(),
+        (tls || auth).then_some(config_path.as_path()),
+    );
+    let (server, stop_tx) = spawn_server_on(&mut cmd, reservation)
+        .await
+        .context("failed to start NATS")?;
+
+    let creds = NatsCredentials {
+        ca_cert,
+        user_jwt,
+        user_key,
+        creds_file,
+        _dir: dir,
+    };
 
     // Wait until nats is ready to take connections
     let nats_client = async_nats::connect_with_options(
         url.as_str(),
-        async_nats::ConnectOptions::new().retry_on_initial_connect(),
+        creds.apply(async_nats::ConnectOptions::new().retry_on_initial_connect()),
     )
     .await
     .context("failed to build nats client")?;
@@ -59,5 +163,173 @@ pub async fn start_nats() -> Result<(
     .await
     .context("failed to ensure connection to NATS server")?;
 
-    Ok((server, stop_tx, url, nats_client))
+    Ok((server, stop_tx, url, nats_client, creds))
+}
+
+/// Build the command used to run NATS on `port`, storing JetStream data in `jetstream_dir`
+pub fn nats_command(port: u16, jetstream_dir: &Path, config: Option<&Path>) -> Command {
+    let mut cmd = Command::new(
+        env::var("WASMCLOUD_NATS")
+            .as_deref()
+            .unwrap_or("nats-server"),
+    );
+    cmd.args([
+        "-js",
+        "-D",
+        "-T=false",
+        "-p",
+        &port.to_string(),
+        "-sd",
+        jetstream_dir.display().to_string().as_str(),
+    ]);
+    if let Some(config) = config {
+        cmd.args(["-c", config.display().to_string().as_str()]);
+    }
+    cmd
+}
+
+/// Generate a self-signed CA and a `localhost` server certificate signed by it in `dir`,
+/// returning the path to the CA certificate
+async fn generate_certificates(dir: &Path) -> Result<PathBuf> {
+    let mut ca_params = CertificateParams::default();
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "wasmCloud test CA");
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    let ca = Certificate::from_params(ca_params).context("failed to generate CA certificate")?;
+
+    let mut server_params = CertificateParams::new(vec!["localhost".to_string()]);
+    server_params
+        .distinguished_name
+        .push(DnType::CommonName, "localhost");
+    let server =
+        Certificate::from_params(server_params).context("failed to generate server certificate")?;
+
+    let ca_path = dir.join("ca.pem");
+    fs::write(
+        &ca_path,
+        ca.serialize_pem()
+            .context("failed to serialize CA certificate")?,
+    )
+    .await
+    .context("failed to write CA certificate")?;
+    fs::write(
+        dir.join("server.pem"),
+        server
+            .serialize_pem_with_signer(&ca)
+            .context("failed to sign server certificate")?,
+    )
+    .await
+    .context("failed to write server certificate")?;
+    fs::write(
+        dir.join("server-key.pem"),
+        server.serialize_private_key_pem(),
+    )
+    .await
+    .context("failed to write server key")?;
+    Ok(ca_path)
+}
+
+/// Encode and sign a NATS JWT with `claims` under the `nats` key
+fn encode_nats_jwt(
+    issuer: &KeyPair,
+    subject: &str,
+    name: &str,
+    claims: serde_json::Value,
+) -> Result<String> {
+    let iat = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .context("system time is before UNIX epoch")?
+        .as_secs();
+    let header = json!({ "typ": "JWT", "alg": "ed25519-nkey" });
+    let payload = json!({
+        "jti": format!("{:032X}", rand::random::<u128>()),
+        "iat": iat,
+        "iss": issuer.public_key(),
+        "name": name,
+        "sub": subject,
+        "nats": claims,
+    });
+    let header =
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header).context("failed to encode JWT header")?);
+    let payload = URL_SAFE_NO_PAD
+        .encode(serde_json::to_vec(&payload).context("failed to encode JWT payload")?);
+    let signing_input = format!("{header}.{payload}");
+    let signature = issuer
+        .sign(signing_input.as_bytes())
+        .context("failed to sign JWT")?;
+    Ok(format!(
+        "{signing_input}.{}",
+        URL_SAFE_NO_PAD.encode(signature)
+    ))
+}
+
+/// Generate an operator, account and user in `dir`, returning the server config fragment
+/// enabling operator mode, the user JWT and key and the path to the user `.creds` file
+async fn generate_operator(dir: &Path) -> Result<(String, String, KeyPair, PathBuf)> {
+    let operator = KeyPair::new_operator();
+    let account = KeyPair::new_account();
+    let user = KeyPair::new_user();
+
+    let operator_jwt = encode_nats_jwt(
+        &operator,
+        &operator.public_key(),
+        "test-operator",
+        json!({ "type": "operator", "version": 2 }),
+    )?;
+    let account_jwt = encode_nats_jwt(
+        &operator,
+        &account.public_key(),
+        "test-account",
+        json!({
+            "limits": {
+                "subs": -1,
+                "data": -1,
+                "payload": -1,
+                "imports": -1,
+                "exports": -1,
+                "wildcards": true,
+                "conn": -1,
+                "leaf": -1,
+                "mem_storage": -1,
+                "disk_storage": -1,
+                "streams": -1,
+                "consumer": -1,
+            },
+            "default_permissions": { "pub": {}, "sub": {} },
+            "type": "account",
+            "version": 2,
+        }),
+    )?;
+    let user_jwt = encode_nats_jwt(
+        &account,
+        &user.public_key(),
+        "test-user",
+        json!({
+            "pub": {},
+            "sub": {},
+            "subs": -1,
+            "data": -1,
+            "payload": -1,
+            "type": "user",
+            "version": 2,
+        }),
+    )?;
+
+    let seed = user.seed().context("failed to get user seed")?;
+    let creds_file = dir.join("user.creds");
+    fs::write(
+        &creds_file,
+        format!(
+            "-----BEGIN NATS USER JWT-----\n{user_jwt}\n------END NATS USER JWT------\n\n-----BEGIN USER NKEY SEED-----\n{seed}\n------END USER NKEY SEED------\n"
+        ),
+    )
+    .await
+    .context("failed to write user creds file")?;
+
+    let config = format!(
+        "operator: {operator_jwt}\nresolver: MEMORY\nresolver_preload: {{\n  {}: {account_jwt}\n}}\n",
+        account.public_key(),
+    );
+    Ok((config, user_jwt, user, creds_file))
 }
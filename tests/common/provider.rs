@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::env::consts::{ARCH, OS};
+use std::path::Path;
+use std::process::{ExitStatus, Stdio};
+use std::sync::Arc;
+
+use anyhow::{bail, ensure, Context, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use nkeys::KeyPair;
+use provider_archive::ProviderArchive;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tokio::process::{Child, Command};
+use tokio::time::{timeout, Duration};
+use url::Url;
+use wasmcloud_core::{
+    HealthCheckResponse, HostData, Invocation, InvocationResponse, LinkDefinition, WasmCloudEntity,
+};
+
+use super::tempdir;
+
+/// A capability provider binary running outside of a host, driven directly over NATS by the test
+/// acting as the host
+pub struct TestProvider {
+    /// NATS client used to interact with the provider
+    pub nats_client: async_nats::Client,
+    /// Lattice prefix the provider subscribes on
+    pub lattice_prefix: String,
+    /// Key of the synthetic host the provider believes it was started by
+    pub host_key: Arc<KeyPair>,
+    /// Cluster key used to sign invocations sent to the provider
+    pub cluster_key: Arc<KeyPair>,
+    /// Public key of the provider
+    pub provider_id: String,
+    /// Contract ID implemented by the provider
+    pub contract_id: String,
+    /// Link name the provider was started with
+    pub link_name: String,
+    child: Child,
+    _dir: TempDir,
+}
+
+impl TestProvider {
+    /// Extract the native binary of the provider archive at `path` and start it
+    pub async fn start_par(
+        path: impl AsRef<Path>,
+        nats_url: &Url,
+        nats_client: async_nats::Client,
+        lattice_prefix: &str,
+        link_name: &str,
+        config_json: Option<String>,
+    ) -> Result<Self> {
+        let target = format!("{ARCH}-{OS}");
+        let par = ProviderArchive::try_load_target_from_file(path, &target)
+            .await
+            .map_err(|e| anyhow::anyhow!(e).context("failed to load provider archive"))?;
+        let claims = par.claims().context("provider archive claims missing")?;
+        let contract_id = claims
+            .metadata
+            .as_ref()
+            .map(|md| md.capid.clone())
+            .context("provider archive claims metadata missing")?;
+        let bin = par
+            .target_bytes(&target)
+            .with_context(|| format!("target `{target}` not found"))?;
+
+        let dir = tempdir()?;
+        let exe = dir.path().join("provider");
+        let mut file = {
+            let mut opts = fs::OpenOptions::new();
+            opts.create(true).truncate(true).write(true);
+            #[cfg(unix)]
+            opts.mode(0o755);
+            opts.open(&exe)
+                .await
+                .context("failed to create provider binary")?
+        };
+        file.write_all(&bin)
+            .await
+            .context("failed to write provider binary")?;
+        file.flush()
+            .await
+            .context("failed to flush provider binary")?;
+        drop(file);
+
+        Self::start(
+            &exe,
+            claims.subject,
+            contract_id,
+            nats_url,
+            nats_client,
+            lattice_prefix,
+            link_name,
+            config_json,
+            dir,
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)] // Shush clippy, it's a test function
+    async fn start(
+        exe: &Path,
+        provider_id: String,
+        contract_id: String,
+        nats_url: &Url,
+        nats_client: async_nats::Client,
+        lattice_prefix: &str,
+        link_name: &str,
+        config_json: Option<String>,
+        dir: TempDir,
+    ) -> Result<Self> {
+        let host_key = Arc::new(KeyPair::new_server());
+        let cluster_key = Arc::new(KeyPair::new_cluster());
+        let host_data = HostData {
+            host_id: host_key.public_key(),
+            lattice_rpc_prefix: lattice_prefix.to_string(),
+            link_name: link_name.to_string(),
+            lattice_rpc_url: nats_url.to_string(),
+            provider_key: provider_id.clone(),
+            invocation_seed: cluster_key
+                .seed()
+                .context("failed to get cluster key seed")?,
+            instance_id: format!("{:032x}", rand::random::<u128>()),
+            cluster_issuers: vec![cluster_key.public_key()],
+            config_json,
+            ..Default::default()
+        };
+        let host_data = serde_json::to_vec(&host_data).context("failed to serialize host data")?;
+
+        let mut child = Command::new(exe)
+            .env_clear()
+            .env("RUST_LOG", "trace")
+            .stdin(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .context("failed to spawn provider")?;
+        let mut stdin = child
+            .stdin
+            .take()
+            .context("failed to take provider stdin")?;
+        stdin
+            .write_all(STANDARD.encode(host_data).as_bytes())
+            .await
+            .context("failed to write host data")?;
+        stdin
+            .write_all(b"\r\n")
+            .await
+            .context("failed to write newline")?;
+        stdin.shutdown().await.context("failed to close stdin")?;
+
+        let provider = Self {
+            nats_client,
+            lattice_prefix: lattice_prefix.to_string(),
+            host_key,
+            cluster_key,
+            provider_id,
+            contract_id,
+            link_name: link_name.to_string(),
+            child,
+            _dir: dir,
+        };
+        provider
+            .wait_healthy()
+            .await
+            .context("provider did not become healthy")?;
+        Ok(provider)
+    }
+
+    fn topic(&self, suffix: &str) -> String {
+        format!(
+            "wasmbus.rpc.{}.{}.{}{suffix}",
+            self.lattice_prefix, self.provider_id, self.link_name
+        )
+    }
+
+    /// Perform a health check request against the provider
+    pub async fn health_check(&self) -> Result<HealthCheckResponse> {
+        let res = self
+            .nats_client
+            .request(self.topic(".health"), "".into())
+            .await
+            .context("failed to perform health check request")?;
+        rmp_serde::from_slice(&res.payload).context("failed to decode health check response")
+    }
+
+    async fn wait_healthy(&self) -> Result<()> {
+        timeout(Duration::from_secs(30), async {
+            loop {
+                if let Ok(HealthCheckResponse { healthy: true, .. }) = self.health_check().await {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        })
+        .await
+        .context("timed out waiting for provider health check")
+    }
+
+    /// Link the actor with `actor_id` to the provider
+    pub async fn put_link(&self, actor_id: &str, values: HashMap<String, String>) -> Result<()> {
+        let ld = LinkDefinition {
+            actor_id: actor_id.to_string(),
+            provider_id: self.provider_id.clone(),
+            link_name: self.link_name.clone(),
+            contract_id: self.contract_id.clone(),
+            values: values.into_iter().collect(),
+        };
+        let payload = rmp_serde::to_vec_named(&ld).context("failed to encode link definition")?;
+        self.nats_client
+            .publish(self.topic(".linkdefs.put"), payload.into())
+            .await
+            .context("failed to publish link definition")?;
+        self.nats_client
+            .flush()
+            .await
+            .context("failed to flush link definition")
+    }
+
+    /// Remove the link between the actor with `actor_id` and the provider
+    pub async fn delete_link(&self, actor_id: &str) -> Result<()> {
+        let ld = LinkDefinition {
+            actor_id: actor_id.to_string(),
+            provider_id: self.provider_id.clone(),
+            link_name: self.link_name.clone(),
+            contract_id: self.contract_id.clone(),
+            values: Vec::default(),
+        };
+        let payload = rmp_serde::to_vec_named(&ld).context("failed to encode link definition")?;
+        self.nats_client
+            .publish(self.topic(".linkdefs.del"), payload.into())
+            .await
+            .context("failed to publish link deletion")?;
+        self.nats_client
+            .flush()
+            .await
+            .context("failed to flush link deletion")
+    }
+
+    /// Invoke `operation` on the provider on behalf of the linked actor with `actor_id`, returning
+    /// the raw response bytes
+    pub async fn invoke(
+        &self,
+        actor_id: &str,
+        operation: &str,
+        msg: impl Into<Vec<u8>>,
+    ) -> Result<Vec<u8>> {
+        let inv = Invocation::new(
+            &self.cluster_key,
+            &self.host_key,
+            WasmCloudEntity {
+                public_key: actor_id.to_string(),
+                ..Default::default()
+            },
+            WasmCloudEntity {
+                public_key: self.provider_id.clone(),
+                link_name: self.link_name.clone(),
+                contract_id: self.contract_id.clone(),
+            },
+            operation,
+            msg.into(),
+            Vec::default(),
+        )
+        .context("failed to create invocation")?;
+        let payload = rmp_serde::to_vec_named(&inv).context("failed to encode invocation")?;
+        let res = self
+            .nats_client
+            .request(self.topic(""), payload.into())
+            .await
+            .context("failed to perform invocation")?;
+        let InvocationResponse { msg, error, .. } =
+            rmp_serde::from_slice(&res.payload).context("failed to decode invocation response")?;
+        if let Some(error) = error {
+            bail!("invocation failed: {error}")
+        }
+        Ok(msg)
+    }
+
+    /// Kill the provider process without requesting a shutdown, simulating a crash
+    pub async fn kill(mut self) -> Result<()> {
+        self.child.kill().await.context("failed to kill provider")
+    }
+
+    /// Request the provider to shut down and wait for the process to exit
+    pub async fn shutdown(mut self) -> Result<ExitStatus> {
+        let res = self
+            .nats_client
+            .request(
+                self.topic(".shutdown"),
+                serde_json::to_vec(&json!({ "host_id": self.host_key.public_key() }))
+                    .context("failed to encode shutdown message")?
+                    .into(),
+            )
+            .await
+            .context("failed to request provider shutdown")?;
+        ensure!(res.payload.as_ref() == b"shutting down");
+        timeout(Duration::from_secs(10), self.child.wait())
+            .await
+            .context("timed out waiting for provider to exit")?
+            .context("failed to wait for provider to exit")
+    }
+}
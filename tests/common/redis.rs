@@ -7,13 +7,14 @@ use tokio::sync::oneshot;
 use tokio::task::JoinHandle;
 use url::Url;
 
-use super::{free_port, spawn_server};
+use super::{reserve_port, spawn_server_on};
 
 pub async fn start_redis() -> Result<(JoinHandle<Result<ExitStatus>>, oneshot::Sender<()>, Url)> {
-    let port = free_port().await?;
+    let reservation = reserve_port().await?;
+    let port = reservation.port();
     let url =
         Url::parse(&format!("redis://localhost:{port}")).context("failed to parse Redis URL")?;
-    let (server, stop_tx) = spawn_server(
+    let (server, stop_tx) = spawn_server_on(
         Command::new(
             env::var("WASMCLOUD_REDIS")
                 .as_deref()
@@ -30,6 +31,7 @@ pub async fn start_redis() -> Result<(JoinHandle<Result<ExitStatus>>, oneshot::S
             "--dbfilename",
             format!("test-redis-{port}.rdb").as_str(),
         ]),
+        reservation,
     )
     .await
     .context("failed to start Redis")?;
@@ -9,7 +9,7 @@ use url::Url;
 use vaultrs::client::{Client, VaultClient, VaultClientSettingsBuilder};
 use vaultrs::sys::ServerStatus;
 
-use super::{free_port, spawn_server};
+use super::{reserve_port, spawn_server_on};
 
 /// Start Hashicorp Vault as a subprocess on a random port
 pub async fn start_vault(
@@ -21,19 +21,23 @@ pub async fn start_vault(
     VaultClient,
 )> {
     let bin_path = std::env::var("TEST_VAULT_BIN").unwrap_or("vault".to_string());
-    let port = free_port()
+    let reservation = reserve_port()
         .await
-        .context("failed to find open port for NATS")?;
+        .context("failed to find open port for Vault")?;
+    let port = reservation.port();
     let host = "127.0.0.1";
-    let (server, stop_tx) = spawn_server(Command::new(bin_path).args([
-        "server",
-        "-dev",
-        "-dev-listen-address",
-        &format!("{host}:{port}"),
-        "-dev-root-token-id",
-        token.as_ref(),
-        "-dev-no-store-token",
-    ]))
+    let (server, stop_tx) = spawn_server_on(
+        Command::new(bin_path).args([
+            "server",
+            "-dev",
+            "-dev-listen-address",
+            &format!("{host}:{port}"),
+            "-dev-root-token-id",
+            token.as_ref(),
+            "-dev-no-store-token",
+        ]),
+        reservation,
+    )
     .await
     .context("failed to start test Vault instance")?;
     let url = format!("http://{host}:{port}");
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::net::Ipv6Addr;
 use std::sync::Arc;
 
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, ensure, Context, Result};
 use nkeys::KeyPair;
 use tokio::fs;
 use tokio::time::Duration;
@@ -17,6 +17,7 @@ use wasmcloud_host::wasmbus::{Host, HostConfig};
 pub mod common;
 use common::free_port;
 
+use crate::common::chaos::assert_eventually;
 use crate::common::nats::start_nats;
 use crate::common::vault::start_vault;
 use crate::common::{
@@ -293,6 +294,235 @@ async fn kv_vault_suite() -> Result<()> {
         complex_value
     );
 
+    // Set a value with a TTL
+    let resp_json: ResponseEnvelope<SetResponseData> = http_client
+        .post(format!("{httpserver_base_url}/set"))
+        .body(r#"{"key": "ttl", "value": "expiring", "expires": 3600}"#)
+        .send()
+        .await
+        .context("failed to perform POST /set with TTL")?
+        .json()
+        .await
+        .context("failed to read /set response body as json")?;
+    assert_eq!(resp_json.status, "success", "set with TTL succeeded");
+    let metadata = vaultrs::kv2::read_metadata(&vault_client, "secret", "ttl")
+        .await
+        .context("failed to read secret metadata")?;
+    assert_eq!(metadata.delete_version_after, "1h0m0s");
+    let version = metadata
+        .versions
+        .get(&metadata.current_version.to_string())
+        .context("current version missing from metadata")?;
+    assert!(
+        !version.deletion_time.is_empty(),
+        "version written with TTL expires"
+    );
+
+    // Setting the value without a TTL only writes the value, leaving the TTL of the key in place
+    let resp_json: ResponseEnvelope<SetResponseData> = http_client
+        .post(format!("{httpserver_base_url}/set"))
+        .body(r#"{"key": "ttl", "value": "persistent"}"#)
+        .send()
+        .await
+        .context("failed to perform POST /set without TTL")?
+        .json()
+        .await
+        .context("failed to read /set response body as json")?;
+    assert_eq!(resp_json.status, "success", "set without TTL succeeded");
+    let metadata = vaultrs::kv2::read_metadata(&vault_client, "secret", "ttl")
+        .await
+        .context("failed to read secret metadata")?;
+    assert_eq!(metadata.delete_version_after, "1h0m0s");
+
+    // Relink the actor with a token, which may only access values and not their metadata
+    vaultrs::sys::policy::set(
+        &vault_client,
+        "data-only",
+        r#"path "secret/data/*" { capabilities = ["create", "read", "update", "delete"] }"#,
+    )
+    .await
+    .context("failed to create data-only policy")?;
+    let data_only_token = vaultrs::token::new(
+        &vault_client,
+        Some(
+            &mut vaultrs::api::token::requests::CreateTokenRequest::builder()
+                .policies(vec!["data-only".to_string()]),
+        ),
+    )
+    .await
+    .context("failed to create data-only token")?
+    .client_token;
+    ctl_client
+        .remove_link(
+            &kv_http_smithy_claims.subject,
+            "wasmcloud:keyvalue",
+            "default",
+        )
+        .await
+        .map_err(|e| anyhow!(e).context("failed to remove link"))?;
+    assert_advertise_link(
+        &ctl_client,
+        &kv_http_smithy_claims,
+        &kv_vault_provider_key,
+        "wasmcloud:keyvalue",
+        "default",
+        HashMap::from([
+            ("ADDR".into(), vault_url.to_string()),
+            ("TOKEN".into(), data_only_token),
+        ]),
+    )
+    .await?;
+
+    // Setting a TTL requires access to the metadata, which also shows that the new link is used
+    assert_eventually(
+        Duration::from_secs(10),
+        Duration::from_millis(200),
+        || async {
+            let resp = http_client
+                .post(format!("{httpserver_base_url}/set"))
+                .body(r#"{"key": "data-only", "value": "expiring", "expires": 60}"#)
+                .send()
+                .await
+                .context("failed to perform POST /set with TTL")?;
+            ensure!(
+                !resp.status().is_success(),
+                "set with TTL succeeded without access to metadata"
+            );
+            Ok(())
+        },
+    )
+    .await?;
+
+    // Plain sets only need access to the values
+    let resp_json: ResponseEnvelope<SetResponseData> = http_client
+        .post(format!("{httpserver_base_url}/set"))
+        .body(r#"{"key": "data-only", "value": "persistent"}"#)
+        .send()
+        .await
+        .context("failed to perform POST /set with data-only policy")?
+        .json()
+        .await
+        .context("failed to read /set response body as json")?;
+    assert_eq!(
+        resp_json.status, "success",
+        "set with data-only policy succeeded"
+    );
+    let resp_json: ResponseEnvelope<GetResponseData> = http_client
+        .post(format!("{httpserver_base_url}/get"))
+        .body(r#"{"key": "data-only"}"#)
+        .send()
+        .await
+        .context("failed to perform POST /get with data-only policy")?
+        .json()
+        .await
+        .context("failed to read /get response body as json")?;
+    assert_eq!(
+        resp_json.status, "success",
+        "get with data-only policy succeeded"
+    );
+    assert_eq!(resp_json.data.value, "persistent");
+
+    // Relink the actor to a mount of the KV v1 secrets engine
+    vaultrs::sys::mount::enable(
+        &vault_client,
+        "kv1",
+        "kv",
+        Some(
+            &mut vaultrs::api::sys::requests::EnableEngineRequest::builder()
+                .options(HashMap::from([("version".to_string(), "1".to_string())])),
+        ),
+    )
+    .await
+    .context("failed to enable KV v1 secrets engine")?;
+    ctl_client
+        .remove_link(
+            &kv_http_smithy_claims.subject,
+            "wasmcloud:keyvalue",
+            "default",
+        )
+        .await
+        .map_err(|e| anyhow!(e).context("failed to remove link"))?;
+    assert_advertise_link(
+        &ctl_client,
+        &kv_http_smithy_claims,
+        &kv_vault_provider_key,
+        "wasmcloud:keyvalue",
+        "default",
+        HashMap::from([
+            ("ADDR".into(), vault_url.to_string()),
+            ("TOKEN".into(), vault_token.to_string()),
+            ("MOUNT".into(), "kv1".to_string()),
+            ("KV_VERSION".into(), "1".to_string()),
+        ]),
+    )
+    .await?;
+
+    // Values are written to the KV v1 mount once the new link is used
+    assert_eventually(
+        Duration::from_secs(10),
+        Duration::from_millis(200),
+        || async {
+            let resp_json: ResponseEnvelope<SetResponseData> = http_client
+                .post(format!("{httpserver_base_url}/set"))
+                .body(r#"{"key": "v1", "value": "unversioned"}"#)
+                .send()
+                .await
+                .context("failed to perform POST /set on KV v1 mount")?
+                .json()
+                .await
+                .context("failed to read /set response body as json")?;
+            ensure!(resp_json.status == "success", "set on KV v1 mount failed");
+            let secret: HashMap<String, String> = vaultrs::kv1::get(&vault_client, "kv1", "v1")
+                .await
+                .context("failed to read secret from KV v1 mount")?;
+            ensure!(secret.values().any(|value| value == "unversioned"));
+            Ok(())
+        },
+    )
+    .await?;
+    let resp_json: ResponseEnvelope<GetResponseData> = http_client
+        .post(format!("{httpserver_base_url}/get"))
+        .body(r#"{"key": "v1"}"#)
+        .send()
+        .await
+        .context("failed to perform POST /get on KV v1 mount")?
+        .json()
+        .await
+        .context("failed to read /get response body as json")?;
+    assert_eq!(resp_json.status, "success", "get on KV v1 mount succeeded");
+    assert!(resp_json.data.exists);
+    assert_eq!(resp_json.data.value, "unversioned");
+
+    // KV v1 does not keep versions, which expire with a TTL
+    let resp = http_client
+        .post(format!("{httpserver_base_url}/set"))
+        .body(r#"{"key": "v1", "value": "expiring", "expires": 60}"#)
+        .send()
+        .await
+        .context("failed to perform POST /set with TTL on KV v1 mount")?;
+    assert!(
+        !resp.status().is_success(),
+        "set with TTL succeeded on KV v1 mount"
+    );
+
+    let resp_json: ResponseEnvelope<DeleteResponseData> = http_client
+        .post(format!("{httpserver_base_url}/del"))
+        .body(r#"{"key": "v1"}"#)
+        .send()
+        .await
+        .context("failed to perform POST /del on KV v1 mount")?
+        .json()
+        .await
+        .context("failed to read /del response body as json")?;
+    assert_eq!(resp_json.status, "success", "del on KV v1 mount succeeded");
+    assert!(resp_json.data);
+    ensure!(
+        vaultrs::kv1::get::<HashMap<String, String>>(&vault_client, "kv1", "v1")
+            .await
+            .is_err(),
+        "secret was not deleted from KV v1 mount"
+    );
+
     // TODO: test reading value from ENV file (specified @ link time)
     // TODO: test renewal of token by introducing a new one and letting it expire..?
     // https://github.com/wasmCloud/capability-providers/commit/353e49b2e21ce8343bc90e1c9bc33986f63094ee
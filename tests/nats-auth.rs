@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use anyhow::{anyhow, ensure, Context, Result};
+use futures::StreamExt;
+use nkeys::KeyPair;
+use tokio::time::Duration;
+use wasmcloud_control_interface::ClientBuilder;
+use wasmcloud_host::wasmbus::{Host, HostConfig};
+
+pub mod common;
+
+use crate::common::nats::{start_nats_with, NatsOptions};
+use crate::common::stop_server;
+
+const TEST_LATTICE_PREFIX: &str = "test-nats-auth";
+
+/// Test that the host connects to a NATS server requiring JWT authentication, and that
+/// unauthenticated clients are rejected
+#[tokio::test(flavor = "multi_thread")]
+async fn nats_auth() -> Result<()> {
+    let (nats_server, stop_nats_tx, nats_url, nats_client, creds) = start_nats_with(NatsOptions {
+        tls: false,
+        auth: true,
+    })
+    .await
+    .context("failed to start NATS")?;
+
+    ensure!(
+        async_nats::ConnectOptions::new()
+            .connect(nats_url.as_str())
+            .await
+            .is_err(),
+        "unauthenticated client was able to connect"
+    );
+
+    let cluster_key = Arc::new(KeyPair::new_cluster());
+    let host_key = Arc::new(KeyPair::new_server());
+    let (_host, shutdown_host) = Host::new(HostConfig {
+        ctl_nats_url: nats_url.clone(),
+        ctl_jwt: creds.user_jwt.clone(),
+        ctl_key: creds.user_key.clone(),
+        rpc_nats_url: nats_url.clone(),
+        rpc_jwt: creds.user_jwt.clone(),
+        rpc_key: creds.user_key.clone(),
+        lattice_prefix: TEST_LATTICE_PREFIX.into(),
+        cluster_key: Some(Arc::clone(&cluster_key)),
+        cluster_issuers: Some(vec![cluster_key.public_key()]),
+        host_key: Some(Arc::clone(&host_key)),
+        provider_shutdown_delay: Some(Duration::from_millis(300)),
+        ..Default::default()
+    })
+    .await
+    .context("failed to initialize host")?;
+
+    let ctl_client = ClientBuilder::new(nats_client.clone())
+        .lattice_prefix(TEST_LATTICE_PREFIX.to_string())
+        .build();
+    let hosts = ctl_client
+        .get_hosts()
+        .await
+        .map_err(|e| anyhow!(e).context("failed to get hosts"))?;
+    ensure!(
+        hosts.iter().any(|host| host.id == host_key.public_key()),
+        "host did not respond over the authenticated connection"
+    );
+
+    shutdown_host.await.context("failed to shutdown host")?;
+    stop_server(nats_server, stop_nats_tx)
+        .await
+        .context("failed to stop NATS")?;
+    Ok(())
+}
+
+/// Test that clients configured with the generated credentials connect to a NATS server serving
+/// TLS and requiring JWT authentication, while plaintext and unauthenticated clients are rejected
+#[tokio::test(flavor = "multi_thread")]
+async fn nats_tls_auth() -> Result<()> {
+    let (nats_server, stop_nats_tx, nats_url, nats_client, creds) = start_nats_with(NatsOptions {
+        tls: true,
+        auth: true,
+    })
+    .await
+    .context("failed to start NATS")?;
+    ensure!(creds.ca_cert.is_some(), "CA certificate was not generated");
+    ensure!(
+        creds.creds_file.is_some(),
+        "credentials file was not generated"
+    );
+
+    let mut sub = nats_client
+        .subscribe("test.tls")
+        .await
+        .context("failed to subscribe")?;
+    let client = creds
+        .apply(async_nats::ConnectOptions::new())
+        .connect(nats_url.as_str())
+        .await
+        .context("failed to connect with generated credentials")?;
+    client
+        .publish("test.tls", "hello".into())
+        .await
+        .context("failed to publish")?;
+    client.flush().await.context("failed to flush")?;
+    let msg = tokio::time::timeout(Duration::from_secs(5), sub.next())
+        .await
+        .context("timed out waiting for message")?
+        .context("subscription ended")?;
+    ensure!(msg.payload == "hello");
+
+    // Trusting the CA without authenticating is not sufficient
+    ensure!(
+        async_nats::ConnectOptions::new()
+            .require_tls(true)
+            .add_root_certificates(creds.ca_cert.clone().expect("CA certificate missing"))
+            .connect(nats_url.as_str())
+            .await
+            .is_err(),
+        "unauthenticated client was able to connect"
+    );
+    // Authenticating without TLS is not sufficient either
+    let creds_file = creds.creds_file.clone().expect("credentials file missing");
+    ensure!(
+        async_nats::ConnectOptions::with_credentials_file(creds_file)
+            .await
+            .context("failed to read credentials file")?
+            .connect(nats_url.as_str())
+            .await
+            .is_err(),
+        "client was able to connect without trusting the CA"
+    );
+
+    drop(client);
+    stop_server(nats_server, stop_nats_tx)
+        .await
+        .context("failed to stop NATS")?;
+    Ok(())
+}
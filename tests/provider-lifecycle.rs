@@ -0,0 +1,60 @@
+use std::collections::HashMap;
+
+use anyhow::{ensure, Context, Result};
+use nkeys::KeyPair;
+
+pub mod common;
+
+use crate::common::nats::start_nats;
+use crate::common::provider::TestProvider;
+use crate::common::stop_server;
+
+const LATTICE_PREFIX: &str = "test-provider-lifecycle";
+
+/// Drive the real httpclient provider binary through its lifecycle without a host
+#[tokio::test(flavor = "multi_thread")]
+async fn provider_lifecycle() -> Result<()> {
+    let (nats_server, stop_nats_tx, nats_url, nats_client) = start_nats()
+        .await
+        .context("failed to start backing services")?;
+
+    let provider = TestProvider::start_par(
+        test_providers::RUST_HTTPCLIENT,
+        &nats_url,
+        nats_client,
+        LATTICE_PREFIX,
+        "default",
+        None,
+    )
+    .await
+    .context("failed to start httpclient provider")?;
+    let provider_key = KeyPair::from_seed(test_providers::RUST_HTTPCLIENT_SUBJECT)
+        .context("failed to parse `rust-httpclient` provider key")?;
+    ensure!(provider.provider_id == provider_key.public_key());
+
+    let actor_id = KeyPair::new_module().public_key();
+    provider
+        .put_link(&actor_id, HashMap::default())
+        .await
+        .context("failed to link actor")?;
+    let health = provider
+        .health_check()
+        .await
+        .context("failed to check provider health")?;
+    ensure!(health.healthy);
+    provider
+        .delete_link(&actor_id)
+        .await
+        .context("failed to unlink actor")?;
+
+    let status = provider
+        .shutdown()
+        .await
+        .context("failed to shut down provider")?;
+    ensure!(status.success());
+
+    stop_server(nats_server, stop_nats_tx)
+        .await
+        .context("failed to stop NATS")?;
+    Ok(())
+}
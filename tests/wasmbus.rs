@@ -873,6 +873,7 @@ expected: {expected_name:?}"#
                 revision,
                 image_ref,
                 max_concurrent,
+                limits,
             } = component_instances
                 .pop()
                 .context("no component actor instances found")?;
@@ -885,6 +886,7 @@ expected: {expected_name:?}"#
             ensure!(revision == expected_revision.unwrap_or_default());
             ensure!(image_ref == component_image_ref);
             ensure!(max_concurrent == 1);
+            ensure!(limits.is_none());
 
             // TODO: Validate `constraints`
             ensure!(module_id == module_actor_claims.subject);
@@ -909,6 +911,7 @@ expected: {expected_name:?}"#
                 revision,
                 image_ref,
                 max_concurrent,
+                limits,
             } = module_instances
                 .pop()
                 .context("no module actor instances found")?;
@@ -921,6 +924,7 @@ expected: {expected_name:?}"#
             ensure!(revision == expected_revision.unwrap_or_default());
             ensure!(image_ref == module_image_ref);
             ensure!(max_concurrent == 1);
+            ensure!(limits.is_none());
 
             // TODO: Validate `constraints`
             ensure!(foobar_id == foobar_actor_claims.subject);
@@ -945,6 +949,7 @@ expected: {expected_name:?}"#
                 revision,
                 image_ref,
                 max_concurrent,
+                limits,
             } = foobar_instances
                 .pop()
                 .context("no foobar actor instances found")?;
@@ -957,6 +962,7 @@ expected: {expected_name:?}"#
             ensure!(revision == expected_revision.unwrap_or_default());
             ensure!(image_ref == foobar_image_ref);
             ensure!(max_concurrent == 1);
+            ensure!(limits.is_none());
         }
         (None, None, None, []) => bail!("no actor found"),
         _ => bail!("more than 3 actors found"),